@@ -0,0 +1,60 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+use crate::error::ResultExt;
+use crate::log_info as info;
+
+const DATA_DIR_OVERRIDE_FILENAME: &str = "data_dir_override.txt";
+
+// The override lives in the config dir so it can still be read when the data dir is broken.
+fn override_file(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    let config_dir = app_handle
+        .path()
+        .app_config_dir()
+        .context("Failed to resolve app config directory")?;
+    Ok(config_dir.join(DATA_DIR_OVERRIDE_FILENAME))
+}
+
+pub fn data_dir_override(app_handle: &AppHandle) -> Option<PathBuf> {
+    let contents = fs::read_to_string(override_file(app_handle).ok()?).ok()?;
+    let trimmed = contents.trim();
+    if trimmed.is_empty() {
+        None
+    } else {
+        Some(PathBuf::from(trimmed))
+    }
+}
+
+pub fn set_data_dir_override(app_handle: &AppHandle, data_dir: Option<&Path>) -> Result<(), String> {
+    let file = override_file(app_handle)?;
+    match data_dir {
+        Some(dir) => {
+            if let Some(parent) = file.parent() {
+                fs::create_dir_all(parent)
+                    .context(format!("Failed to create '{}'", parent.display()))?;
+            }
+            fs::write(&file, dir.to_string_lossy().as_bytes())
+                .context("Failed to save data directory override")?;
+            info!("Data directory override set to {}", dir.display());
+        }
+        None => {
+            if file.exists() {
+                fs::remove_file(&file)
+                    .context("Failed to remove data directory override")?;
+            }
+            info!("Data directory override cleared.");
+        }
+    }
+    Ok(())
+}
+
+pub fn app_data_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    if let Some(dir) = data_dir_override(app_handle) {
+        return Ok(dir);
+    }
+    app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))
+}
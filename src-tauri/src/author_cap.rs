@@ -0,0 +1,115 @@
+use rusqlite::{params, Connection};
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::error::ResultExt;
+use crate::models::AuthorCapSettings;
+use crate::resync::remove_cache_files;
+use crate::{log_info as info, log_warn as warn};
+
+const SECS_PER_DAY: i64 = 24 * 60 * 60;
+
+struct CapCandidate {
+    message_id: String,
+    reaction_count: i64,
+    timestamp: i64,
+    is_used: bool,
+    attachments: Vec<String>,
+}
+
+// Weeks start on Monday; the Unix epoch was a Thursday.
+fn period_bucket(timestamp: i64, period: &str) -> i64 {
+    match period {
+        "week" => (timestamp + 3 * SECS_PER_DAY).div_euclid(7 * SECS_PER_DAY),
+        _ => timestamp.div_euclid(SECS_PER_DAY),
+    }
+}
+
+// Runs after indexing: per author and day/week, keeps the most-reacted (or newest) messages
+// until the image budget is spent and removes the rest with their cached files. Messages
+// already used in a showcase always stay and count against the budget first. Submissions
+// made through the bot are not capped.
+pub(crate) fn enforce_author_cap(
+    conn: &Connection,
+    image_base_dir: &Path,
+    settings: &AuthorCapSettings,
+) -> Result<usize, String> {
+    if settings.max_images == 0 {
+        return Ok(0);
+    }
+    let budget = settings.max_images as usize;
+    let keep_newest = settings.keep == "newest";
+
+    let mut stmt = conn
+        .prepare("SELECT message_id, author_id, timestamp, reaction_count, is_used, attachments FROM messages WHERE deleted = 0 AND is_submission = 0")
+        .context("Failed to prepare author cap query")?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(1)?,
+                CapCandidate {
+                    message_id: row.get(0)?,
+                    timestamp: row.get(2)?,
+                    reaction_count: row.get(3)?,
+                    is_used: row.get(4)?,
+                    attachments: serde_json::from_str(&row.get::<_, String>(5)?).unwrap_or_default(),
+                },
+            ))
+        })
+        .context("Failed to query messages for author cap")?;
+
+    let mut groups: HashMap<(String, i64), Vec<CapCandidate>> = HashMap::new();
+    for row in rows {
+        let (author_id, candidate) = row.context("Error reading message row")?;
+        let bucket = period_bucket(candidate.timestamp, &settings.period);
+        groups.entry((author_id, bucket)).or_default().push(candidate);
+    }
+    drop(stmt);
+
+    let mut removed = 0;
+    for mut candidates in groups.into_values() {
+        let total_images: usize = candidates.iter().map(|c| c.attachments.len().max(1)).sum();
+        if total_images <= budget {
+            continue;
+        }
+        candidates.sort_by(|a, b| {
+            b.is_used.cmp(&a.is_used).then_with(|| {
+                if keep_newest {
+                    b.timestamp.cmp(&a.timestamp)
+                } else {
+                    b.reaction_count
+                        .cmp(&a.reaction_count)
+                        .then(b.timestamp.cmp(&a.timestamp))
+                }
+            })
+        });
+
+        let mut kept_images = 0;
+        for candidate in candidates {
+            let images = candidate.attachments.len().max(1);
+            if candidate.is_used || kept_images == 0 || kept_images + images <= budget {
+                kept_images += images;
+                continue;
+            }
+            remove_cache_files(image_base_dir, &candidate.attachments);
+            if let Err(e) = conn.execute(
+                "DELETE FROM messages WHERE message_id = ?1",
+                params![&candidate.message_id],
+            ) {
+                warn!("Failed to remove capped message {}: {}", candidate.message_id, e);
+                continue;
+            }
+            removed += 1;
+        }
+    }
+
+    if removed > 0 {
+        info!(
+            "Author cap ({} image(s) per author per {}) removed {} message(s)",
+            budget,
+            if settings.period == "week" { "week" } else { "day" },
+            removed
+        );
+    }
+    Ok(removed)
+}
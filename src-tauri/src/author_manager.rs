@@ -0,0 +1,484 @@
+use crate::avatars::{avatar_cache_path, cache_author_avatars};
+use crate::discord::{backoff_delay, is_transient_fetch_error, load_bot_token, FETCH_MAX_ATTEMPTS};
+use crate::error::{AppError, ResultExt};
+use crate::models::{
+    AuthorCredit, AuthorRefreshResult, ConsentStatus, MessageConsent, SelectedMessage,
+    ShowcaseCredit, UncreditedAuthor,
+};
+use crate::network::{configured_discord_http, configured_http_client};
+use crate::showcase_manager::load_selected_messages;
+use crate::sqlite_manager::{get_image_base_dir, retrieve_config, DbConnection};
+use crate::{log_error as error, log_info as info, log_warn as warn};
+
+use chrono::Utc;
+use rusqlite::{params, Connection, Error as RusqliteError, OptionalExtension, Row};
+use serde_json;
+use serenity::http::Http;
+use serenity::model::id::UserId;
+use serenity::model::user::User;
+use std::collections::{HashMap, HashSet};
+use tauri::{AppHandle, Emitter, State};
+use tokio::time::sleep;
+
+// Users are fetched one by one (Discord has no bulk endpoint); updates are written per batch.
+const AUTHOR_REFRESH_BATCH_SIZE: usize = 50;
+
+fn map_row_to_author_credit(row: &Row) -> Result<AuthorCredit, RusqliteError> {
+    let links_json: String = row.get(3)?;
+    let links = serde_json::from_str(&links_json).unwrap_or_else(|e| {
+        error!("Failed to parse links_json for author: {}", e);
+        Vec::new()
+    });
+
+    Ok(AuthorCredit {
+        author_id: row.get(0)?,
+        display_name: row.get(1)?,
+        credit_template: row.get(2)?,
+        links,
+        updated_at: row.get(4)?,
+        notify_opt_out: row.get(5)?,
+    })
+}
+
+pub fn get_author_credit_map(conn: &Connection) -> Result<HashMap<String, AuthorCredit>, String> {
+    let mut stmt = conn
+        .prepare("SELECT author_id, display_name, credit_template, links_json, updated_at, notify_opt_out FROM authors")
+        .context("Failed to prepare author query")?;
+
+    let credits = stmt
+        .query_map([], map_row_to_author_credit)
+        .context("Failed to query authors")?
+        .collect::<Result<Vec<AuthorCredit>, _>>()
+        .context("Error processing author row")?;
+
+    Ok(credits
+        .into_iter()
+        .map(|c| (c.author_id.clone(), c))
+        .collect())
+}
+
+// Supported placeholders: {name}, {id}, {link} (first link) and {links} (all links).
+pub fn render_credit(credit: Option<&AuthorCredit>, author_id: &str, author_name: &str) -> String {
+    let credit = match credit {
+        Some(c) => c,
+        None => return author_name.to_string(),
+    };
+    let name = credit
+        .display_name
+        .as_deref()
+        .filter(|n| !n.trim().is_empty())
+        .unwrap_or(author_name);
+
+    match credit.credit_template.as_deref() {
+        Some(template) if !template.trim().is_empty() => template
+            .replace("{name}", name)
+            .replace("{id}", author_id)
+            .replace("{link}", credit.links.first().map(String::as_str).unwrap_or(""))
+            .replace("{links}", &credit.links.join(" · "))
+            .trim()
+            .to_string(),
+        _ => name.to_string(),
+    }
+}
+
+pub fn resolve_showcase_credits(
+    conn: &Connection,
+    selected_messages: &[SelectedMessage],
+) -> Result<Vec<ShowcaseCredit>, String> {
+    let credit_map = get_author_credit_map(conn)?;
+    Ok(selected_messages
+        .iter()
+        .map(|m| ShowcaseCredit {
+            message_id: m.message_id.clone(),
+            author_id: m.author_id.clone(),
+            author_name: m.author_name.clone(),
+            credit_text: render_credit(credit_map.get(&m.author_id), &m.author_id, &m.author_name),
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub async fn set_author_credit(
+    author_id: String,
+    display_name: Option<String>,
+    credit_template: Option<String>,
+    links: Option<Vec<String>>,
+    db_state: State<'_, DbConnection>,
+) -> Result<(), AppError> {
+    info!("Saving credit settings for author {}", author_id);
+    let links_json = serde_json::to_string(&links.unwrap_or_default())
+        .context("Failed to serialize author links")?;
+
+    db_state
+        .0
+        .run(move |conn| {
+            conn.execute(
+                "INSERT INTO authors (author_id, display_name, credit_template, links_json, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)
+                 ON CONFLICT(author_id) DO UPDATE SET
+                    display_name = excluded.display_name,
+                    credit_template = excluded.credit_template,
+                    links_json = excluded.links_json,
+                    updated_at = excluded.updated_at",
+                params![
+                    &author_id,
+                    &display_name,
+                    &credit_template,
+                    &links_json,
+                    Utc::now().timestamp()
+                ],
+            )
+            .context("DB error saving author credit")?;
+
+            Ok(())
+        })
+        .await
+}
+
+#[tauri::command]
+pub async fn get_author_credit(
+    author_id: String,
+    db_state: State<'_, DbConnection>,
+) -> Result<Option<AuthorCredit>, AppError> {
+    db_state
+        .0
+        .run(move |conn| {
+            conn.query_row(
+                "SELECT author_id, display_name, credit_template, links_json, updated_at, notify_opt_out FROM authors WHERE author_id = ?1",
+                params![&author_id],
+                map_row_to_author_credit,
+            )
+            .optional()
+            .context("DB error getting author credit")
+        })
+        .await
+}
+
+#[tauri::command]
+pub async fn list_author_credits(
+    db_state: State<'_, DbConnection>,
+) -> Result<Vec<AuthorCredit>, AppError> {
+    db_state
+        .0
+        .run(move |conn| {
+            let mut credits: Vec<AuthorCredit> = get_author_credit_map(conn)?
+                .into_values()
+                .collect();
+            credits.sort_by(|a, b| a.author_id.cmp(&b.author_id));
+            Ok(credits)
+        })
+        .await
+}
+
+#[tauri::command]
+pub async fn delete_author_credit(
+    author_id: String,
+    db_state: State<'_, DbConnection>,
+) -> Result<(), AppError> {
+    info!("Removing credit settings for author {}", author_id);
+    db_state
+        .0
+        .run(move |conn| {
+            conn.execute(
+                "UPDATE authors SET credit_template = NULL, display_name = NULL, links_json = '[]', updated_at = ?1 WHERE author_id = ?2",
+                params![Utc::now().timestamp(), &author_id],
+            )
+            .context("DB error removing author credit")?;
+            Ok(())
+        })
+        .await
+}
+
+async fn fetch_user_with_backoff(http: &Http, user_id: UserId) -> Result<User, serenity::Error> {
+    let mut attempt = 1;
+    loop {
+        match http.get_user(user_id).await {
+            Ok(user) => return Ok(user),
+            Err(e) if attempt < FETCH_MAX_ATTEMPTS && is_transient_fetch_error(&e) => {
+                let delay = backoff_delay(attempt, None);
+                warn!("Fetching user {} failed: {}. Retrying in {}s", user_id, e, delay.as_secs());
+                sleep(delay).await;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+fn write_author_updates(
+    conn: &mut Connection,
+    updates: &[(String, String, Option<String>, Option<String>)],
+) -> Result<usize, String> {
+    let tx = conn
+        .transaction()
+        .context("Failed to start transaction")?;
+    let mut changed_authors = 0;
+    for (author_id, name, avatar, avatar_path) in updates {
+        let rows = tx
+            .execute(
+                "UPDATE messages SET author_name = ?1, author_avatar = ?2, author_avatar_path = COALESCE(?4, author_avatar_path)
+                 WHERE author_id = ?3 AND (author_name != ?1 OR author_avatar IS NOT ?2 OR author_avatar_path IS NOT COALESCE(?4, author_avatar_path))",
+                params![name, avatar, author_id, avatar_path],
+            )
+            .context(format!("Failed to update author {}", author_id))?;
+        if rows > 0 {
+            changed_authors += 1;
+        }
+    }
+    tx.commit()
+        .context("Failed to commit author updates")?;
+    Ok(changed_authors)
+}
+
+// Only the indexed name/avatar columns are touched; credit overrides in `authors` stay as set.
+#[tauri::command]
+pub async fn refresh_author_metadata(
+    app_handle: AppHandle,
+    db_state: State<'_, DbConnection>,
+) -> Result<AuthorRefreshResult, AppError> {
+    let (author_ids, encrypt_cache) = db_state
+        .0
+        .run(|conn| {
+            let mut stmt = conn
+                .prepare("SELECT DISTINCT author_id FROM messages")
+                .context("Failed to prepare author query")?;
+            let ids = stmt
+                .query_map([], |row| row.get::<_, String>(0))
+                .context("Failed to query authors")?
+                .collect::<Result<Vec<String>, _>>()
+                .context("Error reading author row")?;
+            let encrypt_cache = retrieve_config(conn)?.encrypt_image_cache.unwrap_or(false);
+            Ok((ids, encrypt_cache))
+        })
+        .await?;
+    info!("Refreshing metadata for {} author(s)...", author_ids.len());
+
+    let image_base_dir = get_image_base_dir(&app_handle)?;
+    let http = configured_discord_http(&db_state, &load_bot_token()?).await?;
+    let avatar_client = configured_http_client(&db_state, None).await?;
+    let mut result = AuthorRefreshResult::default();
+
+    for batch in author_ids.chunks(AUTHOR_REFRESH_BATCH_SIZE) {
+        let mut updates = Vec::with_capacity(batch.len());
+        let mut users = Vec::with_capacity(batch.len());
+        for author_id in batch {
+            // Manifest imports and other non-Discord rows have no user to look up.
+            let Some(user_id) = author_id
+                .parse::<u64>()
+                .ok()
+                .filter(|id| *id != 0)
+                .map(UserId::new)
+            else {
+                result.skipped += 1;
+                continue;
+            };
+            result.checked += 1;
+
+            match fetch_user_with_backoff(&http, user_id).await {
+                Ok(user) => {
+                    updates.push((
+                        author_id.clone(),
+                        user.name.clone(),
+                        user.avatar_url(),
+                        avatar_cache_path(&user),
+                    ));
+                    users.push(user);
+                }
+                Err(serenity::Error::Http(http_err))
+                    if http_err.status_code().map_or(false, |c| c.as_u16() == 404) =>
+                {
+                    // Deleted accounts keep their last known name and avatar.
+                    result.not_found.push(author_id.clone());
+                }
+                Err(e) => {
+                    error!("Failed to fetch user {}: {}", author_id, e);
+                    result.failed.push(author_id.clone());
+                }
+            }
+        }
+
+        cache_author_avatars(&avatar_client, &image_base_dir, &users, encrypt_cache, &mut HashSet::new()).await;
+        result.updated += db_state
+            .0
+            .run(move |conn| Ok(write_author_updates(conn, &updates)?))
+            .await?;
+        app_handle
+            .emit(
+                "indexing-status",
+                format!(
+                    "Refreshed {} of {} authors",
+                    result.checked + result.skipped,
+                    author_ids.len()
+                ),
+            )
+            .unwrap_or_default();
+    }
+
+    info!(
+        "Author refresh finished: {} checked, {} updated, {} not found, {} failed",
+        result.checked,
+        result.updated,
+        result.not_found.len(),
+        result.failed.len()
+    );
+    Ok(result)
+}
+
+// Every selected message of the showcase with its consent, in selection order. Messages
+// nobody has asked about yet are pending.
+pub(crate) fn load_consent(conn: &Connection, id: &str) -> Result<Vec<MessageConsent>, String> {
+    let selected_messages = load_selected_messages(conn, id)?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT message_id, status, credit_text, updated_at FROM showcase_consent WHERE showcase_id = ?1",
+        )
+        .context("Failed to prepare consent query")?;
+    let recorded: HashMap<String, (String, Option<String>, i64)> = stmt
+        .query_map(params![id], |row| {
+            Ok((row.get(0)?, (row.get(1)?, row.get(2)?, row.get(3)?)))
+        })
+        .context("DB error getting consent")?
+        .collect::<Result<_, _>>()
+        .context("Error reading consent row")?;
+    let credits = resolve_showcase_credits(conn, &selected_messages)?;
+
+    Ok(selected_messages
+        .iter()
+        .zip(credits)
+        .map(|(message, credit)| {
+            let record = recorded.get(&message.message_id);
+            let custom = record
+                .and_then(|(_, credit_text, _)| credit_text.clone())
+                .filter(|text| !text.trim().is_empty());
+            MessageConsent {
+                message_id: message.message_id.clone(),
+                author_id: message.author_id.clone(),
+                author_name: message.author_name.clone(),
+                status: record.map_or(ConsentStatus::Pending, |(status, _, _)| {
+                    ConsentStatus::parse(status)
+                }),
+                custom_credit: custom.is_some(),
+                credit_text: custom.unwrap_or(credit.credit_text),
+                updated_at: record.map(|(_, _, updated_at)| *updated_at),
+            }
+        })
+        .collect())
+}
+
+// Records the author's answer for one selected message. A blank credit goes back to the
+// author's rendered credit.
+pub(crate) fn set_consent(
+    conn: &Connection,
+    id: &str,
+    message_id: &str,
+    status: ConsentStatus,
+    credit_text: Option<&str>,
+) -> Result<(), AppError> {
+    let selected: bool = conn
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM showcase_messages WHERE showcase_id = ?1 AND message_id = ?2)",
+            params![id, message_id],
+            |row| row.get(0),
+        )
+        .context("DB error looking up selected message")?;
+    if !selected {
+        return Err(AppError::not_found(format!(
+            "Message '{}' is not selected in showcase '{}'.",
+            message_id, id
+        )));
+    }
+    let credit_text = credit_text.map(str::trim).filter(|text| !text.is_empty());
+    conn.execute(
+        "INSERT INTO showcase_consent (showcase_id, message_id, status, credit_text, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT(showcase_id, message_id) DO UPDATE SET
+            status = excluded.status,
+            credit_text = excluded.credit_text,
+            updated_at = excluded.updated_at",
+        params![
+            id,
+            message_id,
+            status.as_str(),
+            credit_text,
+            Utc::now().timestamp()
+        ],
+    )
+    .context("DB error saving consent")?;
+    Ok(())
+}
+
+// Authors still missing permission for some of their selected messages, by name.
+pub(crate) fn uncredited_authors(
+    conn: &Connection,
+    id: &str,
+) -> Result<Vec<UncreditedAuthor>, String> {
+    let mut authors: Vec<UncreditedAuthor> = Vec::new();
+    for consent in load_consent(conn, id)? {
+        if consent.status == ConsentStatus::Granted {
+            continue;
+        }
+        match authors
+            .iter_mut()
+            .find(|a| a.author_id == consent.author_id)
+        {
+            Some(author) => {
+                author.status = author.status.max(consent.status);
+                author.message_ids.push(consent.message_id);
+            }
+            None => authors.push(UncreditedAuthor {
+                author_id: consent.author_id,
+                author_name: consent.author_name,
+                status: consent.status,
+                message_ids: vec![consent.message_id],
+            }),
+        }
+    }
+    authors.sort_by(|a, b| {
+        a.author_name
+            .to_lowercase()
+            .cmp(&b.author_name.to_lowercase())
+    });
+    Ok(authors)
+}
+
+#[tauri::command]
+pub async fn list_showcase_consent(
+    id: String,
+    db_state: State<'_, DbConnection>,
+) -> Result<Vec<MessageConsent>, AppError> {
+    db_state
+        .0
+        .run(move |conn| Ok(load_consent(conn, &id)?))
+        .await
+}
+
+#[tauri::command]
+pub async fn set_message_consent(
+    id: String,
+    message_id: String,
+    status: ConsentStatus,
+    credit_text: Option<String>,
+    db_state: State<'_, DbConnection>,
+) -> Result<(), AppError> {
+    info!(
+        "Setting consent for message {} of showcase {} to {}",
+        message_id,
+        id,
+        status.as_str()
+    );
+    db_state
+        .0
+        .run(move |conn| set_consent(conn, &id, &message_id, status, credit_text.as_deref()))
+        .await
+}
+
+#[tauri::command]
+pub async fn get_uncredited_authors(
+    id: String,
+    db_state: State<'_, DbConnection>,
+) -> Result<Vec<UncreditedAuthor>, AppError> {
+    db_state
+        .0
+        .run(move |conn| Ok(uncredited_authors(conn, &id)?))
+        .await
+}
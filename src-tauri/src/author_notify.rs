@@ -0,0 +1,321 @@
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+use serenity::all::{ChannelId, CreateMessage, MessageId, UserId};
+use serenity::http::Http;
+use std::collections::HashSet;
+use tauri::State;
+
+use crate::author_manager::resolve_showcase_credits;
+use crate::discord::load_bot_token;
+use crate::error::{AppError, ResultExt};
+use crate::models::{AuthorNotifyResult, NotifyOutcome};
+use crate::network::configured_discord_http;
+use crate::showcase_manager::load_selected_messages;
+use crate::sqlite_manager::DbConnection;
+use crate::{log_error as error, log_info as info, log_warn as warn};
+
+// Supported placeholders: {name}, {mention}, {showcase}, {credit} and {count}.
+pub(crate) const DEFAULT_NOTIFY_TEMPLATE: &str =
+    "Hi {name}! Your work was featured in \"{showcase}\", credited as {credit}. Thanks for sharing it!";
+// Discord rejects longer message content.
+const MAX_MESSAGE_CHARS: usize = 2000;
+const METHOD_DM: &str = "dm";
+const METHOD_REPLY: &str = "reply";
+
+#[derive(Debug, Clone)]
+pub(crate) struct NotifyTarget {
+    pub author_id: String,
+    pub author_name: String,
+    pub credit_text: String,
+    pub message_count: usize,
+    // Their first featured message, replied to when DMs are closed.
+    pub channel_id: String,
+    pub message_id: String,
+    pub skip: Option<NotifyOutcome>,
+}
+
+// Manifest imports and other non-Discord rows have no user to message.
+fn discord_id(id: &str) -> Option<u64> {
+    id.parse::<u64>().ok().filter(|id| *id != 0)
+}
+
+fn author_id_set(
+    conn: &Connection,
+    sql: &str,
+    params: impl rusqlite::Params,
+) -> Result<HashSet<String>, String> {
+    let mut stmt = conn
+        .prepare(sql)
+        .context("Failed to prepare author query")?;
+    let ids = stmt
+        .query_map(params, |row| row.get::<_, String>(0))
+        .context("Failed to query authors")?
+        .collect::<Result<HashSet<String>, _>>()
+        .context("Error reading author row")?;
+    Ok(ids)
+}
+
+// The showcase title and one target per featured author, in selection order.
+pub(crate) fn plan_notifications(
+    conn: &Connection,
+    id: &str,
+) -> Result<(String, Vec<NotifyTarget>), AppError> {
+    let title: Option<String> = conn
+        .query_row(
+            "SELECT title FROM showcases WHERE id = ?1",
+            params![id],
+            |row| row.get(0),
+        )
+        .optional()
+        .context("DB error loading showcase")?;
+    let Some(title) = title else {
+        return Err(AppError::not_found(format!(
+            "Showcase ID '{}' not found.",
+            id
+        )));
+    };
+    let selected_messages = load_selected_messages(conn, id)?;
+    let credits = resolve_showcase_credits(conn, &selected_messages)?;
+    let opted_out = author_id_set(
+        conn,
+        "SELECT author_id FROM authors WHERE notify_opt_out != 0",
+        [],
+    )?;
+    let notified = author_id_set(
+        conn,
+        "SELECT author_id FROM showcase_notifications WHERE showcase_id = ?1",
+        params![id],
+    )?;
+
+    let mut targets: Vec<NotifyTarget> = Vec::new();
+    for (message, credit) in selected_messages.iter().zip(credits) {
+        if let Some(target) = targets
+            .iter_mut()
+            .find(|t| t.author_id == message.author_id)
+        {
+            target.message_count += 1;
+            continue;
+        }
+        let skip = if discord_id(&message.author_id).is_none() {
+            Some(NotifyOutcome::Skipped)
+        } else if opted_out.contains(&message.author_id) {
+            Some(NotifyOutcome::OptedOut)
+        } else if notified.contains(&message.author_id) {
+            Some(NotifyOutcome::AlreadyNotified)
+        } else {
+            None
+        };
+        targets.push(NotifyTarget {
+            author_id: message.author_id.clone(),
+            author_name: message.author_name.clone(),
+            credit_text: credit.credit_text,
+            message_count: 1,
+            channel_id: message.channel_id.clone(),
+            message_id: message.message_id.clone(),
+            skip,
+        });
+    }
+    Ok((title, targets))
+}
+
+pub(crate) fn render_notification(template: &str, target: &NotifyTarget, showcase: &str) -> String {
+    template
+        .replace("{name}", &target.author_name)
+        .replace("{mention}", &format!("<@{}>", target.author_id))
+        .replace("{showcase}", showcase)
+        .replace("{credit}", &target.credit_text)
+        .replace("{count}", &target.message_count.to_string())
+        .trim()
+        .to_string()
+}
+
+pub(crate) fn record_notification(
+    conn: &Connection,
+    id: &str,
+    author_id: &str,
+    method: &str,
+) -> Result<(), String> {
+    conn.execute(
+        "INSERT OR REPLACE INTO showcase_notifications (showcase_id, author_id, method, sent_at) VALUES (?1, ?2, ?3, ?4)",
+        params![id, author_id, method, Utc::now().timestamp()],
+    )
+    .context("DB error recording notification")?;
+    Ok(())
+}
+
+pub(crate) fn set_notify_opt_out(
+    conn: &Connection,
+    author_id: &str,
+    opt_out: bool,
+) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO authors (author_id, notify_opt_out, updated_at) VALUES (?1, ?2, ?3)
+         ON CONFLICT(author_id) DO UPDATE SET notify_opt_out = excluded.notify_opt_out",
+        params![author_id, opt_out, Utc::now().timestamp()],
+    )
+    .context("DB error saving notification opt-out")?;
+    Ok(())
+}
+
+fn is_forbidden(e: &serenity::Error) -> bool {
+    match e {
+        serenity::Error::Http(http_err) => http_err
+            .status_code()
+            .map_or(false, |status| status.as_u16() == 403),
+        _ => false,
+    }
+}
+
+async fn send_dm(http: &Http, user_id: UserId, content: &str) -> Result<(), serenity::Error> {
+    let channel = user_id.create_dm_channel(http).await?;
+    channel
+        .id
+        .send_message(http, CreateMessage::new().content(content))
+        .await?;
+    Ok(())
+}
+
+// DMs first; authors who only accept DMs from friends get a reply in the channel instead.
+async fn deliver(
+    http: &Http,
+    target: &NotifyTarget,
+    content: &str,
+) -> Result<&'static str, AppError> {
+    let user_id = discord_id(&target.author_id)
+        .map(UserId::new)
+        .ok_or_else(|| AppError::invalid_input("Not a Discord user."))?;
+    match send_dm(http, user_id, content).await {
+        Ok(()) => return Ok(METHOD_DM),
+        Err(e) if is_forbidden(&e) => {
+            warn!(
+                "Author {} does not accept DMs, replying in the channel instead",
+                target.author_id
+            );
+        }
+        Err(e) => return Err(AppError::from(e).with_context("Failed to send DM")),
+    }
+
+    let (Some(channel_id), Some(message_id)) = (
+        discord_id(&target.channel_id),
+        discord_id(&target.message_id),
+    ) else {
+        return Err(AppError::invalid_input(
+            "DMs are closed and the featured message is not a Discord message.",
+        ));
+    };
+    let channel_id = ChannelId::new(channel_id);
+    channel_id
+        .send_message(
+            http,
+            CreateMessage::new()
+                .content(content)
+                .reference_message((channel_id, MessageId::new(message_id))),
+        )
+        .await
+        .map_err(|e| AppError::from(e).with_context("DMs are closed and the reply failed"))?;
+    Ok(METHOD_REPLY)
+}
+
+// Tells each featured author their work was included. Opted-out authors and those already
+// told about this showcase are left alone, so the command is safe to run again after failures.
+#[tauri::command]
+pub async fn notify_featured_authors(
+    showcase_id: String,
+    message_template: Option<String>,
+    db_state: State<'_, DbConnection>,
+) -> Result<Vec<AuthorNotifyResult>, AppError> {
+    let template = message_template
+        .filter(|t| !t.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_NOTIFY_TEMPLATE.to_string());
+    let lookup_id = showcase_id.clone();
+    let (title, targets) = db_state
+        .0
+        .run(move |conn| plan_notifications(conn, &lookup_id))
+        .await?;
+    info!(
+        "Notifying {} featured author(s) of showcase ID: {}",
+        targets.len(),
+        showcase_id
+    );
+    let http = configured_discord_http(&db_state, &load_bot_token()?).await?;
+
+    let mut results = Vec::with_capacity(targets.len());
+    for target in targets {
+        let result = |outcome: NotifyOutcome, error: Option<String>| AuthorNotifyResult {
+            author_id: target.author_id.clone(),
+            author_name: target.author_name.clone(),
+            outcome,
+            error,
+        };
+        if let Some(outcome) = target.skip {
+            results.push(result(outcome, None));
+            continue;
+        }
+        let content = render_notification(&template, &target, &title);
+        if content.chars().count() > MAX_MESSAGE_CHARS {
+            results.push(result(
+                NotifyOutcome::Failed,
+                Some(format!(
+                    "The message is longer than Discord's {} character limit.",
+                    MAX_MESSAGE_CHARS
+                )),
+            ));
+            continue;
+        }
+
+        match deliver(&http, &target, &content).await {
+            Ok(method) => {
+                let (id, author_id) = (showcase_id.clone(), target.author_id.clone());
+                if let Err(e) = db_state
+                    .0
+                    .run(move |conn| Ok(record_notification(conn, &id, &author_id, method)?))
+                    .await
+                {
+                    error!(
+                        "Notified author {} but could not record it: {}",
+                        target.author_id, e.message
+                    );
+                }
+                let outcome = if method == METHOD_DM {
+                    NotifyOutcome::Sent
+                } else {
+                    NotifyOutcome::Replied
+                };
+                results.push(result(outcome, None));
+            }
+            Err(e) => {
+                error!(
+                    "Failed to notify author {}: {}",
+                    target.author_id, e.message
+                );
+                results.push(result(NotifyOutcome::Failed, Some(e.message)));
+            }
+        }
+    }
+
+    info!(
+        "Featured author notifications for showcase ID {}: {} delivered",
+        showcase_id,
+        results
+            .iter()
+            .filter(|r| matches!(r.outcome, NotifyOutcome::Sent | NotifyOutcome::Replied))
+            .count()
+    );
+    Ok(results)
+}
+
+#[tauri::command]
+pub async fn set_author_notify_opt_out(
+    author_id: String,
+    opt_out: bool,
+    db_state: State<'_, DbConnection>,
+) -> Result<(), AppError> {
+    info!(
+        "Setting notification opt-out for author {} to {}",
+        author_id, opt_out
+    );
+    db_state
+        .0
+        .run(move |conn| Ok(set_notify_opt_out(conn, &author_id, opt_out)?))
+        .await
+}
@@ -0,0 +1,305 @@
+use image::{ImageFormat, Rgba, RgbaImage};
+use serenity::model::user::User;
+use std::collections::HashSet;
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, State};
+
+use crate::cache_crypto;
+use crate::discord::write_cache_file;
+use crate::error::{AppError, ResultExt};
+use crate::network::configured_http_client;
+use crate::sqlite_manager::{get_image_base_dir, DbConnection};
+use crate::{log_info as info, log_warn as warn};
+
+use base64::{engine::general_purpose::STANDARD as base64_engine, Engine as _};
+
+pub const AVATAR_DIR: &str = "avatars";
+const FALLBACK_AVATAR_SIZE: u32 = 128;
+const AVATAR_DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(10);
+const CACHED_AVATAR_SIZE: u32 = 256;
+
+// Muted background colors that keep white initials readable on stream overlays.
+const AVATAR_COLORS: [[u8; 3]; 10] = [
+    [88, 101, 242],
+    [87, 242, 135],
+    [235, 69, 158],
+    [237, 66, 69],
+    [250, 166, 26],
+    [59, 165, 93],
+    [114, 137, 218],
+    [155, 89, 182],
+    [26, 188, 156],
+    [230, 126, 34],
+];
+
+// 5x7 bitmap glyphs for A-Z and 0-9, one byte per row with the leftmost pixel in bit 4.
+pub(crate) const GLYPH_WIDTH: u32 = 5;
+pub(crate) const GLYPH_HEIGHT: u32 = 7;
+const GLYPHS: [[u8; 7]; 36] = [
+    [0x0E, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11], // A
+    [0x1E, 0x11, 0x11, 0x1E, 0x11, 0x11, 0x1E], // B
+    [0x0E, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0E], // C
+    [0x1E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x1E], // D
+    [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F], // E
+    [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x10], // F
+    [0x0E, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0F], // G
+    [0x11, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11], // H
+    [0x0E, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E], // I
+    [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0C], // J
+    [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11], // K
+    [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1F], // L
+    [0x11, 0x1B, 0x15, 0x15, 0x11, 0x11, 0x11], // M
+    [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11], // N
+    [0x0E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E], // O
+    [0x1E, 0x11, 0x11, 0x1E, 0x10, 0x10, 0x10], // P
+    [0x0E, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0D], // Q
+    [0x1E, 0x11, 0x11, 0x1E, 0x14, 0x12, 0x11], // R
+    [0x0F, 0x10, 0x10, 0x0E, 0x01, 0x01, 0x1E], // S
+    [0x1F, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04], // T
+    [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E], // U
+    [0x11, 0x11, 0x11, 0x11, 0x11, 0x0A, 0x04], // V
+    [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0A], // W
+    [0x11, 0x11, 0x0A, 0x04, 0x0A, 0x11, 0x11], // X
+    [0x11, 0x11, 0x0A, 0x04, 0x04, 0x04, 0x04], // Y
+    [0x1F, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1F], // Z
+    [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E], // 0
+    [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E], // 1
+    [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F], // 2
+    [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E], // 3
+    [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02], // 4
+    [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E], // 5
+    [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E], // 6
+    [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08], // 7
+    [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E], // 8
+    [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C], // 9
+];
+
+pub(crate) fn glyph_for(c: char) -> Option<&'static [u8; 7]> {
+    match c {
+        'A'..='Z' => GLYPHS.get(c as usize - 'A' as usize),
+        '0'..='9' => GLYPHS.get(26 + c as usize - '0' as usize),
+        _ => None,
+    }
+}
+
+// FNV-1a: unlike DefaultHasher it is stable across Rust releases, so colors never shift.
+fn stable_hash(value: &str) -> u64 {
+    value.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+// Up to two initials from the first words of the name; characters without a glyph are skipped.
+fn initials(name: &str) -> String {
+    name.split(|c: char| c.is_whitespace() || c == '_' || c == '-' || c == '.')
+        .filter_map(|word| {
+            word.chars()
+                .flat_map(|c| c.to_uppercase())
+                .find(|c| glyph_for(*c).is_some())
+        })
+        .take(2)
+        .collect()
+}
+
+fn render_fallback_avatar(author_id: &str, text: &str) -> Result<Vec<u8>, String> {
+    let [r, g, b] = AVATAR_COLORS[(stable_hash(author_id) % AVATAR_COLORS.len() as u64) as usize];
+    let mut image = RgbaImage::from_pixel(
+        FALLBACK_AVATAR_SIZE,
+        FALLBACK_AVATAR_SIZE,
+        Rgba([r, g, b, 255]),
+    );
+
+    let chars: Vec<&[u8; 7]> = text.chars().filter_map(glyph_for).collect();
+    if !chars.is_empty() {
+        let scale = if chars.len() == 1 { 9 } else { 7 };
+        let text_width = (chars.len() as u32 * (GLYPH_WIDTH + 1) - 1) * scale;
+        let origin_x = (FALLBACK_AVATAR_SIZE - text_width) / 2;
+        let origin_y = (FALLBACK_AVATAR_SIZE - GLYPH_HEIGHT * scale) / 2;
+
+        for (index, glyph) in chars.iter().enumerate() {
+            let glyph_x = origin_x + index as u32 * (GLYPH_WIDTH + 1) * scale;
+            for (row, bits) in glyph.iter().enumerate() {
+                for col in 0..GLYPH_WIDTH {
+                    if bits & (1 << (GLYPH_WIDTH - 1 - col)) == 0 {
+                        continue;
+                    }
+                    for dy in 0..scale {
+                        for dx in 0..scale {
+                            image.put_pixel(
+                                glyph_x + col * scale + dx,
+                                origin_y + row as u32 * scale + dy,
+                                Rgba([255, 255, 255, 255]),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let mut png_bytes = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png_bytes), ImageFormat::Png)
+        .context("Failed to encode fallback avatar")?;
+    Ok(png_bytes)
+}
+
+fn fallback_avatar_path(app_handle: &AppHandle, author_id: &str, text: &str) -> Result<PathBuf, String> {
+    // Keyed on the initials too, so a renamed author gets a new image.
+    let key = stable_hash(&format!("{}:{}", author_id, text));
+    Ok(get_image_base_dir(app_handle)?
+        .join(AVATAR_DIR)
+        .join("fallback")
+        .join(format!("{:016x}.png", key)))
+}
+
+pub fn load_or_generate_fallback_avatar(
+    app_handle: &AppHandle,
+    author_id: &str,
+    author_name: &str,
+) -> Result<Vec<u8>, String> {
+    let text = initials(author_name);
+    let path = fallback_avatar_path(app_handle, author_id, &text)?;
+    if let Ok(bytes) = fs::read(&path) {
+        return Ok(bytes);
+    }
+
+    let png_bytes = render_fallback_avatar(author_id, &text)?;
+    write_cache_file(&path, &png_bytes, false)?;
+    info!("Generated fallback avatar for author {}", author_id);
+    Ok(png_bytes)
+}
+
+// `client` comes from the network settings, so avatars follow the configured proxy.
+async fn download_avatar(client: &reqwest::Client, url: &str) -> Result<(Vec<u8>, String), String> {
+    let response = client
+        .get(url)
+        .timeout(AVATAR_DOWNLOAD_TIMEOUT)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .context("Failed to download avatar")?;
+    let mime_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("")
+        .to_string();
+    if !mime_type.starts_with("image/") {
+        return Err(format!("Avatar URL did not return an image ({})", mime_type));
+    }
+    let bytes = response
+        .bytes()
+        .await
+        .context("Failed to read avatar")?;
+    Ok((bytes.to_vec(), mime_type))
+}
+
+// Keyed on the avatar hash, so a changed avatar gets a new file while older messages keep theirs.
+pub(crate) fn avatar_cache_path(user: &User) -> Option<String> {
+    user.avatar
+        .as_ref()
+        .map(|hash| format!("{}/{}/{}.png", AVATAR_DIR, user.id, hash))
+}
+
+// Downloads each author's avatar once; `seen` carries the dedupe across batches of one run.
+// Failures only warn: get_author_avatar falls back to the URL when the file is missing.
+pub(crate) async fn cache_author_avatars<'a>(
+    client: &reqwest::Client,
+    image_base_dir: &Path,
+    users: impl IntoIterator<Item = &'a User>,
+    encrypt: bool,
+    seen: &mut HashSet<String>,
+) {
+    for user in users {
+        let Some(relative_path) = avatar_cache_path(user) else {
+            continue;
+        };
+        if !seen.insert(relative_path.clone()) {
+            continue;
+        }
+        let path = image_base_dir.join(&relative_path);
+        if path.exists() {
+            continue;
+        }
+        let Some(hash) = user.avatar.as_ref() else {
+            continue;
+        };
+        let url = format!(
+            "https://cdn.discordapp.com/avatars/{}/{}.png?size={}",
+            user.id, hash, CACHED_AVATAR_SIZE
+        );
+        match download_avatar(client, &url).await {
+            Ok((bytes, _)) => {
+                if let Err(e) = write_cache_file(&path, &bytes, encrypt) {
+                    warn!("Failed to cache avatar for author {}: {}", user.id, e);
+                }
+            }
+            Err(e) => warn!("Failed to cache avatar for author {}: {}", user.id, e),
+        }
+    }
+}
+
+fn read_cached_avatar(app_handle: &AppHandle, relative_path: &str) -> Result<Vec<u8>, String> {
+    if relative_path.contains("..")
+        || relative_path.starts_with('/')
+        || relative_path.starts_with('\\')
+    {
+        return Err("Invalid relative path provided.".to_string());
+    }
+    let path = get_image_base_dir(app_handle)?.join(relative_path);
+    let bytes = fs::read(&path)
+        .context(format!("Failed to read cached avatar {}", path.display()))?;
+    cache_crypto::decrypt_if_encrypted(bytes)
+}
+
+// Always resolves to a usable data URI: the cached avatar when present, then the Discord
+// avatar when it loads, the generated one otherwise.
+#[tauri::command]
+pub async fn get_author_avatar(
+    app_handle: AppHandle,
+    author_id: String,
+    author_name: String,
+    avatar_url: Option<String>,
+    avatar_path: Option<String>,
+    db_state: State<'_, DbConnection>,
+) -> Result<String, AppError> {
+    if let Some(relative_path) = avatar_path.filter(|p| !p.is_empty()) {
+        match read_cached_avatar(&app_handle, &relative_path) {
+            Ok(bytes) => {
+                return Ok(format!(
+                    "data:image/png;base64,{}",
+                    base64_engine.encode(&bytes)
+                ));
+            }
+            Err(e) => warn!("Cached avatar for author {} unavailable: {}", author_id, e),
+        }
+    }
+
+    if let Some(url) = avatar_url.filter(|u| !u.is_empty()) {
+        let client = configured_http_client(&db_state, None).await?;
+        match download_avatar(&client, &url).await {
+            Ok((bytes, mime_type)) => {
+                return Ok(format!(
+                    "data:{};base64,{}",
+                    mime_type,
+                    base64_engine.encode(&bytes)
+                ));
+            }
+            Err(e) => warn!("Avatar for author {} unavailable, using fallback: {}", author_id, e),
+        }
+    }
+
+    let png_bytes = tokio::task::spawn_blocking(move || {
+        load_or_generate_fallback_avatar(&app_handle, &author_id, &author_name)
+    })
+    .await
+    .context("Avatar task failed")??;
+    Ok(format!(
+        "data:image/png;base64,{}",
+        base64_engine.encode(&png_bytes)
+    ))
+}
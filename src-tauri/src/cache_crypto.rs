@@ -0,0 +1,97 @@
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::{engine::general_purpose::STANDARD as base64_engine, Engine as _};
+use keyring::Entry;
+use once_cell::sync::Lazy;
+use std::sync::Mutex;
+
+use crate::error::ResultExt;
+use crate::KEYRING_SERVICE_NAME;
+use crate::{log_error as error, log_info as info};
+
+pub const CACHE_KEY_NAME: &str = "cacheEncryptionKey";
+
+// Every encrypted cache file starts with this marker followed by the 12 byte nonce.
+// Files without it are treated as plain images so mixed caches keep working.
+const ENCRYPTED_FILE_MAGIC: &[u8] = b"SSENC1";
+const NONCE_LEN: usize = 12;
+// How much of a file is_encrypted needs to see.
+pub const HEADER_LEN: usize = ENCRYPTED_FILE_MAGIC.len() + NONCE_LEN;
+
+static CACHED_KEY: Lazy<Mutex<Option<Key<Aes256Gcm>>>> = Lazy::new(|| Mutex::new(None));
+
+fn load_or_create_key() -> Result<Key<Aes256Gcm>, String> {
+    let mut guard = CACHED_KEY.lock().context("Cache key lock error")?;
+    if let Some(key) = guard.as_ref() {
+        return Ok(*key);
+    }
+
+    let entry = Entry::new(KEYRING_SERVICE_NAME, CACHE_KEY_NAME).context("Keyring error")?;
+
+    let key = match entry.get_password() {
+        Ok(encoded) => {
+            let bytes = base64_engine
+                .decode(encoded.trim())
+                .map_err(|e| format!("Stored cache encryption key is invalid: {}", e))?;
+            if bytes.len() != 32 {
+                return Err("Stored cache encryption key has an invalid length.".to_string());
+            }
+            *Key::<Aes256Gcm>::from_slice(&bytes)
+        }
+        Err(keyring::Error::NoEntry) => {
+            info!("No cache encryption key found, generating a new one.");
+            let key = Aes256Gcm::generate_key(OsRng);
+            entry
+                .set_password(&base64_engine.encode(key.as_slice()))
+                .context("Failed to store cache encryption key")?;
+            key
+        }
+        Err(e) => return Err(format!("Failed to retrieve cache encryption key: {}", e)),
+    };
+
+    *guard = Some(key);
+    Ok(key)
+}
+
+pub fn forget_cached_key() {
+    if let Ok(mut guard) = CACHED_KEY.lock() {
+        *guard = None;
+    }
+}
+
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.len() >= HEADER_LEN && data.starts_with(ENCRYPTED_FILE_MAGIC)
+}
+
+pub fn encrypt_bytes(plain: &[u8]) -> Result<Vec<u8>, String> {
+    let key = load_or_create_key()?;
+    let cipher = Aes256Gcm::new(&key);
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+
+    let ciphertext = cipher
+        .encrypt(&nonce, plain)
+        .map_err(|e| format!("Failed to encrypt cache file: {}", e))?;
+
+    let mut output = Vec::with_capacity(ENCRYPTED_FILE_MAGIC.len() + NONCE_LEN + ciphertext.len());
+    output.extend_from_slice(ENCRYPTED_FILE_MAGIC);
+    output.extend_from_slice(nonce.as_slice());
+    output.extend_from_slice(&ciphertext);
+    Ok(output)
+}
+
+pub fn decrypt_if_encrypted(data: Vec<u8>) -> Result<Vec<u8>, String> {
+    if !is_encrypted(&data) {
+        return Ok(data);
+    }
+
+    let key = load_or_create_key()?;
+    let cipher = Aes256Gcm::new(&key);
+    let (nonce_bytes, ciphertext) = data[ENCRYPTED_FILE_MAGIC.len()..].split_at(NONCE_LEN);
+
+    cipher
+        .decrypt(Nonce::from_slice(nonce_bytes), ciphertext)
+        .map_err(|e| {
+            error!("Failed to decrypt cache file: {}", e);
+            format!("Failed to decrypt cached image: {}", e)
+        })
+}
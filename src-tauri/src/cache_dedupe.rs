@@ -0,0 +1,218 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, State};
+
+use crate::cache_crypto;
+use crate::discord::IndexingState;
+use crate::error::{AppError, ResultExt};
+use crate::sqlite_manager::{get_image_base_dir, DbConnection};
+use crate::{log_info as info, log_warn as warn};
+
+#[derive(serde::Serialize, Clone, Debug, Default)]
+pub struct DedupeReport {
+    pub scanned: usize,
+    pub duplicates: usize, // files replaced by a hard link to an identical file
+    pub bytes_reclaimed: u64,
+    pub failed: Vec<String>, // relative paths that could not be read or linked
+}
+
+// Hash of the plain image bytes, so encrypted and plain copies of one image match.
+pub(crate) fn content_hash(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+pub(crate) fn record_content_hash(
+    conn: &Connection,
+    relative_path: &str,
+    content_hash: &str,
+) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO cached_files (relative_path, content_hash) VALUES (?1, ?2)
+         ON CONFLICT(relative_path) DO UPDATE SET content_hash = excluded.content_hash",
+        params![relative_path, content_hash],
+    )
+    .context(format!("Failed to record hash of {}", relative_path))?;
+    Ok(())
+}
+
+fn file_content_hash(path: &Path) -> Result<String, String> {
+    let bytes = fs::read(path).context(format!("Failed to read '{}'", path.display()))?;
+    let bytes = cache_crypto::decrypt_if_encrypted(bytes)?;
+    Ok(content_hash(&bytes))
+}
+
+#[cfg(unix)]
+fn is_same_file(a: &fs::Metadata, b: &fs::Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    a.dev() == b.dev() && a.ino() == b.ino()
+}
+
+// Comparing file ids on Windows needs an open handle, so linked files are linked again there.
+#[cfg(not(unix))]
+fn is_same_file(_a: &fs::Metadata, _b: &fs::Metadata) -> bool {
+    false
+}
+
+// Linked next to the duplicate and renamed over it, so the path never goes missing.
+fn replace_with_link(original: &Path, duplicate: &Path) -> std::io::Result<()> {
+    let temp_path = duplicate.with_extension("dedupe-tmp");
+    let _ = fs::remove_file(&temp_path);
+    fs::hard_link(original, &temp_path)?;
+    fs::rename(&temp_path, duplicate).map_err(|e| {
+        let _ = fs::remove_file(&temp_path);
+        e
+    })
+}
+
+// Brings back a missing cache file from another file with the same recorded hash. Falls back
+// to a copy where hard links are not supported.
+pub(crate) fn link_cached_duplicate(
+    conn: &Connection,
+    image_base_dir: &Path,
+    relative_path: &str,
+) -> Result<bool, String> {
+    let Some(hash) = conn
+        .query_row(
+            "SELECT content_hash FROM cached_files WHERE relative_path = ?1",
+            params![relative_path],
+            |row| row.get::<_, String>(0),
+        )
+        .optional()
+        .context(format!("Failed to look up hash of {}", relative_path))?
+    else {
+        return Ok(false);
+    };
+    let mut stmt = conn
+        .prepare("SELECT relative_path FROM cached_files WHERE content_hash = ?1 AND relative_path != ?2 ORDER BY relative_path")
+        .context("Failed to prepare duplicate query")?;
+    let candidates = stmt
+        .query_map(params![hash, relative_path], |row| row.get::<_, String>(0))
+        .context(format!("Failed to query duplicates of {}", relative_path))?
+        .collect::<Result<Vec<_>, _>>()
+        .context("Error reading cached file row")?;
+
+    let target = image_base_dir.join(relative_path);
+    for candidate in candidates {
+        let source = image_base_dir.join(&candidate);
+        if !source.is_file() {
+            continue;
+        }
+        let linked =
+            fs::hard_link(&source, &target).or_else(|_| fs::copy(&source, &target).map(|_| ()));
+        match linked {
+            Ok(()) => {
+                info!(
+                    "Restored {} from identical file {}",
+                    relative_path, candidate
+                );
+                return Ok(true);
+            }
+            Err(e) => warn!(
+                "Failed to restore {} from {}: {}",
+                relative_path, candidate, e
+            ),
+        }
+    }
+    Ok(false)
+}
+
+// Hashes every file in images/cached, rebuilds cached_files from the result and replaces each
+// duplicate with a hard link to the first file of its group.
+pub(crate) fn dedupe_cache(
+    conn: &mut Connection,
+    image_base_dir: &Path,
+) -> Result<DedupeReport, String> {
+    let cache_dir = image_base_dir.join("cached");
+    let entries = match fs::read_dir(&cache_dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(DedupeReport::default()),
+        Err(e) => return Err(format!("Failed to read {}: {}", cache_dir.display(), e)),
+    };
+
+    let mut report = DedupeReport::default();
+    let mut by_hash: HashMap<String, Vec<(String, PathBuf, fs::Metadata)>> = HashMap::new();
+    for entry in entries.flatten() {
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if !metadata.is_file() {
+            continue;
+        }
+        let path = entry.path();
+        let relative_path = Path::new("cached")
+            .join(entry.file_name())
+            .to_string_lossy()
+            .into_owned();
+        report.scanned += 1;
+        match file_content_hash(&path) {
+            Ok(hash) => by_hash
+                .entry(hash)
+                .or_default()
+                .push((relative_path, path, metadata)),
+            Err(e) => {
+                warn!("Skipping {} during deduplication: {}", relative_path, e);
+                report.failed.push(relative_path);
+            }
+        }
+    }
+
+    let tx = conn.transaction().context("Failed to start transaction")?;
+    tx.execute("DELETE FROM cached_files", [])
+        .context("Failed to clear cached file hashes")?;
+    for (hash, files) in &by_hash {
+        for (relative_path, _, _) in files {
+            record_content_hash(&tx, relative_path, hash)?;
+        }
+    }
+    tx.commit().context("Failed to commit cached file hashes")?;
+
+    for mut files in by_hash.into_values() {
+        if files.len() < 2 {
+            continue;
+        }
+        files.sort_by(|a, b| a.0.cmp(&b.0));
+        let (_, original, original_metadata) = &files[0];
+        for (relative_path, duplicate, metadata) in &files[1..] {
+            if is_same_file(original_metadata, metadata) {
+                continue;
+            }
+            match replace_with_link(original, duplicate) {
+                Ok(()) => {
+                    report.duplicates += 1;
+                    report.bytes_reclaimed += metadata.len();
+                }
+                Err(e) => {
+                    warn!("Failed to link duplicate {}: {}", relative_path, e);
+                    report.failed.push(relative_path.clone());
+                }
+            }
+        }
+    }
+    report.failed.sort();
+    info!(
+        "Cache deduplication: {} file(s) scanned, {} duplicate(s) linked, {} bytes reclaimed",
+        report.scanned, report.duplicates, report.bytes_reclaimed
+    );
+    Ok(report)
+}
+
+#[tauri::command]
+pub async fn dedupe_image_cache(
+    app_handle: AppHandle,
+    db_state: State<'_, DbConnection>,
+    indexing_state: State<'_, IndexingState>,
+) -> Result<DedupeReport, AppError> {
+    if indexing_state.0.is_running() {
+        return Err(AppError::conflict(
+            "Cannot deduplicate the image cache while indexing is running.",
+        ));
+    }
+    let image_base_dir = get_image_base_dir(&app_handle)?;
+    db_state
+        .0
+        .run(move |conn| Ok(dedupe_cache(conn, &image_base_dir)?))
+        .await
+}
@@ -0,0 +1,437 @@
+use rusqlite::Connection;
+use serenity::model::id::{ChannelId, MessageId};
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, State};
+
+use crate::cache_crypto;
+use crate::cache_dedupe::{link_cached_duplicate, record_content_hash};
+use crate::discord::{collect_download_jobs, download_to_cache, load_bot_token, IndexingState};
+use crate::error::{AppError, ResultExt};
+use crate::models::ImageSizeFilter;
+use crate::network::{configured_discord_http, configured_http_client};
+use crate::sqlite_manager::{get_image_base_dir, retrieve_config, DbConnection};
+use crate::thumbnails;
+use crate::{log_error as error, log_info as info, log_warn as warn};
+
+#[derive(serde::Serialize, Clone, Debug, Default)]
+pub struct RepairCacheResult {
+    pub scanned: usize,
+    pub broken: usize,
+    pub repaired: usize,
+    pub permanently_gone: Vec<String>, // relative paths that can no longer be downloaded
+    pub failed: Vec<String>,           // relative paths that may succeed on a later run
+}
+
+struct BrokenFile {
+    message_id: String,
+    channel_id: String,
+    relative_path: String,
+    attachment_type: String,
+}
+
+// Zero-byte files, files that no longer decrypt and images that do not decode. Videos are
+// only checked for being non-empty.
+fn is_cache_file_intact(path: &Path, attachment_type: &str) -> bool {
+    let Ok(bytes) = fs::read(path) else {
+        return false;
+    };
+    if bytes.is_empty() {
+        return false;
+    }
+    let Ok(bytes) = cache_crypto::decrypt_if_encrypted(bytes) else {
+        return false;
+    };
+    attachment_type == "video" || image::load_from_memory(&bytes).is_ok()
+}
+
+fn find_broken_files(
+    db_state: &DbConnection,
+    image_base_dir: &Path,
+) -> Result<(usize, Vec<BrokenFile>), String> {
+    let rows: Vec<(String, String, String, Option<String>)> = {
+        let conn_guard = db_state
+            .0
+            .lock()
+            .context("DB lock error")?;
+        let mut stmt = conn_guard
+            .prepare("SELECT message_id, channel_id, attachments, attachment_types FROM messages WHERE deleted = 0")
+            .context("Failed to prepare cache scan query")?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))
+            .context("Failed to query messages for cache scan")?
+            .collect::<Result<Vec<_>, _>>()
+            .context("Error reading message row")?;
+        rows
+    };
+
+    let mut scanned = 0;
+    let mut broken = Vec::new();
+    for (message_id, channel_id, attachments_json, types_json) in rows {
+        let attachments: Vec<String> = serde_json::from_str(&attachments_json).unwrap_or_default();
+        let types: Vec<String> = types_json
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        for (index, relative_path) in attachments.into_iter().enumerate() {
+            scanned += 1;
+            let attachment_type = types.get(index).map(String::as_str).unwrap_or("image");
+            if !is_cache_file_intact(&image_base_dir.join(&relative_path), attachment_type) {
+                broken.push(BrokenFile {
+                    message_id: message_id.clone(),
+                    channel_id: channel_id.clone(),
+                    relative_path,
+                    attachment_type: attachment_type.to_string(),
+                });
+            }
+        }
+    }
+    Ok((scanned, broken))
+}
+
+fn remove_broken_file(image_base_dir: &Path, relative_path: &str) {
+    let absolute_path = image_base_dir.join(relative_path);
+    if let Err(e) = fs::remove_file(&absolute_path) {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            warn!("Failed to remove broken file {}: {}", absolute_path.display(), e);
+        }
+    }
+    thumbnails::remove_thumbnails(image_base_dir, relative_path);
+}
+
+// Broken files whose content is still cached under another name are linked back instead of
+// downloaded again. Returns how many were restored and the files that still need a download.
+fn restore_from_duplicates(
+    db_state: &DbConnection,
+    image_base_dir: &Path,
+    broken: Vec<BrokenFile>,
+) -> Result<(usize, Vec<BrokenFile>), String> {
+    let conn_guard = db_state
+        .0
+        .lock()
+        .context("DB lock error")?;
+    let mut restored = 0;
+    let mut remaining = Vec::new();
+    for file in broken {
+        remove_broken_file(image_base_dir, &file.relative_path);
+        if link_cached_duplicate(&conn_guard, image_base_dir, &file.relative_path)?
+            && is_cache_file_intact(&image_base_dir.join(&file.relative_path), &file.attachment_type)
+        {
+            restored += 1;
+            continue;
+        }
+        remove_broken_file(image_base_dir, &file.relative_path);
+        remaining.push(file);
+    }
+    Ok((restored, remaining))
+}
+
+// Checks every cached file of indexed messages and re-downloads broken ones from a fresh copy
+// of their message, since Discord CDN links in old rows expire.
+#[tauri::command]
+pub async fn repair_cache(
+    app_handle: AppHandle,
+    db_state: State<'_, DbConnection>,
+    indexing_state: State<'_, IndexingState>,
+) -> Result<RepairCacheResult, AppError> {
+    if indexing_state.0.is_running() {
+        return Err(AppError::conflict("Cannot repair the cache while indexing is running."));
+    }
+    info!("Scanning image cache for broken files...");
+    app_handle
+        .emit("indexing-status", "Checking cached files...")
+        .unwrap_or_default();
+
+    let encrypt_cache = db_state
+        .0
+        .run(|conn| Ok(retrieve_config(conn)?.encrypt_image_cache.unwrap_or(false)))
+        .await?;
+    let image_base_dir = get_image_base_dir(&app_handle)?;
+    let scan_db = db_state.inner().clone();
+    let scan_dir = image_base_dir.clone();
+    let (scanned, broken) = tokio::task::spawn_blocking(move || find_broken_files(&scan_db, &scan_dir))
+        .await
+        .context("Cache scan task failed")??;
+
+    let mut result = RepairCacheResult {
+        scanned,
+        broken: broken.len(),
+        ..Default::default()
+    };
+    info!("Cache scan found {} broken file(s) out of {}", result.broken, scanned);
+    if broken.is_empty() {
+        return Ok(result);
+    }
+
+    let restore_db = db_state.inner().clone();
+    let restore_dir = image_base_dir.clone();
+    let (restored, broken) =
+        tokio::task::spawn_blocking(move || restore_from_duplicates(&restore_db, &restore_dir, broken))
+            .await
+            .context("Cache restore task failed")??;
+    result.repaired += restored;
+    if broken.is_empty() {
+        info!("Restored all {} broken file(s) from identical cached files", restored);
+        return Ok(result);
+    }
+
+    let mut by_message: HashMap<(String, String), Vec<String>> = HashMap::new();
+    for file in broken {
+        by_message
+            .entry((file.channel_id, file.message_id))
+            .or_default()
+            .push(file.relative_path);
+    }
+
+    let http = configured_discord_http(&db_state, &load_bot_token()?).await?;
+    let cache_dir: PathBuf = image_base_dir.join("cached");
+    let download_client = configured_http_client(&db_state, Some(Duration::from_secs(30))).await?;
+
+    let message_count = by_message.len();
+    for (done, ((channel_id, message_id), paths)) in by_message.into_iter().enumerate() {
+        app_handle
+            .emit(
+                "indexing-status",
+                format!("Repairing cached files ({}/{})...", done + 1, message_count),
+            )
+            .unwrap_or_default();
+
+        // Manifest imports and other non-Discord rows cannot be fetched again.
+        let (Ok(raw_channel), Ok(raw_message)) = (channel_id.parse::<u64>(), message_id.parse::<u64>()) else {
+            result.permanently_gone.extend(paths);
+            continue;
+        };
+        if raw_channel == 0 || raw_message == 0 {
+            result.permanently_gone.extend(paths);
+            continue;
+        }
+
+        let msg = match ChannelId::new(raw_channel)
+            .message(&http, MessageId::new(raw_message))
+            .await
+        {
+            Ok(msg) => msg,
+            Err(serenity::Error::Http(http_err))
+                if http_err.status_code().map_or(false, |c| c.as_u16() == 404) =>
+            {
+                warn!("Message {} no longer exists, its files cannot be repaired", message_id);
+                result.permanently_gone.extend(paths);
+                continue;
+            }
+            Err(e) => {
+                error!("Failed to fetch message {} for cache repair: {}", message_id, e);
+                result.failed.extend(paths);
+                continue;
+            }
+        };
+
+        // Unfiltered, so every file the message was originally indexed with is found again.
+        let jobs = collect_download_jobs(&cache_dir, 0, &msg, true, true, &ImageSizeFilter::default());
+        for relative_path in paths {
+            let Some(job) = jobs
+                .iter()
+                .find(|job| job.cached_file().relative_path == relative_path)
+            else {
+                // The attachment or link was removed from the message.
+                result.permanently_gone.push(relative_path);
+                continue;
+            };
+
+            match download_to_cache(&download_client, job, encrypt_cache).await {
+                Ok(Some(cached)) => {
+                    if let Some(hash) = cached.content_hash.clone() {
+                        let hashed_path = cached.relative_path.clone();
+                        if let Err(e) = db_state
+                            .0
+                            .run(move |conn| Ok(record_content_hash(conn, &hashed_path, &hash)?))
+                            .await
+                        {
+                            warn!("{}", e);
+                        }
+                    }
+                    let path = image_base_dir.join(&cached.relative_path);
+                    let attachment_type = cached.attachment_type;
+                    let intact = tokio::task::spawn_blocking(move || is_cache_file_intact(&path, attachment_type))
+                        .await
+                        .unwrap_or(false);
+                    if intact {
+                        result.repaired += 1;
+                    } else {
+                        result.failed.push(relative_path);
+                    }
+                }
+                // Discord answered with a non-retryable status: the file is gone from the CDN.
+                Ok(None) => result.permanently_gone.push(relative_path),
+                Err(e) => {
+                    error!("{}", e);
+                    result.failed.push(relative_path);
+                }
+            }
+        }
+    }
+
+    info!(
+        "Cache repair finished: {} repaired, {} permanently gone, {} failed",
+        result.repaired,
+        result.permanently_gone.len(),
+        result.failed.len()
+    );
+    app_handle
+        .emit("indexing-status", "Cache repair complete")
+        .unwrap_or_default();
+    Ok(result)
+}
+
+// Files younger than this are left alone: indexing writes the file before it inserts the row.
+const ORPHAN_MIN_AGE: Duration = Duration::from_secs(10 * 60);
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct OrphanedCacheFile {
+    pub relative_path: String,
+    pub size_bytes: u64,
+}
+
+#[derive(serde::Serialize, Clone, Debug, Default)]
+pub struct OrphanedCacheReport {
+    pub files: Vec<OrphanedCacheFile>,
+    pub total_bytes: u64,
+    pub deleted: usize, // 0 unless deletion was requested
+    pub bytes_freed: u64,
+}
+
+// Every file in images/cached that neither a surviving message nor a showcase selection lists
+// in its attachments. Imported showcases have no message rows, so their selections are the only
+// reference. The cache is flat, so file names are compared rather than the stored relative paths.
+pub(crate) fn find_orphaned_files(
+    conn: &Connection,
+    image_base_dir: &Path,
+    min_age: Duration,
+) -> Result<Vec<OrphanedCacheFile>, String> {
+    let referenced: HashSet<String> = {
+        let mut stmt = conn
+            .prepare(
+                "SELECT attachments FROM messages
+                 UNION ALL SELECT attachment_filenames_json FROM showcase_messages",
+            )
+            .context("Failed to prepare attachment query")?;
+        let rows = stmt
+            .query_map([], |row| row.get::<_, Option<String>>(0))
+            .context("Failed to query message attachments")?
+            .collect::<Result<Vec<_>, _>>()
+            .context("Error reading message row")?;
+        rows.into_iter()
+            .flatten()
+            .flat_map(|json| serde_json::from_str::<Vec<String>>(&json).unwrap_or_default())
+            .filter_map(|relative_path| {
+                Path::new(&relative_path)
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+            })
+            .collect()
+    };
+
+    let cache_dir = image_base_dir.join("cached");
+    let entries = match fs::read_dir(&cache_dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read {}: {}", cache_dir.display(), e)),
+    };
+
+    let mut orphans = Vec::new();
+    for entry in entries.flatten() {
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        let file_name = entry.file_name().to_string_lossy().into_owned();
+        if !metadata.is_file() || referenced.contains(&file_name) {
+            continue;
+        }
+        let old_enough = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .map(|age| age >= min_age)
+            .unwrap_or(true);
+        if !old_enough {
+            continue;
+        }
+        orphans.push(OrphanedCacheFile {
+            relative_path: Path::new("cached")
+                .join(&file_name)
+                .to_string_lossy()
+                .into_owned(),
+            size_bytes: metadata.len(),
+        });
+    }
+    orphans.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+    Ok(orphans)
+}
+
+// Deletes the files and their thumbnails. Returns the number deleted and the bytes freed.
+pub(crate) fn remove_orphaned_files(image_base_dir: &Path, files: &[OrphanedCacheFile]) -> (usize, u64) {
+    let mut deleted = 0;
+    let mut bytes_freed = 0;
+    for file in files {
+        match fs::remove_file(image_base_dir.join(&file.relative_path)) {
+            Ok(()) => {
+                deleted += 1;
+                bytes_freed += file.size_bytes;
+                thumbnails::remove_thumbnails(image_base_dir, &file.relative_path);
+            }
+            Err(e) => warn!("Failed to delete orphaned cache file {}: {}", file.relative_path, e),
+        }
+    }
+    (deleted, bytes_freed)
+}
+
+// The unattended variant for the cleanup scheduler: finds and deletes in one go.
+pub(crate) fn remove_orphaned_cache_files(
+    conn: &Connection,
+    image_base_dir: &Path,
+) -> Result<(usize, u64), String> {
+    let files = find_orphaned_files(conn, image_base_dir, ORPHAN_MIN_AGE)?;
+    Ok(remove_orphaned_files(image_base_dir, &files))
+}
+
+// Lists cached files no message refers to any more. Nothing is deleted unless `delete` is set,
+// so the UI can show the report and call again once the user confirms.
+#[tauri::command]
+pub async fn collect_orphaned_cache_files(
+    app_handle: AppHandle,
+    db_state: State<'_, DbConnection>,
+    indexing_state: State<'_, IndexingState>,
+    delete: Option<bool>,
+) -> Result<OrphanedCacheReport, AppError> {
+    let delete = delete.unwrap_or(false);
+    if delete && indexing_state.0.is_running() {
+        return Err(AppError::conflict("Cannot delete cached files while indexing is running."));
+    }
+    let image_base_dir = get_image_base_dir(&app_handle)?;
+    db_state
+        .0
+        .run(move |conn| {
+            let files = find_orphaned_files(conn, &image_base_dir, ORPHAN_MIN_AGE)?;
+            let total_bytes = files.iter().map(|file| file.size_bytes).sum();
+            info!(
+                "Found {} orphaned cache file(s) using {} bytes",
+                files.len(),
+                total_bytes
+            );
+            let mut report = OrphanedCacheReport {
+                files,
+                total_bytes,
+                ..Default::default()
+            };
+            if delete {
+                (report.deleted, report.bytes_freed) = remove_orphaned_files(&image_base_dir, &report.files);
+                info!(
+                    "Deleted {} orphaned cache file(s), freeing {} bytes",
+                    report.deleted, report.bytes_freed
+                );
+            }
+            Ok(report)
+        })
+        .await
+}
@@ -0,0 +1,189 @@
+use chrono::Utc;
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::time::sleep;
+
+use crate::cache_repair::remove_orphaned_cache_files;
+use crate::discord::IndexingState;
+use crate::error::ResultExt;
+use crate::models::{CleanupScheduleSettings, CleanupStats};
+use crate::showcase_manager::purge_expired_trash;
+use crate::sqlite_manager::{
+    calculate_dir_size, compact_after_cleanup, format_bytes, get_db_path, get_image_base_dir,
+    perform_cleanup, retrieve_config, DbConnection, DEFAULT_RETENTION_DAYS,
+};
+use crate::{log_error as error, log_info as info, log_warn as warn};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const STARTUP_DELAY: Duration = Duration::from_secs(5 * 60);
+const LAST_RUN_KEY: &str = "last_auto_cleanup_at";
+
+#[derive(Debug, Serialize, Clone)]
+pub struct AutoCleanupSummary {
+    pub ran_at: i64,
+    pub stats: CleanupStats,
+    pub cache_size_before_bytes: u64,
+    pub cache_size_after_bytes: u64,
+    pub orphaned_files_deleted: usize,
+    pub message: String,
+}
+
+pub fn start(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        sleep(STARTUP_DELAY).await;
+        loop {
+            if let Err(e) = purge_trash(&app_handle).await {
+                error!("Trash purge failed: {}", e);
+            }
+            if let Err(e) = run_if_due(&app_handle).await {
+                error!("Scheduled cleanup check failed: {}", e);
+            }
+            sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+fn get_last_run(conn: &Connection) -> Option<i64> {
+    conn.query_row(
+        "SELECT value FROM config WHERE key = ?1",
+        params![LAST_RUN_KEY],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .and_then(|v| v.parse::<i64>().ok())
+}
+
+fn set_last_run(conn: &Connection, ts: i64) -> Result<(), String> {
+    conn.execute(
+        "INSERT OR REPLACE INTO config (key, value) VALUES (?1, ?2)",
+        params![LAST_RUN_KEY, ts.to_string()],
+    )
+    .context(format!("Failed to save {}", LAST_RUN_KEY))?;
+    Ok(())
+}
+
+fn cache_size(app_handle: &AppHandle) -> u64 {
+    match get_image_base_dir(app_handle) {
+        Ok(dir) => calculate_dir_size(&dir.join("cached")).unwrap_or(0),
+        Err(_) => 0,
+    }
+}
+
+// Runs on every check, whether or not the cleanup schedule is enabled.
+async fn purge_trash(app_handle: &AppHandle) -> Result<(), String> {
+    let paths = app_handle.clone();
+    app_handle
+        .state::<DbConnection>()
+        .0
+        .run(move |conn| Ok(purge_expired_trash(&paths, conn, Utc::now().timestamp())?))
+        .await
+        .map(|_| ())
+        .map_err(|e| e.message)
+}
+
+async fn run_if_due(app_handle: &AppHandle) -> Result<(), String> {
+    let db_state = app_handle.state::<DbConnection>();
+
+    let due: Option<(CleanupScheduleSettings, u32, Option<i64>)> = db_state
+        .0
+        .run(|conn| {
+            let config = retrieve_config(conn)?;
+            Ok(match config.cleanup_schedule {
+                Some(schedule) if schedule.enabled => Some((
+                    schedule,
+                    config
+                        .retention_days
+                        .unwrap_or(DEFAULT_RETENTION_DAYS)
+                        .max(1),
+                    get_last_run(conn),
+                )),
+                _ => None,
+            })
+        })
+        .await?;
+    let Some((schedule, retention_days, last_run)) = due else {
+        return Ok(());
+    };
+
+    let now = Utc::now().timestamp();
+    let interval_secs = schedule.interval_days.max(1) as i64 * 24 * 60 * 60;
+    if let Some(last) = last_run {
+        if now - last < interval_secs {
+            return Ok(());
+        }
+    }
+
+    if schedule.only_when_idle && app_handle.state::<IndexingState>().0.is_running() {
+        info!("Scheduled cleanup is due but indexing is running, postponing.");
+        return Ok(());
+    }
+
+    let cache_size_before_bytes = cache_size(app_handle);
+    if let Some(min_mb) = schedule.min_cache_size_mb {
+        if cache_size_before_bytes < min_mb * 1024 * 1024 {
+            info!(
+                "Scheduled cleanup skipped: cache size {} is below the {} MB threshold.",
+                format_bytes(cache_size_before_bytes),
+                min_mb
+            );
+            return Ok(());
+        }
+    }
+
+    info!("Running scheduled cleanup...");
+    let paths = app_handle.clone();
+    let db_path = get_db_path(app_handle)?;
+    let image_base_dir = get_image_base_dir(app_handle)?;
+    // Indexing writes files before their rows, so the cache is only swept while it is idle.
+    let collect_orphans = !app_handle.state::<IndexingState>().0.is_running();
+    let (stats, orphaned_files_deleted) = db_state
+        .0
+        .run(move |conn| {
+            let mut stats = perform_cleanup(&paths, conn, retention_days, false, |_| {})?;
+            let orphaned_files_deleted = if collect_orphans {
+                match remove_orphaned_cache_files(conn, &image_base_dir) {
+                    Ok((deleted, _)) => deleted,
+                    Err(e) => {
+                        warn!("Scheduled cache sweep failed: {}", e);
+                        0
+                    }
+                }
+            } else {
+                0
+            };
+            stats.compaction = compact_after_cleanup(conn, &db_path, &stats, |_| {});
+            if let Err(e) = set_last_run(conn, now) {
+                warn!("{}", e);
+            }
+            Ok((stats, orphaned_files_deleted))
+        })
+        .await?;
+
+    let cache_size_after_bytes = cache_size(app_handle);
+    let message = format!(
+        "Automatic cleanup removed {} messages and {} cached files ({} orphaned), freeing {}.",
+        stats.messages_deleted,
+        stats.files_deleted + orphaned_files_deleted,
+        orphaned_files_deleted,
+        format_bytes(cache_size_before_bytes.saturating_sub(cache_size_after_bytes))
+    );
+    info!("{}", message);
+
+    app_handle
+        .emit(
+            "auto-cleanup-complete",
+            AutoCleanupSummary {
+                ran_at: now,
+                stats,
+                cache_size_before_bytes,
+                cache_size_after_bytes,
+                orphaned_files_deleted,
+                message,
+            },
+        )
+        .unwrap_or_default();
+
+    Ok(())
+}
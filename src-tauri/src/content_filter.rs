@@ -0,0 +1,99 @@
+use regex::{Regex, RegexBuilder};
+use tauri::State;
+
+use crate::error::{AppError, ResultExt};
+use crate::models::{ContentFilterPreview, ContentFilterSettings};
+use crate::sqlite_manager::{retrieve_config, DbConnection};
+use crate::log_info as info;
+
+// Compiled once per indexing run; plain keywords are escaped so they match literally.
+#[derive(Debug, Clone, Default)]
+pub struct ContentFilter {
+    include: Vec<Regex>,
+    exclude: Vec<Regex>,
+}
+
+fn compile_patterns(patterns: &[String], settings: &ContentFilterSettings) -> Result<Vec<Regex>, String> {
+    patterns
+        .iter()
+        .map(|p| p.trim())
+        .filter(|p| !p.is_empty())
+        .map(|pattern| {
+            let source = if settings.use_regex {
+                pattern.to_string()
+            } else {
+                regex::escape(pattern)
+            };
+            RegexBuilder::new(&source)
+                .case_insensitive(!settings.case_sensitive)
+                .build()
+                .map_err(|e| format!("Invalid content filter pattern '{}': {}", pattern, e))
+        })
+        .collect()
+}
+
+impl ContentFilter {
+    pub fn compile(settings: &ContentFilterSettings) -> Result<ContentFilter, String> {
+        Ok(ContentFilter {
+            include: compile_patterns(&settings.include_patterns, settings)?,
+            exclude: compile_patterns(&settings.exclude_patterns, settings)?,
+        })
+    }
+
+    pub fn matches_include(&self, content: &str) -> bool {
+        self.include.is_empty() || self.include.iter().any(|re| re.is_match(content))
+    }
+
+    pub fn matches_exclude(&self, content: &str) -> bool {
+        self.exclude.iter().any(|re| re.is_match(content))
+    }
+
+    // Exclude wins over include, so "#showcase" + "WIP" skips a "#showcase WIP" post.
+    pub fn allows(&self, content: &str) -> bool {
+        self.matches_include(content) && !self.matches_exclude(content)
+    }
+}
+
+// Dry run against what is already indexed; pass settings to try them before saving,
+// or None to check the saved ones.
+#[tauri::command]
+pub async fn preview_content_filter(
+    settings: Option<ContentFilterSettings>,
+    db_state: State<'_, DbConnection>,
+) -> Result<ContentFilterPreview, AppError> {
+    db_state
+        .0
+        .run(move |conn| {
+            let settings = match settings {
+                Some(settings) => settings,
+                None => retrieve_config(conn)?.content_filters.unwrap_or_default(),
+            };
+            let filter = ContentFilter::compile(&settings)?;
+
+            let mut stmt = conn
+                .prepare("SELECT message_content FROM messages WHERE deleted = 0")
+                .context("Failed to prepare content filter preview")?;
+            let rows = stmt
+                .query_map([], |row| row.get::<_, String>(0))
+                .context("Failed to query messages for preview")?;
+
+            let mut preview = ContentFilterPreview::default();
+            for row in rows {
+                let content = row.context("Error reading message row")?;
+                preview.total += 1;
+                if !filter.matches_include(&content) {
+                    preview.missing_include += 1;
+                } else if filter.matches_exclude(&content) {
+                    preview.matched_exclude += 1;
+                } else {
+                    preview.matching += 1;
+                }
+            }
+            info!(
+                "Content filter preview: {} of {} indexed message(s) match",
+                preview.matching, preview.total
+            );
+            Ok(preview)
+        })
+        .await
+}
@@ -0,0 +1,108 @@
+use aes_gcm::aead::{KeyInit, OsRng};
+use aes_gcm::Aes256Gcm;
+use rusqlite::{Connection, OpenFlags};
+use std::fs;
+use std::path::Path;
+
+use crate::error::ResultExt;
+use crate::providers::SecretStore;
+
+pub const DB_KEY_NAME: &str = "databaseEncryptionKey";
+
+const SQLITE_HEADER: &[u8] = b"SQLite format 3\0";
+
+// A plain SQLite file always starts with the magic header; a SQLCipher file is random bytes
+// from the first page on. Missing or unreadable files count as not plaintext.
+pub(crate) fn is_plaintext_database(path: &Path) -> bool {
+    let mut header = [0u8; 16];
+    fs::File::open(path)
+        .and_then(|mut file| std::io::Read::read_exact(&mut file, &mut header))
+        .map(|_| header == SQLITE_HEADER)
+        .unwrap_or(false)
+}
+
+// A missing or 0-byte file holds no database yet. Opening a path creates the empty file before
+// anything is written, so a first launch that stopped early leaves one behind.
+pub(crate) fn is_new_database(path: &Path) -> bool {
+    fs::metadata(path).map(|meta| meta.len() == 0).unwrap_or(true)
+}
+
+// Raw 256-bit key as hex, so SQLCipher skips its own key derivation.
+fn generate_key() -> String {
+    Aes256Gcm::generate_key(OsRng)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+pub(crate) fn load_or_create_key(secrets: &impl SecretStore) -> Result<String, String> {
+    if let Some(key) = secrets.get_secret(DB_KEY_NAME)? {
+        return Ok(key);
+    }
+    let key = generate_key();
+    secrets.set_secret(DB_KEY_NAME, &key)?;
+    Ok(key)
+}
+
+// Both keys and names are generated by us, so they are safe to inline into the statements.
+fn raw_key_literal(key: &str) -> Result<String, String> {
+    if key.len() != 64 || !key.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err("Stored database encryption key is invalid.".to_string());
+    }
+    Ok(format!("\"x'{}'\"", key))
+}
+
+// Must run before anything else touches the connection. A wrong key only shows up on the first
+// read, so one is done here to fail early with a clear message.
+pub(crate) fn apply_key(conn: &Connection, key: &str) -> Result<(), String> {
+    conn.execute_batch(&format!("PRAGMA key = {};", raw_key_literal(key)?))
+        .context("Failed to apply database key")?;
+    conn.query_row("SELECT count(*) FROM sqlite_master", [], |_| Ok(()))
+        .map_err(|e| format!("Database could not be decrypted with the stored key: {}", e))
+}
+
+// Unlocks `conn` when the file at `path` is encrypted. Returns whether it was.
+pub(crate) fn unlock_if_encrypted(
+    conn: &Connection,
+    path: &Path,
+    secrets: &impl SecretStore,
+) -> Result<bool, String> {
+    if is_new_database(path) || is_plaintext_database(path) {
+        return Ok(false);
+    }
+    let key = secrets.get_secret(DB_KEY_NAME)?.ok_or_else(|| {
+        "The database is encrypted but its key is missing from the system keyring.".to_string()
+    })?;
+    apply_key(conn, &key)?;
+    Ok(true)
+}
+
+// Opens `path` read-only, decrypting it if needed, and checks that it is a usable database.
+pub(crate) fn open_read_only(path: &Path, secrets: &impl SecretStore) -> Result<Connection, String> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .context("Failed to open database")?;
+    if !unlock_if_encrypted(&conn, path, secrets)? {
+        conn.query_row("SELECT count(*) FROM sqlite_master", [], |_| Ok(()))
+            .context(format!("'{}' is not a SQLite database", path.display()))?;
+    }
+    Ok(conn)
+}
+
+// Writes an encrypted copy of the plaintext database behind `conn` to `target`.
+pub(crate) fn export_encrypted(conn: &Connection, target: &Path, key: &str) -> Result<(), String> {
+    if target.exists() {
+        fs::remove_file(target)
+            .context(format!("Failed to remove stale '{}'", target.display()))?;
+    }
+    conn.execute(
+        &format!("ATTACH DATABASE ?1 AS encrypted KEY {}", raw_key_literal(key)?),
+        [target.to_string_lossy()],
+    )
+    .context("Failed to create encrypted database")?;
+    let exported = conn
+        .query_row("SELECT sqlcipher_export('encrypted')", [], |_| Ok(()))
+        .map_err(|e| format!("Failed to copy data into encrypted database: {}", e));
+    conn.execute("DETACH DATABASE encrypted", [])
+        .context("Failed to detach encrypted database")?;
+    exported
+}
@@ -0,0 +1,330 @@
+use base64::{engine::general_purpose::STANDARD as base64_engine, Engine as _};
+use chrono::Utc;
+use rusqlite::types::ValueRef;
+use rusqlite::{params, Connection};
+use serde::de::DeserializeOwned;
+use std::collections::HashSet;
+use std::path::Path;
+use tauri::{AppHandle, State};
+
+use crate::error::{AppError, ResultExt};
+use crate::models::{
+    CorruptRow, DatabaseHealthReport, ExportArtifact, MissingCacheFile, OverlaySettings,
+    ReactionSummary, RehearsalRun, ReplyContext,
+};
+use crate::sqlite_manager::{get_image_base_dir, DbConnection};
+use crate::{log_info as info, log_warn as warn};
+
+struct JsonColumn {
+    table: &'static str,
+    key: &'static str,
+    column: &'static str,
+    check: fn(&str) -> Result<(), String>,
+}
+
+fn decodes<T: DeserializeOwned>(raw: &str) -> Result<(), String> {
+    serde_json::from_str::<T>(raw)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+// Every JSON column with the type the showcase_manager row mappers and
+// map_row_to_indexed_message read it as. Showcase child rows have no single-column key, so
+// they are addressed by rowid.
+const JSON_COLUMNS: &[JsonColumn] = &[
+    JsonColumn {
+        table: "showcase_messages",
+        key: "rowid",
+        column: "attachment_filenames_json",
+        check: decodes::<Vec<String>>,
+    },
+    JsonColumn {
+        table: "showcase_messages",
+        key: "rowid",
+        column: "reply_to_json",
+        check: decodes::<ReplyContext>,
+    },
+    JsonColumn {
+        table: "showcase_images",
+        key: "rowid",
+        column: "overlay_json",
+        check: decodes::<OverlaySettings>,
+    },
+    JsonColumn {
+        table: "showcases",
+        key: "id",
+        column: "rehearsal_json",
+        check: decodes::<RehearsalRun>,
+    },
+    JsonColumn {
+        table: "showcases",
+        key: "id",
+        column: "exports_json",
+        check: decodes::<Vec<ExportArtifact>>,
+    },
+    JsonColumn {
+        table: "messages",
+        key: "message_id",
+        column: "attachments",
+        check: decodes::<Vec<String>>,
+    },
+    JsonColumn {
+        table: "messages",
+        key: "message_id",
+        column: "attachment_types",
+        check: decodes::<Vec<String>>,
+    },
+    JsonColumn {
+        table: "messages",
+        key: "message_id",
+        column: "attachment_sources",
+        check: decodes::<Vec<String>>,
+    },
+    JsonColumn {
+        table: "messages",
+        key: "message_id",
+        column: "reactions_json",
+        check: decodes::<Vec<ReactionSummary>>,
+    },
+    JsonColumn {
+        table: "messages",
+        key: "message_id",
+        column: "reply_to_json",
+        check: decodes::<ReplyContext>,
+    },
+];
+
+fn integrity_errors(conn: &Connection) -> Result<Vec<String>, String> {
+    let mut stmt = conn
+        .prepare("PRAGMA integrity_check")
+        .context("Failed to run integrity check")?;
+    let lines = stmt
+        .query_map([], |row| row.get::<_, String>(0))
+        .context("Failed to run integrity check")?
+        .collect::<Result<Vec<_>, _>>()
+        .context("Failed to read integrity check result")?;
+    Ok(if lines == ["ok"] { Vec::new() } else { lines })
+}
+
+fn foreign_key_violations(conn: &Connection) -> Result<Vec<String>, String> {
+    let mut stmt = conn
+        .prepare("PRAGMA foreign_key_check")
+        .context("Failed to run foreign key check")?;
+    let violations = stmt
+        .query_map([], |row| {
+            let table: String = row.get(0)?;
+            let rowid: Option<i64> = row.get(1)?;
+            let parent: String = row.get(2)?;
+            Ok(match rowid {
+                Some(rowid) => format!(
+                    "{} row {} references a missing {} row",
+                    table, rowid, parent
+                ),
+                None => format!("{} references a missing {} row", table, parent),
+            })
+        })
+        .context("Failed to run foreign key check")?
+        .collect::<Result<Vec<_>, _>>()
+        .context("Failed to read foreign key check result")?;
+    Ok(violations)
+}
+
+// NULL, empty and "null" are read as "not set" and are fine.
+fn corrupt_rows(conn: &Connection) -> Result<Vec<CorruptRow>, String> {
+    let mut corrupt = Vec::new();
+    for json_column in JSON_COLUMNS {
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT CAST({} AS TEXT), {} FROM {} WHERE {} IS NOT NULL",
+                json_column.key, json_column.column, json_column.table, json_column.column
+            ))
+            .map_err(|e| {
+                format!(
+                    "Failed to scan {}.{}: {}",
+                    json_column.table, json_column.column, e
+                )
+            })?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })
+            .map_err(|e| {
+                format!(
+                    "Failed to scan {}.{}: {}",
+                    json_column.table, json_column.column, e
+                )
+            })?;
+        for row in rows {
+            let (row_key, raw) = row.context(format!("Error reading {} row", json_column.table))?;
+            let trimmed = raw.trim();
+            if trimmed.is_empty() || trimmed == "null" {
+                continue;
+            }
+            if let Err(error) = (json_column.check)(trimmed) {
+                corrupt.push(CorruptRow {
+                    table: json_column.table.to_string(),
+                    row_key,
+                    column: json_column.column.to_string(),
+                    error,
+                });
+            }
+        }
+    }
+    Ok(corrupt)
+}
+
+// Cached files of messages that are still on Discord. Rows with undecodable attachments are
+// already reported as corrupt.
+fn missing_files(
+    conn: &Connection,
+    image_base_dir: &Path,
+) -> Result<Vec<MissingCacheFile>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT message_id, attachments FROM messages WHERE deleted = 0 ORDER BY message_id",
+        )
+        .context("Failed to prepare cache file query")?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?))
+        })
+        .context("Failed to query cache files")?
+        .collect::<Result<Vec<_>, _>>()
+        .context("Error reading message row")?;
+    let mut missing = Vec::new();
+    for (message_id, attachments_json) in rows {
+        let attachments: Vec<String> = attachments_json
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        for relative_path in attachments {
+            if !image_base_dir.join(&relative_path).exists() {
+                missing.push(MissingCacheFile {
+                    message_id: message_id.clone(),
+                    relative_path,
+                });
+            }
+        }
+    }
+    Ok(missing)
+}
+
+fn value_to_json(value: ValueRef) -> serde_json::Value {
+    match value {
+        ValueRef::Null => serde_json::Value::Null,
+        ValueRef::Integer(i) => i.into(),
+        ValueRef::Real(f) => f.into(),
+        ValueRef::Text(text) => String::from_utf8_lossy(text).into_owned().into(),
+        ValueRef::Blob(blob) => base64_engine.encode(blob).into(),
+    }
+}
+
+// The whole row as a column -> value object, so a quarantined row can be inspected or restored.
+fn row_as_json(conn: &Connection, table: &str, key: &str, row_key: &str) -> Result<String, String> {
+    let mut stmt = conn
+        .prepare(&format!("SELECT * FROM {} WHERE {} = ?1", table, key))
+        .context(format!("Failed to read {} row {}", table, row_key))?;
+    let names: Vec<String> = stmt
+        .column_names()
+        .iter()
+        .map(|name| name.to_string())
+        .collect();
+    let object = stmt
+        .query_row(params![row_key], |row| {
+            let mut object = serde_json::Map::new();
+            for (index, name) in names.iter().enumerate() {
+                object.insert(name.clone(), value_to_json(row.get_ref(index)?));
+            }
+            Ok(object)
+        })
+        .context(format!("Failed to read {} row {}", table, row_key))?;
+    serde_json::to_string(&object)
+        .map_err(|e| format!("Failed to serialize {} row {}: {}", table, row_key, e))
+}
+
+// Moves each corrupt row into quarantined_rows, once even if several of its columns are bad.
+fn quarantine_rows(conn: &mut Connection, corrupt: &[CorruptRow]) -> Result<usize, String> {
+    let tx = conn
+        .transaction()
+        .context("Failed to start quarantine transaction")?;
+    let now = Utc::now().timestamp();
+    let mut moved: HashSet<(&str, &str)> = HashSet::new();
+    for row in corrupt {
+        if !moved.insert((row.table.as_str(), row.row_key.as_str())) {
+            continue;
+        }
+        let key = JSON_COLUMNS
+            .iter()
+            .find(|c| c.table == row.table)
+            .map(|c| c.key)
+            .ok_or_else(|| format!("Unknown table {}", row.table))?;
+        let row_json = row_as_json(&tx, &row.table, key, &row.row_key)?;
+        tx.execute(
+            "INSERT INTO quarantined_rows (table_name, row_key, row_json, reason, quarantined_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![row.table, row.row_key, row_json, format!("{}: {}", row.column, row.error), now],
+        )
+        .context(format!("Failed to quarantine {} row {}", row.table, row.row_key))?;
+        tx.execute(
+            &format!("DELETE FROM {} WHERE {} = ?1", row.table, key),
+            params![row.row_key],
+        )
+        .map_err(|e| {
+            format!(
+                "Failed to remove quarantined {} row {}: {}",
+                row.table, row.row_key, e
+            )
+        })?;
+        warn!(
+            "Quarantined {} row {}: {} is corrupt",
+            row.table, row.row_key, row.column
+        );
+    }
+    tx.commit().context("Failed to commit quarantine")?;
+    Ok(moved.len())
+}
+
+pub(crate) fn check_health(
+    conn: &mut Connection,
+    image_base_dir: &Path,
+    quarantine: bool,
+) -> Result<DatabaseHealthReport, String> {
+    let mut report = DatabaseHealthReport {
+        integrity_errors: integrity_errors(conn)?,
+        foreign_key_violations: foreign_key_violations(conn)?,
+        corrupt_rows: corrupt_rows(conn)?,
+        missing_files: missing_files(conn, image_base_dir)?,
+        quarantined: 0,
+    };
+    if quarantine && !report.corrupt_rows.is_empty() {
+        report.quarantined = quarantine_rows(conn, &report.corrupt_rows)?;
+    }
+    info!(
+        "Database health: {} integrity error(s), {} foreign key violation(s), {} corrupt row(s), {} missing file(s), {} quarantined",
+        report.integrity_errors.len(),
+        report.foreign_key_violations.len(),
+        report.corrupt_rows.len(),
+        report.missing_files.len(),
+        report.quarantined
+    );
+    Ok(report)
+}
+
+// Read-only unless `quarantine` is set, in which case rows with undecodable JSON are moved to
+// quarantined_rows so list queries stop tripping over them.
+#[tauri::command]
+pub async fn check_database_health(
+    app_handle: AppHandle,
+    db_state: State<'_, DbConnection>,
+    quarantine: Option<bool>,
+) -> Result<DatabaseHealthReport, AppError> {
+    let image_base_dir = get_image_base_dir(&app_handle)?;
+    db_state
+        .0
+        .run(move |conn| {
+            Ok(check_health(
+                conn,
+                &image_base_dir,
+                quarantine.unwrap_or(false),
+            )?)
+        })
+        .await
+}
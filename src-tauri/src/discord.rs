@@ -1,24 +1,49 @@
-use keyring::Entry;
-use rusqlite::params;
+use rusqlite::{params, Connection};
 use serenity::all::MessagePagination;
 use serenity::http::Http;
 use serenity::model::guild::GuildInfo;
 
-use serenity::model::channel::{ChannelType, GuildChannel};
+use serenity::model::channel::{Attachment, ChannelType, GuildChannel, Message};
+use serenity::model::sticker::StickerFormatType;
 use serenity::model::id::{ChannelId, GuildId, MessageId};
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 
 use std::time::Duration;
-use tauri::{AppHandle, Emitter, Manager, State};
+use tauri::{AppHandle, Emitter, State};
+use tauri_plugin_opener::OpenerExt;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
 use tokio::time::sleep;
 
-use crate::sqlite_manager::{retrieve_config, DbConnection};
+use crate::app_paths;
+use crate::author_cap::enforce_author_cap;
+use crate::avatars;
+use crate::cache_crypto;
+use crate::cache_dedupe;
+use crate::content_filter::ContentFilter;
+use crate::error::{AppError, ErrorKind, ResultExt};
+use crate::network::{configured_discord_http, configured_http_client, http_client};
+use crate::providers::{HttpFetcher, KeyringSecrets, SecretStore};
+use crate::spans::{self, Span};
+use crate::sqlite_manager::{
+    get_image_base_dir, load_index_resume_cursor, record_channel_index_run, retrieve_config,
+    save_index_resume_cursor, DbConnection, IndexResumeCursor,
+};
+use crate::thumbnails;
 use crate::{log_error as error, log_info as info, log_warn as warn};
-use crate::{AppConfig, KEYRING_SERVICE_NAME};
+use crate::models::{AuthorFilter, AuthorFilterSettings, ImageSizeFilter, IndexedMessage, ReactionSummary, ReplyContext};
+use crate::AppConfig;
+
+use base64::Engine as _;
+use once_cell::sync::Lazy;
+use regex::{Regex, RegexBuilder};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
 
 use chrono::{DateTime, Datelike, Months, NaiveDate, TimeZone, Utc};
 use reqwest;
@@ -41,6 +66,20 @@ pub struct SerializableGuild {
     icon: Option<String>,
 }
 
+#[derive(serde::Serialize, Clone, Debug, Default)]
+pub struct TokenValidationReport {
+    token_valid: bool,
+    bot_user_id: Option<String>,
+    bot_user_name: Option<String>,
+    guild_id: Option<String>,
+    guild_name: Option<String>,
+    guild_visible: Option<bool>, // None when no guild was checked
+    channels_missing_view: Vec<String>,
+    channels_missing_read_history: Vec<String>,
+    channels_not_found: Vec<String>,
+    errors: Vec<String>,
+}
+
 #[derive(serde::Serialize, Clone, Debug)]
 pub struct SerializableChannel {
     id: String,
@@ -49,20 +88,869 @@ pub struct SerializableChannel {
     position: u16,
     parent_id: Option<String>,
     parent_name: Option<String>,
+    kind: String,
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct ChannelPreviewMessage {
+    message_id: String,
+    author_name: String,
+    author_avatar: Option<String>,
+    content: String,
+    timestamp: i64,
+    media_urls: Vec<String>, // Discord CDN URLs of what indexing would download, shown as-is
+}
+
+const PREVIEW_MAX_MESSAGES: u8 = 100;
+
+const INDEXABLE_CHANNEL_KINDS: [ChannelType; 4] = [
+    ChannelType::Text,
+    ChannelType::News,
+    ChannelType::Forum,
+    ChannelType::PublicThread,
+];
+
+fn channel_kind_name(kind: ChannelType) -> &'static str {
+    match kind {
+        ChannelType::Text => "text",
+        ChannelType::News => "news",
+        ChannelType::Forum => "forum",
+        ChannelType::PublicThread => "public_thread",
+        _ => "other",
+    }
+}
+
+#[derive(serde::Serialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum IndexingPhase {
+    #[default]
+    Idle,
+    Starting,
+    FetchingMessages,
+    Downloading,
+    Saving,
+    Paused,
+    Completed,
+    Cancelled,
+}
+
+#[derive(serde::Serialize, Clone, Debug, Default)]
+pub struct IndexingProgress {
+    pub phase: IndexingPhase,
+    pub channel_id: Option<String>,
+    pub channel_name: Option<String>,
+    pub channel_index: usize,
+    pub channel_count: usize,
+    pub messages_scanned: usize,
+    pub messages_saved: usize,
+    pub images_downloaded: usize,
+    // Extrapolated from how far back in the date window the scan has reached.
+    pub estimated_total: Option<usize>,
+    pub percent: f32,
+    pub updated_at: i64,
+}
+
+#[derive(Default)]
+pub struct IndexingControl {
+    running: AtomicBool,
+    cancel_requested: AtomicBool,
+    paused: AtomicBool,
+    progress: Mutex<IndexingProgress>,
+}
+
+#[derive(Clone, Default)]
+pub struct IndexingState(pub Arc<IndexingControl>);
+
+// Held by the indexing task; clears the running flag however the task ends, panics included.
+struct IndexingRun(Arc<IndexingControl>);
+
+impl Drop for IndexingRun {
+    fn drop(&mut self) {
+        self.0.finish();
+    }
+}
+
+impl IndexingControl {
+    fn try_start(self: &Arc<Self>) -> Option<IndexingRun> {
+        if self.running.swap(true, Ordering::SeqCst) {
+            return None;
+        }
+        self.cancel_requested.store(false, Ordering::SeqCst);
+        self.paused.store(false, Ordering::SeqCst);
+        if let Ok(mut progress) = self.progress.lock() {
+            *progress = IndexingProgress {
+                phase: IndexingPhase::Starting,
+                updated_at: Utc::now().timestamp(),
+                ..Default::default()
+            };
+        }
+        Some(IndexingRun(self.clone()))
+    }
+
+    pub fn progress_snapshot(&self) -> IndexingProgress {
+        self.progress
+            .lock()
+            .map(|p| p.clone())
+            .unwrap_or_default()
+    }
+
+    fn update_progress<F: FnOnce(&mut IndexingProgress)>(&self, app_handle: &AppHandle, update: F) {
+        let snapshot = match self.progress.lock() {
+            Ok(mut progress) => {
+                update(&mut progress);
+                progress.updated_at = Utc::now().timestamp();
+                progress.clone()
+            }
+            Err(e) => {
+                error!("Indexing progress lock error: {}", e);
+                return;
+            }
+        };
+        app_handle
+            .emit("indexing-progress", snapshot)
+            .unwrap_or_default();
+    }
+
+    fn finish(&self) {
+        self.running.store(false, Ordering::SeqCst);
+        self.paused.store(false, Ordering::SeqCst);
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel_requested.load(Ordering::SeqCst)
+    }
+
+    pub fn request_cancel(&self) {
+        self.cancel_requested.store(true, Ordering::SeqCst);
+    }
+
+    // Blocks the indexing task while paused. Returns false if cancelled in the meantime.
+    async fn wait_if_paused(&self, app_handle: &AppHandle) -> bool {
+        let mut phase_before_pause = None;
+        while self.paused.load(Ordering::SeqCst) {
+            if self.is_cancelled() {
+                return false;
+            }
+            if phase_before_pause.is_none() {
+                phase_before_pause = Some(self.progress_snapshot().phase);
+                self.update_progress(app_handle, |p| p.phase = IndexingPhase::Paused);
+                app_handle
+                    .emit("indexing-status", "Indexing paused")
+                    .unwrap_or_default();
+            }
+            sleep(Duration::from_millis(500)).await;
+        }
+        if let Some(phase) = phase_before_pause {
+            self.update_progress(app_handle, |p| p.phase = phase);
+            app_handle
+                .emit("indexing-status", "Indexing resumed")
+                .unwrap_or_default();
+        }
+        !self.is_cancelled()
+    }
+
+    // Returns false if cancelled before the delay elapsed.
+    async fn sleep_unless_cancelled(&self, delay: Duration) -> bool {
+        let deadline = tokio::time::Instant::now() + delay;
+        while tokio::time::Instant::now() < deadline {
+            if self.is_cancelled() {
+                return false;
+            }
+            sleep(Duration::from_millis(500).min(deadline - tokio::time::Instant::now())).await;
+        }
+        !self.is_cancelled()
+    }
+}
+
+pub(crate) const DEFAULT_DOWNLOAD_CONCURRENCY: usize = 6;
+pub(crate) const MAX_DOWNLOAD_CONCURRENCY: usize = 16;
+const DOWNLOAD_MAX_ATTEMPTS: u32 = 3;
+pub(crate) const FETCH_MAX_ATTEMPTS: u32 = 6;
+const BACKOFF_BASE_SECS: u64 = 1;
+const MAX_BACKOFF_SECS: u64 = 60;
+const MAX_RETRY_AFTER_SECS: u64 = 300;
+
+// Exponential delay before retry `attempt` (1-based), unless the server said how long to wait.
+pub(crate) fn backoff_delay(attempt: u32, retry_after: Option<Duration>) -> Duration {
+    if let Some(retry_after) = retry_after {
+        return retry_after.min(Duration::from_secs(MAX_RETRY_AFTER_SECS));
+    }
+    let secs = BACKOFF_BASE_SECS << attempt.min(6);
+    Duration::from_secs(secs.min(MAX_BACKOFF_SECS))
+}
+
+// Rate limits, server errors and network failures are worth retrying; other 4xx are not.
+pub(crate) fn is_transient_fetch_error(e: &serenity::Error) -> bool {
+    match e {
+        serenity::Error::Http(http_err) => http_err
+            .status_code()
+            .map_or(true, |status| status.as_u16() == 429 || status.is_server_error()),
+        _ => false,
+    }
+}
+
+pub(crate) struct CacheDownloadJob {
+    message_index: usize,
+    attachment_type: &'static str,
+    pub(crate) source: &'static str,
+    url: String,
+    display_name: String,
+    local_filename: String,
+    relative_path: String,
+    absolute_path: PathBuf,
+    expected_size: Option<u64>, // Discord reports sizes for attachments only
+}
+
+impl CacheDownloadJob {
+    pub(crate) fn for_attachment(
+        cache_dir: &Path,
+        message_index: usize,
+        message_id: &str,
+        attachment: &Attachment,
+        attachment_type: &'static str,
+    ) -> Self {
+        let extension = Path::new(&attachment.filename)
+            .extension()
+            .and_then(|s| s.to_str())
+            .unwrap_or("png");
+        let local_filename = format!("{}_{}.{}", message_id, attachment.id, extension);
+        CacheDownloadJob {
+            message_index,
+            attachment_type,
+            source: SOURCE_ATTACHMENT,
+            url: attachment.url.clone(),
+            display_name: attachment.filename.clone(),
+            relative_path: Path::new("cached")
+                .join(&local_filename)
+                .to_string_lossy()
+                .into_owned(),
+            absolute_path: cache_dir.join(&local_filename),
+            local_filename,
+            expected_size: Some(attachment.size as u64),
+        }
+    }
+
+    pub(crate) fn cached_file(&self) -> CachedFile {
+        CachedFile {
+            relative_path: self.relative_path.clone(),
+            attachment_type: self.attachment_type,
+            source: self.source,
+            content_hash: None,
+        }
+    }
+
+    // Embed, link and sticker images have no attachment id, so they are numbered per message.
+    fn for_linked_image(
+        cache_dir: &Path,
+        message_index: usize,
+        message_id: &str,
+        link_index: usize,
+        image: LinkedImage,
+    ) -> Self {
+        let local_filename = format!(
+            "{}_{}{}.{}",
+            message_id, image.source, link_index, image.extension
+        );
+        CacheDownloadJob {
+            message_index,
+            attachment_type: image.attachment_type,
+            source: image.source,
+            display_name: image.url.clone(),
+            url: image.url,
+            relative_path: Path::new("cached")
+                .join(&local_filename)
+                .to_string_lossy()
+                .into_owned(),
+            absolute_path: cache_dir.join(&local_filename),
+            local_filename,
+            expected_size: None,
+        }
+    }
+}
+
+pub(crate) const SOURCE_ATTACHMENT: &str = "attachment";
+const SOURCE_EMBED: &str = "embed";
+const SOURCE_LINK: &str = "link";
+const SOURCE_STICKER: &str = "sticker";
+
+static IMAGE_LINK_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)https?://[^\s<>]+?\.(png|jpe?g|webp|gif)(\?[^\s<>]*)?(\s|>|$)").unwrap()
+});
+static MESSAGE_LINK_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^https?://(?:(?:ptb|canary)\.)?discord(?:app)?\.com/channels/(\d+|@me)/(\d+)/(\d+)/?$").unwrap()
+});
+
+// A downloaded file ready to be stored on a message row.
+pub(crate) struct CachedFile {
+    pub relative_path: String,
+    pub attachment_type: &'static str,
+    pub source: &'static str,
+    pub content_hash: Option<String>, // None when the file was already cached
+}
+
+struct LinkedImage {
+    url: String,
+    extension: String,
+    attachment_type: &'static str,
+    source: &'static str,
+    width: Option<u32>,
+    height: Option<u32>,
+}
+
+fn linked_image_from_url(url: &str, source: &'static str) -> Option<LinkedImage> {
+    let path = url.split(['?', '#']).next().unwrap_or(url).to_lowercase();
+    let extension = Path::new(&path).extension().and_then(|s| s.to_str());
+    let (extension, attachment_type) = match extension {
+        Some("gif") => ("gif", "gif"),
+        Some(ext @ ("png" | "jpg" | "jpeg" | "webp")) => (ext, "image"),
+        // Discord's media proxy often drops the extension for embed images.
+        _ if source == SOURCE_EMBED => ("png", "image"),
+        _ => return None,
+    };
+    Some(LinkedImage {
+        url: url.to_string(),
+        extension: extension.to_string(),
+        attachment_type,
+        source,
+        width: None,
+        height: None,
+    })
+}
+
+// APNG and GIF stickers count as animated; Lottie stickers have no image to download.
+fn sticker_images(msg: &Message, include_animated: bool) -> Vec<LinkedImage> {
+    msg.sticker_items
+        .iter()
+        .filter_map(|sticker| {
+            let (extension, attachment_type) = match sticker.format_type {
+                StickerFormatType::Png => ("png", "image"),
+                StickerFormatType::Apng => ("png", "gif"),
+                StickerFormatType::Gif => ("gif", "gif"),
+                _ => return None,
+            };
+            if !include_animated && attachment_type != "image" {
+                return None;
+            }
+            Some(LinkedImage {
+                url: sticker.image_url()?,
+                extension: extension.to_string(),
+                attachment_type,
+                source: SOURCE_STICKER,
+                width: None,
+                height: None,
+            })
+        })
+        .collect()
+}
+
+// Embed image/thumbnail URLs plus bare image links in the content, deduplicated.
+fn linked_images(msg: &Message, include_animated: bool) -> Vec<LinkedImage> {
+    let mut seen: Vec<String> = Vec::new();
+    let mut images = Vec::new();
+
+    for embed in &msg.embeds {
+        if let Some(link) = &embed.url {
+            seen.push(link.clone());
+        }
+        let media = embed
+            .image
+            .as_ref()
+            .map(|i| (i.url.clone(), i.proxy_url.clone(), i.width, i.height))
+            .or_else(|| {
+                embed
+                    .thumbnail
+                    .as_ref()
+                    .map(|t| (t.url.clone(), t.proxy_url.clone(), t.width, t.height))
+            });
+        let Some((original_url, proxy_url, width, height)) = media else {
+            continue;
+        };
+        seen.push(original_url.clone());
+        if let Some(image) =
+            linked_image_from_url(proxy_url.as_deref().unwrap_or(&original_url), SOURCE_EMBED)
+        {
+            images.push(LinkedImage {
+                width,
+                height,
+                ..image
+            });
+        }
+    }
+
+    for caps in IMAGE_LINK_RE.captures_iter(&msg.content) {
+        let url = caps[0].trim_end_matches(|c: char| c.is_whitespace() || c == '>');
+        if seen.iter().any(|s| s == url) {
+            continue;
+        }
+        seen.push(url.to_string());
+        if let Some(image) = linked_image_from_url(url, SOURCE_LINK) {
+            images.push(image);
+        }
+    }
+
+    images.retain(|image| include_animated || image.attachment_type == "image");
+    images
+}
+
+fn passes_size_filter(
+    filter: &ImageSizeFilter,
+    width: Option<u32>,
+    height: Option<u32>,
+    file_size: Option<u64>,
+) -> bool {
+    width.map_or(true, |w| w >= filter.min_width)
+        && height.map_or(true, |h| h >= filter.min_height)
+        && match (filter.max_file_size_bytes, file_size) {
+            (Some(max), Some(size)) => max == 0 || size <= max,
+            _ => true,
+        }
+}
+
+// All cache downloads for one message: native attachments first, then stickers, then embeds
+// and links.
+pub(crate) fn collect_download_jobs(
+    cache_dir: &Path,
+    message_index: usize,
+    msg: &Message,
+    include_animated: bool,
+    include_linked: bool,
+    size_filter: &ImageSizeFilter,
+) -> Vec<CacheDownloadJob> {
+    let message_id = msg.id.to_string();
+    let mut jobs: Vec<CacheDownloadJob> = msg
+        .attachments
+        .iter()
+        .filter(|attachment| {
+            passes_size_filter(
+                size_filter,
+                attachment.width,
+                attachment.height,
+                Some(attachment.size as u64),
+            )
+        })
+        .filter_map(|attachment| {
+            indexable_attachment_type(attachment, include_animated).map(|kind| {
+                CacheDownloadJob::for_attachment(cache_dir, message_index, &message_id, attachment, kind)
+            })
+        })
+        .collect();
+
+    // Stickers are always kept: a sticker-only post is a real submission, not a link preview.
+    jobs.extend(
+        sticker_images(msg, include_animated)
+            .into_iter()
+            .enumerate()
+            .map(|(sticker_index, image)| {
+                CacheDownloadJob::for_linked_image(cache_dir, message_index, &message_id, sticker_index, image)
+            }),
+    );
+
+    if include_linked {
+        jobs.extend(
+            linked_images(msg, include_animated)
+                .into_iter()
+                .enumerate()
+                .filter(|(_, image)| passes_size_filter(size_filter, image.width, image.height, None))
+                .map(|(link_index, image)| {
+                    CacheDownloadJob::for_linked_image(
+                        cache_dir,
+                        message_index,
+                        &message_id,
+                        link_index,
+                        image,
+                    )
+                }),
+        );
+    }
+    jobs
+}
+
+// "image", "gif" or "video"; None for anything the indexer never stores.
+fn attachment_type(attachment: &Attachment) -> Option<&'static str> {
+    let filename_lower = attachment.filename.to_lowercase();
+    let ct = attachment.content_type.as_deref().unwrap_or("");
+    if ct == "image/gif" || filename_lower.ends_with(".gif") {
+        Some("gif")
+    } else if ct.starts_with("video/")
+        || [".mp4", ".webm", ".mov"]
+            .iter()
+            .any(|ext| filename_lower.ends_with(ext))
+    {
+        Some("video")
+    } else if is_supported_image(attachment) {
+        Some("image")
+    } else {
+        None
+    }
+}
+
+pub(crate) fn indexable_attachment_type(
+    attachment: &Attachment,
+    include_animated: bool,
+) -> Option<&'static str> {
+    attachment_type(attachment).filter(|kind| include_animated || *kind == "image")
+}
+
+fn is_supported_image(attachment: &Attachment) -> bool {
+    let filename_lower = attachment.filename.to_lowercase();
+    let ct = attachment.content_type.as_deref();
+    ct.map_or(false, |t| t.starts_with("image/") && t != "image/gif")
+        || (!filename_lower.ends_with(".gif")
+            && (filename_lower.ends_with(".png")
+                || filename_lower.ends_with(".jpg")
+                || filename_lower.ends_with(".jpeg")
+                || filename_lower.ends_with(".webp")))
+}
+
+// Ok(Some(file)) when the file is cached, Ok(None) when the server refused it for good.
+pub(crate) async fn download_to_cache<F: HttpFetcher>(
+    fetcher: &F,
+    job: &CacheDownloadJob,
+    encrypt: bool,
+) -> Result<Option<CachedFile>, String> {
+    let path_check = job.absolute_path.clone();
+    if tokio::task::spawn_blocking(move || path_check.exists())
+        .await
+        .unwrap_or(false)
+    {
+        warn!("Skipping download, file exists: {}", job.local_filename);
+        return Ok(Some(job.cached_file()));
+    }
+
+    let mut last_error = String::new();
+    let mut retry_after: Option<Duration> = None;
+    for attempt in 1..=DOWNLOAD_MAX_ATTEMPTS {
+        if attempt > 1 {
+            sleep(backoff_delay(attempt - 1, retry_after.take())).await;
+            warn!(
+                "Retrying download of {} (attempt {}/{})",
+                job.url, attempt, DOWNLOAD_MAX_ATTEMPTS
+            );
+        }
+
+        let response = match fetcher.fetch(&job.url).await {
+            Ok(response) => response,
+            Err(e) => {
+                last_error = e;
+                continue;
+            }
+        };
+
+        let status = response.status;
+        if status == 429 || (500..600).contains(&status) {
+            retry_after = response.retry_after;
+            last_error = format!("Download failed for {}: Status {}", job.url, status);
+            continue;
+        }
+        if !(200..300).contains(&status) {
+            error!("Download failed for {}: Status {}", job.url, status);
+            return Ok(None);
+        }
+        // Links can point at an HTML page rather than the image itself.
+        if job.source != SOURCE_ATTACHMENT {
+            let is_image = response
+                .content_type
+                .as_deref()
+                .map_or(false, |ct| ct.starts_with("image/"));
+            if !is_image {
+                warn!("Skipping {}: response is not an image", job.url);
+                return Ok(None);
+            }
+        }
+
+        let image_bytes = response.body;
+
+        let path_clone = job.absolute_path.clone();
+        let content_hash = tokio::task::spawn_blocking(move || {
+            write_cache_file(&path_clone, &image_bytes, encrypt)
+                .map(|()| cache_dedupe::content_hash(&image_bytes))
+        })
+        .await
+        .context(format!("File write task failed for {}", job.local_filename))?
+        .context(format!("Failed to write file {}", job.local_filename))?;
+
+        info!("Saved image: {}", job.local_filename);
+        return Ok(Some(CachedFile {
+            content_hash: Some(content_hash),
+            ..job.cached_file()
+        }));
+    }
+
+    Err(last_error)
+}
+
+// Emits the "Downloading: ..." status line for each job as it starts.
+pub(crate) fn download_status(app_handle: &AppHandle) -> impl Fn(&str) + Clone + Send + 'static {
+    let app_handle = app_handle.clone();
+    move |display_name| {
+        app_handle
+            .emit("indexing-status", format!("Downloading: {}...", display_name))
+            .unwrap_or_default();
+    }
+}
+
+// Downloads all jobs with at most `semaphore` permits in flight. Results keep the job order.
+pub(crate) async fn run_download_jobs<F: HttpFetcher>(
+    jobs: Vec<CacheDownloadJob>,
+    fetcher: &F,
+    semaphore: Arc<Semaphore>,
+    encrypt: bool,
+    on_start: impl Fn(&str) + Clone + Send + 'static,
+) -> Vec<Result<Option<CachedFile>, String>> {
+    let job_count = jobs.len();
+    let mut set = JoinSet::new();
+
+    for (job_index, job) in jobs.into_iter().enumerate() {
+        let fetcher = fetcher.clone();
+        let semaphore = semaphore.clone();
+        let on_start = on_start.clone();
+        set.spawn(async move {
+            let _permit = match semaphore.acquire_owned().await {
+                Ok(permit) => permit,
+                Err(e) => return (job_index, Err(format!("Download queue closed: {}", e))),
+            };
+            on_start(&job.display_name);
+            (job_index, download_to_cache(&fetcher, &job, encrypt).await)
+        });
+    }
+
+    let mut results: Vec<Result<Option<CachedFile>, String>> = (0..job_count)
+        .map(|_| Err("Download task did not complete".to_string()))
+        .collect();
+    while let Some(joined) = set.join_next().await {
+        match joined {
+            Ok((job_index, result)) => results[job_index] = result,
+            Err(e) => error!("Download task panicked or was cancelled: {}", e),
+        }
+    }
+    results
+}
+
+pub(crate) fn author_filter_for<'a>(settings: &'a AuthorFilterSettings, channel_id: &str) -> &'a AuthorFilter {
+    settings
+        .channel_overrides
+        .get(channel_id)
+        .unwrap_or(&settings.global)
+}
+
+pub(crate) fn is_author_allowed(filter: &AuthorFilter, author_id: &str, is_bot: bool) -> bool {
+    if filter.skip_bot_authors && is_bot {
+        return false;
+    }
+    if filter.blocked_author_ids.iter().any(|id| id == author_id) {
+        return false;
+    }
+    filter.allowed_author_ids.is_empty() || filter.allowed_author_ids.iter().any(|id| id == author_id)
+}
+
+pub(crate) fn has_spoiler_attachment(msg: &Message) -> bool {
+    msg.attachments
+        .iter()
+        .any(|attachment| attachment.filename.starts_with("SPOILER_"))
+}
+
+// Threads always report nsfw = false; the flag lives on their parent channel.
+pub(crate) async fn is_nsfw_channel(http: &Http, channel: &GuildChannel) -> bool {
+    if channel.nsfw {
+        return true;
+    }
+    match channel.parent_id {
+        Some(parent_id) if channel.thread_metadata.is_some() => matches!(
+            http.get_channel(parent_id).await,
+            Ok(serenity::model::channel::Channel::Guild(parent)) if parent.nsfw
+        ),
+        _ => false,
+    }
+}
+
+// Re-indexing an already stored message only refreshes its author identity, reaction counts and
+// sensitivity flags.
+// Every message in a batch comes from the same channel, so they share `nsfw_channel`.
+pub(crate) fn insert_indexed_messages(
+    conn: &mut Connection,
+    batch: &[(Message, Vec<CachedFile>)],
+    nsfw_channel: bool,
+) -> Result<(), String> {
+    let tx = conn.transaction().context("Begin Tx")?;
+    {
+        let mut stmt = tx.prepare_cached(
+            "INSERT INTO messages (message_id, channel_id, author_id, author_name, author_avatar, message_content, attachments, timestamp, attachment_types, attachment_sources, reaction_count, reactions_json, reply_to_json, author_avatar_path, is_spoiler, is_nsfw_channel, author_type) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)
+             ON CONFLICT(message_id) DO UPDATE SET author_id = excluded.author_id, author_type = excluded.author_type, reaction_count = excluded.reaction_count, reactions_json = excluded.reactions_json, reply_to_json = COALESCE(messages.reply_to_json, excluded.reply_to_json), author_avatar_path = COALESCE(excluded.author_avatar_path, messages.author_avatar_path), is_spoiler = excluded.is_spoiler, is_nsfw_channel = excluded.is_nsfw_channel"
+        ).context("Prepare Stmt")?;
+
+        for (msg, saved) in batch {
+            let filenames: Vec<&str> = saved.iter().map(|f| f.relative_path.as_str()).collect();
+            let types: Vec<&str> = saved.iter().map(|f| f.attachment_type).collect();
+            let sources: Vec<&str> = saved.iter().map(|f| f.source).collect();
+            let attachments_json = serde_json::to_string(&filenames)
+                .context("JSON Serialize")?;
+            let types_json = serde_json::to_string(&types)
+                .context("JSON Serialize")?;
+            let sources_json = serde_json::to_string(&sources)
+                .context("JSON Serialize")?;
+            let reactions = reaction_summaries(msg);
+            let reaction_count: u64 = reactions.iter().map(|r| r.count).sum();
+            let reactions_json = serde_json::to_string(&reactions)
+                .context("JSON Serialize")?;
+            let reply_to_json = reply_context(msg)
+                .map(|reply| serde_json::to_string(&reply))
+                .transpose()
+                .context("JSON Serialize")?;
+            let (author_id, author_type) = message_author(msg);
+            stmt.execute(params![
+                msg.id.to_string(),
+                msg.channel_id.to_string(),
+                author_id,
+                msg.author.name,
+                msg.author.avatar_url(),
+                msg.content,
+                attachments_json,
+                msg.timestamp.unix_timestamp(),
+                types_json,
+                sources_json,
+                reaction_count as i64,
+                reactions_json,
+                reply_to_json,
+                avatars::avatar_cache_path(&msg.author),
+                has_spoiler_attachment(msg),
+                nsfw_channel,
+                author_type,
+            ])
+            .context(format!("Exec Insert ({})", msg.id))?;
+            for file in saved {
+                if let Some(hash) = &file.content_hash {
+                    cache_dedupe::record_content_hash(&tx, &file.relative_path, hash)?;
+                }
+            }
+        }
+    }
+    tx.commit().map_err(|e| format!("Commit Tx: {}", e))
+}
+
+pub(crate) fn to_indexed_message(msg: &Message, saved: &[CachedFile], nsfw_channel: bool) -> IndexedMessage {
+    let reactions = reaction_summaries(msg);
+    let (author_id, author_type) = message_author(msg);
+    IndexedMessage {
+        message_id: msg.id.to_string(),
+        channel_id: msg.channel_id.to_string(),
+        author_id,
+        author_name: msg.author.name.clone(),
+        author_avatar: msg.author.avatar_url(),
+        message_content: msg.content.clone(),
+        attachments: saved.iter().map(|f| f.relative_path.clone()).collect(),
+        attachment_types: saved.iter().map(|f| f.attachment_type.to_string()).collect(),
+        attachment_sources: saved.iter().map(|f| f.source.to_string()).collect(),
+        timestamp: msg.timestamp.unix_timestamp(),
+        is_used: false,
+        reaction_count: reactions.iter().map(|r| r.count as i64).sum(),
+        reactions,
+        deleted: false,
+        reply_to: reply_context(msg),
+        author_avatar_path: avatars::avatar_cache_path(&msg.author),
+        is_submission: false,
+        is_spoiler: has_spoiler_attachment(msg),
+        is_nsfw_channel: nsfw_channel,
+        review_decision: None,
+        author_type: author_type.to_string(),
+        is_favorite: false,
+    }
+}
+
+pub(crate) const AUTHOR_TYPE_USER: &str = "user";
+const AUTHOR_TYPE_WEBHOOK: &str = "webhook";
+pub(crate) const AUTHOR_TYPE_BOT: &str = "bot";
+
+// Every message a webhook posts (cross-posts, followed announcement channels) carries the
+// webhook's own user ID with a per-message name and avatar, so the name is folded into the
+// author ID to keep credits, filters and author caps apart per displayed identity.
+pub(crate) fn message_author(msg: &Message) -> (String, &'static str) {
+    match msg.webhook_id {
+        Some(webhook_id) => {
+            let name_part: String = msg
+                .author
+                .name
+                .to_lowercase()
+                .chars()
+                .map(|c| if c.is_alphanumeric() { c } else { '-' })
+                .collect();
+            let name_part = name_part.trim_matches('-');
+            let author_id = if name_part.is_empty() {
+                format!("webhook-{}", webhook_id)
+            } else {
+                format!("webhook-{}-{}", webhook_id, name_part)
+            };
+            (author_id, AUTHOR_TYPE_WEBHOOK)
+        }
+        None if msg.author.bot => (msg.author.id.to_string(), AUTHOR_TYPE_BOT),
+        None => (msg.author.id.to_string(), AUTHOR_TYPE_USER),
+    }
+}
+
+// Discord includes the referenced message on replies; it is None when that message was deleted.
+fn reply_context(msg: &Message) -> Option<ReplyContext> {
+    msg.referenced_message.as_ref().map(|referenced| ReplyContext {
+        message_id: referenced.id.to_string(),
+        author_id: referenced.author.id.to_string(),
+        author_name: referenced.author.name.clone(),
+        content: referenced.content.clone(),
+    })
+}
+
+pub(crate) fn reaction_summaries(msg: &Message) -> Vec<ReactionSummary> {
+    msg.reactions
+        .iter()
+        .map(|reaction| ReactionSummary {
+            emoji: reaction.reaction_type.to_string(),
+            count: reaction.count,
+        })
+        .collect()
+}
+
+pub(crate) fn write_cache_file(path: &Path, bytes: &[u8], encrypt: bool) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .context(format!("Failed to create '{}'", parent.display()))?;
+    }
+    let write_result = if encrypt {
+        let encrypted = cache_crypto::encrypt_bytes(bytes)?;
+        fs::write(path, encrypted)
+    } else {
+        fs::write(path, bytes)
+    };
+    write_result.map_err(|e| format!("Failed to write '{}': {}", path.display(), e))
+}
+
+pub(crate) const BOT_TOKEN_KEY: &str = "discordBotToken";
+
+pub(crate) fn bot_token_from(secrets: &impl SecretStore) -> Result<String, AppError> {
+    match secrets
+        .get_secret(BOT_TOKEN_KEY)
+        .map_err(|e| e.with_context("Failed to retrieve token"))?
+    {
+        Some(t) if !t.is_empty() => Ok(t),
+        Some(_) => Err(AppError::auth("Stored Discord Bot Token is empty.")),
+        None => Err(AppError::auth("Discord Bot Token not found. Please save it first.")),
+    }
+}
+
+pub(crate) fn load_bot_token() -> Result<String, AppError> {
+    bot_token_from(&KeyringSecrets)
 }
 
 #[tauri::command]
 fn get_cached_image_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
-    let app_data_dir = app_handle
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
+    let app_data_dir = app_paths::app_data_dir(app_handle)?;
     Ok(app_data_dir.join("images").join("cached"))
 }
 #[tauri::command]
 pub async fn get_discord_channels(
     guild_id_str: String,
-) -> Result<Vec<SerializableChannel>, String> {
+    db_state: State<'_, DbConnection>,
+) -> Result<Vec<SerializableChannel>, AppError> {
     info!(
         "Attempting to fetch channels for guild ID: {}",
         guild_id_str
@@ -71,30 +959,29 @@ pub async fn get_discord_channels(
     let guild_id = match guild_id_str.parse::<u64>() {
         Ok(id) => GuildId::new(id),
         Err(_) => {
-            return Err(format!(
+            return Err(AppError::invalid_input(format!(
                 "Invalid Guild ID format provided: '{}'",
                 guild_id_str
-            ))
+            )))
         }
     };
 
-    let token_key_name = "discordBotToken";
-    let token_entry = Entry::new(KEYRING_SERVICE_NAME, token_key_name)
-        .map_err(|e| format!("Keyring error: {}", e))?;
-
-    let token = match token_entry.get_password() {
-        Ok(t) => t,
-        Err(keyring::Error::NoEntry) => {
-            return Err("Discord Bot Token not found. Please save it first.".to_string())
+    let token = match KeyringSecrets
+        .get_secret(BOT_TOKEN_KEY)
+        .map_err(|e| e.with_context("Failed to retrieve token"))?
+    {
+        Some(t) => t,
+        None => {
+            // Discord only serves guild channels and message history to bots.
+            return Err(AppError::auth("Discord Bot Token not found. Please save it first; channels cannot be listed with a Discord user login."))
         }
-        Err(e) => return Err(format!("Failed to retrieve token: {}", e)),
     };
 
     if token.is_empty() {
-        return Err("Stored Discord Bot Token is empty.".to_string());
+        return Err(AppError::auth("Stored Discord Bot Token is empty."));
     }
 
-    let http = Arc::new(Http::new(&token));
+    let http = Arc::new(configured_discord_http(&db_state, &token).await?);
 
     match http.get_channels(guild_id).await {
         Ok(channels) => {
@@ -104,29 +991,30 @@ pub async fn get_discord_channels(
                 guild_id
             );
 
+            let mut channels = channels;
+            match http.get_guild_active_threads(guild_id).await {
+                Ok(threads) => {
+                    info!("Found {} active threads in guild {}", threads.threads.len(), guild_id);
+                    channels.extend(threads.threads);
+                }
+                Err(e) => warn!("Failed to fetch active threads for guild {}: {}", guild_id, e),
+            }
+
+            // Threads use their parent channel as "category" so the UI can group them.
             let category_names: HashMap<ChannelId, String> = channels
                 .iter()
-                .filter(|ch| ch.kind == ChannelType::Category)
+                .filter(|ch| {
+                    ch.kind == ChannelType::Category
+                        || (INDEXABLE_CHANNEL_KINDS.contains(&ch.kind)
+                            && ch.kind != ChannelType::PublicThread)
+                })
                 .map(|cat| (cat.id, cat.name.clone()))
                 .collect();
 
             let mut serializable_channels = channels
                 .into_iter()
-                .filter(|ch| ch.kind == ChannelType::Text)
-                .map(|ch: GuildChannel| {
-                    let parent_name = ch
-                        .parent_id
-                        .and_then(|pid| category_names.get(&pid).cloned());
-
-                    SerializableChannel {
-                        id: ch.id.to_string(),
-                        name: ch.name,
-                        topic: ch.topic,
-                        position: ch.position,
-                        parent_id: ch.parent_id.map(|pid| pid.to_string()),
-                        parent_name,
-                    }
-                })
+                .filter(|ch| INDEXABLE_CHANNEL_KINDS.contains(&ch.kind))
+                .map(|ch| serializable_channel(ch, &category_names))
                 .collect::<Vec<_>>();
 
             serializable_channels.sort_by_key(|c| c.position);
@@ -138,168 +1026,1171 @@ pub async fn get_discord_channels(
                 if let Some(status) = http_err.status_code() {
                     match status.as_u16() {
                         401 => {
-                            return Err(
-                                "Discord API Error: Invalid Token (Unauthorized).".to_string()
-                            )
+                            return Err(AppError::auth(
+                                "Discord API Error: Invalid Token (Unauthorized).",
+                            ))
                         }
                         403 => {
-                            return Err(format!(
-                            "Discord API Error: Missing permissions to view channels in guild {}.",
-                            guild_id_str
-                        ))
+                            return Err(AppError::new(
+                                ErrorKind::PermissionDenied,
+                                format!(
+                                    "Discord API Error: Missing permissions to view channels in guild {}.",
+                                    guild_id_str
+                                ),
+                            ))
                         }
                         404 => {
-                            return Err(format!(
+                            return Err(AppError::not_found(format!(
                                 "Discord API Error: Guild not found (ID: {}).",
                                 guild_id_str
-                            ))
+                            )))
                         }
                         _ => {}
                     }
                 }
             }
-            Err(format!(
-                "Failed to fetch channels for guild {}. Error: {}",
-                guild_id_str, e
+            Err(AppError::from(e)
+                .with_context(format!("Failed to fetch channels for guild {}", guild_id_str)))
+        }
+    }
+}
+
+fn serializable_channel(ch: GuildChannel, category_names: &HashMap<ChannelId, String>) -> SerializableChannel {
+    let parent_name = ch
+        .parent_id
+        .and_then(|pid| category_names.get(&pid).cloned());
+
+    SerializableChannel {
+        id: ch.id.to_string(),
+        name: ch.name,
+        topic: ch.topic,
+        position: ch.position,
+        parent_id: ch.parent_id.map(|pid| pid.to_string()),
+        parent_name,
+        kind: channel_kind_name(ch.kind).to_string(),
+    }
+}
+
+// Glob-style and case-insensitive: `*` matches any run of characters, `?` a single one.
+fn channel_pattern_regex(pattern: &str) -> Result<Regex, String> {
+    let mut source = String::from("^");
+    for c in pattern.trim().chars() {
+        match c {
+            '*' => source.push_str(".*"),
+            '?' => source.push('.'),
+            _ => source.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    source.push('$');
+    RegexBuilder::new(&source)
+        .case_insensitive(true)
+        .build()
+        .map_err(|e| format!("Invalid channel name pattern '{}': {}", pattern, e))
+}
+
+// Threads are left out; expand_channels_with_threads adds them for every matched channel.
+fn match_channels_by_name<'a>(
+    channels: &'a [GuildChannel],
+    patterns: &[String],
+) -> Result<Vec<&'a GuildChannel>, String> {
+    let regexes = patterns
+        .iter()
+        .filter(|p| !p.trim().is_empty())
+        .map(|p| channel_pattern_regex(p))
+        .collect::<Result<Vec<Regex>, String>>()?;
+    let mut matched: Vec<&GuildChannel> = channels
+        .iter()
+        .filter(|ch| INDEXABLE_CHANNEL_KINDS.contains(&ch.kind) && ch.kind != ChannelType::PublicThread)
+        .filter(|ch| regexes.iter().any(|re| re.is_match(&ch.name)))
+        .collect();
+    matched.sort_by_key(|ch| ch.position);
+    Ok(matched)
+}
+
+// The hand-picked channels plus, with auto-discovery on, every channel whose name currently
+// matches a saved pattern, so channels created since setup are picked up.
+pub(crate) async fn resolve_indexing_channels(http: &Http, config: &AppConfig) -> Vec<String> {
+    let mut channel_ids = config.selected_channel_ids.clone();
+    let patterns = config.channel_name_patterns.clone().unwrap_or_default();
+    if !config.auto_discover_channels.unwrap_or(false) || patterns.is_empty() {
+        return channel_ids;
+    }
+    let Some(guild_id) = config
+        .selected_server_id
+        .as_deref()
+        .and_then(|id| id.parse::<u64>().ok())
+        .map(GuildId::new)
+    else {
+        warn!("Channel auto-discovery needs a selected server, using the selected channels only.");
+        return channel_ids;
+    };
+
+    let discovered = match http.get_channels(guild_id).await {
+        Ok(channels) => match match_channels_by_name(&channels, &patterns) {
+            Ok(matched) => matched.iter().map(|ch| ch.id.to_string()).collect::<Vec<_>>(),
+            Err(e) => {
+                error!("{}", e);
+                Vec::new()
+            }
+        },
+        Err(e) => {
+            warn!("Channel auto-discovery failed for guild {}: {}", guild_id, e);
+            Vec::new()
+        }
+    };
+    info!("Auto-discovered {} channel(s) matching {:?}", discovered.len(), patterns);
+    for id in discovered {
+        if !channel_ids.contains(&id) {
+            channel_ids.push(id);
+        }
+    }
+    channel_ids
+}
+
+// Lists the selected server's channels matching the patterns; pass patterns to try them
+// before saving, or None to use the saved ones.
+#[tauri::command]
+pub async fn discover_channels(
+    patterns: Option<Vec<String>>,
+    db_state: State<'_, DbConnection>,
+) -> Result<Vec<SerializableChannel>, AppError> {
+    let config = db_state.0.run(|conn| Ok(retrieve_config(conn)?)).await?;
+    let patterns = patterns.unwrap_or_else(|| config.channel_name_patterns.clone().unwrap_or_default());
+    let guild_id = config
+        .selected_server_id
+        .as_deref()
+        .and_then(|id| id.parse::<u64>().ok())
+        .map(GuildId::new)
+        .ok_or_else(|| "Select a server before discovering channels.".to_string())?;
+
+    let http = configured_discord_http(&db_state, &load_bot_token()?).await?;
+    let channels = http
+        .get_channels(guild_id)
+        .await
+        .context(format!("Failed to fetch channels for guild {}", guild_id))?;
+    let category_names: HashMap<ChannelId, String> = channels
+        .iter()
+        .filter(|ch| ch.kind == ChannelType::Category)
+        .map(|cat| (cat.id, cat.name.clone()))
+        .collect();
+
+    let matched = match_channels_by_name(&channels, &patterns)?;
+    info!("{} channel(s) in guild {} match {:?}", matched.len(), guild_id, patterns);
+    Ok(matched
+        .into_iter()
+        .map(|ch| serializable_channel(ch.clone(), &category_names))
+        .collect())
+}
+
+// Live peek at a channel for the setup wizard: nothing is stored or cached.
+#[tauri::command]
+pub async fn preview_channel_messages(
+    channel_id: String,
+    limit: Option<u8>,
+    db_state: State<'_, DbConnection>,
+) -> Result<Vec<ChannelPreviewMessage>, AppError> {
+    let channel_id = channel_id
+        .parse::<u64>()
+        .map(ChannelId::new)
+        .map_err(|_| AppError::invalid_input(format!("Invalid channel ID: {}", channel_id)))?;
+    let limit = limit.unwrap_or(20).clamp(1, PREVIEW_MAX_MESSAGES);
+
+    let http = configured_discord_http(&db_state, &load_bot_token()?).await?;
+    let messages = http
+        .get_messages(channel_id, None, Some(limit))
+        .await
+        .map_err(|e| match http_status(&e) {
+            Some(403) => AppError::new(
+                ErrorKind::PermissionDenied,
+                format!("Missing permissions to read messages in channel {}.", channel_id),
+            ),
+            Some(404) => AppError::not_found(format!("Channel {} not found.", channel_id)),
+            _ => AppError::from(e).with_context(format!("Failed to fetch messages for channel {}", channel_id)),
+        })?;
+
+    info!("Previewing {} message(s) from channel {}", messages.len(), channel_id);
+    Ok(messages
+        .iter()
+        .map(|msg| {
+            let mut media_urls: Vec<String> = msg
+                .attachments
+                .iter()
+                .filter(|attachment| indexable_attachment_type(attachment, true).is_some())
+                .map(|attachment| attachment.url.clone())
+                .collect();
+            media_urls.extend(sticker_images(msg, true).into_iter().map(|image| image.url));
+            media_urls.extend(linked_images(msg, true).into_iter().map(|image| image.url));
+            ChannelPreviewMessage {
+                message_id: msg.id.to_string(),
+                author_name: msg.author.name.clone(),
+                author_avatar: msg.author.avatar_url(),
+                content: msg.content.clone(),
+                timestamp: msg.timestamp.unix_timestamp(),
+                media_urls,
+            }
+        })
+        .collect())
+}
+
+// Without a bot token, a signed-in user's own guild list is used instead.
+#[tauri::command]
+pub async fn fetch_discord_guilds(
+    db_state: State<'_, DbConnection>,
+    oauth_state: State<'_, DiscordOAuthState>,
+) -> Result<Vec<SerializableGuild>, AppError> {
+    info!("Attempting to fetch Discord guilds (from discord module)...");
+
+    let stored_token = KeyringSecrets
+        .get_secret(BOT_TOKEN_KEY)
+        .map_err(|e| e.with_context("Failed to retrieve Discord Bot Token from keyring"))?;
+    let token = match stored_token {
+        Some(t) => t,
+        None => match load_user_access_token(&db_state, &oauth_state).await? {
+            Some(access_token) => {
+                info!("No bot token saved, listing guilds of the signed-in Discord user.");
+                format!("Bearer {}", access_token)
+            }
+            None => {
+                return Err(AppError::auth(
+                    "Discord Bot Token not found in keyring. Please save it first or sign in with Discord.",
+                ));
+            }
+        },
+    };
+
+    if token.is_empty() {
+        return Err(AppError::auth("Stored Discord Bot Token is empty."));
+    }
+
+    let http = Arc::new(configured_discord_http(&db_state, &token).await?);
+
+    match http.get_guilds(None, None).await {
+        Ok(guilds) => {
+            info!("Successfully fetched {} guilds.", guilds.len());
+            let serializable_guilds = guilds
+                .into_iter()
+                .map(|g: GuildInfo| SerializableGuild {
+                    id: g.id.to_string(),
+                    name: g.name,
+                    icon: g.icon.map(|h| h.to_string()),
+                })
+                .collect();
+            Ok(serializable_guilds)
+        }
+        Err(e) => {
+            error!("Failed to fetch guilds from Discord API: {}", e);
+            if let serenity::Error::Http(http_err) = &e {
+                if let Some(status) = http_err.status_code() {
+                    if status.as_u16() == 401 {
+                        return Err(AppError::auth("Discord API Error: Invalid Token (Unauthorized). Please check the saved token."));
+                    }
+                }
+            }
+            Err(AppError::from(e).with_context(
+                "Failed to fetch guilds from Discord API. Check network connection and token permissions",
             ))
         }
     }
 }
 
-#[tauri::command]
-pub async fn fetch_discord_guilds() -> Result<Vec<SerializableGuild>, String> {
-    info!("Attempting to fetch Discord guilds (from discord module)...");
+const DISCORD_OAUTH_AUTHORIZE_URL: &str = "https://discord.com/oauth2/authorize";
+const DISCORD_OAUTH_TOKEN_URL: &str = "https://discord.com/api/oauth2/token";
+const DISCORD_OAUTH_REVOKE_URL: &str = "https://discord.com/api/oauth2/token/revoke";
+// Must be registered as a redirect on the Discord application, with "Public Client" enabled.
+const OAUTH_REDIRECT_PORT: u16 = 53134;
+const OAUTH_SCOPES: &str = "identify guilds";
+const OAUTH_LOGIN_TIMEOUT: Duration = Duration::from_secs(300);
+const OAUTH_REFRESH_TOKEN_KEY: &str = "discordOAuthRefreshToken";
+// Refresh a little before Discord expires the access token.
+const OAUTH_EXPIRY_MARGIN_SECS: i64 = 60;
+
+struct OAuthSession {
+    access_token: String,
+    expires_at: i64,
+}
+
+// The access token lives in memory only; the refresh token is kept in the keyring.
+#[derive(Default)]
+pub struct DiscordOAuthState(tokio::sync::Mutex<Option<OAuthSession>>);
+
+#[derive(serde::Deserialize)]
+struct OAuthTokenResponse {
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_in: i64,
+}
+
+#[derive(serde::Serialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscordOAuthStatus {
+    logged_in: bool,
+    user_id: Option<String>,
+    user_name: Option<String>,
+}
+
+fn oauth_redirect_uri() -> String {
+    format!("http://127.0.0.1:{}/callback", OAUTH_REDIRECT_PORT)
+}
+
+async fn oauth_client_id(db_state: &DbConnection) -> Result<String, String> {
+    db_state
+        .0
+        .run(|conn| Ok(retrieve_config(conn)?.discord_oauth_client_id))
+        .await?
+        .filter(|id| !id.trim().is_empty())
+        .ok_or_else(|| "No Discord OAuth client ID configured.".to_string())
+}
+
+// PKCE (RFC 7636): the verifier is three v4 UUIDs (366 random bits), the challenge its SHA-256.
+pub(crate) fn pkce_pair() -> (String, String) {
+    let verifier: String = (0..3).map(|_| Uuid::new_v4().simple().to_string()).collect();
+    let challenge = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .encode(Sha256::digest(verifier.as_bytes()));
+    (verifier, challenge)
+}
+
+fn load_refresh_token() -> Result<Option<String>, AppError> {
+    Ok(KeyringSecrets
+        .get_secret(OAUTH_REFRESH_TOKEN_KEY)
+        .map_err(|e| e.with_context("Failed to retrieve Discord refresh token"))?
+        .filter(|token| !token.is_empty()))
+}
+
+async fn request_oauth_token(
+    db_state: &DbConnection,
+    params: &[(&str, &str)],
+) -> Result<OAuthTokenResponse, String> {
+    let response = configured_http_client(db_state, None).await?
+        .post(DISCORD_OAUTH_TOKEN_URL)
+        .form(params)
+        .send()
+        .await
+        .context("Failed to reach Discord OAuth")?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("Discord OAuth token request failed ({}): {}", status, body));
+    }
+    response
+        .json::<OAuthTokenResponse>()
+        .await
+        .map_err(|e| format!("Invalid Discord OAuth token response: {}", e))
+}
+
+async fn store_oauth_tokens(
+    oauth_state: &DiscordOAuthState,
+    tokens: OAuthTokenResponse,
+) -> Result<String, String> {
+    // Discord rotates refresh tokens; the old one stops working once a new one is issued.
+    if let Some(refresh_token) = &tokens.refresh_token {
+        KeyringSecrets
+            .set_secret(OAUTH_REFRESH_TOKEN_KEY, refresh_token)
+            .map_err(|e| e.with_context("Failed to save Discord refresh token"))?;
+    }
+    *oauth_state.0.lock().await = Some(OAuthSession {
+        access_token: tokens.access_token.clone(),
+        expires_at: Utc::now().timestamp() + tokens.expires_in,
+    });
+    Ok(tokens.access_token)
+}
+
+// None when nobody is signed in. An expired access token is refreshed transparently.
+pub(crate) async fn load_user_access_token(
+    db_state: &DbConnection,
+    oauth_state: &DiscordOAuthState,
+) -> Result<Option<String>, String> {
+    if let Some(session) = oauth_state.0.lock().await.as_ref() {
+        if session.expires_at - OAUTH_EXPIRY_MARGIN_SECS > Utc::now().timestamp() {
+            return Ok(Some(session.access_token.clone()));
+        }
+    }
+    let Some(refresh_token) = load_refresh_token()? else {
+        return Ok(None);
+    };
+    let client_id = oauth_client_id(db_state).await?;
+    let tokens = request_oauth_token(db_state, &[
+        ("client_id", client_id.as_str()),
+        ("grant_type", "refresh_token"),
+        ("refresh_token", refresh_token.as_str()),
+    ])
+    .await?;
+    info!("Refreshed Discord OAuth access token.");
+    store_oauth_tokens(oauth_state, tokens).await.map(Some)
+}
+
+fn write_oauth_page(stream: &mut std::net::TcpStream, status: &str, message: &str) {
+    use std::io::Write;
+    let body = format!(
+        "<!doctype html><html><body style=\"font-family:sans-serif;background:#000;color:#fff;text-align:center;padding-top:20vh\"><h2>{}</h2><p>You can close this tab and return to Showcase Studio.</p></body></html>",
+        message
+    );
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).ok();
+}
+
+// Serves the loopback redirect until the provider sends the user back with a code (or an
+// error). The listener is polled so the port is released once the login times out.
+pub(crate) fn wait_for_oauth_callback(
+    listener: std::net::TcpListener,
+    expected_state: &str,
+    provider: &str,
+) -> Result<String, String> {
+    use std::io::{BufRead, BufReader};
+    let deadline = std::time::Instant::now() + OAUTH_LOGIN_TIMEOUT;
+    listener
+        .set_nonblocking(true)
+        .context("Failed to configure OAuth listener")?;
+    loop {
+        let mut stream = match listener.accept() {
+            Ok((stream, _)) => stream,
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                if std::time::Instant::now() >= deadline {
+                    return Err(format!("{} sign-in timed out.", provider));
+                }
+                std::thread::sleep(Duration::from_millis(200));
+                continue;
+            }
+            Err(e) => return Err(format!("OAuth callback connection failed: {}", e)),
+        };
+        stream.set_nonblocking(false).ok();
+        stream.set_read_timeout(Some(Duration::from_secs(10))).ok();
+        let mut request_line = String::new();
+        BufReader::new(&stream)
+            .read_line(&mut request_line)
+            .context("Failed to read OAuth callback")?;
+        let path = request_line.split_whitespace().nth(1).unwrap_or("");
+        let Ok(url) = reqwest::Url::parse(&format!("http://127.0.0.1{}", path)) else {
+            write_oauth_page(&mut stream, "400 Bad Request", "Invalid request");
+            continue;
+        };
+        if url.path() != "/callback" {
+            // e.g. the browser asking for /favicon.ico
+            write_oauth_page(&mut stream, "404 Not Found", "Not found");
+            continue;
+        }
+
+        let query: HashMap<String, String> = url.query_pairs().into_owned().collect();
+        if query.get("state").map(String::as_str) != Some(expected_state) {
+            write_oauth_page(&mut stream, "400 Bad Request", "Sign-in failed: state mismatch");
+            return Err(format!("{} sign-in failed: state mismatch.", provider));
+        }
+        if let Some(error) = query.get("error") {
+            write_oauth_page(&mut stream, "200 OK", "Sign-in was cancelled");
+            return Err(format!("{} sign-in was not completed: {}", provider, error));
+        }
+        return match query.get("code") {
+            Some(code) => {
+                write_oauth_page(&mut stream, "200 OK", "Signed in to Showcase Studio");
+                Ok(code.clone())
+            }
+            None => {
+                write_oauth_page(&mut stream, "400 Bad Request", "Sign-in failed: no code received");
+                Err(format!("{} sign-in failed: no authorization code received.", provider))
+            }
+        };
+    }
+}
+
+async fn oauth_status_for_token(
+    db_state: &DbConnection,
+    access_token: &str,
+) -> Result<DiscordOAuthStatus, String> {
+    let user = configured_discord_http(db_state, &format!("Bearer {}", access_token))
+        .await?
+        .get_current_user()
+        .await
+        .context("Failed to fetch the signed-in Discord user")?;
+    Ok(DiscordOAuthStatus {
+        logged_in: true,
+        user_id: Some(user.id.to_string()),
+        user_name: Some(user.name.clone()),
+    })
+}
+
+// Authorization code flow with PKCE: opens Discord in the browser and catches the redirect on
+// a loopback port. User tokens can list the user's guilds; channels and message history still
+// need a bot token, because Discord does not expose them to user OAuth scopes.
+#[tauri::command]
+pub async fn start_discord_oauth_login(
+    app_handle: AppHandle,
+    db_state: State<'_, DbConnection>,
+    oauth_state: State<'_, DiscordOAuthState>,
+) -> Result<DiscordOAuthStatus, AppError> {
+    let client_id = oauth_client_id(&db_state).await?;
+    let (verifier, challenge) = pkce_pair();
+    let state = Uuid::new_v4().simple().to_string();
+    let redirect_uri = oauth_redirect_uri();
+
+    let listener = std::net::TcpListener::bind(("127.0.0.1", OAUTH_REDIRECT_PORT))
+        .context(format!("Failed to listen on port {} for the Discord redirect", OAUTH_REDIRECT_PORT))?;
+    let mut authorize_url = reqwest::Url::parse(DISCORD_OAUTH_AUTHORIZE_URL)
+        .map_err(|e| format!("Invalid authorize URL: {}", e))?;
+    authorize_url
+        .query_pairs_mut()
+        .append_pair("client_id", &client_id)
+        .append_pair("response_type", "code")
+        .append_pair("redirect_uri", &redirect_uri)
+        .append_pair("scope", OAUTH_SCOPES)
+        .append_pair("state", &state)
+        .append_pair("code_challenge", &challenge)
+        .append_pair("code_challenge_method", "S256")
+        .append_pair("prompt", "consent");
+
+    info!("Opening Discord sign-in in the browser...");
+    app_handle
+        .opener()
+        .open_url(authorize_url.as_str(), None::<&str>)
+        .map_err(|e| format!("Failed to open the Discord sign-in page: {}", e))?;
+
+    let code = tokio::task::spawn_blocking(move || wait_for_oauth_callback(listener, &state, "Discord"))
+        .await
+        .context("OAuth callback task failed")??;
+
+    let tokens = request_oauth_token(&db_state, &[
+        ("client_id", client_id.as_str()),
+        ("grant_type", "authorization_code"),
+        ("code", code.as_str()),
+        ("redirect_uri", redirect_uri.as_str()),
+        ("code_verifier", verifier.as_str()),
+    ])
+    .await?;
+    let access_token = store_oauth_tokens(&oauth_state, tokens).await?;
+    let status = oauth_status_for_token(&db_state, &access_token).await?;
+    info!("Signed in to Discord as {:?}", status.user_name);
+    Ok(status)
+}
+
+#[tauri::command]
+pub async fn get_discord_oauth_status(
+    db_state: State<'_, DbConnection>,
+    oauth_state: State<'_, DiscordOAuthState>,
+) -> Result<DiscordOAuthStatus, AppError> {
+    match load_user_access_token(&db_state, &oauth_state).await? {
+        Some(access_token) => Ok(oauth_status_for_token(&db_state, &access_token).await?),
+        None => Ok(DiscordOAuthStatus::default()),
+    }
+}
+
+// Revocation is best effort; the local tokens are removed either way.
+#[tauri::command]
+pub async fn logout_discord_oauth(
+    db_state: State<'_, DbConnection>,
+    oauth_state: State<'_, DiscordOAuthState>,
+) -> Result<(), AppError> {
+    if let (Some(refresh_token), Ok(client_id)) = (load_refresh_token()?, oauth_client_id(&db_state).await) {
+        let revoked = match configured_http_client(&db_state, None).await {
+            Ok(client) => client
+                .post(DISCORD_OAUTH_REVOKE_URL)
+                .form(&[
+                    ("client_id", client_id.as_str()),
+                    ("token", refresh_token.as_str()),
+                    ("token_type_hint", "refresh_token"),
+                ])
+                .send()
+                .await
+                .and_then(|r| r.error_for_status())
+                .map(|_| ())
+                .map_err(|e| e.to_string()),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = revoked {
+            warn!("Failed to revoke Discord refresh token: {}", e);
+        }
+    }
+    KeyringSecrets
+        .delete_secret(OAUTH_REFRESH_TOKEN_KEY)
+        .map_err(|e| e.with_context("Failed to delete Discord refresh token"))?;
+    *oauth_state.0.lock().await = None;
+    info!("Signed out of Discord.");
+    Ok(())
+}
+
+fn http_status(e: &serenity::Error) -> Option<u16> {
+    match e {
+        serenity::Error::Http(http_err) => http_err.status_code().map(|s| s.as_u16()),
+        _ => None,
+    }
+}
+
+// Checks the stored token against the API before indexing starts. Guild and channels default
+// to the saved selection; each problem found is added to `errors` as a message for the user.
+#[tauri::command]
+pub async fn validate_discord_token(
+    guild_id: Option<String>,
+    channel_ids: Option<Vec<String>>,
+    db_state: State<'_, DbConnection>,
+) -> Result<TokenValidationReport, AppError> {
+    info!("Validating Discord bot token...");
+    let config = db_state.0.run(|conn| Ok(retrieve_config(conn)?)).await?;
+    let guild_id_str = guild_id.or(config.selected_server_id);
+    let channel_ids = channel_ids.unwrap_or(config.selected_channel_ids);
+
+    let mut report = TokenValidationReport {
+        guild_id: guild_id_str.clone(),
+        ..Default::default()
+    };
+
+    let token = match load_bot_token() {
+        Ok(token) => token,
+        Err(e) => {
+            report.errors.push(e.message);
+            return Ok(report);
+        }
+    };
+    let http = match configured_discord_http(&db_state, &token).await {
+        Ok(http) => http,
+        Err(e) => {
+            report.errors.push(e);
+            return Ok(report);
+        }
+    };
+
+    let bot_user = match http.get_current_user().await {
+        Ok(user) => user,
+        Err(e) => {
+            warn!("Token validation: failed to fetch bot user: {}", e);
+            report.errors.push(match http_status(&e) {
+                Some(401) => "The saved bot token was rejected by Discord. Reset the token in the Developer Portal and save it again.".to_string(),
+                _ => format!("Could not reach the Discord API: {}", e),
+            });
+            return Ok(report);
+        }
+    };
+    report.token_valid = true;
+    report.bot_user_id = Some(bot_user.id.to_string());
+    report.bot_user_name = Some(bot_user.name.clone());
+
+    let Some(guild_id_str) = guild_id_str else {
+        return Ok(report);
+    };
+    let guild_id = match guild_id_str.parse::<u64>() {
+        Ok(id) if id != 0 => GuildId::new(id),
+        _ => {
+            report.errors.push(format!("Invalid server ID '{}'.", guild_id_str));
+            return Ok(report);
+        }
+    };
+
+    let guild = match http.get_guild(guild_id).await {
+        Ok(guild) => guild,
+        Err(e) => {
+            report.guild_visible = Some(false);
+            report.errors.push(match http_status(&e) {
+                Some(403) | Some(404) => format!(
+                    "The bot is not a member of server {}. Invite it to the server and try again.",
+                    guild_id_str
+                ),
+                _ => format!("Failed to fetch server {}: {}", guild_id_str, e),
+            });
+            return Ok(report);
+        }
+    };
+    report.guild_visible = Some(true);
+    report.guild_name = Some(guild.name.clone());
+
+    if channel_ids.is_empty() {
+        return Ok(report);
+    }
+
+    let member = http
+        .get_member(guild_id, bot_user.id)
+        .await
+        .context(format!("Failed to fetch the bot's member in server {}", guild_id_str))?;
+    let guild_channels: HashMap<ChannelId, GuildChannel> = http
+        .get_channels(guild_id)
+        .await
+        .context(format!("Failed to fetch channels of server {}", guild_id_str))?
+        .into_iter()
+        .map(|c| (c.id, c))
+        .collect();
+
+    for channel_id_str in &channel_ids {
+        let Some(channel_id) = channel_id_str
+            .parse::<u64>()
+            .ok()
+            .filter(|id| *id != 0)
+            .map(ChannelId::new)
+        else {
+            report.channels_not_found.push(channel_id_str.clone());
+            continue;
+        };
+
+        // Threads are not in the guild channel list; they inherit their parent's permissions.
+        let channel = match guild_channels.get(&channel_id) {
+            Some(channel) => Some(channel.clone()),
+            None => match http.get_channel(channel_id).await {
+                Ok(serenity::model::channel::Channel::Guild(thread)) if thread.guild_id == guild_id => {
+                    thread.parent_id.and_then(|p| guild_channels.get(&p).cloned())
+                }
+                _ => None,
+            },
+        };
+        let Some(channel) = channel else {
+            report.channels_not_found.push(channel_id_str.clone());
+            continue;
+        };
+
+        let permissions = guild.user_permissions_in(&channel, &member);
+        if !permissions.view_channel() {
+            report.channels_missing_view.push(channel_id_str.clone());
+        } else if !permissions.read_message_history() {
+            report.channels_missing_read_history.push(channel_id_str.clone());
+        }
+    }
+
+    if !report.channels_not_found.is_empty() {
+        report.errors.push(format!(
+            "{} selected channel(s) no longer exist or are not in this server.",
+            report.channels_not_found.len()
+        ));
+    }
+    if !report.channels_missing_view.is_empty() {
+        report.errors.push(format!(
+            "The bot cannot see {} selected channel(s). Grant it the View Channel permission.",
+            report.channels_missing_view.len()
+        ));
+    }
+    if !report.channels_missing_read_history.is_empty() {
+        report.errors.push(format!(
+            "The bot lacks Read Message History in {} selected channel(s).",
+            report.channels_missing_read_history.len()
+        ));
+    }
+
+    info!(
+        "Token validation finished for {} with {} problem(s).",
+        bot_user.name,
+        report.errors.len()
+    );
+    Ok(report)
+}
+
+// Replaces forum channels with their threads and adds the threads of text/news channels.
+async fn expand_channels_with_threads(
+    http: &Http,
+    guild_id: Option<GuildId>,
+    channel_ids: Vec<String>,
+) -> Vec<String> {
+    let active_threads: Vec<GuildChannel> = match guild_id {
+        Some(gid) => match http.get_guild_active_threads(gid).await {
+            Ok(data) => data.threads,
+            Err(e) => {
+                warn!("Failed to fetch active threads for guild {}: {}", gid, e);
+                Vec::new()
+            }
+        },
+        None => Vec::new(),
+    };
+
+    let mut expanded: Vec<String> = Vec::new();
+    for chan_str in channel_ids {
+        let channel_id = match chan_str.parse::<u64>() {
+            Ok(id) => ChannelId::new(id),
+            Err(_) => {
+                // Keep it so the indexing loop reports the invalid ID.
+                expanded.push(chan_str);
+                continue;
+            }
+        };
+
+        let kind = http
+            .get_channel(channel_id)
+            .await
+            .ok()
+            .and_then(|c| c.guild())
+            .map(|c| c.kind);
+
+        if kind != Some(ChannelType::Forum) {
+            expanded.push(chan_str.clone());
+        }
+
+        if !matches!(
+            kind,
+            Some(ChannelType::Text) | Some(ChannelType::News) | Some(ChannelType::Forum)
+        ) {
+            continue;
+        }
+
+        for thread in active_threads
+            .iter()
+            .filter(|t| t.parent_id == Some(channel_id))
+        {
+            expanded.push(thread.id.to_string());
+        }
+
+        match http
+            .get_channel_archived_public_threads(channel_id, None, Some(100))
+            .await
+        {
+            Ok(data) => {
+                if data.has_more {
+                    warn!(
+                        "Channel {} has more than {} archived threads, only the newest are indexed.",
+                        channel_id,
+                        data.threads.len()
+                    );
+                }
+                expanded.extend(data.threads.into_iter().map(|t| t.id.to_string()));
+            }
+            Err(e) => warn!("Failed to fetch archived threads for {}: {}", channel_id, e),
+        }
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    expanded.retain(|id| seen.insert(id.clone()));
+    expanded
+}
+
+fn parse_indexing_date(date_str: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(date_str.trim(), "%Y-%m-%d")
+        .map_err(|e| format!("Invalid date '{}' (expected YYYY-MM-DD): {}", date_str, e))
+}
+
+fn date_to_timestamp(date: NaiveDate) -> i64 {
+    Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).unwrap())
+        .timestamp()
+}
+
+fn default_indexing_start(now: DateTime<Utc>) -> NaiveDate {
+    let first_day_current = NaiveDate::from_ymd_opt(now.year(), now.month(), 1).unwrap();
+    first_day_current
+        .checked_sub_months(Months::new(1))
+        .map(|d| NaiveDate::from_ymd_opt(d.year(), d.month(), 1).unwrap())
+        .unwrap_or_else(|| NaiveDate::from_ymd_opt(now.year() - 1, 12, 1).unwrap())
+}
+
+/// Resolves the `(start, end)` timestamps for an indexing run.
+///
+/// Per-run arguments take precedence over the stored configuration; when nothing
+/// is configured the window falls back to the start of last month.
+/// The end timestamp is exclusive and `None` means "up to now".
+fn resolve_indexing_window(
+    config: &AppConfig,
+    from_date: Option<String>,
+    to_date: Option<String>,
+    window_days: Option<u32>,
+) -> Result<(i64, Option<i64>), String> {
+    let now = Utc::now();
+
+    let end_ts = match to_date.as_deref() {
+        Some(to) => Some(date_to_timestamp(parse_indexing_date(to)?) + 24 * 60 * 60),
+        None => None,
+    };
+
+    let start_ts = if let Some(from) = from_date.as_deref() {
+        date_to_timestamp(parse_indexing_date(from)?)
+    } else if let Some(days) = window_days {
+        (now - chrono::Duration::days(days as i64)).timestamp()
+    } else if let Some(date) = config.indexing_start_date.as_deref() {
+        date_to_timestamp(parse_indexing_date(date)?)
+    } else if let Some(days) = config.indexing_window_days {
+        (now - chrono::Duration::days(days as i64)).timestamp()
+    } else {
+        date_to_timestamp(default_indexing_start(now))
+    };
+
+    if let Some(end) = end_ts {
+        if end <= start_ts {
+            return Err("Indexing end date must be after the start date.".to_string());
+        }
+    }
+
+    Ok((start_ts, end_ts))
+}
+
+// Everything that decides which messages of a page are kept; fixed for a whole indexing run.
+pub(crate) struct BatchOptions {
+    pub start_ts: i64,
+    pub end_ts: Option<i64>,
+    pub author_filters: AuthorFilterSettings,
+    pub content_filter: ContentFilter,
+    pub size_filter: ImageSizeFilter,
+    pub exclude_sensitive: bool,
+    pub include_animated: bool,
+    pub include_linked: bool,
+    pub cache_dir: PathBuf,
+    pub encrypt: bool,
+}
+
+pub(crate) struct BatchOutcome {
+    pub saved: Vec<(Message, Vec<CachedFile>)>, // Ready for insert_indexed_messages
+    pub failed: Vec<MessageId>,                  // An attachment of the message could not be cached
+    pub images_saved: usize,
+    pub download_jobs: usize,
+    pub failed_downloads: usize,
+    pub reached_older_messages: bool, // The page went past the start of the window
+}
 
-    let token_key_name = "discordBotToken";
-    let token_entry = Entry::new(KEYRING_SERVICE_NAME, token_key_name)
-        .map_err(|e| format!("Keyring error: {}", e))?;
+// Filters one page of a channel, downloads the remaining images into the cache and returns the
+// messages to store. A message is dropped when one of its own attachments fails to download;
+// a dead embed or link only loses that image.
+pub(crate) async fn process_message_batch<F: HttpFetcher>(
+    msgs: Vec<Message>,
+    channel_id: &str,
+    options: &BatchOptions,
+    fetcher: &F,
+    semaphore: Arc<Semaphore>,
+    on_download: impl Fn(&str) + Clone + Send + 'static,
+) -> BatchOutcome {
+    let mut batch_messages: Vec<Message> = Vec::new();
+    let mut download_jobs: Vec<CacheDownloadJob> = Vec::new();
+    let mut reached_older_messages = false;
 
-    let token = match token_entry.get_password() {
-        Ok(t) => t,
-        Err(keyring::Error::NoEntry) => {
-            return Err(
-                "Discord Bot Token not found in keyring. Please save it first.".to_string(),
-            );
+    for msg in msgs {
+        if msg.timestamp.unix_timestamp() < options.start_ts {
+            reached_older_messages = true;
+            continue; // Skip older message
         }
-        Err(e) => {
-            return Err(format!(
-                "Failed to retrieve Discord Bot Token from keyring: {}",
-                e
-            ));
+        if options.end_ts.map_or(false, |end| msg.timestamp.unix_timestamp() >= end) {
+            continue; // Skip messages newer than the requested range
+        }
+        let (author_id, author_type) = message_author(&msg);
+        if !is_author_allowed(
+            author_filter_for(&options.author_filters, channel_id),
+            &author_id,
+            author_type == AUTHOR_TYPE_BOT,
+        ) {
+            continue; // Filtered out by the author allowlist/blocklist
+        }
+        if !options.content_filter.allows(&msg.content) {
+            continue; // Filtered out by the include/exclude keywords
+        }
+        if options.exclude_sensitive && has_spoiler_attachment(&msg) {
+            continue; // Spoilered attachments are excluded by config
         }
-    };
 
-    if token.is_empty() {
-        return Err("Stored Discord Bot Token is empty.".to_string());
+        let message_index = batch_messages.len();
+        let message_jobs = collect_download_jobs(
+            &options.cache_dir,
+            message_index,
+            &msg,
+            options.include_animated,
+            options.include_linked,
+            &options.size_filter,
+        );
+        if !message_jobs.is_empty() {
+            download_jobs.extend(message_jobs);
+            batch_messages.push(msg);
+        }
     }
 
-    let http = Arc::new(Http::new(&token));
+    let job_meta: Vec<(usize, &'static str)> = download_jobs
+        .iter()
+        .map(|j| (j.message_index, j.source))
+        .collect();
+    let download_results =
+        run_download_jobs(download_jobs, fetcher, semaphore, options.encrypt, on_download).await;
 
-    match http.get_guilds(None, None).await {
-        Ok(guilds) => {
-            info!("Successfully fetched {} guilds.", guilds.len());
-            let serializable_guilds = guilds
-                .into_iter()
-                .map(|g: GuildInfo| SerializableGuild {
-                    id: g.id.to_string(),
-                    name: g.name,
-                    icon: g.icon.map(|h| h.to_string()),
-                })
-                .collect();
-            Ok(serializable_guilds)
-        }
-        Err(e) => {
-            error!("Failed to fetch guilds from Discord API: {}", e);
-            if let serenity::Error::Http(http_err) = &e {
-                if let Some(status) = http_err.status_code() {
-                    if status.as_u16() == 401 {
-                        return Err("Discord API Error: Invalid Token (Unauthorized). Please check the saved token.".to_string());
-                    }
-                }
+    let mut outcome = BatchOutcome {
+        saved: Vec::new(),
+        failed: Vec::new(),
+        images_saved: 0,
+        download_jobs: job_meta.len(),
+        failed_downloads: download_results.iter().filter(|r| r.is_err()).count(),
+        reached_older_messages,
+    };
+    let mut saved_per_message: Vec<Vec<CachedFile>> =
+        (0..batch_messages.len()).map(|_| Vec::new()).collect();
+    let mut failed_per_message: Vec<bool> = vec![false; batch_messages.len()];
+    for ((message_index, source), result) in job_meta.into_iter().zip(download_results) {
+        match result {
+            Ok(Some(cached_file)) => {
+                saved_per_message[message_index].push(cached_file);
+                outcome.images_saved += 1;
+            }
+            Ok(None) => {}
+            // A dead external link should not drop the message's own attachments.
+            Err(e) if source != SOURCE_ATTACHMENT => warn!("{}", e),
+            Err(e) => {
+                error!("{}", e);
+                failed_per_message[message_index] = true;
             }
-            Err(format!("Failed to fetch guilds from Discord API. Check network connection and token permissions. Error: {}", e))
         }
     }
+
+    for ((msg, saved), failed) in batch_messages
+        .into_iter()
+        .zip(saved_per_message)
+        .zip(failed_per_message)
+    {
+        if failed {
+            outcome.failed.push(msg.id);
+        } else if !saved.is_empty() {
+            outcome.saved.push((msg, saved));
+        }
+    }
+    outcome
+}
+
+// Http's ratelimiter already waits out Retry-After on route buckets, so what reaches this loop
+// is a limit that outlived it or a transient failure; those are retried with growing delays.
+async fn fetch_messages_with_backoff(
+    http: &Http,
+    app_handle: &AppHandle,
+    control: &IndexingControl,
+    channel_id: ChannelId,
+    before_id: Option<MessageId>,
+) -> Result<Vec<Message>, serenity::Error> {
+    let mut attempt = 1;
+    loop {
+        let pagination = before_id.map(MessagePagination::Before);
+        let error = match http.get_messages(channel_id, pagination, Some(100)).await {
+            Ok(msgs) => return Ok(msgs),
+            Err(e) => e,
+        };
+        if attempt >= FETCH_MAX_ATTEMPTS || !is_transient_fetch_error(&error) {
+            return Err(error);
+        }
+
+        let delay = backoff_delay(attempt, None);
+        warn!(
+            "Fetch for channel {} failed (attempt {}/{}): {}. Retrying in {}s",
+            channel_id,
+            attempt,
+            FETCH_MAX_ATTEMPTS,
+            error,
+            delay.as_secs()
+        );
+        app_handle
+            .emit(
+                "indexing-status",
+                format!("Discord request failed, retrying in {}s...", delay.as_secs()),
+            )
+            .unwrap_or_default();
+        if !control.sleep_unless_cancelled(delay).await {
+            return Err(error);
+        }
+        attempt += 1;
+    }
 }
 
 #[tauri::command]
 pub async fn start_initial_indexing(
     app_handle: AppHandle,
     db_state: State<'_, DbConnection>,
-) -> Result<(), String> {
+    from_date: Option<String>,
+    to_date: Option<String>,
+    window_days: Option<u32>,
+    indexing_state: State<'_, IndexingState>,
+) -> Result<(), AppError> {
     info!("Starting initial message indexing (downloading images to cache)...");
 
-    let token_key_name = "discordBotToken";
-    let token_entry = Entry::new(KEYRING_SERVICE_NAME, token_key_name)
-        .map_err(|e| format!("Keyring error: {}", e))?;
-    let token = match token_entry.get_password() {
-        Ok(t) if !t.is_empty() => t,
-        Ok(_) => return Err("Stored Discord Bot Token is empty.".to_string()),
-        Err(keyring::Error::NoEntry) => {
-            return Err("Discord Bot Token not found. Please save it first.".to_string())
-        }
-        Err(e) => return Err(format!("Failed to retrieve token: {}", e)),
-    };
+    if indexing_state.0.is_running() {
+        return Err(AppError::conflict("Indexing is already running."));
+    }
+
+    let token = load_bot_token()?;
     let http_token = if token.starts_with("Bot ") {
         token.clone()
     } else {
         format!("Bot {}", token)
     };
-    let http = Arc::new(Http::new(&http_token));
+    let http = Arc::new(configured_discord_http(&db_state, &http_token).await?);
 
-    let config: AppConfig = {
-        let conn_guard = db_state
-            .0
-            .lock()
-            .map_err(|e| format!("DB lock error for config: {}", e))?;
-        retrieve_config(&conn_guard)?
-    };
-    if config.selected_channel_ids.is_empty() {
+    let config: AppConfig = db_state.0.run(|conn| Ok(retrieve_config(conn)?)).await?;
+    let channel_ids = resolve_indexing_channels(&http, &config).await;
+    if channel_ids.is_empty() {
         app_handle
             .emit("indexing-status", "No channels selected")
             .unwrap_or_default();
         warn!("No channels selected, indexing aborted.");
         return Ok(());
     }
-    let channel_ids = config.selected_channel_ids;
-    info!("Channels to index: {:?}", channel_ids);
+    let encrypt_cache = config.encrypt_image_cache.unwrap_or(false);
+    let include_animated = config.include_animated_attachments.unwrap_or(false);
+    let include_linked = config.index_linked_images.unwrap_or(true);
+    let thumbnail_settings =
+        thumbnails::sanitize_settings(config.thumbnail_settings.clone().unwrap_or_default());
+    let download_concurrency = config
+        .download_concurrency
+        .map(|n| (n as usize).clamp(1, MAX_DOWNLOAD_CONCURRENCY))
+        .unwrap_or(DEFAULT_DOWNLOAD_CONCURRENCY);
+    if encrypt_cache {
+        info!("Cached images will be encrypted at rest.");
+    }
 
-    let now = Utc::now();
-    let first_day_current = NaiveDate::from_ymd_opt(now.year(), now.month(), 1).unwrap();
-    let target_month_start = first_day_current
-        .checked_sub_months(Months::new(1))
-        .map(|d| NaiveDate::from_ymd_opt(d.year(), d.month(), 1).unwrap())
-        .unwrap_or_else(|| NaiveDate::from_ymd_opt(now.year() - 1, 12, 1).unwrap());
-    let start_utc: DateTime<Utc> =
-        Utc.from_utc_datetime(&target_month_start.and_hms_opt(0, 0, 0).unwrap());
-    let start_ts = start_utc.timestamp();
+    let (start_ts, end_ts) = resolve_indexing_window(&config, from_date, to_date, window_days)?;
     info!(
-        "Indexing messages since: {} (Timestamp: {})",
-        start_utc, start_ts
+        "Indexing messages since timestamp {} until {}",
+        start_ts,
+        end_ts.map_or("now".to_string(), |ts| ts.to_string())
     );
 
+    let author_filters = config.author_filters.clone().unwrap_or_default();
+    let size_filter = config.image_size_filter.clone().unwrap_or_default();
+    let content_filter = ContentFilter::compile(&config.content_filters.clone().unwrap_or_default())?;
+    let exclude_sensitive = config.exclude_sensitive_content.unwrap_or(false);
+    let author_cap = config.author_cap.clone().unwrap_or_default();
+    let download_client = http_client(
+        &config.network_settings.clone().unwrap_or_default(),
+        Some(Duration::from_secs(30)),
+    )?;
+
+    let guild_id = config
+        .selected_server_id
+        .as_deref()
+        .and_then(|id| id.parse::<u64>().ok())
+        .map(GuildId::new);
+    info!("Channels to index: {:?}", channel_ids);
+
     let cache_base_dir = get_cached_image_dir(&app_handle)?;
+    let image_base_dir = get_image_base_dir(&app_handle)?;
     info!(
         "Cached images will be stored base: {}",
         cache_base_dir.display()
     );
 
+    let batch_options = BatchOptions {
+        start_ts,
+        end_ts,
+        author_filters,
+        content_filter,
+        size_filter,
+        exclude_sensitive,
+        include_animated,
+        include_linked,
+        cache_dir: cache_base_dir,
+        encrypt: encrypt_cache,
+    };
+
     let http_clone = http.clone();
     let app_clone = app_handle.clone();
     let db_arc = db_state.0.clone();
+    let control = indexing_state.0.clone();
+
+    let Some(indexing_run) = control.try_start() else {
+        return Err(AppError::conflict("Indexing is already running."));
+    };
 
     tokio::spawn(async move {
         info!("Background indexing task started (downloading).");
+        let run_span = Span::root("indexing", &[("window_start", start_ts.to_string())]);
         let mut total_fetched_metadata = 0;
         let mut total_messages_processed_for_db = 0;
         let mut total_images_saved_or_found = 0;
 
-        let download_client = reqwest::Client::builder()
-            .timeout(Duration::from_secs(30))
-            .build()
-            .unwrap_or_else(|_| reqwest::Client::new());
+        let download_semaphore = Arc::new(Semaphore::new(download_concurrency));
+        info!("Downloading attachments with concurrency {}", download_concurrency);
+        let mut cached_avatars: HashSet<String> = HashSet::new();
+
+        let channel_ids = expand_channels_with_threads(&http_clone, guild_id, channel_ids).await;
+        info!("Channels and threads to index: {:?}", channel_ids);
+
+        let channel_count = channel_ids.len();
+        run_span.record("channels", channel_count);
+        let window_end_ts = end_ts.unwrap_or_else(|| Utc::now().timestamp());
+        let window_secs = (window_end_ts - start_ts).max(1) as f32;
+
+        'channel_loop: for (channel_index, chan_str) in channel_ids.into_iter().enumerate() {
+            if !control.wait_if_paused(&app_clone).await {
+                break 'channel_loop;
+            }
 
-        for chan_str in channel_ids {
             let channel_id = match chan_str.parse::<u64>() {
                 Ok(id) => ChannelId::new(id),
                 Err(_) => {
@@ -314,6 +2205,7 @@ pub async fn start_initial_indexing(
                 }
             };
             info!("Starting indexing for channel: {}", channel_id);
+            let channel_span = run_span.child("indexing.channel", &[("channel_id", chan_str.clone())]);
             app_clone
                 .emit(
                     "indexing-status",
@@ -321,12 +2213,71 @@ pub async fn start_initial_indexing(
                 )
                 .unwrap_or_default();
 
+            let channel_info = http_clone
+                .get_channel(channel_id)
+                .await
+                .ok()
+                .and_then(|c| c.guild());
+            let channel_name = channel_info.as_ref().map(|c| c.name.clone());
+            let nsfw_channel = match &channel_info {
+                Some(channel) => is_nsfw_channel(&http_clone, channel).await,
+                None => false,
+            };
+            if nsfw_channel && exclude_sensitive {
+                info!("Skipping NSFW channel {} (sensitive content is excluded)", channel_id);
+                channel_span.record("skipped", "nsfw");
+                continue;
+            }
+            control.update_progress(&app_clone, |p| {
+                p.phase = IndexingPhase::FetchingMessages;
+                p.channel_id = Some(channel_id.to_string());
+                p.channel_name = channel_name.clone();
+                p.channel_index = channel_index;
+                p.channel_count = channel_count;
+                p.percent = channel_index as f32 / channel_count.max(1) as f32 * 100.0;
+            });
+
+            let saved_before_channel = total_messages_processed_for_db;
+            let mut channel_error: Option<String> = None;
+            let mut cancelled_in_channel = false;
+
+            // A cursor from an unfinished run over the same end date lets this run skip the
+            // range it already covered once it reaches that run's newest message.
+            let cursor_channel = chan_str.clone();
+            let mut pending_resume = db_arc
+                .run(move |conn| Ok(load_index_resume_cursor(conn, &cursor_channel)?))
+                .await
+                .unwrap_or_else(|e| {
+                    error!("Failed to load the resume cursor: {}", e);
+                    None
+                })
+                .filter(|cursor| cursor.window_end == end_ts);
+            let previous_cursor = pending_resume;
+            let mut resume_cursor: Option<IndexResumeCursor> = None;
+            let mut run_top_id: Option<u64> = None;
+
             let mut before_id: Option<MessageId> = None;
             'message_loop: loop {
-                let pagination = before_id.map(MessagePagination::Before);
-                let messages_result = http_clone
-                    .get_messages(channel_id, pagination, Some(100))
-                    .await;
+                if !control.wait_if_paused(&app_clone).await {
+                    info!("Indexing cancelled while processing channel {}", channel_id);
+                    cancelled_in_channel = true;
+                    break 'message_loop;
+                }
+
+                let fetch_span = channel_span.child("indexing.fetch", &[]);
+                let messages_result = fetch_messages_with_backoff(
+                    &http_clone,
+                    &app_clone,
+                    &control,
+                    channel_id,
+                    before_id,
+                )
+                .await;
+                match &messages_result {
+                    Ok(msgs) => fetch_span.record("messages", msgs.len()),
+                    Err(e) => fetch_span.fail(&e.to_string()),
+                }
+                drop(fetch_span);
 
                 match messages_result {
                     Ok(mut msgs) => {
@@ -335,231 +2286,133 @@ pub async fn start_initial_indexing(
                             break 'message_loop;
                         }
                         total_fetched_metadata += msgs.len();
-                        app_clone
-                            .emit(
-                                "indexing-progress",
-                                format!(
-                                    "Fetched {} message metadata total",
-                                    total_fetched_metadata
-                                ),
-                            )
-                            .unwrap_or_default();
 
                         msgs.sort_by_key(|m| m.timestamp);
                         if let Some(first) = msgs.first() {
                             before_id = Some(first.id);
                         }
-
-                        let mut batch_data_for_db: Vec<(
-                            serenity::model::channel::Message,
-                            Vec<String>,
-                        )> = Vec::new();
-                        let mut reached_older_messages = false;
-
-                        for msg in msgs {
-                            if msg.timestamp.unix_timestamp() < start_ts {
-                                reached_older_messages = true;
-                                continue; // Skip older message
-                            }
-
-                            let message_id_str = msg.id.to_string();
-                            let mut saved_filenames_for_msg: Vec<String> = Vec::new();
-                            let mut attachment_processing_failed = false;
-                            let mut attachment_count = 0;
-
-                            for attachment_meta in msg.attachments.iter() {
-                                attachment_count += 1;
-
-                                let filename_lower = attachment_meta.filename.to_lowercase();
-                                let ct = attachment_meta.content_type.as_deref();
-                                let is_image = ct
-                                    .map_or(false, |t| t.starts_with("image/") && t != "image/gif")
-                                    || (!filename_lower.ends_with(".gif")
-                                        && (filename_lower.ends_with(".png")
-                                            || filename_lower.ends_with(".jpg")
-                                            || filename_lower.ends_with(".jpeg")
-                                            || filename_lower.ends_with(".webp")));
-
-                                if !is_image {
-                                    continue;
+                        if run_top_id.is_none() {
+                            run_top_id = msgs.iter().map(|m| m.id.get()).max();
+                        }
+                        if let Some(cursor) = pending_resume {
+                            if before_id.map_or(false, |id| id.get() <= cursor.top_id) {
+                                info!(
+                                    "Resuming channel {} from message {} of an earlier unfinished run.",
+                                    channel_id, cursor.before_id
+                                );
+                                if before_id.map_or(true, |id| id.get() > cursor.before_id) {
+                                    before_id = Some(MessageId::new(cursor.before_id));
                                 }
+                                pending_resume = None;
+                            }
+                        }
 
-                                let attachment_id_str = attachment_meta.id.to_string();
-                                let filename_base =
-                                    format!("{}_{}", message_id_str, attachment_id_str);
-                                let extension = Path::new(&attachment_meta.filename)
-                                    .extension()
-                                    .and_then(|s| s.to_str())
-                                    .unwrap_or("png");
-                                let local_filename = format!("{}.{}", filename_base, extension);
-                                let relative_path_str = Path::new("cached")
-                                    .join(&local_filename)
-                                    .to_string_lossy()
-                                    .into_owned();
-                                let absolute_path = match get_cached_image_dir(&app_clone) {
-                                    Ok(dir) => dir.join(&local_filename),
-                                    Err(e) => {
-                                        error!("Error getting cache dir: {}", e);
-                                        attachment_processing_failed = true;
-                                        break;
-                                    }
-                                };
-
-                                let path_exists = {
-                                    let path_check = absolute_path.clone();
-                                    tokio::task::spawn_blocking(move || path_check.exists())
-                                        .await
-                                        .unwrap_or(false)
-                                };
-
-                                if path_exists {
-                                    warn!("Skipping download, file exists: {}", local_filename);
-                                    saved_filenames_for_msg.push(relative_path_str.clone());
-                                    total_images_saved_or_found += 1;
-                                    continue;
-                                }
+                        let oldest_ts = msgs
+                            .first()
+                            .map(|m| m.timestamp.unix_timestamp())
+                            .unwrap_or(window_end_ts);
+                        let channel_fraction =
+                            ((window_end_ts - oldest_ts) as f32 / window_secs).clamp(0.0, 1.0);
+                        let overall_fraction = (channel_index as f32 + channel_fraction)
+                            / channel_count.max(1) as f32;
+                        control.update_progress(&app_clone, |p| {
+                            p.phase = IndexingPhase::Downloading;
+                            p.messages_scanned = total_fetched_metadata;
+                            p.percent = overall_fraction * 100.0;
+                            p.estimated_total = if overall_fraction > 0.0 {
+                                Some((total_fetched_metadata as f32 / overall_fraction) as usize)
+                            } else {
+                                None
+                            };
+                        });
 
-                                let download_url = attachment_meta.url.clone();
-                                let download_client_clone = download_client.clone();
-                                app_clone
-                                    .emit(
-                                        "indexing-status",
-                                        format!(
-                                            "Downloading: {}... ({} indexed)",
-                                            attachment_meta.filename, total_images_saved_or_found
-                                        ),
-                                    )
-                                    .unwrap_or_default();
-
-                                match download_client_clone.get(&download_url).send().await {
-                                    Ok(response) => {
-                                        if response.status().is_success() {
-                                            match response.bytes().await {
-                                                Ok(image_bytes) => {
-                                                    let path_clone = absolute_path.clone();
-                                                    let save_result =
-                                                        tokio::task::spawn_blocking(move || {
-                                                            if let Some(parent) =
-                                                                path_clone.parent()
-                                                            {
-                                                                fs::create_dir_all(parent)?;
-                                                            }
-                                                            fs::write(&path_clone, &image_bytes)
-                                                        })
-                                                        .await;
-
-                                                    match save_result {
-                                                        Ok(Ok(())) => {
-                                                            info!(
-                                                                "Saved image: {}",
-                                                                local_filename
-                                                            );
-                                                            saved_filenames_for_msg
-                                                                .push(relative_path_str.clone());
-                                                            total_images_saved_or_found += 1;
-                                                        }
-                                                        Ok(Err(e)) => {
-                                                            error!(
-                                                                "Failed to write file {}: {}",
-                                                                local_filename, e
-                                                            );
-                                                            attachment_processing_failed = true;
-                                                            break;
-                                                        }
-                                                        Err(e) => {
-                                                            error!(
-                                                                "File write task failed for {}: {}",
-                                                                local_filename, e
-                                                            );
-                                                            attachment_processing_failed = true;
-                                                            break;
-                                                        }
-                                                    }
-                                                }
-                                                Err(e) => {
-                                                    error!(
-                                                        "Failed to read bytes from download {}: {}",
-                                                        download_url, e
-                                                    );
-                                                    attachment_processing_failed = true;
-                                                    break;
-                                                }
-                                            }
-                                        } else {
-                                            error!(
-                                                "Download failed for {}: Status {}",
-                                                download_url,
-                                                response.status()
-                                            );
-                                        }
-                                    }
-                                    Err(e) => {
-                                        error!(
-                                            "Download request failed for {}: {}",
-                                            download_url, e
-                                        );
-                                        attachment_processing_failed = true;
-                                        break;
-                                    }
-                                }
-                            }
+                        let download_span = channel_span.child("indexing.download", &[]);
+                        let outcome = process_message_batch(
+                            msgs,
+                            &chan_str,
+                            &batch_options,
+                            &download_client,
+                            download_semaphore.clone(),
+                            download_status(&app_clone),
+                        )
+                        .await;
+                        download_span.record("jobs", outcome.download_jobs);
+                        download_span.record("failed", outcome.failed_downloads);
+                        drop(download_span);
 
-                            if !attachment_processing_failed && !saved_filenames_for_msg.is_empty()
-                            {
-                                batch_data_for_db.push((msg.clone(), saved_filenames_for_msg));
-                                total_messages_processed_for_db += 1;
-                            } else if attachment_processing_failed {
-                                error!("Skipping DB insert for message {} due to attachment processing failure.", msg.id);
-                                app_clone
-                                    .emit(
-                                        "indexing-error",
-                                        format!(
-                                            "Failed to process attachments for message {}",
-                                            msg.id
-                                        ),
-                                    )
-                                    .unwrap_or_default();
-                            }
+                        total_images_saved_or_found += outcome.images_saved;
+                        total_messages_processed_for_db += outcome.saved.len();
+                        for message_id in &outcome.failed {
+                            error!("Skipping DB insert for message {} due to attachment processing failure.", message_id);
+                            app_clone
+                                .emit(
+                                    "indexing-error",
+                                    format!("Failed to process attachments for message {}", message_id),
+                                )
+                                .unwrap_or_default();
                         }
+                        let reached_older_messages = outcome.reached_older_messages;
+                        let batch_data_for_db = outcome.saved;
+
+                        control.update_progress(&app_clone, |p| {
+                            p.images_downloaded = total_images_saved_or_found;
+                            p.messages_saved = total_messages_processed_for_db;
+                        });
 
                         if !batch_data_for_db.is_empty() {
+                            avatars::cache_author_avatars(
+                                &download_client,
+                                &image_base_dir,
+                                batch_data_for_db.iter().map(|(msg, _)| &msg.author),
+                                encrypt_cache,
+                                &mut cached_avatars,
+                            )
+                            .await;
+                            control.update_progress(&app_clone, |p| p.phase = IndexingPhase::Saving);
                             let db_arc_blocking = db_arc.clone();
                             let app_block = app_clone.clone();
                             let current_batch_size = batch_data_for_db.len();
+                            let save_span = channel_span
+                                .child("indexing.save", &[("messages", current_batch_size.to_string())]);
+                            let thumbnail_paths: Vec<String> = if thumbnail_settings.pregenerate {
+                                batch_data_for_db
+                                    .iter()
+                                    .flat_map(|(_, files)| files.iter().map(|f| f.relative_path.clone()))
+                                    .collect()
+                            } else {
+                                Vec::new()
+                            };
 
                             let insert_result = tokio::task::spawn_blocking(move || {
                                  let mut conn_guard = db_arc_blocking.lock().map_err(|_| "DB Lock error".to_string())?; 
-                                 let tx = conn_guard.transaction().map_err(|e| format!("Begin Tx: {}", e))?;
-                                 {
-                                     
-                                     let mut stmt = tx.prepare_cached(
-                                        "INSERT OR IGNORE INTO messages (message_id, channel_id, author_id, author_name, author_avatar, message_content, attachments, timestamp) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)"
-                                     ).map_err(|e| format!("Prepare Stmt: {}", e))?;
-
-                                     for (msg, filenames) in batch_data_for_db {
-                                        
-                                          let attachments_json = serde_json::to_string(&filenames).map_err(|e| format!("JSON Serialize: {}", e))?;
-                                          stmt.execute(params![
-                                               msg.id.to_string(), msg.channel_id.to_string(), msg.author.id.to_string(),
-                                               msg.author.name, msg.author.avatar_url(), msg.content,
-                                               attachments_json,
-                                               msg.timestamp.unix_timestamp(),
-                                          ]).map_err(|e| format!("Exec Insert ({}): {}", msg.id, e))?;
-                                     }
-                                 } 
-                                 tx.commit().map_err(|e| format!("Commit Tx: {}", e)) 
+                                 insert_indexed_messages(&mut conn_guard, &batch_data_for_db, nsfw_channel)
                              }).await;
 
                             // Handle insert result
                             match insert_result {
                                 Ok(Ok(())) => {
                                     info!("Successfully inserted batch of {} message(s) into DB for channel {}.", current_batch_size, channel_id);
+                                    if !thumbnail_paths.is_empty() {
+                                        let _thumbnail_span = save_span.child(
+                                            "indexing.thumbnails",
+                                            &[("files", thumbnail_paths.len().to_string())],
+                                        );
+                                        thumbnails::pregenerate_thumbnails(
+                                            image_base_dir.clone(),
+                                            thumbnail_paths,
+                                            thumbnail_settings.clone(),
+                                            encrypt_cache,
+                                        )
+                                        .await;
+                                    }
                                 }
                                 Ok(Err(e)) => {
                                     error!(
                                         "DB Error inserting batch for channel {}: {}",
                                         channel_id, e
                                     );
+                                    channel_error = Some(format!("DB Error: {}", e));
+                                    save_span.fail(&e);
                                     app_block
                                         .emit("indexing-error", format!("DB Error: {}", e))
                                         .unwrap_or_default();
@@ -569,6 +2422,8 @@ pub async fn start_initial_indexing(
                                         "Blocking task failed during DB insert for channel {}: {}",
                                         channel_id, e
                                     );
+                                    channel_error = Some(format!("Task Error: {}", e));
+                                    save_span.fail(&e.to_string());
                                     app_block
                                         .emit("indexing-error", format!("Task Error: {}", e))
                                         .unwrap_or_default();
@@ -580,8 +2435,30 @@ pub async fn start_initial_indexing(
                             info!("Reached messages older than threshold in channel {}. Stopping fetch.", channel_id);
                             break 'message_loop;
                         }
+
+                        // The cursor only moves while every batch so far was saved.
+                        if let (None, Some(before), Some(top)) = (&channel_error, before_id, run_top_id) {
+                            let cursor = IndexResumeCursor {
+                                before_id: before.get(),
+                                top_id: top,
+                                window_end: end_ts,
+                            };
+                            resume_cursor = Some(cursor);
+                            let cursor_channel = chan_str.clone();
+                            if let Err(e) = db_arc
+                                .run(move |conn| Ok(save_index_resume_cursor(conn, &cursor_channel, &cursor)?))
+                                .await
+                            {
+                                error!("Failed to save the resume cursor: {}", e);
+                            }
+                        }
                     }
                     Err(e) => {
+                        if control.is_cancelled() {
+                            info!("Indexing cancelled while retrying channel {}", channel_id);
+                            cancelled_in_channel = true;
+                            break 'message_loop;
+                        }
                         error!("Error fetching message batch for {}: {:?}", channel_id, e);
                         app_clone
                             .emit(
@@ -589,31 +2466,115 @@ pub async fn start_initial_indexing(
                                 format!("Fetch Error {}: {}", channel_id, e),
                             )
                             .unwrap_or_default();
-                        if let serenity::Error::Http(http_err) = &e {
-                            if http_err.status_code().map_or(false, |c| c.as_u16() == 429) {
-                                app_clone
-                                    .emit("indexing-status", "Rate limited, waiting...")
-                                    .unwrap_or_default();
-                                sleep(Duration::from_secs(5)).await;
-                                continue;
-                            }
-                        }
+                        channel_error = Some(format!("Fetch Error: {}", e));
                         break 'message_loop;
                     }
                 }
             }
+
+            let run_result = if cancelled_in_channel {
+                "cancelled"
+            } else if channel_error.is_some() {
+                "failed"
+            } else {
+                "completed"
+            };
+            // A completed run clears the cursor; otherwise keep the furthest known position.
+            let final_cursor = if run_result == "completed" {
+                None
+            } else {
+                resume_cursor.or(previous_cursor)
+            };
+            let run_channel = chan_str.clone();
+            let run_error = channel_error.clone();
+            let messages_saved = total_messages_processed_for_db - saved_before_channel;
+            if let Err(e) = db_arc
+                .run(move |conn| {
+                    Ok(record_channel_index_run(
+                        conn,
+                        &run_channel,
+                        run_result,
+                        messages_saved,
+                        run_error.as_deref(),
+                        final_cursor.as_ref(),
+                    )?)
+                })
+                .await
+            {
+                error!("Failed to record the index run: {}", e);
+            }
+
+            channel_span.record("messages_saved", messages_saved);
+            if cancelled_in_channel {
+                channel_span.set_status(spans::STATUS_CANCELLED);
+            } else if let Some(e) = &channel_error {
+                channel_span.fail(e);
+            }
+            drop(channel_span);
+
+            if cancelled_in_channel {
+                break 'channel_loop;
+            }
             info!("Finished indexing channel {}", channel_id);
         }
 
+        if author_cap.max_images > 0 {
+            let cap_dir = image_base_dir.clone();
+            let cap = author_cap.clone();
+            if let Err(e) = db_arc
+                .run(move |conn| Ok(enforce_author_cap(conn, &cap_dir, &cap)?))
+                .await
+            {
+                error!("Failed to apply the author cap: {}", e);
+            }
+        }
+
+        let was_cancelled = control.is_cancelled();
+        control.update_progress(&app_clone, |p| {
+            p.phase = if was_cancelled {
+                IndexingPhase::Cancelled
+            } else {
+                IndexingPhase::Completed
+            };
+            p.messages_scanned = total_fetched_metadata;
+            p.messages_saved = total_messages_processed_for_db;
+            p.images_downloaded = total_images_saved_or_found;
+            if !was_cancelled {
+                p.percent = 100.0;
+            }
+        });
+        drop(indexing_run);
+
+        run_span.record("messages_fetched", total_fetched_metadata);
+        run_span.record("messages_saved", total_messages_processed_for_db);
+        run_span.record("images_saved", total_images_saved_or_found);
+        if was_cancelled {
+            run_span.set_status(spans::STATUS_CANCELLED);
+        }
+        drop(run_span);
+
         info!(
-            "Background indexing task finished. Metadata Fetched: {}, Messages Processed: {}, Images Saved/Found: {}",
+            "Background indexing task {}. Metadata Fetched: {}, Messages Processed: {}, Images Saved/Found: {}",
+            if was_cancelled { "cancelled" } else { "finished" },
             total_fetched_metadata, total_messages_processed_for_db, total_images_saved_or_found
         );
+        if was_cancelled {
+            app_clone
+                .emit(
+                    "indexing-cancelled",
+                    format!(
+                        "Indexing cancelled. {} messages with images processed.",
+                        total_messages_processed_for_db
+                    ),
+                )
+                .unwrap_or_default();
+        }
         app_clone
             .emit(
                 "indexing-complete",
                 format!(
-                    "Indexing finished. {} messages with images processed.",
+                    "Indexing {}. {} messages with images processed.",
+                    if was_cancelled { "cancelled" } else { "finished" },
                     total_messages_processed_for_db
                 ),
             )
@@ -622,3 +2583,383 @@ pub async fn start_initial_indexing(
 
     Ok(())
 }
+
+#[derive(serde::Serialize, Clone, Debug, Default)]
+pub struct IndexingEstimate {
+    pub channel_count: usize,
+    pub message_count: usize, // messages that would be saved
+    pub image_count: usize,   // files that would be downloaded, already cached ones excluded
+    pub total_bytes: u64,
+    pub unknown_size_count: usize, // embed and link images, not included in total_bytes
+    pub already_cached_count: usize,
+    pub skipped_channels: Vec<String>, // NSFW channels when sensitive content is excluded
+    pub failed_channels: Vec<String>,
+}
+
+// Pages message metadata with the same window and filters as start_initial_indexing,
+// without downloading anything or touching the database.
+#[tauri::command]
+pub async fn estimate_indexing(
+    app_handle: AppHandle,
+    db_state: State<'_, DbConnection>,
+    from_date: Option<String>,
+    to_date: Option<String>,
+    window_days: Option<u32>,
+) -> Result<IndexingEstimate, AppError> {
+    info!("Estimating indexing run...");
+    let http = configured_discord_http(&db_state, &load_bot_token()?).await?;
+    let config: AppConfig = db_state.0.run(|conn| Ok(retrieve_config(conn)?)).await?;
+    let (start_ts, end_ts) = resolve_indexing_window(&config, from_date, to_date, window_days)?;
+    let include_animated = config.include_animated_attachments.unwrap_or(false);
+    let include_linked = config.index_linked_images.unwrap_or(true);
+    let author_filters = config.author_filters.clone().unwrap_or_default();
+    let size_filter = config.image_size_filter.clone().unwrap_or_default();
+    let content_filter = ContentFilter::compile(&config.content_filters.clone().unwrap_or_default())?;
+    let exclude_sensitive = config.exclude_sensitive_content.unwrap_or(false);
+    let guild_id = config
+        .selected_server_id
+        .as_deref()
+        .and_then(|id| id.parse::<u64>().ok())
+        .map(GuildId::new);
+    let cache_base_dir = get_cached_image_dir(&app_handle)?;
+
+    let channel_ids = resolve_indexing_channels(&http, &config).await;
+    let channel_ids = expand_channels_with_threads(&http, guild_id, channel_ids).await;
+    let mut estimate = IndexingEstimate {
+        channel_count: channel_ids.len(),
+        ..Default::default()
+    };
+
+    for chan_str in channel_ids {
+        let Ok(raw_channel_id) = chan_str.parse::<u64>() else {
+            estimate.failed_channels.push(chan_str);
+            continue;
+        };
+        let channel_id = ChannelId::new(raw_channel_id);
+        app_handle
+            .emit("indexing-status", format!("Estimating channel {}...", channel_id))
+            .unwrap_or_default();
+
+        if exclude_sensitive {
+            let channel_info = http.get_channel(channel_id).await.ok().and_then(|c| c.guild());
+            if let Some(channel) = &channel_info {
+                if is_nsfw_channel(&http, channel).await {
+                    estimate.skipped_channels.push(chan_str);
+                    continue;
+                }
+            }
+        }
+
+        let mut before_id: Option<MessageId> = None;
+        loop {
+            let mut msgs = match http
+                .get_messages(channel_id, before_id.map(MessagePagination::Before), Some(100))
+                .await
+            {
+                Ok(msgs) => msgs,
+                Err(e) => {
+                    warn!("Estimate failed for channel {}: {}", channel_id, e);
+                    estimate.failed_channels.push(chan_str.clone());
+                    break;
+                }
+            };
+            if msgs.is_empty() {
+                break;
+            }
+            msgs.sort_by_key(|m| m.timestamp);
+            before_id = msgs.first().map(|m| m.id);
+            let reached_older_messages = msgs
+                .first()
+                .map_or(false, |m| m.timestamp.unix_timestamp() < start_ts);
+
+            for msg in &msgs {
+                let ts = msg.timestamp.unix_timestamp();
+                if ts < start_ts || end_ts.map_or(false, |end| ts >= end) {
+                    continue;
+                }
+                let (author_id, author_type) = message_author(msg);
+                if !is_author_allowed(
+                    author_filter_for(&author_filters, &chan_str),
+                    &author_id,
+                    author_type == AUTHOR_TYPE_BOT,
+                ) || !content_filter.allows(&msg.content)
+                    || (exclude_sensitive && has_spoiler_attachment(msg))
+                {
+                    continue;
+                }
+
+                let jobs = collect_download_jobs(
+                    &cache_base_dir,
+                    0,
+                    msg,
+                    include_animated,
+                    include_linked,
+                    &size_filter,
+                );
+                if jobs.is_empty() {
+                    continue;
+                }
+                estimate.message_count += 1;
+                for job in jobs {
+                    if job.absolute_path.exists() {
+                        estimate.already_cached_count += 1;
+                        continue;
+                    }
+                    estimate.image_count += 1;
+                    match job.expected_size {
+                        Some(size) => estimate.total_bytes += size,
+                        None => estimate.unknown_size_count += 1,
+                    }
+                }
+            }
+
+            if reached_older_messages || msgs.len() < 100 {
+                break;
+            }
+        }
+    }
+
+    info!(
+        "Indexing estimate: {} message(s), {} file(s), {} bytes across {} channel(s)",
+        estimate.message_count, estimate.image_count, estimate.total_bytes, estimate.channel_count
+    );
+    app_handle
+        .emit("indexing-status", "Estimate complete")
+        .unwrap_or_default();
+    Ok(estimate)
+}
+
+#[tauri::command]
+pub async fn cancel_indexing(indexing_state: State<'_, IndexingState>) -> Result<(), AppError> {
+    if !indexing_state.0.is_running() {
+        return Err(AppError::conflict("No indexing task is running."));
+    }
+    info!("Cancellation requested for the running indexing task.");
+    indexing_state.0.request_cancel();
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn pause_indexing(indexing_state: State<'_, IndexingState>) -> Result<(), AppError> {
+    if !indexing_state.0.is_running() {
+        return Err(AppError::conflict("No indexing task is running."));
+    }
+    info!("Pausing the running indexing task.");
+    indexing_state.0.paused.store(true, Ordering::SeqCst);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn resume_indexing(indexing_state: State<'_, IndexingState>) -> Result<(), AppError> {
+    if !indexing_state.0.is_running() {
+        return Err(AppError::conflict("No indexing task is running."));
+    }
+    info!("Resuming the paused indexing task.");
+    indexing_state.0.paused.store(false, Ordering::SeqCst);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_indexing_status(
+    indexing_state: State<'_, IndexingState>,
+) -> Result<IndexingProgress, AppError> {
+    Ok(indexing_state.0.progress_snapshot())
+}
+
+fn parse_message_link(link: &str) -> Result<(ChannelId, MessageId), String> {
+    let caps = MESSAGE_LINK_RE
+        .captures(link.trim())
+        .ok_or_else(|| format!("Not a Discord message link: {}", link))?;
+    let channel_id = caps[2]
+        .parse::<u64>()
+        .ok()
+        .filter(|id| *id != 0)
+        .ok_or_else(|| format!("Invalid channel ID in link: {}", &caps[2]))?;
+    let message_id = caps[3]
+        .parse::<u64>()
+        .ok()
+        .filter(|id| *id != 0)
+        .ok_or_else(|| format!("Invalid message ID in link: {}", &caps[3]))?;
+    Ok((ChannelId::new(channel_id), MessageId::new(message_id)))
+}
+
+// One-off import: ignores the selected channels, date window, author and size filters.
+#[tauri::command]
+pub async fn import_message_by_link(
+    app_handle: AppHandle,
+    link: String,
+    db_state: State<'_, DbConnection>,
+) -> Result<IndexedMessage, AppError> {
+    let (channel_id, message_id) = parse_message_link(&link)?;
+
+    let config = db_state
+        .0
+        .run(move |conn| {
+            let already_indexed: bool = conn
+                .query_row(
+                    "SELECT EXISTS(SELECT 1 FROM messages WHERE message_id = ?1 AND deleted = 0)",
+                    [message_id.to_string()],
+                    |row| row.get(0),
+                )
+                .context(format!("Failed to check message {}", message_id))?;
+            if already_indexed {
+                return Err(AppError::conflict(format!("Message {} is already indexed.", message_id)));
+            }
+            Ok(retrieve_config(conn)?)
+        })
+        .await?;
+    let encrypt_cache = config.encrypt_image_cache.unwrap_or(false);
+
+    let http = configured_discord_http(&db_state, &load_bot_token()?).await?;
+    let msg = channel_id
+        .message(&http, message_id)
+        .await
+        .context(format!("Failed to fetch message {}", message_id))?;
+    let nsfw_channel = match http.get_channel(channel_id).await {
+        Ok(serenity::model::channel::Channel::Guild(channel)) => is_nsfw_channel(&http, &channel).await,
+        _ => false,
+    };
+    if config.exclude_sensitive_content.unwrap_or(false) && (nsfw_channel || has_spoiler_attachment(&msg)) {
+        return Err(AppError::invalid_input(format!(
+            "Message {} is spoilered or in an NSFW channel, and sensitive content is excluded.",
+            message_id
+        )));
+    }
+
+    let image_base_dir = get_image_base_dir(&app_handle)?;
+    let jobs = collect_download_jobs(
+        &image_base_dir.join("cached"),
+        0,
+        &msg,
+        config.include_animated_attachments.unwrap_or(false),
+        config.index_linked_images.unwrap_or(true),
+        &ImageSizeFilter::default(),
+    );
+    if jobs.is_empty() {
+        return Err(AppError::invalid_input(format!("Message {} has no images to import.", message_id)));
+    }
+
+    let download_client = http_client(
+        &config.network_settings.clone().unwrap_or_default(),
+        Some(Duration::from_secs(30)),
+    )?;
+    let sources: Vec<&'static str> = jobs.iter().map(|job| job.source).collect();
+    let results = run_download_jobs(
+        jobs,
+        &download_client,
+        Arc::new(Semaphore::new(DEFAULT_DOWNLOAD_CONCURRENCY)),
+        encrypt_cache,
+        download_status(&app_handle),
+    )
+    .await;
+
+    let mut saved = Vec::new();
+    for (result, source) in results.into_iter().zip(sources) {
+        match result {
+            Ok(Some(cached_file)) => saved.push(cached_file),
+            Ok(None) => {}
+            Err(e) if source != SOURCE_ATTACHMENT => warn!("{}", e),
+            Err(e) => return Err(e.into()),
+        }
+    }
+    if saved.is_empty() {
+        return Err(AppError::new(
+            ErrorKind::Network,
+            format!("No images could be downloaded from message {}.", message_id),
+        ));
+    }
+
+    avatars::cache_author_avatars(
+        &download_client,
+        &image_base_dir,
+        [&msg.author],
+        encrypt_cache,
+        &mut HashSet::new(),
+    )
+    .await;
+    let indexed = to_indexed_message(&msg, &saved, nsfw_channel);
+    db_state
+        .0
+        .run(move |conn| {
+            // A previously deleted copy is replaced by the fresh one.
+            conn.execute(
+                "DELETE FROM messages WHERE message_id = ?1 AND deleted = 1",
+                [message_id.to_string()],
+            )
+            .context(format!("Failed to replace deleted message {}", message_id))?;
+            Ok(insert_indexed_messages(conn, &[(msg, saved)], nsfw_channel)?)
+        })
+        .await?;
+
+    let thumbnail_settings = thumbnails::sanitize_settings(config.thumbnail_settings.unwrap_or_default());
+    if thumbnail_settings.pregenerate {
+        thumbnails::pregenerate_thumbnails(
+            image_base_dir,
+            indexed.attachments.clone(),
+            thumbnail_settings,
+            encrypt_cache,
+        )
+        .await;
+    }
+
+    info!(
+        "Imported message {} from channel {} with {} file(s)",
+        indexed.message_id,
+        indexed.channel_id,
+        indexed.attachments.len()
+    );
+    app_handle
+        .emit("message-indexed", indexed.clone())
+        .unwrap_or_default();
+    Ok(indexed)
+}
+
+// Web links work everywhere; `discord://` opens the desktop client directly when it is installed.
+#[tauri::command]
+pub async fn open_message_in_discord(
+    app_handle: AppHandle,
+    message_id: String,
+    prefer_app: Option<bool>,
+    db_state: State<'_, DbConnection>,
+) -> Result<String, AppError> {
+    let lookup_id = message_id.clone();
+    let (channel_id, guild_id) = db_state
+        .0
+        .run(move |conn| {
+            let channel_id: String = conn
+                .query_row(
+                    "SELECT channel_id FROM messages WHERE message_id = ?1",
+                    [&lookup_id],
+                    |row| row.get(0),
+                )
+                .map_err(|e| match e {
+                    rusqlite::Error::QueryReturnedNoRows => format!("Message {} is not indexed.", lookup_id),
+                    e => format!("Failed to look up message {}: {}", lookup_id, e),
+                })?;
+            Ok((channel_id, retrieve_config(conn)?.selected_server_id))
+        })
+        .await?;
+
+    // Manifest imports and other non-Discord rows have no original to jump to.
+    let is_snowflake = |id: &str| id.parse::<u64>().map_or(false, |id| id != 0);
+    if !is_snowflake(&message_id) || !is_snowflake(&channel_id) {
+        return Err(AppError::invalid_input(format!("Message {} did not come from Discord.", message_id)));
+    }
+    let guild_part = guild_id
+        .filter(|id| is_snowflake(id))
+        .unwrap_or_else(|| "@me".to_string());
+
+    let path = format!("channels/{}/{}/{}", guild_part, channel_id, message_id);
+    let url = if prefer_app.unwrap_or(false) {
+        format!("discord://-/{}", path)
+    } else {
+        format!("https://discord.com/{}", path)
+    };
+
+    app_handle
+        .opener()
+        .open_url(&url, None::<&str>)
+        .map_err(|e| format!("Failed to open {}: {}", url, e))?;
+    info!("Opened message {} in Discord", message_id);
+    Ok(url)
+}
@@ -0,0 +1,295 @@
+use serde::Serialize;
+use std::fmt;
+use std::sync::PoisonError;
+
+// What the frontend switches on to pick a recovery action (re-enter the token, free disk
+// space, retry later, ...). Serialized in snake_case, mirrored by AppErrorKind in types.ts.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    Auth,             // missing, invalid or revoked Discord token / sign-in
+    Network,          // connection failures, timeouts, proxy and TLS problems
+    RateLimited,      // Discord or GitHub asked us to slow down
+    NotFound,         // showcase, message, channel or file does not exist
+    InvalidInput,     // bad argument or setting supplied by the user
+    Conflict,         // something is already running or in the wrong state
+    Database,         // SQLite errors other than a full disk
+    DiskFull,
+    PermissionDenied, // file system or Discord permission
+    Io,
+    Internal,
+}
+
+impl ErrorKind {
+    fn default_retryable(self) -> bool {
+        matches!(self, ErrorKind::Network | ErrorKind::RateLimited | ErrorKind::Conflict)
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AppError {
+    pub kind: ErrorKind,
+    pub message: String,
+    pub retryable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>, // what the command was doing, e.g. "Indexing channel 123"
+}
+
+impl AppError {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        AppError {
+            kind,
+            message: message.into(),
+            retryable: kind.default_retryable(),
+            context: None,
+        }
+    }
+
+    pub fn auth(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Auth, message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::NotFound, message)
+    }
+
+    pub fn invalid_input(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::InvalidInput, message)
+    }
+
+    pub fn conflict(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Conflict, message)
+    }
+
+    pub fn with_context(mut self, context: impl Into<String>) -> Self {
+        self.context = Some(context.into());
+        self
+    }
+
+    pub fn retryable(mut self, retryable: bool) -> Self {
+        self.retryable = retryable;
+        self
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.context {
+            Some(context) => write!(f, "{}: {}", context, self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+// Last resort for errors that were never given a kind: helpers that still return
+// Result<_, String>. Errors are classified where they are made (the typed conversions below,
+// `ResultExt::context` and `context_as`, the AppError constructors), so this only looks for
+// wording the OS and SQLite put in their own messages. Quoted values and numbers are ignored:
+// messages embed titles, paths and snowflake IDs, and "Showcase '…401…' not found" is not an
+// auth error.
+fn classify_message(message: &str) -> ErrorKind {
+    let lower = strip_quoted(&message.to_lowercase());
+    let has = |needles: &[&str]| needles.iter().any(|n| lower.contains(n));
+    if has(&["no space left on device", "disk is full", "not enough space on the disk"]) {
+        ErrorKind::DiskFull
+    } else if has(&["permission denied", "access is denied"]) {
+        ErrorKind::PermissionDenied
+    } else if has(&["timed out", "connection refused", "connection reset", "dns error"]) {
+        ErrorKind::Network
+    } else if has(&["db lock error", "database is locked", "sqlite"]) {
+        ErrorKind::Database
+    } else if has(&["no such file or directory", "cannot find the file"]) {
+        ErrorKind::NotFound
+    } else {
+        ErrorKind::Internal
+    }
+}
+
+fn strip_quoted(message: &str) -> String {
+    let mut stripped = String::with_capacity(message.len());
+    let mut quote = None;
+    for c in message.chars() {
+        match quote {
+            Some(open) if c == open => quote = None,
+            Some(_) => {}
+            None if c == '\'' || c == '"' => quote = Some(c),
+            None => stripped.push(c),
+        }
+    }
+    stripped
+}
+
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        AppError::new(classify_message(&message), message)
+    }
+}
+
+impl From<&str> for AppError {
+    fn from(message: &str) -> Self {
+        AppError::from(message.to_string())
+    }
+}
+
+// Lets helpers that still return Result<_, String> call ones that were converted with `?`.
+impl From<AppError> for String {
+    fn from(error: AppError) -> Self {
+        error.to_string()
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(error: std::io::Error) -> Self {
+        use std::io::ErrorKind as IoKind;
+        let kind = match error.kind() {
+            IoKind::NotFound => ErrorKind::NotFound,
+            IoKind::PermissionDenied => ErrorKind::PermissionDenied,
+            IoKind::StorageFull | IoKind::QuotaExceeded => ErrorKind::DiskFull,
+            IoKind::TimedOut | IoKind::ConnectionRefused | IoKind::ConnectionReset => ErrorKind::Network,
+            _ => ErrorKind::Io,
+        };
+        AppError::new(kind, error.to_string())
+    }
+}
+
+impl From<rusqlite::Error> for AppError {
+    fn from(error: rusqlite::Error) -> Self {
+        use rusqlite::ErrorCode;
+        match (&error, error.sqlite_error_code()) {
+            (rusqlite::Error::QueryReturnedNoRows, _) => AppError::not_found(error.to_string()),
+            (_, Some(ErrorCode::DiskFull)) => AppError::new(ErrorKind::DiskFull, error.to_string()),
+            (_, Some(ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked)) => {
+                AppError::new(ErrorKind::Database, error.to_string()).retryable(true)
+            }
+            (_, Some(ErrorCode::PermissionDenied | ErrorCode::ReadOnly)) => {
+                AppError::new(ErrorKind::PermissionDenied, error.to_string())
+            }
+            _ => AppError::new(ErrorKind::Database, error.to_string()),
+        }
+    }
+}
+
+impl<T> From<PoisonError<T>> for AppError {
+    fn from(error: PoisonError<T>) -> Self {
+        AppError::new(ErrorKind::Database, format!("DB lock error: {}", error))
+    }
+}
+
+impl From<serde_json::Error> for AppError {
+    fn from(error: serde_json::Error) -> Self {
+        AppError::new(ErrorKind::Internal, error.to_string())
+    }
+}
+
+fn kind_for_status(status: u16) -> Option<ErrorKind> {
+    match status {
+        401 => Some(ErrorKind::Auth),
+        403 => Some(ErrorKind::PermissionDenied),
+        404 => Some(ErrorKind::NotFound),
+        429 => Some(ErrorKind::RateLimited),
+        500..=599 => Some(ErrorKind::Network),
+        _ => None,
+    }
+}
+
+impl From<reqwest::Error> for AppError {
+    fn from(error: reqwest::Error) -> Self {
+        let kind = error
+            .status()
+            .and_then(|status| kind_for_status(status.as_u16()))
+            .unwrap_or(if error.is_timeout() || error.is_connect() || error.is_request() {
+                ErrorKind::Network
+            } else {
+                ErrorKind::Internal
+            });
+        AppError::new(kind, error.to_string())
+    }
+}
+
+// Serenity bundles its own reqwest, so the status is read through its HttpError.
+impl From<serenity::Error> for AppError {
+    fn from(error: serenity::Error) -> Self {
+        let kind = match &error {
+            serenity::Error::Http(http_error) => http_error
+                .status_code()
+                .and_then(|status| kind_for_status(status.as_u16()))
+                .unwrap_or(ErrorKind::Network),
+            serenity::Error::Io(_) | serenity::Error::Tungstenite(_) => ErrorKind::Network,
+            serenity::Error::Gateway(serenity::gateway::GatewayError::InvalidAuthentication) => {
+                ErrorKind::Auth
+            }
+            _ => ErrorKind::Internal,
+        };
+        AppError::new(kind, error.to_string())
+    }
+}
+
+impl From<keyring::Error> for AppError {
+    fn from(error: keyring::Error) -> Self {
+        let kind = match error {
+            keyring::Error::NoEntry => ErrorKind::Auth,
+            _ => ErrorKind::Internal,
+        };
+        AppError::new(kind, error.to_string())
+    }
+}
+
+impl From<tauri::Error> for AppError {
+    fn from(error: tauri::Error) -> Self {
+        AppError::new(ErrorKind::Internal, error.to_string())
+    }
+}
+
+impl From<zip::result::ZipError> for AppError {
+    fn from(error: zip::result::ZipError) -> Self {
+        match error {
+            zip::result::ZipError::Io(error) => AppError::from(error),
+            zip::result::ZipError::FileNotFound => AppError::not_found(error.to_string()),
+            _ => AppError::invalid_input(error.to_string()),
+        }
+    }
+}
+
+impl From<image::ImageError> for AppError {
+    fn from(error: image::ImageError) -> Self {
+        match error {
+            image::ImageError::IoError(error) => AppError::from(error),
+            image::ImageError::Decoding(_) | image::ImageError::Unsupported(_) => {
+                AppError::invalid_input(error.to_string())
+            }
+            _ => AppError::new(ErrorKind::Internal, error.to_string()),
+        }
+    }
+}
+
+impl From<tokio::task::JoinError> for AppError {
+    fn from(error: tokio::task::JoinError) -> Self {
+        AppError::new(ErrorKind::Internal, error.to_string())
+    }
+}
+
+// Replaces `.map_err(|e| format!("Failed to do X: {}", e))`: the kind comes from the source
+// error and the description becomes the context shown before the message. `context_as` states
+// the kind outright, for errors whose type says nothing about it (strings, parse errors).
+pub trait ResultExt<T, E> {
+    fn context(self, context: impl Into<String>) -> Result<T, AppError>
+    where
+        E: Into<AppError>;
+    fn context_as(self, kind: ErrorKind, context: impl Into<String>) -> Result<T, AppError>;
+}
+
+impl<T, E: fmt::Display> ResultExt<T, E> for Result<T, E> {
+    fn context(self, context: impl Into<String>) -> Result<T, AppError>
+    where
+        E: Into<AppError>,
+    {
+        self.map_err(|e| e.into().with_context(context))
+    }
+
+    fn context_as(self, kind: ErrorKind, context: impl Into<String>) -> Result<T, AppError> {
+        self.map_err(|e| AppError::new(kind, e.to_string()).with_context(context))
+    }
+}
@@ -0,0 +1,217 @@
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageFormat, Rgb, RgbImage};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Cursor;
+use std::sync::Mutex;
+use sysinfo::System;
+use tauri::{AppHandle, State};
+
+use crate::cache_crypto;
+use crate::error::{AppError, ResultExt};
+use crate::spans::{self, Span};
+use crate::sqlite_manager::{get_image_base_dir, retrieve_config, DbConnection};
+use crate::{log_info as info, log_warn as warn};
+
+use base64::{engine::general_purpose::STANDARD as base64_engine, Engine as _};
+
+// Below this much free RAM, "auto" switches exports to the streaming pipeline.
+const LOW_MEMORY_AVAILABLE_MB: u64 = 3 * 1024;
+const STANDARD_IMAGES_IN_FLIGHT: usize = 16;
+const LOW_MEMORY_IMAGES_IN_FLIGHT: usize = 1;
+const STANDARD_CHUNK_BYTES: usize = 16 * 1024 * 1024;
+const LOW_MEMORY_CHUNK_BYTES: usize = 2 * 1024 * 1024;
+
+pub const EXPORT_MODE_AUTO: &str = "auto";
+pub const EXPORT_MODE_STANDARD: &str = "standard";
+pub const EXPORT_MODE_LOW_MEMORY: &str = "low_memory";
+
+// PowerPoint 2016 and LibreOffice Impress mishandle WebP and very large pictures, so legacy
+// exports get every slide as an opaque PNG no bigger than the 16:9 slide at 1080p.
+const LEGACY_MAX_WIDTH: u32 = 1920;
+const LEGACY_MAX_HEIGHT: u32 = 1080;
+
+struct ExportJob {
+    span: Span,
+    chunks: usize,
+    bytes: usize,
+}
+
+// Chunked PPTX exports between begin and finish, keyed by showcase ID.
+#[derive(Default)]
+pub struct ExportJobsState(Mutex<HashMap<String, ExportJob>>);
+
+impl ExportJobsState {
+    pub fn start(&self, showcase_id: &str) {
+        if let Ok(mut active) = self.0.lock() {
+            let job = ExportJob {
+                span: Span::root("export.pptx", &[("showcase_id", showcase_id.to_string())]),
+                chunks: 0,
+                bytes: 0,
+            };
+            // Restarting an export abandons the previous attempt.
+            if let Some(previous) = active.insert(showcase_id.to_string(), job) {
+                previous.span.set_status(spans::STATUS_CANCELLED);
+            }
+        }
+    }
+
+    pub fn add_chunk(&self, showcase_id: &str, bytes: usize) {
+        if let Ok(mut active) = self.0.lock() {
+            if let Some(job) = active.get_mut(showcase_id) {
+                job.chunks += 1;
+                job.bytes += bytes;
+            }
+        }
+    }
+
+    pub fn finish(&self, showcase_id: &str, status: &'static str) {
+        let job = self.0.lock().ok().and_then(|mut active| active.remove(showcase_id));
+        if let Some(job) = job {
+            job.span.record("chunks", job.chunks);
+            job.span.record("bytes", job.bytes);
+            job.span.set_status(status);
+        }
+    }
+
+    pub fn active(&self) -> Vec<String> {
+        self.0
+            .lock()
+            .map(|active| active.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportMemoryProfile {
+    pub total_memory_mb: u64,
+    pub available_memory_mb: u64,
+    pub configured_mode: String,
+    pub effective_mode: String, // "standard" | "low_memory"
+    pub max_images_in_flight: usize,
+    pub chunk_size_bytes: usize,
+}
+
+fn memory_snapshot_mb() -> (u64, u64) {
+    let mut sys = System::new();
+    sys.refresh_memory();
+    (
+        sys.total_memory() / (1024 * 1024),
+        sys.available_memory() / (1024 * 1024),
+    )
+}
+
+pub fn resolve_export_mode(configured: Option<&str>, available_mb: u64) -> &'static str {
+    match configured.unwrap_or(EXPORT_MODE_AUTO) {
+        EXPORT_MODE_STANDARD => EXPORT_MODE_STANDARD,
+        EXPORT_MODE_LOW_MEMORY => EXPORT_MODE_LOW_MEMORY,
+        other => {
+            if other != EXPORT_MODE_AUTO {
+                warn!("Unknown export memory mode '{}', falling back to auto", other);
+            }
+            // Platforms that cannot report memory give 0, which also lands on low memory.
+            if available_mb < LOW_MEMORY_AVAILABLE_MB {
+                EXPORT_MODE_LOW_MEMORY
+            } else {
+                EXPORT_MODE_STANDARD
+            }
+        }
+    }
+}
+
+pub fn export_memory_profile(configured: Option<&str>) -> ExportMemoryProfile {
+    let (total_memory_mb, available_memory_mb) = memory_snapshot_mb();
+    let effective_mode = resolve_export_mode(configured, available_memory_mb);
+    let low_memory = effective_mode == EXPORT_MODE_LOW_MEMORY;
+
+    ExportMemoryProfile {
+        total_memory_mb,
+        available_memory_mb,
+        configured_mode: configured.unwrap_or(EXPORT_MODE_AUTO).to_string(),
+        effective_mode: effective_mode.to_string(),
+        max_images_in_flight: if low_memory {
+            LOW_MEMORY_IMAGES_IN_FLIGHT
+        } else {
+            STANDARD_IMAGES_IN_FLIGHT
+        },
+        chunk_size_bytes: if low_memory {
+            LOW_MEMORY_CHUNK_BYTES
+        } else {
+            STANDARD_CHUNK_BYTES
+        },
+    }
+}
+
+#[tauri::command]
+pub async fn get_export_memory_profile(
+    db_state: State<'_, DbConnection>,
+) -> Result<ExportMemoryProfile, AppError> {
+    let configured = db_state
+        .0
+        .run(|conn| Ok(retrieve_config(conn)?.export_memory_mode))
+        .await?;
+
+    let profile = export_memory_profile(configured.as_deref());
+    info!(
+        "Export memory profile: {} MB available of {} MB, using {} mode",
+        profile.available_memory_mb, profile.total_memory_mb, profile.effective_mode
+    );
+    Ok(profile)
+}
+
+// Transparency is flattened onto black, the slide master background.
+pub(crate) fn render_legacy_slide_image(source_bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let image = image::load_from_memory(source_bytes)
+        .context("Failed to decode slide image")?;
+    let image = if image.width() > LEGACY_MAX_WIDTH || image.height() > LEGACY_MAX_HEIGHT {
+        image.resize(LEGACY_MAX_WIDTH, LEGACY_MAX_HEIGHT, FilterType::Lanczos3)
+    } else {
+        image
+    };
+
+    let rgba = image.to_rgba8();
+    let mut flattened = RgbImage::new(rgba.width(), rgba.height());
+    for (x, y, pixel) in rgba.enumerate_pixels() {
+        let alpha = pixel[3] as u32;
+        flattened.put_pixel(
+            x,
+            y,
+            Rgb([
+                (pixel[0] as u32 * alpha / 255) as u8,
+                (pixel[1] as u32 * alpha / 255) as u8,
+                (pixel[2] as u32 * alpha / 255) as u8,
+            ]),
+        );
+    }
+
+    let mut png_bytes = Vec::new();
+    DynamicImage::ImageRgb8(flattened)
+        .write_to(&mut Cursor::new(&mut png_bytes), ImageFormat::Png)
+        .context("Failed to encode slide image")?;
+    Ok(png_bytes)
+}
+
+#[tauri::command]
+pub async fn get_legacy_slide_image(
+    app_handle: AppHandle,
+    relative_path: String,
+) -> Result<String, AppError> {
+    if relative_path.contains("..")
+        || relative_path.starts_with('/')
+        || relative_path.starts_with('\\')
+    {
+        return Err(AppError::invalid_input("Invalid relative path provided."));
+    }
+    let file_path = get_image_base_dir(&app_handle)?.join(&relative_path);
+
+    let png_bytes = tokio::task::spawn_blocking(move || {
+        let bytes = fs::read(&file_path)
+            .context(format!("Failed to read '{}'", file_path.display()))?;
+        render_legacy_slide_image(&cache_crypto::decrypt_if_encrypted(bytes)?)
+    })
+    .await
+    .context("Slide conversion task failed")??;
+
+    Ok(format!("data:image/png;base64,{}", base64_engine.encode(png_bytes)))
+}
@@ -0,0 +1,384 @@
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::time::Duration;
+use tauri::{AppHandle, State};
+use tauri_plugin_opener::OpenerExt;
+use uuid::Uuid;
+
+use crate::discord::{pkce_pair, wait_for_oauth_callback};
+use crate::error::{AppError, ResultExt};
+use crate::network::configured_http_client;
+use crate::providers::{KeyringSecrets, PathProvider, SecretStore};
+use crate::sqlite_manager::{retrieve_config, DbConnection};
+use crate::{log_info as info, log_warn as warn};
+
+const GOOGLE_OAUTH_AUTHORIZE_URL: &str = "https://accounts.google.com/o/oauth2/v2/auth";
+const GOOGLE_OAUTH_TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+const GOOGLE_OAUTH_REVOKE_URL: &str = "https://oauth2.googleapis.com/revoke";
+const DRIVE_UPLOAD_URL: &str = "https://www.googleapis.com/upload/drive/v3/files";
+// Only files the app creates itself; the rest of the user's Drive stays out of reach.
+const GOOGLE_SCOPES: &str = "https://www.googleapis.com/auth/drive.file";
+const GOOGLE_REFRESH_TOKEN_KEY: &str = "googleOAuthRefreshToken";
+// Google gives desktop clients a secret it does not treat as confidential, but the token
+// endpoint still wants it. Saved through save_secret like the bot token.
+const GOOGLE_CLIENT_SECRET_KEY: &str = "googleOAuthClientSecret";
+const GOOGLE_EXPIRY_MARGIN_SECS: i64 = 60;
+const PPTX_MIME: &str = "application/vnd.openxmlformats-officedocument.presentationml.presentation";
+// Uploading with this target type makes Drive convert the deck.
+const SLIDES_MIME: &str = "application/vnd.google-apps.presentation";
+const UPLOAD_TIMEOUT: Duration = Duration::from_secs(600);
+
+struct GoogleSession {
+    access_token: String,
+    expires_at: i64,
+}
+
+// Like DiscordOAuthState: the access token in memory, the refresh token in the keyring.
+#[derive(Default)]
+pub struct GoogleOAuthState(tokio::sync::Mutex<Option<GoogleSession>>);
+
+#[derive(serde::Deserialize)]
+struct GoogleTokenResponse {
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_in: i64,
+}
+
+#[derive(serde::Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DriveFile {
+    pub id: String,
+    pub web_view_link: Option<String>,
+}
+
+#[derive(serde::Serialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GoogleOAuthStatus {
+    logged_in: bool,
+}
+
+// Google accepts any loopback port for desktop clients, so the redirect uses whatever port
+// the OS hands out.
+fn google_redirect_uri(port: u16) -> String {
+    format!("http://127.0.0.1:{}/callback", port)
+}
+
+async fn google_client(db_state: &DbConnection) -> Result<(String, Option<String>), AppError> {
+    let client_id = db_state
+        .0
+        .run(|conn| Ok(retrieve_config(conn)?.google_oauth_client_id))
+        .await?
+        .filter(|id| !id.trim().is_empty())
+        .ok_or_else(|| AppError::invalid_input("No Google OAuth client ID configured."))?;
+    let client_secret = KeyringSecrets
+        .get_secret(GOOGLE_CLIENT_SECRET_KEY)
+        .map_err(|e| e.with_context("Failed to retrieve Google client secret"))?
+        .filter(|secret| !secret.is_empty());
+    Ok((client_id, client_secret))
+}
+
+fn load_refresh_token() -> Result<Option<String>, AppError> {
+    Ok(KeyringSecrets
+        .get_secret(GOOGLE_REFRESH_TOKEN_KEY)
+        .map_err(|e| e.with_context("Failed to retrieve Google refresh token"))?
+        .filter(|token| !token.is_empty()))
+}
+
+async fn ensure_success(
+    response: reqwest::Response,
+    action: &str,
+) -> Result<reqwest::Response, AppError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response.text().await.unwrap_or_default();
+    let message = format!("Failed to {} ({}): {}", action, status, body);
+    if status.as_u16() == 401 {
+        return Err(AppError::auth(message));
+    }
+    Err(message.into())
+}
+
+async fn request_google_token(
+    db_state: &DbConnection,
+    params: &[(&str, &str)],
+) -> Result<GoogleTokenResponse, AppError> {
+    let response = configured_http_client(db_state, None)
+        .await?
+        .post(GOOGLE_OAUTH_TOKEN_URL)
+        .form(params)
+        .send()
+        .await
+        .map_err(|e| AppError::from(e).with_context("Failed to reach Google OAuth"))?;
+    let response = ensure_success(response, "get a Google OAuth token").await?;
+    response
+        .json::<GoogleTokenResponse>()
+        .await
+        .map_err(|e| format!("Invalid Google OAuth token response: {}", e).into())
+}
+
+async fn store_google_tokens(
+    google_state: &GoogleOAuthState,
+    tokens: GoogleTokenResponse,
+) -> Result<String, AppError> {
+    // Refresh responses usually leave the refresh token out; the stored one stays valid.
+    if let Some(refresh_token) = &tokens.refresh_token {
+        KeyringSecrets
+            .set_secret(GOOGLE_REFRESH_TOKEN_KEY, refresh_token)
+            .map_err(|e| e.with_context("Failed to save Google refresh token"))?;
+    }
+    *google_state.0.lock().await = Some(GoogleSession {
+        access_token: tokens.access_token.clone(),
+        expires_at: Utc::now().timestamp() + tokens.expires_in,
+    });
+    Ok(tokens.access_token)
+}
+
+// None when nobody is signed in. An expired access token is refreshed transparently.
+async fn load_google_access_token(
+    db_state: &DbConnection,
+    google_state: &GoogleOAuthState,
+) -> Result<Option<String>, AppError> {
+    if let Some(session) = google_state.0.lock().await.as_ref() {
+        if session.expires_at - GOOGLE_EXPIRY_MARGIN_SECS > Utc::now().timestamp() {
+            return Ok(Some(session.access_token.clone()));
+        }
+    }
+    let Some(refresh_token) = load_refresh_token()? else {
+        return Ok(None);
+    };
+    let (client_id, client_secret) = google_client(db_state).await?;
+    let mut params = vec![
+        ("client_id", client_id.as_str()),
+        ("grant_type", "refresh_token"),
+        ("refresh_token", refresh_token.as_str()),
+    ];
+    if let Some(secret) = &client_secret {
+        params.push(("client_secret", secret.as_str()));
+    }
+    let tokens = request_google_token(db_state, &params).await?;
+    info!("Refreshed Google OAuth access token.");
+    store_google_tokens(google_state, tokens).await.map(Some)
+}
+
+pub(crate) fn google_slides_link(file: &DriveFile) -> String {
+    file.web_view_link
+        .clone()
+        .filter(|link| !link.is_empty())
+        .unwrap_or_else(|| format!("https://docs.google.com/presentation/d/{}/edit", file.id))
+}
+
+pub(crate) fn save_google_slides_url(
+    conn: &Connection,
+    id: &str,
+    url: Option<&str>,
+) -> Result<(), AppError> {
+    let rows = conn
+        .execute(
+            "UPDATE showcases SET google_slides_url = ?1 WHERE id = ?2",
+            params![url, id],
+        )
+        .context("DB error saving Google Slides link")?;
+    if rows == 0 {
+        return Err(AppError::not_found(format!(
+            "Showcase ID '{}' not found.",
+            id
+        )));
+    }
+    Ok(())
+}
+
+// Same loopback + PKCE flow as the Discord sign-in, asking for offline access so uploads
+// keep working after a restart.
+#[tauri::command]
+pub async fn start_google_oauth_login(
+    app_handle: AppHandle,
+    db_state: State<'_, DbConnection>,
+    google_state: State<'_, GoogleOAuthState>,
+) -> Result<GoogleOAuthStatus, AppError> {
+    let (client_id, client_secret) = google_client(&db_state).await?;
+    let (verifier, challenge) = pkce_pair();
+    let state = Uuid::new_v4().simple().to_string();
+
+    let listener = std::net::TcpListener::bind(("127.0.0.1", 0))
+        .context("Failed to listen for the Google redirect")?;
+    let port = listener
+        .local_addr()
+        .context("Failed to read the Google redirect port")?
+        .port();
+    let redirect_uri = google_redirect_uri(port);
+    let mut authorize_url = reqwest::Url::parse(GOOGLE_OAUTH_AUTHORIZE_URL)
+        .map_err(|e| format!("Invalid authorize URL: {}", e))?;
+    authorize_url
+        .query_pairs_mut()
+        .append_pair("client_id", &client_id)
+        .append_pair("response_type", "code")
+        .append_pair("redirect_uri", &redirect_uri)
+        .append_pair("scope", GOOGLE_SCOPES)
+        .append_pair("state", &state)
+        .append_pair("code_challenge", &challenge)
+        .append_pair("code_challenge_method", "S256")
+        .append_pair("access_type", "offline")
+        .append_pair("prompt", "consent");
+
+    info!("Opening Google sign-in in the browser...");
+    app_handle
+        .opener()
+        .open_url(authorize_url.as_str(), None::<&str>)
+        .map_err(|e| format!("Failed to open the Google sign-in page: {}", e))?;
+
+    let code =
+        tokio::task::spawn_blocking(move || wait_for_oauth_callback(listener, &state, "Google"))
+            .await
+            .context("OAuth callback task failed")??;
+
+    let mut params = vec![
+        ("client_id", client_id.as_str()),
+        ("grant_type", "authorization_code"),
+        ("code", code.as_str()),
+        ("redirect_uri", redirect_uri.as_str()),
+        ("code_verifier", verifier.as_str()),
+    ];
+    if let Some(secret) = &client_secret {
+        params.push(("client_secret", secret.as_str()));
+    }
+    let tokens = request_google_token(&db_state, &params).await?;
+    store_google_tokens(&google_state, tokens).await?;
+    info!("Signed in to Google.");
+    Ok(GoogleOAuthStatus { logged_in: true })
+}
+
+#[tauri::command]
+pub async fn get_google_oauth_status(
+    db_state: State<'_, DbConnection>,
+    google_state: State<'_, GoogleOAuthState>,
+) -> Result<GoogleOAuthStatus, AppError> {
+    let logged_in = load_google_access_token(&db_state, &google_state)
+        .await?
+        .is_some();
+    Ok(GoogleOAuthStatus { logged_in })
+}
+
+// Revocation is best effort; the local tokens are removed either way.
+#[tauri::command]
+pub async fn logout_google_oauth(
+    db_state: State<'_, DbConnection>,
+    google_state: State<'_, GoogleOAuthState>,
+) -> Result<(), AppError> {
+    if let Some(refresh_token) = load_refresh_token()? {
+        let revoked = match configured_http_client(&db_state, None).await {
+            Ok(client) => client
+                .post(GOOGLE_OAUTH_REVOKE_URL)
+                .form(&[("token", refresh_token.as_str())])
+                .send()
+                .await
+                .and_then(|r| r.error_for_status())
+                .map(|_| ())
+                .map_err(|e| e.to_string()),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = revoked {
+            warn!("Failed to revoke Google refresh token: {}", e);
+        }
+    }
+    KeyringSecrets
+        .delete_secret(GOOGLE_REFRESH_TOKEN_KEY)
+        .map_err(|e| e.with_context("Failed to delete Google refresh token"))?;
+    *google_state.0.lock().await = None;
+    info!("Signed out of Google.");
+    Ok(())
+}
+
+// Uploads the generated deck to the signed-in user's Drive as a Google Slides presentation
+// and keeps the link on the showcase. Each upload creates a new presentation.
+#[tauri::command]
+pub async fn upload_showcase_to_google_slides(
+    app_handle: AppHandle,
+    id: String,
+    db_state: State<'_, DbConnection>,
+    google_state: State<'_, GoogleOAuthState>,
+) -> Result<String, AppError> {
+    let lookup_id = id.clone();
+    let showcase: Option<(String, Option<String>)> = db_state
+        .0
+        .run(move |conn| {
+            conn.query_row(
+                "SELECT title, pptx_path FROM showcases WHERE id = ?1",
+                params![lookup_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()
+            .context("DB error loading showcase")
+        })
+        .await?;
+    let Some((title, pptx_path)) = showcase else {
+        return Err(AppError::not_found(format!(
+            "Showcase ID '{}' not found.",
+            id
+        )));
+    };
+    let Some(pptx_path) = pptx_path.filter(|p| !p.is_empty()) else {
+        return Err(AppError::invalid_input(
+            "Generate the presentation before uploading it to Google Slides.",
+        ));
+    };
+    let file_path = app_handle.app_data_dir()?.join(&pptx_path);
+    let deck = tokio::fs::read(&file_path).await.map_err(|e| {
+        AppError::from(e).with_context(format!("Failed to read '{}'", file_path.display()))
+    })?;
+
+    let Some(access_token) = load_google_access_token(&db_state, &google_state).await? else {
+        return Err(AppError::auth(
+            "Sign in to Google before uploading to Google Slides.",
+        ));
+    };
+    info!(
+        "Uploading showcase ID {} to Google Slides ({} bytes)",
+        id,
+        deck.len()
+    );
+
+    // A resumable session takes the metadata first and the whole deck in one PUT, which
+    // avoids the 5 MB cap on multipart uploads.
+    let client = configured_http_client(&db_state, Some(UPLOAD_TIMEOUT)).await?;
+    let session = client
+        .post(DRIVE_UPLOAD_URL)
+        .query(&[("uploadType", "resumable"), ("fields", "id,webViewLink")])
+        .bearer_auth(&access_token)
+        .header("X-Upload-Content-Type", PPTX_MIME)
+        .header("X-Upload-Content-Length", deck.len())
+        .json(&serde_json::json!({ "name": title, "mimeType": SLIDES_MIME }))
+        .send()
+        .await
+        .map_err(|e| AppError::from(e).with_context("Failed to reach Google Drive"))?;
+    let session = ensure_success(session, "start the Google Drive upload").await?;
+    let upload_url = session
+        .headers()
+        .get(reqwest::header::LOCATION)
+        .and_then(|value| value.to_str().ok())
+        .ok_or_else(|| AppError::from("Google Drive did not return an upload URL."))?
+        .to_string();
+
+    let uploaded = client
+        .put(&upload_url)
+        .header(reqwest::header::CONTENT_TYPE, PPTX_MIME)
+        .body(deck)
+        .send()
+        .await
+        .map_err(|e| AppError::from(e).with_context("Failed to upload the deck to Google Drive"))?;
+    let file: DriveFile = ensure_success(uploaded, "upload the deck to Google Drive")
+        .await?
+        .json()
+        .await
+        .context("Invalid Google Drive upload response")?;
+
+    let url = google_slides_link(&file);
+    let saved_url = url.clone();
+    db_state
+        .0
+        .run(move |conn| save_google_slides_url(conn, &id, Some(&saved_url)))
+        .await?;
+    info!("Uploaded to Google Slides: {}", url);
+    Ok(url)
+}
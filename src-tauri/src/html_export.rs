@@ -0,0 +1,273 @@
+use base64::{engine::general_purpose::STANDARD as base64_engine, Engine as _};
+use chrono::Utc;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, State};
+
+use crate::author_manager::resolve_showcase_credits;
+use crate::error::{AppError, ResultExt};
+use crate::log_info as info;
+use crate::models::{slide_key, ExportArtifact, HtmlExport};
+use crate::redaction::redact_text;
+use crate::share_server::escape_html;
+use crate::showcase_manager::{
+    get_showcase_image_dir, get_showcase_presentation_dir, load_showcase, publish_export,
+    record_export_artifact,
+};
+use crate::slide_render::{parse_resolution, plan_slides, write_slide_images, SlidePlan};
+use crate::spans::Span;
+use crate::sqlite_manager::{get_image_base_dir, retrieve_config, DbConnection};
+
+pub(crate) const HTML_EXPORT_DIR: &str = "web";
+const HTML_ASSETS_DIR: &str = "assets";
+const HTML_INDEX_FILE: &str = "index.html";
+// Slides are photos; JPEG keeps an inlined page a fraction of the PNG size.
+const HTML_IMAGE_FORMAT: &str = "jpg";
+
+// The text shown around one slide. The slide image already carries the overlay.
+pub(crate) struct GalleryText {
+    pub title: Option<String>,
+    pub credit: String,
+    pub caption: String,
+}
+
+pub(crate) struct GalleryPage<'a> {
+    pub title: &'a str,
+    pub description: Option<&'a str>,
+    pub slides: Vec<(String, GalleryText)>, // image src, text
+}
+
+pub(crate) fn render_static_gallery(page: &GalleryPage) -> String {
+    let items: String = page
+        .slides
+        .iter()
+        .enumerate()
+        .map(|(index, (src, text))| {
+            let heading = text
+                .title
+                .as_deref()
+                .map(|title| format!("<h2>{}</h2>", escape_html(title)))
+                .unwrap_or_default();
+            let caption = if text.caption.is_empty() {
+                String::new()
+            } else {
+                format!("<p>{}</p>", escape_html(&text.caption))
+            };
+            format!(
+                "<figure id=\"slide-{n}\">{heading}<img loading=\"lazy\" src=\"{src}\" alt=\"Slide {n} by {credit}\"><figcaption><strong>{credit}</strong>{caption}</figcaption></figure>",
+                n = index + 1,
+                heading = heading,
+                src = escape_html(src),
+                credit = escape_html(&text.credit),
+                caption = caption
+            )
+        })
+        .collect();
+
+    // Every featured artist once, in the order they first appear.
+    let mut seen = HashSet::new();
+    let credits: String = page
+        .slides
+        .iter()
+        .map(|(_, text)| text.credit.as_str())
+        .filter(|credit| seen.insert(*credit))
+        .map(|credit| format!("<li>{}</li>", escape_html(credit)))
+        .collect();
+    let description = page
+        .description
+        .map(str::trim)
+        .filter(|description| !description.is_empty())
+        .map(|description| format!("<p class=\"desc\">{}</p>", escape_html(description)))
+        .unwrap_or_default();
+
+    format!(
+        "<!doctype html><html lang=\"en\"><head><meta charset=\"utf-8\"><meta name=\"viewport\" content=\"width=device-width,initial-scale=1\"><title>{title}</title><style>body{{font-family:sans-serif;background:#000;color:#fff;margin:0 auto;padding:24px;max-width:1000px}}h1{{margin:0 0 4px}}h2{{font-size:18px;margin:0 0 8px}}.desc{{color:#ccc;white-space:pre-wrap}}.meta{{color:#aaa;margin-bottom:24px}}figure{{margin:0 0 40px}}img{{width:100%;border-radius:8px;background:#111}}figcaption{{margin-top:6px}}figcaption p{{color:#ccc;margin:4px 0 0;white-space:pre-wrap}}footer{{border-top:1px solid #333;color:#aaa;padding-top:16px}}footer ul{{columns:2;padding-left:18px}}</style></head><body><header><h1>{title}</h1>{description}<div class=\"meta\">{count} slide(s)</div></header><main>{items}</main><footer><h2>Featured artists</h2><ul>{credits}</ul></footer></body></html>",
+        title = escape_html(page.title),
+        description = description,
+        count = page.slides.len(),
+        items = items,
+        credits = credits
+    )
+}
+
+// Renders the slides into `folder` and writes the page as folder/index.html. Inlined pages
+// are a single file; otherwise the images stay next to it under assets/. A previous export
+// in the folder is replaced. Returns the page, the number of slides on it and the skipped
+// slide keys.
+pub(crate) fn write_html_export(
+    folder: &Path,
+    title: &str,
+    description: Option<&str>,
+    slides: Vec<(SlidePlan, GalleryText)>,
+    inline_images: bool,
+    resolution: (u32, u32),
+) -> Result<(PathBuf, usize, Vec<String>), String> {
+    if folder.exists() {
+        fs::remove_dir_all(folder).context(format!("Failed to clear '{}'", folder.display()))?;
+    }
+    let assets_dir = folder.join(HTML_ASSETS_DIR);
+    let (plans, texts): (Vec<SlidePlan>, Vec<GalleryText>) = slides.into_iter().unzip();
+    let (files, skipped) = write_slide_images(&plans, &assets_dir, HTML_IMAGE_FORMAT, resolution)?;
+
+    let rendered = plans
+        .iter()
+        .zip(texts)
+        .filter(|(plan, _)| !skipped.contains(&plan.slide_key))
+        .map(|(_, text)| text);
+    let mut gallery = Vec::with_capacity(files.len());
+    for (file, text) in files.iter().zip(rendered) {
+        let src = if inline_images {
+            let path = assets_dir.join(file);
+            let bytes = fs::read(&path).context(format!("Failed to read '{}'", path.display()))?;
+            format!("data:image/jpeg;base64,{}", base64_engine.encode(bytes))
+        } else {
+            format!("{}/{}", HTML_ASSETS_DIR, file)
+        };
+        gallery.push((src, text));
+    }
+    if inline_images {
+        fs::remove_dir_all(&assets_dir)
+            .context(format!("Failed to remove '{}'", assets_dir.display()))?;
+    }
+
+    let slide_count = gallery.len();
+    let html = render_static_gallery(&GalleryPage {
+        title,
+        description,
+        slides: gallery,
+    });
+    let index = folder.join(HTML_INDEX_FILE);
+    fs::write(&index, html).context(format!("Failed to write '{}'", index.display()))?;
+    Ok((index, slide_count, skipped))
+}
+
+// Writes a read-only web gallery of the slides, with credits, under presentations/<id>/web.
+// An inlined page is a single file and is published and recorded like a deck; a page with an
+// assets folder only works next to that folder, so it is recorded where it was written.
+#[tauri::command]
+pub async fn export_showcase_html(
+    app_handle: AppHandle,
+    id: String,
+    inline_images: Option<bool>,
+    resolution: Option<String>,
+    output_dir: Option<String>,
+    db_state: State<'_, DbConnection>,
+) -> Result<HtmlExport, AppError> {
+    let inline_images = inline_images.unwrap_or(true);
+    let resolution = parse_resolution(resolution.as_deref())?;
+    let span = Span::root("export.html", &[("showcase_id", id.clone())]);
+
+    let lookup_id = id.clone();
+    let (showcase, credits, redaction_rules) = db_state
+        .0
+        .run(move |conn| {
+            let showcase = load_showcase(conn, &lookup_id)?;
+            let selected_messages = showcase.selected_messages.clone().unwrap_or_default();
+            let credits = resolve_showcase_credits(conn, &selected_messages)?;
+            let rules = retrieve_config(conn)?.redaction_rules.unwrap_or_default();
+            Ok((showcase, credits, rules))
+        })
+        .await?;
+    let image_base_dir = get_image_base_dir(&app_handle)?;
+    let showcase_dir = get_showcase_image_dir(&app_handle, &id)?;
+    let plans = plan_slides(
+        &showcase,
+        &credits,
+        &image_base_dir,
+        &showcase_dir,
+        |text| redact_text(text, &redaction_rules),
+    );
+    if plans.is_empty() {
+        span.fail("no slides");
+        return Err(AppError::invalid_input(
+            "This showcase has no slides to export.",
+        ));
+    }
+
+    let selected_messages = showcase.selected_messages.clone().unwrap_or_default();
+    let slides: Vec<(SlidePlan, GalleryText)> = plans
+        .into_iter()
+        .map(|plan| {
+            let message = selected_messages.iter().find(|m| {
+                (0..m.attachment_filenames().len().max(1))
+                    .any(|index| slide_key(&m.message_id, index) == plan.slide_key)
+            });
+            let credit = message
+                .and_then(|m| credits.iter().find(|c| c.message_id == m.message_id))
+                .map(|c| c.credit_text.clone())
+                .or_else(|| message.map(|m| m.author_name.clone()))
+                .or_else(|| plan.overlay.as_ref().map(|slide| slide.sender.clone()))
+                .unwrap_or_default();
+            let caption = match &plan.overlay {
+                Some(slide) => slide.message.clone(),
+                None => message
+                    .map(|m| redact_text(&m.message_content, &redaction_rules))
+                    .unwrap_or_default(),
+            };
+            let text = GalleryText {
+                title: plan
+                    .overlay
+                    .as_ref()
+                    .and_then(|slide| slide.slide_title.clone()),
+                credit,
+                caption,
+            };
+            (plan, text)
+        })
+        .collect();
+
+    let folder = get_showcase_presentation_dir(&app_handle, &id)?.join(HTML_EXPORT_DIR);
+    let title = showcase.title.clone();
+    let description = showcase.description.clone();
+    let written = tokio::task::spawn_blocking(move || {
+        write_html_export(
+            &folder,
+            &title,
+            description.as_deref(),
+            slides,
+            inline_images,
+            resolution,
+        )
+    })
+    .await
+    .map_err(|e| format!("HTML export task panicked or was cancelled: {}", e))
+    .and_then(|result| result);
+    let (index, slide_count, skipped) = match written {
+        Ok(written) => written,
+        Err(e) => {
+            span.fail(&e);
+            return Err(e.into());
+        }
+    };
+    span.record("slides", slide_count);
+    info!(
+        "Exported HTML gallery with {} slide(s) for showcase ID: {} ({} skipped)",
+        slide_count,
+        id,
+        skipped.len()
+    );
+
+    let published = if inline_images {
+        publish_export(&app_handle, &db_state, &id, &index, "html", output_dir).await?
+    } else {
+        let artifact = ExportArtifact {
+            path: index.to_string_lossy().into_owned(),
+            format: "html".to_string(),
+            created_at: Utc::now().timestamp(),
+            sha256: None,
+            manifest: None,
+        };
+        db_state
+            .0
+            .run(move |conn| Ok(record_export_artifact(conn, &id, artifact)?))
+            .await?;
+        index
+    };
+    Ok(HtmlExport {
+        path: published.to_string_lossy().into_owned(),
+        slides: slide_count,
+        skipped,
+        inline_images,
+    })
+}
@@ -0,0 +1,296 @@
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Component, Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use tauri::http::{header, Request, Response, StatusCode};
+use tauri::{AppHandle, Manager};
+
+use crate::cache_crypto;
+use crate::error::AppError;
+use crate::log_warn as warn;
+use crate::models::ThumbnailSettings;
+use crate::sqlite_manager::{attachment_type_from_path, get_image_base_dir, DbConnection};
+use crate::thumbnails;
+
+// The frontend builds URLs with convertFileSrc(relativePath, IMAGE_PROTOCOL), which takes care of
+// the per-platform scheme format. "?size=thumb" serves the thumbnail instead of the original.
+pub(crate) const IMAGE_PROTOCOL: &str = "showcase-image";
+
+// Files are revalidated on every use, which is only a metadata read thanks to the ETag. A
+// repaired file keeps its path, so a long max-age would keep showing the broken one.
+const CACHE_CONTROL: &str = "private, no-cache";
+
+// Most bytes one range request gets back. Media elements ask for "bytes=0-" and come back for
+// the rest, so a long video is never held in memory whole.
+const MAX_RANGE_LEN: u64 = 8 * 1024 * 1024;
+
+fn percent_decode(input: &str) -> Option<String> {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'%' {
+            let hex = input.get(index + 1..index + 3)?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            index += 3;
+        } else {
+            decoded.push(bytes[index]);
+            index += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+// Only plain relative paths resolve. Canonical paths are compared as well, so a symlink inside
+// the image directory can't expose files outside it.
+pub(crate) fn resolve_image_path(
+    image_base_dir: &Path,
+    uri_path: &str,
+) -> Option<(String, PathBuf)> {
+    let relative_path = percent_decode(uri_path.trim_start_matches('/'))?.replace('\\', "/");
+    if relative_path.is_empty()
+        || !Path::new(&relative_path)
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+    {
+        return None;
+    }
+    let base_dir = image_base_dir.canonicalize().ok()?;
+    let path = base_dir.join(&relative_path).canonicalize().ok()?;
+    (path.starts_with(&base_dir) && path.is_file()).then_some((relative_path, path))
+}
+
+// A single "bytes=start-end", "bytes=start-" or "bytes=-suffix" range, as media elements send.
+// Returns the inclusive byte range, or None when it can't be satisfied.
+pub(crate) fn parse_range(value: &str, len: u64) -> Option<(u64, u64)> {
+    let spec = value.trim().strip_prefix("bytes=")?;
+    if spec.contains(',') {
+        return None;
+    }
+    let last = len.checked_sub(1)?;
+    let (start, end) = spec.split_once('-')?;
+    let (start, end) = match (start.trim(), end.trim()) {
+        ("", suffix) => {
+            let suffix: u64 = suffix.parse().ok()?;
+            if suffix == 0 {
+                return None;
+            }
+            (len.saturating_sub(suffix), last)
+        }
+        (start, "") => (start.parse().ok()?, last),
+        (start, end) => (start.parse().ok()?, end.parse::<u64>().ok()?.min(last)),
+    };
+    (start <= end).then_some((start, end))
+}
+
+fn text_response(status: StatusCode, message: &str) -> Response<Vec<u8>> {
+    Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, "text/plain; charset=utf-8")
+        .body(message.as_bytes().to_vec())
+        .unwrap_or_default()
+}
+
+fn entity_tag(path: &Path, variant: &str) -> Option<String> {
+    let metadata = fs::metadata(path).ok()?;
+    let modified = metadata
+        .modified()
+        .ok()?
+        .duration_since(UNIX_EPOCH)
+        .ok()?
+        .as_nanos();
+    Some(format!(
+        "\"{:x}-{:x}{}\"",
+        metadata.len(),
+        modified,
+        variant
+    ))
+}
+
+// Thumbnails and encrypted files only exist whole once loaded. Plain files stay open so a range
+// request reads just the bytes it asked for.
+enum Body {
+    Whole(Vec<u8>),
+    File(File, u64),
+}
+
+impl Body {
+    fn open(path: &Path) -> Result<Body, String> {
+        let mut file = File::open(path).map_err(|e| e.to_string())?;
+        let len = file.metadata().map_err(|e| e.to_string())?.len();
+        let mut data = Vec::with_capacity(cache_crypto::HEADER_LEN);
+        (&mut file)
+            .take(cache_crypto::HEADER_LEN as u64)
+            .read_to_end(&mut data)
+            .map_err(|e| e.to_string())?;
+        if !cache_crypto::is_encrypted(&data) {
+            return Ok(Body::File(file, len));
+        }
+        file.read_to_end(&mut data).map_err(|e| e.to_string())?;
+        cache_crypto::decrypt_if_encrypted(data).map(Body::Whole)
+    }
+
+    fn len(&self) -> u64 {
+        match self {
+            Body::Whole(bytes) => bytes.len() as u64,
+            Body::File(_, len) => *len,
+        }
+    }
+
+    fn read_all(self) -> Result<Vec<u8>, String> {
+        match self {
+            Body::Whole(bytes) => Ok(bytes),
+            Body::File(mut file, len) => {
+                let mut bytes = Vec::with_capacity(len as usize);
+                file.seek(SeekFrom::Start(0))
+                    .and_then(|_| file.read_to_end(&mut bytes))
+                    .map_err(|e| e.to_string())?;
+                Ok(bytes)
+            }
+        }
+    }
+
+    // `start..=end` as returned by parse_range.
+    fn read_range(self, start: u64, end: u64) -> Result<Vec<u8>, String> {
+        match self {
+            Body::Whole(bytes) => Ok(bytes[start as usize..=end as usize].to_vec()),
+            Body::File(mut file, _) => {
+                let mut bytes = Vec::with_capacity((end - start + 1) as usize);
+                file.seek(SeekFrom::Start(start))
+                    .and_then(|_| (&mut file).take(end - start + 1).read_to_end(&mut bytes))
+                    .map_err(|e| e.to_string())?;
+                Ok(bytes)
+            }
+        }
+    }
+}
+
+fn header_value<'a>(request: &'a Request<Vec<u8>>, name: header::HeaderName) -> Option<&'a str> {
+    request
+        .headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+}
+
+// `thumbnail_settings` is only called for thumbnail requests, so originals are served without
+// touching the database.
+pub(crate) fn serve_image(
+    image_base_dir: &Path,
+    request: &Request<Vec<u8>>,
+    thumbnail_settings: impl FnOnce() -> Result<(ThumbnailSettings, bool), AppError>,
+) -> Response<Vec<u8>> {
+    let Some((relative_path, path)) = resolve_image_path(image_base_dir, request.uri().path())
+    else {
+        return text_response(StatusCode::NOT_FOUND, "Image not found.");
+    };
+    let wants_thumbnail = request.uri().query().map_or(false, |query| {
+        query.split('&').any(|pair| pair == "size=thumb")
+    }) && attachment_type_from_path(&relative_path) != "video";
+
+    let thumbnail = if wants_thumbnail {
+        match thumbnail_settings() {
+            Ok(settings) => Some(settings),
+            Err(e) => return text_response(StatusCode::SERVICE_UNAVAILABLE, &e.message),
+        }
+    } else {
+        None
+    };
+    let variant = thumbnail
+        .as_ref()
+        .map(|(settings, _)| format!("-t{}q{}", settings.max_dimension, settings.quality))
+        .unwrap_or_default();
+    let etag = entity_tag(&path, &variant);
+    if let (Some(etag), Some(if_none_match)) = (&etag, header_value(request, header::IF_NONE_MATCH))
+    {
+        if if_none_match.split(',').any(|tag| tag.trim() == etag) {
+            return Response::builder()
+                .status(StatusCode::NOT_MODIFIED)
+                .header(header::ETAG, etag)
+                .header(header::CACHE_CONTROL, CACHE_CONTROL)
+                .body(Vec::new())
+                .unwrap_or_default();
+        }
+    }
+
+    let (body, content_type) = match &thumbnail {
+        Some((settings, encrypt)) => {
+            match thumbnails::load_or_generate_thumbnail(
+                image_base_dir,
+                &relative_path,
+                settings,
+                *encrypt,
+            ) {
+                Ok(bytes) => (Body::Whole(bytes), "image/jpeg".to_string()),
+                Err(e) => {
+                    warn!("Failed to serve thumbnail of {}: {}", relative_path, e);
+                    return text_response(StatusCode::INTERNAL_SERVER_ERROR, &e);
+                }
+            }
+        }
+        None => {
+            let body = match Body::open(&path) {
+                Ok(body) => body,
+                Err(e) => {
+                    warn!("Failed to serve {}: {}", relative_path, e);
+                    return text_response(StatusCode::INTERNAL_SERVER_ERROR, &e);
+                }
+            };
+            let mime_type = mime_guess::from_path(&path).first_or_octet_stream();
+            (body, mime_type.essence_str().to_string())
+        }
+    };
+
+    let len = body.len();
+    let served = match header_value(request, header::RANGE).map(|range| parse_range(range, len)) {
+        None => body.read_all().map(|bytes| (StatusCode::OK, None, bytes)),
+        Some(Some((start, end))) => {
+            let end = end.min(start + MAX_RANGE_LEN - 1);
+            body.read_range(start, end).map(|bytes| {
+                let content_range = format!("bytes {}-{}/{}", start, end, len);
+                (StatusCode::PARTIAL_CONTENT, Some(content_range), bytes)
+            })
+        }
+        Some(None) => Ok((
+            StatusCode::RANGE_NOT_SATISFIABLE,
+            Some(format!("bytes */{}", len)),
+            Vec::new(),
+        )),
+    };
+    let (status, content_range, bytes) = match served {
+        Ok(served) => served,
+        Err(e) => {
+            warn!("Failed to serve {}: {}", relative_path, e);
+            return text_response(StatusCode::INTERNAL_SERVER_ERROR, &e);
+        }
+    };
+
+    let mut response = Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, content_type)
+        .header(header::ACCEPT_RANGES, "bytes")
+        .header(header::CACHE_CONTROL, CACHE_CONTROL);
+    if let Some(etag) = &etag {
+        response = response.header(header::ETAG, etag);
+    }
+    if let Some(content_range) = content_range {
+        response = response.header(header::CONTENT_RANGE, content_range);
+    }
+    response.body(bytes).unwrap_or_default()
+}
+
+pub(crate) fn handle_request(
+    app_handle: &AppHandle,
+    request: &Request<Vec<u8>>,
+) -> Response<Vec<u8>> {
+    let image_base_dir = match get_image_base_dir(app_handle) {
+        Ok(dir) => dir,
+        Err(e) => return text_response(StatusCode::INTERNAL_SERVER_ERROR, &e),
+    };
+    serve_image(&image_base_dir, request, || {
+        let db_state = app_handle
+            .try_state::<DbConnection>()
+            .ok_or_else(|| AppError::conflict("The database is not loaded."))?;
+        // Requests are already served on a blocking thread.
+        Ok(thumbnails::read_settings(&db_state.0.lock()?)?)
+    })
+}
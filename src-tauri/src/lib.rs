@@ -1,108 +1,180 @@
-use keyring::Entry;
 use rusqlite::params;
 use serde_json;
-use std::{
-    fs,
-    sync::{Arc, Mutex},
-};
+use std::fs;
 use tauri::State;
 
+mod app_paths;
+mod author_cap;
+mod author_manager;
+mod author_notify;
+mod avatars;
+mod cache_crypto;
+mod cache_dedupe;
+mod cache_repair;
+mod cleanup_scheduler;
+mod content_filter;
+mod db_encryption;
+mod db_health;
 mod discord;
+mod error;
+mod export_pipeline;
+mod google_slides;
+mod html_export;
+mod image_protocol;
+mod live_indexing;
+mod locale_format;
 mod logging;
+mod manifest_import;
+mod migrations;
 mod models;
+mod network;
+mod pptx;
+mod pptx_template;
+mod providers;
+mod redaction;
+mod resync;
+mod reveal;
+mod safe_mode;
+mod share_server;
+mod showcase_archive;
+mod showcase_drafts;
 mod showcase_manager;
+mod showcase_revisions;
+mod shutdown;
+mod slide_render;
+mod spans;
+mod submission_bot;
 mod sqlite_manager;
+mod thumbnails;
+mod triage;
 mod version_manager;
+mod video;
+
+#[cfg(test)]
+mod tests;
 
-use discord::{fetch_discord_guilds, get_discord_channels, start_initial_indexing};
-use log::{error, info};
+use discord::{
+    cancel_indexing, discover_channels, estimate_indexing, fetch_discord_guilds, get_discord_channels, get_discord_oauth_status,
+    get_indexing_status, import_message_by_link, logout_discord_oauth, open_message_in_discord,
+    pause_indexing, preview_channel_messages, resume_indexing, start_discord_oauth_login, start_initial_indexing,
+    validate_discord_token, DiscordOAuthState, IndexingState,
+};
+use avatars::get_author_avatar;
+use error::{AppError, ResultExt};
+use cache_dedupe::dedupe_image_cache;
+use cache_repair::{collect_orphaned_cache_files, repair_cache};
+use content_filter::preview_content_filter;
+use author_manager::{
+    delete_author_credit, get_author_credit, get_uncredited_authors, list_author_credits,
+    list_showcase_consent, refresh_author_metadata, set_author_credit, set_message_consent,
+};
+use author_notify::{notify_featured_authors, set_author_notify_opt_out};
+use export_pipeline::{get_export_memory_profile, get_legacy_slide_image, ExportJobsState};
+use google_slides::{
+    get_google_oauth_status, logout_google_oauth, start_google_oauth_login,
+    upload_showcase_to_google_slides, GoogleOAuthState,
+};
+use html_export::export_showcase_html;
+use live_indexing::{
+    get_live_indexing_status, start_live_indexing, stop_live_indexing, LiveIndexingState,
+};
+use log::{error, info, warn};
+use manifest_import::import_manifest;
+use db_health::check_database_health;
+use migrations::get_schema_info;
 // Ensure models::AppConfig is usable, along with other necessary models
 use models::{AppConfig, FirstSlideSettings, OverlaySettings};
+use pptx::generate_showcase_pptx;
+use pptx_template::set_presentation_template;
+use providers::{KeyringSecrets, SecretStore};
+use resync::resync_indexed_messages;
+use reveal::{open_path, reveal_showcase_output};
+use safe_mode::{
+    get_safe_mode_status, list_database_backups, read_recent_logs, restore_database_backup,
+    run_startup_diagnostics, set_data_directory, SafeModeReport, SafeModeState,
+};
+use share_server::{
+    get_share_server_status, start_share_server, stop_share_server, ShareServerState,
+};
+use shutdown::resolve_shutdown;
+use submission_bot::{
+    get_submission_bot_status, start_submission_bot, stop_submission_bot, SubmissionBotState,
+};
+use showcase_archive::{
+    archive_showcase, export_showcase_archive, import_showcase_archive, unarchive_showcase,
+};
 use showcase_manager::{
-    check_showcase_pptx_exists, create_showcase, delete_showcase, get_selected_messages,
-    get_showcase, get_showcase_images, list_showcases, open_showcase_pptx, save_selected_messages,
-    save_showcase_pptx, sort_showcase_images, update_showcase, update_showcase_phase,
-    upload_showcase_image,
+    append_showcase_pptx_chunk, begin_showcase_pptx_export, check_showcase_pptx_exists,
+    create_recap_showcase, create_showcase, delete_showcase, diff_showcases, finish_showcase_pptx_export,
+    get_recent_export_locations, get_selected_messages,
+    generate_announcement, get_showcase, get_showcase_credits, get_showcase_images, get_showcase_render_info,
+    list_showcases, list_trashed_showcases, open_showcase_pptx, purge_showcase, query_showcases, restore_showcase,
+    repair_used_flags, save_selected_messages, save_showcase_pptx, save_showcase_rehearsal, shuffle_showcase_images, sort_showcase_images, update_showcase,
+    move_showcase_image, remove_showcase_image, update_showcase_image, update_showcase_phase, upload_showcase_image, upload_showcase_images_batch, verify_export_artifact,
 };
+use showcase_drafts::{discard_showcase_draft, get_showcase_draft, save_showcase_draft};
+use showcase_revisions::{list_showcase_revisions, restore_showcase_revision};
 use sqlite_manager::{
-    clean_old_data, delete_all_application_data, get_cached_image_data, get_indexed_messages,
-    get_storage_usage, retrieve_config, DbConnection,
+    clean_old_data, clear_image_cache_only, compact_database, delete_all_application_data, delete_indexed_messages, reset_configuration_only, encrypt_existing_database, get_cached_image_data, get_cached_images_batch, get_channel_index_status,
+    get_config_history, get_indexed_messages, get_indexing_stats, revert_config_to,
+    export_indexed_messages, get_storage_usage, query_indexed_messages, retrieve_config, search_indexed_messages, DbConnection,
 };
 
+use slide_render::{export_showcase_images, render_slide_preview};
+use thumbnails::get_cached_image_thumbnail;
+use triage::{get_next_unreviewed_message, record_selection_decision, set_message_favorite};
 use version_manager::{
     check_for_updates, get_current_version, get_update_github_link, get_version_info,
 };
+use video::export_showcase_video;
 
 pub const KEYRING_SERVICE_NAME: &str = "com.megalith.showcase-app";
 
 #[tauri::command]
-async fn save_secret(key_name: String, secret: String) -> Result<(), String> {
+async fn save_secret(key_name: String, secret: String) -> Result<(), AppError> {
     info!("Attempting to save secret for key: {}", key_name);
-    let entry = Entry::new(KEYRING_SERVICE_NAME, &key_name)
-        .map_err(|e| format!("Failed to create keyring entry for {}: {}", key_name, e))?;
-
-    match entry.set_password(&secret) {
-        Ok(_) => {
+    match KeyringSecrets.set_secret(&key_name, &secret) {
+        Ok(()) => {
             info!("Successfully saved secret for key: {}", key_name);
             Ok(())
         }
         Err(e) => {
             error!("Error saving secret for {}: {}", key_name, e);
-
-            Err(format!(
-                "Could not save secret for '{}'. Error: {}",
-                key_name, e
-            ))
+            Err(e)
         }
     }
 }
 
 #[tauri::command]
-async fn get_secret(key_name: String) -> Result<Option<String>, String> {
+async fn get_secret(key_name: String) -> Result<Option<String>, AppError> {
     info!("Attempting to get secret for key: {}", key_name);
-    let entry = Entry::new(KEYRING_SERVICE_NAME, &key_name)
-        .map_err(|e| format!("Failed to create keyring entry for {}: {}", key_name, e))?;
-
-    match entry.get_password() {
-        Ok(secret) => {
+    match KeyringSecrets.get_secret(&key_name) {
+        Ok(Some(secret)) => {
             info!("Successfully retrieved secret for key: {}", key_name);
             Ok(Some(secret))
         }
-        Err(keyring::Error::NoEntry) => {
+        Ok(None) => {
             info!("No secret found for key: {}", key_name);
             Ok(None)
         }
         Err(e) => {
             error!("Error retrieving secret for {}: {}", key_name, e);
-            Err(format!(
-                "Could not retrieve secret for '{}'. Error: {}",
-                key_name, e
-            ))
+            Err(e)
         }
     }
 }
 
 #[tauri::command]
-async fn delete_secret(key_name: String) -> Result<(), String> {
+async fn delete_secret(key_name: String) -> Result<(), AppError> {
     info!("Attempting to delete secret for key: {}", key_name);
-    let entry = Entry::new(KEYRING_SERVICE_NAME, &key_name)
-        .map_err(|e| format!("Failed to create keyring entry for {}: {}", key_name, e))?;
-
-    match entry.delete_credential() {
-        Ok(_) => {
+    match KeyringSecrets.delete_secret(&key_name) {
+        Ok(()) => {
             info!("Successfully deleted secret for key: {}", key_name);
             Ok(())
         }
-        Err(keyring::Error::NoEntry) => {
-            error!("No secret to delete for key: {}", key_name);
-            Ok(())
-        }
         Err(e) => {
             error!("Error deleting secret for {}: {}", key_name, e);
-            Err(format!(
-                "Could not delete secret for '{}'. Error: {}",
-                key_name, e
-            ))
+            Err(e)
         }
     }
 }
@@ -113,96 +185,380 @@ async fn delete_secret(key_name: String) -> Result<(), String> {
 async fn set_configuration(
     config: models::AppConfig, // Changed to use models::AppConfig
     db_state: State<'_, DbConnection>,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     info!("Saving full configuration: {:?}", config);
 
-    let mut conn_guard = db_state
+    db_state
         .0
-        .lock()
-        .map_err(|e| format!("DB lock error: {}", e))?;
+        .run(move |conn| {
+            let tx = conn
+                .transaction()
+                .context("Failed to start transaction")?;
+
+            let config_before = sqlite_manager::snapshot_config(&tx)?;
+            let insert_sql = "INSERT OR REPLACE INTO config (key, value) VALUES (?1, ?2);";
+
+            // selected_server_id
+            if let Some(id) = &config.selected_server_id {
+                tx.execute(insert_sql, params!["selected_server_id", id])
+                    .context("Failed to save selected_server_id")?;
+            } else {
+                tx.execute("DELETE FROM config WHERE key = 'selected_server_id';", [])
+                    .context("Failed to delete selected_server_id")?;
+            }
 
-    let tx = conn_guard
-        .transaction()
-        .map_err(|e| format!("Failed to start transaction: {}", e))?;
+            // selected_channel_ids
+            let channels_json = serde_json::to_string(&config.selected_channel_ids)
+                .context("Failed to serialize selected_channel_ids")?;
+            tx.execute(insert_sql, params!["selected_channel_ids", &channels_json])
+                .context("Failed to save selected_channel_ids")?;
+
+            // is_setup_complete
+            tx.execute(
+                insert_sql,
+                params![
+                    "is_setup_complete",
+                    if config.is_setup_complete { "true" } else { "false" }
+                ],
+            )
+            .context("Failed to save is_setup_complete")?;
+
+            // overlay_settings
+            if let Some(settings) = &config.overlay_settings {
+                let json_val = serde_json::to_string(settings)
+                    .context("Failed to serialize overlay_settings")?;
+                tx.execute(insert_sql, params!["overlay_settings_json", json_val])
+                    .context("Failed to save overlay_settings_json")?;
+            } else {
+                tx.execute("DELETE FROM config WHERE key = 'overlay_settings_json';", [])
+                    .context("Failed to delete overlay_settings_json")?;
+            }
 
-    let insert_sql = "INSERT OR REPLACE INTO config (key, value) VALUES (?1, ?2);";
+            // first_slide_settings
+            if let Some(settings) = &config.first_slide_settings {
+                let json_val = serde_json::to_string(settings)
+                    .context("Failed to serialize first_slide_settings")?;
+                tx.execute(insert_sql, params!["first_slide_settings_json", json_val])
+                    .context("Failed to save first_slide_settings_json")?;
+            } else {
+                tx.execute("DELETE FROM config WHERE key = 'first_slide_settings_json';", [])
+                    .context("Failed to delete first_slide_settings_json")?;
+            }
 
-    // selected_server_id
-    if let Some(id) = &config.selected_server_id {
-        tx.execute(insert_sql, params!["selected_server_id", id])
-            .map_err(|e| format!("Failed to save selected_server_id: {}", e))?;
-    } else {
-        tx.execute("DELETE FROM config WHERE key = 'selected_server_id';", [])
-            .map_err(|e| format!("Failed to delete selected_server_id: {}", e))?;
-    }
+            // auto_update_enabled
+            if let Some(enabled) = config.auto_update_enabled {
+                tx.execute(insert_sql, params!["auto_update_enabled", if enabled { "true" } else { "false" }])
+                    .context("Failed to save auto_update_enabled")?;
+            } else {
+                tx.execute("DELETE FROM config WHERE key = 'auto_update_enabled';", [])
+                    .context("Failed to delete auto_update_enabled")?;
+            }
 
-    // selected_channel_ids
-    let channels_json = serde_json::to_string(&config.selected_channel_ids)
-        .map_err(|e| format!("Failed to serialize selected_channel_ids: {}", e))?;
-    tx.execute(insert_sql, params!["selected_channel_ids", &channels_json])
-        .map_err(|e| format!("Failed to save selected_channel_ids: {}", e))?;
-
-    // is_setup_complete
-    tx.execute(
-        insert_sql,
-        params![
-            "is_setup_complete",
-            if config.is_setup_complete { "true" } else { "false" }
-        ],
-    )
-    .map_err(|e| format!("Failed to save is_setup_complete: {}", e))?;
-
-    // overlay_settings
-    if let Some(settings) = &config.overlay_settings {
-        let json_val = serde_json::to_string(settings)
-            .map_err(|e| format!("Failed to serialize overlay_settings: {}", e))?;
-        tx.execute(insert_sql, params!["overlay_settings_json", json_val])
-            .map_err(|e| format!("Failed to save overlay_settings_json: {}", e))?;
-    } else {
-        tx.execute("DELETE FROM config WHERE key = 'overlay_settings_json';", [])
-            .map_err(|e| format!("Failed to delete overlay_settings_json: {}", e))?;
-    }
+            // indexing_start_date
+            if let Some(date) = &config.indexing_start_date {
+                tx.execute(insert_sql, params!["indexing_start_date", date])
+                    .context("Failed to save indexing_start_date")?;
+            } else {
+                tx.execute("DELETE FROM config WHERE key = 'indexing_start_date';", [])
+                    .context("Failed to delete indexing_start_date")?;
+            }
 
-    // first_slide_settings
-    if let Some(settings) = &config.first_slide_settings {
-        let json_val = serde_json::to_string(settings)
-            .map_err(|e| format!("Failed to serialize first_slide_settings: {}", e))?;
-        tx.execute(insert_sql, params!["first_slide_settings_json", json_val])
-            .map_err(|e| format!("Failed to save first_slide_settings_json: {}", e))?;
-    } else {
-        tx.execute("DELETE FROM config WHERE key = 'first_slide_settings_json';", [])
-            .map_err(|e| format!("Failed to delete first_slide_settings_json: {}", e))?;
-    }
+            // indexing_window_days
+            if let Some(days) = config.indexing_window_days {
+                tx.execute(insert_sql, params!["indexing_window_days", days.to_string()])
+                    .context("Failed to save indexing_window_days")?;
+            } else {
+                tx.execute("DELETE FROM config WHERE key = 'indexing_window_days';", [])
+                    .context("Failed to delete indexing_window_days")?;
+            }
 
-    // auto_update_enabled
-    if let Some(enabled) = config.auto_update_enabled {
-        tx.execute(insert_sql, params!["auto_update_enabled", if enabled { "true" } else { "false" }])
-            .map_err(|e| format!("Failed to save auto_update_enabled: {}", e))?;
-    } else {
-        tx.execute("DELETE FROM config WHERE key = 'auto_update_enabled';", [])
-            .map_err(|e| format!("Failed to delete auto_update_enabled: {}", e))?;
-    }
+            // retention_days
+            if let Some(days) = config.retention_days {
+                tx.execute(insert_sql, params!["retention_days", days.to_string()])
+                    .context("Failed to save retention_days")?;
+            } else {
+                tx.execute("DELETE FROM config WHERE key = 'retention_days';", [])
+                    .context("Failed to delete retention_days")?;
+            }
 
-    tx.commit()
-        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+            // trash_retention_days
+            if let Some(days) = config.trash_retention_days {
+                tx.execute(insert_sql, params!["trash_retention_days", days.to_string()])
+                    .context("Failed to save trash_retention_days")?;
+            } else {
+                tx.execute("DELETE FROM config WHERE key = 'trash_retention_days';", [])
+                    .context("Failed to delete trash_retention_days")?;
+            }
 
-    info!("Full configuration saved successfully to DB.");
-    Ok(())
+            // compact_after_cleanup
+            if let Some(enabled) = config.compact_after_cleanup {
+                tx.execute(insert_sql, params!["compact_after_cleanup", if enabled { "true" } else { "false" }])
+                    .context("Failed to save compact_after_cleanup")?;
+            } else {
+                tx.execute("DELETE FROM config WHERE key = 'compact_after_cleanup';", [])
+                    .context("Failed to delete compact_after_cleanup")?;
+            }
+
+            // encrypt_image_cache
+            if let Some(enabled) = config.encrypt_image_cache {
+                tx.execute(insert_sql, params!["encrypt_image_cache", if enabled { "true" } else { "false" }])
+                    .context("Failed to save encrypt_image_cache")?;
+            } else {
+                tx.execute("DELETE FROM config WHERE key = 'encrypt_image_cache';", [])
+                    .context("Failed to delete encrypt_image_cache")?;
+            }
+
+            // cleanup_schedule
+            if let Some(schedule) = &config.cleanup_schedule {
+                let json_val = serde_json::to_string(schedule)
+                    .context("Failed to serialize cleanup_schedule")?;
+                tx.execute(insert_sql, params!["cleanup_schedule_json", json_val])
+                    .context("Failed to save cleanup_schedule_json")?;
+            } else {
+                tx.execute("DELETE FROM config WHERE key = 'cleanup_schedule_json';", [])
+                    .context("Failed to delete cleanup_schedule_json")?;
+            }
+
+            // download_concurrency
+            if let Some(concurrency) = config.download_concurrency {
+                tx.execute(insert_sql, params!["download_concurrency", concurrency.to_string()])
+                    .context("Failed to save download_concurrency")?;
+            } else {
+                tx.execute("DELETE FROM config WHERE key = 'download_concurrency';", [])
+                    .context("Failed to delete download_concurrency")?;
+            }
+
+            // author_filters
+            if let Some(filters) = &config.author_filters {
+                let json_val = serde_json::to_string(filters)
+                    .context("Failed to serialize author_filters")?;
+                tx.execute(insert_sql, params!["author_filters_json", json_val])
+                    .context("Failed to save author_filters_json")?;
+            } else {
+                tx.execute("DELETE FROM config WHERE key = 'author_filters_json';", [])
+                    .context("Failed to delete author_filters_json")?;
+            }
+
+            // locale
+            if let Some(locale) = &config.locale {
+                tx.execute(insert_sql, params!["locale", locale])
+                    .context("Failed to save locale")?;
+            } else {
+                tx.execute("DELETE FROM config WHERE key = 'locale';", [])
+                    .context("Failed to delete locale")?;
+            }
+
+            // live_indexing_enabled
+            if let Some(enabled) = config.live_indexing_enabled {
+                tx.execute(insert_sql, params!["live_indexing_enabled", if enabled { "true" } else { "false" }])
+                    .context("Failed to save live_indexing_enabled")?;
+            } else {
+                tx.execute("DELETE FROM config WHERE key = 'live_indexing_enabled';", [])
+                    .context("Failed to delete live_indexing_enabled")?;
+            }
+
+            // submission_bot_enabled
+            if let Some(enabled) = config.submission_bot_enabled {
+                tx.execute(insert_sql, params!["submission_bot_enabled", if enabled { "true" } else { "false" }])
+                    .context("Failed to save submission_bot_enabled")?;
+            } else {
+                tx.execute("DELETE FROM config WHERE key = 'submission_bot_enabled';", [])
+                    .context("Failed to delete submission_bot_enabled")?;
+            }
+
+            // exclude_sensitive_content
+            if let Some(enabled) = config.exclude_sensitive_content {
+                tx.execute(insert_sql, params!["exclude_sensitive_content", if enabled { "true" } else { "false" }])
+                    .context("Failed to save exclude_sensitive_content")?;
+            } else {
+                tx.execute("DELETE FROM config WHERE key = 'exclude_sensitive_content';", [])
+                    .context("Failed to delete exclude_sensitive_content")?;
+            }
+
+            // include_animated_attachments
+            if let Some(enabled) = config.include_animated_attachments {
+                tx.execute(insert_sql, params!["include_animated_attachments", if enabled { "true" } else { "false" }])
+                    .context("Failed to save include_animated_attachments")?;
+            } else {
+                tx.execute("DELETE FROM config WHERE key = 'include_animated_attachments';", [])
+                    .context("Failed to delete include_animated_attachments")?;
+            }
+
+            // export_memory_mode
+            if let Some(mode) = &config.export_memory_mode {
+                tx.execute(insert_sql, params!["export_memory_mode", mode])
+                    .context("Failed to save export_memory_mode")?;
+            } else {
+                tx.execute("DELETE FROM config WHERE key = 'export_memory_mode';", [])
+                    .context("Failed to delete export_memory_mode")?;
+            }
+
+            // index_linked_images
+            if let Some(enabled) = config.index_linked_images {
+                tx.execute(insert_sql, params!["index_linked_images", if enabled { "true" } else { "false" }])
+                    .context("Failed to save index_linked_images")?;
+            } else {
+                tx.execute("DELETE FROM config WHERE key = 'index_linked_images';", [])
+                    .context("Failed to delete index_linked_images")?;
+            }
+
+            // thumbnail_settings
+            if let Some(settings) = &config.thumbnail_settings {
+                let json_val = serde_json::to_string(settings)
+                    .context("Failed to serialize thumbnail_settings")?;
+                tx.execute(insert_sql, params!["thumbnail_settings_json", json_val])
+                    .context("Failed to save thumbnail_settings_json")?;
+            } else {
+                tx.execute("DELETE FROM config WHERE key = 'thumbnail_settings_json';", [])
+                    .context("Failed to delete thumbnail_settings_json")?;
+            }
+
+            // redaction_rules
+            if let Some(rules) = &config.redaction_rules {
+                let json_val = serde_json::to_string(rules)
+                    .context("Failed to serialize redaction_rules")?;
+                tx.execute(insert_sql, params!["redaction_rules_json", json_val])
+                    .context("Failed to save redaction_rules_json")?;
+            } else {
+                tx.execute("DELETE FROM config WHERE key = 'redaction_rules_json';", [])
+                    .context("Failed to delete redaction_rules_json")?;
+            }
+
+            // legacy_pptx_compatibility
+            if let Some(enabled) = config.legacy_pptx_compatibility {
+                tx.execute(insert_sql, params!["legacy_pptx_compatibility", if enabled { "true" } else { "false" }])
+                    .context("Failed to save legacy_pptx_compatibility")?;
+            } else {
+                tx.execute("DELETE FROM config WHERE key = 'legacy_pptx_compatibility';", [])
+                    .context("Failed to delete legacy_pptx_compatibility")?;
+            }
+
+            // image_size_filter
+            if let Some(filter) = &config.image_size_filter {
+                let json_val = serde_json::to_string(filter)
+                    .context("Failed to serialize image_size_filter")?;
+                tx.execute(insert_sql, params!["image_size_filter_json", json_val])
+                    .context("Failed to save image_size_filter_json")?;
+            } else {
+                tx.execute("DELETE FROM config WHERE key = 'image_size_filter_json';", [])
+                    .context("Failed to delete image_size_filter_json")?;
+            }
+
+            // content_filters
+            if let Some(filters) = &config.content_filters {
+                let json_val = serde_json::to_string(filters)
+                    .context("Failed to serialize content_filters")?;
+                tx.execute(insert_sql, params!["content_filters_json", json_val])
+                    .context("Failed to save content_filters_json")?;
+            } else {
+                tx.execute("DELETE FROM config WHERE key = 'content_filters_json';", [])
+                    .context("Failed to delete content_filters_json")?;
+            }
+
+            // discord_oauth_client_id
+            if let Some(client_id) = &config.discord_oauth_client_id {
+                tx.execute(insert_sql, params!["discord_oauth_client_id", client_id])
+                    .context("Failed to save discord_oauth_client_id")?;
+            } else {
+                tx.execute("DELETE FROM config WHERE key = 'discord_oauth_client_id';", [])
+                    .context("Failed to delete discord_oauth_client_id")?;
+            }
+
+            // google_oauth_client_id
+            if let Some(client_id) = &config.google_oauth_client_id {
+                tx.execute(insert_sql, params!["google_oauth_client_id", client_id])
+                    .context("Failed to save google_oauth_client_id")?;
+            } else {
+                tx.execute("DELETE FROM config WHERE key = 'google_oauth_client_id';", [])
+                    .context("Failed to delete google_oauth_client_id")?;
+            }
+
+            // network_settings
+            if let Some(settings) = &config.network_settings {
+                let json_val = serde_json::to_string(settings)
+                    .context("Failed to serialize network_settings")?;
+                tx.execute(insert_sql, params!["network_settings_json", json_val])
+                    .context("Failed to save network_settings_json")?;
+            } else {
+                tx.execute("DELETE FROM config WHERE key = 'network_settings_json';", [])
+                    .context("Failed to delete network_settings_json")?;
+            }
+
+            // channel_name_patterns
+            if let Some(patterns) = &config.channel_name_patterns {
+                let json_val = serde_json::to_string(patterns)
+                    .context("Failed to serialize channel_name_patterns")?;
+                tx.execute(insert_sql, params!["channel_name_patterns_json", json_val])
+                    .context("Failed to save channel_name_patterns_json")?;
+            } else {
+                tx.execute("DELETE FROM config WHERE key = 'channel_name_patterns_json';", [])
+                    .context("Failed to delete channel_name_patterns_json")?;
+            }
+
+            // auto_discover_channels
+            if let Some(enabled) = config.auto_discover_channels {
+                tx.execute(insert_sql, params!["auto_discover_channels", if enabled { "true" } else { "false" }])
+                    .context("Failed to save auto_discover_channels")?;
+            } else {
+                tx.execute("DELETE FROM config WHERE key = 'auto_discover_channels';", [])
+                    .context("Failed to delete auto_discover_channels")?;
+            }
+
+            // author_cap
+            if let Some(cap) = &config.author_cap {
+                let json_val = serde_json::to_string(cap)
+                    .context("Failed to serialize author_cap")?;
+                tx.execute(insert_sql, params!["author_cap_json", json_val])
+                    .context("Failed to save author_cap_json")?;
+            } else {
+                tx.execute("DELETE FROM config WHERE key = 'author_cap_json';", [])
+                    .context("Failed to delete author_cap_json")?;
+            }
+
+            // export_directory
+            if let Some(dir) = &config.export_directory {
+                tx.execute(insert_sql, params!["export_directory", dir])
+                    .context("Failed to save export_directory")?;
+            } else {
+                tx.execute("DELETE FROM config WHERE key = 'export_directory';", [])
+                    .context("Failed to delete export_directory")?;
+            }
+
+            // presentation_template
+            if let Some(path) = &config.presentation_template {
+                tx.execute(insert_sql, params!["presentation_template", path])
+                    .context("Failed to save presentation_template")?;
+            } else {
+                tx.execute("DELETE FROM config WHERE key = 'presentation_template';", [])
+                    .context("Failed to delete presentation_template")?;
+            }
+
+            let config_after = sqlite_manager::snapshot_config(&tx)?;
+            sqlite_manager::record_config_changes(&tx, &config_before, &config_after)?;
+
+            tx.commit()
+                .context("Failed to commit transaction")?;
+
+            info!("Full configuration saved successfully to DB.");
+            Ok(())
+        })
+        .await
 }
 
 #[tauri::command]
-async fn get_configuration(db_state: State<'_, DbConnection>) -> Result<models::AppConfig, String> { // Return type changed
+async fn get_configuration(db_state: State<'_, DbConnection>) -> Result<models::AppConfig, AppError> { // Return type changed
     info!("Command get_configuration called.");
-    let conn_guard = db_state
+    db_state
         .0
-        .lock()
-        .map_err(|e| format!("DB lock error in get_configuration command: {}", e))?;
-    // retrieve_config is already expected to return models::AppConfig from sqlite_manager modifications
-    sqlite_manager::retrieve_config(&conn_guard)
+        .run(move |conn| {
+            // retrieve_config is already expected to return models::AppConfig from sqlite_manager modifications
+            Ok(sqlite_manager::retrieve_config(conn)?)
+        })
+        .await
 }
 
 #[tauri::command]
-async fn is_setup_complete(db_state: State<'_, DbConnection>) -> Result<bool, String> {
+async fn is_setup_complete(db_state: State<'_, DbConnection>) -> Result<bool, AppError> {
     info!("Command is_setup_complete called.");
     // Re-use get_configuration to simplify and ensure consistency
     let config = get_configuration(db_state).await?;
@@ -210,19 +566,19 @@ async fn is_setup_complete(db_state: State<'_, DbConnection>) -> Result<bool, St
 }
 
 #[tauri::command]
-async fn log_frontend_info(message: String) -> Result<(), String> {
+async fn log_frontend_info(message: String) -> Result<(), AppError> {
     crate::log_info!("Frontend Info: {}", message);
     Ok(())
 }
 
 #[tauri::command]
-async fn log_frontend_warn(message: String) -> Result<(), String> {
+async fn log_frontend_warn(message: String) -> Result<(), AppError> {
     crate::log_warn!("Frontend Warn: {}", message);
     Ok(())
 }
 
 #[tauri::command]
-async fn log_frontend_error(message: String, error_details: Option<String>) -> Result<(), String> {
+async fn log_frontend_error(message: String, error_details: Option<String>) -> Result<(), AppError> {
     if let Some(details) = error_details {
         crate::log_error!("Frontend Error: {} - Details: {}", message, details);
     } else {
@@ -239,16 +595,22 @@ struct CustomizationSettingsPayload {
     first_slide_settings: Option<models::FirstSlideSettings>,
     #[serde(rename = "autoUpdateEnabled", skip_serializing_if = "Option::is_none")]
     auto_update_enabled: Option<bool>,
+    #[serde(rename = "legacyPptxCompatibility", skip_serializing_if = "Option::is_none")]
+    legacy_pptx_compatibility: Option<bool>,
+    #[serde(rename = "exportDirectory", skip_serializing_if = "Option::is_none")]
+    export_directory: Option<String>,
 }
 
 #[tauri::command]
-async fn get_customization_settings(db_state: State<'_, DbConnection>) -> Result<CustomizationSettingsPayload, String> {
+async fn get_customization_settings(db_state: State<'_, DbConnection>) -> Result<CustomizationSettingsPayload, AppError> {
     info!("Fetching customization settings...");
     let config = get_configuration(db_state).await?;
     Ok(CustomizationSettingsPayload {
         overlay_settings: config.overlay_settings,
         first_slide_settings: config.first_slide_settings,
         auto_update_enabled: config.auto_update_enabled,
+        legacy_pptx_compatibility: config.legacy_pptx_compatibility,
+        export_directory: config.export_directory,
     })
 }
 
@@ -256,19 +618,21 @@ async fn get_customization_settings(db_state: State<'_, DbConnection>) -> Result
 async fn save_customization_settings(
     payload: CustomizationSettingsPayload,
     db_state: State<'_, DbConnection>,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     info!("Saving customization settings: {:?}", payload);
     let mut current_config = get_configuration(db_state.clone()).await?; // Clone db_state for multiple uses
     
     current_config.overlay_settings = payload.overlay_settings;
     current_config.first_slide_settings = payload.first_slide_settings;
     current_config.auto_update_enabled = payload.auto_update_enabled;
+    current_config.legacy_pptx_compatibility = payload.legacy_pptx_compatibility;
+    current_config.export_directory = payload.export_directory;
     
     set_configuration(current_config, db_state).await
 }
 
 #[tauri::command]
-async fn get_auto_update_setting(db_state: State<'_, DbConnection>) -> Result<Option<bool>, String> {
+async fn get_auto_update_setting(db_state: State<'_, DbConnection>) -> Result<Option<bool>, AppError> {
     info!("Fetching auto_update_setting...");
     let config = get_configuration(db_state).await?;
     Ok(config.auto_update_enabled)
@@ -278,7 +642,7 @@ async fn get_auto_update_setting(db_state: State<'_, DbConnection>) -> Result<Op
 async fn set_auto_update_setting(
     enabled: bool,
     db_state: State<'_, DbConnection>,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     info!("Setting auto_update_setting to: {}", enabled);
     let mut current_config = get_configuration(db_state.clone()).await?;
     current_config.auto_update_enabled = Some(enabled);
@@ -293,25 +657,83 @@ pub fn run() {
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_store::Builder::new().build())
         .plugin(tauri_plugin_opener::init())
+        // Files are read and decrypted off the webview's thread.
+        .register_asynchronous_uri_scheme_protocol(image_protocol::IMAGE_PROTOCOL, |ctx, request, responder| {
+            let app_handle = ctx.app_handle().clone();
+            tauri::async_runtime::spawn_blocking(move || {
+                responder.respond(image_protocol::handle_request(&app_handle, &request));
+            });
+        })
         .setup(|app| {
             use tauri::Manager;
 
-            let log_path = logging::init_logging(&app.handle())?;
+            let log_path = match logging::init_logging(&app.handle()) {
+                Ok(path) => Some(path),
+                Err(e) => {
+                    eprintln!("Failed to initialize logging: {}", e);
+                    None
+                }
+            };
             info!("Application starting...");
-            info!("Log file: {}", log_path.display());
+            if let Some(path) = &log_path {
+                info!("Log file: {}", path.display());
+            }
+
+            // Initialization failures start the app in safe mode instead of aborting,
+            // so the user can still reach diagnostics, backups and the data dir picker.
+            let mut safe_mode_report = None;
 
             info!("Setting up database connection...");
-            let connection_raw = sqlite_manager::initialize_database(app.handle())
-                .map_err(|e| format!("FATAL: Database initialization failed: {}", e))?;
+            match sqlite_manager::initialize_database(app.handle()) {
+                Ok(pool) => {
+                    info!("Database initialized successfully.");
 
-            info!("Database initialized successfully.");
-            let db_arc = Arc::new(Mutex::new(connection_raw));
+                    info!("Managing state of type DbConnection.");
+                    app.manage(DbConnection(pool));
+                }
+                Err(e) => {
+                    error!("Database initialization failed, starting in safe mode: {}", e);
+                    safe_mode_report = Some(SafeModeReport {
+                        failed_step: "database".to_string(),
+                        reason: e,
+                    });
+                }
+            }
+
+            if safe_mode_report.is_none() {
+                if let Err(e) = safe_mode::probe_keyring() {
+                    error!("Keyring access failed, starting in safe mode: {}", e);
+                    safe_mode_report = Some(SafeModeReport {
+                        failed_step: "keyring".to_string(),
+                        reason: e,
+                    });
+                }
+            }
+
+            let is_safe_mode = safe_mode_report.is_some();
+            app.manage(SafeModeState {
+                report: safe_mode_report,
+                log_path,
+            });
+            app.manage(IndexingState::default());
+            app.manage(DiscordOAuthState::default());
+            app.manage(GoogleOAuthState::default());
+            app.manage(LiveIndexingState::default());
+            app.manage(SubmissionBotState::default());
+            app.manage(ShareServerState::default());
+            app.manage(ExportJobsState::default());
+
+            if is_safe_mode {
+                warn!("Safe mode active; background tasks are not started.");
+                return Ok(());
+            }
 
-            info!("Managing state of type DbConnection.");
-            app.manage(DbConnection(db_arc));
+            cleanup_scheduler::start(app.handle().clone());
+            live_indexing::start_if_enabled(app.handle().clone());
+            submission_bot::start_if_enabled(app.handle().clone());
 
             info!("Ensuring image directories exist...");
-            match app.path().app_data_dir() {
+            match app_paths::app_data_dir(app.handle()) {
                 Ok(app_data_dir) => {
                     let image_base_dir = app_data_dir.join("images");
                     let cached_image_dir = image_base_dir.join("cached");
@@ -351,6 +773,15 @@ pub fn run() {
             info!("Setup complete.");
             Ok(())
         })
+        .on_window_event(|window, event| {
+            use tauri::Manager;
+
+            if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+                if shutdown::defer_close(window.app_handle()) {
+                    api.prevent_close();
+                }
+            }
+        })
         .invoke_handler(tauri::generate_handler![
             // Keyring Commands (keyring.rs)
             save_secret,
@@ -359,31 +790,142 @@ pub fn run() {
             // Discord Commands (discord.rs)
             fetch_discord_guilds,
             get_discord_channels,
+            discover_channels,
+            preview_channel_messages,
+            validate_discord_token,
+            start_discord_oauth_login,
+            get_discord_oauth_status,
+            logout_discord_oauth,
             set_configuration,
             get_configuration,
             is_setup_complete,
             start_initial_indexing,
+            estimate_indexing,
+            cancel_indexing,
+            pause_indexing,
+            resume_indexing,
+            get_indexing_status,
+            resync_indexed_messages,
+            repair_cache,
+            collect_orphaned_cache_files,
+            dedupe_image_cache,
+            import_message_by_link,
+            preview_content_filter,
+            open_message_in_discord,
+            // Live Indexing Commands (live_indexing.rs)
+            start_live_indexing,
+            stop_live_indexing,
+            get_live_indexing_status,
+            // Submission Bot Commands (submission_bot.rs)
+            start_submission_bot,
+            stop_submission_bot,
+            get_submission_bot_status,
+            // Share Server Commands (share_server.rs)
+            start_share_server,
+            stop_share_server,
+            get_share_server_status,
             // Showcase Commands (showcase_manager.rs)
             create_showcase,
             get_showcase,
             list_showcases,
             delete_showcase,
+            restore_showcase,
+            list_trashed_showcases,
+            purge_showcase,
+            query_showcases,
+            save_showcase_draft,
+            get_showcase_draft,
+            discard_showcase_draft,
+            list_showcase_revisions,
+            restore_showcase_revision,
             update_showcase,
             update_showcase_phase,
+            save_showcase_rehearsal,
             save_selected_messages,
+            repair_used_flags,
             get_selected_messages,
+            remove_showcase_image,
+            update_showcase_image,
             upload_showcase_image,
+            upload_showcase_images_batch,
             sort_showcase_images,
+            move_showcase_image,
+            shuffle_showcase_images,
             get_showcase_images,
+            get_showcase_credits,
+            diff_showcases,
+            get_showcase_render_info,
+            generate_announcement,
+            create_recap_showcase,
+            get_recent_export_locations,
+            verify_export_artifact,
             get_storage_usage,
             save_showcase_pptx,
+            generate_showcase_pptx,
+            set_presentation_template,
+            export_showcase_images,
+            render_slide_preview,
+            export_showcase_video,
+            export_showcase_html,
+            export_showcase_archive,
+            import_showcase_archive,
+            archive_showcase,
+            unarchive_showcase,
+            begin_showcase_pptx_export,
+            append_showcase_pptx_chunk,
+            finish_showcase_pptx_export,
             open_showcase_pptx,
             check_showcase_pptx_exists,
+            // Google Slides Commands (google_slides.rs)
+            start_google_oauth_login,
+            get_google_oauth_status,
+            logout_google_oauth,
+            upload_showcase_to_google_slides,
+            // Reveal Commands (reveal.rs)
+            reveal_showcase_output,
+            open_path,
+            // Author Commands (author_manager.rs)
+            set_author_credit,
+            get_author_credit,
+            list_author_credits,
+            delete_author_credit,
+            refresh_author_metadata,
+            get_author_avatar,
+            list_showcase_consent,
+            set_message_consent,
+            get_uncredited_authors,
+            // Notification Commands (author_notify.rs)
+            notify_featured_authors,
+            set_author_notify_opt_out,
+            // Import Commands (manifest_import.rs)
+            import_manifest,
+            // Export Commands (export_pipeline.rs)
+            get_export_memory_profile,
+            get_legacy_slide_image,
             // Database/Other Commands (sqlite_manager.rs)
             get_indexed_messages,
+            search_indexed_messages,
+            query_indexed_messages,
+            export_indexed_messages,
+            get_next_unreviewed_message,
+            record_selection_decision,
+            set_message_favorite,
+            get_channel_index_status,
+            get_indexing_stats,
+            get_config_history,
+            revert_config_to,
             get_cached_image_data,
+            get_cached_images_batch,
+            get_cached_image_thumbnail,
             clean_old_data,
+            compact_database,
+            delete_indexed_messages,
+            clear_image_cache_only,
+            reset_configuration_only,
             delete_all_application_data,
+            encrypt_existing_database,
+            get_schema_info,
+            check_database_health,
             // Version Commands (version_manager.rs)
             check_for_updates,
             get_version_info,
@@ -397,8 +939,22 @@ pub fn run() {
             // Frontend Logging Commands
             log_frontend_info,
             log_frontend_warn,
-            log_frontend_error
+            log_frontend_error,
+            // Safe Mode Commands (safe_mode.rs)
+            get_safe_mode_status,
+            read_recent_logs,
+            run_startup_diagnostics,
+            list_database_backups,
+            restore_database_backup,
+            set_data_directory,
+            // Shutdown Commands (shutdown.rs)
+            resolve_shutdown
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app_handle, event| {
+            if let tauri::RunEvent::Exit = event {
+                shutdown::finalize(app_handle);
+            }
+        });
 }
@@ -0,0 +1,392 @@
+use serenity::all::{GatewayIntents, ShardManager};
+use serenity::async_trait;
+use serenity::client::{ClientBuilder, Context, EventHandler};
+use serenity::model::channel::{Channel, Message};
+use serenity::model::event::MessageUpdateEvent;
+use serenity::model::gateway::Ready;
+use serenity::model::id::{ChannelId, GuildId, MessageId};
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::Mutex as AsyncMutex;
+
+use crate::avatars;
+use crate::content_filter::ContentFilter;
+use crate::discord::{
+    author_filter_for, collect_download_jobs, download_to_cache, has_spoiler_attachment,
+    insert_indexed_messages, is_author_allowed, is_nsfw_channel, load_bot_token, message_author,
+    resolve_indexing_channels, to_indexed_message,
+    CacheDownloadJob, AUTHOR_TYPE_BOT, SOURCE_ATTACHMENT,
+};
+use crate::error::{AppError, ResultExt};
+use crate::models::{AuthorFilterSettings, ImageSizeFilter, IndexedMessage, ThumbnailSettings};
+use crate::network::{discord_http, ensure_gateway_allowed, http_client};
+use crate::sqlite_manager::{get_image_base_dir, retrieve_config, DbConnection, DbPool};
+use crate::thumbnails::{pregenerate_thumbnails, sanitize_settings};
+use crate::{log_error as error, log_info as info, log_warn as warn};
+
+#[derive(Default)]
+pub struct LiveIndexingState(pub AsyncMutex<Option<Arc<ShardManager>>>);
+
+#[derive(serde::Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveIndexingStatus {
+    pub running: bool,
+    pub channel_count: usize,
+}
+
+struct LiveIndexHandler {
+    app_handle: AppHandle,
+    db: DbPool,
+    guild_id: Option<GuildId>,
+    channel_ids: HashSet<ChannelId>,
+    nsfw_channel_ids: HashSet<ChannelId>, // selected channels flagged NSFW when the client started
+    exclude_sensitive: bool,
+    author_filters: AuthorFilterSettings,
+    content_filter: ContentFilter,
+    encrypt_cache: bool,
+    include_animated: bool,
+    include_linked: bool,
+    size_filter: ImageSizeFilter,
+    thumbnail_settings: ThumbnailSettings,
+    image_base_dir: PathBuf,
+    cache_dir: PathBuf,
+    download_client: reqwest::Client,
+}
+
+impl LiveIndexHandler {
+    // Threads are indexed when their parent forum/channel is selected.
+    async fn resolve_selected_channel(&self, ctx: &Context, msg: &Message) -> Option<ChannelId> {
+        if self.channel_ids.contains(&msg.channel_id) {
+            return Some(msg.channel_id);
+        }
+        if self.guild_id.is_some() && msg.guild_id != self.guild_id {
+            return None;
+        }
+        match msg.channel_id.to_channel(&ctx.http).await {
+            Ok(Channel::Guild(channel)) => channel
+                .parent_id
+                .filter(|parent| self.channel_ids.contains(parent)),
+            Ok(_) => None,
+            Err(e) => {
+                warn!("Live indexing: could not resolve channel {}: {}", msg.channel_id, e);
+                None
+            }
+        }
+    }
+
+    async fn index_message(
+        &self,
+        msg: Message,
+        jobs: Vec<CacheDownloadJob>,
+        nsfw_channel: bool,
+    ) -> Result<Option<IndexedMessage>, String> {
+        let mut saved = Vec::new();
+        for job in &jobs {
+            match download_to_cache(&self.download_client, job, self.encrypt_cache).await {
+                Ok(Some(cached_file)) => saved.push(cached_file),
+                Ok(None) => {}
+                Err(e) if job.source != SOURCE_ATTACHMENT => warn!("{}", e),
+                Err(e) => return Err(e),
+            }
+        }
+        if saved.is_empty() {
+            return Ok(None);
+        }
+
+        avatars::cache_author_avatars(
+            &self.download_client,
+            &self.image_base_dir,
+            [&msg.author],
+            self.encrypt_cache,
+            &mut HashSet::new(),
+        )
+        .await;
+        let indexed = to_indexed_message(&msg, &saved, nsfw_channel);
+
+        self.db
+            .run(move |conn| Ok(insert_indexed_messages(conn, &[(msg, saved)], nsfw_channel)?))
+            .await?;
+
+        if self.thumbnail_settings.pregenerate {
+            pregenerate_thumbnails(
+                self.image_base_dir.clone(),
+                indexed.attachments.clone(),
+                self.thumbnail_settings.clone(),
+                self.encrypt_cache,
+            )
+            .await;
+        }
+
+        Ok(Some(indexed))
+    }
+
+    async fn handle_message(&self, ctx: &Context, msg: Message) {
+        let jobs = collect_download_jobs(
+            &self.cache_dir,
+            0,
+            &msg,
+            self.include_animated,
+            self.include_linked,
+            &self.size_filter,
+        );
+        if jobs.is_empty() {
+            return;
+        }
+        let Some(selected_channel) = self.resolve_selected_channel(ctx, &msg).await else {
+            return;
+        };
+        let (author_id, author_type) = message_author(&msg);
+        if !is_author_allowed(
+            author_filter_for(&self.author_filters, &selected_channel.to_string()),
+            &author_id,
+            author_type == AUTHOR_TYPE_BOT,
+        ) {
+            return;
+        }
+        if !self.content_filter.allows(&msg.content) {
+            return;
+        }
+        let nsfw_channel = self.nsfw_channel_ids.contains(&selected_channel);
+        if self.exclude_sensitive && (nsfw_channel || has_spoiler_attachment(&msg)) {
+            return;
+        }
+
+        let message_id = msg.id;
+        match self.index_message(msg, jobs, nsfw_channel).await {
+            Ok(Some(indexed)) => {
+                info!("Live indexed message {} in channel {}", indexed.message_id, indexed.channel_id);
+                self.app_handle
+                    .emit("message-indexed", indexed)
+                    .unwrap_or_default();
+            }
+            Ok(None) => {}
+            Err(e) => {
+                error!("Live indexing failed for message {}: {}", message_id, e);
+                self.app_handle
+                    .emit(
+                        "indexing-error",
+                        format!("Live indexing failed for message {}: {}", message_id, e),
+                    )
+                    .unwrap_or_default();
+            }
+        }
+    }
+
+    async fn is_already_indexed(&self, message_id: MessageId) -> bool {
+        self.db
+            .run(move |conn| {
+                Ok(conn.query_row(
+                    "SELECT EXISTS(SELECT 1 FROM messages WHERE message_id = ?1)",
+                    [message_id.to_string()],
+                    |row| row.get(0),
+                )?)
+            })
+            .await
+            .unwrap_or(false)
+    }
+}
+
+#[async_trait]
+impl EventHandler for LiveIndexHandler {
+    async fn message(&self, ctx: Context, msg: Message) {
+        self.handle_message(&ctx, msg).await;
+    }
+
+    // Discord adds link previews in a follow-up update, so embed-only posts are picked up here.
+    async fn message_update(
+        &self,
+        ctx: Context,
+        _old_if_available: Option<Message>,
+        _new: Option<Message>,
+        event: MessageUpdateEvent,
+    ) {
+        if !self.include_linked || event.embeds.as_ref().map_or(true, |e| e.is_empty()) {
+            return;
+        }
+        if self.is_already_indexed(event.id).await {
+            return;
+        }
+        match event.channel_id.message(&ctx.http, event.id).await {
+            Ok(msg) => self.handle_message(&ctx, msg).await,
+            Err(e) => warn!("Live indexing: could not fetch updated message {}: {}", event.id, e),
+        }
+    }
+
+    async fn ready(&self, _ctx: Context, ready: Ready) {
+        info!(
+            "Live indexing connected as {} ({} channel(s) watched)",
+            ready.user.name,
+            self.channel_ids.len()
+        );
+        self.app_handle
+            .emit("live-indexing-status", "connected")
+            .unwrap_or_default();
+    }
+}
+
+async fn connect(app_handle: &AppHandle, live_state: &LiveIndexingState) -> Result<(), String> {
+    let mut running = live_state.0.lock().await;
+    if running.is_some() {
+        return Err("Live indexing is already running.".to_string());
+    }
+
+    let db_state = app_handle.state::<DbConnection>();
+    let config = db_state.0.run(|conn| Ok(retrieve_config(conn)?)).await?;
+
+    let network_settings = config.network_settings.clone().unwrap_or_default();
+    ensure_gateway_allowed(&network_settings)?;
+
+    let token = load_bot_token()?;
+    let http = discord_http(&network_settings, &token)?;
+    let channel_ids: HashSet<ChannelId> = resolve_indexing_channels(&http, &config)
+        .await
+        .iter()
+        .filter_map(|id| id.parse::<u64>().ok())
+        .map(ChannelId::new)
+        .collect();
+    if channel_ids.is_empty() {
+        return Err("No channels selected for live indexing.".to_string());
+    }
+    let mut nsfw_channel_ids = HashSet::new();
+    for channel_id in &channel_ids {
+        if let Ok(Channel::Guild(channel)) = http.get_channel(*channel_id).await {
+            if is_nsfw_channel(&http, &channel).await {
+                nsfw_channel_ids.insert(*channel_id);
+            }
+        }
+    }
+
+    let handler = LiveIndexHandler {
+        app_handle: app_handle.clone(),
+        db: db_state.0.clone(),
+        guild_id: config
+            .selected_server_id
+            .as_deref()
+            .and_then(|id| id.parse::<u64>().ok())
+            .map(GuildId::new),
+        channel_ids,
+        nsfw_channel_ids,
+        exclude_sensitive: config.exclude_sensitive_content.unwrap_or(false),
+        author_filters: config.author_filters.clone().unwrap_or_default(),
+        content_filter: ContentFilter::compile(&config.content_filters.clone().unwrap_or_default())?,
+        encrypt_cache: config.encrypt_image_cache.unwrap_or(false),
+        include_animated: config.include_animated_attachments.unwrap_or(false),
+        include_linked: config.index_linked_images.unwrap_or(true),
+        size_filter: config.image_size_filter.clone().unwrap_or_default(),
+        thumbnail_settings: sanitize_settings(config.thumbnail_settings.clone().unwrap_or_default()),
+        image_base_dir: get_image_base_dir(app_handle)?,
+        cache_dir: get_image_base_dir(app_handle)?.join("cached"),
+        download_client: http_client(&network_settings, Some(Duration::from_secs(30)))?,
+    };
+    let channel_count = handler.channel_ids.len();
+
+    // MESSAGE_CONTENT is privileged; without it guild messages arrive with no attachments.
+    let intents = GatewayIntents::GUILDS
+        | GatewayIntents::GUILD_MESSAGES
+        | GatewayIntents::MESSAGE_CONTENT;
+    let mut client = ClientBuilder::new_with_http(http, intents)
+        .event_handler(handler)
+        .await
+        .context("Failed to create gateway client")?;
+
+    let shard_manager = client.shard_manager.clone();
+    *running = Some(shard_manager.clone());
+    drop(running);
+
+    let app_clone = app_handle.clone();
+    tokio::spawn(async move {
+        if let Err(e) = client.start().await {
+            error!("Live indexing gateway client stopped with error: {}", e);
+            app_clone
+                .emit("indexing-error", format!("Live indexing disconnected: {}", e))
+                .unwrap_or_default();
+        }
+        {
+            let live_state = app_clone.state::<LiveIndexingState>();
+            let mut running = live_state.0.lock().await;
+            // A newer client may have been started after this one was stopped.
+            if running
+                .as_ref()
+                .map_or(false, |current| Arc::ptr_eq(current, &shard_manager))
+            {
+                *running = None;
+            }
+        }
+        app_clone
+            .emit("live-indexing-status", "disconnected")
+            .unwrap_or_default();
+        info!("Live indexing gateway client exited.");
+    });
+
+    info!("Live indexing started for {} channel(s).", channel_count);
+    Ok(())
+}
+
+// Called from setup; connects only when the user enabled live indexing.
+pub fn start_if_enabled(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let enabled = app_handle
+            .state::<DbConnection>()
+            .0
+            .run(|conn| {
+                let config = retrieve_config(conn)?;
+                Ok(config.is_setup_complete && config.live_indexing_enabled.unwrap_or(false))
+            })
+            .await;
+        let enabled = match enabled {
+            Ok(enabled) => enabled,
+            Err(e) => {
+                error!("Live indexing: could not read the settings: {}", e);
+                return;
+            }
+        };
+        if !enabled {
+            return;
+        }
+        let live_state = app_handle.state::<LiveIndexingState>();
+        if let Err(e) = connect(&app_handle, &live_state).await {
+            error!("Failed to start live indexing: {}", e);
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn start_live_indexing(
+    app_handle: AppHandle,
+    live_state: State<'_, LiveIndexingState>,
+) -> Result<(), AppError> {
+    Ok(connect(&app_handle, &live_state).await?)
+}
+
+#[tauri::command]
+pub async fn stop_live_indexing(live_state: State<'_, LiveIndexingState>) -> Result<(), AppError> {
+    let shard_manager = live_state.0.lock().await.take();
+    match shard_manager {
+        Some(manager) => {
+            info!("Stopping live indexing...");
+            manager.shutdown_all().await;
+            Ok(())
+        }
+        None => Err(AppError::conflict("Live indexing is not running.")),
+    }
+}
+
+#[tauri::command]
+pub async fn get_live_indexing_status(
+    db_state: State<'_, DbConnection>,
+    live_state: State<'_, LiveIndexingState>,
+) -> Result<LiveIndexingStatus, AppError> {
+    let running = live_state.0.lock().await.is_some();
+    let channel_count = db_state
+        .0
+        .run(|conn| Ok(retrieve_config(conn)?.selected_channel_ids.len()))
+        .await?;
+    Ok(LiveIndexingStatus {
+        running,
+        channel_count,
+    })
+}
@@ -0,0 +1,67 @@
+use chrono::{Local, Locale, TimeZone};
+
+pub const DEFAULT_LOCALE: &str = "en-US";
+
+fn language(tag: &str) -> String {
+    tag.split(['-', '_'])
+        .next()
+        .unwrap_or("en")
+        .to_lowercase()
+}
+
+// Accepts BCP 47 style tags ("de-DE") as well as POSIX style ("de_DE").
+pub fn parse_locale(tag: &str) -> Locale {
+    let posix = tag.trim().replace('-', "_");
+    Locale::try_from(posix.as_str())
+        .or_else(|_| Locale::try_from(language(tag).as_str()))
+        .unwrap_or(Locale::en_US)
+}
+
+pub fn format_date(timestamp: i64, tag: &str, long: bool) -> String {
+    let datetime = match Local.timestamp_opt(timestamp, 0).single() {
+        Some(dt) => dt,
+        None => return timestamp.to_string(),
+    };
+    let locale = parse_locale(tag);
+
+    let pattern = if !long {
+        "%x"
+    } else {
+        match language(tag).as_str() {
+            "ja" | "zh" | "ko" => "%x",
+            "en" if !tag.to_lowercase().ends_with("us") && tag.contains(['-', '_']) => "%-d %B %Y",
+            "en" => "%B %-d, %Y",
+            "de" | "da" | "nb" | "fi" | "cs" | "pl" => "%-d. %B %Y",
+            _ => "%-d %B %Y",
+        }
+    };
+
+    datetime.format_localized(pattern, locale).to_string()
+}
+
+fn thousands_separator(tag: &str) -> &'static str {
+    match language(tag).as_str() {
+        "de" | "es" | "it" | "nl" | "pt" | "tr" | "id" | "da" => ".",
+        "fr" | "ru" | "pl" | "cs" | "sv" | "nb" | "fi" | "uk" => "\u{202f}",
+        _ => ",",
+    }
+}
+
+pub fn format_number(value: i64, tag: &str) -> String {
+    let digits = value.unsigned_abs().to_string();
+    let separator = thousands_separator(tag);
+
+    let mut grouped = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            grouped.push_str(separator);
+        }
+        grouped.push(c);
+    }
+
+    if value < 0 {
+        format!("-{}", grouped)
+    } else {
+        grouped
+    }
+}
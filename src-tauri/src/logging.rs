@@ -8,7 +8,8 @@ use std::sync::Mutex;
 use zip::write::{FileOptions, ZipWriter};
 use zip::CompressionMethod;
 use tauri::AppHandle;
-use tauri::Manager;
+
+use crate::app_paths;
 
 static BACKEND_LOG_FILE_HANDLER: Lazy<Mutex<Option<LogFileHandler>>> = Lazy::new(|| Mutex::new(None));
 static FRONTEND_LOG_FILE_HANDLER: Lazy<Mutex<Option<LogFileHandler>>> = Lazy::new(|| Mutex::new(None));
@@ -298,10 +299,7 @@ impl LogFileHandler {
 }
 
 pub fn init_logging(app_handle: &AppHandle) -> Result<PathBuf, String> {
-    let app_data_dir = app_handle
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data dir: {}", e))?;
+    let app_data_dir = app_paths::app_data_dir(app_handle)?;
 
     let logs_dir = app_data_dir.join("logs");
 
@@ -0,0 +1,248 @@
+use chrono::Utc;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, State};
+
+use crate::discord::write_cache_file;
+use crate::error::{AppError, ResultExt};
+use crate::network::http_client;
+use crate::sqlite_manager::{get_image_base_dir, retrieve_config, DbConnection};
+use crate::{log_error as error, log_info as info, log_warn as warn};
+
+// Manifest format is documented in docs/import-manifest.md.
+#[derive(Debug, Deserialize)]
+pub struct ImportManifest {
+    pub source: Option<String>,
+    pub items: Vec<ManifestItem>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ManifestItem {
+    pub id: Option<String>,
+    pub images: Vec<String>,
+    pub author: String,
+    pub author_id: Option<String>,
+    pub author_avatar: Option<String>,
+    #[serde(default)]
+    pub caption: String,
+    pub timestamp: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ManifestImportResult {
+    pub imported_messages: usize,
+    pub skipped_messages: usize,
+    pub imported_images: usize,
+    pub errors: Vec<String>,
+}
+
+fn sanitize_id_part(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '-' })
+        .collect::<String>()
+        .trim_matches('-')
+        .to_string()
+}
+
+fn image_extension(source: &str) -> String {
+    let without_query = source.split(['?', '#']).next().unwrap_or(source);
+    Path::new(without_query)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .filter(|e| matches!(e.as_str(), "png" | "jpg" | "jpeg" | "webp"))
+        .unwrap_or_else(|| "png".to_string())
+}
+
+async fn read_manifest_image(
+    client: &reqwest::Client,
+    manifest_dir: &Path,
+    source: &str,
+) -> Result<Vec<u8>, String> {
+    if source.starts_with("http://") || source.starts_with("https://") {
+        let response = client
+            .get(source)
+            .send()
+            .await
+            .context(format!("Download request failed for {}", source))?;
+        if !response.status().is_success() {
+            return Err(format!("Download failed for {}: Status {}", source, response.status()));
+        }
+        return response
+            .bytes()
+            .await
+            .map(|b| b.to_vec())
+            .map_err(|e| format!("Failed to read bytes from download {}: {}", source, e));
+    }
+
+    let path = PathBuf::from(source);
+    let path = if path.is_absolute() {
+        path
+    } else {
+        manifest_dir.join(path)
+    };
+    tokio::task::spawn_blocking(move || {
+        fs::read(&path).map_err(|e| format!("Failed to read '{}': {}", path.display(), e))
+    })
+    .await
+    .context("File read task failed")?
+}
+
+#[tauri::command]
+pub async fn import_manifest(
+    app_handle: AppHandle,
+    manifest_path: String,
+    db_state: State<'_, DbConnection>,
+) -> Result<ManifestImportResult, AppError> {
+    info!("Importing manifest from {}", manifest_path);
+
+    let manifest_file = PathBuf::from(&manifest_path);
+    let manifest_json = fs::read_to_string(&manifest_file)
+        .context(format!("Failed to read manifest '{}'", manifest_path))?;
+    let manifest: ImportManifest = serde_json::from_str(&manifest_json)
+        .context("Invalid manifest format")?;
+    let manifest_dir = manifest_file
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+
+    let source = sanitize_id_part(manifest.source.as_deref().unwrap_or("manifest"));
+    let source = if source.is_empty() { "manifest".to_string() } else { source };
+    let channel_id = format!("manifest-{}", source);
+
+    let config = db_state.0.run(|conn| Ok(retrieve_config(conn)?)).await?;
+    let encrypt_cache = config.encrypt_image_cache.unwrap_or(false);
+    let cache_dir = get_image_base_dir(&app_handle)?.join("cached");
+
+    let client = http_client(
+        &config.network_settings.clone().unwrap_or_default(),
+        Some(Duration::from_secs(30)),
+    )?;
+
+    let mut result = ManifestImportResult {
+        imported_messages: 0,
+        skipped_messages: 0,
+        imported_images: 0,
+        errors: Vec::new(),
+    };
+    let now = Utc::now().timestamp();
+    let item_count = manifest.items.len();
+
+    for (index, item) in manifest.items.into_iter().enumerate() {
+        let item_id = item
+            .id
+            .as_deref()
+            .map(sanitize_id_part)
+            .filter(|id| !id.is_empty())
+            .unwrap_or_else(|| index.to_string());
+        let message_id = format!("{}-{}", channel_id, item_id);
+
+        app_handle
+            .emit(
+                "manifest-import-progress",
+                format!("Importing item {}/{}", index + 1, item_count),
+            )
+            .unwrap_or_default();
+
+        if item.images.is_empty() {
+            warn!("Manifest item {} has no images, skipping.", message_id);
+            result.skipped_messages += 1;
+            continue;
+        }
+
+        let mut saved_paths: Vec<String> = Vec::new();
+        for (image_index, image_source) in item.images.iter().enumerate() {
+            let local_filename = format!(
+                "{}_{}.{}",
+                message_id,
+                image_index,
+                image_extension(image_source)
+            );
+            let absolute_path = cache_dir.join(&local_filename);
+
+            if !absolute_path.exists() {
+                let bytes = match read_manifest_image(&client, &manifest_dir, image_source).await {
+                    Ok(bytes) => bytes,
+                    Err(e) => {
+                        error!("{}", e);
+                        result.errors.push(e);
+                        continue;
+                    }
+                };
+                let path_clone = absolute_path.clone();
+                let write_result = tokio::task::spawn_blocking(move || {
+                    write_cache_file(&path_clone, &bytes, encrypt_cache)
+                })
+                .await
+                .map_err(|e| format!("File write task failed: {}", e))
+                .and_then(|r| r);
+                if let Err(e) = write_result {
+                    error!("{}", e);
+                    result.errors.push(e);
+                    continue;
+                }
+                result.imported_images += 1;
+            }
+
+            saved_paths.push(
+                Path::new("cached")
+                    .join(&local_filename)
+                    .to_string_lossy()
+                    .into_owned(),
+            );
+        }
+
+        if saved_paths.is_empty() {
+            result.skipped_messages += 1;
+            continue;
+        }
+
+        let attachments_json = serde_json::to_string(&saved_paths)
+            .context("JSON Serialize")?;
+        let author_id = item
+            .author_id
+            .clone()
+            .unwrap_or_else(|| format!("manifest-{}", sanitize_id_part(&item.author)));
+
+        let channel_id = channel_id.clone();
+        let rows = db_state
+            .0
+            .run(move |conn| {
+                Ok(conn
+                    .execute(
+                        "INSERT OR IGNORE INTO messages (message_id, channel_id, author_id, author_name, author_avatar, message_content, attachments, timestamp) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                        params![
+                            &message_id,
+                            &channel_id,
+                            &author_id,
+                            &item.author,
+                            &item.author_avatar,
+                            &item.caption,
+                            &attachments_json,
+                            item.timestamp.unwrap_or(now),
+                        ],
+                    )
+                    .context(format!("Exec Insert ({})", message_id))?)
+            })
+            .await?;
+
+        if rows > 0 {
+            result.imported_messages += 1;
+        } else {
+            result.skipped_messages += 1;
+        }
+    }
+
+    info!(
+        "Manifest import finished: {} messages imported, {} skipped, {} images stored, {} errors",
+        result.imported_messages,
+        result.skipped_messages,
+        result.imported_images,
+        result.errors.len()
+    );
+    Ok(result)
+}
@@ -0,0 +1,803 @@
+use chrono::Utc;
+use rusqlite::{params, Connection, Error as RusqliteError, Transaction};
+use tauri::State;
+
+use crate::error::{AppError, ResultExt};
+use crate::models::{SchemaInfo, SchemaMigration, SchemaTable, SelectedMessage, ShowcaseImage};
+use crate::sqlite_manager::DbConnection;
+use crate::{log_info as info, log_warn as warn};
+
+struct Migration {
+    version: i32, // the schema version after this step
+    description: &'static str,
+    apply: fn(&Transaction) -> Result<(), String>,
+}
+
+fn sql(tx: &Transaction, batch: &str) -> Result<(), String> {
+    tx.execute_batch(batch).map_err(|e| e.to_string())
+}
+
+// Entries of a legacy JSON array column. A value that is not an array at all is quarantined
+// whole; otherwise each entry is decoded on its own.
+fn legacy_json_entries(
+    tx: &Transaction,
+    showcase_id: &str,
+    column: &str,
+    raw: Option<String>,
+) -> Result<Vec<serde_json::Value>, String> {
+    let Some(raw) = raw.filter(|raw| !raw.trim().is_empty() && raw.trim() != "null") else {
+        return Ok(Vec::new());
+    };
+    match serde_json::from_str::<serde_json::Value>(&raw) {
+        Ok(serde_json::Value::Array(entries)) => Ok(entries),
+        Ok(_) => {
+            quarantine_legacy_entry(tx, showcase_id, &raw, &format!("{}: not an array", column))?;
+            Ok(Vec::new())
+        }
+        Err(e) => {
+            quarantine_legacy_entry(tx, showcase_id, &raw, &format!("{}: {}", column, e))?;
+            Ok(Vec::new())
+        }
+    }
+}
+
+fn quarantine_legacy_entry(
+    tx: &Transaction,
+    showcase_id: &str,
+    row_json: &str,
+    reason: &str,
+) -> Result<(), String> {
+    warn!(
+        "Quarantining part of showcase {} during migration: {}",
+        showcase_id, reason
+    );
+    tx.execute(
+        "INSERT INTO quarantined_rows (table_name, row_key, row_json, reason, quarantined_at) VALUES ('showcases', ?1, ?2, ?3, ?4)",
+        params![showcase_id, row_json, reason, Utc::now().timestamp()],
+    )
+    .map(|_| ())
+    .map_err(|e| e.to_string())
+}
+
+// Moves selected_messages_json and images_json into showcase_messages and showcase_images.
+// The inserts name the columns as they are at version 22, so later columns can't break it.
+fn split_showcase_json(tx: &Transaction) -> Result<(), String> {
+    sql(
+        tx,
+        "
+CREATE TABLE IF NOT EXISTS showcase_messages (
+    showcase_id TEXT NOT NULL REFERENCES showcases(id) ON DELETE CASCADE,
+    message_id TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    channel_id TEXT NOT NULL,
+    author_id TEXT NOT NULL,
+    author_name TEXT NOT NULL,
+    author_avatar TEXT,
+    message_content TEXT NOT NULL,
+    attachment_filenames_json TEXT NOT NULL,
+    timestamp INTEGER NOT NULL,
+    reply_to_json TEXT,
+    author_avatar_path TEXT,
+    PRIMARY KEY (showcase_id, message_id)
+);
+CREATE INDEX IF NOT EXISTS idx_showcase_messages_message ON showcase_messages (message_id);
+CREATE TABLE IF NOT EXISTS showcase_images (
+    showcase_id TEXT NOT NULL REFERENCES showcases(id) ON DELETE CASCADE,
+    slide_key TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    message_id TEXT NOT NULL,
+    attachment_index INTEGER NOT NULL DEFAULT 0,
+    sender TEXT NOT NULL,
+    avatar TEXT NOT NULL,
+    message TEXT NOT NULL,
+    is_edited INTEGER NOT NULL DEFAULT 0,
+    overlay_json TEXT NOT NULL,
+    credit TEXT,
+    PRIMARY KEY (showcase_id, slide_key)
+);",
+    )?;
+
+    let showcases: Vec<(String, Option<String>, Option<String>)> = {
+        let mut stmt = tx
+            .prepare("SELECT id, selected_messages_json, images_json FROM showcases")
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+        rows
+    };
+    for (showcase_id, selected_json, images_json) in showcases {
+        let entries =
+            legacy_json_entries(tx, &showcase_id, "selected_messages_json", selected_json)?;
+        for (position, entry) in entries.into_iter().enumerate() {
+            let message = match serde_json::from_value::<SelectedMessage>(entry.clone()) {
+                Ok(message) => message,
+                Err(e) => {
+                    quarantine_legacy_entry(
+                        tx,
+                        &showcase_id,
+                        &entry.to_string(),
+                        &format!("selected_messages_json: {}", e),
+                    )?;
+                    continue;
+                }
+            };
+            let reply_to_json = message
+                .reply_to
+                .as_ref()
+                .map(serde_json::to_string)
+                .transpose()
+                .map_err(|e| e.to_string())?;
+            tx.execute(
+                "INSERT OR IGNORE INTO showcase_messages (showcase_id, message_id, position, channel_id, author_id, author_name, author_avatar, message_content, attachment_filenames_json, timestamp, reply_to_json, author_avatar_path)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+                params![
+                    &showcase_id,
+                    message.message_id,
+                    position as i64,
+                    message.channel_id,
+                    message.author_id,
+                    message.author_name,
+                    message.author_avatar,
+                    message.message_content,
+                    serde_json::to_string(&message.attachment_filenames()).map_err(|e| e.to_string())?,
+                    message.timestamp,
+                    reply_to_json,
+                    message.author_avatar_path,
+                ],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+
+        let entries = legacy_json_entries(tx, &showcase_id, "images_json", images_json)?;
+        for (position, entry) in entries.into_iter().enumerate() {
+            let image = match serde_json::from_value::<ShowcaseImage>(entry.clone()) {
+                Ok(image) => image,
+                Err(e) => {
+                    quarantine_legacy_entry(
+                        tx,
+                        &showcase_id,
+                        &entry.to_string(),
+                        &format!("images_json: {}", e),
+                    )?;
+                    continue;
+                }
+            };
+            tx.execute(
+                "INSERT OR IGNORE INTO showcase_images (showcase_id, slide_key, position, message_id, attachment_index, sender, avatar, message, is_edited, overlay_json, credit)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                params![
+                    &showcase_id,
+                    image.slide_key(),
+                    position as i64,
+                    image.message_id,
+                    image.attachment_index as i64,
+                    image.sender,
+                    image.avatar,
+                    image.message,
+                    image.is_edited,
+                    serde_json::to_string(&image.overlay).map_err(|e| e.to_string())?,
+                    image.credit,
+                ],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+    }
+
+    sql(
+        tx,
+        "
+ALTER TABLE showcases DROP COLUMN selected_messages_json;
+ALTER TABLE showcases DROP COLUMN images_json;",
+    )
+}
+
+// Released steps are frozen: change the schema by appending a new migration, never by editing
+// one that shipped. Each step runs in its own transaction together with the version bump.
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "Config, showcases and messages tables",
+        apply: |tx| {
+            sql(
+                tx,
+                "
+CREATE TABLE IF NOT EXISTS config (
+    key TEXT PRIMARY KEY NOT NULL,
+    value TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS showcases (
+    id TEXT PRIMARY KEY NOT NULL,
+    title TEXT NOT NULL,
+    description TEXT,
+    status TEXT NOT NULL DEFAULT 'Draft',
+    created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+    last_modified INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+    phase INTEGER NOT NULL DEFAULT 1,
+    selected_messages_json TEXT,
+    pptx_path TEXT,
+    images_json TEXT
+);
+CREATE TABLE IF NOT EXISTS messages (
+    message_id TEXT PRIMARY KEY NOT NULL,
+    channel_id TEXT NOT NULL,
+    author_id TEXT NOT NULL,
+    author_name TEXT NOT NULL,
+    author_avatar TEXT,
+    message_content TEXT NOT NULL,
+    attachments TEXT NOT NULL DEFAULT '[]',
+    timestamp INTEGER NOT NULL,
+    is_used INTEGER NOT NULL DEFAULT 0
+);
+CREATE INDEX IF NOT EXISTS idx_messages_channel_id ON messages (channel_id);
+CREATE INDEX IF NOT EXISTS idx_messages_timestamp ON messages (timestamp);
+CREATE INDEX IF NOT EXISTS idx_messages_author_id ON messages (author_id);",
+            )
+        },
+    },
+    Migration {
+        version: 2,
+        description: "Per-author credit templates and links",
+        apply: |tx| {
+            sql(
+                tx,
+                "
+CREATE TABLE IF NOT EXISTS authors (
+    author_id TEXT PRIMARY KEY NOT NULL,
+    display_name TEXT,
+    credit_template TEXT,
+    links_json TEXT NOT NULL DEFAULT '[]',
+    updated_at INTEGER NOT NULL DEFAULT 0
+);",
+            )
+        },
+    },
+    Migration {
+        version: 3,
+        description: "Attachment types",
+        apply: |tx| {
+            sql(
+                tx,
+                "ALTER TABLE messages ADD COLUMN attachment_types TEXT NOT NULL DEFAULT '[]';",
+            )
+        },
+    },
+    Migration {
+        version: 4,
+        description: "Attachment sources",
+        apply: |tx| {
+            sql(
+                tx,
+                "ALTER TABLE messages ADD COLUMN attachment_sources TEXT NOT NULL DEFAULT '[]';",
+            )
+        },
+    },
+    Migration {
+        version: 5,
+        description: "Per-channel indexing runs",
+        apply: |tx| {
+            sql(
+                tx,
+                "
+CREATE TABLE IF NOT EXISTS channel_index_runs (
+    channel_id TEXT PRIMARY KEY NOT NULL,
+    last_run_at INTEGER NOT NULL,
+    last_result TEXT NOT NULL,
+    messages_saved INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT
+);",
+            )
+        },
+    },
+    Migration {
+        version: 6,
+        description: "Message reactions",
+        apply: |tx| {
+            sql(
+                tx,
+                "
+ALTER TABLE messages ADD COLUMN reaction_count INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE messages ADD COLUMN reactions_json TEXT NOT NULL DEFAULT '[]';",
+            )
+        },
+    },
+    Migration {
+        version: 7,
+        description: "Deleted flag for resynced messages",
+        apply: |tx| {
+            sql(
+                tx,
+                "ALTER TABLE messages ADD COLUMN deleted INTEGER NOT NULL DEFAULT 0;",
+            )
+        },
+    },
+    Migration {
+        version: 8,
+        description: "Reply context",
+        apply: |tx| sql(tx, "ALTER TABLE messages ADD COLUMN reply_to_json TEXT;"),
+    },
+    Migration {
+        version: 9,
+        description: "Resumable channel runs",
+        apply: |tx| {
+            sql(
+                tx,
+                "
+ALTER TABLE channel_index_runs ADD COLUMN resume_before_id TEXT;
+ALTER TABLE channel_index_runs ADD COLUMN resume_top_id TEXT;
+ALTER TABLE channel_index_runs ADD COLUMN resume_window_end INTEGER;",
+            )
+        },
+    },
+    Migration {
+        version: 10,
+        description: "Cached author avatars",
+        apply: |tx| {
+            sql(
+                tx,
+                "ALTER TABLE messages ADD COLUMN author_avatar_path TEXT;",
+            )
+        },
+    },
+    Migration {
+        version: 11,
+        description: "Rehearsal timings",
+        apply: |tx| sql(tx, "ALTER TABLE showcases ADD COLUMN rehearsal_json TEXT;"),
+    },
+    Migration {
+        version: 12,
+        description: "Config history",
+        apply: |tx| {
+            sql(
+                tx,
+                "
+CREATE TABLE IF NOT EXISTS config_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    changed_at INTEGER NOT NULL,
+    key TEXT NOT NULL,
+    old_value TEXT,
+    new_value TEXT
+);
+CREATE INDEX IF NOT EXISTS idx_config_history_changed_at ON config_history (changed_at);",
+            )
+        },
+    },
+    Migration {
+        version: 13,
+        description: "Bot submissions",
+        apply: |tx| {
+            sql(
+                tx,
+                "ALTER TABLE messages ADD COLUMN is_submission INTEGER NOT NULL DEFAULT 0;",
+            )
+        },
+    },
+    Migration {
+        version: 14,
+        description: "Spoiler and NSFW flags",
+        apply: |tx| {
+            sql(
+                tx,
+                "
+ALTER TABLE messages ADD COLUMN is_spoiler INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE messages ADD COLUMN is_nsfw_channel INTEGER NOT NULL DEFAULT 0;",
+            )
+        },
+    },
+    Migration {
+        version: 15,
+        description: "Export history",
+        apply: |tx| sql(tx, "ALTER TABLE showcases ADD COLUMN exports_json TEXT;"),
+    },
+    Migration {
+        version: 16,
+        description: "Triage decisions",
+        apply: |tx| {
+            sql(
+                tx,
+                "
+ALTER TABLE messages ADD COLUMN review_decision TEXT;
+ALTER TABLE messages ADD COLUMN reviewed_at INTEGER;",
+            )
+        },
+    },
+    Migration {
+        version: 17,
+        description: "Author types",
+        apply: |tx| {
+            sql(
+                tx,
+                "ALTER TABLE messages ADD COLUMN author_type TEXT NOT NULL DEFAULT 'user';",
+            )
+        },
+    },
+    // External-content FTS index over messages, kept in sync by triggers so every insert path
+    // (indexing, submissions, manifest imports) is covered. It is keyed by the implicit rowid,
+    // which VACUUM may renumber, so anything that vacuums the database must rebuild it afterwards.
+    Migration {
+        version: 18,
+        description: "Full-text message search",
+        apply: |tx| {
+            sql(tx,
+            "
+CREATE VIRTUAL TABLE IF NOT EXISTS messages_fts USING fts5(
+    message_content,
+    author_name,
+    content='messages',
+    content_rowid='rowid',
+    tokenize='unicode61 remove_diacritics 2'
+);
+CREATE TRIGGER IF NOT EXISTS messages_fts_insert AFTER INSERT ON messages BEGIN
+    INSERT INTO messages_fts(rowid, message_content, author_name) VALUES (new.rowid, new.message_content, new.author_name);
+END;
+CREATE TRIGGER IF NOT EXISTS messages_fts_delete AFTER DELETE ON messages BEGIN
+    INSERT INTO messages_fts(messages_fts, rowid, message_content, author_name) VALUES ('delete', old.rowid, old.message_content, old.author_name);
+END;
+CREATE TRIGGER IF NOT EXISTS messages_fts_update AFTER UPDATE OF message_content, author_name ON messages BEGIN
+    INSERT INTO messages_fts(messages_fts, rowid, message_content, author_name) VALUES ('delete', old.rowid, old.message_content, old.author_name);
+    INSERT INTO messages_fts(rowid, message_content, author_name) VALUES (new.rowid, new.message_content, new.author_name);
+END;
+INSERT INTO messages_fts(messages_fts) VALUES ('rebuild');",
+        )
+        },
+    },
+    Migration {
+        version: 19,
+        description: "Quarantine for undecodable rows",
+        apply: |tx| {
+            sql(
+                tx,
+                "
+CREATE TABLE IF NOT EXISTS quarantined_rows (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    table_name TEXT NOT NULL,
+    row_key TEXT NOT NULL,
+    row_json TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    quarantined_at INTEGER NOT NULL
+);",
+            )
+        },
+    },
+    Migration {
+        version: 20,
+        description: "Favorite messages",
+        apply: |tx| {
+            sql(
+                tx,
+                "
+ALTER TABLE messages ADD COLUMN is_favorite INTEGER NOT NULL DEFAULT 0;
+CREATE INDEX IF NOT EXISTS idx_messages_favorite ON messages (is_favorite) WHERE is_favorite = 1;",
+            )
+        },
+    },
+    Migration {
+        version: 21,
+        description: "Content hashes of cached files",
+        apply: |tx| {
+            sql(
+                tx,
+                "
+CREATE TABLE IF NOT EXISTS cached_files (
+    relative_path TEXT PRIMARY KEY NOT NULL,
+    content_hash TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_cached_files_hash ON cached_files (content_hash);",
+            )
+        },
+    },
+    Migration {
+        version: 22,
+        description: "Showcase selections and slides in their own tables",
+        apply: split_showcase_json,
+    },
+    Migration {
+        version: 23,
+        description: "Showcase trash",
+        apply: |tx| {
+            sql(
+                tx,
+                "
+ALTER TABLE showcases ADD COLUMN deleted_at INTEGER;
+CREATE INDEX IF NOT EXISTS idx_showcases_deleted_at ON showcases (deleted_at) WHERE deleted_at IS NOT NULL;",
+            )
+        },
+    },
+    Migration {
+        version: 24,
+        description: "Showcase revisions",
+        apply: |tx| {
+            sql(
+                tx,
+                "
+CREATE TABLE IF NOT EXISTS showcase_revisions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    showcase_id TEXT NOT NULL REFERENCES showcases(id) ON DELETE CASCADE,
+    created_at INTEGER NOT NULL,
+    action TEXT NOT NULL,
+    state_json TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_showcase_revisions_showcase ON showcase_revisions (showcase_id, id);",
+            )
+        },
+    },
+    Migration {
+        version: 25,
+        description: "Slide titles and speaker notes",
+        apply: |tx| {
+            sql(
+                tx,
+                "
+ALTER TABLE showcase_images ADD COLUMN slide_title TEXT;
+ALTER TABLE showcase_images ADD COLUMN notes TEXT;",
+            )
+        },
+    },
+    Migration {
+        version: 26,
+        description: "Author consent per selected message",
+        apply: |tx| {
+            sql(
+                tx,
+                "
+CREATE TABLE IF NOT EXISTS showcase_consent (
+    showcase_id TEXT NOT NULL REFERENCES showcases(id) ON DELETE CASCADE,
+    message_id TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending',
+    credit_text TEXT,
+    updated_at INTEGER NOT NULL,
+    PRIMARY KEY (showcase_id, message_id)
+);",
+            )
+        },
+    },
+    Migration {
+        version: 27,
+        description: "Featured author notifications and opt-outs",
+        apply: |tx| {
+            sql(
+                tx,
+                "
+ALTER TABLE authors ADD COLUMN notify_opt_out INTEGER NOT NULL DEFAULT 0;
+CREATE TABLE IF NOT EXISTS showcase_notifications (
+    showcase_id TEXT NOT NULL REFERENCES showcases(id) ON DELETE CASCADE,
+    author_id TEXT NOT NULL,
+    method TEXT NOT NULL,
+    sent_at INTEGER NOT NULL,
+    PRIMARY KEY (showcase_id, author_id)
+);",
+            )
+        },
+    },
+    Migration {
+        version: 28,
+        description: "Google Slides link per showcase",
+        apply: |tx| sql(tx, "ALTER TABLE showcases ADD COLUMN google_slides_url TEXT;"),
+    },
+    Migration {
+        version: 29,
+        description: "Showcase status values",
+        apply: |tx| {
+            sql(
+                tx,
+                "
+UPDATE showcases SET status = 'Completed' WHERE status = 'Published';
+UPDATE showcases SET status = 'Draft'
+WHERE status NOT IN ('Draft', 'In Progress', 'Completed', 'Archived');",
+            )
+        },
+    },
+    Migration {
+        version: 30,
+        description: "Autosaved editor drafts",
+        apply: |tx| {
+            sql(
+                tx,
+                "
+CREATE TABLE IF NOT EXISTS showcase_drafts (
+    showcase_id TEXT NOT NULL REFERENCES showcases(id) ON DELETE CASCADE,
+    phase INTEGER NOT NULL,
+    draft_json TEXT NOT NULL,
+    updated_at INTEGER NOT NULL,
+    PRIMARY KEY (showcase_id, phase)
+);",
+            )
+        },
+    },
+];
+
+pub(crate) const CURRENT_SCHEMA_VERSION: i32 = MIGRATIONS.len() as i32;
+
+const SQL_CREATE_SCHEMA_VERSION_TABLE: &str = "
+CREATE TABLE IF NOT EXISTS schema_version (
+    version INTEGER PRIMARY KEY NOT NULL
+);";
+
+// Tables every versioned database has had since version 1.
+const BASELINE_TABLES: &[&str] = &["config", "showcases", "messages"];
+
+pub(crate) fn schema_version(conn: &Connection) -> Result<i32, String> {
+    let table_exists: bool = conn
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type='table' AND name='schema_version')",
+            [],
+            |row| row.get(0),
+        )
+        .context("Failed to check if schema_version table exists")?;
+
+    if !table_exists {
+        return Ok(0);
+    }
+
+    match conn.query_row("SELECT version FROM schema_version LIMIT 1", [], |row| {
+        row.get::<_, i32>(0)
+    }) {
+        Ok(version) => Ok(version),
+        Err(RusqliteError::QueryReturnedNoRows) => Ok(0),
+        Err(e) => Err(format!("Failed to get schema version: {}", e)),
+    }
+}
+
+fn set_schema_version(conn: &Connection, version: i32) -> Result<(), String> {
+    conn.execute("DELETE FROM schema_version", [])
+        .context("Failed to clear schema_version table")?;
+
+    conn.execute(
+        "INSERT INTO schema_version (version) VALUES (?1)",
+        [version],
+    )
+    .context(format!("Failed to update schema version to {}", version))?;
+
+    Ok(())
+}
+
+fn table_names(conn: &Connection) -> Result<Vec<String>, String> {
+    let mut stmt = conn
+        .prepare("SELECT name FROM sqlite_master WHERE type='table' AND name NOT LIKE 'sqlite_%' ORDER BY name")
+        .context("Failed to prepare query for existing tables")?;
+
+    let tables = stmt
+        .query_map([], |row| row.get::<_, String>(0))
+        .context("Failed to query existing tables")?
+        .collect::<Result<Vec<String>, _>>()
+        .context("Error processing table names")?;
+
+    Ok(tables)
+}
+
+// Checks everything that can be known before the first step runs, so a database that cannot be
+// migrated is refused untouched instead of failing halfway through.
+fn validate(conn: &Connection, from_version: i32) -> Result<(), String> {
+    for (index, migration) in MIGRATIONS.iter().enumerate() {
+        if migration.version != index as i32 + 1 {
+            return Err(format!(
+                "Migration list is out of order: step {} is labelled version {}.",
+                index + 1,
+                migration.version
+            ));
+        }
+    }
+
+    if from_version > CURRENT_SCHEMA_VERSION {
+        return Err(format!(
+            "Database schema version {} is newer than application version {}. Please update the application.",
+            from_version, CURRENT_SCHEMA_VERSION
+        ));
+    }
+    if from_version < 0 {
+        return Err(format!(
+            "Database reports an invalid schema version {}.",
+            from_version
+        ));
+    }
+
+    let tables = table_names(conn)?;
+    if from_version == 0 {
+        let unexpected: Vec<&String> = tables.iter().filter(|t| *t != "schema_version").collect();
+        if !unexpected.is_empty() {
+            return Err(format!(
+                "Database has tables ({:?}) but no schema version; it was not created by this application.",
+                unexpected
+            ));
+        }
+    } else {
+        for table in BASELINE_TABLES {
+            if !tables.iter().any(|t| t == table) {
+                return Err(format!(
+                    "Database claims schema version {} but has no '{}' table.",
+                    from_version, table
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+// Brings the database up to CURRENT_SCHEMA_VERSION. Returns the version it started from.
+pub(crate) fn migrate(conn: &mut Connection) -> Result<i32, String> {
+    let from_version = schema_version(conn)?;
+    validate(conn, from_version)?;
+    conn.execute(SQL_CREATE_SCHEMA_VERSION_TABLE, [])
+        .context("Failed to create schema_version table")?;
+
+    for migration in MIGRATIONS.iter().filter(|m| m.version > from_version) {
+        info!(
+            "Applying migration {}: {}",
+            migration.version, migration.description
+        );
+        let tx = conn
+            .transaction()
+            .context(format!("Failed to start migration {}", migration.version))?;
+        if let Err(e) = (migration.apply)(&tx) {
+            warn!(
+                "Migration {} failed, rolling back: {}",
+                migration.version, e
+            );
+            return Err(format!(
+                "Migration {} ({}) failed: {}",
+                migration.version, migration.description, e
+            ));
+        }
+        set_schema_version(&tx, migration.version)?;
+        tx.commit()
+            .context(format!("Failed to commit migration {}", migration.version))?;
+    }
+
+    Ok(from_version)
+}
+
+fn table_info(conn: &Connection, name: &str) -> Result<SchemaTable, String> {
+    let quoted = format!("\"{}\"", name.replace('"', "\"\""));
+    let mut stmt = conn
+        .prepare(&format!("PRAGMA table_info({})", quoted))
+        .context(format!("Failed to read columns of {}", name))?;
+    let columns = stmt
+        .query_map([], |row| {
+            let column: String = row.get(1)?;
+            let type_name: String = row.get(2)?;
+            Ok(format!("{} {}", column, type_name).trim_end().to_string())
+        })
+        .context(format!("Failed to read columns of {}", name))?
+        .collect::<Result<Vec<String>, _>>()
+        .context("Error processing column info")?;
+    let row_count: i64 = conn
+        .query_row(&format!("SELECT COUNT(*) FROM {}", quoted), [], |row| {
+            row.get(0)
+        })
+        .context(format!("Failed to count rows of {}", name))?;
+    Ok(SchemaTable {
+        name: name.to_string(),
+        columns,
+        row_count,
+    })
+}
+
+#[tauri::command]
+pub async fn get_schema_info(db_state: State<'_, DbConnection>) -> Result<SchemaInfo, AppError> {
+    db_state
+        .0
+        .run(move |conn| {
+            let version = schema_version(conn)?;
+            let migrations = MIGRATIONS
+                .iter()
+                .map(|m| SchemaMigration {
+                    version: m.version,
+                    description: m.description.to_string(),
+                    applied: m.version <= version,
+                })
+                .collect();
+            let tables = table_names(conn)?
+                .iter()
+                .map(|name| table_info(conn, name))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(SchemaInfo {
+                version,
+                latest_version: CURRENT_SCHEMA_VERSION,
+                migrations,
+                tables,
+            })
+        })
+        .await
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "kebab-case")]
@@ -36,17 +37,166 @@ pub struct SelectedMessage {
     pub author_avatar: Option<String>,
     pub message_content: String,
     pub selected_attachment_filename: String,
+    // Every chosen attachment in slide order; the first one mirrors selected_attachment_filename.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub selected_attachment_filenames: Vec<String>,
     pub timestamp: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reply_to: Option<ReplyContext>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author_avatar_path: Option<String>,
+}
+
+impl SelectedMessage {
+    // Selections saved before multi-attachment support only carry the single filename.
+    pub fn attachment_filenames(&self) -> Vec<String> {
+        if self.selected_attachment_filenames.is_empty() {
+            vec![self.selected_attachment_filename.clone()]
+        } else {
+            self.selected_attachment_filenames.clone()
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ShowcaseImage {
     pub message_id: String,
+    #[serde(default)]
+    pub attachment_index: usize, // position among the message's selected attachments
     pub sender: String,
     pub avatar: String,
     pub message: String,
     pub is_edited: bool,
     pub overlay: OverlaySettings,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credit: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slide_title: Option<String>,
+    // Speaker notes, exported into the deck's notes pane.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    // Stored slide order. Read back from the database; writes take the order from the call instead.
+    #[serde(default)]
+    pub sort_index: i64,
+}
+
+// The first attachment keeps the bare message ID so existing image files still resolve.
+pub fn slide_key(message_id: &str, attachment_index: usize) -> String {
+    if attachment_index == 0 {
+        message_id.to_string()
+    } else {
+        format!("{}_{}", message_id, attachment_index)
+    }
+}
+
+impl ShowcaseImage {
+    pub fn slide_key(&self) -> String {
+        slide_key(&self.message_id, self.attachment_index)
+    }
+}
+
+// One entry per image of a batch upload, in request order.
+#[derive(Debug, Serialize, Clone)]
+pub struct SlideUploadResult {
+    pub slide_key: String,
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AuthorCredit {
+    pub author_id: String,
+    pub display_name: Option<String>,
+    pub credit_template: Option<String>,
+    pub links: Vec<String>,
+    pub updated_at: i64,
+    #[serde(default)]
+    pub notify_opt_out: bool, // never DM'd by notify_featured_authors
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NotifyOutcome {
+    Sent,    // direct message
+    Replied, // DMs closed, replied to their featured message instead
+    OptedOut,
+    AlreadyNotified,
+    Skipped, // not a Discord user
+    Failed,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct AuthorNotifyResult {
+    pub author_id: String,
+    pub author_name: String,
+    pub outcome: NotifyOutcome,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct AuthorRefreshResult {
+    pub checked: usize,
+    pub updated: usize,
+    pub skipped: usize, // non-Discord author IDs
+    pub not_found: Vec<String>,
+    pub failed: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ShowcaseCredit {
+    pub message_id: String,
+    pub author_id: String,
+    pub author_name: String,
+    pub credit_text: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum ConsentStatus {
+    Pending,
+    Granted,
+    Declined,
+}
+
+impl ConsentStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConsentStatus::Pending => "pending",
+            ConsentStatus::Granted => "granted",
+            ConsentStatus::Declined => "declined",
+        }
+    }
+
+    // Unknown values read as pending, so a bad row never counts as permission.
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "granted" => ConsentStatus::Granted,
+            "declined" => ConsentStatus::Declined,
+            _ => ConsentStatus::Pending,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct MessageConsent {
+    pub message_id: String,
+    pub author_id: String,
+    pub author_name: String,
+    pub status: ConsentStatus,
+    pub credit_text: String, // the recorded credit, else the author's rendered credit
+    pub custom_credit: bool,
+    pub updated_at: Option<i64>,
+}
+
+// An author with at least one selected message they haven't agreed to.
+#[derive(Debug, Serialize, Clone)]
+pub struct UncreditedAuthor {
+    pub author_id: String,
+    pub author_name: String,
+    pub status: ConsentStatus, // declined when any message was declined, otherwise pending
+    pub message_ids: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -61,13 +211,208 @@ pub struct Showcase {
     pub selected_messages: Option<Vec<SelectedMessage>>,
     pub images: Option<Vec<ShowcaseImage>>,
     pub pptx_path: Option<String>,
+    pub rehearsal: Option<RehearsalRun>, // latest practice run, see save_showcase_rehearsal
+    #[serde(default)]
+    pub exports: Vec<ExportArtifact>, // oldest first
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<i64>, // set while the showcase is in the trash
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub google_slides_url: Option<String>, // last upload_showcase_to_google_slides result
+}
+
+// The selection and slides of a showcase as they were before a change.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ShowcaseRevisionState {
+    pub phase: i32,
+    #[serde(default)]
+    pub selected_messages: Vec<SelectedMessage>,
+    #[serde(default)]
+    pub images: Vec<ShowcaseImage>,
+}
+
+// Unsaved editor state of one phase, as the frontend sent it.
+#[derive(Debug, Serialize, Clone)]
+pub struct ShowcaseDraft {
+    pub showcase_id: String,
+    pub phase: i32,
+    pub draft_json: String,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ShowcaseRevision {
+    pub id: i64,
+    pub showcase_id: String,
+    pub created_at: i64,
+    pub action: String, // the change that replaced this state, e.g. "sort_images"
+    pub phase: i32,
+    pub message_count: usize,
+    pub slide_count: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExportArtifact {
+    pub path: String, // absolute, may point outside app data
+    pub format: String, // "pptx"
+    pub created_at: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub manifest: Option<ExportManifest>, // also written next to the file as <file>.manifest.json
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExportManifestFile {
+    pub path: String, // file name; slide images are relative to the showcase image folder
+    pub sha256: String,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExportManifest {
+    pub showcase_id: String,
+    pub showcase_title: String,
+    pub app_version: String,
+    pub created_at: i64,
+    pub artifact: ExportManifestFile,
+    pub files: Vec<ExportManifestFile>, // slide images the deck was built from
+    pub settings: serde_json::Value, // config values that change how slides render
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ExportVerification {
+    pub sha256: String,
+    pub size_bytes: u64,
+    pub showcase_id: Option<String>, // None when no recorded export has this checksum
+    pub artifact: Option<ExportArtifact>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SlideTiming {
+    pub message_id: String,
+    #[serde(default)]
+    pub attachment_index: usize,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RehearsalRun {
+    pub started_at: i64,
+    pub total_ms: u64,
+    pub slides: Vec<SlideTiming>, // in showcase order; revisited slides are summed
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SlideRenderInfo {
+    pub message_id: String,
+    pub attachment_index: usize,
+    pub credit_text: String,
+    pub caption: String, // message text after redaction rules
+    pub reply_author: Option<String>,
+    pub reply_caption: Option<String>, // replied-to message text after redaction rules
+    pub posted_at: i64,
+    pub posted_at_formatted: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ShowcaseRenderInfo {
+    pub title: String,
+    pub locale: String,
+    pub created_at_formatted: String,
+    pub subtitle: String,
+    pub artist_count_formatted: String,
+    pub slide_count_formatted: String,
+    pub slides: Vec<SlideRenderInfo>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct DiffAuthor {
+    pub author_id: String,
+    pub author_name: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ShowcaseFieldChange {
+    pub field: String,
+    pub message_id: Option<String>,
+    pub value_a: Option<String>,
+    pub value_b: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ShowcaseDiff {
+    pub showcase_a: String,
+    pub showcase_b: String,
+    pub common_message_ids: Vec<String>,
+    pub messages_only_in_a: Vec<String>,
+    pub messages_only_in_b: Vec<String>,
+    pub common_authors: Vec<DiffAuthor>,
+    pub authors_only_in_a: Vec<DiffAuthor>,
+    pub authors_only_in_b: Vec<DiffAuthor>,
+    pub setting_changes: Vec<ShowcaseFieldChange>,
+}
+
+// Stored in showcases.status under its display name.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ShowcaseStatus {
+    Draft,
+    #[serde(rename = "In Progress")]
+    InProgress,
+    Completed,
+    Archived, // only through archive_showcase, which also packs the slide renders
+}
+
+impl ShowcaseStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ShowcaseStatus::Draft => "Draft",
+            ShowcaseStatus::InProgress => "In Progress",
+            ShowcaseStatus::Completed => "Completed",
+            ShowcaseStatus::Archived => "Archived",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        [
+            ShowcaseStatus::Draft,
+            ShowcaseStatus::InProgress,
+            ShowcaseStatus::Completed,
+            ShowcaseStatus::Archived,
+        ]
+        .into_iter()
+        .find(|status| status.as_str().eq_ignore_ascii_case(value))
+    }
+
+    // Where a phase change leaves the status: a generated deck completes the showcase, and
+    // any work past selection takes a draft (or a completed showcase) back to in progress.
+    pub fn after_phase(self, phase: i32) -> Self {
+        match (self, phase) {
+            (_, 4) => ShowcaseStatus::Completed,
+            (ShowcaseStatus::Draft, 1) => ShowcaseStatus::Draft,
+            _ => ShowcaseStatus::InProgress,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
 pub struct UpdateShowcasePayload {
     pub title: Option<String>,
     pub description: Option<String>,
-    pub status: Option<String>,
+    pub status: Option<String>, // a ShowcaseStatus name other than Archived
+}
+
+// Per-slide changes; fields left out keep their current value.
+#[derive(Debug, Deserialize, Default)]
+pub struct ShowcaseImagePatch {
+    pub position: Option<OverlayPosition>,
+    pub style: Option<OverlayStyle>,
+    #[serde(rename = "showAvatar")]
+    pub show_avatar: Option<bool>,
+    pub caption: Option<String>, // Replaces the message text shown on the slide
+    // An empty string clears the title or notes.
+    pub slide_title: Option<String>,
+    pub notes: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -80,6 +425,121 @@ pub struct FirstSlideSettings {
     pub show_author: bool,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct CleanupScheduleSettings {
+    pub enabled: bool,
+    #[serde(rename = "intervalDays")]
+    pub interval_days: u32,
+    #[serde(rename = "onlyWhenIdle")]
+    pub only_when_idle: bool,
+    #[serde(rename = "minCacheSizeMb", skip_serializing_if = "Option::is_none")]
+    pub min_cache_size_mb: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ThumbnailSettings {
+    #[serde(rename = "maxDimension")]
+    pub max_dimension: u32, // px, longest edge
+    pub quality: u8, // JPEG quality 1-100
+    // true: generate while indexing; false: generate on first view.
+    pub pregenerate: bool,
+}
+
+impl Default for ThumbnailSettings {
+    fn default() -> Self {
+        ThumbnailSettings {
+            max_dimension: 320,
+            quality: 75,
+            pregenerate: false,
+        }
+    }
+}
+
+// Checked against Discord's metadata before anything is downloaded, to keep emotes and
+// reaction images out of the cache. 0 / None disables a limit; unknown sizes always pass.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ImageSizeFilter {
+    #[serde(rename = "minWidth", default)]
+    pub min_width: u32,
+    #[serde(rename = "minHeight", default)]
+    pub min_height: u32,
+    #[serde(rename = "maxFileSizeBytes", default, skip_serializing_if = "Option::is_none")]
+    pub max_file_size_bytes: Option<u64>,
+}
+
+// Keeps one author from flooding the candidate pool; applied at the end of each indexing run.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct AuthorCapSettings {
+    #[serde(rename = "maxImages", default)]
+    pub max_images: u32, // 0 disables the cap
+    #[serde(default)]
+    pub period: String, // "day" | "week"
+    #[serde(default)]
+    pub keep: String, // "most_reacted" | "newest"
+}
+
+// Used for every HTTP client the app builds: cache downloads, Discord REST and OAuth, update checks.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct NetworkSettings {
+    #[serde(rename = "proxyUrl", default, skip_serializing_if = "Option::is_none")]
+    pub proxy_url: Option<String>, // http://, https:// or socks5://, credentials may be inline
+    #[serde(rename = "caCertPath", default, skip_serializing_if = "Option::is_none")]
+    pub ca_cert_path: Option<String>, // PEM file trusted in addition to the system roots
+}
+
+// Applied to message text before it reaches overlays and exports.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct RedactionRules {
+    #[serde(rename = "stripLinks", default)]
+    pub strip_links: bool,
+    #[serde(rename = "stripEmails", default)]
+    pub strip_emails: bool,
+    #[serde(rename = "stripPhoneNumbers", default)]
+    pub strip_phone_numbers: bool,
+    #[serde(rename = "maxCaptionLength", skip_serializing_if = "Option::is_none")]
+    pub max_caption_length: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct AuthorFilter {
+    #[serde(rename = "skipBotAuthors", default)]
+    pub skip_bot_authors: bool,
+    #[serde(rename = "allowedAuthorIds", default)]
+    pub allowed_author_ids: Vec<String>,
+    #[serde(rename = "blockedAuthorIds", default)]
+    pub blocked_author_ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct AuthorFilterSettings {
+    #[serde(default)]
+    pub global: AuthorFilter,
+    // Keyed by channel ID, replaces the global filter for that channel.
+    #[serde(rename = "channelOverrides", default)]
+    pub channel_overrides: HashMap<String, AuthorFilter>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ContentFilterSettings {
+    // Plain keywords unless use_regex is set; an empty include list lets everything through.
+    #[serde(rename = "includePatterns", default)]
+    pub include_patterns: Vec<String>,
+    #[serde(rename = "excludePatterns", default)]
+    pub exclude_patterns: Vec<String>,
+    #[serde(rename = "useRegex", default)]
+    pub use_regex: bool,
+    #[serde(rename = "caseSensitive", default)]
+    pub case_sensitive: bool,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct ContentFilterPreview {
+    pub total: usize,
+    pub matching: usize,
+    pub missing_include: usize, // no include pattern matched
+    pub matched_exclude: usize, // matched an include pattern but also an exclude one
+}
+
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
 
 pub struct AppConfig {
@@ -93,6 +553,64 @@ pub struct AppConfig {
     pub first_slide_settings: Option<FirstSlideSettings>,
     #[serde(rename = "autoUpdateEnabled", skip_serializing_if = "Option::is_none")]
     pub auto_update_enabled: Option<bool>,
+    #[serde(rename = "indexingStartDate", skip_serializing_if = "Option::is_none")]
+    pub indexing_start_date: Option<String>, // YYYY-MM-DD
+    #[serde(rename = "indexingWindowDays", skip_serializing_if = "Option::is_none")]
+    pub indexing_window_days: Option<u32>,
+    #[serde(rename = "encryptImageCache", skip_serializing_if = "Option::is_none")]
+    pub encrypt_image_cache: Option<bool>,
+    #[serde(rename = "cleanupSchedule", skip_serializing_if = "Option::is_none")]
+    pub cleanup_schedule: Option<CleanupScheduleSettings>,
+    #[serde(rename = "downloadConcurrency", skip_serializing_if = "Option::is_none")]
+    pub download_concurrency: Option<u32>,
+    #[serde(rename = "authorFilters", skip_serializing_if = "Option::is_none")]
+    pub author_filters: Option<AuthorFilterSettings>,
+    #[serde(rename = "locale", skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    #[serde(rename = "liveIndexingEnabled", skip_serializing_if = "Option::is_none")]
+    pub live_indexing_enabled: Option<bool>,
+    #[serde(rename = "includeAnimatedAttachments", skip_serializing_if = "Option::is_none")]
+    pub include_animated_attachments: Option<bool>,
+    #[serde(rename = "exportMemoryMode", skip_serializing_if = "Option::is_none")]
+    pub export_memory_mode: Option<String>, // "auto" | "standard" | "low_memory"
+    #[serde(rename = "indexLinkedImages", skip_serializing_if = "Option::is_none")]
+    pub index_linked_images: Option<bool>,
+    #[serde(rename = "thumbnailSettings", skip_serializing_if = "Option::is_none")]
+    pub thumbnail_settings: Option<ThumbnailSettings>,
+    #[serde(rename = "redactionRules", skip_serializing_if = "Option::is_none")]
+    pub redaction_rules: Option<RedactionRules>,
+    #[serde(rename = "legacyPptxCompatibility", skip_serializing_if = "Option::is_none")]
+    pub legacy_pptx_compatibility: Option<bool>,
+    #[serde(rename = "imageSizeFilter", skip_serializing_if = "Option::is_none")]
+    pub image_size_filter: Option<ImageSizeFilter>,
+    #[serde(rename = "contentFilters", skip_serializing_if = "Option::is_none")]
+    pub content_filters: Option<ContentFilterSettings>,
+    #[serde(rename = "discordOAuthClientId", skip_serializing_if = "Option::is_none")]
+    pub discord_oauth_client_id: Option<String>, // application used for "Sign in with Discord"
+    #[serde(rename = "googleOAuthClientId", skip_serializing_if = "Option::is_none")]
+    pub google_oauth_client_id: Option<String>, // desktop client for the Google Slides export
+    #[serde(rename = "submissionBotEnabled", skip_serializing_if = "Option::is_none")]
+    pub submission_bot_enabled: Option<bool>,
+    #[serde(rename = "excludeSensitiveContent", skip_serializing_if = "Option::is_none")]
+    pub exclude_sensitive_content: Option<bool>, // skip spoilered attachments and NSFW channels
+    #[serde(rename = "exportDirectory", skip_serializing_if = "Option::is_none")]
+    pub export_directory: Option<String>, // finished exports are copied here
+    #[serde(rename = "networkSettings", skip_serializing_if = "Option::is_none")]
+    pub network_settings: Option<NetworkSettings>,
+    #[serde(rename = "channelNamePatterns", skip_serializing_if = "Option::is_none")]
+    pub channel_name_patterns: Option<Vec<String>>, // glob-style, e.g. "*showcase*"
+    #[serde(rename = "autoDiscoverChannels", skip_serializing_if = "Option::is_none")]
+    pub auto_discover_channels: Option<bool>, // match the patterns again at the start of every run
+    #[serde(rename = "authorCap", skip_serializing_if = "Option::is_none")]
+    pub author_cap: Option<AuthorCapSettings>,
+    #[serde(rename = "retentionDays", skip_serializing_if = "Option::is_none")]
+    pub retention_days: Option<u32>, // default age cutoff for clean_old_data, 30 when unset
+    #[serde(rename = "compactAfterCleanup", skip_serializing_if = "Option::is_none")]
+    pub compact_after_cleanup: Option<bool>, // run compact_database after cleanups that removed rows
+    #[serde(rename = "trashRetentionDays", skip_serializing_if = "Option::is_none")]
+    pub trash_retention_days: Option<u32>, // trashed showcases are purged after this, 30 when unset
+    #[serde(rename = "presentationTemplate", skip_serializing_if = "Option::is_none")]
+    pub presentation_template: Option<String>, // stored copy of the .pptx whose masters exports use
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -105,6 +623,39 @@ pub struct AttachmentInfo {
     pub height: Option<u64>,
 }
 
+#[derive(Debug, Serialize, Clone)]
+pub struct ChannelIndexStatus {
+    pub channel_id: String,
+    pub indexed_message_count: i64,
+    pub newest_message_timestamp: Option<i64>,
+    pub last_run_at: Option<i64>,
+    pub last_run_result: Option<String>, // "completed" | "cancelled" | "failed" | "interrupted"
+    pub last_run_messages_saved: Option<i64>,
+    pub last_run_error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ConfigHistoryEntry {
+    pub id: i64,
+    pub changed_at: i64, // milliseconds; pass to revert_config_to to undo from here on
+    pub key: String,
+    pub old_value: Option<String>, // None when the key did not exist
+    pub new_value: Option<String>, // None when the key was removed
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ChannelIndexingStats {
+    pub channel_id: String,
+    pub selected: bool,
+    pub message_count: i64,
+    pub image_count: i64, // images and GIFs
+    pub video_count: i64,
+    pub unique_authors: i64,
+    pub oldest_message_timestamp: Option<i64>,
+    pub newest_message_timestamp: Option<i64>,
+    pub cache_size_bytes: u64, // cached originals only, thumbnails are not counted
+}
+
 #[derive(Debug, Serialize, Clone)]
 pub struct IndexedMessage {
     pub message_id: String,
@@ -114,8 +665,224 @@ pub struct IndexedMessage {
     pub author_avatar: Option<String>,
     pub message_content: String,
     pub attachments: Vec<String>,
+    pub attachment_types: Vec<String>, // "image" | "gif" | "video", parallel to `attachments`
+    pub attachment_sources: Vec<String>, // "attachment" | "sticker" | "embed" | "link", parallel to `attachments`
     pub timestamp: i64,
     pub is_used: bool,
+    pub reaction_count: i64,
+    pub reactions: Vec<ReactionSummary>,
+    pub deleted: bool, // removed on Discord, found by resync_indexed_messages
+    pub reply_to: Option<ReplyContext>,
+    pub author_avatar_path: Option<String>, // relative to the image cache, see avatars::avatar_cache_path
+    pub is_submission: bool, // sent through the /submit command rather than indexed from a channel
+    pub is_spoiler: bool, // has a SPOILER_ attachment
+    pub is_nsfw_channel: bool,
+    pub review_decision: Option<String>, // "accept" | "reject" | "maybe", None until triaged
+    pub author_type: String, // "user" | "webhook" | "bot"
+    pub is_favorite: bool, // shortlisted with set_message_favorite, independent of is_used
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct MessageSearchFilters {
+    #[serde(rename = "channelIds", default)]
+    pub channel_ids: Vec<String>,
+    #[serde(rename = "authorIds", default)]
+    pub author_ids: Vec<String>,
+    #[serde(rename = "fromDate", default)]
+    pub from_date: Option<i64>, // Unix seconds, inclusive
+    #[serde(rename = "toDate", default)]
+    pub to_date: Option<i64>, // Unix seconds, exclusive
+    #[serde(rename = "includeDeleted", default)]
+    pub include_deleted: bool,
+    #[serde(rename = "submissionsOnly", default)]
+    pub submissions_only: bool,
+    #[serde(rename = "favoritesOnly", default)]
+    pub favorites_only: bool,
+    #[serde(rename = "sortBy", default)]
+    pub sort_by: Option<String>, // "relevance" (default) | "timestamp" | "reactions"
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+// One page of query_indexed_messages. The search filters apply as-is, except that sort_by is
+// "timestamp" (default) or "reactions" since there is no relevance without a search term.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct MessageQuery {
+    #[serde(flatten)]
+    pub filters: MessageSearchFilters,
+    #[serde(rename = "isUsed", default)]
+    pub is_used: Option<bool>, // None returns both featured and unfeatured messages
+    #[serde(rename = "sortOrder", default)]
+    pub sort_order: Option<String>, // "desc" (default) | "asc"
+    #[serde(default)]
+    pub page: Option<usize>, // 1-based
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct MessagePage {
+    pub messages: Vec<IndexedMessage>,
+    pub total: usize, // Matching messages across all pages
+    pub page: usize,
+    pub limit: usize,
+}
+
+// Filters and paging for query_showcases. Trashed showcases are never included.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct ShowcaseQuery {
+    #[serde(default)]
+    pub search: Option<String>, // matched against title and description
+    #[serde(default)]
+    pub statuses: Vec<String>,
+    #[serde(default)]
+    pub phases: Vec<i32>,
+    #[serde(rename = "createdFrom", default)]
+    pub created_from: Option<i64>, // Unix seconds, inclusive
+    #[serde(rename = "createdTo", default)]
+    pub created_to: Option<i64>, // Unix seconds, exclusive
+    #[serde(rename = "modifiedFrom", default)]
+    pub modified_from: Option<i64>,
+    #[serde(rename = "modifiedTo", default)]
+    pub modified_to: Option<i64>,
+    #[serde(rename = "sortBy", default)]
+    pub sort_by: Option<String>, // "last_modified" (default) | "created_at" | "title"
+    #[serde(rename = "sortOrder", default)]
+    pub sort_order: Option<String>, // "desc" (default) | "asc"
+    #[serde(default)]
+    pub page: Option<usize>, // 1-based
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+// A dashboard row: the showcase without its selection and slides.
+#[derive(Debug, Serialize, Clone)]
+pub struct ShowcaseSummary {
+    pub id: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub status: String,
+    pub created_at: i64,
+    pub last_modified: i64,
+    pub phase: i32,
+    pub message_count: usize,
+    pub slide_count: usize,
+    pub pptx_path: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ShowcasePage {
+    pub showcases: Vec<ShowcaseSummary>,
+    pub total: usize, // Matching showcases across all pages
+    pub page: usize,
+    pub limit: usize,
+}
+
+// Both maps are keyed by the requested relative path.
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct CachedImageBatch {
+    pub images: HashMap<String, String>, // data URIs
+    pub errors: HashMap<String, String>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct PptxOptions {
+    // Falls back to the legacyPptxCompatibility setting.
+    #[serde(rename = "legacyCompatibility", default)]
+    pub legacy_compatibility: Option<bool>,
+    #[serde(rename = "outputDir", default)]
+    pub output_dir: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct VideoSettings {
+    // Seconds each slide stays fully visible; 5 by default.
+    #[serde(rename = "slideDuration", default)]
+    pub slide_duration: Option<f64>,
+    #[serde(default)]
+    pub transition: Option<String>, // "none" | "fade" (default)
+    #[serde(rename = "transitionDuration", default)]
+    pub transition_duration: Option<f64>,
+    #[serde(default)]
+    pub resolution: Option<String>, // same values as the slide image export
+    // Looped or cut to the length of the video, and faded out at the end.
+    #[serde(rename = "musicPath", default)]
+    pub music_path: Option<String>,
+    #[serde(rename = "outputDir", default)]
+    pub output_dir: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct VideoExport {
+    pub path: String,
+    pub slides: usize,
+    pub skipped: Vec<String>, // slide keys that could not be rendered
+    pub duration: f64,        // seconds
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct HtmlExport {
+    pub path: String,         // the page; its assets folder sits next to it unless inlined
+    pub slides: usize,
+    pub skipped: Vec<String>, // slide keys that could not be rendered
+    pub inline_images: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ShowcaseArchiveExport {
+    pub path: String,
+    pub files: usize,         // cached attachments and avatars
+    pub slides: usize,        // uploaded slide renders
+    pub missing: Vec<String>, // cache paths that were no longer on disk
+}
+
+// Slide renders packed by archive_showcase.
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct ShowcaseImagePack {
+    pub files: usize,
+    pub original_bytes: u64,
+    pub packed_bytes: u64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ShowcaseArchiveImport {
+    pub showcase_id: String, // always a new ID
+    pub title: String,
+    pub files_restored: usize, // cached files that were not already present
+    pub slides_restored: usize,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SlideImageExport {
+    pub folder: String,
+    pub files: Vec<String>,   // file names inside `folder`, in slide order
+    pub skipped: Vec<String>, // slide keys that could not be rendered
+    pub format: String,       // "png" | "jpg"
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct MessageExportResult {
+    pub path: String,
+    pub format: String, // "csv" | "json"
+    pub rows: usize,
+    pub size_bytes: u64,
+}
+
+// The message a submission replied to, e.g. the theme announcement it answers.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReplyContext {
+    pub message_id: String,
+    pub author_id: String,
+    pub author_name: String,
+    pub content: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReactionSummary {
+    pub emoji: String, // unicode emoji, or <:name:id> for custom ones
+    pub count: u64,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -130,11 +897,126 @@ pub struct StorageUsage {
     pub cache_file_count: u64,
     pub oldest_message_date: Option<i64>,
     pub newest_message_date: Option<i64>,
+    pub showcases: Vec<ShowcaseStorage>, // largest first
+    pub channels: Vec<ChannelStorage>,   // largest cache first
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Clone)]
+pub struct ShowcaseStorage {
+    pub showcase_id: String,
+    pub title: String,
+    pub image_dir_size_bytes: u64,
+    pub presentation_size_bytes: u64,
+    pub message_count: usize, // selected messages
+}
+
+// Cached attachments of a channel's indexed messages.
+#[derive(Debug, Serialize, Clone)]
+pub struct ChannelStorage {
+    pub channel_id: String,
+    pub message_count: i64,
+    pub cache_file_count: u64,
+    pub cache_size_bytes: u64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SchemaMigration {
+    pub version: i32,
+    pub description: String,
+    pub applied: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SchemaTable {
+    pub name: String,
+    pub columns: Vec<String>,
+    pub row_count: i64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SchemaInfo {
+    pub version: i32,        // what the open database is at
+    pub latest_version: i32, // what this build migrates to
+    pub migrations: Vec<SchemaMigration>,
+    pub tables: Vec<SchemaTable>,
+}
+
+// A row whose JSON column does not decode into the type the app reads it as.
+#[derive(Debug, Serialize, Clone)]
+pub struct CorruptRow {
+    pub table: String,
+    pub row_key: String, // showcase id or message id
+    pub column: String,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct MissingCacheFile {
+    pub message_id: String,
+    pub relative_path: String,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct DatabaseHealthReport {
+    pub integrity_errors: Vec<String>, // empty when PRAGMA integrity_check reports "ok"
+    pub foreign_key_violations: Vec<String>,
+    pub corrupt_rows: Vec<CorruptRow>,
+    pub missing_files: Vec<MissingCacheFile>,
+    pub quarantined: usize, // rows moved to quarantined_rows by this run
+}
+
+#[derive(Debug, Serialize, Clone)]
 pub struct CleanupStats {
-    pub messages_deleted: usize,
+    pub messages_deleted: usize, // for a dry run: would be deleted
     pub files_deleted: usize,
     pub skipped_used_messages: usize,
+    pub retention_days: u32,
+    pub dry_run: bool,
+    pub sample_messages: Vec<IndexedMessage>, // oldest affected messages, dry runs only
+    pub compaction: Option<CompactResult>, // set when compactAfterCleanup ran
+}
+
+// Unix seconds; `from` is inclusive, `to` exclusive. Either end may be open.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct DateRange {
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct MessageDeletionResult {
+    pub messages_deleted: usize,
+    pub files_deleted: usize,
+    pub skipped_used_messages: usize, // selected by a showcase, kept
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct CacheClearResult {
+    pub files_deleted: usize,
+    pub bytes_freed: u64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct CompactResult {
+    pub size_before_bytes: u64,
+    pub size_after_bytes: u64,
+    pub wal_size_before_bytes: u64,
+    pub wal_size_after_bytes: u64,
+    pub duration_ms: u64,
+}
+
+// Emitted as "compact-progress" before each step of compact_database.
+#[derive(Debug, Serialize, Clone)]
+pub struct CompactProgress {
+    pub step: usize,
+    pub total_steps: usize,
+    pub message: String,
+}
+
+// Emitted as "cleanup-progress" after each chunk of deleted messages.
+#[derive(Debug, Serialize, Clone)]
+pub struct CleanupProgress {
+    pub processed: usize,
+    pub total: usize,
+    pub files_deleted: usize,
 }
@@ -0,0 +1,116 @@
+use serenity::http::{Http, HttpBuilder};
+use std::fs;
+use std::time::Duration;
+
+use crate::error::ResultExt;
+use crate::models::NetworkSettings;
+use crate::sqlite_manager::{retrieve_config, DbConnection};
+
+fn proxy_url(settings: &NetworkSettings) -> Option<&str> {
+    settings.proxy_url.as_deref().map(str::trim).filter(|u| !u.is_empty())
+}
+
+fn read_ca_bundle(settings: &NetworkSettings) -> Result<Option<(&str, Vec<u8>)>, String> {
+    let Some(ca_path) = settings.ca_cert_path.as_deref().map(str::trim).filter(|p| !p.is_empty()) else {
+        return Ok(None);
+    };
+    let pem = fs::read(ca_path)
+        .context(format!("Failed to read CA certificate '{}'", ca_path))?;
+    Ok(Some((ca_path, pem)))
+}
+
+// Every reqwest client the app builds goes through here so the proxy and extra CA apply to
+// downloads, OAuth and update checks alike. Discord REST calls go through `discord_http`.
+pub(crate) fn http_client(
+    settings: &NetworkSettings,
+    timeout: Option<Duration>,
+) -> Result<reqwest::Client, String> {
+    let mut builder = reqwest::Client::builder();
+    if let Some(timeout) = timeout {
+        builder = builder.timeout(timeout);
+    }
+
+    if let Some(proxy_url) = proxy_url(settings) {
+        // reqwest::Proxy::all accepts http://, https://, socks5:// and socks5h:// URLs.
+        let proxy = reqwest::Proxy::all(proxy_url)
+            .context(format!("Invalid proxy URL '{}'", proxy_url))?;
+        builder = builder.proxy(proxy);
+    }
+
+    if let Some((ca_path, pem)) = read_ca_bundle(settings)? {
+        let certificates = reqwest::Certificate::from_pem_bundle(&pem)
+            .context(format!("Invalid CA certificate '{}'", ca_path))?;
+        for certificate in certificates {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+
+    builder
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))
+}
+
+// Serenity 0.12 is built on reqwest 0.11, so its client is configured separately from the
+// same settings.
+fn serenity_client(settings: &NetworkSettings) -> Result<serenity_reqwest::Client, String> {
+    let mut builder = serenity_reqwest::Client::builder().use_rustls_tls();
+
+    if let Some(proxy_url) = proxy_url(settings) {
+        let proxy = serenity_reqwest::Proxy::all(proxy_url)
+            .map_err(|e| format!("Invalid proxy URL '{}': {}", proxy_url, e))?;
+        builder = builder.proxy(proxy);
+    }
+
+    if let Some((ca_path, pem)) = read_ca_bundle(settings)? {
+        let certificates = serenity_reqwest::Certificate::from_pem_bundle(&pem)
+            .map_err(|e| format!("Invalid CA certificate '{}': {}", ca_path, e))?;
+        for certificate in certificates {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+
+    builder
+        .build()
+        .map_err(|e| format!("Failed to build Discord HTTP client: {}", e))
+}
+
+// Every Serenity REST client is built here. `token` is a bot token (with or without "Bot ")
+// or a "Bearer " OAuth token, as for `Http::new`.
+pub(crate) fn discord_http(settings: &NetworkSettings, token: &str) -> Result<Http, String> {
+    Ok(HttpBuilder::new(token)
+        .client(serenity_client(settings)?)
+        .build())
+}
+
+pub(crate) async fn configured_discord_http(
+    db_state: &DbConnection,
+    token: &str,
+) -> Result<Http, String> {
+    let settings = db_state
+        .0
+        .run(|conn| Ok(retrieve_config(conn)?.network_settings.unwrap_or_default()))
+        .await?;
+    discord_http(&settings, token)
+}
+
+// Serenity opens the gateway websocket itself, with no way to route it through a proxy, so
+// gateway features are refused rather than connecting around it.
+pub(crate) fn ensure_gateway_allowed(settings: &NetworkSettings) -> Result<(), String> {
+    match proxy_url(settings) {
+        Some(_) => Err("The Discord gateway cannot be reached through the configured proxy. \
+            Clear the proxy in the network settings to use live indexing or the submission bot."
+            .to_string()),
+        None => Ok(()),
+    }
+}
+
+pub(crate) async fn configured_http_client(
+    db_state: &DbConnection,
+    timeout: Option<Duration>,
+) -> Result<reqwest::Client, String> {
+    let settings = db_state
+        .0
+        .run(|conn| Ok(retrieve_config(conn)?.network_settings.unwrap_or_default()))
+        .await?;
+    http_client(&settings, timeout)
+}
@@ -0,0 +1,730 @@
+use std::fs;
+use std::io::{Seek, Write};
+use std::path::Path;
+use tauri::{AppHandle, State};
+use zip::write::{SimpleFileOptions, ZipWriter};
+use zip::CompressionMethod;
+
+use crate::author_manager::resolve_showcase_credits;
+use crate::error::{AppError, ResultExt};
+use crate::export_pipeline::render_legacy_slide_image;
+use crate::models::PptxOptions;
+use crate::pptx_template::{load_template_file, PptxTemplate};
+use crate::redaction::redact_text;
+use crate::showcase_manager::{
+    get_showcase_image_dir, get_showcase_presentation_dir, load_showcase, pptx_filename,
+    pptx_part_path, register_pptx_export,
+};
+use crate::slide_render::{
+    encode_slide, plan_slides, render_credits_slide, render_slide, unique_credits, SlidePlan,
+    DEFAULT_RESOLUTION,
+};
+use crate::spans::Span;
+use crate::sqlite_manager::{get_image_base_dir, retrieve_config, DbConnection};
+use crate::{log_info as info, log_warn as warn};
+
+// LAYOUT_16x9 of the old pptxgenjs generator: 10in x 5.625in.
+const SLIDE_WIDTH_EMU: u64 = 9_144_000;
+const SLIDE_HEIGHT_EMU: u64 = 5_143_500;
+// Portrait letter pages for the notes view.
+const NOTES_WIDTH_EMU: u64 = 6_858_000;
+const NOTES_HEIGHT_EMU: u64 = 9_144_000;
+// Named apart from the slide themes so it can't clash with a template's.
+const NOTES_THEME: &str = "theme/notesTheme1.xml";
+
+// The masters, theme and layout slides use: the built-in blank design or a template's.
+struct Design<'a> {
+    masters: Vec<(u64, String)>, // id, target relative to ppt/
+    theme: String,
+    layout: String,
+    slide_size: (u64, u64),
+    template: Option<&'a PptxTemplate>,
+}
+
+impl<'a> Design<'a> {
+    fn new(template: Option<&'a PptxTemplate>) -> Self {
+        match template {
+            Some(template) => Design {
+                masters: template
+                    .masters
+                    .iter()
+                    .map(|m| (m.id, m.target.clone()))
+                    .collect(),
+                theme: template.theme.clone(),
+                layout: template.layout.clone(),
+                slide_size: template
+                    .slide_size
+                    .unwrap_or((SLIDE_WIDTH_EMU, SLIDE_HEIGHT_EMU)),
+                template: Some(template),
+            },
+            None => Design {
+                masters: vec![(2147483648, "slideMasters/slideMaster1.xml".to_string())],
+                theme: "theme/theme1.xml".to_string(),
+                layout: "slideLayouts/slideLayout1.xml".to_string(),
+                slide_size: (SLIDE_WIDTH_EMU, SLIDE_HEIGHT_EMU),
+                template: None,
+            },
+        }
+    }
+
+    fn fill(&self, legacy: bool) -> SlideFill<'a> {
+        match self.template.and_then(|t| t.picture_placeholder.as_deref()) {
+            Some(placeholder) => SlideFill::Placeholder(placeholder),
+            None if legacy => SlideFill::FullSlide,
+            None => SlideFill::Background,
+        }
+    }
+}
+
+pub(crate) struct SlideImage {
+    pub bytes: Vec<u8>,
+    pub extension: String,
+    pub title: Option<String>,
+    pub notes: Option<String>,
+}
+
+impl SlideImage {
+    fn has_notes(&self) -> bool {
+        self.title.is_some() || self.notes.is_some()
+    }
+}
+
+fn media_content_type(extension: &str) -> &'static str {
+    match extension {
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        _ => "image/png",
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+// Draws every slide with the backend renderer, in slide order. Legacy decks get flattened PNGs
+// within the old size limit; others JPEGs. A slide that can't be drawn is skipped, as the
+// frontend generator did.
+pub(crate) fn load_slide_images(
+    plans: &[SlidePlan],
+    resolution: (u32, u32),
+    legacy: bool,
+) -> Vec<SlideImage> {
+    plans
+        .iter()
+        .filter_map(|plan| {
+            let extension = if legacy { "png" } else { "jpg" };
+            let bytes = render_slide(plan, resolution.0, resolution.1)
+                .and_then(|canvas| encode_slide(canvas, extension))
+                .and_then(|bytes| {
+                    if legacy {
+                        render_legacy_slide_image(&bytes)
+                    } else {
+                        Ok(bytes)
+                    }
+                });
+            let overlay = plan.overlay.as_ref();
+            bytes
+                .map(|bytes| SlideImage {
+                    bytes,
+                    extension: extension.to_string(),
+                    title: overlay.and_then(|image| image.slide_title.clone()),
+                    notes: overlay.and_then(|image| image.notes.clone()),
+                })
+                .map_err(|e| warn!("Skipping slide {}: {}", plan.slide_key, e))
+                .ok()
+        })
+        .collect()
+}
+
+// The closing slide naming every credited author, with the same list in its notes. None when
+// nobody is credited.
+pub(crate) fn credits_slide_image(
+    credits: &[String],
+    (width, height): (u32, u32),
+    legacy: bool,
+) -> Option<SlideImage> {
+    if credits.is_empty() {
+        return None;
+    }
+    let extension = if legacy { "png" } else { "jpg" };
+    encode_slide(render_credits_slide(credits, width, height), extension)
+        .and_then(|bytes| {
+            if legacy {
+                render_legacy_slide_image(&bytes)
+            } else {
+                Ok(bytes)
+            }
+        })
+        .map(|bytes| SlideImage {
+            bytes,
+            extension: extension.to_string(),
+            title: Some("Credits".to_string()),
+            notes: Some(credits.join("\n")),
+        })
+        .map_err(|e| warn!("Skipping the credits slide: {}", e))
+        .ok()
+}
+
+fn content_types_xml(slides: &[SlideImage], design: &Design) -> String {
+    let mut extensions: Vec<&str> = slides.iter().map(|s| s.extension.as_str()).collect();
+    extensions.sort_unstable();
+    extensions.dedup();
+    let mut defaults: String = extensions
+        .iter()
+        .map(|ext| {
+            format!(
+                r#"<Default Extension="{}" ContentType="{}"/>"#,
+                ext,
+                media_content_type(ext)
+            )
+        })
+        .collect();
+    let mut overrides = String::new();
+    match design.template {
+        Some(template) => {
+            for (ext, content_type) in &template.defaults {
+                if ext != "rels" && ext != "xml" && !extensions.contains(&ext.as_str()) {
+                    defaults.push_str(&format!(
+                        r#"<Default Extension="{}" ContentType="{}"/>"#,
+                        escape_xml(ext),
+                        escape_xml(content_type)
+                    ));
+                }
+            }
+            for (part, content_type) in &template.overrides {
+                overrides.push_str(&format!(
+                    r#"<Override PartName="{}" ContentType="{}"/>"#,
+                    escape_xml(part),
+                    escape_xml(content_type)
+                ));
+            }
+        }
+        None => overrides.push_str(r#"<Override PartName="/ppt/slideMasters/slideMaster1.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.slideMaster+xml"/><Override PartName="/ppt/slideLayouts/slideLayout1.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.slideLayout+xml"/><Override PartName="/ppt/theme/theme1.xml" ContentType="application/vnd.openxmlformats-officedocument.theme+xml"/>"#),
+    }
+    overrides.extend((1..=slides.len())
+        .map(|n| {
+            format!(
+                r#"<Override PartName="/ppt/slides/slide{}.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.slide+xml"/>"#,
+                n
+            )
+        }));
+    if slides.iter().any(SlideImage::has_notes) {
+        overrides.push_str(&format!(
+            r#"<Override PartName="/ppt/notesMasters/notesMaster1.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.notesMaster+xml"/><Override PartName="/ppt/{}" ContentType="application/vnd.openxmlformats-officedocument.theme+xml"/>"#,
+            NOTES_THEME
+        ));
+    }
+    for (index, _) in slides.iter().enumerate().filter(|(_, s)| s.has_notes()) {
+        overrides.push_str(&format!(
+            r#"<Override PartName="/ppt/notesSlides/notesSlide{}.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.notesSlide+xml"/>"#,
+            index + 1
+        ));
+    }
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/>{}<Override PartName="/ppt/presentation.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.presentation.main+xml"/><Override PartName="/ppt/presProps.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.presProps+xml"/><Override PartName="/ppt/viewProps.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.viewProps+xml"/><Override PartName="/ppt/tableStyles.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.tableStyles+xml"/><Override PartName="/docProps/core.xml" ContentType="application/vnd.openxmlformats-package.core-properties+xml"/><Override PartName="/docProps/app.xml" ContentType="application/vnd.openxmlformats-officedocument.extended-properties+xml"/>{}</Types>"#,
+        defaults, overrides
+    )
+}
+
+const ROOT_RELS_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="ppt/presentation.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/package/2006/relationships/metadata/core-properties" Target="docProps/core.xml"/><Relationship Id="rId3" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/extended-properties" Target="docProps/app.xml"/></Relationships>"#;
+
+fn core_xml(title: &str, created: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<cp:coreProperties xmlns:cp="http://schemas.openxmlformats.org/package/2006/metadata/core-properties" xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:dcterms="http://purl.org/dc/terms/" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><dc:title>{}</dc:title><dc:subject>Showcase Presentation</dc:subject><dc:creator>Showcase Studio</dc:creator><dcterms:created xsi:type="dcterms:W3CDTF">{}</dcterms:created><dcterms:modified xsi:type="dcterms:W3CDTF">{}</dcterms:modified></cp:coreProperties>"#,
+        escape_xml(title),
+        created,
+        created
+    )
+}
+
+fn app_xml(slide_count: usize) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Properties xmlns="http://schemas.openxmlformats.org/officeDocument/2006/extended-properties"><Application>Showcase Studio</Application><Company>Showcase Studio</Company><Slides>{}</Slides><PresentationFormat>On-screen Show (16:9)</PresentationFormat></Properties>"#,
+        slide_count
+    )
+}
+
+// The slide masters come first from rId1, then the slides, the property parts, the theme and
+// the notes master when there are notes.
+fn presentation_xml(slide_count: usize, has_notes: bool, design: &Design) -> String {
+    let master_count = design.masters.len();
+    let master_ids: String = design
+        .masters
+        .iter()
+        .enumerate()
+        .map(|(i, (id, _))| format!(r#"<p:sldMasterId id="{}" r:id="rId{}"/>"#, id, i + 1))
+        .collect();
+    let slide_ids: String = (0..slide_count)
+        .map(|i| {
+            format!(
+                r#"<p:sldId id="{}" r:id="rId{}"/>"#,
+                256 + i,
+                master_count + i + 1
+            )
+        })
+        .collect();
+    let notes_master = if has_notes {
+        format!(
+            r#"<p:notesMasterIdLst><p:notesMasterId r:id="rId{}"/></p:notesMasterIdLst>"#,
+            master_count + slide_count + 5
+        )
+    } else {
+        String::new()
+    };
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<p:presentation xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" saveSubsetFonts="1"><p:sldMasterIdLst>{}</p:sldMasterIdLst>{}<p:sldIdLst>{}</p:sldIdLst><p:sldSz cx="{}" cy="{}"/><p:notesSz cx="{}" cy="{}"/><p:defaultTextStyle/></p:presentation>"#,
+        master_ids,
+        notes_master,
+        slide_ids,
+        design.slide_size.0,
+        design.slide_size.1,
+        NOTES_WIDTH_EMU,
+        NOTES_HEIGHT_EMU
+    )
+}
+
+fn presentation_rels_xml(slide_count: usize, has_notes: bool, design: &Design) -> String {
+    let master_count = design.masters.len();
+    let masters: String = design
+        .masters
+        .iter()
+        .enumerate()
+        .map(|(i, (_, target))| {
+            format!(
+                r#"<Relationship Id="rId{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slideMaster" Target="{}"/>"#,
+                i + 1,
+                escape_xml(target)
+            )
+        })
+        .collect();
+    let slides: String = (1..=slide_count)
+        .map(|n| {
+            format!(
+                r#"<Relationship Id="rId{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide" Target="slides/slide{}.xml"/>"#,
+                master_count + n,
+                n
+            )
+        })
+        .collect();
+    let next = master_count + slide_count + 1;
+    let notes_master = if has_notes {
+        format!(
+            r#"<Relationship Id="rId{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/notesMaster" Target="notesMasters/notesMaster1.xml"/>"#,
+            next + 4
+        )
+    } else {
+        String::new()
+    };
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">{}{}<Relationship Id="rId{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/presProps" Target="presProps.xml"/><Relationship Id="rId{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/viewProps" Target="viewProps.xml"/><Relationship Id="rId{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/theme" Target="{}"/><Relationship Id="rId{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/tableStyles" Target="tableStyles.xml"/>{}</Relationships>"#,
+        masters,
+        slides,
+        next,
+        next + 1,
+        next + 2,
+        escape_xml(&design.theme),
+        next + 3,
+        notes_master
+    )
+}
+
+const PRES_PROPS_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<p:presentationPr xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"/>"#;
+
+const VIEW_PROPS_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<p:viewPr xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"/>"#;
+
+const TABLE_STYLES_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<a:tblStyleLst xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" def="{5C22544A-7EE6-4342-B048-85BDC9FD1C3A}"/>"#;
+
+const EMPTY_SHAPE_TREE: &str = r#"<p:spTree><p:nvGrpSpPr><p:cNvPr id="1" name=""/><p:cNvGrpSpPr/><p:nvPr/></p:nvGrpSpPr><p:grpSpPr><a:xfrm><a:off x="0" y="0"/><a:ext cx="0" cy="0"/><a:chOff x="0" y="0"/><a:chExt cx="0" cy="0"/></a:xfrm></p:grpSpPr></p:spTree>"#;
+
+// Black background, like the MASTER_SLIDE the frontend defined.
+fn slide_master_xml() -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<p:sldMaster xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"><p:cSld><p:bg><p:bgPr><a:solidFill><a:srgbClr val="000000"/></a:solidFill><a:effectLst/></p:bgPr></p:bg>{}</p:cSld><p:clrMap bg1="lt1" tx1="dk1" bg2="lt2" tx2="dk2" accent1="accent1" accent2="accent2" accent3="accent3" accent4="accent4" accent5="accent5" accent6="accent6" hlink="hlink" folHlink="folHlink"/><p:sldLayoutIdLst><p:sldLayoutId id="2147483649" r:id="rId1"/></p:sldLayoutIdLst><p:txStyles><p:titleStyle/><p:bodyStyle/><p:otherStyle/></p:txStyles></p:sldMaster>"#,
+        EMPTY_SHAPE_TREE
+    )
+}
+
+const SLIDE_MASTER_RELS_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slideLayout" Target="../slideLayouts/slideLayout1.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/theme" Target="../theme/theme1.xml"/></Relationships>"#;
+
+fn slide_layout_xml() -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<p:sldLayout xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" type="blank" preserve="1"><p:cSld name="Blank">{}</p:cSld><p:clrMapOvr><a:masterClrMapping/></p:clrMapOvr></p:sldLayout>"#,
+        EMPTY_SHAPE_TREE
+    )
+}
+
+const SLIDE_LAYOUT_RELS_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slideMaster" Target="../slideMasters/slideMaster1.xml"/></Relationships>"#;
+
+const THEME_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<a:theme xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" name="Showcase"><a:themeElements><a:clrScheme name="Showcase"><a:dk1><a:srgbClr val="000000"/></a:dk1><a:lt1><a:srgbClr val="FFFFFF"/></a:lt1><a:dk2><a:srgbClr val="1F2937"/></a:dk2><a:lt2><a:srgbClr val="E5E7EB"/></a:lt2><a:accent1><a:srgbClr val="4F46E5"/></a:accent1><a:accent2><a:srgbClr val="2563EB"/></a:accent2><a:accent3><a:srgbClr val="7C3AED"/></a:accent3><a:accent4><a:srgbClr val="DB2777"/></a:accent4><a:accent5><a:srgbClr val="059669"/></a:accent5><a:accent6><a:srgbClr val="D97706"/></a:accent6><a:hlink><a:srgbClr val="2563EB"/></a:hlink><a:folHlink><a:srgbClr val="7C3AED"/></a:folHlink></a:clrScheme><a:fontScheme name="Showcase"><a:majorFont><a:latin typeface="Arial"/><a:ea typeface=""/><a:cs typeface=""/></a:majorFont><a:minorFont><a:latin typeface="Arial"/><a:ea typeface=""/><a:cs typeface=""/></a:minorFont></a:fontScheme><a:fmtScheme name="Showcase"><a:fillStyleLst><a:solidFill><a:schemeClr val="phClr"/></a:solidFill><a:solidFill><a:schemeClr val="phClr"/></a:solidFill><a:solidFill><a:schemeClr val="phClr"/></a:solidFill></a:fillStyleLst><a:lnStyleLst><a:ln w="6350"><a:solidFill><a:schemeClr val="phClr"/></a:solidFill></a:ln><a:ln w="12700"><a:solidFill><a:schemeClr val="phClr"/></a:solidFill></a:ln><a:ln w="19050"><a:solidFill><a:schemeClr val="phClr"/></a:solidFill></a:ln></a:lnStyleLst><a:effectStyleLst><a:effectStyle><a:effectLst/></a:effectStyle><a:effectStyle><a:effectLst/></a:effectStyle><a:effectStyle><a:effectLst/></a:effectStyle></a:effectStyleLst><a:bgFillStyleLst><a:solidFill><a:schemeClr val="phClr"/></a:solidFill><a:solidFill><a:schemeClr val="phClr"/></a:solidFill><a:solidFill><a:schemeClr val="phClr"/></a:solidFill></a:bgFillStyleLst></a:fmtScheme></a:themeElements></a:theme>"#;
+
+// Where a slide's image goes. Older viewers drop or tile picture backgrounds, so legacy decks
+// get a full-slide picture instead; a template layout with a picture placeholder gets the
+// image in that placeholder, sized by the layout.
+enum SlideFill<'a> {
+    Background,
+    FullSlide,
+    Placeholder(&'a str),
+}
+
+// Slide text is baked into the images, so there are no fonts to embed; the slide title only
+// names the slide.
+fn slide_xml(fill: &SlideFill, title: Option<&str>, (width, height): (u64, u64)) -> String {
+    let name = title.map_or_else(String::new, |title| {
+        format!(r#" name="{}""#, escape_xml(title))
+    });
+    let (background, shapes) = match fill {
+        SlideFill::FullSlide => (
+            String::new(),
+            format!(
+                r#"<p:pic><p:nvPicPr><p:cNvPr id="2" name="Slide image"/><p:cNvPicPr><a:picLocks noChangeAspect="1"/></p:cNvPicPr><p:nvPr/></p:nvPicPr><p:blipFill><a:blip r:embed="rId2"/><a:stretch><a:fillRect/></a:stretch></p:blipFill><p:spPr><a:xfrm><a:off x="0" y="0"/><a:ext cx="{}" cy="{}"/></a:xfrm><a:prstGeom prst="rect"><a:avLst/></a:prstGeom></p:spPr></p:pic>"#,
+                width, height
+            ),
+        ),
+        SlideFill::Placeholder(placeholder) => (
+            String::new(),
+            format!(
+                r#"<p:pic><p:nvPicPr><p:cNvPr id="2" name="Slide image"/><p:cNvPicPr><a:picLocks noGrp="1" noChangeAspect="1"/></p:cNvPicPr><p:nvPr>{}</p:nvPr></p:nvPicPr><p:blipFill><a:blip r:embed="rId2"/><a:stretch><a:fillRect/></a:stretch></p:blipFill><p:spPr/></p:pic>"#,
+                placeholder
+            ),
+        ),
+        SlideFill::Background => (
+            r#"<p:bg><p:bgPr><a:blipFill dpi="0" rotWithShape="1"><a:blip r:embed="rId2"/><a:srcRect/><a:stretch><a:fillRect/></a:stretch></a:blipFill><a:effectLst/></p:bgPr></p:bg>"#.to_string(),
+            String::new(),
+        ),
+    };
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<p:sld xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"><p:cSld{}>{}<p:spTree><p:nvGrpSpPr><p:cNvPr id="1" name=""/><p:cNvGrpSpPr/><p:nvPr/></p:nvGrpSpPr><p:grpSpPr><a:xfrm><a:off x="0" y="0"/><a:ext cx="0" cy="0"/><a:chOff x="0" y="0"/><a:chExt cx="0" cy="0"/></a:xfrm></p:grpSpPr>{}</p:spTree></p:cSld><p:clrMapOvr><a:masterClrMapping/></p:clrMapOvr></p:sld>"#,
+        name, background, shapes
+    )
+}
+
+fn slide_rels_xml(media_name: &str, layout: &str, notes_slide: Option<usize>) -> String {
+    let notes = notes_slide.map_or_else(String::new, |n| {
+        format!(
+            r#"<Relationship Id="rId3" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/notesSlide" Target="../notesSlides/notesSlide{}.xml"/>"#,
+            n
+        )
+    });
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slideLayout" Target="../{}"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/image" Target="../media/{}"/>{}</Relationships>"#,
+        escape_xml(layout),
+        media_name,
+        notes
+    )
+}
+
+fn notes_master_xml() -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<p:notesMaster xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"><p:cSld>{}</p:cSld><p:clrMap bg1="lt1" tx1="dk1" bg2="lt2" tx2="dk2" accent1="accent1" accent2="accent2" accent3="accent3" accent4="accent4" accent5="accent5" accent6="accent6" hlink="hlink" folHlink="folHlink"/></p:notesMaster>"#,
+        EMPTY_SHAPE_TREE
+    )
+}
+
+fn notes_master_rels_xml() -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/theme" Target="../{}"/></Relationships>"#,
+        NOTES_THEME
+    )
+}
+
+fn notes_paragraph(text: &str, bold: bool) -> String {
+    if text.is_empty() {
+        return "<a:p/>".to_string();
+    }
+    format!(
+        r#"<a:p><a:r><a:rPr lang="en-US"{}/><a:t>{}</a:t></a:r></a:p>"#,
+        if bold { r#" b="1""# } else { "" },
+        escape_xml(text)
+    )
+}
+
+// The notes page shows the slide above the notes text; the slide title leads in bold.
+fn notes_slide_xml(slide: &SlideImage) -> String {
+    let mut paragraphs: String = slide
+        .title
+        .iter()
+        .map(|title| notes_paragraph(title, true))
+        .collect();
+    for line in slide.notes.iter().flat_map(|notes| notes.lines()) {
+        paragraphs.push_str(&notes_paragraph(line.trim_end(), false));
+    }
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<p:notes xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"><p:cSld><p:spTree><p:nvGrpSpPr><p:cNvPr id="1" name=""/><p:cNvGrpSpPr/><p:nvPr/></p:nvGrpSpPr><p:grpSpPr><a:xfrm><a:off x="0" y="0"/><a:ext cx="0" cy="0"/><a:chOff x="0" y="0"/><a:chExt cx="0" cy="0"/></a:xfrm></p:grpSpPr><p:sp><p:nvSpPr><p:cNvPr id="2" name="Slide Image Placeholder 1"/><p:cNvSpPr><a:spLocks noGrp="1" noRot="1" noChangeAspect="1"/></p:cNvSpPr><p:nvPr><p:ph type="sldImg"/></p:nvPr></p:nvSpPr><p:spPr><a:xfrm><a:off x="685800" y="1143000"/><a:ext cx="5486400" cy="3086100"/></a:xfrm></p:spPr></p:sp><p:sp><p:nvSpPr><p:cNvPr id="3" name="Notes Placeholder 2"/><p:cNvSpPr><a:spLocks noGrp="1"/></p:cNvSpPr><p:nvPr><p:ph type="body" idx="1"/></p:nvPr></p:nvSpPr><p:spPr><a:xfrm><a:off x="685800" y="4400550"/><a:ext cx="5486400" cy="3600450"/></a:xfrm></p:spPr><p:txBody><a:bodyPr/><a:lstStyle/>{}</p:txBody></p:sp></p:spTree></p:cSld><p:clrMapOvr><a:masterClrMapping/></p:clrMapOvr></p:notes>"#,
+        if paragraphs.is_empty() {
+            "<a:p/>".to_string()
+        } else {
+            paragraphs
+        }
+    )
+}
+
+fn notes_slide_rels_xml(n: usize) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/notesMaster" Target="../notesMasters/notesMaster1.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide" Target="../slides/slide{}.xml"/></Relationships>"#,
+        n
+    )
+}
+
+// Writes a complete .pptx package with one slide per image, on the template's masters and
+// layout when one is given.
+pub(crate) fn write_pptx<W: Write + Seek>(
+    writer: W,
+    title: &str,
+    slides: &[SlideImage],
+    legacy: bool,
+    template: Option<&PptxTemplate>,
+) -> Result<W, String> {
+    let mut zip = ZipWriter::new(writer);
+    let xml_options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    // Slide images are already compressed.
+    let media_options = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+    let created = chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
+    let has_notes = slides.iter().any(SlideImage::has_notes);
+    let design = Design::new(template);
+    let fill = design.fill(legacy);
+    // Template media is copied as is, so slide images get names it can't already use.
+    let media_stem = if template.is_some() {
+        "showcaseImage"
+    } else {
+        "image"
+    };
+
+    let mut parts: Vec<(String, String)> = vec![
+        (
+            "[Content_Types].xml".to_string(),
+            content_types_xml(slides, &design),
+        ),
+        ("_rels/.rels".to_string(), ROOT_RELS_XML.to_string()),
+        ("docProps/core.xml".to_string(), core_xml(title, &created)),
+        ("docProps/app.xml".to_string(), app_xml(slides.len())),
+        (
+            "ppt/presentation.xml".to_string(),
+            presentation_xml(slides.len(), has_notes, &design),
+        ),
+        (
+            "ppt/_rels/presentation.xml.rels".to_string(),
+            presentation_rels_xml(slides.len(), has_notes, &design),
+        ),
+        ("ppt/presProps.xml".to_string(), PRES_PROPS_XML.to_string()),
+        ("ppt/viewProps.xml".to_string(), VIEW_PROPS_XML.to_string()),
+        (
+            "ppt/tableStyles.xml".to_string(),
+            TABLE_STYLES_XML.to_string(),
+        ),
+    ];
+    if template.is_none() {
+        parts.extend([
+            ("ppt/theme/theme1.xml".to_string(), THEME_XML.to_string()),
+            (
+                "ppt/slideMasters/slideMaster1.xml".to_string(),
+                slide_master_xml(),
+            ),
+            (
+                "ppt/slideMasters/_rels/slideMaster1.xml.rels".to_string(),
+                SLIDE_MASTER_RELS_XML.to_string(),
+            ),
+            (
+                "ppt/slideLayouts/slideLayout1.xml".to_string(),
+                slide_layout_xml(),
+            ),
+            (
+                "ppt/slideLayouts/_rels/slideLayout1.xml.rels".to_string(),
+                SLIDE_LAYOUT_RELS_XML.to_string(),
+            ),
+        ]);
+    }
+    if has_notes {
+        parts.push((
+            "ppt/notesMasters/notesMaster1.xml".to_string(),
+            notes_master_xml(),
+        ));
+        parts.push((
+            "ppt/notesMasters/_rels/notesMaster1.xml.rels".to_string(),
+            notes_master_rels_xml(),
+        ));
+        parts.push((format!("ppt/{}", NOTES_THEME), THEME_XML.to_string()));
+    }
+    for (index, slide) in slides.iter().enumerate() {
+        let n = index + 1;
+        parts.push((
+            format!("ppt/slides/slide{}.xml", n),
+            slide_xml(&fill, slide.title.as_deref(), design.slide_size),
+        ));
+        parts.push((
+            format!("ppt/slides/_rels/slide{}.xml.rels", n),
+            slide_rels_xml(
+                &format!("{}{}.{}", media_stem, n, slide.extension),
+                &design.layout,
+                slide.has_notes().then_some(n),
+            ),
+        ));
+        if slide.has_notes() {
+            parts.push((
+                format!("ppt/notesSlides/notesSlide{}.xml", n),
+                notes_slide_xml(slide),
+            ));
+            parts.push((
+                format!("ppt/notesSlides/_rels/notesSlide{}.xml.rels", n),
+                notes_slide_rels_xml(n),
+            ));
+        }
+    }
+
+    for (name, xml) in &parts {
+        zip.start_file(name.as_str(), xml_options)
+            .context(format!("Failed to add {} to PPTX", name))?;
+        zip.write_all(xml.as_bytes())
+            .context(format!("Failed to write {} to PPTX", name))?;
+    }
+    for (name, bytes) in template.iter().flat_map(|t| t.parts.iter()) {
+        zip.start_file(name.as_str(), xml_options)
+            .context(format!("Failed to add {} to PPTX", name))?;
+        zip.write_all(bytes)
+            .context(format!("Failed to write {} to PPTX", name))?;
+    }
+    for (index, slide) in slides.iter().enumerate() {
+        let name = format!("ppt/media/{}{}.{}", media_stem, index + 1, slide.extension);
+        zip.start_file(name.as_str(), media_options)
+            .context(format!("Failed to add {} to PPTX", name))?;
+        zip.write_all(&slide.bytes)
+            .context(format!("Failed to write {} to PPTX", name))?;
+    }
+    zip.finish()
+        .map_err(|e| format!("Failed to finish PPTX archive: {}", e))
+}
+
+// Draws the deck's slides in the backend, so nothing but the options crosses IPC and the export
+// survives a webview crash. Written to a .part file and renamed when complete.
+#[tauri::command]
+pub async fn generate_showcase_pptx(
+    app_handle: AppHandle,
+    id: String,
+    options: Option<PptxOptions>,
+    db_state: State<'_, DbConnection>,
+) -> Result<String, AppError> {
+    let options = options.unwrap_or_default();
+    let span = Span::root("export.pptx.generate", &[("showcase_id", id.clone())]);
+    let lookup_id = id.clone();
+    let (showcase, credits, configured_legacy, template_path, redaction_rules) = db_state
+        .0
+        .run(move |conn| {
+            let showcase = load_showcase(conn, &lookup_id)?;
+            let selected_messages = showcase.selected_messages.clone().unwrap_or_default();
+            let credits = resolve_showcase_credits(conn, &selected_messages)?;
+            let config = retrieve_config(conn)?;
+            Ok((
+                showcase,
+                credits,
+                config.legacy_pptx_compatibility,
+                config.presentation_template,
+                config.redaction_rules.unwrap_or_default(),
+            ))
+        })
+        .await?;
+    let legacy = options
+        .legacy_compatibility
+        .or(configured_legacy)
+        .unwrap_or(false);
+    let image_base_dir = get_image_base_dir(&app_handle)?;
+    let showcase_dir = get_showcase_image_dir(&app_handle, &id)?;
+    let plans = plan_slides(
+        &showcase,
+        &credits,
+        &image_base_dir,
+        &showcase_dir,
+        |text| redact_text(text, &redaction_rules),
+    );
+    let credit_lines = unique_credits(&credits);
+    if plans.is_empty() {
+        span.fail("no slides");
+        return Err(AppError::invalid_input(
+            "This showcase has no slide images to put in a presentation.",
+        ));
+    }
+
+    let presentation_dir = get_showcase_presentation_dir(&app_handle, &id)?;
+    let part_path = pptx_part_path(&app_handle, &id)?;
+    let file_path = presentation_dir.join(pptx_filename(&id));
+    let title = showcase.title;
+    let written = tokio::task::spawn_blocking(move || -> Result<usize, String> {
+        let mut slides = load_slide_images(&plans, DEFAULT_RESOLUTION, legacy);
+        if slides.is_empty() {
+            return Err("None of the slide images could be read.".to_string());
+        }
+        slides.extend(credits_slide_image(
+            &credit_lines,
+            DEFAULT_RESOLUTION,
+            legacy,
+        ));
+        let template = template_path
+            .as_deref()
+            .map(|path| load_template_file(Path::new(path)))
+            .transpose()?;
+        fs::create_dir_all(&presentation_dir).map_err(|e| {
+            format!(
+                "Failed to create showcase presentation directory '{}': {}",
+                presentation_dir.display(),
+                e
+            )
+        })?;
+        let file = fs::File::create(&part_path)
+            .context(format!("Failed to create '{}'", part_path.display()))?;
+        let result = write_pptx(file, &title, &slides, legacy, template.as_ref())
+            .and_then(|file| file.sync_all().map_err(|e| e.to_string()))
+            .and_then(|_| {
+                fs::rename(&part_path, &file_path).map_err(|e| {
+                    format!(
+                        "Failed to finalize PPTX file '{}': {}",
+                        file_path.display(),
+                        e
+                    )
+                })
+            });
+        if result.is_err() {
+            let _ = fs::remove_file(&part_path);
+        }
+        result.map(|_| slides.len())
+    })
+    .await
+    .map_err(|e| format!("PPTX generation task panicked or was cancelled: {}", e))
+    .and_then(|result| result);
+    let slide_count = match written {
+        Ok(slide_count) => slide_count,
+        Err(e) => {
+            span.fail(&e);
+            return Err(e.into());
+        }
+    };
+    span.record("slides", slide_count);
+    info!(
+        "Generated PPTX with {} slide(s) for showcase ID: {}",
+        slide_count, id
+    );
+
+    register_pptx_export(&app_handle, &db_state, &id, options.output_dir).await
+}
@@ -0,0 +1,355 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use rusqlite::params;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::io::{Read, Seek};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, State};
+use zip::ZipArchive;
+
+use crate::error::{AppError, ResultExt};
+use crate::log_info as info;
+use crate::providers::PathProvider;
+use crate::sqlite_manager::DbConnection;
+
+const TEMPLATE_DIR: &str = "templates";
+const TEMPLATE_FILENAME: &str = "presentation_template.pptx";
+// Everything a slide needs from the template; its own slides and notes are left behind.
+const COPIED_PREFIXES: &[&str] = &["ppt/slideMasters/", "ppt/slideLayouts/", "ppt/theme/"];
+
+static ATTRIBUTE_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"([\w:]+)\s*=\s*"([^"]*)""#).unwrap());
+static PICTURE_PLACEHOLDER_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"<p:ph\b[^>]*\btype="pic"[^>]*?/?>"#).unwrap());
+
+pub(crate) struct TemplateMaster {
+    pub id: u64,
+    pub target: String, // relative to ppt/
+}
+
+// The masters, layouts and themes of a branded deck, ready to be written into an export.
+pub(crate) struct PptxTemplate {
+    pub parts: Vec<(String, Vec<u8>)>,
+    pub masters: Vec<TemplateMaster>,
+    pub theme: String,  // relative to ppt/
+    pub layout: String, // relative to ppt/
+    // The layout's picture placeholder, e.g. `<p:ph type="pic" idx="1"/>`; slides fill the
+    // background when the layout has none.
+    pub picture_placeholder: Option<String>,
+    pub slide_size: Option<(u64, u64)>,
+    pub defaults: Vec<(String, String)>, // extension, content type
+    pub overrides: Vec<(String, String)>, // part name, content type
+}
+
+fn elements<'a>(xml: &'a str, name: &str) -> Vec<&'a str> {
+    let re = Regex::new(&format!(r"<{}\b[^>]*>", regex::escape(name))).unwrap();
+    re.find_iter(xml).map(|m| m.as_str()).collect()
+}
+
+fn attribute(element: &str, name: &str) -> Option<String> {
+    ATTRIBUTE_RE
+        .captures_iter(element)
+        .find(|c| &c[1] == name)
+        .map(|c| c[2].to_string())
+}
+
+struct Relationship {
+    id: String,
+    kind: String,
+    target: String,
+    external: bool,
+}
+
+fn relationships(xml: &str) -> Vec<Relationship> {
+    elements(xml, "Relationship")
+        .into_iter()
+        .filter_map(|element| {
+            Some(Relationship {
+                id: attribute(element, "Id")?,
+                kind: attribute(element, "Type")?
+                    .rsplit('/')
+                    .next()
+                    .unwrap_or_default()
+                    .to_string(),
+                target: attribute(element, "Target")?,
+                external: attribute(element, "TargetMode").as_deref() == Some("External"),
+            })
+        })
+        .collect()
+}
+
+// ppt/slideMasters/slideMaster1.xml -> ppt/slideMasters/_rels/slideMaster1.xml.rels
+fn rels_name(part: &str) -> String {
+    match part.rsplit_once('/') {
+        Some((dir, name)) => format!("{}/_rels/{}.rels", dir, name),
+        None => format!("_rels/{}.rels", part),
+    }
+}
+
+// A relationship target resolved against the part that declares it.
+pub(crate) fn resolve_target(part: &str, target: &str) -> String {
+    if let Some(absolute) = target.strip_prefix('/') {
+        return absolute.to_string();
+    }
+    let mut segments: Vec<&str> = part.split('/').collect();
+    segments.pop();
+    for segment in target.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            other => segments.push(other),
+        }
+    }
+    segments.join("/")
+}
+
+fn read_part<R: Read + Seek>(archive: &mut ZipArchive<R>, name: &str) -> Result<Vec<u8>, String> {
+    let mut file = archive
+        .by_name(name)
+        .map_err(|_| format!("The template has no {}", name))?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)
+        .context(format!("Failed to read {} from the template", name))?;
+    Ok(bytes)
+}
+
+fn read_text<R: Read + Seek>(archive: &mut ZipArchive<R>, name: &str) -> Result<String, String> {
+    String::from_utf8(read_part(archive, name)?)
+        .map_err(|_| format!("{} in the template is not UTF-8", name))
+}
+
+// The first layout of the first master with a picture placeholder, else its blank layout,
+// else its first layout.
+fn choose_layout<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    master: &str,
+) -> Result<(String, Option<String>), String> {
+    let rels = read_text(archive, &rels_name(master))?;
+    let layouts: Vec<String> = relationships(&rels)
+        .into_iter()
+        .filter(|r| r.kind == "slideLayout" && !r.external)
+        .map(|r| resolve_target(master, &r.target))
+        .collect();
+    let mut blank = None;
+    for layout in &layouts {
+        let xml = read_text(archive, layout)?;
+        if let Some(placeholder) = PICTURE_PLACEHOLDER_RE.find(&xml) {
+            let idx = attribute(placeholder.as_str(), "idx");
+            let element = match idx {
+                Some(idx) => format!(r#"<p:ph type="pic" idx="{}"/>"#, idx),
+                None => r#"<p:ph type="pic"/>"#.to_string(),
+            };
+            return Ok((layout.clone(), Some(element)));
+        }
+        let is_blank = elements(&xml, "p:sldLayout")
+            .first()
+            .and_then(|root| attribute(root, "type"))
+            .as_deref()
+            == Some("blank");
+        if is_blank && blank.is_none() {
+            blank = Some(layout.clone());
+        }
+    }
+    blank
+        .or_else(|| layouts.first().cloned())
+        .map(|layout| (layout, None))
+        .ok_or_else(|| "The template's slide master has no layouts.".to_string())
+}
+
+pub(crate) fn load_template<R: Read + Seek>(reader: R) -> Result<PptxTemplate, String> {
+    let mut archive = ZipArchive::new(reader).context("The template is not a .pptx file")?;
+    let presentation = read_text(&mut archive, "ppt/presentation.xml")?;
+    let presentation_rels: HashMap<String, Relationship> =
+        relationships(&read_text(&mut archive, "ppt/_rels/presentation.xml.rels")?)
+            .into_iter()
+            .map(|r| (r.id.clone(), r))
+            .collect();
+
+    let masters: Vec<TemplateMaster> = elements(&presentation, "p:sldMasterId")
+        .into_iter()
+        .filter_map(|element| {
+            let id = attribute(element, "id")?.parse().ok()?;
+            let rel = presentation_rels.get(&attribute(element, "r:id")?)?;
+            (rel.kind == "slideMaster").then(|| TemplateMaster {
+                id,
+                target: resolve_target("ppt/presentation.xml", &rel.target)
+                    .trim_start_matches("ppt/")
+                    .to_string(),
+            })
+        })
+        .collect();
+    let Some(first_master) = masters.first() else {
+        return Err("The template has no slide master.".to_string());
+    };
+    let theme = presentation_rels
+        .values()
+        .find(|r| r.kind == "theme")
+        .map(|r| {
+            resolve_target("ppt/presentation.xml", &r.target)
+                .trim_start_matches("ppt/")
+                .to_string()
+        })
+        .ok_or_else(|| "The template has no theme.".to_string())?;
+    let slide_size = elements(&presentation, "p:sldSz").first().and_then(|size| {
+        Some((
+            attribute(size, "cx")?.parse().ok()?,
+            attribute(size, "cy")?.parse().ok()?,
+        ))
+    });
+    let (layout, picture_placeholder) =
+        choose_layout(&mut archive, &format!("ppt/{}", first_master.target))?;
+
+    let names: Vec<String> = archive.file_names().map(str::to_string).collect();
+    let mut wanted: BTreeSet<String> = names
+        .iter()
+        .filter(|name| {
+            COPIED_PREFIXES
+                .iter()
+                .any(|prefix| name.starts_with(prefix))
+        })
+        .cloned()
+        .collect();
+    // Pull in the media and other parts the copied masters, layouts and themes point at.
+    let mut pending: Vec<String> = wanted
+        .iter()
+        .filter(|n| n.ends_with(".rels"))
+        .cloned()
+        .collect();
+    while let Some(rels) = pending.pop() {
+        let Some(source) = rels
+            .strip_suffix(".rels")
+            .map(|s| s.replacen("/_rels/", "/", 1))
+        else {
+            continue;
+        };
+        for rel in relationships(&read_text(&mut archive, &rels)?) {
+            let target = resolve_target(&source, &rel.target);
+            if rel.external || !names.contains(&target) || !wanted.insert(target.clone()) {
+                continue;
+            }
+            let target_rels = rels_name(&target);
+            if names.contains(&target_rels) && wanted.insert(target_rels.clone()) {
+                pending.push(target_rels);
+            }
+        }
+    }
+    let mut parts = Vec::with_capacity(wanted.len());
+    for name in wanted.iter() {
+        parts.push((name.clone(), read_part(&mut archive, name)?));
+    }
+
+    let content_types = read_text(&mut archive, "[Content_Types].xml")?;
+    let defaults = elements(&content_types, "Default")
+        .into_iter()
+        .filter_map(|e| {
+            Some((
+                attribute(e, "Extension")?.to_ascii_lowercase(),
+                attribute(e, "ContentType")?,
+            ))
+        })
+        .collect();
+    let overrides = elements(&content_types, "Override")
+        .into_iter()
+        .filter_map(|e| Some((attribute(e, "PartName")?, attribute(e, "ContentType")?)))
+        .filter(|(part, _)| wanted.contains(part.trim_start_matches('/')))
+        .collect();
+
+    Ok(PptxTemplate {
+        parts,
+        masters,
+        theme,
+        layout: layout.trim_start_matches("ppt/").to_string(),
+        picture_placeholder,
+        slide_size,
+        defaults,
+        overrides,
+    })
+}
+
+pub(crate) fn load_template_file(path: &Path) -> Result<PptxTemplate, String> {
+    let file = fs::File::open(path).map_err(|e| {
+        format!(
+            "Failed to open presentation template '{}': {}",
+            path.display(),
+            e
+        )
+    })?;
+    load_template(file)
+}
+
+// Copies the template into the data folder, so exports keep working if the original moves.
+pub(crate) fn store_template(
+    paths: &impl PathProvider,
+    source: &Path,
+) -> Result<PathBuf, AppError> {
+    load_template_file(source).map_err(AppError::invalid_input)?;
+    let dir = paths.app_data_dir()?.join(TEMPLATE_DIR);
+    fs::create_dir_all(&dir).context(format!("Failed to create '{}'", dir.display()))?;
+    let stored = dir.join(TEMPLATE_FILENAME);
+    let part = dir.join(format!("{}.part", TEMPLATE_FILENAME));
+    fs::copy(source, &part)
+        .and_then(|_| fs::rename(&part, &stored))
+        .map_err(|e| {
+            let _ = fs::remove_file(&part);
+            format!("Failed to copy presentation template: {}", e)
+        })?;
+    Ok(stored)
+}
+
+// Registers the .pptx whose masters and layouts exports use, or goes back to the built-in
+// blank layout when `path` is empty. Returns the stored copy.
+#[tauri::command]
+pub async fn set_presentation_template(
+    app_handle: AppHandle,
+    path: Option<String>,
+    db_state: State<'_, DbConnection>,
+) -> Result<Option<String>, AppError> {
+    let source = path
+        .as_deref()
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(PathBuf::from);
+    let stored = match &source {
+        Some(source) => {
+            let source = source.clone();
+            let paths = app_handle.clone();
+            let stored = tokio::task::spawn_blocking(move || store_template(&paths, &source))
+                .await
+                .context("Template task panicked or was cancelled")??;
+            Some(stored.to_string_lossy().into_owned())
+        }
+        None => {
+            let stored = app_handle
+                .app_data_dir()?
+                .join(TEMPLATE_DIR)
+                .join(TEMPLATE_FILENAME);
+            if stored.exists() {
+                fs::remove_file(&stored).context("Failed to remove presentation template")?;
+            }
+            None
+        }
+    };
+
+    let value = stored.clone();
+    db_state
+        .0
+        .run(move |conn| {
+            match &value {
+                Some(path) => conn.execute(
+                    "INSERT OR REPLACE INTO config (key, value) VALUES ('presentation_template', ?1)",
+                    params![path],
+                ),
+                None => conn.execute("DELETE FROM config WHERE key = 'presentation_template'", []),
+            }
+            .context("Failed to save presentation_template")?;
+            Ok(())
+        })
+        .await?;
+    match &stored {
+        Some(path) => info!("Presentation template set to '{}'", path),
+        None => info!("Presentation template cleared"),
+    }
+    Ok(stored)
+}
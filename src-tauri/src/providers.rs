@@ -0,0 +1,121 @@
+use keyring::Entry;
+use std::future::Future;
+use std::path::PathBuf;
+use std::time::Duration;
+use tauri::AppHandle;
+
+use crate::app_paths;
+use crate::error::{AppError, ResultExt};
+use crate::KEYRING_SERVICE_NAME;
+
+// Where the database, cache and presentations live. The app resolves it through the
+// AppHandle (honouring the safe-mode override); tests point it at a temporary directory.
+pub(crate) trait PathProvider {
+    fn app_data_dir(&self) -> Result<PathBuf, String>;
+}
+
+impl PathProvider for AppHandle {
+    fn app_data_dir(&self) -> Result<PathBuf, String> {
+        app_paths::app_data_dir(self)
+    }
+}
+
+impl PathProvider for PathBuf {
+    fn app_data_dir(&self) -> Result<PathBuf, String> {
+        Ok(self.clone())
+    }
+}
+
+pub(crate) trait SecretStore {
+    fn get_secret(&self, key_name: &str) -> Result<Option<String>, AppError>;
+    fn set_secret(&self, key_name: &str, secret: &str) -> Result<(), AppError>;
+    // Deleting a secret that does not exist is not an error.
+    fn delete_secret(&self, key_name: &str) -> Result<(), AppError>;
+}
+
+// The OS credential store (Keychain, Credential Manager, Secret Service).
+pub(crate) struct KeyringSecrets;
+
+fn keyring_entry(key_name: &str) -> Result<Entry, AppError> {
+    Entry::new(KEYRING_SERVICE_NAME, key_name)
+        .context(format!("Failed to create keyring entry for {}", key_name))
+}
+
+impl SecretStore for KeyringSecrets {
+    fn get_secret(&self, key_name: &str) -> Result<Option<String>, AppError> {
+        match keyring_entry(key_name)?.get_password() {
+            Ok(secret) => Ok(Some(secret)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(AppError::from(e)
+                .with_context(format!("Could not retrieve secret for '{}'", key_name))),
+        }
+    }
+
+    fn set_secret(&self, key_name: &str, secret: &str) -> Result<(), AppError> {
+        keyring_entry(key_name)?
+            .set_password(secret)
+            .context(format!("Could not save secret for '{}'", key_name))
+    }
+
+    fn delete_secret(&self, key_name: &str) -> Result<(), AppError> {
+        match keyring_entry(key_name)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(AppError::from(e)
+                .with_context(format!("Could not delete secret for '{}'", key_name))),
+        }
+    }
+}
+
+pub(crate) struct HttpResponse {
+    pub status: u16,
+    pub content_type: Option<String>,
+    pub retry_after: Option<Duration>,
+    pub body: Vec<u8>, // Only read for successful responses
+}
+
+// The GET requests behind cache downloads. Err means the request itself failed (connection,
+// timeout, truncated body); HTTP error statuses come back as a response.
+pub(crate) trait HttpFetcher: Clone + Send + Sync + 'static {
+    fn fetch(&self, url: &str) -> impl Future<Output = Result<HttpResponse, String>> + Send;
+}
+
+fn parse_retry_after(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    headers
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<f64>().ok())
+        .filter(|secs| secs.is_finite() && *secs >= 0.0)
+        .map(Duration::from_secs_f64)
+}
+
+impl HttpFetcher for reqwest::Client {
+    async fn fetch(&self, url: &str) -> Result<HttpResponse, String> {
+        let response = self
+            .get(url)
+            .send()
+            .await
+            .context(format!("Download request failed for {}", url))?;
+        let status = response.status();
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let retry_after = parse_retry_after(response.headers());
+        let body = if status.is_success() {
+            response
+                .bytes()
+                .await
+                .context(format!("Failed to read bytes from download {}", url))?
+                .to_vec()
+        } else {
+            Vec::new()
+        };
+        Ok(HttpResponse {
+            status: status.as_u16(),
+            content_type,
+            retry_after,
+            body,
+        })
+    }
+}
@@ -0,0 +1,49 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::models::RedactionRules;
+
+static LINK_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b(?:https?://|www\.|discord\.gg/)\S+").unwrap()
+});
+static EMAIL_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b[a-z0-9._%+-]+@[a-z0-9.-]+\.[a-z]{2,}\b").unwrap()
+});
+static PHONE_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\+?\d[\d\s().-]{6,}\d").unwrap());
+static WHITESPACE_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"[ \t]{2,}").unwrap());
+
+// Fewer digits than this is more likely a date or a count than a phone number.
+const MIN_PHONE_DIGITS: usize = 9;
+
+pub fn redact_text(text: &str, rules: &RedactionRules) -> String {
+    let mut result = text.to_string();
+
+    if rules.strip_links {
+        result = LINK_RE.replace_all(&result, "").into_owned();
+    }
+    if rules.strip_emails {
+        result = EMAIL_RE.replace_all(&result, "").into_owned();
+    }
+    if rules.strip_phone_numbers {
+        result = PHONE_RE
+            .replace_all(&result, |caps: &regex::Captures| {
+                let matched = &caps[0];
+                if matched.chars().filter(|c| c.is_ascii_digit()).count() >= MIN_PHONE_DIGITS {
+                    String::new()
+                } else {
+                    matched.to_string()
+                }
+            })
+            .into_owned();
+    }
+
+    let mut result = WHITESPACE_RE.replace_all(&result, " ").trim().to_string();
+
+    if let Some(max_len) = rules.max_caption_length {
+        if result.chars().count() > max_len {
+            result = result.chars().take(max_len.saturating_sub(1)).collect::<String>();
+            result = format!("{}…", result.trim_end());
+        }
+    }
+    result
+}
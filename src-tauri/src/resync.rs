@@ -0,0 +1,331 @@
+use rusqlite::{params, Connection};
+use serenity::all::MessagePagination;
+use serenity::http::Http;
+use serenity::model::channel::Message;
+use serenity::model::id::{ChannelId, MessageId};
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, State};
+use tokio::sync::Semaphore;
+
+use crate::discord::{
+    collect_download_jobs, download_status, load_bot_token, reaction_summaries, run_download_jobs,
+    CachedFile, IndexingState, DEFAULT_DOWNLOAD_CONCURRENCY,
+};
+use crate::error::{AppError, ResultExt};
+use crate::models::ImageSizeFilter;
+use crate::network::{configured_discord_http, http_client};
+use crate::spans::Span;
+use crate::sqlite_manager::{get_image_base_dir, retrieve_config, DbConnection};
+use crate::thumbnails;
+use crate::{log_error as error, log_info as info, log_warn as warn};
+
+const RESYNC_PAGE_SIZE: u8 = 100;
+
+#[derive(serde::Serialize, Clone, Debug, Default)]
+pub struct ResyncResult {
+    pub checked: usize,
+    pub updated: usize,
+    pub deleted: usize,
+    pub failed_channels: Vec<String>,
+}
+
+struct KnownMessage {
+    id: u64,
+    content: String,
+    attachments: Vec<String>,
+    is_used: bool,
+}
+
+fn load_known_messages(
+    conn: &Connection,
+    channel_filter: Option<&[String]>,
+) -> Result<HashMap<String, Vec<KnownMessage>>, String> {
+    let mut stmt = conn
+        // Submissions have no channel message behind them, so resync would mark them deleted.
+        .prepare("SELECT message_id, channel_id, message_content, attachments, is_used FROM messages WHERE deleted = 0 AND is_submission = 0")
+        .context("Failed to prepare resync query")?;
+
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, bool>(4)?,
+            ))
+        })
+        .context("Failed to query messages for resync")?;
+
+    let mut by_channel: HashMap<String, Vec<KnownMessage>> = HashMap::new();
+    for row in rows {
+        let (message_id, channel_id, content, attachments_json, is_used) =
+            row.context("Error reading message row")?;
+        if channel_filter.map_or(false, |ids| !ids.contains(&channel_id)) {
+            continue;
+        }
+        // Manifest imports and other non-Discord rows have non-numeric IDs.
+        let (Ok(id), Ok(channel_raw)) = (message_id.parse::<u64>(), channel_id.parse::<u64>()) else {
+            continue;
+        };
+        if id == 0 || channel_raw == 0 {
+            continue;
+        }
+        by_channel.entry(channel_id).or_default().push(KnownMessage {
+            id,
+            content,
+            attachments: serde_json::from_str(&attachments_json).unwrap_or_default(),
+            is_used,
+        });
+    }
+    for messages in by_channel.values_mut() {
+        messages.sort_by_key(|m| m.id);
+    }
+    Ok(by_channel)
+}
+
+// Walks forward from the oldest known ID, 100 messages per request. Known IDs inside a
+// returned window that Discord did not return have been deleted.
+async fn fetch_current_messages(
+    http: &Http,
+    channel_id: ChannelId,
+    known: &[KnownMessage],
+) -> Result<(HashMap<u64, Message>, HashSet<u64>), serenity::Error> {
+    let mut found: HashMap<u64, Message> = HashMap::new();
+    let mut deleted: HashSet<u64> = HashSet::new();
+    let mut next = 0;
+
+    while next < known.len() {
+        let start_id = known[next].id;
+        let batch = http
+            .get_messages(
+                channel_id,
+                Some(MessagePagination::After(MessageId::new(start_id - 1))),
+                Some(RESYNC_PAGE_SIZE),
+            )
+            .await?;
+
+        let page_full = batch.len() == RESYNC_PAGE_SIZE as usize;
+        let covered_until = if page_full {
+            batch.iter().map(|m| m.id.get()).max().unwrap_or(start_id)
+        } else {
+            u64::MAX
+        };
+        for msg in batch {
+            found.insert(msg.id.get(), msg);
+        }
+
+        while next < known.len() && known[next].id <= covered_until {
+            if !found.contains_key(&known[next].id) {
+                deleted.insert(known[next].id);
+            }
+            next += 1;
+        }
+    }
+    Ok((found, deleted))
+}
+
+pub(crate) fn remove_cache_files(image_base_dir: &Path, relative_paths: &[String]) {
+    for relative_path in relative_paths {
+        let path = image_base_dir.join(relative_path);
+        if path.exists() {
+            if let Err(e) = fs::remove_file(&path) {
+                warn!("Failed to delete cached file {}: {}", path.display(), e);
+            }
+        }
+        thumbnails::remove_thumbnails(image_base_dir, relative_path);
+    }
+}
+
+fn files_to_json(files: &[CachedFile]) -> Result<(String, String, String), String> {
+    let paths: Vec<&str> = files.iter().map(|f| f.relative_path.as_str()).collect();
+    let types: Vec<&str> = files.iter().map(|f| f.attachment_type).collect();
+    let sources: Vec<&str> = files.iter().map(|f| f.source).collect();
+    Ok((
+        serde_json::to_string(&paths).context("JSON Serialize")?,
+        serde_json::to_string(&types).context("JSON Serialize")?,
+        serde_json::to_string(&sources).context("JSON Serialize")?,
+    ))
+}
+
+#[tauri::command]
+pub async fn resync_indexed_messages(
+    app_handle: AppHandle,
+    channel_ids: Option<Vec<String>>,
+    db_state: State<'_, DbConnection>,
+    indexing_state: State<'_, IndexingState>,
+) -> Result<ResyncResult, AppError> {
+    if indexing_state.0.is_running() {
+        return Err(AppError::conflict("Cannot resync while indexing is running."));
+    }
+    info!("Starting resync of indexed messages...");
+    let span = Span::root("resync", &[]);
+
+    let http = configured_discord_http(&db_state, &load_bot_token()?).await?;
+    let config = db_state.0.run(|conn| Ok(retrieve_config(conn)?)).await?;
+    let encrypt_cache = config.encrypt_image_cache.unwrap_or(false);
+    let include_animated = config.include_animated_attachments.unwrap_or(false);
+    let include_linked = config.index_linked_images.unwrap_or(true);
+
+    let image_base_dir = get_image_base_dir(&app_handle)?;
+    let cache_dir = image_base_dir.join("cached");
+    let download_client = http_client(
+        &config.network_settings.clone().unwrap_or_default(),
+        Some(Duration::from_secs(30)),
+    )?;
+    let semaphore = Arc::new(Semaphore::new(DEFAULT_DOWNLOAD_CONCURRENCY));
+
+    let known_by_channel = db_state
+        .0
+        .run(move |conn| Ok(load_known_messages(conn, channel_ids.as_deref())?))
+        .await?;
+    let mut result = ResyncResult::default();
+
+    for (channel_str, known) in known_by_channel {
+        let Ok(raw_channel_id) = channel_str.parse::<u64>() else {
+            continue;
+        };
+        let channel_id = ChannelId::new(raw_channel_id);
+        let channel_span = span.child(
+            "resync.channel",
+            &[("channel_id", channel_str.clone()), ("known", known.len().to_string())],
+        );
+        app_handle
+            .emit("indexing-status", format!("Resyncing channel {}...", channel_id))
+            .unwrap_or_default();
+
+        let (mut current, deleted_ids) = match fetch_current_messages(&http, channel_id, &known).await {
+            Ok(fetched) => fetched,
+            Err(serenity::Error::Http(http_err))
+                if http_err.status_code().map_or(false, |c| c.as_u16() == 404) =>
+            {
+                // The whole channel is gone.
+                warn!("Channel {} no longer exists, marking its messages deleted", channel_id);
+                (HashMap::new(), known.iter().map(|m| m.id).collect())
+            }
+            Err(e) => {
+                error!("Resync failed for channel {}: {}", channel_id, e);
+                channel_span.fail(&e.to_string());
+                result.failed_channels.push(channel_str.clone());
+                continue;
+            }
+        };
+        result.checked += known.len();
+
+        for known_msg in &known {
+            if deleted_ids.contains(&known_msg.id) {
+                // Used messages keep their files so existing showcases stay intact.
+                if !known_msg.is_used {
+                    remove_cache_files(&image_base_dir, &known_msg.attachments);
+                }
+                let message_id = known_msg.id;
+                db_state
+                    .0
+                    .run(move |conn| {
+                        conn.execute(
+                            "UPDATE messages SET deleted = 1, attachments = CASE WHEN is_used = 1 THEN attachments ELSE '[]' END WHERE message_id = ?1",
+                            params![message_id.to_string()],
+                        )
+                        .context(format!("Failed to mark message {} deleted", message_id))?;
+                        Ok(())
+                    })
+                    .await?;
+                result.deleted += 1;
+                continue;
+            }
+
+            let Some(msg) = current.remove(&known_msg.id) else {
+                continue;
+            };
+
+            // No size filter: files already in the cache should not be dropped because of it.
+            let jobs = collect_download_jobs(
+                &cache_dir,
+                0,
+                &msg,
+                include_animated,
+                include_linked,
+                &ImageSizeFilter::default(),
+            );
+            let downloads = run_download_jobs(
+                jobs,
+                &download_client,
+                semaphore.clone(),
+                encrypt_cache,
+                download_status(&app_handle),
+            )
+            .await;
+            // On a failed download keep the stored attachments rather than dropping them.
+            let download_failed = downloads.iter().any(|r| r.is_err());
+            let files: Vec<CachedFile> = downloads.into_iter().filter_map(|r| r.ok().flatten()).collect();
+            let new_paths: Vec<String> = files.iter().map(|f| f.relative_path.clone()).collect();
+
+            let content_changed = msg.content != known_msg.content;
+            let attachments_changed = !download_failed && new_paths != known_msg.attachments;
+
+            let reactions = reaction_summaries(&msg);
+            let reaction_count: u64 = reactions.iter().map(|r| r.count).sum();
+            let reactions_json = serde_json::to_string(&reactions)
+                .context("JSON Serialize")?;
+
+            let attachments_json = if attachments_changed {
+                Some(files_to_json(&files)?)
+            } else {
+                None
+            };
+            let (message_id, content) = (msg.id, msg.content.clone());
+            db_state
+                .0
+                .run(move |conn| {
+                    conn.execute(
+                        "UPDATE messages SET message_content = ?1, reaction_count = ?2, reactions_json = ?3 WHERE message_id = ?4",
+                        params![content, reaction_count as i64, reactions_json, message_id.to_string()],
+                    )
+                    .context(format!("Failed to update message {}", message_id))?;
+                    if let Some((attachments_json, types_json, sources_json)) = attachments_json {
+                        conn.execute(
+                            "UPDATE messages SET attachments = ?1, attachment_types = ?2, attachment_sources = ?3 WHERE message_id = ?4",
+                            params![attachments_json, types_json, sources_json, message_id.to_string()],
+                        )
+                        .context(format!("Failed to update attachments of {}", message_id))?;
+                    }
+                    Ok(())
+                })
+                .await?;
+
+            if attachments_changed && !known_msg.is_used {
+                let stale: Vec<String> = known_msg
+                    .attachments
+                    .iter()
+                    .filter(|p| !new_paths.contains(p))
+                    .cloned()
+                    .collect();
+                remove_cache_files(&image_base_dir, &stale);
+            }
+            if content_changed || attachments_changed {
+                result.updated += 1;
+            }
+        }
+    }
+
+    span.record("checked", result.checked);
+    span.record("updated", result.updated);
+    span.record("deleted", result.deleted);
+    drop(span);
+    info!(
+        "Resync finished: {} checked, {} updated, {} deleted, {} channel(s) failed",
+        result.checked,
+        result.updated,
+        result.deleted,
+        result.failed_channels.len()
+    );
+    app_handle
+        .emit("indexing-status", "Resync complete")
+        .unwrap_or_default();
+    Ok(result)
+}
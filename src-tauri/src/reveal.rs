@@ -0,0 +1,174 @@
+use rusqlite::{params, OptionalExtension};
+use std::path::{Component, Path, PathBuf};
+use tauri::{AppHandle, State};
+use tauri_plugin_opener::OpenerExt;
+
+use crate::error::{AppError, ResultExt};
+use crate::log_info as info;
+use crate::providers::PathProvider;
+use crate::showcase_manager::{get_showcase_image_dir, get_showcase_presentation_dir};
+use crate::sqlite_manager::{retrieve_config, DbConnection};
+
+// Files handed to the OS default app. Others, like the database or anything executable, are
+// refused.
+const OPENABLE_EXTENSIONS: &[&str] = &[
+    "pptx", "mp4", "png", "jpg", "jpeg", "webp", "gif", "zip", "json", "html",
+];
+
+// `path` may be absolute or relative to the data directory, but must resolve (after symlinks)
+// to an existing folder or openable file inside it or inside the configured export directory.
+pub(crate) fn resolve_openable_path(
+    paths: &impl PathProvider,
+    export_dir: Option<&str>,
+    path: &str,
+) -> Result<PathBuf, AppError> {
+    let requested = Path::new(path.trim());
+    if requested.as_os_str().is_empty() {
+        return Err(AppError::invalid_input("No path given."));
+    }
+    if requested
+        .components()
+        .any(|c| matches!(c, Component::ParentDir))
+    {
+        return Err(AppError::invalid_input(format!(
+            "Path '{}' must not contain '..'.",
+            path
+        )));
+    }
+    let data_dir = paths.app_data_dir()?;
+    let data_dir = data_dir.canonicalize().map_err(|e| {
+        AppError::from(e).with_context(format!(
+            "Failed to resolve data directory '{}'",
+            data_dir.display()
+        ))
+    })?;
+    let resolved = match data_dir.join(requested).canonicalize() {
+        Ok(resolved) => resolved,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Err(AppError::not_found(format!("'{}' does not exist.", path)))
+        }
+        Err(e) => {
+            return Err(AppError::from(e).with_context(format!("Failed to resolve '{}'", path)))
+        }
+    };
+    // An export directory that does not exist (yet) holds nothing to open.
+    let export_dir = export_dir
+        .map(str::trim)
+        .filter(|dir| !dir.is_empty())
+        .and_then(|dir| Path::new(dir).canonicalize().ok());
+    let inside_export_dir = export_dir.map_or(false, |dir| resolved.starts_with(dir));
+    if !resolved.starts_with(&data_dir) && !inside_export_dir {
+        return Err(AppError::invalid_input(format!(
+            "'{}' is outside the app data and export directories.",
+            path
+        )));
+    }
+    if resolved.is_file() {
+        let extension = resolved
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_ascii_lowercase)
+            .unwrap_or_default();
+        if !OPENABLE_EXTENSIONS.contains(&extension.as_str()) {
+            return Err(AppError::invalid_input(format!(
+                "'{}' is not a file type the app opens.",
+                path
+            )));
+        }
+    }
+    Ok(resolved)
+}
+
+async fn load_export_directory(
+    db_state: &State<'_, DbConnection>,
+) -> Result<Option<String>, AppError> {
+    db_state
+        .0
+        .run(|conn| Ok(retrieve_config(conn)?.export_directory))
+        .await
+}
+
+#[tauri::command]
+pub async fn open_path(
+    app_handle: AppHandle,
+    path: String,
+    db_state: State<'_, DbConnection>,
+) -> Result<String, AppError> {
+    let export_dir = load_export_directory(&db_state).await?;
+    let resolved = resolve_openable_path(&app_handle, export_dir.as_deref(), &path)?;
+    let target = resolved.to_string_lossy().into_owned();
+    app_handle
+        .opener()
+        .open_path(target.as_str(), None::<&str>)
+        .map_err(|e| format!("Failed to open '{}': {}", target, e))?;
+    info!("Opened {}", target);
+    Ok(target)
+}
+
+// Shows the showcase's exported deck selected in the OS file manager, or opens its
+// presentations folder when there is no deck yet. `folder: "images"` opens the slide renders.
+#[tauri::command]
+pub async fn reveal_showcase_output(
+    app_handle: AppHandle,
+    id: String,
+    folder: Option<String>,
+    db_state: State<'_, DbConnection>,
+) -> Result<String, AppError> {
+    let lookup_id = id.clone();
+    let pptx_path: Option<Option<String>> = db_state
+        .0
+        .run(move |conn| {
+            conn.query_row(
+                "SELECT pptx_path FROM showcases WHERE id = ?1",
+                params![lookup_id],
+                |row| row.get(0),
+            )
+            .optional()
+            .context("DB error loading showcase")
+        })
+        .await?;
+    let Some(pptx_path) = pptx_path else {
+        return Err(AppError::not_found(format!(
+            "Showcase ID '{}' not found.",
+            id
+        )));
+    };
+
+    let (dir, deck) = match folder.as_deref().map(str::trim).unwrap_or("presentations") {
+        "" | "presentations" => (
+            get_showcase_presentation_dir(&app_handle, &id)?,
+            pptx_path.filter(|p| !p.is_empty()),
+        ),
+        "images" => (get_showcase_image_dir(&app_handle, &id)?, None),
+        other => {
+            return Err(AppError::invalid_input(format!(
+                "Unknown folder '{}'. Use 'presentations' or 'images'.",
+                other
+            )))
+        }
+    };
+
+    if let Some(deck) = deck {
+        let export_dir = load_export_directory(&db_state).await?;
+        if let Ok(deck) = resolve_openable_path(&app_handle, export_dir.as_deref(), &deck) {
+            app_handle
+                .opener()
+                .reveal_item_in_dir(&deck)
+                .map_err(|e| format!("Failed to reveal '{}': {}", deck.display(), e))?;
+            info!("Revealed {}", deck.display());
+            return Ok(deck.to_string_lossy().into_owned());
+        }
+    }
+    if !dir.is_dir() {
+        return Err(AppError::not_found(
+            "Nothing has been exported for this showcase yet.",
+        ));
+    }
+    let target = dir.to_string_lossy().into_owned();
+    app_handle
+        .opener()
+        .open_path(target.as_str(), None::<&str>)
+        .map_err(|e| format!("Failed to open '{}': {}", target, e))?;
+    info!("Opened {}", target);
+    Ok(target)
+}
@@ -0,0 +1,244 @@
+use keyring::Entry;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager, State};
+
+use crate::app_paths;
+use crate::db_encryption;
+use crate::error::{AppError, ResultExt};
+use crate::providers::KeyringSecrets;
+use crate::sqlite_manager::{get_backup_dir, get_db_path, DbConnection};
+use crate::KEYRING_SERVICE_NAME;
+use crate::{log_error as error, log_info as info, log_warn as warn};
+
+const DEFAULT_LOG_LINES: usize = 200;
+
+#[derive(serde::Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SafeModeReport {
+    pub failed_step: String, // "database" | "keyring"
+    pub reason: String,
+}
+
+// Set once during setup. When `report` is present the app runs in safe mode:
+// background tasks are not started and only the diagnostics commands are useful.
+#[derive(Default)]
+pub struct SafeModeState {
+    pub report: Option<SafeModeReport>,
+    pub log_path: Option<PathBuf>,
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SafeModeStatus {
+    pub active: bool,
+    pub failed_step: Option<String>,
+    pub reason: Option<String>,
+    pub data_dir: Option<String>,
+    pub data_dir_overridden: bool,
+    pub log_path: Option<String>,
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct DiagnosticCheck {
+    pub name: String,
+    pub ok: bool,
+    pub detail: String,
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseBackup {
+    pub path: String,
+    pub file_name: String,
+    pub size_bytes: u64,
+    pub modified_at: Option<i64>,
+}
+
+// A missing token is fine here; only a keyring that cannot be reached at all is a failure.
+pub fn probe_keyring() -> Result<(), String> {
+    let entry = Entry::new(KEYRING_SERVICE_NAME, "discordBotToken")
+        .context("Keyring error")?;
+    match entry.get_password() {
+        Ok(_) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(format!("Keyring is not accessible: {}", e)),
+    }
+}
+
+fn check_dir_writable(dir: &Path) -> Result<(), String> {
+    fs::create_dir_all(dir).context(format!("Cannot create '{}'", dir.display()))?;
+    let probe = dir.join(".write_test");
+    fs::write(&probe, b"ok").context(format!("Cannot write to '{}'", dir.display()))?;
+    fs::remove_file(&probe).ok();
+    Ok(())
+}
+
+fn check_database_file(db_path: &Path) -> Result<String, String> {
+    if !db_path.exists() {
+        return Ok("No database yet; a new one will be created.".to_string());
+    }
+    let conn = db_encryption::open_read_only(db_path, &KeyringSecrets)?;
+    let result: String = conn
+        .query_row("PRAGMA quick_check;", [], |row| row.get(0))
+        .context("Integrity check failed to run")?;
+    if result == "ok" {
+        Ok("Integrity check passed.".to_string())
+    } else {
+        Err(format!("Integrity check reported: {}", result))
+    }
+}
+
+fn diagnostic(name: &str, result: Result<String, String>) -> DiagnosticCheck {
+    match result {
+        Ok(detail) => DiagnosticCheck {
+            name: name.to_string(),
+            ok: true,
+            detail,
+        },
+        Err(detail) => DiagnosticCheck {
+            name: name.to_string(),
+            ok: false,
+            detail,
+        },
+    }
+}
+
+#[tauri::command]
+pub async fn get_safe_mode_status(
+    app_handle: AppHandle,
+    safe_mode: State<'_, SafeModeState>,
+) -> Result<SafeModeStatus, AppError> {
+    Ok(SafeModeStatus {
+        active: safe_mode.report.is_some(),
+        failed_step: safe_mode.report.as_ref().map(|r| r.failed_step.clone()),
+        reason: safe_mode.report.as_ref().map(|r| r.reason.clone()),
+        data_dir: app_paths::app_data_dir(&app_handle)
+            .ok()
+            .map(|dir| dir.display().to_string()),
+        data_dir_overridden: app_paths::data_dir_override(&app_handle).is_some(),
+        log_path: safe_mode
+            .log_path
+            .as_ref()
+            .map(|path| path.display().to_string()),
+    })
+}
+
+#[tauri::command]
+pub async fn read_recent_logs(
+    max_lines: Option<usize>,
+    safe_mode: State<'_, SafeModeState>,
+) -> Result<Vec<String>, AppError> {
+    let log_path = safe_mode
+        .log_path
+        .as_ref()
+        .ok_or_else(|| "Logging is not available in this session.".to_string())?;
+    let contents = fs::read_to_string(log_path)
+        .context(format!("Failed to read log file '{}'", log_path.display()))?;
+    let lines: Vec<&str> = contents.lines().collect();
+    let keep = max_lines.unwrap_or(DEFAULT_LOG_LINES).min(lines.len());
+    Ok(lines[lines.len() - keep..]
+        .iter()
+        .map(|line| line.to_string())
+        .collect())
+}
+
+#[tauri::command]
+pub async fn run_startup_diagnostics(app_handle: AppHandle) -> Result<Vec<DiagnosticCheck>, AppError> {
+    info!("Running startup diagnostics...");
+    let data_dir = app_paths::app_data_dir(&app_handle);
+    let mut checks = vec![diagnostic(
+        "Data directory",
+        data_dir.clone().and_then(|dir| {
+            check_dir_writable(&dir)?;
+            Ok(format!("{} is writable.", dir.display()))
+        }),
+    )];
+    checks.push(diagnostic(
+        "Database",
+        get_db_path(&app_handle).and_then(|path| check_database_file(&path)),
+    ));
+    checks.push(diagnostic(
+        "Keyring",
+        probe_keyring().map(|_| "Keyring is reachable.".to_string()),
+    ));
+
+    for check in checks.iter().filter(|c| !c.ok) {
+        warn!("Diagnostic '{}' failed: {}", check.name, check.detail);
+    }
+    Ok(checks)
+}
+
+#[tauri::command]
+pub async fn list_database_backups(app_handle: AppHandle) -> Result<Vec<DatabaseBackup>, AppError> {
+    let backup_dir = get_backup_dir(&app_handle)?;
+    let Ok(entries) = fs::read_dir(&backup_dir) else {
+        return Ok(Vec::new());
+    };
+
+    let mut backups: Vec<DatabaseBackup> = entries
+        .flatten()
+        .filter(|entry| entry.path().extension().map_or(false, |ext| ext == "db"))
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            Some(DatabaseBackup {
+                path: entry.path().display().to_string(),
+                file_name: entry.file_name().to_string_lossy().to_string(),
+                size_bytes: metadata.len(),
+                modified_at: metadata
+                    .modified()
+                    .ok()
+                    .map(|time| chrono::DateTime::<chrono::Utc>::from(time).timestamp()),
+            })
+        })
+        .collect();
+    backups.sort_by(|a, b| b.modified_at.cmp(&a.modified_at));
+    Ok(backups)
+}
+
+// The current database is moved aside rather than deleted, so a bad restore can be undone by hand.
+#[tauri::command]
+pub async fn restore_database_backup(
+    app_handle: AppHandle,
+    backup_path: String,
+) -> Result<(), AppError> {
+    if app_handle.try_state::<DbConnection>().is_some() {
+        return Err(AppError::conflict("Backups can only be restored in safe mode, while the database is not loaded."));
+    }
+    let backup_path = PathBuf::from(backup_path);
+    // Encrypted backups are accepted as long as the current key opens them.
+    if let Err(e) = db_encryption::open_read_only(&backup_path, &KeyringSecrets) {
+        return Err(AppError::invalid_input(format!("'{}' is not a usable database: {}", backup_path.display(), e)));
+    }
+
+    let db_path = get_db_path(&app_handle)?;
+    let suffix = chrono::Utc::now().format("%Y%m%d_%H%M%S").to_string();
+    for extension in ["db", "db-wal", "db-shm"] {
+        let path = db_path.with_extension(extension);
+        if path.exists() {
+            let moved = db_path.with_extension(format!("{}.broken-{}", extension, suffix));
+            fs::rename(&path, &moved)
+                .context(format!("Failed to move '{}' aside", path.display()))?;
+            info!("Moved {} to {}", path.display(), moved.display());
+        }
+    }
+
+    if let Err(e) = fs::copy(&backup_path, &db_path) {
+        error!("Failed to restore backup '{}': {}", backup_path.display(), e);
+        return Err(AppError::from(e).with_context("Failed to restore backup"));
+    }
+    info!("Restored database from {}", backup_path.display());
+    Ok(())
+}
+
+// Takes effect on the next launch; pass None to go back to the default location.
+#[tauri::command]
+pub async fn set_data_directory(app_handle: AppHandle, path: Option<String>) -> Result<(), AppError> {
+    match path {
+        Some(path) => {
+            let dir = PathBuf::from(path);
+            check_dir_writable(&dir)?;
+            Ok(app_paths::set_data_dir_override(&app_handle, Some(&dir))?)
+        }
+        None => Ok(app_paths::set_data_dir_override(&app_handle, None)?),
+    }
+}
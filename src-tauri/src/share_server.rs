@@ -0,0 +1,417 @@
+use chrono::Utc;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, State};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Mutex as AsyncMutex;
+use tokio::task::{JoinHandle, JoinSet};
+use uuid::Uuid;
+
+use crate::author_manager::resolve_showcase_credits;
+use crate::cache_crypto;
+use crate::error::{AppError, ResultExt};
+use crate::models::{Showcase, ShowcaseStatus};
+use crate::redaction::redact_text;
+use crate::showcase_manager::{find_slide_image, get_showcase_image_dir, load_showcase};
+use crate::sqlite_manager::{get_image_base_dir, retrieve_config, DbConnection, DbPool};
+use crate::{log_info as info, log_warn as warn};
+
+const MAX_REQUEST_HEAD_BYTES: usize = 8 * 1024;
+const REQUEST_READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub struct ShareServerHandle {
+    showcase_id: String,
+    url: String,
+    expires_at: Option<i64>,
+    task: JoinHandle<()>, // the accept loop, which owns the JoinSet of open connections
+}
+
+impl ShareServerHandle {
+    // Aborting the accept loop drops its JoinSet, which aborts every connection still being
+    // served, so a stopped or replaced link stops sending slides straight away.
+    fn shutdown(self) {
+        self.task.abort();
+    }
+}
+
+#[derive(Default)]
+pub struct ShareServerState(pub AsyncMutex<Option<ShareServerHandle>>);
+
+#[derive(serde::Serialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareServerStatus {
+    pub running: bool,
+    pub showcase_id: Option<String>,
+    pub url: Option<String>, // includes the access token
+    pub expires_at: Option<i64>,
+}
+
+struct ShareContext {
+    db: DbPool,
+    showcase_id: String,
+    showcase_dir: PathBuf,
+    image_base_dir: PathBuf,
+    token: String,
+    expires_at: Option<i64>,
+    gallery: AsyncMutex<Option<Arc<Gallery>>>, // what the last page load showed
+}
+
+struct Gallery {
+    title: String,
+    slides: Vec<SharedSlide>,
+}
+
+struct SharedSlide {
+    file: PathBuf,
+    from_cache: bool, // original attachment, possibly encrypted
+    credit: String,
+    caption: String,
+}
+
+async fn load_gallery(ctx: &ShareContext) -> Result<Gallery, String> {
+    let showcase_id = ctx.showcase_id.clone();
+    let (showcase, credits, redaction_rules) = ctx
+        .db
+        .run(move |conn| {
+            let redaction_rules = retrieve_config(conn)?.redaction_rules.unwrap_or_default();
+            let showcase = load_showcase(conn, &showcase_id)?;
+            let selected_messages = showcase.selected_messages.clone().unwrap_or_default();
+            let credits = resolve_showcase_credits(conn, &selected_messages)?;
+            Ok((showcase, credits, redaction_rules))
+        })
+        .await?;
+    let selected_messages = showcase.selected_messages.clone().unwrap_or_default();
+
+    let ordered: Vec<(String, usize, Option<String>)> = match &showcase.images {
+        Some(images) if !images.is_empty() => images
+            .iter()
+            .map(|i| (i.message_id.clone(), i.attachment_index, Some(i.slide_key())))
+            .collect(),
+        _ => selected_messages
+            .iter()
+            .flat_map(|m| (0..m.attachment_filenames().len()).map(move |index| (m.message_id.clone(), index, None)))
+            .collect(),
+    };
+
+    let slides = ordered
+        .into_iter()
+        .filter_map(|(message_id, attachment_index, slide_key)| {
+            let message = selected_messages.iter().find(|m| m.message_id == message_id)?;
+            // Prefer the edited slide image; fall back to the cached original before editing.
+            let edited = slide_key.and_then(|key| find_slide_image(&ctx.showcase_dir, &ctx.showcase_id, &key));
+            let (file, from_cache) = match edited {
+                Some(path) => (path, false),
+                None => {
+                    let relative = message.attachment_filenames().get(attachment_index)?.clone();
+                    if relative.contains("..") {
+                        return None;
+                    }
+                    (ctx.image_base_dir.join(relative), true)
+                }
+            };
+            let credit = credits
+                .iter()
+                .find(|c| c.message_id == message_id)
+                .map(|c| c.credit_text.clone())
+                .unwrap_or_else(|| message.author_name.clone());
+            Some(SharedSlide {
+                file,
+                from_cache,
+                credit,
+                caption: redact_text(&message.message_content, &redaction_rules),
+            })
+        })
+        .collect();
+    Ok(Gallery {
+        title: showcase.title,
+        slides,
+    })
+}
+
+// Rebuilt on every page load so a refresh shows the current slide order. Slide requests reuse
+// the gallery of the last page, so their indexes match what the reviewer sees and a page of
+// N slides doesn't reload the showcase N times.
+async fn current_gallery(ctx: &ShareContext, refresh: bool) -> Result<Arc<Gallery>, String> {
+    let mut cached = ctx.gallery.lock().await;
+    if let Some(gallery) = cached.as_ref().filter(|_| !refresh) {
+        return Ok(gallery.clone());
+    }
+    let gallery = Arc::new(load_gallery(ctx).await?);
+    *cached = Some(gallery.clone());
+    Ok(gallery)
+}
+
+pub(crate) fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn render_gallery(title: &str, slides: &[SharedSlide], token: &str) -> String {
+    let items: String = slides
+        .iter()
+        .enumerate()
+        .map(|(index, slide)| {
+            format!(
+                "<figure><div class=\"n\">{}</div><img loading=\"lazy\" src=\"slide/{}?token={}\" alt=\"Slide {}\"><figcaption><strong>{}</strong>{}</figcaption></figure>",
+                index + 1,
+                index,
+                token,
+                index + 1,
+                escape_html(&slide.credit),
+                if slide.caption.is_empty() {
+                    String::new()
+                } else {
+                    format!("<p>{}</p>", escape_html(&slide.caption))
+                }
+            )
+        })
+        .collect();
+    format!(
+        "<!doctype html><html><head><meta charset=\"utf-8\"><meta name=\"viewport\" content=\"width=device-width,initial-scale=1\"><meta name=\"robots\" content=\"noindex\"><title>{title}</title><style>body{{font-family:sans-serif;background:#000;color:#fff;margin:0;padding:24px}}h1{{margin:0 0 4px}}.meta{{color:#aaa;margin-bottom:24px}}figure{{margin:0 0 32px;max-width:960px}}img{{width:100%;border-radius:8px;background:#111}}.n{{color:#aaa;font-size:14px;margin-bottom:6px}}figcaption p{{color:#ccc;margin:4px 0 0;white-space:pre-wrap}}</style></head><body><h1>{title}</h1><div class=\"meta\">Draft preview &middot; {count} slide(s) &middot; read-only</div>{items}</body></html>",
+        title = escape_html(title),
+        count = slides.len(),
+        items = items
+    )
+}
+
+async fn write_response(stream: &mut TcpStream, status: &str, content_type: &str, body: &[u8], head_only: bool) {
+    // no-referrer keeps the token out of requests to other hosts; no-store keeps drafts out of shared caches.
+    let header = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nReferrer-Policy: no-referrer\r\nX-Robots-Tag: noindex\r\nConnection: close\r\n\r\n",
+        status,
+        content_type,
+        body.len()
+    );
+    if stream.write_all(header.as_bytes()).await.is_err() {
+        return;
+    }
+    if !head_only {
+        stream.write_all(body).await.ok();
+    }
+    stream.shutdown().await.ok();
+}
+
+async fn write_text(stream: &mut TcpStream, status: &str, message: &str) {
+    write_response(stream, status, "text/plain; charset=utf-8", message.as_bytes(), false).await;
+}
+
+async fn read_request_line(stream: &mut TcpStream) -> Option<String> {
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).await.ok()?;
+    // Drain the headers; nothing in them is needed.
+    let mut total = request_line.len();
+    loop {
+        let mut line = String::new();
+        let read = reader.read_line(&mut line).await.ok()?;
+        total += read;
+        if read == 0 || line == "\r\n" || line == "\n" || total > MAX_REQUEST_HEAD_BYTES {
+            break;
+        }
+    }
+    Some(request_line)
+}
+
+// Looks at every byte whatever the first mismatch, so response times don't tell how much of a
+// guessed token was right. Tokens all have the same length, so checking it first leaks nothing.
+pub(crate) fn tokens_match(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+async fn handle_connection(mut stream: TcpStream, ctx: Arc<ShareContext>) {
+    let request_line = match tokio::time::timeout(REQUEST_READ_TIMEOUT, read_request_line(&mut stream)).await {
+        Ok(Some(line)) => line,
+        _ => return,
+    };
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("");
+    let target = parts.next().unwrap_or("");
+    let head_only = method == "HEAD";
+    if method != "GET" && !head_only {
+        write_text(&mut stream, "405 Method Not Allowed", "This link is read-only.").await;
+        return;
+    }
+
+    // Paths stay relative in the page, so the gallery works unchanged behind a tunnel.
+    let Ok(url) = reqwest::Url::parse(&format!("http://localhost{}", target)) else {
+        write_text(&mut stream, "400 Bad Request", "Invalid request").await;
+        return;
+    };
+    let token = url
+        .query_pairs()
+        .find(|(key, _)| key == "token")
+        .map(|(_, value)| value.into_owned());
+    if !token.map_or(false, |token| tokens_match(&token, &ctx.token)) {
+        write_text(&mut stream, "403 Forbidden", "This review link is invalid.").await;
+        return;
+    }
+    if ctx.expires_at.map_or(false, |expires_at| Utc::now().timestamp() >= expires_at) {
+        write_text(&mut stream, "410 Gone", "This review link has expired.").await;
+        return;
+    }
+
+    let path = url.path();
+    let gallery = match current_gallery(&ctx, path == "/").await {
+        Ok(gallery) => gallery,
+        Err(e) => {
+            warn!("Share server: failed to load showcase {}: {}", ctx.showcase_id, e);
+            write_text(&mut stream, "500 Internal Server Error", "The showcase could not be loaded.").await;
+            return;
+        }
+    };
+
+    if path == "/" {
+        let page = render_gallery(&gallery.title, &gallery.slides, &ctx.token);
+        write_response(&mut stream, "200 OK", "text/html; charset=utf-8", page.as_bytes(), head_only).await;
+        return;
+    }
+    let Some(slide) = path
+        .strip_prefix("/slide/")
+        .and_then(|index| index.parse::<usize>().ok())
+        .and_then(|index| gallery.slides.get(index))
+    else {
+        write_text(&mut stream, "404 Not Found", "Not found").await;
+        return;
+    };
+
+    let bytes = match tokio::fs::read(&slide.file).await {
+        Ok(bytes) if slide.from_cache => cache_crypto::decrypt_if_encrypted(bytes),
+        Ok(bytes) => Ok(bytes),
+        Err(e) => Err(format!("Failed to read {}: {}", slide.file.display(), e)),
+    };
+    match bytes {
+        Ok(bytes) => {
+            let mime_type = mime_guess::from_path(&slide.file).first_or_octet_stream();
+            write_response(&mut stream, "200 OK", mime_type.essence_str(), &bytes, head_only).await;
+        }
+        Err(e) => {
+            warn!("Share server: {}", e);
+            write_text(&mut stream, "404 Not Found", "Slide image not available").await;
+        }
+    }
+}
+
+fn status_of(handle: Option<&ShareServerHandle>) -> ShareServerStatus {
+    match handle {
+        Some(handle) => ShareServerStatus {
+            running: true,
+            showcase_id: Some(handle.showcase_id.clone()),
+            url: Some(handle.url.clone()),
+            expires_at: handle.expires_at,
+        },
+        None => ShareServerStatus::default(),
+    }
+}
+
+// Review links are for showcases still being put together: drafts and those past phase 1.
+pub(crate) fn ensure_shareable(showcase: &Showcase) -> Result<(), AppError> {
+    match ShowcaseStatus::parse(&showcase.status) {
+        Some(ShowcaseStatus::Draft | ShowcaseStatus::InProgress) => Ok(()),
+        _ => Err(AppError::invalid_input(format!(
+            "Only showcases in progress can be shared for review ('{}' is {}).",
+            showcase.title, showcase.status
+        ))),
+    }
+}
+
+// Serves a read-only gallery of a draft on 127.0.0.1 only; to reach remote moderators, point a
+// tunnel (e.g. cloudflared or ngrok) at the port and share the tunnel URL with the same token.
+// Only one showcase is shared at a time; starting again replaces the previous link.
+#[tauri::command]
+pub async fn start_share_server(
+    showcase_id: String,
+    port: Option<u16>,
+    expires_in_minutes: Option<u32>,
+    app_handle: AppHandle,
+    db_state: State<'_, DbConnection>,
+    share_state: State<'_, ShareServerState>,
+) -> Result<ShareServerStatus, AppError> {
+    let lookup_id = showcase_id.clone();
+    let showcase = db_state
+        .0
+        .run(move |conn| Ok(load_showcase(conn, &lookup_id)?))
+        .await?;
+    ensure_shareable(&showcase)?;
+
+    let mut share_guard = share_state.0.lock().await;
+    if let Some(previous) = share_guard.take() {
+        previous.shutdown();
+    }
+
+    let listener = TcpListener::bind(("127.0.0.1", port.unwrap_or(0)))
+        .await
+        .context("Failed to start share server")?;
+    let local_port = listener
+        .local_addr()
+        .context("Failed to read share server address")?
+        .port();
+
+    let token = Uuid::new_v4().simple().to_string();
+    let expires_at = expires_in_minutes
+        .filter(|m| *m > 0)
+        .map(|m| Utc::now().timestamp() + i64::from(m) * 60);
+    let ctx = Arc::new(ShareContext {
+        db: db_state.0.clone(),
+        showcase_id: showcase_id.clone(),
+        showcase_dir: get_showcase_image_dir(&app_handle, &showcase_id)?,
+        image_base_dir: get_image_base_dir(&app_handle)?,
+        token: token.clone(),
+        expires_at,
+        gallery: AsyncMutex::new(None),
+    });
+
+    let task = tokio::spawn(async move {
+        let mut connections = JoinSet::new();
+        loop {
+            tokio::select! {
+                accepted = listener.accept() => match accepted {
+                    Ok((stream, _)) => {
+                        connections.spawn(handle_connection(stream, ctx.clone()));
+                    }
+                    Err(e) => warn!("Share server: connection failed: {}", e),
+                },
+                // Finished connections are reaped so the set only holds open ones.
+                Some(_) = connections.join_next(), if !connections.is_empty() => {}
+            }
+        }
+    });
+
+    let handle = ShareServerHandle {
+        showcase_id: showcase_id.clone(),
+        url: format!("http://127.0.0.1:{}/?token={}", local_port, token),
+        expires_at,
+        task,
+    };
+    info!("Sharing showcase {} for review on port {}", showcase_id, local_port);
+    let status = status_of(Some(&handle));
+    *share_guard = Some(handle);
+    Ok(status)
+}
+
+#[tauri::command]
+pub async fn stop_share_server(share_state: State<'_, ShareServerState>) -> Result<(), AppError> {
+    match share_state.0.lock().await.take() {
+        Some(handle) => {
+            info!("Stopped sharing showcase {}", handle.showcase_id);
+            handle.shutdown();
+            Ok(())
+        }
+        None => Err(AppError::conflict("No showcase is being shared.")),
+    }
+}
+
+#[tauri::command]
+pub async fn get_share_server_status(
+    share_state: State<'_, ShareServerState>,
+) -> Result<ShareServerStatus, AppError> {
+    Ok(status_of(share_state.0.lock().await.as_ref()))
+}
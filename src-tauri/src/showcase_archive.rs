@@ -0,0 +1,631 @@
+use chrono::Utc;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::{Read, Seek, Write};
+use std::path::{Component, Path, PathBuf};
+use tauri::{AppHandle, State};
+use zip::write::{SimpleFileOptions, ZipWriter};
+use zip::{CompressionMethod, ZipArchive};
+
+use crate::cache_crypto;
+use crate::discord::write_cache_file;
+use crate::error::{AppError, ResultExt};
+use crate::models::{
+    slide_key, SelectedMessage, Showcase, ShowcaseArchiveExport, ShowcaseArchiveImport,
+    ShowcaseImage, ShowcaseImagePack, ShowcaseStatus,
+};
+use crate::providers::PathProvider;
+use crate::showcase_manager::{
+    export_file_stem, find_slide_image, get_showcase_image_dir, get_showcase_image_pack,
+    insert_selected_messages, insert_showcase, load_showcase, load_showcase_status,
+    refresh_used_flags, write_showcase_image,
+};
+use crate::sqlite_manager::{get_image_base_dir, retrieve_config, DbConnection};
+use crate::version_manager::CURRENT_VERSION;
+use crate::{log_info as info, log_warn as warn};
+
+pub(crate) const ARCHIVE_EXTENSION: &str = "showcase";
+const ARCHIVE_FORMAT_VERSION: u32 = 1;
+const MANIFEST_ENTRY: &str = "showcase.json";
+// Attachments and avatars, under their path relative to the image cache.
+const CACHE_PREFIX: &str = "cache/";
+// Uploaded slide renders, named <slide_key>.<ext> so they can be renamed for the new ID.
+const SLIDES_PREFIX: &str = "slides/";
+// Discord attachments top out at 500 MiB; anything larger is not from an export.
+const MAX_ENTRY_SIZE: u64 = 512 * 1024 * 1024;
+// The declared size is only a hint, so it is not trusted for more than this up front.
+const MAX_ENTRY_PREALLOC: u64 = 16 * 1024 * 1024;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct ArchiveManifest {
+    pub format_version: u32,
+    pub app_version: String,
+    pub exported_at: i64,
+    pub title: String,
+    pub description: Option<String>,
+    pub status: String,
+    pub phase: i32,
+    pub created_at: i64,
+    #[serde(default)]
+    pub selected_messages: Vec<SelectedMessage>,
+    #[serde(default)]
+    pub images: Vec<ShowcaseImage>,
+}
+
+// Archive entries and cache paths must stay inside the folder they are extracted to.
+fn is_plain_relative(path: &str) -> bool {
+    !path.is_empty()
+        && Path::new(path)
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+}
+
+// Slide keys (from manifest message IDs) and image pack entries become a single file name.
+fn is_plain_file_name(name: &str) -> bool {
+    is_plain_relative(name) && !name.contains(['/', '\\'])
+}
+
+fn referenced_cache_files(messages: &[SelectedMessage]) -> BTreeSet<String> {
+    messages
+        .iter()
+        .flat_map(|m| {
+            m.attachment_filenames()
+                .into_iter()
+                .chain(m.author_avatar_path.clone())
+        })
+        .map(|path| path.replace('\\', "/"))
+        .filter(|path| is_plain_relative(path))
+        .collect()
+}
+
+fn slide_keys(showcase: &Showcase) -> Vec<String> {
+    match &showcase.images {
+        Some(images) if !images.is_empty() => images.iter().map(ShowcaseImage::slide_key).collect(),
+        _ => showcase
+            .selected_messages
+            .iter()
+            .flatten()
+            .flat_map(|m| {
+                (0..m.attachment_filenames().len())
+                    .map(move |index| slide_key(&m.message_id, index))
+            })
+            .collect(),
+    }
+}
+
+fn add_entry<W: Write + Seek>(
+    zip: &mut ZipWriter<W>,
+    name: &str,
+    bytes: &[u8],
+    options: SimpleFileOptions,
+) -> Result<(), String> {
+    zip.start_file(name, options)
+        .context(format!("Failed to add {} to the showcase archive", name))?;
+    zip.write_all(bytes)
+        .map_err(|e| format!("Failed to write {} to the showcase archive: {}", name, e))
+}
+
+// Cached files are stored decrypted, since the cache key does not travel with the archive.
+// Files that are gone from the cache are listed in `missing` instead of failing the export.
+pub(crate) fn write_showcase_archive<W: Write + Seek>(
+    writer: W,
+    paths: &impl PathProvider,
+    showcase: &Showcase,
+) -> Result<(W, ShowcaseArchiveExport), String> {
+    let image_base_dir = get_image_base_dir(paths)?;
+    let showcase_dir = get_showcase_image_dir(paths, &showcase.id)?;
+    let selected_messages = showcase.selected_messages.clone().unwrap_or_default();
+    let manifest = ArchiveManifest {
+        format_version: ARCHIVE_FORMAT_VERSION,
+        app_version: CURRENT_VERSION.to_string(),
+        exported_at: Utc::now().timestamp(),
+        title: showcase.title.clone(),
+        description: showcase.description.clone(),
+        status: showcase.status.clone(),
+        phase: showcase.phase,
+        created_at: showcase.created_at,
+        selected_messages,
+        images: showcase.images.clone().unwrap_or_default(),
+    };
+    let manifest_json = serde_json::to_vec_pretty(&manifest)
+        .context("Failed to serialize showcase archive manifest")?;
+
+    let json_options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let media_options = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+    let mut zip = ZipWriter::new(writer);
+    add_entry(&mut zip, MANIFEST_ENTRY, &manifest_json, json_options)?;
+
+    let mut files = 0;
+    let mut missing = Vec::new();
+    for relative_path in referenced_cache_files(&manifest.selected_messages) {
+        let bytes = fs::read(image_base_dir.join(&relative_path))
+            .map_err(|e| e.to_string())
+            .and_then(cache_crypto::decrypt_if_encrypted);
+        match bytes {
+            Ok(bytes) => {
+                let name = format!("{}{}", CACHE_PREFIX, relative_path);
+                add_entry(&mut zip, &name, &bytes, media_options)?;
+                files += 1;
+            }
+            Err(e) => {
+                warn!(
+                    "Leaving {} out of the showcase archive: {}",
+                    relative_path, e
+                );
+                missing.push(relative_path);
+            }
+        }
+    }
+    let mut slides = 0;
+    for key in slide_keys(showcase) {
+        let Some(render) = find_slide_image(&showcase_dir, &showcase.id, &key) else {
+            continue;
+        };
+        let extension = render
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("png")
+            .to_string();
+        let bytes = fs::read(&render)
+            .context(format!("Failed to read slide image '{}'", render.display()))?;
+        let name = format!("{}{}.{}", SLIDES_PREFIX, key, extension);
+        add_entry(&mut zip, &name, &bytes, media_options)?;
+        slides += 1;
+    }
+    let writer = zip.finish().context("Failed to finish showcase archive")?;
+    Ok((
+        writer,
+        ShowcaseArchiveExport {
+            path: String::new(),
+            files,
+            slides,
+            missing,
+        },
+    ))
+}
+
+fn read_entry<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    index: usize,
+) -> Result<(String, Vec<u8>), AppError> {
+    let mut entry = archive.by_index(index).map_err(|e| {
+        AppError::invalid_input(format!("Unreadable showcase archive entry: {}", e))
+    })?;
+    let name = entry.name().to_string();
+    let mut bytes = Vec::with_capacity(entry.size().min(MAX_ENTRY_PREALLOC) as usize);
+    (&mut entry)
+        .take(MAX_ENTRY_SIZE + 1)
+        .read_to_end(&mut bytes)
+        .map_err(|e| {
+            AppError::invalid_input(format!("Failed to read {} from the archive: {}", name, e))
+        })?;
+    if bytes.len() as u64 > MAX_ENTRY_SIZE {
+        return Err(AppError::invalid_input(format!(
+            "{} in the archive is larger than {} MiB.",
+            name,
+            MAX_ENTRY_SIZE / (1024 * 1024)
+        )));
+    }
+    Ok((name, bytes))
+}
+
+// Imports as a new showcase with a fresh ID, so importing the same archive twice (or on the
+// machine it came from) never overwrites anything. Cached files that already exist locally are
+// kept; the archive only fills in what is missing.
+pub(crate) fn import_showcase_archive_from<R: Read + Seek>(
+    reader: R,
+    paths: &impl PathProvider,
+    conn: &mut Connection,
+    encrypt_cache: bool,
+) -> Result<ShowcaseArchiveImport, AppError> {
+    let mut archive = ZipArchive::new(reader)
+        .map_err(|e| AppError::invalid_input(format!("Not a showcase archive: {}", e)))?;
+    let manifest: ArchiveManifest = {
+        let index = archive.index_for_name(MANIFEST_ENTRY).ok_or_else(|| {
+            AppError::invalid_input(format!(
+                "Not a showcase archive: {} is missing.",
+                MANIFEST_ENTRY
+            ))
+        })?;
+        let (_, json) = read_entry(&mut archive, index)?;
+        serde_json::from_slice(&json).map_err(|e| {
+            AppError::invalid_input(format!("Invalid showcase archive manifest: {}", e))
+        })?
+    };
+    if manifest.format_version > ARCHIVE_FORMAT_VERSION {
+        return Err(AppError::invalid_input(format!(
+            "This archive was made by a newer version of Showcase Studio ({}). Update to import it.",
+            manifest.app_version
+        )));
+    }
+
+    let image_base_dir = get_image_base_dir(paths)?;
+    let known_keys: BTreeSet<String> = manifest
+        .images
+        .iter()
+        .map(ShowcaseImage::slide_key)
+        .chain(manifest.selected_messages.iter().flat_map(|m| {
+            (0..m.attachment_filenames().len()).map(move |index| slide_key(&m.message_id, index))
+        }))
+        .filter(|key| is_plain_file_name(key))
+        .collect();
+    // Only what the selections point at; anything else in cache/ was not put there by an export.
+    let cache_files = referenced_cache_files(&manifest.selected_messages);
+
+    let tx = conn
+        .transaction()
+        .context("Failed to start the archive import")?;
+    let id = insert_showcase(&tx, &manifest.title, manifest.description.as_deref())?;
+    // The slides arrive unpacked, so an archived showcase comes back active.
+    let status = match ShowcaseStatus::parse(&manifest.status) {
+        Some(status) if status != ShowcaseStatus::Archived => status,
+        _ => ShowcaseStatus::Draft.after_phase(manifest.phase),
+    };
+    tx.execute(
+        "UPDATE showcases SET status = ?1, phase = ?2, created_at = ?3 WHERE id = ?4",
+        params![status.as_str(), manifest.phase, manifest.created_at, id],
+    )
+    .context("DB error restoring showcase details")?;
+    insert_selected_messages(&tx, &id, &manifest.selected_messages)?;
+    refresh_used_flags(
+        &tx,
+        manifest.selected_messages.iter().map(|m| &m.message_id),
+    )?;
+    for (position, image) in manifest.images.iter().enumerate() {
+        write_showcase_image(&tx, &id, image, Some(position as i64))?;
+    }
+
+    let showcase_dir = get_showcase_image_dir(paths, &id)?;
+    // Cache files this import created, removed again if it fails.
+    let mut written: Vec<PathBuf> = Vec::new();
+    let restored = (|| -> Result<(usize, usize), AppError> {
+        let mut files = 0;
+        let mut slides = 0;
+        for index in 0..archive.len() {
+            let (name, bytes) = read_entry(&mut archive, index)?;
+            if let Some(relative_path) = name.strip_prefix(CACHE_PREFIX) {
+                if !cache_files.contains(relative_path) {
+                    warn!("Skipping unreferenced archive entry {}", name);
+                    continue;
+                }
+                let path = image_base_dir.join(relative_path);
+                if !path.exists() {
+                    write_cache_file(&path, &bytes, encrypt_cache)?;
+                    written.push(path);
+                    files += 1;
+                }
+            } else if let Some(file_name) = name.strip_prefix(SLIDES_PREFIX) {
+                let Some((key, extension)) = file_name.rsplit_once('.') else {
+                    continue;
+                };
+                if !known_keys.contains(key)
+                    || !extension.chars().all(|c| c.is_ascii_alphanumeric())
+                {
+                    warn!("Skipping unknown slide image {} in archive", name);
+                    continue;
+                }
+                fs::create_dir_all(&showcase_dir)
+                    .context(format!("Failed to create '{}'", showcase_dir.display()))?;
+                let path = showcase_dir.join(format!("{}_{}.{}", id, key, extension));
+                fs::write(&path, &bytes)
+                    .context(format!("Failed to write '{}'", path.display()))?;
+                slides += 1;
+            }
+        }
+        Ok((files, slides))
+    })();
+    let (files_restored, slides_restored) = match restored.and_then(|counts| {
+        tx.commit()
+            .context("Failed to save the imported showcase")?;
+        Ok(counts)
+    }) {
+        Ok(counts) => counts,
+        Err(e) => {
+            let _ = fs::remove_dir_all(&showcase_dir);
+            for path in &written {
+                let _ = fs::remove_file(path);
+            }
+            return Err(e);
+        }
+    };
+
+    info!(
+        "Imported showcase archive as {} ({} cached file(s), {} slide image(s))",
+        id, files_restored, slides_restored
+    );
+    Ok(ShowcaseArchiveImport {
+        showcase_id: id,
+        title: manifest.title,
+        files_restored,
+        slides_restored,
+    })
+}
+
+// `dest` may be a file path or an existing folder, in which case the archive is named after
+// the showcase like other exports.
+#[tauri::command]
+pub async fn export_showcase_archive(
+    app_handle: AppHandle,
+    id: String,
+    dest: String,
+    db_state: State<'_, DbConnection>,
+) -> Result<ShowcaseArchiveExport, AppError> {
+    let dest = PathBuf::from(dest.trim());
+    if dest.as_os_str().is_empty() {
+        return Err(AppError::invalid_input(
+            "Choose where to save the showcase archive.",
+        ));
+    }
+    let lookup_id = id.clone();
+    let showcase = db_state
+        .0
+        .run(move |conn| Ok(load_showcase(conn, &lookup_id)?))
+        .await?;
+    let file_path = if dest.is_dir() {
+        dest.join(format!(
+            "{}.{}",
+            export_file_stem(&showcase.title, &id),
+            ARCHIVE_EXTENSION
+        ))
+    } else {
+        dest
+    };
+
+    tokio::task::spawn_blocking(move || -> Result<ShowcaseArchiveExport, String> {
+        if let Some(parent) = file_path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)
+                .context(format!("Failed to create '{}'", parent.display()))?;
+        }
+        let mut part_name = file_path.clone().into_os_string();
+        part_name.push(".part");
+        let part_path = PathBuf::from(part_name);
+        let file = fs::File::create(&part_path)
+            .context(format!("Failed to create '{}'", part_path.display()))?;
+        let result = write_showcase_archive(file, &app_handle, &showcase)
+            .and_then(|(file, export)| {
+                file.sync_all().map_err(|e| e.to_string())?;
+                Ok(export)
+            })
+            .and_then(|export| {
+                fs::rename(&part_path, &file_path)
+                    .context(format!("Failed to finalize '{}'", file_path.display()))?;
+                Ok(export)
+            });
+        if result.is_err() {
+            let _ = fs::remove_file(&part_path);
+        }
+        let export = result?;
+        info!(
+            "Exported showcase {} to archive {} ({} file(s), {} slide image(s), {} missing)",
+            showcase.id,
+            file_path.display(),
+            export.files,
+            export.slides,
+            export.missing.len()
+        );
+        Ok(ShowcaseArchiveExport {
+            path: file_path.to_string_lossy().into_owned(),
+            ..export
+        })
+    })
+    .await
+    .context("Archive export task panicked or was cancelled")?
+    .map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn import_showcase_archive(
+    app_handle: AppHandle,
+    path: String,
+    db_state: State<'_, DbConnection>,
+) -> Result<ShowcaseArchiveImport, AppError> {
+    let archive_path = PathBuf::from(path.trim());
+    let file = fs::File::open(&archive_path).map_err(|e| {
+        AppError::from(e).with_context(format!("Failed to open '{}'", archive_path.display()))
+    })?;
+    db_state
+        .0
+        .run(move |conn| {
+            let encrypt_cache = retrieve_config(conn)?.encrypt_image_cache.unwrap_or(false);
+            import_showcase_archive_from(file, &app_handle, conn, encrypt_cache)
+        })
+        .await
+}
+
+// Packs the slide renders of images/<id> into images/<id>.zip and removes them. Returns
+// what was packed; a showcase without renders packs nothing.
+pub(crate) fn pack_showcase_images(
+    paths: &impl PathProvider,
+    id: &str,
+) -> Result<ShowcaseImagePack, String> {
+    let image_dir = get_showcase_image_dir(paths, id)?;
+    let pack_path = get_showcase_image_pack(paths, id)?;
+    let mut pack = ShowcaseImagePack::default();
+    if !image_dir.is_dir() {
+        return Ok(pack);
+    }
+    let mut files: Vec<PathBuf> = fs::read_dir(&image_dir)
+        .context(format!("Failed to read '{}'", image_dir.display()))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .collect();
+    files.sort();
+    if files.is_empty() {
+        return Ok(pack);
+    }
+
+    let mut part_name = pack_path.clone().into_os_string();
+    part_name.push(".part");
+    let part_path = PathBuf::from(part_name);
+    let written: Result<(), String> = (|| {
+        let file = fs::File::create(&part_path)
+            .context(format!("Failed to create '{}'", part_path.display()))?;
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        let mut zip = ZipWriter::new(file);
+        for path in &files {
+            let name = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .ok_or_else(|| format!("Unsupported file name '{}'", path.display()))?;
+            let bytes = fs::read(path).context(format!("Failed to read '{}'", path.display()))?;
+            add_entry(&mut zip, name, &bytes, options)?;
+            pack.original_bytes += bytes.len() as u64;
+            pack.files += 1;
+        }
+        zip.finish()
+            .context(format!("Failed to finish '{}'", part_path.display()))?;
+        fs::rename(&part_path, &pack_path)
+            .map_err(|e| format!("Failed to finalize '{}': {}", pack_path.display(), e))
+    })();
+    if let Err(e) = written {
+        let _ = fs::remove_file(&part_path);
+        return Err(e);
+    }
+    pack.packed_bytes = fs::metadata(&pack_path).map_or(0, |m| m.len());
+
+    for path in &files {
+        if let Err(e) = fs::remove_file(path) {
+            warn!("Packed '{}' but could not remove it: {}", path.display(), e);
+        }
+    }
+    // Left in place if anything else is still in there.
+    let _ = fs::remove_dir(&image_dir);
+    Ok(pack)
+}
+
+// Extracts images/<id>.zip back into images/<id> and removes it. Renders uploaded since the
+// showcase was packed are kept over the packed copies.
+pub(crate) fn unpack_showcase_images(paths: &impl PathProvider, id: &str) -> Result<usize, String> {
+    let pack_path = get_showcase_image_pack(paths, id)?;
+    if !pack_path.exists() {
+        return Ok(0);
+    }
+    let image_dir = get_showcase_image_dir(paths, id)?;
+    fs::create_dir_all(&image_dir)
+        .context(format!("Failed to create '{}'", image_dir.display()))?;
+    let file =
+        fs::File::open(&pack_path).context(format!("Failed to open '{}'", pack_path.display()))?;
+    let mut archive = ZipArchive::new(file)
+        .context(format!("Unreadable image pack '{}'", pack_path.display()))?;
+    let mut restored = 0;
+    for index in 0..archive.len() {
+        let (name, bytes) = read_entry(&mut archive, index)?;
+        if !is_plain_file_name(&name) {
+            warn!(
+                "Skipping unexpected entry {} in {}",
+                name,
+                pack_path.display()
+            );
+            continue;
+        }
+        let target = image_dir.join(&name);
+        if target.exists() {
+            continue;
+        }
+        fs::write(&target, bytes).context(format!("Failed to write '{}'", target.display()))?;
+        restored += 1;
+    }
+    drop(archive);
+    fs::remove_file(&pack_path).context(format!("Failed to remove '{}'", pack_path.display()))?;
+    Ok(restored)
+}
+
+fn write_status(conn: &Connection, id: &str, status: ShowcaseStatus) -> Result<(), AppError> {
+    conn.execute(
+        "UPDATE showcases SET status = ?1, last_modified = ?2 WHERE id = ?3",
+        params![status.as_str(), Utc::now().timestamp(), id],
+    )
+    .context("DB error updating showcase status")?;
+    Ok(())
+}
+
+pub(crate) fn archive_showcase_data(
+    paths: &impl PathProvider,
+    conn: &Connection,
+    id: &str,
+) -> Result<ShowcaseImagePack, AppError> {
+    if load_showcase_status(conn, id)? == ShowcaseStatus::Archived {
+        return Err(AppError::conflict(format!(
+            "Showcase ID '{}' is already archived.",
+            id
+        )));
+    }
+    let pack = pack_showcase_images(paths, id)?;
+    if let Err(e) = write_status(conn, id, ShowcaseStatus::Archived) {
+        if let Err(restore_err) = unpack_showcase_images(paths, id) {
+            warn!(
+                "Failed to unpack images of showcase {}: {}",
+                id, restore_err
+            );
+        }
+        return Err(e);
+    }
+    Ok(pack)
+}
+
+// The status comes back from the phase, as if the showcase had just been worked on.
+pub(crate) fn unarchive_showcase_data(
+    paths: &impl PathProvider,
+    conn: &Connection,
+    id: &str,
+) -> Result<usize, AppError> {
+    if load_showcase_status(conn, id)? != ShowcaseStatus::Archived {
+        return Err(AppError::conflict(format!(
+            "Showcase ID '{}' is not archived.",
+            id
+        )));
+    }
+    let phase: i32 = conn
+        .query_row(
+            "SELECT phase FROM showcases WHERE id = ?1",
+            params![id],
+            |row| row.get(0),
+        )
+        .context("DB error loading showcase phase")?;
+    let restored = unpack_showcase_images(paths, id)?;
+    write_status(conn, id, ShowcaseStatus::Draft.after_phase(phase))?;
+    Ok(restored)
+}
+
+// Archived showcases drop out of the default list and have their slide renders packed into
+// one zip, unpacked again by unarchive_showcase.
+#[tauri::command]
+pub async fn archive_showcase(
+    app_handle: AppHandle,
+    id: String,
+    db_state: State<'_, DbConnection>,
+) -> Result<ShowcaseImagePack, AppError> {
+    db_state
+        .0
+        .run(move |conn| {
+            let pack = archive_showcase_data(&app_handle, conn, &id)?;
+            info!(
+                "Archived showcase ID {}: packed {} file(s), {} -> {} bytes",
+                id, pack.files, pack.original_bytes, pack.packed_bytes
+            );
+            Ok(pack)
+        })
+        .await
+}
+
+#[tauri::command]
+pub async fn unarchive_showcase(
+    app_handle: AppHandle,
+    id: String,
+    db_state: State<'_, DbConnection>,
+) -> Result<usize, AppError> {
+    db_state
+        .0
+        .run(move |conn| {
+            let restored = unarchive_showcase_data(&app_handle, conn, &id)?;
+            info!(
+                "Unarchived showcase ID {}: {} file(s) restored",
+                id, restored
+            );
+            Ok(restored)
+        })
+        .await
+}
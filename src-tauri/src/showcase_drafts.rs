@@ -0,0 +1,159 @@
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+use tauri::State;
+
+use crate::error::{AppError, ResultExt};
+use crate::log_info as info;
+use crate::models::{ShowcaseDraft, ShowcaseStatus};
+use crate::showcase_manager::load_showcase_status;
+use crate::sqlite_manager::DbConnection;
+
+// Only the editing phases keep work that is not saved as it happens.
+const DRAFT_PHASES: std::ops::RangeInclusive<i32> = 2..=3;
+// A draft is rewritten on every autosave; anything this large is not editor state.
+pub(crate) const MAX_DRAFT_BYTES: usize = 2 * 1024 * 1024;
+
+fn check_draft_phase(phase: i32) -> Result<(), AppError> {
+    if DRAFT_PHASES.contains(&phase) {
+        Ok(())
+    } else {
+        Err(AppError::invalid_input(format!(
+            "Drafts are kept for phases {} to {} only.",
+            DRAFT_PHASES.start(),
+            DRAFT_PHASES.end()
+        )))
+    }
+}
+
+pub(crate) fn write_draft(
+    conn: &Connection,
+    id: &str,
+    phase: i32,
+    draft_json: &str,
+) -> Result<ShowcaseDraft, AppError> {
+    check_draft_phase(phase)?;
+    if draft_json.len() > MAX_DRAFT_BYTES {
+        return Err(AppError::invalid_input(format!(
+            "Draft is too large ({} bytes, at most {}).",
+            draft_json.len(),
+            MAX_DRAFT_BYTES
+        )));
+    }
+    serde_json::from_str::<serde_json::Value>(draft_json)
+        .map_err(|e| AppError::invalid_input(format!("Draft is not valid JSON: {}", e)))?;
+    if load_showcase_status(conn, id)? == ShowcaseStatus::Archived {
+        return Err(AppError::conflict(format!(
+            "Showcase ID '{}' is archived.",
+            id
+        )));
+    }
+
+    let updated_at = Utc::now().timestamp();
+    conn.execute(
+        "INSERT INTO showcase_drafts (showcase_id, phase, draft_json, updated_at) VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(showcase_id, phase) DO UPDATE SET
+            draft_json = excluded.draft_json,
+            updated_at = excluded.updated_at",
+        params![id, phase, draft_json, updated_at],
+    )
+    .context("DB error saving showcase draft")?;
+    Ok(ShowcaseDraft {
+        showcase_id: id.to_string(),
+        phase,
+        draft_json: draft_json.to_string(),
+        updated_at,
+    })
+}
+
+// The draft of `phase`, or of the showcase's current phase when none is given.
+pub(crate) fn load_draft(
+    conn: &Connection,
+    id: &str,
+    phase: Option<i32>,
+) -> Result<Option<ShowcaseDraft>, AppError> {
+    let phase = match phase {
+        Some(phase) => phase,
+        None => conn
+            .query_row(
+                "SELECT phase FROM showcases WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()
+            .context("DB error loading showcase phase")?
+            .ok_or_else(|| AppError::not_found(format!("Showcase ID '{}' not found.", id)))?,
+    };
+    conn.query_row(
+        "SELECT draft_json, updated_at FROM showcase_drafts WHERE showcase_id = ?1 AND phase = ?2",
+        params![id, phase],
+        |row| {
+            Ok(ShowcaseDraft {
+                showcase_id: id.to_string(),
+                phase,
+                draft_json: row.get(0)?,
+                updated_at: row.get(1)?,
+            })
+        },
+    )
+    .optional()
+    .context("DB error loading showcase draft")
+}
+
+// Drafts of phases the showcase has moved past are done with. Call when the phase changes.
+pub(crate) fn clear_completed_drafts(
+    conn: &Connection,
+    id: &str,
+    phase: i32,
+) -> Result<usize, String> {
+    conn.execute(
+        "DELETE FROM showcase_drafts WHERE showcase_id = ?1 AND phase < ?2",
+        params![id, phase],
+    )
+    .map_err(|e| format!("DB error clearing showcase drafts: {}", e))
+}
+
+#[tauri::command]
+pub async fn save_showcase_draft(
+    id: String,
+    phase: i32,
+    draft_json: String,
+    db_state: State<'_, DbConnection>,
+) -> Result<ShowcaseDraft, AppError> {
+    db_state
+        .0
+        .run(move |conn| write_draft(conn, &id, phase, &draft_json))
+        .await
+}
+
+#[tauri::command]
+pub async fn get_showcase_draft(
+    id: String,
+    phase: Option<i32>,
+    db_state: State<'_, DbConnection>,
+) -> Result<Option<ShowcaseDraft>, AppError> {
+    db_state
+        .0
+        .run(move |conn| load_draft(conn, &id, phase))
+        .await
+}
+
+// Throws away unsaved work, e.g. when the user declines to restore it.
+#[tauri::command]
+pub async fn discard_showcase_draft(
+    id: String,
+    phase: i32,
+    db_state: State<'_, DbConnection>,
+) -> Result<(), AppError> {
+    db_state
+        .0
+        .run(move |conn| {
+            conn.execute(
+                "DELETE FROM showcase_drafts WHERE showcase_id = ?1 AND phase = ?2",
+                params![id, phase],
+            )
+            .context("DB error discarding showcase draft")?;
+            info!("Discarded phase {} draft of showcase ID: {}", phase, id);
+            Ok(())
+        })
+        .await
+}
@@ -1,34 +1,71 @@
-use crate::models::{SelectedMessage, Showcase, ShowcaseImage, UpdateShowcasePayload};
-use crate::sqlite_manager::DbConnection;
+use crate::app_paths;
+use crate::author_manager::resolve_showcase_credits;
+use crate::error::{AppError, ErrorKind, ResultExt};
+use crate::export_pipeline::ExportJobsState;
+use crate::spans::{self, Span};
+use crate::redaction::redact_text;
+use crate::locale_format::{format_date, format_number, DEFAULT_LOCALE};
+use crate::models::{
+    AppConfig, DiffAuthor, ExportArtifact, ExportManifest, ExportManifestFile, ExportVerification, RehearsalRun, SelectedMessage, Showcase, ShowcaseCredit, ShowcaseDiff, ShowcaseFieldChange,
+    ShowcaseImage, ShowcaseImagePatch, ShowcasePage, ShowcaseQuery, ShowcaseRenderInfo, ShowcaseStatus, ShowcaseSummary, SlideRenderInfo, SlideUploadResult, UpdateShowcasePayload,
+    slide_key,
+};
+use crate::providers::PathProvider;
+use crate::showcase_drafts::clear_completed_drafts;
+use crate::showcase_revisions::{
+    record_revision, ACTION_EDIT_IMAGE, ACTION_MOVE_IMAGE, ACTION_REMOVE_IMAGE,
+    ACTION_SAVE_SELECTION, ACTION_SHUFFLE_IMAGES, ACTION_SORT_IMAGES, ACTION_UPLOAD_IMAGE,
+};
+use crate::sqlite_manager::{retrieve_config, DbConnection};
+use crate::version_manager::CURRENT_VERSION;
 use crate::{log_error as error, log_info as info, log_warn as warn};
 
 use base64::{engine::general_purpose::STANDARD as base64_engine, Engine as _};
 use chrono::Utc;
-use rusqlite::{params, types::Value as RusqliteValue, Error as RusqliteError, Row};
+use rusqlite::{params, types::Value as RusqliteValue, Connection, Error as RusqliteError, OptionalExtension, Row};
 use serde::Deserialize;
+use sha2::{Digest, Sha256};
 use serde_json;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::io::Write;
-use std::path::PathBuf;
-use tauri::{AppHandle, Manager, State};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::{AppHandle, State};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
 use uuid::Uuid;
 
-fn get_showcase_image_dir(app_handle: &AppHandle, showcase_id: &str) -> Result<PathBuf, String> {
-    let app_data_dir = app_handle
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data dir: {}", e))?;
+pub(crate) fn get_showcase_image_dir(paths: &impl PathProvider, showcase_id: &str) -> Result<PathBuf, String> {
+    let app_data_dir = paths.app_data_dir()?;
     Ok(app_data_dir.join("images").join(showcase_id))
 }
 
-fn get_showcase_presentation_dir(
-    app_handle: &AppHandle,
+// Where archive_showcase packs the image folder, next to it.
+pub(crate) fn get_showcase_image_pack(paths: &impl PathProvider, showcase_id: &str) -> Result<PathBuf, String> {
+    let app_data_dir = paths.app_data_dir()?;
+    Ok(app_data_dir.join("images").join(format!("{}.zip", showcase_id)))
+}
+
+// The uploaded render of a slide, whatever extension its data URI had.
+pub(crate) fn find_slide_image(showcase_dir: &Path, showcase_id: &str, slide_key: &str) -> Option<PathBuf> {
+    let prefix = format!("{}_{}.", showcase_id, slide_key);
+    fs::read_dir(showcase_dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .find(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .map_or(false, |n| n.starts_with(&prefix))
+        })
+}
+
+pub(crate) fn get_showcase_presentation_dir(
+    paths: &impl PathProvider,
     showcase_id: &str,
 ) -> Result<PathBuf, String> {
-    let app_data_dir = app_handle
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data dir: {}", e))?;
+    let app_data_dir = paths.app_data_dir()?;
     Ok(app_data_dir.join("presentations").join(showcase_id))
 }
 
@@ -64,28 +101,32 @@ fn decode_base64_image(data_uri: &str) -> Result<(Vec<u8>, String), String> {
     Ok((bytes, extension.to_string()))
 }
 
-fn map_row_to_showcase(row: &Row) -> Result<Showcase, RusqliteError> {
-    fn parse_json_col<T: for<'de> Deserialize<'de>>(
-        row: &Row,
-        idx: usize,
-        col_name: &str,
-    ) -> Result<Option<T>, RusqliteError> {
-        let raw: Option<String> = row.get(idx)?;
-        if let Some(ref s) = raw {
-            if !s.trim().is_empty() && s.trim() != "null" {
-                return serde_json::from_str(s).map(Some).map_err(|e| {
-                    error!("❌ JSON parse error in column `{}`: {}", col_name, e);
-                    RusqliteError::FromSqlConversionFailure(
-                        idx,
-                        rusqlite::types::Type::Text,
-                        Box::new(e),
-                    )
-                });
-            }
+fn parse_json_col<T: for<'de> Deserialize<'de>>(
+    row: &Row,
+    idx: usize,
+    col_name: &str,
+) -> Result<Option<T>, RusqliteError> {
+    let raw: Option<String> = row.get(idx)?;
+    if let Some(ref s) = raw {
+        if !s.trim().is_empty() && s.trim() != "null" {
+            return serde_json::from_str(s).map(Some).map_err(|e| {
+                error!("❌ JSON parse error in column `{}`: {}", col_name, e);
+                RusqliteError::FromSqlConversionFailure(
+                    idx,
+                    rusqlite::types::Type::Text,
+                    Box::new(e),
+                )
+            });
         }
-        Ok(None)
     }
+    Ok(None)
+}
+
+const SHOWCASE_COLUMNS: &str =
+    "id, title, description, status, created_at, last_modified, phase, pptx_path, rehearsal_json, exports_json, deleted_at, google_slides_url";
 
+// Selected messages and images live in their own tables; with_children fills them in.
+fn map_row_to_showcase(row: &Row) -> Result<Showcase, RusqliteError> {
     Ok(Showcase {
         id: row.get(0)?,
         title: row.get(1)?,
@@ -94,33 +135,248 @@ fn map_row_to_showcase(row: &Row) -> Result<Showcase, RusqliteError> {
         created_at: row.get(4)?,
         last_modified: row.get(5)?,
         phase: row.get(6)?,
-        selected_messages: parse_json_col(row, 7, "selected_messages_json")?,
-        pptx_path: row.get(8)?,
-        images: parse_json_col(row, 9, "images_json")?,
+        selected_messages: None,
+        pptx_path: row.get(7)?,
+        images: None,
+        rehearsal: parse_json_col(row, 8, "rehearsal_json")?,
+        exports: parse_json_col(row, 9, "exports_json")?.unwrap_or_default(),
+        deleted_at: row.get(10)?,
+        google_slides_url: row.get(11)?,
+    })
+}
+
+const SHOWCASE_MESSAGE_COLUMNS: &str = "message_id, channel_id, author_id, author_name, author_avatar, message_content, attachment_filenames_json, timestamp, reply_to_json, author_avatar_path";
+
+fn map_row_to_selected_message(row: &Row) -> Result<SelectedMessage, RusqliteError> {
+    let filenames: Vec<String> =
+        parse_json_col(row, 6, "attachment_filenames_json")?.unwrap_or_default();
+    Ok(SelectedMessage {
+        message_id: row.get(0)?,
+        channel_id: row.get(1)?,
+        author_id: row.get(2)?,
+        author_name: row.get(3)?,
+        author_avatar: row.get(4)?,
+        message_content: row.get(5)?,
+        selected_attachment_filename: filenames.first().cloned().unwrap_or_default(),
+        selected_attachment_filenames: filenames,
+        timestamp: row.get(7)?,
+        reply_to: parse_json_col(row, 8, "reply_to_json")?,
+        author_avatar_path: row.get(9)?,
+    })
+}
+
+const SHOWCASE_IMAGE_COLUMNS: &str =
+    "message_id, attachment_index, sender, avatar, message, is_edited, overlay_json, credit, slide_title, notes";
+// Read after the stored columns; written through the `position` argument of write_showcase_image.
+const SHOWCASE_IMAGE_SELECT: &str =
+    "message_id, attachment_index, sender, avatar, message, is_edited, overlay_json, credit, slide_title, notes, position";
+
+fn map_row_to_showcase_image(row: &Row) -> Result<ShowcaseImage, RusqliteError> {
+    let overlay = parse_json_col(row, 6, "overlay_json")?.ok_or_else(|| {
+        RusqliteError::FromSqlConversionFailure(
+            6,
+            rusqlite::types::Type::Null,
+            "showcase image has no overlay settings".into(),
+        )
+    })?;
+    Ok(ShowcaseImage {
+        message_id: row.get(0)?,
+        attachment_index: row.get::<_, i64>(1)? as usize,
+        sender: row.get(2)?,
+        avatar: row.get(3)?,
+        message: row.get(4)?,
+        is_edited: row.get(5)?,
+        overlay,
+        credit: row.get(7)?,
+        slide_title: row.get(8)?,
+        notes: row.get(9)?,
+        sort_index: row.get(10)?,
     })
 }
 
+fn ensure_showcase_exists(conn: &Connection, id: &str) -> Result<(), AppError> {
+    let exists: bool = conn
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM showcases WHERE id = ?1)",
+            params![id],
+            |row| row.get(0),
+        )
+        .context("DB error looking up showcase")?;
+    if exists {
+        Ok(())
+    } else {
+        Err(AppError::not_found(format!("Showcase ID '{}' not found.", id)))
+    }
+}
+
+// Rows written before statuses were checked read as drafts.
+pub(crate) fn load_showcase_status(conn: &Connection, id: &str) -> Result<ShowcaseStatus, AppError> {
+    let status: Option<String> = conn
+        .query_row("SELECT status FROM showcases WHERE id = ?1", params![id], |row| row.get(0))
+        .optional()
+        .context("DB error looking up showcase status")?;
+    match status {
+        Some(status) => Ok(ShowcaseStatus::parse(&status).unwrap_or(ShowcaseStatus::Draft)),
+        None => Err(AppError::not_found(format!("Showcase ID '{}' not found.", id))),
+    }
+}
+
+pub(crate) fn load_selected_messages(conn: &Connection, id: &str) -> Result<Vec<SelectedMessage>, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM showcase_messages WHERE showcase_id = ?1 ORDER BY position",
+            SHOWCASE_MESSAGE_COLUMNS
+        ))
+        .context("Failed to prepare selected message query")?;
+    let messages = stmt
+        .query_map(params![id], map_row_to_selected_message)
+        .context("DB error getting selected messages")?
+        .collect::<Result<Vec<_>, _>>()
+        .context("Error reading selected message row")?;
+    Ok(messages)
+}
+
+pub(crate) fn load_showcase_images(conn: &Connection, id: &str) -> Result<Vec<ShowcaseImage>, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM showcase_images WHERE showcase_id = ?1 ORDER BY position",
+            SHOWCASE_IMAGE_SELECT
+        ))
+        .context("Failed to prepare showcase image query")?;
+    let images = stmt
+        .query_map(params![id], map_row_to_showcase_image)
+        .context("DB error getting showcase images")?
+        .collect::<Result<Vec<_>, _>>()
+        .context("Error reading showcase image row")?;
+    Ok(images)
+}
+
+// Empty child tables read as None, the way NULL JSON columns used to.
+fn with_children(conn: &Connection, mut showcase: Showcase) -> Result<Showcase, String> {
+    let selected_messages = load_selected_messages(conn, &showcase.id)?;
+    let images = load_showcase_images(conn, &showcase.id)?;
+    showcase.selected_messages = (!selected_messages.is_empty()).then_some(selected_messages);
+    showcase.images = (!images.is_empty()).then_some(images);
+    Ok(showcase)
+}
+
+pub(crate) fn insert_selected_messages(
+    conn: &Connection,
+    id: &str,
+    selected_messages: &[SelectedMessage],
+) -> Result<(), String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "INSERT INTO showcase_messages (showcase_id, position, {}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+             ON CONFLICT(showcase_id, message_id) DO NOTHING",
+            SHOWCASE_MESSAGE_COLUMNS
+        ))
+        .context("Failed to prepare selected message insert")?;
+    for (position, message) in selected_messages.iter().enumerate() {
+        let filenames_json = serde_json::to_string(&message.attachment_filenames())
+            .context("Failed to serialize attachment filenames")?;
+        let reply_to_json = message
+            .reply_to
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()
+            .context("Failed to serialize reply context")?;
+        stmt.execute(params![
+            id,
+            position as i64,
+            message.message_id,
+            message.channel_id,
+            message.author_id,
+            message.author_name,
+            message.author_avatar,
+            message.message_content,
+            filenames_json,
+            message.timestamp,
+            reply_to_json,
+            message.author_avatar_path,
+        ])
+        .context(format!("DB error saving selected message {}", message.message_id))?;
+    }
+    Ok(())
+}
+
+// Inserts or replaces the image of one slide. Without a position a new slide goes last and an
+// existing one keeps its place.
+pub(crate) fn write_showcase_image(
+    conn: &Connection,
+    id: &str,
+    image: &ShowcaseImage,
+    position: Option<i64>,
+) -> Result<(), String> {
+    let overlay_json = serde_json::to_string(&image.overlay)
+        .context("Failed to serialize overlay settings")?;
+    conn.execute(
+        &format!(
+            "INSERT INTO showcase_images (showcase_id, slide_key, position, {})
+             VALUES (?1, ?2, COALESCE(?3, (SELECT COALESCE(MAX(position) + 1, 0) FROM showcase_images WHERE showcase_id = ?1)), ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
+             ON CONFLICT(showcase_id, slide_key) DO UPDATE SET
+                 position = COALESCE(?3, showcase_images.position),
+                 message_id = excluded.message_id,
+                 attachment_index = excluded.attachment_index,
+                 sender = excluded.sender,
+                 avatar = excluded.avatar,
+                 message = excluded.message,
+                 is_edited = excluded.is_edited,
+                 overlay_json = excluded.overlay_json,
+                 credit = excluded.credit,
+                 slide_title = excluded.slide_title,
+                 notes = excluded.notes",
+            SHOWCASE_IMAGE_COLUMNS
+        ),
+        params![
+            id,
+            image.slide_key(),
+            position,
+            image.message_id,
+            image.attachment_index as i64,
+            image.sender,
+            image.avatar,
+            image.message,
+            image.is_edited,
+            overlay_json,
+            image.credit,
+            image.slide_title,
+            image.notes,
+        ],
+    )
+    .context(format!("DB error saving image of slide {}", image.slide_key()))?;
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn create_showcase(
     title: String,
     description: Option<String>,
     db_state: State<'_, DbConnection>,
-) -> Result<String, String> {
+) -> Result<String, AppError> {
     info!("Attempting to create showcase: title='{}'", title);
+    db_state
+        .0
+        .run(move |conn| {
+            insert_showcase(conn, &title, description.as_deref())
+        })
+        .await
+}
+
+pub(crate) fn insert_showcase(
+    conn: &Connection,
+    title: &str,
+    description: Option<&str>,
+) -> Result<String, AppError> {
     let new_id = Uuid::new_v4().to_string();
     let current_ts = Utc::now().timestamp();
-    let status_val = "Draft";
+    let status_val = ShowcaseStatus::Draft.as_str();
     let initial_phase = 1;
 
-    let conn_guard = db_state
-        .0
-        .lock()
-        .map_err(|e| format!("DB lock error: {}", e))?;
-
-    let result = conn_guard.execute(
-        "INSERT INTO showcases (id, title, description, status, created_at, last_modified, phase, selected_messages_json, images_json, pptx_path) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, NULL, NULL, NULL)",
+    let result = conn.execute(
+        "INSERT INTO showcases (id, title, description, status, created_at, last_modified, phase, pptx_path) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, NULL)",
         params![
-            &new_id, &title, &description, status_val,
+            &new_id, title, description, status_val,
             current_ts, current_ts, initial_phase
         ],
     );
@@ -130,10 +386,13 @@ pub async fn create_showcase(
             info!("Showcase created successfully with ID: {}", new_id);
             Ok(new_id)
         }
-        Ok(_) => Err("Failed to create showcase (0 rows affected). Check constraints.".to_string()),
+        Ok(_) => Err(AppError::new(
+            ErrorKind::Database,
+            "Failed to create showcase (0 rows affected). Check constraints.",
+        )),
         Err(e) => {
             error!("Error creating showcase: {}", e);
-            Err(format!("Database error creating showcase: {}", e))
+            Err(AppError::from(e).with_context("Database error creating showcase"))
         }
     }
 }
@@ -143,29 +402,77 @@ pub async fn update_showcase_phase(
     id: String,
     phase: i32,
     db_state: State<'_, DbConnection>,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     info!("Updating phase for showcase ID: {} to {}", id, phase);
     if !(1..=4).contains(&phase) {
-        return Err("Invalid phase value provided (must be 1-4).".to_string());
+        return Err(AppError::invalid_input("Invalid phase value provided (must be 1-4)."));
     }
-    let conn_guard = db_state
+    db_state
         .0
-        .lock()
-        .map_err(|e| format!("DB lock error: {}", e))?;
-    let current_ts = Utc::now().timestamp();
-    let rows = conn_guard
-        .execute(
-            "UPDATE showcases SET phase = ?1, last_modified = ?2 WHERE id = ?3",
-            params![phase, current_ts, &id],
-        )
-        .map_err(|e| format!("DB error updating phase: {}", e))?;
+        .run(move |conn| {
+            let status = load_showcase_status(conn, &id)?;
+            if status == ShowcaseStatus::Archived {
+                return Err(AppError::conflict(format!(
+                    "Showcase ID '{}' is archived. Unarchive it before changing its phase.",
+                    id
+                )));
+            }
+            let current_ts = Utc::now().timestamp();
+            let rows = conn
+                .execute(
+                    "UPDATE showcases SET phase = ?1, status = ?2, last_modified = ?3 WHERE id = ?4",
+                    params![phase, status.after_phase(phase).as_str(), current_ts, &id],
+                )
+                .context("DB error updating phase")?;
+            clear_completed_drafts(conn, &id, phase)?;
 
-    if rows == 0 {
-        Err(format!("Showcase ID '{}' not found for phase update.", id))
-    } else {
-        info!("Phase updated successfully for showcase ID: {}", id);
-        Ok(())
-    }
+            if rows == 0 {
+                Err(AppError::not_found(format!("Showcase ID '{}' not found for phase update.", id)))
+            } else {
+                info!("Phase updated successfully for showcase ID: {}", id);
+                Ok(())
+            }
+        })
+        .await
+}
+
+// Replaces the stored practice run; None clears it. Not an edit, so last_modified is left alone.
+#[tauri::command]
+pub async fn save_showcase_rehearsal(
+    id: String,
+    rehearsal: Option<RehearsalRun>,
+    db_state: State<'_, DbConnection>,
+) -> Result<(), AppError> {
+    let rehearsal_json = rehearsal
+        .as_ref()
+        .map(serde_json::to_string)
+        .transpose()
+        .context("Failed to serialize rehearsal")?;
+    db_state
+        .0
+        .run(move |conn| {
+            let rows = conn
+                .execute(
+                    "UPDATE showcases SET rehearsal_json = ?1 WHERE id = ?2",
+                    params![rehearsal_json, &id],
+                )
+                .context("DB error saving rehearsal")?;
+
+            if rows == 0 {
+                return Err(AppError::not_found(format!("Showcase ID '{}' not found for rehearsal update.", id)));
+            }
+            match rehearsal {
+                Some(run) => info!(
+                    "Saved rehearsal for showcase {}: {} slide(s), {} ms",
+                    id,
+                    run.slides.len(),
+                    run.total_ms
+                ),
+                None => info!("Cleared rehearsal for showcase {}", id),
+            }
+            Ok(())
+        })
+        .await
 }
 
 #[tauri::command]
@@ -173,45 +480,143 @@ pub async fn save_selected_messages(
     id: String,
     selected_messages: Vec<SelectedMessage>,
     db_state: State<'_, DbConnection>,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     info!("Saving selected messages for showcase ID: {}", id);
-    let mut conn_guard = db_state
+    db_state
         .0
-        .lock()
-        .map_err(|e| format!("DB lock error: {}", e))?;
+        .run(move |conn| {
+            Ok(store_selected_messages(conn, &id, &selected_messages)?)
+        })
+        .await
+}
+
+fn showcase_message_ids(conn: &Connection, id: &str) -> Result<Vec<String>, String> {
+    let mut stmt = conn
+        .prepare("SELECT message_id FROM showcase_messages WHERE showcase_id = ?1")
+        .context("Failed to prepare selection query")?;
+    let message_ids = stmt
+        .query_map(params![id], |row| row.get(0))
+        .context("DB error getting selected messages")?
+        .collect::<Result<Vec<_>, _>>()
+        .context("Error reading selection row")?;
+    Ok(message_ids)
+}
 
-    let json_data = serde_json::to_string(&selected_messages)
-        .map_err(|e| format!("Failed to serialize selected messages: {}", e))?;
+// Every message some showcase currently selects.
+fn referenced_message_ids(conn: &Connection) -> Result<HashSet<String>, String> {
+    let mut stmt = conn
+        .prepare("SELECT DISTINCT message_id FROM showcase_messages")
+        .context("Failed to prepare selection query")?;
+    let message_ids = stmt
+        .query_map([], |row| row.get(0))
+        .context("Failed to query selections")?
+        .collect::<Result<HashSet<_>, _>>()
+        .context("Error reading selection row")?;
+    Ok(message_ids)
+}
+
+// is_used is a reference count collapsed to a flag: a message stays used while any showcase
+// selects it. Call inside the transaction that changed the selections, with every message
+// that may have gained or lost a reference. Returns how many flags changed.
+pub(crate) fn refresh_used_flags<'a>(
+    conn: &Connection,
+    message_ids: impl IntoIterator<Item = &'a String>,
+) -> Result<usize, String> {
+    let referenced = referenced_message_ids(conn)?;
+    let mut changed = 0;
+    for message_id in message_ids {
+        changed += conn
+            .execute(
+                "UPDATE messages SET is_used = ?2 WHERE message_id = ?1 AND is_used != ?2",
+                params![message_id, referenced.contains(message_id)],
+            )
+            .context(format!("Failed to update used flag of {}", message_id))?;
+    }
+    Ok(changed)
+}
+
+#[derive(serde::Serialize, Clone, Debug, Default)]
+pub struct UsedFlagRepair {
+    pub marked: usize,  // selected by a showcase but not flagged
+    pub cleared: usize, // flagged but no longer selected anywhere
+}
+
+// Recomputes is_used for every message, for data saved before the flag was kept in sync.
+pub(crate) fn repair_all_used_flags(conn: &mut Connection) -> Result<UsedFlagRepair, String> {
+    let tx = conn
+        .transaction()
+        .context("Failed to start transaction")?;
+    let referenced = referenced_message_ids(&tx)?;
+    let marked = {
+        let mut stmt = tx
+            .prepare("UPDATE messages SET is_used = 1 WHERE message_id = ?1 AND is_used = 0")
+            .context("Failed to prepare used flag update")?;
+        let mut marked = 0;
+        for message_id in &referenced {
+            marked += stmt
+                .execute(params![message_id])
+                .context(format!("Failed to mark message {} as used", message_id))?;
+        }
+        marked
+    };
+    let flagged: Vec<String> = {
+        let mut stmt = tx
+            .prepare("SELECT message_id FROM messages WHERE is_used = 1")
+            .context("Failed to prepare used message query")?;
+        let rows = stmt
+            .query_map([], |row| row.get(0))
+            .context("Failed to query used messages")?
+            .collect::<Result<Vec<_>, _>>()
+            .context("Error reading used message row")?;
+        rows
+    };
+    let stale: Vec<&String> = flagged.iter().filter(|id| !referenced.contains(*id)).collect();
+    let cleared = refresh_used_flags(&tx, stale)?;
+    tx.commit()
+        .context("Failed to commit transaction")?;
+    info!("Used flags repaired: {} marked, {} cleared", marked, cleared);
+    Ok(UsedFlagRepair { marked, cleared })
+}
+
+#[tauri::command]
+pub async fn repair_used_flags(db_state: State<'_, DbConnection>) -> Result<UsedFlagRepair, AppError> {
+    db_state
+        .0
+        .run(move |conn| Ok(repair_all_used_flags(conn)?))
+        .await
+}
 
+// Saves the selection and moves the showcase to phase 2. Newly chosen messages are protected
+// from cleanup and dropped ones released unless another showcase still selects them.
+pub(crate) fn store_selected_messages(
+    conn: &mut Connection,
+    id: &str,
+    selected_messages: &[SelectedMessage],
+) -> Result<(), AppError> {
     let current_ts = Utc::now().timestamp();
     let next_phase = 2;
 
-    let tx = conn_guard
+    let tx = conn
         .transaction()
-        .map_err(|e| format!("Failed to start transaction: {}", e))?;
-
+        .context("Failed to start transaction")?;
+
+    ensure_showcase_exists(&tx, id)?;
+    record_revision(&tx, id, ACTION_SAVE_SELECTION)?;
+    let mut affected = showcase_message_ids(&tx, id)?;
+    tx.execute("DELETE FROM showcase_messages WHERE showcase_id = ?1", params![id])
+        .context("DB error clearing selected messages")?;
+    insert_selected_messages(&tx, id, selected_messages)?;
     tx.execute(
-        "UPDATE showcases SET selected_messages_json = ?1, phase = ?2, last_modified = ?3 WHERE id = ?4",
-        params![&json_data, next_phase, current_ts, &id]
-    ).map_err(|e| format!("DB error saving selected messages: {}", e))?;
-
-    for message in &selected_messages {
-        tx.execute(
-            "UPDATE messages SET is_used = 1 WHERE message_id = ?1",
-            params![&message.message_id],
-        )
-        .map_err(|e| {
-            format!(
-                "Failed to mark message {} as used: {}",
-                message.message_id, e
-            )
-        })?;
+        "UPDATE showcases SET phase = ?1, last_modified = ?2 WHERE id = ?3",
+        params![next_phase, current_ts, id]
+    ).context("DB error saving selected messages")?;
 
-        info!("Marked message {} as used", message.message_id);
-    }
+    affected.extend(selected_messages.iter().map(|m| m.message_id.clone()));
+    let changed = refresh_used_flags(&tx, &affected)?;
+    info!("Updated the used flag of {} message(s)", changed);
 
     tx.commit()
-        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+        .context("Failed to commit transaction")?;
 
     info!(
         "Selected messages saved and phase updated to {} for showcase ID: {}",
@@ -224,32 +629,15 @@ pub async fn save_selected_messages(
 pub async fn get_selected_messages(
     id: String,
     db_state: State<'_, DbConnection>,
-) -> Result<Vec<SelectedMessage>, String> {
+) -> Result<Vec<SelectedMessage>, AppError> {
     info!("Getting selected messages for showcase ID: {}", id);
-    let conn_guard = db_state
+    db_state
         .0
-        .lock()
-        .map_err(|e| format!("DB lock error: {}", e))?;
-
-    let result = conn_guard.query_row(
-        "SELECT selected_messages_json FROM showcases WHERE id = ?1",
-        params![&id],
-        |row| row.get::<_, Option<String>>(0),
-    );
-
-    match result {
-        Ok(Some(json_data)) => {
-            if json_data.is_empty() || json_data == "null" {
-                Ok(Vec::new())
-            } else {
-                serde_json::from_str(&json_data)
-                    .map_err(|e| format!("Failed to parse selected messages JSON: {}", e))
-            }
-        }
-        Ok(None) => Ok(Vec::new()),
-        Err(RusqliteError::QueryReturnedNoRows) => Err(format!("Showcase ID '{}' not found.", id)),
-        Err(e) => Err(format!("DB error getting selected messages: {}", e)),
-    }
+        .run(move |conn| {
+            ensure_showcase_exists(conn, &id)?;
+            Ok(load_selected_messages(conn, &id)?)
+        })
+        .await
 }
 
 #[tauri::command]
@@ -259,111 +647,274 @@ pub async fn upload_showcase_image(
     image_metadata: ShowcaseImage,
     image_data_uri: String,
     db_state: State<'_, DbConnection>,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     info!(
         "Uploading image for showcase ID: {}, message ID: {}",
         id, image_metadata.message_id
     );
 
+    ensure_valid_message_id(&image_metadata.message_id)?;
     let (image_bytes, extension) = decode_base64_image(&image_data_uri)?;
 
     let image_dir = get_showcase_image_dir(&app_handle, &id)?;
-    // Filename format: <showcase_id>_<message_id>.<ext>
-    let filename = format!("{}_{}.{}", id, image_metadata.message_id, extension);
+    // Filename format: <showcase_id>_<message_id>[_<attachment_index>].<ext>
+    let filename = format!("{}_{}.{}", id, image_metadata.slide_key(), extension);
     let file_path = image_dir.join(&filename);
 
-    print!("{}", image_metadata.overlay.width);
-
-    let file_path_clone = file_path.clone();
-    tokio::task::spawn_blocking(move || -> Result<(), String> {
-        if let Some(parent) = file_path_clone.parent() {
-            fs::create_dir_all(parent).map_err(|e| {
-                format!(
-                    "Failed to create image directory '{}': {}",
-                    parent.display(),
-                    e
-                )
-            })?;
-        }
-        fs::write(&file_path_clone, &image_bytes).map_err(|e| {
-            format!(
-                "Failed to write image file '{}': {}",
-                file_path_clone.display(),
-                e
-            )
-        })?;
-        info!(
-            "Image file saved successfully: {}",
-            file_path_clone.display()
-        );
-        Ok(())
+    let staged_path = tokio::task::spawn_blocking(move || {
+        stage_slide_image(&image_dir, &filename, &image_bytes)
     })
     .await
-    .map_err(|e| format!("File saving task panicked or was cancelled: {}", e))??;
+    .context("File saving task panicked or was cancelled")??;
 
-    let conn_guard = db_state
+    let saved = db_state
         .0
-        .lock()
-        .map_err(|e| format!("DB lock error: {}", e))?;
+        .run(move |conn| save_uploaded_slide(conn, &id, &image_metadata))
+        .await;
+    if let Err(e) = saved {
+        if let Err(remove_error) = fs::remove_file(&staged_path) {
+            warn!(
+                "Failed to remove staged image '{}': {}",
+                staged_path.display(),
+                remove_error
+            );
+        }
+        return Err(e);
+    }
 
-    let current_images: Vec<ShowcaseImage> = conn_guard
-        .query_row(
-            "SELECT images_json FROM showcases WHERE id = ?1",
-            params![&id],
-            |row| {
-                let json_opt: Option<String> = row.get(0)?;
-                match json_opt {
-                    Some(json_str) if !json_str.is_empty() && json_str != "null" => {
-                        serde_json::from_str(&json_str).map_err(|e| {
-                            RusqliteError::FromSqlConversionFailure(
-                                0,
-                                rusqlite::types::Type::Text,
-                                Box::new(e),
-                            )
-                        })
-                    }
-                    _ => Ok(Vec::new()),
-                }
-            },
+    tokio::task::spawn_blocking(move || publish_slide_image(&staged_path, &file_path))
+        .await
+        .context("File saving task panicked or was cancelled")??;
+    Ok(())
+}
+
+// The message ID comes from the frontend and ends up in the slide's file name.
+pub(crate) fn ensure_valid_message_id(message_id: &str) -> Result<(), AppError> {
+    if message_id.is_empty() || !message_id.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(AppError::invalid_input(format!(
+            "Invalid message ID: {}",
+            message_id
+        )));
+    }
+    Ok(())
+}
+
+// Uploads are written under a name find_slide_image doesn't match and only moved into place
+// once the slide's row is saved, so a failed save leaves neither an orphan file nor a
+// half-replaced render. Each upload gets its own staging file, so two uploads of the same
+// slide can't publish each other's bytes.
+pub(crate) fn stage_slide_image(
+    image_dir: &Path,
+    filename: &str,
+    bytes: &[u8],
+) -> Result<PathBuf, String> {
+    fs::create_dir_all(image_dir).map_err(|e| {
+        format!(
+            "Failed to create image directory '{}': {}",
+            image_dir.display(),
+            e
+        )
+    })?;
+    let staged_path = image_dir.join(format!(".{}.{}.part", filename, Uuid::new_v4()));
+    fs::write(&staged_path, bytes).map_err(|e| {
+        format!(
+            "Failed to write image file '{}': {}",
+            staged_path.display(),
+            e
         )
-        .unwrap_or_else(|_| Vec::new());
+    })?;
+    Ok(staged_path)
+}
 
-    let mut updated_images: Vec<ShowcaseImage> = current_images;
+// A single upsert of the slide's row, so concurrent uploads for one showcase can't
+// overwrite each other's slides.
+pub(crate) fn save_uploaded_slide(
+    conn: &mut Connection,
+    id: &str,
+    image: &ShowcaseImage,
+) -> Result<(), AppError> {
+    save_uploaded_slides(conn, id, std::slice::from_ref(image))
+}
 
-    let existing_index = updated_images
-        .iter()
-        .position(|img| img.message_id == image_metadata.message_id);
+// Upserts the rows of several uploaded slides in one transaction, recorded as one revision.
+pub(crate) fn save_uploaded_slides(
+    conn: &mut Connection,
+    id: &str,
+    images: &[ShowcaseImage],
+) -> Result<(), AppError> {
+    let tx = conn
+        .transaction()
+        .context("Failed to start transaction")?;
+    ensure_showcase_exists(&tx, id)?;
+    record_revision(&tx, id, ACTION_UPLOAD_IMAGE)?;
+    for image in images {
+        write_showcase_image(&tx, id, image, None)?;
+    }
+    tx.execute(
+        "UPDATE showcases SET last_modified = ?1 WHERE id = ?2",
+        params![Utc::now().timestamp(), id],
+    )
+    .context("DB error updating images after upload")?;
+    tx.commit()
+        .context("Failed to commit transaction")?;
 
-    if let Some(index) = existing_index {
-        updated_images[index] = image_metadata.clone();
-        warn!(
-            "Replaced existing image for message ID: {} in showcase ID: {}",
-            image_metadata.message_id, id
-        );
-    } else {
-        updated_images.push(image_metadata.clone());
+    for image in images {
         info!(
-            "Added new image for message ID: {} to showcase ID: {}",
-            image_metadata.message_id, id
+            "Saved image for slide {} of showcase ID: {} after image upload.",
+            image.slide_key(),
+            id
         );
     }
+    Ok(())
+}
 
-    let images_json = serde_json::to_string(&updated_images)
-        .map_err(|e| format!("Failed to serialize images metadata: {}", e))?;
+const SLIDE_UPLOAD_CONCURRENCY: usize = 4;
 
-    let current_ts = Utc::now().timestamp();
-    conn_guard
-        .execute(
-            "UPDATE showcases SET images_json = ?1, last_modified = ?2 WHERE id = ?3",
-            params![images_json, current_ts, &id],
-        )
-        .map_err(|e| format!("DB error updating images after upload: {}", e))?;
+// Writes the files concurrently and saves every slide row in one transaction. Slides whose
+// image can't be decoded or written are reported and left out; if the rows can't be saved
+// nothing is kept. A slide listed twice keeps its last upload.
+#[tauri::command]
+pub async fn upload_showcase_images_batch(
+    app_handle: AppHandle,
+    id: String,
+    images: Vec<(ShowcaseImage, String)>,
+    db_state: State<'_, DbConnection>,
+) -> Result<Vec<SlideUploadResult>, AppError> {
+    info!("Uploading {} image(s) for showcase ID: {}", images.len(), id);
+    let image_dir = get_showcase_image_dir(&app_handle, &id)?;
+    let mut results: Vec<SlideUploadResult> = images
+        .iter()
+        .map(|(image, _)| SlideUploadResult {
+            slide_key: image.slide_key(),
+            ok: false,
+            error: None,
+        })
+        .collect();
+    let last_index: HashMap<String, usize> = results
+        .iter()
+        .enumerate()
+        .map(|(index, result)| (result.slide_key.clone(), index))
+        .collect();
+
+    let semaphore = Arc::new(Semaphore::new(SLIDE_UPLOAD_CONCURRENCY));
+    let mut set = JoinSet::new();
+    let mut metadata: Vec<Option<ShowcaseImage>> = Vec::with_capacity(images.len());
+    for (index, (image, data_uri)) in images.into_iter().enumerate() {
+        if let Err(e) = ensure_valid_message_id(&image.message_id) {
+            results[index].error = Some(e.message);
+            metadata.push(None);
+            continue;
+        }
+        if last_index.get(&image.slide_key()) != Some(&index) {
+            results[index].error =
+                Some("Replaced by a later upload of the same slide in this batch.".to_string());
+            metadata.push(None);
+            continue;
+        }
+        let Ok(permit) = semaphore.clone().acquire_owned().await else {
+            break;
+        };
+        let filename_prefix = format!("{}_{}", id, image.slide_key());
+        let image_dir = image_dir.clone();
+        set.spawn_blocking(move || {
+            let _permit = permit;
+            let staged = decode_base64_image(&data_uri).and_then(|(bytes, extension)| {
+                let filename = format!("{}.{}", filename_prefix, extension);
+                let staged_path = stage_slide_image(&image_dir, &filename, &bytes)?;
+                Ok((staged_path, image_dir.join(filename)))
+            });
+            (index, staged)
+        });
+        metadata.push(Some(image));
+    }
+
+    let mut staged: Vec<(usize, PathBuf, PathBuf)> = Vec::new();
+    while let Some(joined) = set.join_next().await {
+        match joined {
+            Ok((index, Ok((staged_path, file_path)))) => {
+                staged.push((index, staged_path, file_path))
+            }
+            Ok((index, Err(e))) => results[index].error = Some(e),
+            Err(e) => error!("Slide upload task panicked or was cancelled: {}", e),
+        }
+    }
+    staged.sort_by_key(|(index, _, _)| *index);
+
+    let rows: Vec<ShowcaseImage> = staged
+        .iter()
+        .filter_map(|(index, _, _)| metadata[*index].clone())
+        .collect();
+    let saved = db_state
+        .0
+        .run(move |conn| save_uploaded_slides(conn, &id, &rows))
+        .await;
+    if let Err(e) = saved {
+        for (_, staged_path, _) in &staged {
+            if let Err(remove_error) = fs::remove_file(staged_path) {
+                warn!(
+                    "Failed to remove staged image '{}': {}",
+                    staged_path.display(),
+                    remove_error
+                );
+            }
+        }
+        return Err(e);
+    }
 
+    let published = tokio::task::spawn_blocking(move || {
+        staged
+            .into_iter()
+            .map(|(index, staged_path, file_path)| {
+                (index, publish_slide_image(&staged_path, &file_path))
+            })
+            .collect::<Vec<_>>()
+    })
+    .await
+    .context("File saving task panicked or was cancelled")?;
+    for (index, result) in published {
+        match result {
+            Ok(()) => results[index].ok = true,
+            Err(e) => results[index].error = Some(e),
+        }
+    }
+    let uploaded = results.iter().filter(|result| result.ok).count();
     info!(
-        "Images metadata and timestamp updated for showcase ID: {} after image upload.",
+        "Uploaded {} of {} image(s) for showcase ID: {}",
+        uploaded,
+        results.len(),
         id
     );
+    Ok(results)
+}
 
+// Moves the staged upload into place and drops an earlier render of the slide saved with a
+// different extension, which find_slide_image could otherwise pick instead.
+pub(crate) fn publish_slide_image(staged_path: &Path, file_path: &Path) -> Result<(), String> {
+    fs::rename(staged_path, file_path).map_err(|e| {
+        format!(
+            "Failed to move image file into place '{}': {}",
+            file_path.display(),
+            e
+        )
+    })?;
+    let (Some(dir), Some(name)) = (file_path.parent(), file_path.file_name().and_then(|n| n.to_str())) else {
+        return Ok(());
+    };
+    let stem = name.rsplit_once('.').map_or(name, |(stem, _)| stem);
+    let prefix = format!("{}.", stem);
+    for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+        let path = entry.path();
+        let stale = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .map_or(false, |n| n.starts_with(&prefix) && n != name);
+        if stale {
+            if let Err(e) = fs::remove_file(&path) {
+                warn!("Failed to remove replaced render '{}': {}", path.display(), e);
+            }
+        }
+    }
+    info!("Image file saved successfully: {}", file_path.display());
     Ok(())
 }
 
@@ -371,32 +922,45 @@ pub async fn upload_showcase_image(
 pub async fn get_showcase_images(
     id: String,
     db_state: State<'_, DbConnection>,
-) -> Result<Vec<ShowcaseImage>, String> {
+) -> Result<Vec<ShowcaseImage>, AppError> {
     info!("Getting showcase images for showcase ID: {}", id);
-    let conn_guard = db_state
+    db_state
         .0
-        .lock()
-        .map_err(|e| format!("DB lock error: {}", e))?;
+        .run(move |conn| {
+            ensure_showcase_exists(conn, &id)?;
+            let mut images = load_showcase_images(conn, &id)?;
+
+            let selected_messages = load_selected_messages(conn, &id)?;
+            let credits = resolve_showcase_credits(conn, &selected_messages)?;
+            let redaction_rules = retrieve_config(conn)?
+                .redaction_rules
+                .unwrap_or_default();
+            for image in images.iter_mut() {
+                image.credit = credits
+                    .iter()
+                    .find(|c| c.message_id == image.message_id)
+                    .map(|c| c.credit_text.clone());
+                image.message = redact_text(&image.message, &redaction_rules);
+            }
 
-    let result = conn_guard.query_row(
-        "SELECT images_json FROM showcases WHERE id = ?1",
-        params![&id],
-        |row| row.get::<_, Option<String>>(0),
-    );
+            Ok(images)
+        })
+        .await
+}
 
-    match result {
-        Ok(Some(json_data)) => {
-            if json_data.is_empty() || json_data == "null" {
-                Ok(Vec::new())
-            } else {
-                serde_json::from_str(&json_data)
-                    .map_err(|e| format!("Failed to parse showcase images JSON: {}", e))
-            }
-        }
-        Ok(None) => Ok(Vec::new()),
-        Err(RusqliteError::QueryReturnedNoRows) => Err(format!("Showcase ID '{}' not found.", id)),
-        Err(e) => Err(format!("DB error getting showcase images: {}", e)),
-    }
+#[tauri::command]
+pub async fn get_showcase_credits(
+    id: String,
+    db_state: State<'_, DbConnection>,
+) -> Result<Vec<ShowcaseCredit>, AppError> {
+    info!("Resolving author credits for showcase ID: {}", id);
+    db_state
+        .0
+        .run(move |conn| {
+            let selected_messages = load_selected_messages(conn, &id)?;
+            Ok(resolve_showcase_credits(conn, &selected_messages)?)
+        })
+        .await
 }
 
 #[tauri::command]
@@ -404,166 +968,751 @@ pub async fn sort_showcase_images(
     id: String,
     sorted_images: Vec<ShowcaseImage>,
     db_state: State<'_, DbConnection>,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     info!(
         "Saving final sorted images metadata for showcase ID: {}",
         id
     );
-    let conn_guard = db_state
+    db_state
         .0
-        .lock()
-        .map_err(|e| format!("DB lock error: {}", e))?;
-
-    let final_images_json = serde_json::to_string(&sorted_images)
-        .map_err(|e| format!("Failed to serialize final images metadata: {}", e))?;
+        .run(move |conn| {
+            let current_ts = Utc::now().timestamp();
+            let final_phase = 4;
+
+            let tx = conn
+                .transaction()
+                .context("Failed to start transaction")?;
+            ensure_showcase_exists(&tx, &id)?;
+            record_revision(&tx, &id, ACTION_SORT_IMAGES)?;
+            tx.execute("DELETE FROM showcase_images WHERE showcase_id = ?1", params![&id])
+                .context("DB error clearing showcase images")?;
+            for (position, image) in sorted_images.iter().enumerate() {
+                write_showcase_image(&tx, &id, image, Some(position as i64))?;
+            }
+            tx.execute(
+                "UPDATE showcases SET phase = ?1, last_modified = ?2 WHERE id = ?3",
+                params![final_phase, current_ts, &id],
+            )
+            .context("DB error saving final sorted images metadata")?;
+            tx.commit()
+                .context("Failed to commit transaction")?;
+
+            info!(
+                "Final images metadata saved and phase updated to {} for showcase ID: {}",
+                final_phase, id
+            );
+            Ok(())
+        })
+        .await
+}
 
-    let current_ts = Utc::now().timestamp();
-    let final_phase = 4;
+// Stores `keys` as the slide order, numbered from 0.
+fn write_slide_order(conn: &Connection, id: &str, keys: &[String]) -> Result<(), String> {
+    let mut stmt = conn
+        .prepare("UPDATE showcase_images SET position = ?1 WHERE showcase_id = ?2 AND slide_key = ?3")
+        .context("Failed to prepare slide order update")?;
+    for (position, key) in keys.iter().enumerate() {
+        stmt.execute(params![position as i64, id, key])
+            .context(format!("DB error ordering slide {}", key))?;
+    }
+    conn.execute(
+        "UPDATE showcases SET last_modified = ?1 WHERE id = ?2",
+        params![Utc::now().timestamp(), id],
+    )
+    .context("DB error updating showcase after reordering slides")?;
+    Ok(())
+}
 
-    let rows = conn_guard
-        .execute(
-            "UPDATE showcases SET images_json = ?1, phase = ?2, last_modified = ?3 WHERE id = ?4",
-            params![final_images_json, final_phase, current_ts, &id],
-        )
-        .map_err(|e| format!("DB error saving final sorted images metadata: {}", e))?;
+// Moves one slide to `new_index` of the current order and shifts the slides in between.
+// Returns the slides in their new order.
+pub(crate) fn move_slide(
+    conn: &mut Connection,
+    id: &str,
+    slide_key: &str,
+    new_index: usize,
+) -> Result<Vec<ShowcaseImage>, AppError> {
+    let tx = conn
+        .transaction()
+        .context("Failed to start transaction")?;
+    ensure_showcase_exists(&tx, id)?;
+    let mut keys: Vec<String> = load_showcase_images(&tx, id)?
+        .iter()
+        .map(ShowcaseImage::slide_key)
+        .collect();
+    let Some(from) = keys.iter().position(|key| key == slide_key) else {
+        return Err(AppError::not_found(format!(
+            "Slide '{}' not found in showcase '{}'.",
+            slide_key, id
+        )));
+    };
+    if new_index >= keys.len() {
+        return Err(AppError::invalid_input(format!(
+            "Slide index {} is out of range (showcase has {} slides).",
+            new_index,
+            keys.len()
+        )));
+    }
 
-    if rows == 0 {
-        Err(format!(
-            "Showcase ID '{}' not found for final image sort save.",
-            id
-        ))
-    } else {
-        info!(
-            "Final images metadata saved and phase updated to {} for showcase ID: {}",
-            final_phase, id
-        );
-        Ok(())
+    if from != new_index {
+        record_revision(&tx, id, ACTION_MOVE_IMAGE)?;
+        let key = keys.remove(from);
+        keys.insert(new_index, key);
+        write_slide_order(&tx, id, &keys)?;
     }
+    let images = load_showcase_images(&tx, id)?;
+    tx.commit()
+        .context("Failed to commit transaction")?;
+
+    info!("Moved slide {} of showcase ID: {} from {} to {}", slide_key, id, from, new_index);
+    Ok(images)
 }
 
-#[tauri::command]
-pub async fn get_showcase(
-    id: String,
-    db_state: State<'_, DbConnection>,
-) -> Result<Showcase, String> {
-    info!("Attempting to get showcase with ID: {}", id);
-    let conn_guard = db_state
-        .0
-        .lock()
-        .map_err(|e| format!("DB lock error: {}", e))?;
-    let result = conn_guard.query_row(
-        "SELECT id, title, description, status, created_at, last_modified, phase, selected_messages_json, pptx_path, images_json FROM showcases WHERE id = ?1",
-        params![&id],
-        map_row_to_showcase,
-    );
+// splitmix64, so a seed gives the same order on every platform and release.
+fn next_random(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
 
-    if let Ok(ref showcase) = result {
-        info!("Showcase images_json: {:?}", showcase.images);
-    }
+// Puts the slides in a random order determined by `seed`. Returns the slides in their new order.
+pub(crate) fn shuffle_slides(
+    conn: &mut Connection,
+    id: &str,
+    seed: u64,
+) -> Result<Vec<ShowcaseImage>, AppError> {
+    let tx = conn
+        .transaction()
+        .context("Failed to start transaction")?;
+    ensure_showcase_exists(&tx, id)?;
+    let mut keys: Vec<String> = load_showcase_images(&tx, id)?
+        .iter()
+        .map(ShowcaseImage::slide_key)
+        .collect();
 
-    match result {
-        Ok(showcase) => Ok(showcase),
-        Err(RusqliteError::QueryReturnedNoRows) => {
-            Err(format!("Showcase with ID '{}' not found.", id))
+    if keys.len() > 1 {
+        record_revision(&tx, id, ACTION_SHUFFLE_IMAGES)?;
+        let mut state = seed;
+        for i in (1..keys.len()).rev() {
+            let j = (next_random(&mut state) % (i as u64 + 1)) as usize;
+            keys.swap(i, j);
         }
-        Err(e) => Err(format!(
-            "Database error fetching showcase (check logs for JSON errors): {}",
-            e
-        )),
+        write_slide_order(&tx, id, &keys)?;
     }
+    let images = load_showcase_images(&tx, id)?;
+    tx.commit()
+        .context("Failed to commit transaction")?;
+
+    info!("Shuffled {} slides of showcase ID: {} with seed {}", images.len(), id, seed);
+    Ok(images)
 }
 
 #[tauri::command]
-pub async fn list_showcases(db_state: State<'_, DbConnection>) -> Result<Vec<Showcase>, String> {
-    info!("Attempting to list all showcases...");
-    let conn_guard = db_state
+pub async fn move_showcase_image(
+    id: String,
+    message_id: String,
+    attachment_index: Option<usize>,
+    new_index: usize,
+    db_state: State<'_, DbConnection>,
+) -> Result<Vec<ShowcaseImage>, AppError> {
+    let key = slide_key(&message_id, attachment_index.unwrap_or(0));
+    info!("Moving slide {} of showcase ID: {} to index {}", key, id, new_index);
+    db_state
         .0
-        .lock()
-        .map_err(|e| format!("DB lock error: {}", e))?;
-    let mut stmt = conn_guard.prepare(
-        "SELECT id, title, description, status, created_at, last_modified, phase, selected_messages_json, pptx_path, images_json FROM showcases ORDER BY last_modified DESC"
-    ).map_err(|e| format!("Failed to prepare list query: {}", e))?;
-    let showcase_iter = stmt
-        .query_map([], map_row_to_showcase)
-        .map_err(|e| format!("Failed to query showcases: {}", e))?;
-    let showcases = showcase_iter
-        .collect::<Result<Vec<Showcase>, _>>()
-        .map_err(|e| format!("Error processing showcase row during list: {}", e))?;
-    info!("Found {} showcases.", showcases.len());
-    Ok(showcases)
+        .run(move |conn| move_slide(conn, &id, &key, new_index))
+        .await
 }
 
 #[tauri::command]
-pub async fn delete_showcase(
+pub async fn shuffle_showcase_images(
+    id: String,
+    seed: u64,
+    db_state: State<'_, DbConnection>,
+) -> Result<Vec<ShowcaseImage>, AppError> {
+    info!("Shuffling slides of showcase ID: {}", id);
+    db_state
+        .0
+        .run(move |conn| shuffle_slides(conn, &id, seed))
+        .await
+}
+
+// Takes the message's slide for one attachment, or all of its slides, out of the showcase. The renders are set aside before the
+// rows are deleted and put back if the transaction fails, so files and metadata change
+// together. Returns how many slides were removed.
+pub(crate) fn remove_slide(
+    paths: &impl PathProvider,
+    conn: &mut Connection,
+    id: &str,
+    message_id: &str,
+    attachment_index: Option<usize>,
+) -> Result<usize, AppError> {
+    let image_dir = get_showcase_image_dir(paths, id)?;
+    let tx = conn
+        .transaction()
+        .context("Failed to start transaction")?;
+    ensure_showcase_exists(&tx, id)?;
+    let slide_keys: Vec<String> = load_showcase_images(&tx, id)?
+        .iter()
+        .filter(|image| {
+            image.message_id == message_id
+                && attachment_index.map_or(true, |index| image.attachment_index == index)
+        })
+        .map(|image| image.slide_key())
+        .collect();
+    if slide_keys.is_empty() {
+        return Err(AppError::not_found(format!(
+            "Message '{}' has no slide in showcase '{}'.",
+            message_id, id
+        )));
+    }
+
+    record_revision(&tx, id, ACTION_REMOVE_IMAGE)?;
+    for slide_key in &slide_keys {
+        tx.execute(
+            "DELETE FROM showcase_images WHERE showcase_id = ?1 AND slide_key = ?2",
+            params![id, slide_key],
+        )
+        .context("DB error removing showcase image")?;
+    }
+    tx.execute(
+        "UPDATE showcases SET last_modified = ?1 WHERE id = ?2",
+        params![Utc::now().timestamp(), id],
+    )
+    .context("DB error updating showcase after image removal")?;
+
+    let prefixes: Vec<String> = slide_keys
+        .iter()
+        .map(|key| format!("{}_{}.", id, key))
+        .collect();
+    let mut set_aside: Vec<(PathBuf, PathBuf)> = Vec::new();
+    for entry in fs::read_dir(&image_dir).into_iter().flatten().flatten() {
+        let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+            continue;
+        };
+        if !prefixes.iter().any(|prefix| name.starts_with(prefix.as_str())) {
+            continue;
+        }
+        let path = entry.path();
+        let removed = image_dir.join(format!(".{}.removed", name));
+        if let Err(e) = fs::rename(&path, &removed) {
+            restore_set_aside(&set_aside);
+            return Err(AppError::from(e)
+                .with_context(format!("Failed to remove image file '{}'", path.display())));
+        }
+        set_aside.push((path, removed));
+    }
+
+    if let Err(e) = tx.commit() {
+        restore_set_aside(&set_aside);
+        return Err(format!("Failed to commit transaction: {}", e).into());
+    }
+    for (_, removed) in &set_aside {
+        if let Err(e) = fs::remove_file(removed) {
+            warn!("Failed to delete removed image '{}': {}", removed.display(), e);
+        }
+    }
+
+    info!(
+        "Removed {} slide(s) and {} file(s) of message {} from showcase ID: {}",
+        slide_keys.len(),
+        set_aside.len(),
+        message_id,
+        id
+    );
+    Ok(slide_keys.len())
+}
+
+fn restore_set_aside(set_aside: &[(PathBuf, PathBuf)]) {
+    for (original, removed) in set_aside {
+        if let Err(e) = fs::rename(removed, original) {
+            error!("Failed to put back image '{}': {}", original.display(), e);
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn remove_showcase_image(
     app_handle: AppHandle,
     id: String,
+    message_id: String,
+    attachment_index: Option<usize>,
     db_state: State<'_, DbConnection>,
-) -> Result<(), String> {
-    info!("Attempting to delete showcase with ID: {}", id);
+) -> Result<usize, AppError> {
+    info!(
+        "Removing image of message {} from showcase ID: {}",
+        message_id, id
+    );
+    db_state
+        .0
+        .run(move |conn| remove_slide(&app_handle, conn, &id, &message_id, attachment_index))
+        .await
+}
 
-    let image_dir = get_showcase_image_dir(&app_handle, &id)?;
-    if image_dir.exists() {
-        info!("Deleting image directory: {}", image_dir.display());
-        let image_dir_for_task = image_dir.clone();
-        tokio::task::spawn_blocking(move || fs::remove_dir_all(&image_dir_for_task))
-            .await
-            .map_err(|e| format!("Image directory deletion task failed: {}", e))?
-            .map_err(|e: std::io::Error| {
-                format!(
-                    "Failed to delete image directory '{}': {}",
-                    image_dir.display(),
-                    e
-                )
-            })?;
-    } else {
-        warn!(
-            "Image directory not found, skipping deletion: {}",
-            image_dir.display()
-        );
+// Changes one slide's overlay, caption, title and notes in place. Only the metadata changes; the editor
+// re-uploads the slide's render to bake the change into it.
+pub(crate) fn patch_slide(
+    conn: &mut Connection,
+    id: &str,
+    slide_key: &str,
+    patch: ShowcaseImagePatch,
+) -> Result<ShowcaseImage, AppError> {
+    let tx = conn
+        .transaction()
+        .context("Failed to start transaction")?;
+    ensure_showcase_exists(&tx, id)?;
+    let Some(mut image) = load_showcase_images(&tx, id)?
+        .into_iter()
+        .find(|image| image.slide_key() == slide_key)
+    else {
+        return Err(AppError::not_found(format!(
+            "Slide '{}' not found in showcase '{}'.",
+            slide_key, id
+        )));
+    };
+
+    if let Some(position) = patch.position {
+        image.overlay.position = position;
+    }
+    if let Some(style) = patch.style {
+        image.overlay.style = style;
+    }
+    if let Some(show_avatar) = patch.show_avatar {
+        image.overlay.show_avatar = show_avatar;
+    }
+    if let Some(caption) = patch.caption {
+        image.message = caption;
+    }
+    if let Some(slide_title) = patch.slide_title {
+        image.slide_title = Some(slide_title).filter(|title| !title.trim().is_empty());
+    }
+    if let Some(notes) = patch.notes {
+        image.notes = Some(notes).filter(|notes| !notes.trim().is_empty());
     }
 
-    let presentation_dir = get_showcase_presentation_dir(&app_handle, &id)?;
-    if presentation_dir.exists() {
-        info!(
-            "Deleting presentation directory: {}",
-            presentation_dir.display()
+    record_revision(&tx, id, ACTION_EDIT_IMAGE)?;
+    write_showcase_image(&tx, id, &image, None)?;
+    tx.execute(
+        "UPDATE showcases SET last_modified = ?1 WHERE id = ?2",
+        params![Utc::now().timestamp(), id],
+    )
+    .context("DB error updating showcase after slide edit")?;
+    tx.commit()
+        .context("Failed to commit transaction")?;
+
+    info!("Updated slide {} of showcase ID: {}", slide_key, id);
+    Ok(image)
+}
+
+#[tauri::command]
+pub async fn update_showcase_image(
+    id: String,
+    message_id: String,
+    attachment_index: Option<usize>,
+    patch: ShowcaseImagePatch,
+    db_state: State<'_, DbConnection>,
+) -> Result<ShowcaseImage, AppError> {
+    let key = slide_key(&message_id, attachment_index.unwrap_or(0));
+    info!(
+        "Updating slide {} of showcase ID: {}, Patch: {:?}",
+        key, id, patch
+    );
+    db_state
+        .0
+        .run(move |conn| patch_slide(conn, &id, &key, patch))
+        .await
+}
+
+#[tauri::command]
+pub async fn get_showcase(
+    id: String,
+    db_state: State<'_, DbConnection>,
+) -> Result<Showcase, AppError> {
+    info!("Attempting to get showcase with ID: {}", id);
+    db_state
+        .0
+        .run(move |conn| {
+            let result = conn.query_row(
+                &format!("SELECT {} FROM showcases WHERE id = ?1", SHOWCASE_COLUMNS),
+                params![&id],
+                map_row_to_showcase,
+            );
+
+            match result {
+                Ok(showcase) => {
+                    let showcase = with_children(conn, showcase)?;
+                    info!("Showcase images: {:?}", showcase.images);
+                    Ok(showcase)
+                }
+                Err(RusqliteError::QueryReturnedNoRows) => {
+                    Err(AppError::not_found(format!("Showcase with ID '{}' not found.", id)))
+                }
+                Err(e) => Err(AppError::from(e)
+                    .with_context("Database error fetching showcase (check logs for JSON errors)")),
+            }
+        })
+        .await
+}
+
+// Archived showcases are left out unless include_archived is set.
+#[tauri::command]
+pub async fn list_showcases(
+    include_archived: Option<bool>,
+    db_state: State<'_, DbConnection>,
+) -> Result<Vec<Showcase>, AppError> {
+    info!("Attempting to list all showcases...");
+    let include_archived = include_archived.unwrap_or(false);
+    db_state
+        .0
+        .run(move |conn| {
+            let mut stmt = conn.prepare(
+                &format!("SELECT {} FROM showcases WHERE deleted_at IS NULL AND (?1 OR status != ?2) ORDER BY last_modified DESC", SHOWCASE_COLUMNS)
+            ).context("Failed to prepare list query")?;
+            let showcase_iter = stmt
+                .query_map(params![include_archived, ShowcaseStatus::Archived.as_str()], map_row_to_showcase)
+                .context("Failed to query showcases")?;
+            // One undecodable row should not hide every other showcase; check_database_health
+            // reports and quarantines it.
+            let showcases: Vec<Showcase> = showcase_iter
+                .filter_map(|result| {
+                    result
+                        .map_err(|e| e.to_string())
+                        .and_then(|showcase| with_children(conn, showcase))
+                        .map_err(|e| error!("Skipping unreadable showcase row during list: {}", e))
+                        .ok()
+                })
+                .collect();
+            info!("Found {} showcases.", showcases.len());
+            Ok(showcases)
+        })
+        .await
+}
+
+const SHOWCASE_QUERY_DEFAULT_LIMIT: usize = 50;
+const SHOWCASE_QUERY_MAX_LIMIT: usize = 500;
+
+// id breaks ties so showcases with equal values don't move between pages.
+fn showcase_order_clause(sort_by: Option<&str>, sort_order: Option<&str>) -> Result<String, String> {
+    let direction = match sort_order {
+        None | Some("desc") => "DESC",
+        Some("asc") => "ASC",
+        Some(other) => return Err(format!("Unknown sort direction: {}", other)),
+    };
+    let column = match sort_by {
+        None | Some("last_modified") => "s.last_modified",
+        Some("created_at") => "s.created_at",
+        Some("title") => "s.title COLLATE NOCASE",
+        Some(other) => return Err(format!("Unknown sort order: {}", other)),
+    };
+    Ok(format!("{0} {1}, s.id {1}", column, direction))
+}
+
+fn escape_like(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
+
+pub(crate) fn query_showcases_page(conn: &Connection, query: &ShowcaseQuery) -> Result<ShowcasePage, String> {
+    let order_by = showcase_order_clause(query.sort_by.as_deref(), query.sort_order.as_deref())?;
+    let limit = query
+        .limit
+        .unwrap_or(SHOWCASE_QUERY_DEFAULT_LIMIT)
+        .clamp(1, SHOWCASE_QUERY_MAX_LIMIT);
+    let page = query.page.unwrap_or(1).max(1);
+
+    let mut conditions: Vec<String> = vec!["s.deleted_at IS NULL".to_string()];
+    let mut values: Vec<RusqliteValue> = Vec::new();
+    if let Some(search) = query.search.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+        conditions.push(
+            "(s.title LIKE ? ESCAPE '\\' OR COALESCE(s.description, '') LIKE ? ESCAPE '\\')".to_string(),
         );
-        let presentation_dir_for_task = presentation_dir.clone();
-        tokio::task::spawn_blocking(move || fs::remove_dir_all(&presentation_dir_for_task))
-            .await
-            .map_err(|e| format!("Presentation directory deletion task failed: {}", e))?
-            .map_err(|e: std::io::Error| {
-                format!(
-                    "Failed to delete presentation directory '{}': {}",
-                    presentation_dir.display(),
-                    e
-                )
-            })?;
+        let pattern = format!("%{}%", escape_like(search));
+        values.push(pattern.clone().into());
+        values.push(pattern.into());
+    }
+    if !query.statuses.is_empty() {
+        conditions.push(format!(
+            "s.status IN ({})",
+            vec!["?"; query.statuses.len()].join(", ")
+        ));
+        values.extend(query.statuses.iter().map(|status| status.clone().into()));
     } else {
-        warn!(
-            "Presentation directory not found, skipping deletion: {}",
-            presentation_dir.display()
-        );
+        // Archived showcases only show up when asked for by status.
+        conditions.push("s.status != ?".to_string());
+        values.push(ShowcaseStatus::Archived.as_str().to_string().into());
+    }
+    if !query.phases.is_empty() {
+        conditions.push(format!(
+            "s.phase IN ({})",
+            vec!["?"; query.phases.len()].join(", ")
+        ));
+        values.extend(query.phases.iter().map(|phase| (*phase as i64).into()));
     }
+    for (column, operator, value) in [
+        ("s.created_at", ">=", query.created_from),
+        ("s.created_at", "<", query.created_to),
+        ("s.last_modified", ">=", query.modified_from),
+        ("s.last_modified", "<", query.modified_to),
+    ] {
+        if let Some(value) = value {
+            conditions.push(format!("{} {} ?", column, operator));
+            values.push(value.into());
+        }
+    }
+    let where_clause = format!("WHERE {}", conditions.join(" AND "));
+
+    let total: i64 = conn
+        .query_row(
+            &format!("SELECT COUNT(*) FROM showcases s {}", where_clause),
+            rusqlite::params_from_iter(values.iter()),
+            |row| row.get(0),
+        )
+        .context("Failed to count showcases")?;
+
+    values.push((limit as i64).into());
+    values.push((((page - 1) * limit) as i64).into());
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT s.id, s.title, s.description, s.status, s.created_at, s.last_modified, s.phase, s.pptx_path,
+                    (SELECT COUNT(*) FROM showcase_messages WHERE showcase_id = s.id),
+                    (SELECT COUNT(*) FROM showcase_images WHERE showcase_id = s.id)
+             FROM showcases s {} ORDER BY {} LIMIT ? OFFSET ?",
+            where_clause, order_by
+        ))
+        .context("Failed to prepare showcase query")?;
+    let showcases = stmt
+        .query_map(rusqlite::params_from_iter(values.iter()), |row| {
+            Ok(ShowcaseSummary {
+                id: row.get(0)?,
+                title: row.get(1)?,
+                description: row.get(2)?,
+                status: row.get(3)?,
+                created_at: row.get(4)?,
+                last_modified: row.get(5)?,
+                phase: row.get(6)?,
+                pptx_path: row.get(7)?,
+                message_count: row.get::<_, i64>(8)? as usize,
+                slide_count: row.get::<_, i64>(9)? as usize,
+            })
+        })
+        .context("Failed to query showcases")?
+        .collect::<Result<Vec<_>, _>>()
+        .context("Error reading showcase row")?;
+
+    Ok(ShowcasePage {
+        showcases,
+        total: total as usize,
+        page,
+        limit,
+    })
+}
+
+#[tauri::command]
+pub async fn query_showcases(
+    query: Option<ShowcaseQuery>,
+    db_state: State<'_, DbConnection>,
+) -> Result<ShowcasePage, AppError> {
+    let query = query.unwrap_or_default();
+    db_state
+        .0
+        .run(move |conn| Ok(query_showcases_page(conn, &query)?))
+        .await
+}
+
+// Moves the showcase to the trash; its files stay until it is purged, by hand or once it has
+// been there longer than trashRetentionDays. `permanent` skips the trash.
+#[tauri::command]
+pub async fn delete_showcase(
+    app_handle: AppHandle,
+    id: String,
+    permanent: Option<bool>,
+    db_state: State<'_, DbConnection>,
+) -> Result<(), AppError> {
+    if permanent.unwrap_or(false) {
+        return purge_showcase(app_handle, id, db_state).await;
+    }
+    info!("Moving showcase {} to the trash", id);
+    db_state
+        .0
+        .run(move |conn| {
+            let rows_affected = conn
+                .execute(
+                    "UPDATE showcases SET deleted_at = ?1 WHERE id = ?2 AND deleted_at IS NULL",
+                    params![Utc::now().timestamp(), &id],
+                )
+                .context("Database error moving showcase to the trash")?;
+            if rows_affected == 0 {
+                ensure_showcase_exists(conn, &id)?;
+                warn!("Showcase {} is already in the trash.", id);
+            }
+            Ok(())
+        })
+        .await
+}
+
+#[tauri::command]
+pub async fn restore_showcase(id: String, db_state: State<'_, DbConnection>) -> Result<(), AppError> {
+    info!("Restoring showcase {} from the trash", id);
+    db_state
+        .0
+        .run(move |conn| {
+            let rows_affected = conn
+                .execute(
+                    "UPDATE showcases SET deleted_at = NULL, last_modified = ?1 WHERE id = ?2 AND deleted_at IS NOT NULL",
+                    params![Utc::now().timestamp(), &id],
+                )
+                .context("Database error restoring showcase")?;
+            if rows_affected == 0 {
+                return Err(AppError::not_found(format!(
+                    "Showcase ID '{}' is not in the trash.",
+                    id
+                )));
+            }
+            Ok(())
+        })
+        .await
+}
+
+// Most recently trashed first.
+#[tauri::command]
+pub async fn list_trashed_showcases(
+    db_state: State<'_, DbConnection>,
+) -> Result<Vec<Showcase>, AppError> {
+    db_state
+        .0
+        .run(move |conn| {
+            let mut stmt = conn
+                .prepare(&format!(
+                    "SELECT {} FROM showcases WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC",
+                    SHOWCASE_COLUMNS
+                ))
+                .context("Failed to prepare trash query")?;
+            let showcases = stmt
+                .query_map([], map_row_to_showcase)
+                .context("Failed to query trashed showcases")?
+                .map(|result| {
+                    result
+                        .map_err(|e| e.to_string())
+                        .and_then(|showcase| with_children(conn, showcase))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(showcases)
+        })
+        .await
+}
 
-    let conn_guard = db_state
+#[tauri::command]
+pub async fn purge_showcase(
+    app_handle: AppHandle,
+    id: String,
+    db_state: State<'_, DbConnection>,
+) -> Result<(), AppError> {
+    info!("Attempting to permanently delete showcase with ID: {}", id);
+    db_state
         .0
-        .lock()
-        .map_err(|e| format!("DB lock error: {}", e))?;
-    let rows_affected = conn_guard
-        .execute("DELETE FROM showcases WHERE id = ?1", params![&id])
-        .map_err(|e| format!("Database error deleting showcase row: {}", e))?;
+        .run(move |conn| {
+            if !purge_showcase_data(&app_handle, conn, &id)? {
+                warn!(
+                    "Showcase row with ID '{}' not found for deletion (or already deleted).",
+                    id
+                );
+            }
+            Ok(())
+        })
+        .await
+}
+
+// Deletes the showcase's image and presentation folders and its rows, and releases its
+// messages. Returns whether the showcase row existed.
+pub(crate) fn purge_showcase_data(
+    paths: &impl PathProvider,
+    conn: &mut Connection,
+    id: &str,
+) -> Result<bool, String> {
+    for dir in [
+        get_showcase_image_dir(paths, id)?,
+        get_showcase_presentation_dir(paths, id)?,
+    ] {
+        if dir.exists() {
+            info!("Deleting directory: {}", dir.display());
+            fs::remove_dir_all(&dir)
+                .context(format!("Failed to delete directory '{}'", dir.display()))?;
+        }
+    }
+    let image_pack = get_showcase_image_pack(paths, id)?;
+    if image_pack.exists() {
+        fs::remove_file(&image_pack)
+            .context(format!("Failed to delete '{}'", image_pack.display()))?;
+    }
 
+    let tx = conn
+        .transaction()
+        .context("Failed to start transaction")?;
+    let released = showcase_message_ids(&tx, id)?;
+    // Foreign keys cascade these too, but not on connections that leave them off.
+    tx.execute("DELETE FROM showcase_messages WHERE showcase_id = ?1", params![id])
+        .context("Database error deleting selected messages")?;
+    tx.execute("DELETE FROM showcase_images WHERE showcase_id = ?1", params![id])
+        .context("Database error deleting showcase images")?;
+    tx.execute("DELETE FROM showcase_revisions WHERE showcase_id = ?1", params![id])
+        .context("Database error deleting showcase revisions")?;
+    tx.execute("DELETE FROM showcase_consent WHERE showcase_id = ?1", params![id])
+        .context("Database error deleting showcase consent")?;
+    tx.execute("DELETE FROM showcase_notifications WHERE showcase_id = ?1", params![id])
+        .context("Database error deleting showcase notifications")?;
+    tx.execute("DELETE FROM showcase_drafts WHERE showcase_id = ?1", params![id])
+        .context("Database error deleting showcase drafts")?;
+    let rows_affected = tx
+        .execute("DELETE FROM showcases WHERE id = ?1", params![id])
+        .context("Database error deleting showcase row")?;
+    refresh_used_flags(&tx, &released)?;
+    tx.commit()
+        .context("Failed to commit transaction")?;
     if rows_affected > 0 {
         info!("Showcase row deleted successfully: {}", id);
-        Ok(())
-    } else {
-        warn!(
-            "Showcase row with ID '{}' not found for deletion (or already deleted).",
-            id
+    }
+    Ok(rows_affected > 0)
+}
+
+pub(crate) const DEFAULT_TRASH_RETENTION_DAYS: u32 = 30;
+
+// Purges showcases that have been in the trash longer than trashRetentionDays. Returns the
+// purged IDs; one that fails to purge is logged and retried on the next run.
+pub(crate) fn purge_expired_trash(
+    paths: &impl PathProvider,
+    conn: &mut Connection,
+    now: i64,
+) -> Result<Vec<String>, String> {
+    let days = retrieve_config(conn)?
+        .trash_retention_days
+        .unwrap_or(DEFAULT_TRASH_RETENTION_DAYS);
+    let cutoff = now - days as i64 * 24 * 60 * 60;
+    let expired: Vec<String> = {
+        let mut stmt = conn
+            .prepare("SELECT id FROM showcases WHERE deleted_at IS NOT NULL AND deleted_at <= ?1")
+            .context("Failed to prepare expired trash query")?;
+        let ids = stmt
+            .query_map(params![cutoff], |row| row.get(0))
+            .context("Failed to query expired trash")?
+            .collect::<Result<Vec<_>, _>>()
+            .context("Error reading expired trash row")?;
+        ids
+    };
+
+    let mut purged = Vec::new();
+    for id in expired {
+        match purge_showcase_data(paths, conn, &id) {
+            Ok(_) => purged.push(id),
+            Err(e) => error!("Failed to purge trashed showcase {}: {}", id, e),
+        }
+    }
+    if !purged.is_empty() {
+        info!(
+            "Purged {} showcase(s) older than {} days from the trash",
+            purged.len(),
+            days
         );
-        Ok(())
     }
+    Ok(purged)
 }
 
 #[tauri::command]
@@ -571,66 +1720,83 @@ pub async fn update_showcase(
     id: String,
     payload: UpdateShowcasePayload,
     db_state: State<'_, DbConnection>,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     info!(
         "Attempting to update showcase (basic info only) ID: {}, Payload: {:?}",
         id, payload
     );
-    let conn_guard = db_state
+    db_state
         .0
-        .lock()
-        .map_err(|e| format!("DB lock error: {}", e))?;
+        .run(move |conn| {
+            let mut set_parts: Vec<String> = Vec::new();
+            let mut params_list: Vec<RusqliteValue> = Vec::new();
 
-    let mut set_parts: Vec<String> = Vec::new();
-    let mut params_list: Vec<RusqliteValue> = Vec::new();
-
-    if let Some(title) = payload.title {
-        set_parts.push("title = ?".to_string());
-        params_list.push(title.into());
-    }
-    if let Some(description) = payload.description {
-        set_parts.push("description = ?".to_string());
-        params_list.push(description.into());
-    }
-    if let Some(status) = payload.status {
-        set_parts.push("status = ?".to_string());
-        params_list.push(status.into());
-    }
+            if let Some(title) = payload.title {
+                set_parts.push("title = ?".to_string());
+                params_list.push(title.into());
+            }
+            if let Some(description) = payload.description {
+                set_parts.push("description = ?".to_string());
+                params_list.push(description.into());
+            }
+            if let Some(status) = payload.status {
+                let status = ShowcaseStatus::parse(&status).ok_or_else(|| {
+                    AppError::invalid_input(format!(
+                        "Unknown status '{}'. Use Draft, In Progress or Completed.",
+                        status
+                    ))
+                })?;
+                if status == ShowcaseStatus::Archived {
+                    return Err(AppError::invalid_input(
+                        "Use archive_showcase to archive a showcase.",
+                    ));
+                }
+                if load_showcase_status(conn, &id)? == ShowcaseStatus::Archived {
+                    return Err(AppError::conflict(format!(
+                        "Showcase ID '{}' is archived. Unarchive it before changing its status.",
+                        id
+                    )));
+                }
+                set_parts.push("status = ?".to_string());
+                params_list.push(status.as_str().to_string().into());
+            }
 
-    if set_parts.is_empty() {
-        error!("No basic showcase data provided for update. Skipping.");
-        return Ok(());
-    }
+            if set_parts.is_empty() {
+                error!("No basic showcase data provided for update. Skipping.");
+                return Ok(());
+            }
 
-    set_parts.push("last_modified = ?".to_string());
-    params_list.push(Utc::now().timestamp().into());
+            set_parts.push("last_modified = ?".to_string());
+            params_list.push(Utc::now().timestamp().into());
 
-    params_list.push(id.clone().into());
+            params_list.push(id.clone().into());
 
-    let sql = format!(
-        "UPDATE showcases SET {} WHERE id = ?{}",
-        set_parts.join(", "),
-        params_list.len()
-    );
+            let sql = format!(
+                "UPDATE showcases SET {} WHERE id = ?{}",
+                set_parts.join(", "),
+                params_list.len()
+            );
 
-    let params_refs: Vec<&dyn rusqlite::ToSql> = params_list
-        .iter()
-        .map(|v| v as &dyn rusqlite::ToSql)
-        .collect();
+            let params_refs: Vec<&dyn rusqlite::ToSql> = params_list
+                .iter()
+                .map(|v| v as &dyn rusqlite::ToSql)
+                .collect();
 
-    info!("Executing update: {}", sql);
-    let rows_affected = conn_guard
-        .execute(&sql, params_refs.as_slice())
-        .map_err(|e| format!("Database error updating showcase basic info: {}", e))?;
+            info!("Executing update: {}", sql);
+            let rows_affected = conn
+                .execute(&sql, params_refs.as_slice())
+                .context("Database error updating showcase basic info")?;
 
-    if rows_affected == 0 {
-        return Err(format!(
-            "Update failed: Showcase with ID '{}' not found or not updated.",
-            id
-        ));
-    }
-    info!("Showcase basic info updated successfully: {}", id);
-    Ok(())
+            if rows_affected == 0 {
+                return Err(AppError::not_found(format!(
+                    "Update failed: Showcase with ID '{}' not found or not updated.",
+                    id
+                )));
+            }
+            info!("Showcase basic info updated successfully: {}", id);
+            Ok(())
+        })
+        .await
 }
 
 #[tauri::command]
@@ -639,13 +1805,16 @@ pub async fn save_showcase_pptx(
     id: String,
     _title: String,
     pptx_base64: String,
+    output_dir: Option<String>,
     db_state: State<'_, DbConnection>,
-) -> Result<String, String> {
+) -> Result<String, AppError> {
     info!("Saving PPTX for showcase ID: {}", id);
+    let span = Span::root("export.pptx.save", &[("showcase_id", id.clone())]);
 
     let pptx_bytes = base64_engine
         .decode(pptx_base64)
         .map_err(|e| format!("Failed to decode base64 PPTX data: {}", e))?;
+    span.record("bytes", pptx_bytes.len());
 
     let presentation_dir = get_showcase_presentation_dir(&app_handle, &id)?;
     if let Some(parent) = presentation_dir.parent() {
@@ -666,8 +1835,7 @@ pub async fn save_showcase_pptx(
         )
     })?;
 
-    let filename = format!("showcase_{}.pptx", id);
-    let file_path = presentation_dir.join(&filename);
+    let file_path = presentation_dir.join(pptx_filename(&id));
 
     let file_path_clone = file_path.clone();
     tokio::task::spawn_blocking(move || -> Result<(), String> {
@@ -694,23 +1862,328 @@ pub async fn save_showcase_pptx(
         Ok(())
     })
     .await
-    .map_err(|e| format!("File saving task panicked or was cancelled: {}", e))??;
+    .map_err(|e| format!("File saving task panicked or was cancelled: {}", e))
+    .and_then(|result| result);
+    if let Err(e) = &write_result {
+        span.fail(e);
+    }
+    write_result?;
+
+    register_pptx_export(&app_handle, &db_state, &id, output_dir).await
+}
+
+const RECENT_EXPORT_LOCATIONS: usize = 5;
+
+pub(crate) fn export_file_stem(title: &str, id: &str) -> String {
+    let title_part: String = title
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect::<String>()
+        .trim_matches('_')
+        .to_string();
+    let id_part: String = id.chars().take(8).collect();
+    if title_part.is_empty() {
+        format!("showcase_{}", id_part)
+    } else {
+        format!("{}_{}", title_part, id_part)
+    }
+}
+
+pub(crate) fn record_export_artifact(
+    conn: &Connection,
+    id: &str,
+    artifact: ExportArtifact,
+) -> Result<(), String> {
+    let exports_json: Option<String> = conn
+        .query_row(
+            "SELECT exports_json FROM showcases WHERE id = ?1",
+            params![id],
+            |row| row.get(0),
+        )
+        .context(format!("Failed to load export history for {}", id))?;
+    let mut exports: Vec<ExportArtifact> = exports_json
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+    exports.push(artifact);
+    let exports_json = serde_json::to_string(&exports)
+        .context("Failed to serialize export history")?;
+    conn.execute(
+        "UPDATE showcases SET exports_json = ?1 WHERE id = ?2",
+        params![exports_json, id],
+    )
+    .context(format!("Failed to save export history for {}", id))?;
+    Ok(())
+}
+
+fn sha256_file(path: &Path) -> Result<(String, u64), String> {
+    let mut file = fs::File::open(path)
+        .context(format!("Failed to open '{}' for hashing", path.display()))?;
+    let mut hasher = Sha256::new();
+    let size_bytes = std::io::copy(&mut file, &mut hasher)
+        .context(format!("Failed to hash '{}'", path.display()))?;
+    Ok((format!("{:x}", hasher.finalize()), size_bytes))
+}
+
+fn manifest_file(path: &Path, display_path: String) -> Result<ExportManifestFile, String> {
+    let (sha256, size_bytes) = sha256_file(path)?;
+    Ok(ExportManifestFile {
+        path: display_path,
+        sha256,
+        size_bytes,
+    })
+}
+
+fn build_export_manifest(
+    paths: &impl PathProvider,
+    id: &str,
+    title: &str,
+    artifact_path: &Path,
+    config: &AppConfig,
+) -> Result<ExportManifest, String> {
+    let artifact_name = artifact_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let artifact = manifest_file(artifact_path, artifact_name)?;
+
+    let mut files = Vec::new();
+    let image_dir = get_showcase_image_dir(paths, id)?;
+    if let Ok(entries) = fs::read_dir(&image_dir) {
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.is_file())
+            .collect();
+        paths.sort();
+        for path in paths {
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            files.push(manifest_file(&path, name)?);
+        }
+    }
+
+    Ok(ExportManifest {
+        showcase_id: id.to_string(),
+        showcase_title: title.to_string(),
+        app_version: CURRENT_VERSION.to_string(),
+        created_at: Utc::now().timestamp(),
+        artifact,
+        files,
+        settings: serde_json::json!({
+            "overlaySettings": config.overlay_settings,
+            "firstSlideSettings": config.first_slide_settings,
+            "legacyPptxCompatibility": config.legacy_pptx_compatibility,
+            "redactionRules": config.redaction_rules,
+            "locale": config.locale,
+        }),
+    })
+}
+
+// What publishing needs from the database: the settings and the showcase's title.
+fn load_export_details(conn: &Connection, id: &str) -> Result<(AppConfig, String), String> {
+    let config = retrieve_config(conn)?;
+    let title: String = conn
+        .query_row("SELECT title FROM showcases WHERE id = ?1", params![id], |row| row.get(0))
+        .context(format!("Failed to load showcase title for {}", id))?;
+    Ok((config, title))
+}
+
+// Copies the export to output_dir, or the configured export directory, and writes a checksum
+// manifest next to it as "<file>.manifest.json". Files only; no connection is needed.
+fn write_published_export(
+    paths: &impl PathProvider,
+    id: &str,
+    title: &str,
+    config: &AppConfig,
+    file_path: &Path,
+    format: &str,
+    output_dir: Option<String>,
+) -> Result<(PathBuf, ExportArtifact), String> {
+    let target_dir = match output_dir.filter(|dir| !dir.trim().is_empty()) {
+        Some(dir) => Some(PathBuf::from(dir)),
+        None => config
+            .export_directory
+            .clone()
+            .filter(|dir| !dir.trim().is_empty())
+            .map(PathBuf::from),
+    };
+
+    let published = match target_dir {
+        Some(dir) => {
+            fs::create_dir_all(&dir).map_err(|e| {
+                format!("Failed to create export directory '{}': {}", dir.display(), e)
+            })?;
+            let target = dir.join(format!("{}.{}", export_file_stem(title, id), format));
+            fs::copy(file_path, &target).map_err(|e| {
+                format!("Failed to copy export to '{}': {}", target.display(), e)
+            })?;
+            info!("Export copied to {}", target.display());
+            target
+        }
+        None => file_path.to_path_buf(),
+    };
+
+    let manifest = build_export_manifest(paths, id, title, &published, config)?;
+    let mut manifest_name = published.clone().into_os_string();
+    manifest_name.push(".manifest.json");
+    let manifest_path = PathBuf::from(manifest_name);
+    let manifest_json = serde_json::to_string_pretty(&manifest)
+        .context("Failed to serialize export manifest")?;
+    fs::write(&manifest_path, manifest_json).map_err(|e| {
+        format!("Failed to write export manifest '{}': {}", manifest_path.display(), e)
+    })?;
 
-    let conn_guard = db_state
+    let artifact = ExportArtifact {
+        path: published.to_string_lossy().to_string(),
+        format: format.to_string(),
+        created_at: manifest.created_at,
+        sha256: Some(manifest.artifact.sha256.clone()),
+        manifest: Some(manifest),
+    };
+    Ok((published, artifact))
+}
+
+// The app-data copy stays where open_showcase_pptx expects it; the user-facing copy is what
+// the history records. The copy and hashing run on the blocking pool between two short
+// database visits.
+pub(crate) async fn publish_export<P: PathProvider + Clone + Send + 'static>(
+    paths: &P,
+    db_state: &DbConnection,
+    id: &str,
+    file_path: &Path,
+    format: &str,
+    output_dir: Option<String>,
+) -> Result<PathBuf, AppError> {
+    let lookup_id = id.to_string();
+    let (config, title) = db_state
+        .0
+        .run(move |conn| Ok(load_export_details(conn, &lookup_id)?))
+        .await?;
+
+    let task_paths = paths.clone();
+    let task_id = id.to_string();
+    let task_file = file_path.to_path_buf();
+    let task_format = format.to_string();
+    let (published, artifact) = tokio::task::spawn_blocking(move || {
+        write_published_export(&task_paths, &task_id, &title, &config, &task_file, &task_format, output_dir)
+    })
+    .await
+    .context("Export publishing task panicked or was cancelled")??;
+
+    let record_id = id.to_string();
+    db_state
         .0
-        .lock()
-        .map_err(|e| format!("DB lock error: {}", e))?;
+        .run(move |conn| Ok(record_export_artifact(conn, &record_id, artifact)?))
+        .await?;
+    Ok(published)
+}
+
+// Hashes a deck (e.g. the copy on the streaming PC) and looks it up in the export history.
+#[tauri::command]
+pub async fn verify_export_artifact(
+    path: String,
+    db_state: State<'_, DbConnection>,
+) -> Result<ExportVerification, AppError> {
+    let file_path = PathBuf::from(&path);
+    let (sha256, size_bytes) = tokio::task::spawn_blocking(move || sha256_file(&file_path))
+        .await
+        .context("Hashing task panicked or was cancelled")??;
 
-    let pptx_relative_path = format!("presentations/{}/{}", id, &filename);
+    let history = db_state
+        .0
+        .run(|conn| {
+            let mut stmt = conn
+                .prepare("SELECT id, exports_json FROM showcases WHERE exports_json IS NOT NULL")
+                .context("Failed to prepare export history query")?;
+            let rows = stmt
+                .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+                .context("Failed to query export history")?
+                .collect::<Result<Vec<(String, String)>, _>>()
+                .context("Error reading export history row")?;
+            Ok(rows)
+        })
+        .await?;
+
+    for (showcase_id, json) in history {
+        let exports: Vec<ExportArtifact> = serde_json::from_str(&json).unwrap_or_default();
+        if let Some(artifact) = exports
+            .into_iter()
+            .rev()
+            .find(|artifact| artifact.sha256.as_deref() == Some(sha256.as_str()))
+        {
+            info!("{} matches an export of showcase {}", path, showcase_id);
+            return Ok(ExportVerification {
+                sha256,
+                size_bytes,
+                showcase_id: Some(showcase_id),
+                artifact: Some(artifact),
+            });
+        }
+    }
+    warn!("{} does not match any recorded export", path);
+    Ok(ExportVerification {
+        sha256,
+        size_bytes,
+        showcase_id: None,
+        artifact: None,
+    })
+}
+
+// Distinct folders of past exports in this format, most recent first.
+#[tauri::command]
+pub async fn get_recent_export_locations(
+    format: String,
+    db_state: State<'_, DbConnection>,
+) -> Result<Vec<String>, AppError> {
+    db_state
+        .0
+        .run(move |conn| {
+            let mut stmt = conn
+                .prepare("SELECT exports_json FROM showcases WHERE exports_json IS NOT NULL")
+                .context("Failed to prepare export history query")?;
+            let rows = stmt
+                .query_map([], |row| row.get::<_, String>(0))
+                .context("Failed to query export history")?;
+
+            let mut artifacts: Vec<ExportArtifact> = Vec::new();
+            for row in rows {
+                let json = row.context("Error reading export history row")?;
+                match serde_json::from_str::<Vec<ExportArtifact>>(&json) {
+                    Ok(exports) => artifacts.extend(exports.into_iter().filter(|a| a.format == format)),
+                    Err(e) => warn!("Skipping unreadable export history: {}", e),
+                }
+            }
+            artifacts.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+
+            let mut locations: Vec<String> = Vec::new();
+            for artifact in artifacts {
+                let Some(parent) = Path::new(&artifact.path).parent() else {
+                    continue;
+                };
+                let location = parent.to_string_lossy().to_string();
+                if !locations.contains(&location) {
+                    locations.push(location);
+                }
+                if locations.len() >= RECENT_EXPORT_LOCATIONS {
+                    break;
+                }
+            }
+            Ok(locations)
+        })
+        .await
+}
+
+fn record_showcase_pptx(conn: &Connection, id: &str, filename: &str) -> Result<String, String> {
+    let pptx_relative_path = format!("presentations/{}/{}", id, filename);
     let current_ts = Utc::now().timestamp();
     let final_phase = 4;
 
-    conn_guard
-        .execute(
-            "UPDATE showcases SET pptx_path = ?1, phase = ?2, last_modified = ?3 WHERE id = ?4",
-            params![pptx_relative_path, final_phase, current_ts, &id],
-        )
-        .map_err(|e| format!("DB error updating showcase with PPTX path: {}", e))?;
+    conn.execute(
+        "UPDATE showcases SET pptx_path = ?1, phase = ?2, last_modified = ?3 WHERE id = ?4",
+        params![pptx_relative_path, final_phase, current_ts, id],
+    )
+    .context("DB error updating showcase with PPTX path")?;
 
     info!(
         "Showcase updated with PPTX path and set to final phase {} for ID: {}",
@@ -720,53 +2193,152 @@ pub async fn save_showcase_pptx(
     Ok(pptx_relative_path)
 }
 
+pub(crate) fn pptx_part_path(paths: &impl PathProvider, id: &str) -> Result<PathBuf, String> {
+    Ok(get_showcase_presentation_dir(paths, id)?.join(format!("showcase_{}.pptx.part", id)))
+}
+
+pub(crate) fn pptx_filename(id: &str) -> String {
+    format!("showcase_{}.pptx", id)
+}
+
+// Points the showcase at its freshly written deck and publishes it (copy, manifest, history).
+pub(crate) async fn register_pptx_export<P: PathProvider + Clone + Send + 'static>(
+    paths: &P,
+    db_state: &DbConnection,
+    id: &str,
+    output_dir: Option<String>,
+) -> Result<String, AppError> {
+    let filename = pptx_filename(id);
+    let file_path = get_showcase_presentation_dir(paths, id)?.join(&filename);
+    let record_id = id.to_string();
+    let pptx_relative_path = db_state
+        .0
+        .run(move |conn| Ok(record_showcase_pptx(conn, &record_id, &filename)?))
+        .await?;
+    publish_export(paths, db_state, id, &file_path, "pptx", output_dir).await?;
+    Ok(pptx_relative_path)
+}
+
+// Low-memory export: the deck is streamed in base64 chunks (each a multiple of 4 chars)
+// into a .part file instead of one large IPC payload.
+#[tauri::command]
+pub async fn begin_showcase_pptx_export(
+    app_handle: AppHandle,
+    id: String,
+    export_jobs: State<'_, ExportJobsState>,
+) -> Result<(), AppError> {
+    info!("Starting chunked PPTX export for showcase ID: {}", id);
+    let presentation_dir = get_showcase_presentation_dir(&app_handle, &id)?;
+    fs::create_dir_all(&presentation_dir).map_err(|e| {
+        format!(
+            "Failed to create showcase presentation directory '{}': {}",
+            presentation_dir.display(),
+            e
+        )
+    })?;
+
+    let part_path = pptx_part_path(&app_handle, &id)?;
+    fs::File::create(&part_path)
+        .context(format!("Failed to create '{}'", part_path.display()))?;
+    export_jobs.start(&id);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn append_showcase_pptx_chunk(
+    app_handle: AppHandle,
+    id: String,
+    chunk_base64: String,
+    export_jobs: State<'_, ExportJobsState>,
+) -> Result<(), AppError> {
+    let chunk = base64_engine
+        .decode(chunk_base64)
+        .map_err(|e| format!("Failed to decode base64 PPTX chunk: {}", e))?;
+    export_jobs.add_chunk(&id, chunk.len());
+
+    let part_path = pptx_part_path(&app_handle, &id)?;
+    tokio::task::spawn_blocking(move || -> Result<(), String> {
+        let mut file = fs::OpenOptions::new()
+            .append(true)
+            .open(&part_path)
+            .map_err(|e| {
+                format!(
+                    "Export for this showcase was not started ('{}'): {}",
+                    part_path.display(),
+                    e
+                )
+            })?;
+        file.write_all(&chunk)
+            .map_err(|e| format!("Failed to write PPTX chunk '{}': {}", part_path.display(), e))
+    })
+    .await
+    .context("Chunk write task panicked or was cancelled")??;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn finish_showcase_pptx_export(
+    app_handle: AppHandle,
+    id: String,
+    output_dir: Option<String>,
+    db_state: State<'_, DbConnection>,
+    export_jobs: State<'_, ExportJobsState>,
+) -> Result<String, AppError> {
+    let part_path = pptx_part_path(&app_handle, &id)?;
+    let file_path = get_showcase_presentation_dir(&app_handle, &id)?.join(pptx_filename(&id));
+
+    if let Err(e) = fs::rename(&part_path, &file_path) {
+        export_jobs.finish(&id, spans::STATUS_ERROR);
+        return Err(AppError::from(e)
+            .with_context(format!("Failed to finalize PPTX file '{}'", file_path.display())));
+    }
+    info!("PPTX file saved successfully: {}", file_path.display());
+    export_jobs.finish(&id, spans::STATUS_OK);
+
+    register_pptx_export(&app_handle, &db_state, &id, output_dir).await
+}
+
 #[tauri::command]
 pub async fn open_showcase_pptx(
     app_handle: AppHandle,
     id: String,
     db_state: State<'_, DbConnection>,
-) -> Result<String, String> {
+) -> Result<String, AppError> {
     info!("Opening PPTX for showcase ID: {}", id);
 
-    let conn_guard = db_state
+    db_state
         .0
-        .lock()
-        .map_err(|e| format!("DB lock error: {}", e))?;
-
-    let pptx_path: String = conn_guard
-        .query_row(
-            "SELECT pptx_path FROM showcases WHERE id = ?1",
-            params![&id],
-            |row| row.get(0),
-        )
-        .map_err(|e| format!("Failed to query PPTX path: {}", e))?;
+        .run(move |conn| {
+            let pptx_path: String = conn
+                .query_row(
+                    "SELECT pptx_path FROM showcases WHERE id = ?1",
+                    params![&id],
+                    |row| row.get(0),
+                )
+                .context("Failed to query PPTX path")?;
 
-    if pptx_path.is_empty() {
-        return Err("No PPTX file found for this showcase".to_string());
-    }
+            if pptx_path.is_empty() {
+                return Err(AppError::not_found("No PPTX file found for this showcase"));
+            }
 
-    let app_data_dir = app_handle
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data dir: {}", e))?;
+            let app_data_dir = app_paths::app_data_dir(&app_handle)?;
 
-    let file_path = app_data_dir.join(&pptx_path);
+            let file_path = app_data_dir.join(&pptx_path);
 
-    if !file_path.exists() {
-        return Err(format!("PPTX file not found at {}", file_path.display()));
-    }
-    Ok(file_path.display().to_string())
+            if !file_path.exists() {
+                return Err(AppError::not_found(format!("PPTX file not found at {}", file_path.display())));
+            }
+            Ok(file_path.display().to_string())
+        })
+        .await
 }
 
 #[tauri::command]
 pub async fn check_showcase_pptx_exists(
     app_handle: tauri::AppHandle,
     id: String,
-) -> Result<bool, String> {
-    let app_data_dir = app_handle
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
+) -> Result<bool, AppError> {
+    let app_data_dir = app_paths::app_data_dir(&app_handle)?;
 
     let presentation_dir = app_data_dir.join("presentations");
     let pptx_path = presentation_dir.join(format!("{}/showcase_{}.pptx", id, id));
@@ -778,3 +2350,485 @@ pub async fn check_showcase_pptx_exists(
 
     Ok(exists)
 }
+
+pub(crate) fn load_showcase(conn: &Connection, id: &str) -> Result<Showcase, AppError> {
+    let showcase = conn
+        .query_row(
+            &format!("SELECT {} FROM showcases WHERE id = ?1", SHOWCASE_COLUMNS),
+            params![id],
+            map_row_to_showcase,
+        )
+        .map_err(|e| match e {
+            RusqliteError::QueryReturnedNoRows => {
+                AppError::not_found(format!("Showcase with ID '{}' not found.", id))
+            }
+            e => AppError::from(e).with_context(format!("Database error fetching showcase '{}'", id)),
+        })?;
+    Ok(with_children(conn, showcase)?)
+}
+
+fn field_change(
+    field: &str,
+    message_id: Option<&str>,
+    value_a: Option<String>,
+    value_b: Option<String>,
+) -> Option<ShowcaseFieldChange> {
+    if value_a == value_b {
+        return None;
+    }
+    Some(ShowcaseFieldChange {
+        field: field.to_string(),
+        message_id: message_id.map(str::to_string),
+        value_a,
+        value_b,
+    })
+}
+
+fn collect_authors(messages: &[SelectedMessage]) -> Vec<DiffAuthor> {
+    let mut authors: Vec<DiffAuthor> = Vec::new();
+    for m in messages {
+        if !authors.iter().any(|a| a.author_id == m.author_id) {
+            authors.push(DiffAuthor {
+                author_id: m.author_id.clone(),
+                author_name: m.author_name.clone(),
+            });
+        }
+    }
+    authors
+}
+
+#[tauri::command]
+pub async fn diff_showcases(
+    a: String,
+    b: String,
+    db_state: State<'_, DbConnection>,
+) -> Result<ShowcaseDiff, AppError> {
+    info!("Comparing showcases {} and {}", a, b);
+    db_state
+        .0
+        .run(move |conn| {
+            let showcase_a = load_showcase(conn, &a)?;
+            let showcase_b = load_showcase(conn, &b)?;
+            drop(conn);
+
+            let messages_a = showcase_a.selected_messages.clone().unwrap_or_default();
+            let messages_b = showcase_b.selected_messages.clone().unwrap_or_default();
+            let images_a = showcase_a.images.clone().unwrap_or_default();
+            let images_b = showcase_b.images.clone().unwrap_or_default();
+
+            let mut common_message_ids = Vec::new();
+            let mut messages_only_in_a = Vec::new();
+            for m in &messages_a {
+                if messages_b.iter().any(|o| o.message_id == m.message_id) {
+                    common_message_ids.push(m.message_id.clone());
+                } else {
+                    messages_only_in_a.push(m.message_id.clone());
+                }
+            }
+            let messages_only_in_b: Vec<String> = messages_b
+                .iter()
+                .filter(|m| !messages_a.iter().any(|o| o.message_id == m.message_id))
+                .map(|m| m.message_id.clone())
+                .collect();
+
+            let authors_a = collect_authors(&messages_a);
+            let authors_b = collect_authors(&messages_b);
+            let (common_authors, authors_only_in_a): (Vec<DiffAuthor>, Vec<DiffAuthor>) = authors_a
+                .iter()
+                .cloned()
+                .partition(|author| authors_b.iter().any(|o| o.author_id == author.author_id));
+            let authors_only_in_b: Vec<DiffAuthor> = authors_b
+                .into_iter()
+                .filter(|author| !authors_a.iter().any(|o| o.author_id == author.author_id))
+                .collect();
+
+            let mut setting_changes: Vec<ShowcaseFieldChange> = [
+                field_change(
+                    "title",
+                    None,
+                    Some(showcase_a.title.clone()),
+                    Some(showcase_b.title.clone()),
+                ),
+                field_change(
+                    "description",
+                    None,
+                    showcase_a.description.clone(),
+                    showcase_b.description.clone(),
+                ),
+                field_change(
+                    "status",
+                    None,
+                    Some(showcase_a.status.clone()),
+                    Some(showcase_b.status.clone()),
+                ),
+                field_change(
+                    "phase",
+                    None,
+                    Some(showcase_a.phase.to_string()),
+                    Some(showcase_b.phase.to_string()),
+                ),
+            ]
+            .into_iter()
+            .flatten()
+            .collect();
+
+            for message_id in &common_message_ids {
+                let selected_a = messages_a.iter().find(|m| &m.message_id == message_id);
+                let selected_b = messages_b.iter().find(|m| &m.message_id == message_id);
+                setting_changes.extend(field_change(
+                    "selected_attachment_filenames",
+                    Some(message_id),
+                    selected_a.map(|m| m.attachment_filenames().join(", ")),
+                    selected_b.map(|m| m.attachment_filenames().join(", ")),
+                ));
+
+                let image_a = images_a.iter().find(|img| &img.message_id == message_id);
+                let image_b = images_b.iter().find(|img| &img.message_id == message_id);
+                let overlay_json = |img: Option<&ShowcaseImage>| {
+                    img.and_then(|i| serde_json::to_string(&i.overlay).ok())
+                };
+                setting_changes.extend(field_change(
+                    "overlay",
+                    Some(message_id),
+                    overlay_json(image_a),
+                    overlay_json(image_b),
+                ));
+                setting_changes.extend(field_change(
+                    "message",
+                    Some(message_id),
+                    image_a.map(|i| i.message.clone()),
+                    image_b.map(|i| i.message.clone()),
+                ));
+
+                let position_a = images_a.iter().position(|img| &img.message_id == message_id);
+                let position_b = images_b.iter().position(|img| &img.message_id == message_id);
+                setting_changes.extend(field_change(
+                    "position",
+                    Some(message_id),
+                    position_a.map(|p| p.to_string()),
+                    position_b.map(|p| p.to_string()),
+                ));
+            }
+
+            Ok(ShowcaseDiff {
+                showcase_a: a,
+                showcase_b: b,
+                common_message_ids,
+                messages_only_in_a,
+                messages_only_in_b,
+                common_authors,
+                authors_only_in_a,
+                authors_only_in_b,
+                setting_changes,
+            })
+        })
+        .await
+}
+
+#[tauri::command]
+pub async fn get_showcase_render_info(
+    id: String,
+    db_state: State<'_, DbConnection>,
+) -> Result<ShowcaseRenderInfo, AppError> {
+    info!("Building render info for showcase ID: {}", id);
+    db_state
+        .0
+        .run(move |conn| {
+            let config = retrieve_config(conn)?;
+            let locale = config
+                .locale
+                .unwrap_or_else(|| DEFAULT_LOCALE.to_string());
+            let redaction_rules = config.redaction_rules.unwrap_or_default();
+            let showcase = load_showcase(conn, &id)?;
+            let selected_messages = showcase.selected_messages.clone().unwrap_or_default();
+            let credits = resolve_showcase_credits(conn, &selected_messages)?;
+            drop(conn);
+
+            // Slides follow the sorted image order when available, otherwise the selection order.
+            // Every attachment picked from one message becomes its own slide with the same credit.
+            let ordered_slides: Vec<(String, usize)> = match &showcase.images {
+                Some(images) if !images.is_empty() => images
+                    .iter()
+                    .map(|i| (i.message_id.clone(), i.attachment_index))
+                    .collect(),
+                _ => selected_messages
+                    .iter()
+                    .flat_map(|m| {
+                        (0..m.attachment_filenames().len()).map(move |index| (m.message_id.clone(), index))
+                    })
+                    .collect(),
+            };
+
+            let slides: Vec<SlideRenderInfo> = ordered_slides
+                .iter()
+                .filter_map(|(message_id, attachment_index)| {
+                    let message = selected_messages.iter().find(|m| &m.message_id == message_id)?;
+                    let credit_text = credits
+                        .iter()
+                        .find(|c| &c.message_id == message_id)
+                        .map(|c| c.credit_text.clone())
+                        .unwrap_or_else(|| message.author_name.clone());
+                    Some(SlideRenderInfo {
+                        message_id: message_id.clone(),
+                        attachment_index: *attachment_index,
+                        credit_text,
+                        caption: redact_text(&message.message_content, &redaction_rules),
+                        reply_author: message.reply_to.as_ref().map(|r| r.author_name.clone()),
+                        reply_caption: message
+                            .reply_to
+                            .as_ref()
+                            .map(|r| redact_text(&r.content, &redaction_rules)),
+                        posted_at: message.timestamp,
+                        posted_at_formatted: format_date(message.timestamp, &locale, false),
+                    })
+                })
+                .collect();
+
+            let mut artist_ids: Vec<&str> = selected_messages.iter().map(|m| m.author_id.as_str()).collect();
+            artist_ids.sort_unstable();
+            artist_ids.dedup();
+
+            let created_at_formatted = format_date(showcase.created_at, &locale, true);
+
+            Ok(ShowcaseRenderInfo {
+                title: showcase.title,
+                subtitle: created_at_formatted.clone(),
+                created_at_formatted,
+                artist_count_formatted: format_number(artist_ids.len() as i64, &locale),
+                slide_count_formatted: format_number(slides.len() as i64, &locale),
+                slides,
+                locale,
+            })
+        })
+        .await
+}
+
+const DEFAULT_ANNOUNCEMENT_TEMPLATE: &str = "**{title}** is live! {slide_count} pieces from {artist_count} artists, {date}.\n\n{segments}\n\nMost loved: {top_credit} ({top_reactions} reactions)";
+
+// Placeholders: {title}, {date}, {slide_count}, {artist_count}, {artists}, {segments} (one line
+// per artist with their piece count, in slide order), {top_credit}, {top_caption}, {top_reactions}.
+#[tauri::command]
+pub async fn generate_announcement(
+    id: String,
+    template: Option<String>,
+    db_state: State<'_, DbConnection>,
+) -> Result<String, AppError> {
+    info!("Generating announcement for showcase ID: {}", id);
+    db_state
+        .0
+        .run(move |conn| {
+            let config = retrieve_config(conn)?;
+            let locale = config
+                .locale
+                .unwrap_or_else(|| DEFAULT_LOCALE.to_string());
+            let redaction_rules = config.redaction_rules.unwrap_or_default();
+            let showcase = load_showcase(conn, &id)?;
+            let selected_messages = showcase.selected_messages.clone().unwrap_or_default();
+            if selected_messages.is_empty() {
+                return Err(AppError::invalid_input(format!("Showcase '{}' has no selected messages yet.", id)));
+            }
+            let credits = resolve_showcase_credits(conn, &selected_messages)?;
+
+            // Reaction counts live on the indexed rows, not on the saved selection.
+            let mut top: Option<(&SelectedMessage, i64)> = None;
+            for message in &selected_messages {
+                let reactions: i64 = conn
+                    .query_row(
+                        "SELECT reaction_count FROM messages WHERE message_id = ?1",
+                        params![&message.message_id],
+                        |row| row.get(0),
+                    )
+                    .unwrap_or(0);
+                if top.map_or(true, |(_, best)| reactions > best) {
+                    top = Some((message, reactions));
+                }
+            }
+            drop(conn);
+
+            let credit_for = |message: &SelectedMessage| {
+                credits
+                    .iter()
+                    .find(|c| c.message_id == message.message_id)
+                    .map(|c| c.credit_text.clone())
+                    .unwrap_or_else(|| message.author_name.clone())
+            };
+
+            // Artists in slide order with how many slides each one has.
+            let mut ordered_messages: Vec<&SelectedMessage> = Vec::new();
+            match &showcase.images {
+                Some(images) if !images.is_empty() => {
+                    for image in images {
+                        if let Some(m) = selected_messages.iter().find(|m| m.message_id == image.message_id) {
+                            ordered_messages.push(m);
+                        }
+                    }
+                }
+                _ => {
+                    for m in &selected_messages {
+                        for _ in m.attachment_filenames() {
+                            ordered_messages.push(m);
+                        }
+                    }
+                }
+            }
+            let mut segments: Vec<(String, String, usize)> = Vec::new();
+            for message in &ordered_messages {
+                match segments.iter_mut().find(|(author_id, _, _)| author_id == &message.author_id) {
+                    Some(segment) => segment.2 += 1,
+                    None => segments.push((message.author_id.clone(), credit_for(message), 1)),
+                }
+            }
+            let segment_lines: Vec<String> = segments
+                .iter()
+                .map(|(_, credit, count)| match count {
+                    1 => format!("• {}", credit),
+                    n => format!("• {} ({} pieces)", credit, n),
+                })
+                .collect();
+            let artists: Vec<&str> = segments.iter().map(|(_, credit, _)| credit.as_str()).collect();
+
+            let (top_credit, top_caption, top_reactions) = match top {
+                Some((message, reactions)) => (
+                    credit_for(message),
+                    redact_text(&message.message_content, &redaction_rules),
+                    reactions,
+                ),
+                None => (String::new(), String::new(), 0),
+            };
+
+            let template = template
+                .filter(|t| !t.trim().is_empty())
+                .unwrap_or_else(|| DEFAULT_ANNOUNCEMENT_TEMPLATE.to_string());
+            let announcement = template
+                .replace("{title}", &showcase.title)
+                .replace("{date}", &format_date(showcase.created_at, &locale, true))
+                .replace("{slide_count}", &format_number(ordered_messages.len() as i64, &locale))
+                .replace("{artist_count}", &format_number(segments.len() as i64, &locale))
+                .replace("{artists}", &artists.join(", "))
+                .replace("{segments}", &segment_lines.join("\n"))
+                .replace("{top_credit}", &top_credit)
+                .replace("{top_caption}", &top_caption)
+                .replace("{top_reactions}", &format_number(top_reactions, &locale))
+                .trim()
+                .to_string();
+            Ok(announcement)
+        })
+        .await
+}
+
+const DEFAULT_RECAP_SLIDES_PER_SHOWCASE: usize = 3;
+
+// Assembles a new draft from the best slides of earlier showcases, oldest showcase first.
+// rank_by "reactions" (default) uses reaction counts; "screen_time" uses the latest
+// rehearsal's per-slide time, falling back to reactions for showcases never rehearsed.
+// The draft starts at phase 2 so the picks can be reviewed and reordered before export.
+#[tauri::command]
+pub async fn create_recap_showcase(
+    title: String,
+    source_ids: Vec<String>,
+    per_showcase: Option<usize>,
+    rank_by: Option<String>,
+    db_state: State<'_, DbConnection>,
+) -> Result<String, AppError> {
+    let per_showcase = per_showcase.unwrap_or(DEFAULT_RECAP_SLIDES_PER_SHOWCASE).max(1);
+    let by_screen_time = match rank_by.as_deref() {
+        None | Some("reactions") => false,
+        Some("screen_time") => true,
+        Some(other) => return Err(AppError::invalid_input(format!("Unknown recap ranking: {}", other))),
+    };
+    if source_ids.is_empty() {
+        return Err(AppError::invalid_input("Pick at least one showcase for the recap."));
+    }
+    info!(
+        "Building recap '{}' from {} showcase(s), {} slide(s) each",
+        title,
+        source_ids.len(),
+        per_showcase
+    );
+
+    db_state
+        .0
+        .run(move |conn| {
+            let mut sources = source_ids
+                .iter()
+                .map(|id| load_showcase(conn, id))
+                .collect::<Result<Vec<Showcase>, AppError>>()?;
+            sources.sort_by_key(|showcase| showcase.created_at);
+
+            let mut picks: Vec<SelectedMessage> = Vec::new();
+            for source in &sources {
+                let messages = source.selected_messages.clone().unwrap_or_default();
+                let screen_time: Option<HashMap<&str, u64>> = source
+                    .rehearsal
+                    .as_ref()
+                    .filter(|_| by_screen_time)
+                    .map(|run| {
+                        let mut totals: HashMap<&str, u64> = HashMap::new();
+                        for slide in &run.slides {
+                            *totals.entry(slide.message_id.as_str()).or_default() += slide.duration_ms;
+                        }
+                        totals
+                    });
+                if by_screen_time && screen_time.is_none() {
+                    warn!("Showcase {} has no rehearsal, ranking it by reactions", source.id);
+                }
+
+                let mut scored: Vec<(i64, SelectedMessage)> = messages
+                    .into_iter()
+                    .map(|message| {
+                        let score = match &screen_time {
+                            Some(totals) => totals.get(message.message_id.as_str()).copied().unwrap_or(0) as i64,
+                            None => conn
+                                .query_row(
+                                    "SELECT reaction_count FROM messages WHERE message_id = ?1",
+                                    params![&message.message_id],
+                                    |row| row.get(0),
+                                )
+                                .unwrap_or(0),
+                        };
+                        (score, message)
+                    })
+                    .collect();
+                // Stable, so ties keep the source showcase's slide order.
+                scored.sort_by(|a, b| b.0.cmp(&a.0));
+
+                for (_, message) in scored
+                    .into_iter()
+                    .filter(|(_, m)| !picks.iter().any(|p| p.message_id == m.message_id))
+                    .take(per_showcase)
+                {
+                    picks.push(message);
+                }
+            }
+            if picks.is_empty() {
+                return Err(AppError::invalid_input("None of the chosen showcases have selected slides."));
+            }
+
+            let new_id = Uuid::new_v4().to_string();
+            let current_ts = Utc::now().timestamp();
+            let description = format!("Recap of {} showcase(s)", sources.len());
+
+            let tx = conn
+                .transaction()
+                .context("Failed to start transaction")?;
+            tx.execute(
+                "INSERT INTO showcases (id, title, description, status, created_at, last_modified, phase, pptx_path) VALUES (?1, ?2, ?3, 'Draft', ?4, ?4, 2, NULL)",
+                params![&new_id, &title, &description, current_ts],
+            )
+            .context("Database error creating recap showcase")?;
+            insert_selected_messages(&tx, &new_id, &picks)?;
+            for message in &picks {
+                tx.execute(
+                    "UPDATE messages SET is_used = 1 WHERE message_id = ?1",
+                    params![&message.message_id],
+                )
+                .context(format!("Failed to mark message {} as used", message.message_id))?;
+            }
+            tx.commit()
+                .context("Failed to commit transaction")?;
+
+            info!("Recap showcase {} created with {} slide(s)", new_id, picks.len());
+            Ok(new_id)
+        })
+        .await
+}
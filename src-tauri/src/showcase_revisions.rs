@@ -0,0 +1,177 @@
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+use tauri::State;
+
+use crate::error::{AppError, ResultExt};
+use crate::log_info as info;
+use crate::models::{ShowcaseRevision, ShowcaseRevisionState};
+use crate::showcase_manager::{
+    insert_selected_messages, load_selected_messages, load_showcase_images, refresh_used_flags,
+    write_showcase_image,
+};
+use crate::sqlite_manager::DbConnection;
+
+// Oldest revisions beyond this are dropped as new ones are recorded.
+pub(crate) const MAX_REVISIONS_PER_SHOWCASE: i64 = 50;
+
+pub(crate) const ACTION_SAVE_SELECTION: &str = "save_selection";
+pub(crate) const ACTION_UPLOAD_IMAGE: &str = "upload_image";
+pub(crate) const ACTION_SORT_IMAGES: &str = "sort_images";
+pub(crate) const ACTION_REMOVE_IMAGE: &str = "remove_image";
+pub(crate) const ACTION_EDIT_IMAGE: &str = "edit_image";
+pub(crate) const ACTION_MOVE_IMAGE: &str = "move_image";
+pub(crate) const ACTION_SHUFFLE_IMAGES: &str = "shuffle_images";
+pub(crate) const ACTION_RESTORE: &str = "restore_revision";
+
+fn current_state(conn: &Connection, id: &str) -> Result<ShowcaseRevisionState, String> {
+    let phase = conn
+        .query_row(
+            "SELECT phase FROM showcases WHERE id = ?1",
+            params![id],
+            |row| row.get(0),
+        )
+        .context(format!("DB error reading phase of showcase {}", id))?;
+    Ok(ShowcaseRevisionState {
+        phase,
+        selected_messages: load_selected_messages(conn, id)?,
+        images: load_showcase_images(conn, id)?,
+    })
+}
+
+// Snapshots the showcase as it is now, before `action` changes it. Call inside the
+// transaction that makes the change. Only database state is kept; slide image files are
+// overwritten in place.
+pub(crate) fn record_revision(conn: &Connection, id: &str, action: &str) -> Result<(), String> {
+    let state = current_state(conn, id)?;
+    let state_json =
+        serde_json::to_string(&state).context("Failed to serialize showcase revision")?;
+    conn.execute(
+        "INSERT INTO showcase_revisions (showcase_id, created_at, action, state_json) VALUES (?1, ?2, ?3, ?4)",
+        params![id, Utc::now().timestamp(), action, state_json],
+    )
+    .context("DB error saving showcase revision")?;
+    conn.execute(
+        "DELETE FROM showcase_revisions WHERE showcase_id = ?1 AND id NOT IN (
+             SELECT id FROM showcase_revisions WHERE showcase_id = ?1 ORDER BY id DESC LIMIT ?2
+         )",
+        params![id, MAX_REVISIONS_PER_SHOWCASE],
+    )
+    .context("DB error trimming showcase revisions")?;
+    Ok(())
+}
+
+// Newest first.
+pub(crate) fn load_revisions(conn: &Connection, id: &str) -> Result<Vec<ShowcaseRevision>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, created_at, action, state_json FROM showcase_revisions WHERE showcase_id = ?1 ORDER BY id DESC",
+        )
+        .context("Failed to prepare revision query")?;
+    let rows = stmt
+        .query_map(params![id], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+            ))
+        })
+        .context("DB error getting showcase revisions")?
+        .collect::<Result<Vec<_>, _>>()
+        .context("Error reading showcase revision row")?;
+    rows.into_iter()
+        .map(|(revision_id, created_at, action, state_json)| {
+            let state: ShowcaseRevisionState = serde_json::from_str(&state_json)
+                .context(format!("Unreadable showcase revision {}", revision_id))?;
+            Ok(ShowcaseRevision {
+                id: revision_id,
+                showcase_id: id.to_string(),
+                created_at,
+                action,
+                phase: state.phase,
+                message_count: state.selected_messages.len(),
+                slide_count: state.images.len(),
+            })
+        })
+        .collect()
+}
+
+// Puts the selection, slides and phase back as they were in the revision. The state being
+// replaced is recorded first, so a restore can itself be undone.
+pub(crate) fn apply_revision(
+    conn: &mut Connection,
+    id: &str,
+    revision_id: i64,
+) -> Result<(), AppError> {
+    let tx = conn.transaction().context("Failed to start transaction")?;
+    let state_json: Option<String> = tx
+        .query_row(
+            "SELECT state_json FROM showcase_revisions WHERE id = ?1 AND showcase_id = ?2",
+            params![revision_id, id],
+            |row| row.get(0),
+        )
+        .optional()
+        .context("DB error loading showcase revision")?;
+    let Some(state_json) = state_json else {
+        return Err(AppError::not_found(format!(
+            "Revision {} of showcase '{}' not found.",
+            revision_id, id
+        )));
+    };
+    let state: ShowcaseRevisionState = serde_json::from_str(&state_json)
+        .context(format!("Unreadable showcase revision {}", revision_id))?;
+
+    record_revision(&tx, id, ACTION_RESTORE)?;
+    let mut affected: Vec<String> = load_selected_messages(&tx, id)?
+        .into_iter()
+        .map(|m| m.message_id)
+        .collect();
+    tx.execute(
+        "DELETE FROM showcase_messages WHERE showcase_id = ?1",
+        params![id],
+    )
+    .context("DB error clearing selected messages")?;
+    tx.execute(
+        "DELETE FROM showcase_images WHERE showcase_id = ?1",
+        params![id],
+    )
+    .context("DB error clearing showcase images")?;
+    insert_selected_messages(&tx, id, &state.selected_messages)?;
+    for (position, image) in state.images.iter().enumerate() {
+        write_showcase_image(&tx, id, image, Some(position as i64))?;
+    }
+    tx.execute(
+        "UPDATE showcases SET phase = ?1, last_modified = ?2 WHERE id = ?3",
+        params![state.phase, Utc::now().timestamp(), id],
+    )
+    .context("DB error restoring showcase revision")?;
+    affected.extend(state.selected_messages.iter().map(|m| m.message_id.clone()));
+    refresh_used_flags(&tx, &affected)?;
+    tx.commit().context("Failed to commit transaction")?;
+
+    info!("Restored revision {} of showcase ID: {}", revision_id, id);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn list_showcase_revisions(
+    id: String,
+    db_state: State<'_, DbConnection>,
+) -> Result<Vec<ShowcaseRevision>, AppError> {
+    db_state
+        .0
+        .run(move |conn| Ok(load_revisions(conn, &id)?))
+        .await
+}
+
+#[tauri::command]
+pub async fn restore_showcase_revision(
+    id: String,
+    revision_id: i64,
+    db_state: State<'_, DbConnection>,
+) -> Result<(), AppError> {
+    db_state
+        .0
+        .run(move |conn| apply_revision(conn, &id, revision_id))
+        .await
+}
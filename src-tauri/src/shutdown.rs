@@ -0,0 +1,156 @@
+use rusqlite::params;
+use std::fs;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::discord::IndexingState;
+use crate::error::AppError;
+use crate::export_pipeline::ExportJobsState;
+use crate::live_indexing::LiveIndexingState;
+use crate::showcase_manager::pptx_part_path;
+use crate::spans;
+use crate::sqlite_manager::DbConnection;
+use crate::{log_error as error, log_info as info, log_warn as warn};
+
+pub const SHUTDOWN_WAIT: &str = "wait";
+pub const SHUTDOWN_CANCEL: &str = "cancel";
+pub const SHUTDOWN_BACKGROUND: &str = "background";
+
+const JOB_POLL_INTERVAL: Duration = Duration::from_millis(500);
+// After cancelling, indexing gets this long to finish its current batch before the app exits anyway.
+const CANCEL_GRACE_PERIOD: Duration = Duration::from_secs(30);
+const LIVE_INDEXING_STOP_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(serde::Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveJobs {
+    pub indexing: bool,
+    pub exports: Vec<String>,
+}
+
+impl ActiveJobs {
+    fn is_empty(&self) -> bool {
+        !self.indexing && self.exports.is_empty()
+    }
+}
+
+fn active_jobs(app_handle: &AppHandle) -> ActiveJobs {
+    ActiveJobs {
+        indexing: app_handle
+            .try_state::<IndexingState>()
+            .map_or(false, |state| state.0.is_running()),
+        exports: app_handle
+            .try_state::<ExportJobsState>()
+            .map(|state| state.active())
+            .unwrap_or_default(),
+    }
+}
+
+// Window close hook. Returns true when the close must be deferred because a job is running;
+// the frontend is then asked how to proceed and answers through resolve_shutdown.
+pub fn defer_close(app_handle: &AppHandle) -> bool {
+    let jobs = active_jobs(app_handle);
+    if jobs.is_empty() {
+        return false;
+    }
+    warn!(
+        "Close requested while jobs are running (indexing: {}, exports: {})",
+        jobs.indexing,
+        jobs.exports.len()
+    );
+    app_handle
+        .emit("shutdown-requested", jobs)
+        .unwrap_or_default();
+    true
+}
+
+// Runs once on exit: stops the gateway client, checkpoints the WAL and flushes the log files.
+pub fn finalize(app_handle: &AppHandle) {
+    info!("Shutting down...");
+
+    if let Some(live_state) = app_handle.try_state::<LiveIndexingState>() {
+        tauri::async_runtime::block_on(async {
+            if let Some(manager) = live_state.0.lock().await.take() {
+                if tokio::time::timeout(LIVE_INDEXING_STOP_TIMEOUT, manager.shutdown_all())
+                    .await
+                    .is_err()
+                {
+                    warn!("Live indexing did not stop in time.");
+                }
+            }
+        });
+    }
+
+    if let Some(db_state) = app_handle.try_state::<DbConnection>() {
+        match db_state.0.lock() {
+            Ok(conn_guard) => {
+                match conn_guard.query_row("PRAGMA wal_checkpoint(TRUNCATE);", params![], |_| Ok(())) {
+                    Ok(_) => info!("Database WAL checkpointed."),
+                    Err(e) => error!("WAL checkpoint failed: {}", e),
+                }
+            }
+            Err(e) => error!("DB lock error during shutdown: {}", e),
+        }
+    }
+
+    info!("Shutdown complete.");
+    log::logger().flush();
+}
+
+async fn wait_for_jobs(app_handle: &AppHandle, limit: Option<Duration>) {
+    let started = tokio::time::Instant::now();
+    while !active_jobs(app_handle).is_empty() {
+        if limit.map_or(false, |limit| started.elapsed() >= limit) {
+            warn!("Jobs still running after the grace period, exiting anyway.");
+            return;
+        }
+        tokio::time::sleep(JOB_POLL_INTERVAL).await;
+    }
+}
+
+fn cancel_jobs(app_handle: &AppHandle) {
+    if let Some(indexing_state) = app_handle.try_state::<IndexingState>() {
+        if indexing_state.0.is_running() {
+            info!("Cancelling indexing for shutdown.");
+            indexing_state.0.request_cancel();
+        }
+    }
+    if let Some(export_jobs) = app_handle.try_state::<ExportJobsState>() {
+        // The frontend's next chunk upload fails once the .part file is gone.
+        for showcase_id in export_jobs.active() {
+            if let Ok(part_path) = pptx_part_path(app_handle, &showcase_id) {
+                fs::remove_file(&part_path).ok();
+            }
+            export_jobs.finish(&showcase_id, spans::STATUS_CANCELLED);
+            info!("Cancelled PPTX export for showcase {}", showcase_id);
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn resolve_shutdown(app_handle: AppHandle, choice: String) -> Result<(), AppError> {
+    info!("Shutdown choice: {}", choice);
+    let limit = match choice.as_str() {
+        SHUTDOWN_WAIT => None,
+        SHUTDOWN_CANCEL => {
+            cancel_jobs(&app_handle);
+            Some(CANCEL_GRACE_PERIOD)
+        }
+        SHUTDOWN_BACKGROUND => {
+            for window in app_handle.webview_windows().values() {
+                if let Err(e) = window.hide() {
+                    warn!("Failed to hide window {}: {}", window.label(), e);
+                }
+            }
+            None
+        }
+        other => return Err(AppError::invalid_input(format!("Unknown shutdown choice: {}", other))),
+    };
+
+    let app_clone = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        wait_for_jobs(&app_clone, limit).await;
+        app_clone.exit(0);
+    });
+    Ok(())
+}
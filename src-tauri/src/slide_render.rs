@@ -0,0 +1,707 @@
+use ab_glyph::{point, Font, FontRef, GlyphId, ScaleFont};
+use base64::{engine::general_purpose::STANDARD as base64_engine, Engine as _};
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::{self, FilterType};
+use image::{DynamicImage, ImageFormat, Rgba, RgbaImage};
+use once_cell::sync::Lazy;
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, State};
+
+use crate::author_manager::resolve_showcase_credits;
+use crate::cache_crypto;
+use crate::error::{AppError, ResultExt};
+use crate::models::{
+    slide_key, OverlayPosition, OverlaySettings, OverlayStyle, Showcase, ShowcaseCredit,
+    ShowcaseImage, SlideImageExport,
+};
+use crate::redaction::redact_text;
+use crate::showcase_manager::{
+    find_slide_image, get_showcase_image_dir, get_showcase_presentation_dir, load_showcase,
+};
+use crate::spans::Span;
+use crate::sqlite_manager::{get_image_base_dir, retrieve_config, DbConnection};
+use crate::{log_info as info, log_warn as warn};
+
+pub(crate) const DEFAULT_RESOLUTION: (u32, u32) = (1920, 1080);
+const MIN_DIMENSION: u32 = 16;
+const MAX_DIMENSION: u32 = 7680;
+const SLIDE_JPEG_QUALITY: u8 = 92;
+pub(crate) const SLIDE_EXPORT_DIR: &str = "slides";
+
+// The editor lays overlays out in CSS pixels on a 1920px-wide preview; everything scales
+// from there.
+const REFERENCE_WIDTH: f32 = 1920.0;
+const OVERLAY_MARGIN: f32 = 16.0;
+const OVERLAY_PADDING: f32 = 16.0;
+const OVERLAY_AVATAR_SIZE: f32 = 48.0;
+const OVERLAY_GAP: f32 = 16.0;
+const OVERLAY_LINE_GAP: f32 = 4.0;
+const SENDER_FONT_SIZE: f32 = 22.0;
+const MESSAGE_FONT_SIZE: f32 = 18.0;
+const MESSAGE_MAX_LINES: usize = 4;
+const CREDITS_TITLE_FONT_SIZE: f32 = 64.0;
+const CREDITS_FONT_SIZE: f32 = 32.0;
+const CREDITS_LINE_GAP: f32 = 12.0;
+
+// Bundled so every machine draws the same text; DejaVu covers Latin, Greek, Cyrillic and more.
+static SANS: Lazy<FontRef<'static>> = Lazy::new(|| {
+    FontRef::try_from_slice(include_bytes!("../fonts/DejaVuSans.ttf"))
+        .expect("bundled DejaVu Sans is a valid font")
+});
+static SANS_BOLD: Lazy<FontRef<'static>> = Lazy::new(|| {
+    FontRef::try_from_slice(include_bytes!("../fonts/DejaVuSans-Bold.ttf"))
+        .expect("bundled DejaVu Sans Bold is a valid font")
+});
+
+// "720p", "1080p", "1440p", "4k" or "WIDTHxHEIGHT"; 1080p when not given.
+pub(crate) fn parse_resolution(value: Option<&str>) -> Result<(u32, u32), AppError> {
+    let Some(value) = value.map(str::trim).filter(|v| !v.is_empty()) else {
+        return Ok(DEFAULT_RESOLUTION);
+    };
+    let (width, height) = match value.to_ascii_lowercase().as_str() {
+        "720p" => (1280, 720),
+        "1080p" => (1920, 1080),
+        "1440p" => (2560, 1440),
+        "4k" | "2160p" => (3840, 2160),
+        other => other
+            .split_once('x')
+            .and_then(|(w, h)| Some((w.trim().parse().ok()?, h.trim().parse().ok()?)))
+            .ok_or_else(|| {
+                AppError::invalid_input(format!(
+                    "Unknown resolution '{}'. Use 720p, 1080p, 1440p, 4k or WIDTHxHEIGHT.",
+                    value
+                ))
+            })?,
+    };
+    let valid = MIN_DIMENSION..=MAX_DIMENSION;
+    if !valid.contains(&width) || !valid.contains(&height) {
+        return Err(AppError::invalid_input(format!(
+            "Resolution must be between {} and {} pixels per side.",
+            MIN_DIMENSION, MAX_DIMENSION
+        )));
+    }
+    Ok((width, height))
+}
+
+// Returns the file extension for the requested output format.
+pub(crate) fn parse_image_format(value: &str) -> Result<&'static str, AppError> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "png" => Ok("png"),
+        "jpg" | "jpeg" => Ok("jpg"),
+        other => Err(AppError::invalid_input(format!(
+            "Unsupported image format '{}'. Use png or jpeg.",
+            other
+        ))),
+    }
+}
+
+fn read_image(path: &Path) -> Result<DynamicImage, String> {
+    let bytes = fs::read(path).context(format!("Failed to read '{}'", path.display()))?;
+    let bytes = cache_crypto::decrypt_if_encrypted(bytes)?;
+    image::load_from_memory(&bytes)
+        .map_err(|e| format!("Failed to decode '{}': {}", path.display(), e))
+}
+
+// The image scaled to fit inside the frame and centered on black, the deck's master background.
+pub(crate) fn fit_on_background(image: &DynamicImage, width: u32, height: u32) -> RgbaImage {
+    let mut canvas = RgbaImage::from_pixel(width, height, Rgba([0, 0, 0, 255]));
+    let fitted = image.resize(width, height, FilterType::Lanczos3).to_rgba8();
+    let x = (width - fitted.width().min(width)) / 2;
+    let y = (height - fitted.height().min(height)) / 2;
+    imageops::overlay(&mut canvas, &fitted, x as i64, y as i64);
+    canvas
+}
+
+fn blend_pixel(canvas: &mut RgbaImage, x: u32, y: u32, color: [u8; 3], alpha: f32) {
+    if x >= canvas.width() || y >= canvas.height() {
+        return;
+    }
+    let pixel = canvas.get_pixel_mut(x, y);
+    for channel in 0..3 {
+        pixel[channel] =
+            (pixel[channel] as f32 * (1.0 - alpha) + color[channel] as f32 * alpha).round() as u8;
+    }
+}
+
+fn fill_rect(
+    canvas: &mut RgbaImage,
+    (x, y): (u32, u32),
+    (width, height): (u32, u32),
+    color: [u8; 3],
+    alpha: f32,
+) {
+    for dy in 0..height {
+        for dx in 0..width {
+            blend_pixel(canvas, x + dx, y + dy, color, alpha);
+        }
+    }
+}
+
+fn text_width(font: &FontRef<'static>, size: f32, text: &str) -> f32 {
+    let scaled = font.as_scaled(size);
+    let mut width = 0.0;
+    let mut previous: Option<GlyphId> = None;
+    for c in text.chars() {
+        let id = scaled.glyph_id(c);
+        if let Some(previous) = previous {
+            width += scaled.kern(previous, id);
+        }
+        width += scaled.h_advance(id);
+        previous = Some(id);
+    }
+    width
+}
+
+fn line_height(font: &FontRef<'static>, size: f32) -> u32 {
+    font.as_scaled(size).height().ceil() as u32
+}
+
+// Draws one line of anti-aliased text with its top at `y`.
+fn draw_text(
+    canvas: &mut RgbaImage,
+    font: &FontRef<'static>,
+    size: f32,
+    text: &str,
+    (x, y): (u32, u32),
+    color: [u8; 3],
+) {
+    let scaled = font.as_scaled(size);
+    let baseline = y as f32 + scaled.ascent();
+    let mut caret = x as f32;
+    let mut previous: Option<GlyphId> = None;
+    for c in text.chars() {
+        let id = scaled.glyph_id(c);
+        if let Some(previous) = previous {
+            caret += scaled.kern(previous, id);
+        }
+        let glyph = id.with_scale_and_position(size, point(caret, baseline));
+        caret += scaled.h_advance(id);
+        previous = Some(id);
+        let Some(outlined) = font.outline_glyph(glyph) else {
+            continue;
+        };
+        let bounds = outlined.px_bounds();
+        outlined.draw(|gx, gy, coverage| {
+            let px = bounds.min.x + gx as f32;
+            let py = bounds.min.y + gy as f32;
+            if px >= 0.0 && py >= 0.0 {
+                blend_pixel(canvas, px as u32, py as u32, color, coverage.min(1.0));
+            }
+        });
+    }
+}
+
+// The longest prefix of `text` that fits in `max_width`.
+fn fit_text(font: &FontRef<'static>, size: f32, text: &str, max_width: f32) -> String {
+    let mut fitted = String::new();
+    for c in text.chars() {
+        fitted.push(c);
+        if text_width(font, size, &fitted) > max_width {
+            fitted.pop();
+            break;
+        }
+    }
+    fitted
+}
+
+// Greedy word wrap by rendered width; words wider than a line are split.
+fn wrap_text(
+    font: &FontRef<'static>,
+    size: f32,
+    text: &str,
+    max_width: f32,
+    max_lines: usize,
+) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        let mut word = word.to_string();
+        while !word.is_empty() && lines.len() < max_lines {
+            let candidate = if current.is_empty() {
+                word.clone()
+            } else {
+                format!("{} {}", current, word)
+            };
+            if text_width(font, size, &candidate) <= max_width {
+                current = candidate;
+                word.clear();
+            } else if !current.is_empty() {
+                lines.push(std::mem::take(&mut current));
+            } else {
+                let head = fit_text(font, size, &word, max_width);
+                if head.is_empty() {
+                    // Not even one character fits.
+                    return lines;
+                }
+                word = word[head.len()..].to_string();
+                lines.push(head);
+            }
+        }
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    lines.truncate(max_lines);
+    lines
+}
+
+fn draw_avatar(canvas: &mut RgbaImage, avatar: &DynamicImage, (x, y): (u32, u32), size: u32) {
+    let avatar = avatar
+        .resize_to_fill(size, size, FilterType::Lanczos3)
+        .to_rgba8();
+    let radius = size as f32 / 2.0;
+    for (ax, ay, pixel) in avatar.enumerate_pixels() {
+        let dx = ax as f32 + 0.5 - radius;
+        let dy = ay as f32 + 0.5 - radius;
+        if dx * dx + dy * dy <= radius * radius {
+            blend_pixel(
+                canvas,
+                x + ax,
+                y + ay,
+                [pixel[0], pixel[1], pixel[2]],
+                pixel[3] as f32 / 255.0,
+            );
+        }
+    }
+}
+
+// The sender panel the editor draws: avatar, credit line and caption on a translucent box in
+// one corner of the frame.
+pub(crate) fn draw_overlay(
+    canvas: &mut RgbaImage,
+    overlay: &OverlaySettings,
+    sender: &str,
+    message: &str,
+    avatar: Option<&DynamicImage>,
+) {
+    if overlay.position == OverlayPosition::Hidden {
+        return;
+    }
+    let factor = canvas.width() as f32 / REFERENCE_WIDTH;
+    let px = |value: f32| ((value * factor).round() as u32).max(1);
+    let margin = px(OVERLAY_MARGIN);
+    let padding = px(OVERLAY_PADDING);
+    let avatar_size = px(OVERLAY_AVATAR_SIZE);
+    let line_gap = px(OVERLAY_LINE_GAP);
+    let sender_size = SENDER_FONT_SIZE * factor;
+    let message_size = MESSAGE_FONT_SIZE * factor;
+    let sender_height = line_height(&SANS_BOLD, sender_size);
+    let message_height = line_height(&SANS, message_size);
+    let avatar = avatar.filter(|_| overlay.show_avatar);
+
+    let panel_width = px(overlay.width).min(canvas.width().saturating_sub(2 * margin));
+    let text_x = padding + avatar.map_or(0, |_| avatar_size + px(OVERLAY_GAP));
+    let text_room = panel_width.saturating_sub(text_x + padding) as f32;
+    let sender_line = fit_text(&SANS_BOLD, sender_size, sender, text_room);
+    let message_lines = wrap_text(&SANS, message_size, message, text_room, MESSAGE_MAX_LINES);
+    let text_height = sender_height + message_lines.len() as u32 * (line_gap + message_height);
+    let content_height = text_height.max(avatar.map_or(0, |_| avatar_size));
+    let panel_height =
+        (content_height + 2 * padding).min(canvas.height().saturating_sub(2 * margin));
+
+    let right = canvas.width().saturating_sub(margin + panel_width);
+    let bottom = canvas.height().saturating_sub(margin + panel_height);
+    let (x, y) = match overlay.position {
+        OverlayPosition::TopLeft => (margin, margin),
+        OverlayPosition::TopRight => (right, margin),
+        OverlayPosition::BottomLeft => (margin, bottom),
+        OverlayPosition::BottomRight | OverlayPosition::Hidden => (right, bottom),
+    };
+    let (background, text_color) = match overlay.style {
+        OverlayStyle::Black => ([0, 0, 0], [255, 255, 255]),
+        OverlayStyle::White => ([255, 255, 255], [0, 0, 0]),
+    };
+    let opacity = 1.0 - overlay.transparency.min(100) as f32 / 100.0;
+    fill_rect(
+        canvas,
+        (x, y),
+        (panel_width, panel_height),
+        background,
+        opacity,
+    );
+
+    if let Some(avatar) = avatar {
+        draw_avatar(canvas, avatar, (x + padding, y + padding), avatar_size);
+    }
+    // A name without a caption is centered next to the avatar.
+    let mut text_y = y + padding + (content_height - text_height) / 2;
+    draw_text(
+        canvas,
+        &SANS_BOLD,
+        sender_size,
+        &sender_line,
+        (x + text_x, text_y),
+        text_color,
+    );
+    text_y += sender_height;
+    for line in &message_lines {
+        text_y += line_gap;
+        draw_text(
+            canvas,
+            &SANS,
+            message_size,
+            line,
+            (x + text_x, text_y),
+            text_color,
+        );
+        text_y += message_height;
+    }
+}
+
+pub(crate) fn encode_slide(canvas: RgbaImage, extension: &str) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    if extension == "jpg" {
+        JpegEncoder::new_with_quality(&mut bytes, SLIDE_JPEG_QUALITY)
+            .encode_image(&DynamicImage::ImageRgba8(canvas).to_rgb8())
+            .context("Failed to encode slide")?;
+    } else {
+        DynamicImage::ImageRgba8(canvas)
+            .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+            .context("Failed to encode slide")?;
+    }
+    Ok(bytes)
+}
+
+// Where one slide's pixels come from. Exports draw the overlay here on the original attachment,
+// so every machine produces the same slide whatever the webview's DPI. The editor's uploaded
+// render, which already carries the overlay, stands in when the original is gone from the cache.
+pub(crate) struct SlidePlan {
+    pub slide_key: String,
+    pub render: Option<PathBuf>,
+    pub original: Option<PathBuf>,
+    pub overlay: Option<ShowcaseImage>,
+    pub avatar: Option<PathBuf>,
+}
+
+// Slides follow the sorted image order when available, otherwise the selection order, like
+// get_showcase_render_info. Captions are redacted as they are everywhere else, and each overlay
+// carries its author's resolved credit.
+pub(crate) fn plan_slides(
+    showcase: &Showcase,
+    credits: &[ShowcaseCredit],
+    image_base_dir: &Path,
+    showcase_dir: &Path,
+    redact: impl Fn(&str) -> String,
+) -> Vec<SlidePlan> {
+    let selected_messages = showcase.selected_messages.clone().unwrap_or_default();
+    let slides: Vec<(String, usize, Option<&ShowcaseImage>)> = match &showcase.images {
+        Some(images) if !images.is_empty() => images
+            .iter()
+            .map(|image| {
+                (
+                    image.message_id.clone(),
+                    image.attachment_index,
+                    Some(image),
+                )
+            })
+            .collect(),
+        _ => selected_messages
+            .iter()
+            .flat_map(|m| {
+                (0..m.attachment_filenames().len())
+                    .map(move |index| (m.message_id.clone(), index, None))
+            })
+            .collect(),
+    };
+    slides
+        .into_iter()
+        .map(|(message_id, attachment_index, image)| {
+            let key = slide_key(&message_id, attachment_index);
+            let message = selected_messages
+                .iter()
+                .find(|m| m.message_id == message_id);
+            SlidePlan {
+                render: find_slide_image(showcase_dir, &showcase.id, &key),
+                original: message
+                    .and_then(|m| m.attachment_filenames().get(attachment_index).cloned())
+                    .map(|filename| image_base_dir.join(filename)),
+                overlay: image.map(|image| ShowcaseImage {
+                    message: redact(&image.message),
+                    credit: credits
+                        .iter()
+                        .find(|c| c.message_id == image.message_id)
+                        .map(|c| c.credit_text.clone())
+                        .or_else(|| image.credit.clone()),
+                    ..image.clone()
+                }),
+                avatar: message
+                    .and_then(|m| m.author_avatar_path.as_deref())
+                    .filter(|path| !path.is_empty())
+                    .map(|path| image_base_dir.join(path)),
+                slide_key: key,
+            }
+        })
+        .collect()
+}
+
+pub(crate) fn render_slide(plan: &SlidePlan, width: u32, height: u32) -> Result<RgbaImage, String> {
+    let original = match plan.original.as_ref().map(|path| read_image(path)) {
+        Some(Ok(original)) => Some(original),
+        Some(Err(e)) if plan.render.is_some() => {
+            warn!(
+                "Using the uploaded render of slide {}: {}",
+                plan.slide_key, e
+            );
+            None
+        }
+        Some(Err(e)) => return Err(e),
+        None => None,
+    };
+    let Some(original) = original else {
+        let render = plan
+            .render
+            .as_ref()
+            .ok_or_else(|| format!("Slide {} has no image", plan.slide_key))?;
+        return Ok(fit_on_background(&read_image(render)?, width, height));
+    };
+    let mut canvas = fit_on_background(&original, width, height);
+    if let Some(slide) = &plan.overlay {
+        let avatar = plan.avatar.as_ref().and_then(|path| {
+            read_image(path)
+                .map_err(|e| warn!("Drawing slide {} without avatar: {}", plan.slide_key, e))
+                .ok()
+        });
+        draw_overlay(
+            &mut canvas,
+            &slide.overlay,
+            slide.credit.as_deref().unwrap_or(&slide.sender),
+            &slide.message,
+            avatar.as_ref(),
+        );
+    }
+    Ok(canvas)
+}
+
+// Every credit once, in selection order.
+pub(crate) fn unique_credits(credits: &[ShowcaseCredit]) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for credit in credits {
+        let text = credit.credit_text.trim();
+        if !text.is_empty() && !lines.iter().any(|line| line == text) {
+            lines.push(text.to_string());
+        }
+    }
+    lines
+}
+
+// A closing slide listing the credits under a heading. Lines that don't fit are cut rather
+// than shrunk, like the overlay's credit line.
+pub(crate) fn render_credits_slide(credits: &[String], width: u32, height: u32) -> RgbaImage {
+    let mut canvas = RgbaImage::from_pixel(width, height, Rgba([0, 0, 0, 255]));
+    let factor = width as f32 / REFERENCE_WIDTH;
+    let title_size = CREDITS_TITLE_FONT_SIZE * factor;
+    let credit_size = CREDITS_FONT_SIZE * factor;
+    let title_height = line_height(&SANS_BOLD, title_size);
+    let credit_height = line_height(&SANS, credit_size);
+    let line_gap = ((CREDITS_LINE_GAP * factor).round() as u32).max(1);
+    let room = width as f32 * 0.8;
+
+    // Only as many lines as fit below the heading.
+    let available = height.saturating_sub(title_height + 4 * line_gap);
+    let lines = &credits[..credits
+        .len()
+        .min((available / (credit_height + line_gap)) as usize)];
+
+    let block_height = title_height + lines.len() as u32 * (credit_height + line_gap) + line_gap;
+    let mut y = height.saturating_sub(block_height) / 2;
+    let centered = |font: &FontRef<'static>, size: f32, text: &str| {
+        (width as f32 - text_width(font, size, text)).max(0.0) as u32 / 2
+    };
+    draw_text(
+        &mut canvas,
+        &SANS_BOLD,
+        title_size,
+        "Credits",
+        (centered(&SANS_BOLD, title_size, "Credits"), y),
+        [255, 255, 255],
+    );
+    y += title_height + line_gap;
+    for line in lines {
+        let line = fit_text(&SANS, credit_size, line, room);
+        y += line_gap;
+        draw_text(
+            &mut canvas,
+            &SANS,
+            credit_size,
+            &line,
+            (centered(&SANS, credit_size, &line), y),
+            [255, 255, 255],
+        );
+        y += credit_height;
+    }
+    canvas
+}
+
+// Renders every slide into a fresh folder; slides that fail are listed instead of aborting
+// the export.
+pub(crate) fn write_slide_images(
+    plans: &[SlidePlan],
+    folder: &Path,
+    extension: &str,
+    (width, height): (u32, u32),
+) -> Result<(Vec<String>, Vec<String>), String> {
+    if folder.exists() {
+        fs::remove_dir_all(folder).context(format!("Failed to clear '{}'", folder.display()))?;
+    }
+    fs::create_dir_all(folder).context(format!("Failed to create '{}'", folder.display()))?;
+
+    let mut files = Vec::new();
+    let mut skipped = Vec::new();
+    for plan in plans {
+        let encoded =
+            render_slide(plan, width, height).and_then(|canvas| encode_slide(canvas, extension));
+        match encoded {
+            Ok(bytes) => {
+                let file_name = format!("slide_{:03}.{}", files.len() + 1, extension);
+                let path = folder.join(&file_name);
+                fs::write(&path, bytes).context(format!("Failed to write '{}'", path.display()))?;
+                files.push(file_name);
+            }
+            Err(e) => {
+                warn!("Skipping slide {} in image export: {}", plan.slide_key, e);
+                skipped.push(plan.slide_key.clone());
+            }
+        }
+    }
+    Ok((files, skipped))
+}
+
+// Writes each slide as a standalone picture under presentations/<id>/slides, ready to post
+// without opening PowerPoint. A previous export in that folder is replaced.
+#[tauri::command]
+pub async fn export_showcase_images(
+    app_handle: AppHandle,
+    id: String,
+    format: String,
+    resolution: Option<String>,
+    db_state: State<'_, DbConnection>,
+) -> Result<SlideImageExport, AppError> {
+    let extension = parse_image_format(&format)?;
+    let (width, height) = parse_resolution(resolution.as_deref())?;
+    let span = Span::root("export.images", &[("showcase_id", id.clone())]);
+
+    let lookup_id = id.clone();
+    let (showcase, credits, redaction_rules) = db_state
+        .0
+        .run(move |conn| {
+            let showcase = load_showcase(conn, &lookup_id)?;
+            let selected_messages = showcase.selected_messages.clone().unwrap_or_default();
+            let credits = resolve_showcase_credits(conn, &selected_messages)?;
+            let rules = retrieve_config(conn)?.redaction_rules.unwrap_or_default();
+            Ok((showcase, credits, rules))
+        })
+        .await?;
+    let image_base_dir = get_image_base_dir(&app_handle)?;
+    let showcase_dir = get_showcase_image_dir(&app_handle, &id)?;
+    let plans = plan_slides(
+        &showcase,
+        &credits,
+        &image_base_dir,
+        &showcase_dir,
+        |text| redact_text(text, &redaction_rules),
+    );
+    if plans.is_empty() {
+        span.fail("no slides");
+        return Err(AppError::invalid_input(
+            "This showcase has no slides to export.",
+        ));
+    }
+
+    let folder = get_showcase_presentation_dir(&app_handle, &id)?.join(SLIDE_EXPORT_DIR);
+    let task_folder = folder.clone();
+    let written = tokio::task::spawn_blocking(move || {
+        write_slide_images(&plans, &task_folder, extension, (width, height))
+    })
+    .await
+    .map_err(|e| format!("Slide export task panicked or was cancelled: {}", e))
+    .and_then(|result| result);
+    let (files, skipped) = match written {
+        Ok(written) => written,
+        Err(e) => {
+            span.fail(&e);
+            return Err(e.into());
+        }
+    };
+    span.record("slides", files.len());
+    info!(
+        "Exported {} slide image(s) for showcase ID: {} ({} skipped)",
+        files.len(),
+        id,
+        skipped.len()
+    );
+    Ok(SlideImageExport {
+        folder: folder.to_string_lossy().into_owned(),
+        files,
+        skipped,
+        format: extension.to_string(),
+        width,
+        height,
+    })
+}
+
+// One slide exactly as the image, video, web and deck exports draw it. Without `composite` the
+// editor's uploaded render is shown instead, to compare the two.
+pub(crate) fn preview_slide(
+    plans: Vec<SlidePlan>,
+    slide_key: &str,
+    composite: bool,
+    (width, height): (u32, u32),
+) -> Result<Vec<u8>, AppError> {
+    let Some(mut plan) = plans.into_iter().find(|plan| plan.slide_key == slide_key) else {
+        return Err(AppError::not_found(format!(
+            "Slide '{}' is not part of this showcase.",
+            slide_key
+        )));
+    };
+    if !composite && plan.render.is_some() {
+        plan.original = None;
+    }
+    let canvas = render_slide(&plan, width, height)?;
+    Ok(encode_slide(canvas, "png")?)
+}
+
+#[tauri::command]
+pub async fn render_slide_preview(
+    app_handle: AppHandle,
+    id: String,
+    message_id: String,
+    attachment_index: Option<usize>,
+    resolution: Option<String>,
+    composite: Option<bool>,
+    db_state: State<'_, DbConnection>,
+) -> Result<String, AppError> {
+    let resolution = parse_resolution(resolution.as_deref())?;
+    let key = slide_key(&message_id, attachment_index.unwrap_or(0));
+
+    let lookup_id = id.clone();
+    let (showcase, credits, redaction_rules) = db_state
+        .0
+        .run(move |conn| {
+            let showcase = load_showcase(conn, &lookup_id)?;
+            let selected_messages = showcase.selected_messages.clone().unwrap_or_default();
+            let credits = resolve_showcase_credits(conn, &selected_messages)?;
+            let rules = retrieve_config(conn)?.redaction_rules.unwrap_or_default();
+            Ok((showcase, credits, rules))
+        })
+        .await?;
+    let image_base_dir = get_image_base_dir(&app_handle)?;
+    let showcase_dir = get_showcase_image_dir(&app_handle, &id)?;
+    let plans = plan_slides(
+        &showcase,
+        &credits,
+        &image_base_dir,
+        &showcase_dir,
+        |text| redact_text(text, &redaction_rules),
+    );
+    let composite = composite.unwrap_or(true);
+    let png =
+        tokio::task::spawn_blocking(move || preview_slide(plans, &key, composite, resolution))
+            .await
+            .context("Slide preview task panicked or was cancelled")??;
+    Ok(format!(
+        "data:image/png;base64,{}",
+        base64_engine.encode(png)
+    ))
+}
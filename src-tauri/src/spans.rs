@@ -0,0 +1,111 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+// Span lines share one target so a run can be rebuilt from the backend log with a grep:
+//   SPAN_START id=4 parent=1 name=indexing.channel channel_id=123
+//   SPAN_END id=4 parent=1 name=indexing.channel status=ok duration_ms=5120 messages_saved=12
+const SPAN_TARGET: &str = "span";
+
+pub const STATUS_OK: &str = "ok";
+pub const STATUS_ERROR: &str = "error";
+pub const STATUS_CANCELLED: &str = "cancelled";
+
+static NEXT_SPAN_ID: AtomicU64 = AtomicU64::new(1);
+
+pub struct Span {
+    id: u64,
+    parent_id: Option<u64>,
+    name: &'static str,
+    started: Instant,
+    status: Mutex<&'static str>,
+    end_fields: Mutex<Vec<String>>,
+}
+
+fn format_field(key: &str, value: &str) -> String {
+    if value.is_empty() || value.contains(char::is_whitespace) || value.contains('"') {
+        format!("{}={:?}", key, value)
+    } else {
+        format!("{}={}", key, value)
+    }
+}
+
+fn format_fields(fields: &[(&str, String)]) -> String {
+    fields
+        .iter()
+        .map(|(key, value)| format!(" {}", format_field(key, value)))
+        .collect()
+}
+
+impl Span {
+    fn start(name: &'static str, parent_id: Option<u64>, fields: &[(&str, String)]) -> Span {
+        let id = NEXT_SPAN_ID.fetch_add(1, Ordering::Relaxed);
+        log::info!(
+            target: SPAN_TARGET,
+            "SPAN_START id={} parent={} name={}{}",
+            id,
+            parent_id.map_or("-".to_string(), |p| p.to_string()),
+            name,
+            format_fields(fields)
+        );
+        Span {
+            id,
+            parent_id,
+            name,
+            started: Instant::now(),
+            status: Mutex::new(STATUS_OK),
+            end_fields: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn root(name: &'static str, fields: &[(&str, String)]) -> Span {
+        Span::start(name, None, fields)
+    }
+
+    pub fn child(&self, name: &'static str, fields: &[(&str, String)]) -> Span {
+        Span::start(name, Some(self.id), fields)
+    }
+
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    // Adds a field to the SPAN_END line, e.g. counts only known once the work is done.
+    pub fn record(&self, key: &str, value: impl ToString) {
+        if let Ok(mut fields) = self.end_fields.lock() {
+            fields.push(format_field(key, &value.to_string()));
+        }
+    }
+
+    pub fn set_status(&self, status: &'static str) {
+        if let Ok(mut current) = self.status.lock() {
+            *current = status;
+        }
+    }
+
+    pub fn fail(&self, error_message: &str) {
+        self.set_status(STATUS_ERROR);
+        self.record("error", error_message);
+    }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        let status = self.status.lock().map(|s| *s).unwrap_or(STATUS_OK);
+        let fields: String = self
+            .end_fields
+            .lock()
+            .map(|fields| fields.iter().map(|f| format!(" {}", f)).collect())
+            .unwrap_or_default();
+        log::info!(
+            target: SPAN_TARGET,
+            "SPAN_END id={} parent={} name={} status={} duration_ms={}{}",
+            self.id,
+            self.parent_id.map_or("-".to_string(), |p| p.to_string()),
+            self.name,
+            status,
+            self.started.elapsed().as_millis(),
+            fields
+        );
+    }
+}
@@ -1,353 +1,297 @@
-use keyring::Entry;
-use regex::Regex;
-use rusqlite::{params, Connection as RusqliteConnection};
-use rusqlite::{Connection, Error as RusqliteError, Row};
+use rusqlite::{params, params_from_iter, Connection as RusqliteConnection};
+use rusqlite::{types::Value as RusqliteValue, Connection, Error as RusqliteError, Row};
 use serde_json;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, Mutex, MutexGuard};
-use tauri::{AppHandle, Manager, State};
-
-use crate::models::{AppConfig, CleanupStats, FirstSlideSettings, IndexedMessage, OverlaySettings, StorageUsage};
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Condvar, Mutex, PoisonError};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, State};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use crate::app_paths;
+use crate::avatars::AVATAR_DIR;
+use crate::cache_crypto;
+use crate::db_encryption;
+use crate::discord::{IndexingState, BOT_TOKEN_KEY};
+use crate::error::{AppError, ErrorKind, ResultExt};
+use crate::migrations::{self, CURRENT_SCHEMA_VERSION};
+use crate::models::{AppConfig, AuthorCapSettings, AuthorFilterSettings, CachedImageBatch, ChannelIndexStatus, CacheClearResult, ChannelIndexingStats, ChannelStorage, CleanupProgress, CleanupScheduleSettings, CompactProgress, CompactResult, ConfigHistoryEntry, CleanupStats, ContentFilterSettings, DateRange, FirstSlideSettings, ImageSizeFilter, IndexedMessage, MessageDeletionResult, MessageExportResult, MessagePage, MessageQuery, MessageSearchFilters, NetworkSettings, OverlaySettings, ReactionSummary, RedactionRules, ReplyContext, ShowcaseStorage, StorageUsage, ThumbnailSettings};
+use crate::providers::{KeyringSecrets, PathProvider, SecretStore};
+use crate::showcase_manager::{
+    get_showcase_image_dir, get_showcase_image_pack, get_showcase_presentation_dir,
+};
+use crate::spans::Span;
+use crate::thumbnails;
 use crate::{log_error as error, log_info as info, log_warn as warn};
 
 use base64::{engine::general_purpose::STANDARD as base64_engine, Engine as _};
 use mime_guess;
 
 const DB_FILENAME: &str = "showcase_app_data.db";
-const CURRENT_SCHEMA_VERSION: i32 = 1;
-
-const SQL_CREATE_SCHEMA_VERSION_TABLE: &str = "
-CREATE TABLE IF NOT EXISTS schema_version (
-    version INTEGER PRIMARY KEY NOT NULL
-);";
-
-const SQL_CREATE_CONFIG_TABLE: &str = "
-CREATE TABLE IF NOT EXISTS config (
-    key TEXT PRIMARY KEY NOT NULL,
-    value TEXT NOT NULL
-);";
-
-const SQL_CREATE_SHOWCASES_TABLE: &str = "
-CREATE TABLE IF NOT EXISTS showcases (
-    id TEXT PRIMARY KEY NOT NULL,
-    title TEXT NOT NULL,
-    description TEXT,
-    status TEXT NOT NULL DEFAULT 'Draft', 
-    created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
-    last_modified INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
-    phase INTEGER NOT NULL DEFAULT 1,           
-    selected_messages_json TEXT,  
-    pptx_path TEXT,              
-    images_json TEXT                           
-);";
-
-const SQL_CREATE_MESSAGES_TABLE: &str = "
-CREATE TABLE IF NOT EXISTS messages (
-    message_id TEXT PRIMARY KEY NOT NULL,      
-    channel_id TEXT NOT NULL,                  
-    author_id TEXT NOT NULL,                   
-    author_name TEXT NOT NULL,                 
-    author_avatar TEXT,                        
-    message_content TEXT NOT NULL,             
-    attachments TEXT NOT NULL DEFAULT '[]',   
-    timestamp INTEGER NOT NULL,
-    is_used INTEGER NOT NULL DEFAULT 0      
-);";
-
-const SQL_CREATE_MESSAGES_CHANNEL_INDEX: &str = "
-CREATE INDEX IF NOT EXISTS idx_messages_channel_id ON messages (channel_id);";
-
-const SQL_CREATE_MESSAGES_TIMESTAMP_INDEX: &str = "
-CREATE INDEX IF NOT EXISTS idx_messages_timestamp ON messages (timestamp);";
-
-const SQL_CREATE_MESSAGES_AUTHOR_INDEX: &str = "
-CREATE INDEX IF NOT EXISTS idx_messages_author_id ON messages (author_id);";
+const CONFIG_HISTORY_LIMIT: i64 = 1000;
 
 #[derive(Clone)]
-pub struct DbConnection(pub Arc<Mutex<RusqliteConnection>>);
+pub struct DbConnection(pub DbPool);
 
-fn get_db_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
-    let app_data_dir = app_handle
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
+const DB_POOL_SIZE: usize = 4;
+// How long a write waits for another connection's write to finish before failing as busy.
+const DB_BUSY_TIMEOUT: Duration = Duration::from_secs(10);
 
-    let path = app_data_dir.join(DB_FILENAME);
+type ConnectionOpener = dyn Fn() -> Result<RusqliteConnection, String> + Send + Sync;
 
-    if let Some(parent_dir) = path.parent() {
-        fs::create_dir_all(parent_dir)
-            .map_err(|e| format!("Failed to create database directory: {}", e))?;
-    } else {
-        return Err("Failed to determine parent directory for database.".to_string());
-    }
+struct PoolState {
+    idle: Vec<RusqliteConnection>,
+    open: usize,  // idle plus handed out
+    closed: bool, // set while with_closed has the file
+}
 
-    Ok(path)
+struct PoolInner {
+    state: Mutex<PoolState>,
+    returned: Condvar,
+    opener: Box<ConnectionOpener>,
 }
 
-fn parse_create_table_statement(
-    create_sql: &str,
-) -> Result<(String, Vec<(String, String)>), String> {
-    let table_name_re = Regex::new(r"CREATE TABLE IF NOT EXISTS (\w+)").unwrap();
-    let table_name = match table_name_re.captures(create_sql) {
-        Some(caps) => caps.get(1).unwrap().as_str().to_string(),
-        None => return Err("Could not extract table name from CREATE TABLE statement".to_string()),
-    };
+// A few connections to the same database file, opened on demand. WAL lets readers run next to
+// the single writer, and the busy timeout makes concurrent writers queue instead of failing.
+#[derive(Clone)]
+pub struct DbPool(Arc<PoolInner>);
+
+pub struct PooledConnection {
+    conn: Option<RusqliteConnection>, // only None while being returned on drop
+    pool: Arc<PoolInner>,
+}
 
-    let mut columns = Vec::new();
+impl DbPool {
+    pub fn new(
+        first: RusqliteConnection,
+        opener: impl Fn() -> Result<RusqliteConnection, String> + Send + Sync + 'static,
+    ) -> Self {
+        DbPool(Arc::new(PoolInner {
+            state: Mutex::new(PoolState {
+                idle: vec![first],
+                open: 1,
+                closed: false,
+            }),
+            returned: Condvar::new(),
+            opener: Box::new(opener),
+        }))
+    }
 
-    let columns_re = Regex::new(r"\(\s*([\s\S]+?)\s*\);").unwrap();
-    let columns_text = match columns_re.captures(create_sql) {
-        Some(caps) => caps.get(1).unwrap().as_str(),
-        None => {
-            return Err(
-                "Could not extract column definitions from CREATE TABLE statement".to_string(),
-            )
+    // Hands out an idle connection, opens another while below DB_POOL_SIZE, or waits for one.
+    pub fn lock(&self) -> Result<PooledConnection, AppError> {
+        let mut state = self.0.state.lock()?;
+        loop {
+            if !state.closed {
+                if let Some(conn) = state.idle.pop() {
+                    return Ok(self.pooled(conn));
+                }
+                if state.open < DB_POOL_SIZE {
+                    state.open += 1;
+                    drop(state);
+                    return match (self.0.opener)() {
+                        Ok(conn) => Ok(self.pooled(conn)),
+                        Err(e) => {
+                            self.0.state.lock()?.open -= 1;
+                            self.0.returned.notify_one();
+                            Err(AppError::from(e))
+                        }
+                    };
+                }
+            }
+            state = self.0.returned.wait(state)?;
         }
-    };
+    }
 
-    for line in columns_text.split(',') {
-        let line = line.trim();
-        if line.starts_with("PRIMARY KEY") || line.starts_with("FOREIGN KEY") || line.is_empty() {
-            continue;
+    fn pooled(&self, conn: RusqliteConnection) -> PooledConnection {
+        PooledConnection {
+            conn: Some(conn),
+            pool: self.0.clone(),
         }
+    }
 
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        if parts.len() >= 2 {
-            let column_name = parts[0].to_string();
-            let column_def = parts[1..].join(" ");
+    // Runs `f` on a pooled connection on the blocking thread pool, so a slow query only
+    // occupies that thread instead of an async runtime worker.
+    pub async fn run<T, F>(&self, f: F) -> Result<T, AppError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut RusqliteConnection) -> Result<T, AppError> + Send + 'static,
+    {
+        let pool = self.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut conn = pool.lock()?;
+            f(&mut conn)
+        })
+        .await
+        .map_err(|e| AppError::new(ErrorKind::Internal, format!("Database task failed: {}", e)))?
+    }
 
-            columns.push((column_name, column_def));
+    // Closes every connection so the file can be deleted or replaced, then runs `f`. Waits for
+    // connections in use to come back, so the caller must not hold one. Connections opened
+    // afterwards see whatever file `f` left behind. Blocks; async code uses `run_closed`.
+    pub fn with_closed<T>(&self, f: impl FnOnce() -> T) -> Result<T, AppError> {
+        // Declared first so it drops last: the pool reopens even if `f` panics.
+        let reopen = ReopenOnDrop(&self.0);
+        let mut state = self.0.state.lock()?;
+        state.closed = true;
+        while state.idle.len() < state.open {
+            state = self.0.returned.wait(state)?;
         }
+        state.idle.clear();
+        state.open = 0;
+        // `closed` keeps other callers waiting; the mutex itself is not held while `f` runs.
+        drop(state);
+        let result = f();
+        drop(reopen);
+        Ok(result)
     }
 
-    Ok((table_name, columns))
+    // `with_closed` on the blocking thread pool, for the same reason as `run`.
+    pub async fn run_closed<T, F>(&self, f: F) -> Result<T, AppError>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        let pool = self.clone();
+        tokio::task::spawn_blocking(move || pool.with_closed(f))
+            .await
+            .map_err(|e| AppError::new(ErrorKind::Internal, format!("Database task failed: {}", e)))?
+    }
 }
 
-fn get_existing_tables(conn: &Connection) -> Result<Vec<String>, String> {
-    let mut stmt = conn
-        .prepare("SELECT name FROM sqlite_master WHERE type='table' AND name NOT LIKE 'sqlite_%'")
-        .map_err(|e| format!("Failed to prepare query for existing tables: {}", e))?;
-
-    let tables = stmt
-        .query_map([], |row| row.get::<_, String>(0))
-        .map_err(|e| format!("Failed to query existing tables: {}", e))?
-        .collect::<Result<Vec<String>, _>>()
-        .map_err(|e| format!("Error processing table names: {}", e))?;
+struct ReopenOnDrop<'a>(&'a PoolInner);
 
-    Ok(tables)
+impl Drop for ReopenOnDrop<'_> {
+    fn drop(&mut self) {
+        let mut state = self.0.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state.closed = false;
+        self.0.returned.notify_all();
+    }
 }
 
-fn get_existing_columns(
-    conn: &Connection,
-    table_name: &str,
-) -> Result<HashMap<String, String>, String> {
-    let mut stmt = conn
-        .prepare(&format!("PRAGMA table_info({})", table_name))
-        .map_err(|e| {
-            format!(
-                "Failed to prepare query for columns of {}: {}",
-                table_name, e
-            )
-        })?;
-
-    let columns = stmt
-        .query_map([], |row| {
-            let name: String = row.get(1)?;
-            let type_name: String = row.get(2)?;
-            let notnull: bool = row.get(3)?;
-            let dflt_value: Option<String> = row.get(4)?;
-            let pk: bool = row.get(5)?;
-
-            let mut def = type_name;
-            if pk {
-                def += " PRIMARY KEY";
-            }
-            if notnull {
-                def += " NOT NULL";
-            }
-            if let Some(default) = dflt_value {
-                def += &format!(" DEFAULT {}", default);
-            }
+impl Deref for PooledConnection {
+    type Target = RusqliteConnection;
 
-            Ok((name, def))
-        })
-        .map_err(|e| format!("Failed to query columns for {}: {}", table_name, e))?
-        .collect::<Result<HashMap<String, String>, _>>()
-        .map_err(|e| format!("Error processing column info: {}", e))?;
-
-    Ok(columns)
+    fn deref(&self) -> &RusqliteConnection {
+        self.conn.as_ref().expect("pooled connection used after return")
+    }
 }
 
-fn update_database_schema(conn: &mut Connection) -> Result<(), String> {
-    info!("Starting dynamic schema analysis and update...");
-
-    let tx = conn
-        .transaction()
-        .map_err(|e| format!("Failed to start schema update transaction: {}", e))?;
-
-    let table_definitions = vec![
-        SQL_CREATE_CONFIG_TABLE,
-        SQL_CREATE_SHOWCASES_TABLE,
-        SQL_CREATE_MESSAGES_TABLE,
-    ];
-
-    let existing_tables = get_existing_tables(&tx)?;
-    info!("Existing tables: {:?}", existing_tables);
-
-    for create_sql in table_definitions {
-        let (table_name, expected_columns) = parse_create_table_statement(create_sql)?;
-
-        if !existing_tables.contains(&table_name) {
-            info!("Creating missing table: {}", table_name);
-            tx.execute(create_sql, [])
-                .map_err(|e| format!("Failed to create table {}: {}", table_name, e))?;
-        } else {
-            let existing_columns = get_existing_columns(&tx, &table_name)?;
-
-            for (col_name, col_def) in &expected_columns {
-                if !existing_columns.contains_key(col_name) {
-                    info!("Adding missing column: {}.{}", table_name, col_name);
-
-                    let simple_def = if col_def.contains("PRIMARY KEY") {
-                        col_def.replace("PRIMARY KEY", "").trim().to_string()
-                    } else {
-                        col_def.clone()
-                    };
-
-                    let alter_sql = format!(
-                        "ALTER TABLE {} ADD COLUMN {} {}",
-                        table_name, col_name, simple_def
-                    );
+impl DerefMut for PooledConnection {
+    fn deref_mut(&mut self) -> &mut RusqliteConnection {
+        self.conn.as_mut().expect("pooled connection used after return")
+    }
+}
 
-                    tx.execute(&alter_sql, []).map_err(|e| {
-                        format!("Failed to add column {}.{}: {}", table_name, col_name, e)
-                    })?;
-                }
-            }
+impl Drop for PooledConnection {
+    fn drop(&mut self) {
+        if let (Some(conn), Ok(mut state)) = (self.conn.take(), self.pool.state.lock()) {
+            state.idle.push(conn);
+            self.pool.returned.notify_all();
         }
     }
+}
 
-    let index_definitions = vec![
-        SQL_CREATE_MESSAGES_CHANNEL_INDEX,
-        SQL_CREATE_MESSAGES_TIMESTAMP_INDEX,
-        SQL_CREATE_MESSAGES_AUTHOR_INDEX,
-    ];
-
-    for index_sql in index_definitions {
-        tx.execute(index_sql, [])
-            .map_err(|e| format!("Failed to create index: {}", e))?;
-    }
+pub(crate) fn get_db_path(paths: &impl PathProvider) -> Result<PathBuf, String> {
+    let app_data_dir = paths.app_data_dir()?;
 
-    set_schema_version(&tx, CURRENT_SCHEMA_VERSION)?;
+    let path = app_data_dir.join(DB_FILENAME);
 
-    tx.commit()
-        .map_err(|e| format!("Failed to commit schema updates: {}", e))?;
+    if let Some(parent_dir) = path.parent() {
+        fs::create_dir_all(parent_dir)
+            .context("Failed to create database directory")?;
+    } else {
+        return Err("Failed to determine parent directory for database.".to_string());
+    }
 
-    info!("Schema update completed successfully.");
-    Ok(())
+    Ok(path)
 }
 
-fn get_schema_version(conn: &Connection) -> Result<i32, String> {
-    let table_exists: bool = conn
-        .query_row(
-            "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type='table' AND name='schema_version')",
-            [],
-            |row| row.get(0),
-        )
-        .map_err(|e| format!("Failed to check if schema_version table exists: {}", e))?;
+pub(crate) fn get_backup_dir(paths: &impl PathProvider) -> Result<PathBuf, String> {
+    Ok(paths.app_data_dir()?.join("backups"))
+}
 
-    if !table_exists {
-        return Ok(0);
+// Best effort: a failed backup is logged but never blocks the migration itself.
+fn backup_before_migration(app_handle: &AppHandle, conn: &Connection, from_version: i32) {
+    let backup_dir = match get_backup_dir(app_handle) {
+        Ok(dir) => dir,
+        Err(e) => {
+            warn!("Skipping pre-migration backup: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = fs::create_dir_all(&backup_dir) {
+        warn!("Skipping pre-migration backup, cannot create '{}': {}", backup_dir.display(), e);
+        return;
     }
-
-    match conn.query_row("SELECT version FROM schema_version LIMIT 1", [], |row| {
-        row.get::<_, i32>(0)
-    }) {
-        Ok(version) => Ok(version),
-        Err(RusqliteError::QueryReturnedNoRows) => Ok(0),
-        Err(e) => Err(format!("Failed to get schema version: {}", e)),
+    let backup_path = backup_dir.join(format!(
+        "showcase_app_data_v{}_{}.db",
+        from_version,
+        chrono::Utc::now().format("%Y%m%d_%H%M%S")
+    ));
+    match conn.execute("VACUUM INTO ?1", [backup_path.to_string_lossy()]) {
+        Ok(_) => info!("Database backed up to {}", backup_path.display()),
+        Err(e) => warn!("Pre-migration backup failed: {}", e),
     }
 }
 
-// Sets the schema version in the database
-fn set_schema_version(conn: &Connection, version: i32) -> Result<(), String> {
-    conn.execute("DELETE FROM schema_version", [])
-        .map_err(|e| format!("Failed to clear schema_version table: {}", e))?;
-
-    conn.execute(
-        "INSERT INTO schema_version (version) VALUES (?1)",
-        [version],
-    )
-    .map_err(|e| format!("Failed to update schema version to {}: {}", version, e))?;
-
-    Ok(())
+pub fn initialize_database(app_handle: &AppHandle) -> Result<DbPool, String> {
+    let span = Span::root("database.init", &[]);
+    let result = open_and_migrate_database(app_handle, &span);
+    if let Err(e) = &result {
+        span.fail(e);
+    }
+    let db_path = get_db_path(app_handle)?;
+    Ok(DbPool::new(result?, move || open_connection(&db_path, &KeyringSecrets)))
 }
 
-pub fn initialize_database(app_handle: &AppHandle) -> Result<Connection, String> {
-    let db_path = get_db_path(app_handle)?;
-    info!("Database path: {}", db_path.display());
+// Opens the database file, unlocking it first when it is encrypted, and applies the PRAGMAs.
+pub(crate) fn open_connection(
+    db_path: &Path,
+    secrets: &impl SecretStore,
+) -> Result<Connection, String> {
+    let conn = Connection::open(db_path)
+        .context("Failed to open database connection")?;
 
-    let is_new_database = !db_path.exists();
-    info!("Database exists: {}", !is_new_database);
+    info!("Database connection opened successfully.");
 
-    let mut conn = Connection::open(&db_path)
-        .map_err(|e| format!("Failed to open database connection: {}", e))?;
+    if db_encryption::unlock_if_encrypted(&conn, db_path, secrets)? {
+        info!("Unlocked encrypted database.");
+    }
 
-    info!("Database connection opened successfully.");
+    conn.busy_timeout(DB_BUSY_TIMEOUT)
+        .context("Failed to set busy timeout")?;
 
     conn.query_row("PRAGMA journal_mode=WAL;", [], |_| Ok(()))
-        .map_err(|e| format!("Failed to set journal_mode=WAL: {}", e))?;
+        .context("Failed to set journal_mode=WAL")?;
 
     conn.execute("PRAGMA foreign_keys=ON;", [])
-        .map_err(|e| format!("Failed to enable foreign keys: {}", e))?;
+        .context("Failed to enable foreign keys")?;
     info!("Enabled foreign keys.");
 
     conn.execute("PRAGMA synchronous=NORMAL;", [])
-        .map_err(|e| format!("Failed to set synchronous=NORMAL: {}", e))?;
+        .context("Failed to set synchronous=NORMAL")?;
     info!("Set synchronous=NORMAL.");
 
     info!("Applied PRAGMAs.");
+    Ok(conn)
+}
 
-    if is_new_database {
-        info!("Setting up new database...");
-
-        conn.execute(SQL_CREATE_SCHEMA_VERSION_TABLE, [])
-            .map_err(|e| format!("Failed to create schema_version table: {}", e))?;
-
-        let tx = conn
-            .transaction()
-            .map_err(|e| format!("Failed to start schema transaction: {}", e))?;
-
-        info!("Starting schema creation transaction...");
-
-        tx.execute(SQL_CREATE_CONFIG_TABLE, [])
-            .map_err(|e| format!("Failed to create config table: {}", e))?;
-        info!("Created config table.");
-
-        tx.execute(SQL_CREATE_SHOWCASES_TABLE, [])
-            .map_err(|e| format!("Failed to create showcases table: {}", e))?;
-        info!("Created showcases table.");
-
-        tx.execute(SQL_CREATE_MESSAGES_TABLE, [])
-            .map_err(|e| format!("Failed to create messages table: {}", e))?;
-        info!("Created messages table.");
-
-        // Create indexes
-        tx.execute(SQL_CREATE_MESSAGES_CHANNEL_INDEX, [])
-            .map_err(|e| format!("Failed to create messages channel index: {}", e))?;
-        tx.execute(SQL_CREATE_MESSAGES_TIMESTAMP_INDEX, [])
-            .map_err(|e| format!("Failed to create messages timestamp index: {}", e))?;
-        tx.execute(SQL_CREATE_MESSAGES_AUTHOR_INDEX, [])
-            .map_err(|e| format!("Failed to create messages author index: {}", e))?;
-        info!("Created messages indexes.");
+fn open_and_migrate_database(app_handle: &AppHandle, span: &Span) -> Result<Connection, String> {
+    let db_path = get_db_path(app_handle)?;
+    info!("Database path: {}", db_path.display());
 
-        set_schema_version(&tx, CURRENT_SCHEMA_VERSION)?;
+    let is_new_database = db_encryption::is_new_database(&db_path);
+    info!("Database exists: {}", !is_new_database);
+    span.record("new_database", is_new_database);
 
-        tx.commit()
-            .map_err(|e| format!("Failed to commit schema transaction: {}", e))?;
+    let mut conn = open_connection(&db_path, &KeyringSecrets)?;
 
+    if is_new_database {
+        info!("Setting up new database...");
+        migrations::migrate(&mut conn)?;
         info!(
             "New database schema created with version {}",
             CURRENT_SCHEMA_VERSION
@@ -355,10 +299,7 @@ pub fn initialize_database(app_handle: &AppHandle) -> Result<Connection, String>
     } else {
         warn!("Existing database found, checking schema version...");
 
-        conn.execute(SQL_CREATE_SCHEMA_VERSION_TABLE, [])
-            .map_err(|e| format!("Failed to create schema_version table: {}", e))?;
-
-        let current_version = get_schema_version(&conn)?;
+        let current_version = migrations::schema_version(&conn)?;
         info!("Current database schema version: {}", current_version);
 
         if current_version < CURRENT_SCHEMA_VERSION {
@@ -366,13 +307,24 @@ pub fn initialize_database(app_handle: &AppHandle) -> Result<Connection, String>
                 "Database schema needs update from version {} to {}",
                 current_version, CURRENT_SCHEMA_VERSION
             );
-            update_database_schema(&mut conn)?;
-        } else if current_version > CURRENT_SCHEMA_VERSION {
-            return Err(format!(
-                "Database schema version {} is newer than application version {}. Please update the application.", 
-                current_version, CURRENT_SCHEMA_VERSION
-            ));
+            let migrate_span = span.child(
+                "database.migrate",
+                &[
+                    ("from", current_version.to_string()),
+                    ("to", CURRENT_SCHEMA_VERSION.to_string()),
+                ],
+            );
+            {
+                let _backup_span = migrate_span.child("database.backup", &[]);
+                backup_before_migration(app_handle, &conn, current_version);
+            }
+            if let Err(e) = migrations::migrate(&mut conn) {
+                migrate_span.fail(&e);
+                return Err(e);
+            }
         } else {
+            // Still validates, so a database from a newer build is refused here.
+            migrations::migrate(&mut conn)?;
             info!(
                 "Database schema is already at current version {}",
                 CURRENT_SCHEMA_VERSION
@@ -384,11 +336,11 @@ pub fn initialize_database(app_handle: &AppHandle) -> Result<Connection, String>
     Ok(conn)
 }
 
-pub fn retrieve_config(conn_guard: &MutexGuard<Connection>) -> Result<AppConfig, String> {
+pub fn retrieve_config(conn_guard: &Connection) -> Result<AppConfig, String> {
     info!("Retrieving config...");
     let mut stmt = conn_guard
         .prepare("SELECT key, value FROM config;")
-        .map_err(|e| format!("Failed to prepare config query: {}", e))?;
+        .context("Failed to prepare config query")?;
 
     let config_iter = stmt
         .query_map([], |row| {
@@ -397,7 +349,7 @@ pub fn retrieve_config(conn_guard: &MutexGuard<Connection>) -> Result<AppConfig,
                 row.get::<_, String>(1)?, // value
             ))
         })
-        .map_err(|e| format!("Failed to query configuration: {}", e))?;
+        .context("Failed to query configuration")?;
 
     let mut config = AppConfig::default();
 
@@ -433,6 +385,146 @@ pub fn retrieve_config(conn_guard: &MutexGuard<Connection>) -> Result<AppConfig,
                         _ => error!("Invalid boolean string for auto_update_enabled: '{}'", value),
                     }
                 }
+                "indexing_start_date" => config.indexing_start_date = Some(value),
+                "indexing_window_days" => match value.parse::<u32>() {
+                    Ok(days) => config.indexing_window_days = Some(days),
+                    Err(e) => error!("Invalid value for indexing_window_days: {}. Value was: '{}'", e, value),
+                },
+                "retention_days" => match value.parse::<u32>() {
+                    Ok(days) => config.retention_days = Some(days),
+                    Err(e) => error!("Invalid value for retention_days: {}. Value was: '{}'", e, value),
+                },
+                "trash_retention_days" => match value.parse::<u32>() {
+                    Ok(days) => config.trash_retention_days = Some(days),
+                    Err(e) => error!("Invalid value for trash_retention_days: {}. Value was: '{}'", e, value),
+                },
+                "cleanup_schedule_json" => {
+                    match serde_json::from_str::<CleanupScheduleSettings>(&value) {
+                        Ok(schedule) => config.cleanup_schedule = Some(schedule),
+                        Err(e) => error!("Failed to deserialize cleanup_schedule_json: {}. Value was: '{}'", e, value),
+                    }
+                }
+                "download_concurrency" => match value.parse::<u32>() {
+                    Ok(n) => config.download_concurrency = Some(n),
+                    Err(e) => error!("Invalid value for download_concurrency: {}. Value was: '{}'", e, value),
+                },
+                "author_filters_json" => {
+                    match serde_json::from_str::<AuthorFilterSettings>(&value) {
+                        Ok(filters) => config.author_filters = Some(filters),
+                        Err(e) => error!("Failed to deserialize author_filters_json: {}. Value was: '{}'", e, value),
+                    }
+                }
+                "locale" => config.locale = Some(value),
+                "export_directory" => config.export_directory = Some(value),
+                "presentation_template" => config.presentation_template = Some(value),
+                "export_memory_mode" => config.export_memory_mode = Some(value),
+                "discord_oauth_client_id" => config.discord_oauth_client_id = Some(value),
+                "google_oauth_client_id" => config.google_oauth_client_id = Some(value),
+                "redaction_rules_json" => {
+                    match serde_json::from_str::<RedactionRules>(&value) {
+                        Ok(rules) => config.redaction_rules = Some(rules),
+                        Err(e) => error!("Failed to deserialize redaction_rules_json: {}. Value was: '{}'", e, value),
+                    }
+                }
+                "image_size_filter_json" => {
+                    match serde_json::from_str::<ImageSizeFilter>(&value) {
+                        Ok(filter) => config.image_size_filter = Some(filter),
+                        Err(e) => error!("Failed to deserialize image_size_filter_json: {}. Value was: '{}'", e, value),
+                    }
+                }
+                "channel_name_patterns_json" => {
+                    match serde_json::from_str::<Vec<String>>(&value) {
+                        Ok(patterns) => config.channel_name_patterns = Some(patterns),
+                        Err(e) => error!("Failed to deserialize channel_name_patterns_json: {}. Value was: '{}'", e, value),
+                    }
+                }
+                "auto_discover_channels" => {
+                    match value.to_lowercase().as_str() {
+                        "true" => config.auto_discover_channels = Some(true),
+                        "false" => config.auto_discover_channels = Some(false),
+                        _ => error!("Invalid boolean string for auto_discover_channels: '{}'", value),
+                    }
+                }
+                "author_cap_json" => {
+                    match serde_json::from_str::<AuthorCapSettings>(&value) {
+                        Ok(cap) => config.author_cap = Some(cap),
+                        Err(e) => error!("Failed to deserialize author_cap_json: {}. Value was: '{}'", e, value),
+                    }
+                }
+                "network_settings_json" => {
+                    match serde_json::from_str::<NetworkSettings>(&value) {
+                        Ok(settings) => config.network_settings = Some(settings),
+                        Err(e) => error!("Failed to deserialize network_settings_json: {}. Value was: '{}'", e, value),
+                    }
+                }
+                "content_filters_json" => {
+                    match serde_json::from_str::<ContentFilterSettings>(&value) {
+                        Ok(filters) => config.content_filters = Some(filters),
+                        Err(e) => error!("Failed to deserialize content_filters_json: {}. Value was: '{}'", e, value),
+                    }
+                }
+                "thumbnail_settings_json" => {
+                    match serde_json::from_str::<ThumbnailSettings>(&value) {
+                        Ok(settings) => config.thumbnail_settings = Some(settings),
+                        Err(e) => error!("Failed to deserialize thumbnail_settings_json: {}. Value was: '{}'", e, value),
+                    }
+                }
+                "index_linked_images" => {
+                    match value.to_lowercase().as_str() {
+                        "true" => config.index_linked_images = Some(true),
+                        "false" => config.index_linked_images = Some(false),
+                        _ => error!("Invalid boolean string for index_linked_images: '{}'", value),
+                    }
+                }
+                "legacy_pptx_compatibility" => {
+                    match value.to_lowercase().as_str() {
+                        "true" => config.legacy_pptx_compatibility = Some(true),
+                        "false" => config.legacy_pptx_compatibility = Some(false),
+                        _ => error!("Invalid boolean string for legacy_pptx_compatibility: '{}'", value),
+                    }
+                }
+                "include_animated_attachments" => {
+                    match value.to_lowercase().as_str() {
+                        "true" => config.include_animated_attachments = Some(true),
+                        "false" => config.include_animated_attachments = Some(false),
+                        _ => error!("Invalid boolean string for include_animated_attachments: '{}'", value),
+                    }
+                }
+                "live_indexing_enabled" => {
+                    match value.to_lowercase().as_str() {
+                        "true" => config.live_indexing_enabled = Some(true),
+                        "false" => config.live_indexing_enabled = Some(false),
+                        _ => error!("Invalid boolean string for live_indexing_enabled: '{}'", value),
+                    }
+                }
+                "exclude_sensitive_content" => {
+                    match value.to_lowercase().as_str() {
+                        "true" => config.exclude_sensitive_content = Some(true),
+                        "false" => config.exclude_sensitive_content = Some(false),
+                        _ => error!("Invalid boolean string for exclude_sensitive_content: '{}'", value),
+                    }
+                }
+                "submission_bot_enabled" => {
+                    match value.to_lowercase().as_str() {
+                        "true" => config.submission_bot_enabled = Some(true),
+                        "false" => config.submission_bot_enabled = Some(false),
+                        _ => error!("Invalid boolean string for submission_bot_enabled: '{}'", value),
+                    }
+                }
+                "encrypt_image_cache" => {
+                    match value.to_lowercase().as_str() {
+                        "true" => config.encrypt_image_cache = Some(true),
+                        "false" => config.encrypt_image_cache = Some(false),
+                        _ => error!("Invalid boolean string for encrypt_image_cache: '{}'", value),
+                    }
+                }
+                "compact_after_cleanup" => {
+                    match value.to_lowercase().as_str() {
+                        "true" => config.compact_after_cleanup = Some(true),
+                        "false" => config.compact_after_cleanup = Some(false),
+                        _ => error!("Invalid boolean string for compact_after_cleanup: '{}'", value),
+                    }
+                }
                 _ => {
                     // Optionally log unknown keys
                     // warn!("Unknown config key found: {}", key);
@@ -447,9 +539,148 @@ pub fn retrieve_config(conn_guard: &MutexGuard<Connection>) -> Result<AppConfig,
     Ok(config)
 }
 
-fn map_row_to_indexed_message(row: &Row) -> Result<IndexedMessage, RusqliteError> {
+pub(crate) fn snapshot_config(conn: &Connection) -> Result<HashMap<String, String>, String> {
+    let mut stmt = conn
+        .prepare("SELECT key, value FROM config")
+        .context("Failed to prepare config snapshot")?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+        .context("Failed to read config snapshot")?
+        .collect::<Result<HashMap<String, String>, _>>()
+        .context("Error reading config row")?;
+    Ok(rows)
+}
+
+// Writes one history row per key that differs between the two snapshots, then trims old rows.
+pub(crate) fn record_config_changes(
+    conn: &Connection,
+    before: &HashMap<String, String>,
+    after: &HashMap<String, String>,
+) -> Result<usize, String> {
+    let mut keys: Vec<&String> = before.keys().chain(after.keys()).collect();
+    keys.sort();
+    keys.dedup();
+
+    let changed_at = chrono::Utc::now().timestamp_millis();
+    let mut recorded = 0;
+    for key in keys {
+        let (old_value, new_value) = (before.get(key), after.get(key));
+        if old_value == new_value {
+            continue;
+        }
+        conn.execute(
+            "INSERT INTO config_history (changed_at, key, old_value, new_value) VALUES (?1, ?2, ?3, ?4)",
+            params![changed_at, key, old_value, new_value],
+        )
+        .context(format!("Failed to record config change for {}", key))?;
+        recorded += 1;
+    }
+
+    if recorded > 0 {
+        conn.execute(
+            "DELETE FROM config_history WHERE id NOT IN (SELECT id FROM config_history ORDER BY id DESC LIMIT ?1)",
+            [CONFIG_HISTORY_LIMIT],
+        )
+        .context("Failed to trim config history")?;
+    }
+    Ok(recorded)
+}
+
+#[tauri::command]
+pub async fn get_config_history(
+    limit: Option<i64>,
+    db_state: State<'_, DbConnection>,
+) -> Result<Vec<ConfigHistoryEntry>, AppError> {
+    db_state
+        .0
+        .run(move |conn| {
+            let mut stmt = conn
+                .prepare(
+                    "SELECT id, changed_at, key, old_value, new_value FROM config_history ORDER BY id DESC LIMIT ?1",
+                )
+                .context("Failed to prepare config history query")?;
+            let entries = stmt
+                .query_map([limit.unwrap_or(CONFIG_HISTORY_LIMIT)], |row| {
+                    Ok(ConfigHistoryEntry {
+                        id: row.get(0)?,
+                        changed_at: row.get(1)?,
+                        key: row.get(2)?,
+                        old_value: row.get(3)?,
+                        new_value: row.get(4)?,
+                    })
+                })
+                .context("Failed to query config history")?
+                .collect::<Result<Vec<_>, _>>()
+                .context("Error reading config history row")?;
+            Ok(entries)
+        })
+        .await
+}
+
+// Puts every key changed at or after `timestamp` (milliseconds) back to the value it had
+// before that change. The revert is itself recorded, so it can be undone the same way.
+#[tauri::command]
+pub async fn revert_config_to(
+    timestamp: i64,
+    db_state: State<'_, DbConnection>,
+) -> Result<usize, AppError> {
+    info!("Reverting configuration to its state at {}", timestamp);
+    db_state
+        .0
+        .run(move |conn| {
+            let tx = conn
+                .transaction()
+                .context("Failed to start transaction")?;
+
+            let mut restore: HashMap<String, Option<String>> = HashMap::new();
+            {
+                let mut stmt = tx
+                    .prepare("SELECT key, old_value FROM config_history WHERE changed_at >= ?1 ORDER BY id ASC")
+                    .context("Failed to prepare config history query")?;
+                let rows = stmt
+                    .query_map([timestamp], |row| {
+                        Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?))
+                    })
+                    .context("Failed to query config history")?;
+                for row in rows {
+                    let (key, old_value) = row.context("Error reading config history row")?;
+                    // The earliest change after the timestamp holds the value from before it.
+                    restore.entry(key).or_insert(old_value);
+                }
+            }
+
+            let before = snapshot_config(&tx)?;
+            for (key, value) in &restore {
+                let result = match value {
+                    Some(value) => tx.execute(
+                        "INSERT OR REPLACE INTO config (key, value) VALUES (?1, ?2)",
+                        params![key, value],
+                    ),
+                    None => tx.execute("DELETE FROM config WHERE key = ?1", params![key]),
+                };
+                result.context(format!("Failed to restore config key {}", key))?;
+            }
+            let after = snapshot_config(&tx)?;
+            let reverted = record_config_changes(&tx, &before, &after)?;
+
+            tx.commit()
+                .context("Failed to commit config revert")?;
+            info!("Reverted {} config key(s)", reverted);
+            Ok(reverted)
+        })
+        .await
+}
+
+pub(crate) const INDEXED_MESSAGE_COLUMNS: &str = "message_id, channel_id, author_id, author_name, author_avatar, message_content, attachments, timestamp, is_used, attachment_types, attachment_sources, reaction_count, reactions_json, deleted, reply_to_json, author_avatar_path, is_submission, is_spoiler, is_nsfw_channel, review_decision, author_type, is_favorite";
+
+pub(crate) fn map_row_to_indexed_message(row: &Row) -> Result<IndexedMessage, RusqliteError> {
     // 0: message_id, 1: channel_id, 2: author_id, 3: author_name,
-    // 4: author_avatar, 5: message_content, 6: attachments (JSON array of strings), 7: timestamp, 8: is_used
+    // 4: author_avatar, 5: message_content, 6: attachments (JSON array of strings), 7: timestamp, 8: is_used,
+    // 9: attachment_types, 10: attachment_sources (JSON arrays, parallel to attachments),
+    // 11: reaction_count, 12: reactions_json, 13: deleted, 14: reply_to_json (nullable),
+    // 15: author_avatar_path (nullable, relative to the image cache), 16: is_submission,
+    // 17: is_spoiler, 18: is_nsfw_channel, 19: review_decision (nullable), 20: author_type,
+    // 21: is_favorite
     let attachments_json_opt: Option<String> = row.get(6)?;
 
     let attachments: Vec<String> = match attachments_json_opt {
@@ -473,6 +704,44 @@ fn map_row_to_indexed_message(row: &Row) -> Result<IndexedMessage, RusqliteError
 
     let is_used: bool = row.get(8).unwrap_or(false);
 
+    // Rows indexed before attachment_types existed (or imported from a manifest) fall back to the extension.
+    let stored_types: Vec<String> = row
+        .get::<_, Option<String>>(9)
+        .ok()
+        .flatten()
+        .and_then(|json_str| serde_json::from_str(&json_str).ok())
+        .unwrap_or_default();
+    let attachment_types = if stored_types.len() == attachments.len() {
+        stored_types
+    } else {
+        attachments
+            .iter()
+            .map(|path| attachment_type_from_path(path).to_string())
+            .collect()
+    };
+    let stored_sources: Vec<String> = row
+        .get::<_, Option<String>>(10)
+        .ok()
+        .flatten()
+        .and_then(|json_str| serde_json::from_str(&json_str).ok())
+        .unwrap_or_default();
+    let attachment_sources = if stored_sources.len() == attachments.len() {
+        stored_sources
+    } else {
+        vec!["attachment".to_string(); attachments.len()]
+    };
+    let reactions: Vec<ReactionSummary> = row
+        .get::<_, Option<String>>(12)
+        .ok()
+        .flatten()
+        .and_then(|json_str| serde_json::from_str(&json_str).ok())
+        .unwrap_or_default();
+    let reply_to: Option<ReplyContext> = row
+        .get::<_, Option<String>>(14)
+        .ok()
+        .flatten()
+        .and_then(|json_str| serde_json::from_str(&json_str).ok());
+
     Ok(IndexedMessage {
         message_id: row.get(0)?,
         channel_id: row.get(1)?,
@@ -481,221 +750,991 @@ fn map_row_to_indexed_message(row: &Row) -> Result<IndexedMessage, RusqliteError
         author_avatar: row.get(4)?,
         message_content: row.get(5)?,
         attachments,
+        attachment_types,
+        attachment_sources,
         timestamp: row.get(7)?,
         is_used,
+        reaction_count: row.get(11).unwrap_or(0),
+        reactions,
+        deleted: row.get(13).unwrap_or(false),
+        reply_to,
+        author_avatar_path: row.get(15).unwrap_or(None),
+        is_submission: row.get(16).unwrap_or(false),
+        is_spoiler: row.get(17).unwrap_or(false),
+        is_nsfw_channel: row.get(18).unwrap_or(false),
+        review_decision: row.get(19).unwrap_or(None),
+        author_type: row.get(20).unwrap_or_else(|_| "user".to_string()),
+        is_favorite: row.get(21).unwrap_or(false),
     })
 }
 
+pub(crate) fn attachment_type_from_path(path: &str) -> &'static str {
+    let lower = path.to_lowercase();
+    if lower.ends_with(".gif") {
+        "gif"
+    } else if lower.ends_with(".mp4") || lower.ends_with(".webm") || lower.ends_with(".mov") {
+        "video"
+    } else {
+        "image"
+    }
+}
+
+pub(crate) fn message_order_clause(sort_by: Option<&str>) -> Result<&'static str, String> {
+    match sort_by {
+        None | Some("timestamp") => Ok("timestamp DESC"),
+        Some("reactions") => Ok("reaction_count DESC, timestamp DESC"),
+        Some(other) => Err(format!("Unknown sort order: {}", other)),
+    }
+}
+
 #[tauri::command]
 pub async fn get_indexed_messages(
     db_state: State<'_, DbConnection>,
-) -> Result<Vec<IndexedMessage>, String> {
+    sort_by: Option<String>,
+    include_deleted: Option<bool>,
+    submissions_only: Option<bool>,
+) -> Result<Vec<IndexedMessage>, AppError> {
     info!("Fetching all indexed messages from DB...");
-    let conn_guard = db_state
+    db_state
         .0
-        .lock()
-        .map_err(|e| format!("DB lock error: {}", e))?;
-
-    let mut stmt = conn_guard.prepare(
-        "SELECT message_id, channel_id, author_id, author_name, author_avatar, message_content, attachments, timestamp, is_used FROM messages ORDER BY timestamp DESC"
-    ).map_err(|e| format!("Failed to prepare message query: {}", e))?;
-
-    let message_iter = stmt
-        .query_map([], map_row_to_indexed_message)
-        .map_err(|e| format!("Failed to query indexed messages: {}", e))?;
-
-    let messages = message_iter
-        .collect::<Result<Vec<IndexedMessage>, _>>()
-        .map_err(|e| format!("Error processing message row: {}", e))?;
-
-    info!("Successfully fetched {} indexed messages.", messages.len());
-    Ok(messages)
+        .run(move |conn| {
+            let order_by = message_order_clause(sort_by.as_deref())?;
+            let mut conditions = Vec::new();
+            if !include_deleted.unwrap_or(false) {
+                conditions.push("deleted = 0");
+            }
+            if submissions_only.unwrap_or(false) {
+                conditions.push("is_submission = 1");
+            }
+            let where_clause = if conditions.is_empty() {
+                String::new()
+            } else {
+                format!("WHERE {}", conditions.join(" AND "))
+            };
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM messages {} ORDER BY {}",
+                INDEXED_MESSAGE_COLUMNS, where_clause, order_by
+            )).context("Failed to prepare message query")?;
+
+            let message_iter = stmt
+                .query_map([], map_row_to_indexed_message)
+                .context("Failed to query indexed messages")?;
+
+            let messages = message_iter
+                .collect::<Result<Vec<IndexedMessage>, _>>()
+                .context("Error processing message row")?;
+
+            info!("Successfully fetched {} indexed messages.", messages.len());
+            Ok(messages)
+        })
+        .await
 }
 
-fn calculate_dir_size(path: &Path) -> Result<u64, std::io::Error> {
-    let mut total_size = 0;
-    if path.is_dir() {
-        for entry_result in fs::read_dir(path)? {
-            let entry = entry_result?;
-            let entry_path = entry.path();
-            if entry_path.is_dir() {
-                total_size += calculate_dir_size(&entry_path)?;
-            } else {
-                total_size += entry.metadata()?.len();
+const SEARCH_DEFAULT_LIMIT: usize = 200;
+const SEARCH_MAX_LIMIT: usize = 1000;
+
+// Turns user input into an FTS5 query: "quoted text" is a phrase, a trailing * makes a word or
+// phrase a prefix search, and all parts must match. Everything is quoted before it reaches FTS5,
+// so operators or stray punctuation in the input can't produce a syntax error.
+fn fts_match_expression(query: &str) -> Option<String> {
+    let mut parts = Vec::new();
+    let mut rest = query;
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            break;
+        }
+        let (text, mut prefix, remaining) = match rest.strip_prefix('"') {
+            Some(after) => match after.find('"') {
+                Some(end) => (&after[..end], false, &after[end + 1..]),
+                None => (after, false, ""),
+            },
+            None => {
+                let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+                let word = &rest[..end];
+                let trimmed = word.trim_end_matches('*');
+                (trimmed, trimmed.len() != word.len(), &rest[end..])
             }
+        };
+        rest = remaining;
+        if let Some(after_star) = rest.strip_prefix('*') {
+            prefix = true;
+            rest = after_star;
         }
-    } else {
+        let text = text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        parts.push(format!(
+            "\"{}\"{}",
+            text.replace('"', "\"\""),
+            if prefix { "*" } else { "" }
+        ));
     }
-    Ok(total_size)
-}
-
-fn format_bytes(bytes: u64) -> String {
-    const KB: u64 = 1024;
-    const MB: u64 = KB * 1024;
-    const GB: u64 = MB * 1024;
-
-    if bytes >= GB {
-        format!("{:.2} GB", bytes as f64 / GB as f64)
-    } else if bytes >= MB {
-        format!("{:.2} MB", bytes as f64 / MB as f64)
-    } else if bytes >= KB {
-        format!("{:.2} KB", bytes as f64 / KB as f64)
+    if parts.is_empty() {
+        None
     } else {
-        format!("{} bytes", bytes)
+        Some(parts.join(" "))
     }
 }
 
-#[tauri::command]
-pub async fn get_storage_usage(
-    app_handle: AppHandle,
-    db_state: State<'_, DbConnection>,
-) -> Result<StorageUsage, String> {
-    info!("Calculating storage usage...");
+// Adds the WHERE conditions shared by search and paged queries, with their bound values in order.
+fn push_message_filters(
+    filters: &MessageSearchFilters,
+    conditions: &mut Vec<String>,
+    values: &mut Vec<RusqliteValue>,
+) {
+    if !filters.include_deleted {
+        conditions.push("deleted = 0".to_string());
+    }
+    if filters.submissions_only {
+        conditions.push("is_submission = 1".to_string());
+    }
+    if filters.favorites_only {
+        conditions.push("is_favorite = 1".to_string());
+    }
+    for (column, ids) in [("channel_id", &filters.channel_ids), ("author_id", &filters.author_ids)] {
+        if ids.is_empty() {
+            continue;
+        }
+        conditions.push(format!(
+            "{} IN ({})",
+            column,
+            vec!["?"; ids.len()].join(", ")
+        ));
+        values.extend(ids.iter().map(|id| RusqliteValue::from(id.clone())));
+    }
+    if let Some(from_date) = filters.from_date {
+        conditions.push("timestamp >= ?".to_string());
+        values.push(from_date.into());
+    }
+    if let Some(to_date) = filters.to_date {
+        conditions.push("timestamp < ?".to_string());
+        values.push(to_date.into());
+    }
+}
 
-    let conn_guard = db_state
-        .0
-        .lock()
-        .map_err(|e| format!("DB lock error: {}", e))?;
+fn message_where_clause(conditions: &[String]) -> String {
+    if conditions.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", conditions.join(" AND "))
+    }
+}
 
-    let db_path = get_db_path(&app_handle)?;
-    let database_size_bytes = match fs::metadata(&db_path) {
-        Ok(metadata) => {
-            if metadata.is_file() {
-                metadata.len()
-            } else {
-                error!(
-                    "Expected database file, but found directory or other at {}",
-                    db_path.display()
-                );
-                0
-            }
-        }
-        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
-            info!("Database file not found at {}", db_path.display());
-            0
-        }
-        Err(e) => {
-            return Err(format!("Failed to get database file metadata: {}", e));
-        }
+#[tauri::command]
+pub async fn search_indexed_messages(
+    db_state: State<'_, DbConnection>,
+    query: String,
+    filters: Option<MessageSearchFilters>,
+) -> Result<Vec<IndexedMessage>, AppError> {
+    let filters = filters.unwrap_or_default();
+    let match_expression =
+        fts_match_expression(&query).ok_or_else(|| "Search query is empty.".to_string())?;
+    info!("Searching indexed messages for: {}", match_expression);
+
+    let order_by = match filters.sort_by.as_deref() {
+        None | Some("relevance") => "hits.match_rank, timestamp DESC",
+        Some(other) => message_order_clause(Some(other))?,
     };
 
-    let message_count: i64 = conn_guard
-        .query_row("SELECT COUNT(*) FROM messages", [], |row| row.get(0))
-        .map_err(|e| format!("Failed to count messages: {}", e))?;
+    let mut conditions: Vec<String> = Vec::new();
+    let mut values: Vec<RusqliteValue> = vec![match_expression.into()];
+    push_message_filters(&filters, &mut conditions, &mut values);
+    let limit = filters
+        .limit
+        .unwrap_or(SEARCH_DEFAULT_LIMIT)
+        .clamp(1, SEARCH_MAX_LIMIT);
+    values.push((limit as i64).into());
+
+    let where_clause = message_where_clause(&conditions);
+    let sql = format!(
+        "SELECT {} FROM messages
+         JOIN (SELECT rowid AS match_rowid, rank AS match_rank FROM messages_fts WHERE messages_fts MATCH ?) hits
+           ON messages.rowid = hits.match_rowid
+         {} ORDER BY {} LIMIT ?",
+        INDEXED_MESSAGE_COLUMNS, where_clause, order_by
+    );
 
-    let showcase_count: i64 = conn_guard
-        .query_row("SELECT COUNT(*) FROM showcases", [], |row| row.get(0))
-        .map_err(|e| format!("Failed to count showcases: {}", e))?;
+    db_state
+        .0
+        .run(move |conn| {
+            let mut stmt = conn
+                .prepare(&sql)
+                .context("Failed to prepare search query")?;
+            let params_refs: Vec<&dyn rusqlite::ToSql> =
+                values.iter().map(|v| v as &dyn rusqlite::ToSql).collect();
+            let messages = stmt
+                .query_map(params_refs.as_slice(), map_row_to_indexed_message)
+                .context("Failed to search indexed messages")?
+                .collect::<Result<Vec<IndexedMessage>, _>>()
+                .context("Error processing message row")?;
+
+            info!("Search matched {} message(s).", messages.len());
+            Ok(messages)
+        })
+        .await
+}
 
-    let protected_message_count: i64 = conn_guard
+// message_id breaks ties so rows with equal timestamps don't move between pages.
+fn paged_order_clause(sort_by: Option<&str>, sort_order: Option<&str>) -> Result<String, String> {
+    let direction = match sort_order {
+        None | Some("desc") => "DESC",
+        Some("asc") => "ASC",
+        Some(other) => return Err(format!("Unknown sort direction: {}", other)),
+    };
+    match sort_by {
+        None | Some("timestamp") => Ok(format!(
+            "timestamp {0}, message_id {0}",
+            direction
+        )),
+        Some("reactions") => Ok(format!(
+            "reaction_count {0}, timestamp {0}, message_id {0}",
+            direction
+        )),
+        Some(other) => Err(format!("Unknown sort order: {}", other)),
+    }
+}
+
+pub(crate) fn query_messages_page(conn: &Connection, query: &MessageQuery) -> Result<MessagePage, String> {
+    let filters = &query.filters;
+    let order_by = paged_order_clause(filters.sort_by.as_deref(), query.sort_order.as_deref())?;
+    let limit = filters
+        .limit
+        .unwrap_or(SEARCH_DEFAULT_LIMIT)
+        .clamp(1, SEARCH_MAX_LIMIT);
+    let page = query.page.unwrap_or(1).max(1);
+
+    let mut conditions: Vec<String> = Vec::new();
+    let mut values: Vec<RusqliteValue> = Vec::new();
+    push_message_filters(filters, &mut conditions, &mut values);
+    if let Some(is_used) = query.is_used {
+        conditions.push("is_used = ?".to_string());
+        values.push(is_used.into());
+    }
+    let where_clause = message_where_clause(&conditions);
+
+    let total: i64 = conn
         .query_row(
-            "SELECT COUNT(*) FROM messages WHERE is_used = 1",
-            [],
+            &format!("SELECT COUNT(*) FROM messages {}", where_clause),
+            params_from_iter(values.iter()),
             |row| row.get(0),
         )
-        .map_err(|e| format!("Failed to count protected messages: {}", e))?;
+        .context("Failed to count indexed messages")?;
 
-    let oldest_message_date: Option<i64> =
-        match conn_guard.query_row("SELECT MIN(timestamp) FROM messages", [], |row| row.get(0)) {
-            Ok(timestamp) => timestamp,
-            Err(e) => {
-                warn!("Failed to get oldest message date: {}", e);
-                None
-            }
-        };
+    values.push((limit as i64).into());
+    values.push((((page - 1) * limit) as i64).into());
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM messages {} ORDER BY {} LIMIT ? OFFSET ?",
+            INDEXED_MESSAGE_COLUMNS, where_clause, order_by
+        ))
+        .context("Failed to prepare message query")?;
+    let messages = stmt
+        .query_map(params_from_iter(values.iter()), map_row_to_indexed_message)
+        .context("Failed to query indexed messages")?
+        .collect::<Result<Vec<IndexedMessage>, _>>()
+        .context("Error processing message row")?;
 
-    let newest_message_date: Option<i64> =
-        match conn_guard.query_row("SELECT MAX(timestamp) FROM messages", [], |row| row.get(0)) {
-            Ok(timestamp) => timestamp,
-            Err(e) => {
-                warn!("Failed to get newest message date: {}", e);
-                None
+    Ok(MessagePage {
+        messages,
+        total: total as usize,
+        page,
+        limit,
+    })
+}
+
+#[tauri::command]
+pub async fn query_indexed_messages(
+    db_state: State<'_, DbConnection>,
+    query: Option<MessageQuery>,
+) -> Result<MessagePage, AppError> {
+    let query = query.unwrap_or_default();
+    db_state
+        .0
+        .run(move |conn| {
+            let result = query_messages_page(conn, &query)?;
+            info!(
+                "Fetched page {} of indexed messages ({} of {} total).",
+                result.page,
+                result.messages.len(),
+                result.total
+            );
+            Ok(result)
+        })
+        .await
+}
+
+// Every message matching the filters, oldest first. The limit and sort order of the filters
+// only apply to the list views.
+pub(crate) fn query_messages_for_export(
+    conn: &Connection,
+    filters: &MessageSearchFilters,
+) -> Result<Vec<IndexedMessage>, String> {
+    let mut conditions: Vec<String> = Vec::new();
+    let mut values: Vec<RusqliteValue> = Vec::new();
+    push_message_filters(filters, &mut conditions, &mut values);
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM messages {} ORDER BY timestamp ASC, message_id ASC",
+            INDEXED_MESSAGE_COLUMNS,
+            message_where_clause(&conditions)
+        ))
+        .context("Failed to prepare export query")?;
+    let messages = stmt
+        .query_map(params_from_iter(values.iter()), map_row_to_indexed_message)
+        .context("Failed to query messages for export")?
+        .collect::<Result<Vec<IndexedMessage>, _>>()
+        .context("Error processing message row")?;
+    Ok(messages)
+}
+
+const MESSAGE_CSV_HEADER: &[&str] = &[
+    "message_id",
+    "channel_id",
+    "author_id",
+    "author_name",
+    "author_type",
+    "timestamp",
+    "posted_at",
+    "message_content",
+    "attachments",
+    "attachment_types",
+    "reaction_count",
+    "is_used",
+    "is_favorite",
+    "is_submission",
+    "deleted",
+    "review_decision",
+];
+
+// Quotes fields per RFC 4180. Text that a spreadsheet would run as a formula gets a leading
+// apostrophe, since message content is written by anyone on the server.
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+// Attachment paths are relative to the image cache and joined with "|" inside one column.
+pub(crate) fn messages_to_csv(messages: &[IndexedMessage]) -> String {
+    let mut csv = MESSAGE_CSV_HEADER.join(",");
+    csv.push_str("\r\n");
+    for msg in messages {
+        let posted_at = chrono::DateTime::from_timestamp(msg.timestamp, 0)
+            .map(|time| time.to_rfc3339())
+            .unwrap_or_default();
+        let fields = [
+            msg.message_id.clone(),
+            msg.channel_id.clone(),
+            msg.author_id.clone(),
+            msg.author_name.clone(),
+            msg.author_type.clone(),
+            msg.timestamp.to_string(),
+            posted_at,
+            msg.message_content.clone(),
+            msg.attachments.join("|"),
+            msg.attachment_types.join("|"),
+            msg.reaction_count.to_string(),
+            msg.is_used.to_string(),
+            msg.is_favorite.to_string(),
+            msg.is_submission.to_string(),
+            msg.deleted.to_string(),
+            msg.review_decision.clone().unwrap_or_default(),
+        ];
+        let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&row.join(","));
+        csv.push_str("\r\n");
+    }
+    csv
+}
+
+#[tauri::command]
+pub async fn export_indexed_messages(
+    db_state: State<'_, DbConnection>,
+    format: String,
+    filters: Option<MessageSearchFilters>,
+    dest_path: String,
+) -> Result<MessageExportResult, AppError> {
+    let format = format.to_lowercase();
+    if format != "csv" && format != "json" {
+        return Err(AppError::invalid_input(format!("Unknown export format: {}", format)));
+    }
+    let dest_path = PathBuf::from(dest_path);
+    if let Some(parent) = dest_path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        if !parent.is_dir() {
+            return Err(AppError::invalid_input(format!("Folder '{}' does not exist.", parent.display())));
+        }
+    }
+    let filters = filters.unwrap_or_default();
+
+    db_state
+        .0
+        .run(move |conn| {
+            let messages = query_messages_for_export(conn, &filters)?;
+            let contents = if format == "csv" {
+                messages_to_csv(&messages).into_bytes()
+            } else {
+                serde_json::to_vec_pretty(&messages)
+                    .context("Failed to serialize messages")?
+            };
+            fs::write(&dest_path, &contents)
+                .context(format!("Failed to write '{}'", dest_path.display()))?;
+            info!("Exported {} message(s) as {} to {}", messages.len(), format, dest_path.display());
+            Ok(MessageExportResult {
+                path: dest_path.display().to_string(),
+                format,
+                rows: messages.len(),
+                size_bytes: contents.len() as u64,
+            })
+        })
+        .await
+}
+
+pub(crate) fn calculate_dir_size(path: &Path) -> Result<u64, std::io::Error> {
+    let mut total_size = 0;
+    if path.is_dir() {
+        for entry_result in fs::read_dir(path)? {
+            let entry = entry_result?;
+            let entry_path = entry.path();
+            if entry_path.is_dir() {
+                total_size += calculate_dir_size(&entry_path)?;
+            } else {
+                total_size += entry.metadata()?.len();
             }
-        };
+        }
+    } else {
+    }
+    Ok(total_size)
+}
 
-    let image_base_dir = get_image_base_dir(&app_handle)?;
-    let cache_dir = image_base_dir.join("cached");
-
-    let mut cache_file_count = 0;
-    if cache_dir.exists() {
-        match fs::read_dir(&cache_dir) {
-            Ok(entries) => {
-                for entry_result in entries {
-                    if let Ok(entry) = entry_result {
-                        if entry.path().is_file() {
-                            cache_file_count += 1;
+pub(crate) fn format_bytes(bytes: u64) -> String {
+    const KB: u64 = 1024;
+    const MB: u64 = KB * 1024;
+    const GB: u64 = MB * 1024;
+
+    if bytes >= GB {
+        format!("{:.2} GB", bytes as f64 / GB as f64)
+    } else if bytes >= MB {
+        format!("{:.2} MB", bytes as f64 / MB as f64)
+    } else if bytes >= KB {
+        format!("{:.2} KB", bytes as f64 / KB as f64)
+    } else {
+        format!("{} bytes", bytes)
+    }
+}
+
+#[tauri::command]
+pub async fn get_storage_usage(
+    app_handle: AppHandle,
+    db_state: State<'_, DbConnection>,
+) -> Result<StorageUsage, AppError> {
+    info!("Calculating storage usage...");
+
+    db_state
+        .0
+        .run(move |conn| {
+            let db_path = get_db_path(&app_handle)?;
+            let database_size_bytes = match fs::metadata(&db_path) {
+                Ok(metadata) => {
+                    if metadata.is_file() {
+                        metadata.len()
+                    } else {
+                        error!(
+                            "Expected database file, but found directory or other at {}",
+                            db_path.display()
+                        );
+                        0
+                    }
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    info!("Database file not found at {}", db_path.display());
+                    0
+                }
+                Err(e) => {
+                    return Err(AppError::from(e).with_context("Failed to get database file metadata"));
+                }
+            };
+
+            let message_count: i64 = conn
+                .query_row("SELECT COUNT(*) FROM messages", [], |row| row.get(0))
+                .context("Failed to count messages")?;
+
+            let showcase_count: i64 = conn
+                .query_row("SELECT COUNT(*) FROM showcases", [], |row| row.get(0))
+                .context("Failed to count showcases")?;
+
+            let protected_message_count: i64 = conn
+                .query_row(
+                    "SELECT COUNT(*) FROM messages WHERE is_used = 1",
+                    [],
+                    |row| row.get(0),
+                )
+                .context("Failed to count protected messages")?;
+
+            let oldest_message_date: Option<i64> =
+                match conn.query_row("SELECT MIN(timestamp) FROM messages", [], |row| row.get(0)) {
+                    Ok(timestamp) => timestamp,
+                    Err(e) => {
+                        warn!("Failed to get oldest message date: {}", e);
+                        None
+                    }
+                };
+
+            let newest_message_date: Option<i64> =
+                match conn.query_row("SELECT MAX(timestamp) FROM messages", [], |row| row.get(0)) {
+                    Ok(timestamp) => timestamp,
+                    Err(e) => {
+                        warn!("Failed to get newest message date: {}", e);
+                        None
+                    }
+                };
+
+            let image_base_dir = get_image_base_dir(&app_handle)?;
+            let cache_dir = image_base_dir.join("cached");
+
+            let mut cache_file_count = 0;
+            if cache_dir.exists() {
+                match fs::read_dir(&cache_dir) {
+                    Ok(entries) => {
+                        for entry_result in entries {
+                            if let Ok(entry) = entry_result {
+                                if entry.path().is_file() {
+                                    cache_file_count += 1;
+                                }
+                            }
                         }
                     }
+                    Err(e) => error!("Failed to read cache directory: {}", e),
                 }
             }
-            Err(e) => error!("Failed to read cache directory: {}", e),
-        }
-    }
 
-    let image_cache_size_bytes = if cache_dir.exists() {
-        match calculate_dir_size(&cache_dir) {
-            Ok(size) => size,
-            Err(e) => {
-                error!("Failed to calculate cache directory size: {}", e);
+            let image_cache_size_bytes = if cache_dir.exists() {
+                match calculate_dir_size(&cache_dir) {
+                    Ok(size) => size,
+                    Err(e) => {
+                        error!("Failed to calculate cache directory size: {}", e);
+                        0
+                    }
+                }
+            } else {
                 0
-            }
-        }
-    } else {
-        0
-    };
+            };
 
-    let total_size_bytes = database_size_bytes + image_cache_size_bytes;
+            let total_size_bytes = database_size_bytes + image_cache_size_bytes;
+            let showcases = showcase_storage(conn, &app_handle)?;
+            let channels = channel_storage(conn, &image_base_dir)?;
 
-    info!(
-        "Storage usage calculated: {} DB, {} cache, {} total",
-        format_bytes(database_size_bytes),
-        format_bytes(image_cache_size_bytes),
-        format_bytes(total_size_bytes)
-    );
+            info!(
+                "Storage usage calculated: {} DB, {} cache, {} total",
+                format_bytes(database_size_bytes),
+                format_bytes(image_cache_size_bytes),
+                format_bytes(total_size_bytes)
+            );
 
-    Ok(StorageUsage {
-        database_size_bytes,
-        image_cache_size_bytes,
-        total_size_bytes,
-        database_path: db_path.to_string_lossy().to_string(),
-        message_count,
-        showcase_count,
-        protected_message_count,
-        cache_file_count,
-        oldest_message_date,
-        newest_message_date,
+            Ok(StorageUsage {
+                database_size_bytes,
+                image_cache_size_bytes,
+                total_size_bytes,
+                database_path: db_path.to_string_lossy().to_string(),
+                message_count,
+                showcase_count,
+                protected_message_count,
+                cache_file_count,
+                oldest_message_date,
+                newest_message_date,
+                showcases,
+                channels,
+            })
+        })
+        .await
+}
+
+fn dir_size_or_zero(dir: &Path) -> u64 {
+    if !dir.exists() {
+        return 0;
+    }
+    calculate_dir_size(dir).unwrap_or_else(|e| {
+        error!("Failed to calculate size of {}: {}", dir.display(), e);
+        0
     })
 }
 
-fn get_image_base_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
-    let app_data_dir = app_handle
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data dir: {}", e))?;
+pub(crate) fn showcase_storage(
+    conn: &Connection,
+    paths: &impl PathProvider,
+) -> Result<Vec<ShowcaseStorage>, String> {
+    let mut stmt = conn
+        .prepare("SELECT s.id, s.title, (SELECT COUNT(*) FROM showcase_messages WHERE showcase_id = s.id) FROM showcases s")
+        .context("Failed to prepare showcase storage query")?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, i64>(2)? as usize,
+            ))
+        })
+        .context("Failed to query showcases")?
+        .collect::<Result<Vec<_>, _>>()
+        .context("Error reading showcase row")?;
+
+    let mut showcases = Vec::with_capacity(rows.len());
+    for (showcase_id, title, message_count) in rows {
+        showcases.push(ShowcaseStorage {
+            image_dir_size_bytes: dir_size_or_zero(&get_showcase_image_dir(paths, &showcase_id)?)
+                + fs::metadata(get_showcase_image_pack(paths, &showcase_id)?).map_or(0, |m| m.len()),
+            presentation_size_bytes: dir_size_or_zero(&get_showcase_presentation_dir(paths, &showcase_id)?),
+            message_count,
+            showcase_id,
+            title,
+        });
+    }
+    showcases.sort_by(|a, b| {
+        (b.image_dir_size_bytes + b.presentation_size_bytes)
+            .cmp(&(a.image_dir_size_bytes + a.presentation_size_bytes))
+    });
+    Ok(showcases)
+}
+
+pub(crate) fn channel_storage(
+    conn: &Connection,
+    image_base_dir: &Path,
+) -> Result<Vec<ChannelStorage>, String> {
+    let mut stmt = conn
+        .prepare("SELECT channel_id, attachments FROM messages")
+        .context("Failed to prepare channel storage query")?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?)))
+        .context("Failed to query message attachments")?;
+
+    let mut by_channel: HashMap<String, ChannelStorage> = HashMap::new();
+    for row in rows {
+        let (channel_id, attachments_json) =
+            row.context("Error reading message row")?;
+        let entry = by_channel
+            .entry(channel_id.clone())
+            .or_insert_with(|| ChannelStorage {
+                channel_id,
+                message_count: 0,
+                cache_file_count: 0,
+                cache_size_bytes: 0,
+            });
+        entry.message_count += 1;
+        let attachments: Vec<String> = attachments_json
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        for relative_path in attachments {
+            if let Ok(metadata) = fs::metadata(image_base_dir.join(&relative_path)) {
+                entry.cache_file_count += 1;
+                entry.cache_size_bytes += metadata.len();
+            }
+        }
+    }
+    let mut channels: Vec<ChannelStorage> = by_channel.into_values().collect();
+    channels.sort_by(|a, b| {
+        b.cache_size_bytes
+            .cmp(&a.cache_size_bytes)
+            .then_with(|| a.channel_id.cmp(&b.channel_id))
+    });
+    Ok(channels)
+}
+
+pub(crate) fn get_image_base_dir(paths: &impl PathProvider) -> Result<PathBuf, String> {
+    let app_data_dir = paths.app_data_dir()?;
     Ok(app_data_dir.join("images"))
 }
 
+// Where an unfinished run stopped. Channels are walked newest to oldest, so everything between
+// `top_id` (the newest message seen by that run) and `before_id` has already been processed.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct IndexResumeCursor {
+    pub before_id: u64,
+    pub top_id: u64,
+    pub window_end: Option<i64>,
+}
+
+pub fn record_channel_index_run(
+    conn: &Connection,
+    channel_id: &str,
+    result: &str,
+    messages_saved: usize,
+    error_message: Option<&str>,
+    resume: Option<&IndexResumeCursor>,
+) -> Result<(), String> {
+    conn.execute(
+        "INSERT OR REPLACE INTO channel_index_runs (channel_id, last_run_at, last_result, messages_saved, last_error, resume_before_id, resume_top_id, resume_window_end) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            channel_id,
+            chrono::Utc::now().timestamp(),
+            result,
+            messages_saved as i64,
+            error_message,
+            resume.map(|c| c.before_id.to_string()),
+            resume.map(|c| c.top_id.to_string()),
+            resume.and_then(|c| c.window_end)
+        ],
+    )
+    .context(format!("Failed to record index run for channel {}", channel_id))?;
+    Ok(())
+}
+
+// Saved after every batch so a crash mid-run can resume as well.
+pub(crate) fn save_index_resume_cursor(
+    conn: &Connection,
+    channel_id: &str,
+    cursor: &IndexResumeCursor,
+) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO channel_index_runs (channel_id, last_run_at, last_result, resume_before_id, resume_top_id, resume_window_end) VALUES (?1, ?2, 'interrupted', ?3, ?4, ?5)
+         ON CONFLICT(channel_id) DO UPDATE SET resume_before_id = excluded.resume_before_id, resume_top_id = excluded.resume_top_id, resume_window_end = excluded.resume_window_end",
+        params![
+            channel_id,
+            chrono::Utc::now().timestamp(),
+            cursor.before_id.to_string(),
+            cursor.top_id.to_string(),
+            cursor.window_end
+        ],
+    )
+    .context(format!("Failed to save resume cursor for channel {}", channel_id))?;
+    Ok(())
+}
+
+pub(crate) fn load_index_resume_cursor(
+    conn: &Connection,
+    channel_id: &str,
+) -> Result<Option<IndexResumeCursor>, String> {
+    let row = conn.query_row(
+        "SELECT resume_before_id, resume_top_id, resume_window_end FROM channel_index_runs WHERE channel_id = ?1",
+        params![channel_id],
+        |row| {
+            Ok((
+                row.get::<_, Option<String>>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, Option<i64>>(2)?,
+            ))
+        },
+    );
+    match row {
+        Ok((Some(before_id), Some(top_id), window_end)) => {
+            match (before_id.parse::<u64>(), top_id.parse::<u64>()) {
+                (Ok(before_id), Ok(top_id)) if before_id != 0 && top_id != 0 => {
+                    Ok(Some(IndexResumeCursor {
+                        before_id,
+                        top_id,
+                        window_end,
+                    }))
+                }
+                _ => Ok(None),
+            }
+        }
+        Ok(_) | Err(RusqliteError::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(format!("Failed to load resume cursor for channel {}: {}", channel_id, e)),
+    }
+}
+
 #[tauri::command]
-pub async fn get_cached_image_data(
+pub async fn get_channel_index_status(
+    db_state: State<'_, DbConnection>,
+) -> Result<Vec<ChannelIndexStatus>, AppError> {
+    db_state
+        .0
+        .run(move |conn| {
+            let config = retrieve_config(conn)?;
+
+            let mut stats_stmt = conn
+                .prepare("SELECT COUNT(*), MAX(timestamp) FROM messages WHERE channel_id = ?1")
+                .context("Failed to prepare channel stats query")?;
+            let mut run_stmt = conn
+                .prepare("SELECT last_run_at, last_result, messages_saved, last_error FROM channel_index_runs WHERE channel_id = ?1")
+                .context("Failed to prepare channel run query")?;
+
+            let mut statuses = Vec::with_capacity(config.selected_channel_ids.len());
+            for channel_id in &config.selected_channel_ids {
+                let (indexed_message_count, newest_message_timestamp): (i64, Option<i64>) = stats_stmt
+                    .query_row(params![channel_id], |row| Ok((row.get(0)?, row.get(1)?)))
+                    .context(format!("Failed to query stats for channel {}", channel_id))?;
+
+                let last_run = match run_stmt.query_row(params![channel_id], |row| {
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, i64>(2)?,
+                        row.get::<_, Option<String>>(3)?,
+                    ))
+                }) {
+                    Ok(run) => Some(run),
+                    Err(RusqliteError::QueryReturnedNoRows) => None,
+                    Err(e) => {
+                        return Err(AppError::from(e)
+                            .with_context(format!("Failed to query last run for channel {}", channel_id)))
+                    }
+                };
+
+                statuses.push(ChannelIndexStatus {
+                    channel_id: channel_id.clone(),
+                    indexed_message_count,
+                    newest_message_timestamp,
+                    last_run_at: last_run.as_ref().map(|r| r.0),
+                    last_run_result: last_run.as_ref().map(|r| r.1.clone()),
+                    last_run_messages_saved: last_run.as_ref().map(|r| r.2),
+                    last_run_error: last_run.and_then(|r| r.3),
+                });
+            }
+
+            Ok(statuses)
+        })
+        .await
+}
+
+// Covers every channel with indexed messages, selected or not, busiest first.
+#[tauri::command]
+pub async fn get_indexing_stats(
     app_handle: AppHandle,
-    relative_path: String,
-) -> Result<String, String> {
-    info!("Fetching image data for relative path: {}", relative_path);
+    db_state: State<'_, DbConnection>,
+) -> Result<Vec<ChannelIndexingStats>, AppError> {
+    let image_base_dir = get_image_base_dir(&app_handle)?;
+    db_state
+        .0
+        .run(move |conn| {
+            let selected_channel_ids = retrieve_config(conn)?.selected_channel_ids;
+
+            let mut stmt = conn
+                .prepare(
+                    "SELECT channel_id, COUNT(*), COUNT(DISTINCT author_id), MIN(timestamp), MAX(timestamp)
+                     FROM messages WHERE deleted = 0 GROUP BY channel_id",
+                )
+                .context("Failed to prepare indexing stats query")?;
+            let mut stats: HashMap<String, ChannelIndexingStats> = stmt
+                .query_map([], |row| {
+                    let channel_id: String = row.get(0)?;
+                    Ok(ChannelIndexingStats {
+                        selected: selected_channel_ids.contains(&channel_id),
+                        channel_id,
+                        message_count: row.get(1)?,
+                        image_count: 0,
+                        video_count: 0,
+                        unique_authors: row.get(2)?,
+                        oldest_message_timestamp: row.get(3)?,
+                        newest_message_timestamp: row.get(4)?,
+                        cache_size_bytes: 0,
+                    })
+                })
+                .context("Failed to query indexing stats")?
+                .map(|r| r.map(|s| (s.channel_id.clone(), s)))
+                .collect::<Result<_, _>>()
+                .context("Error reading indexing stats row")?;
+
+            let mut files_stmt = conn
+                .prepare("SELECT channel_id, attachments, attachment_types FROM messages WHERE deleted = 0")
+                .context("Failed to prepare attachment query")?;
+            let rows = files_stmt
+                .query_map([], |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, Option<String>>(2)?,
+                    ))
+                })
+                .context("Failed to query attachments")?;
+            for row in rows {
+                let (channel_id, attachments_json, types_json) =
+                    row.context("Error reading attachment row")?;
+                let Some(channel_stats) = stats.get_mut(&channel_id) else {
+                    continue;
+                };
+                let attachments: Vec<String> = serde_json::from_str(&attachments_json).unwrap_or_default();
+                let types: Vec<String> = types_json
+                    .and_then(|json| serde_json::from_str(&json).ok())
+                    .unwrap_or_default();
+                for (index, relative_path) in attachments.iter().enumerate() {
+                    let kind = types
+                        .get(index)
+                        .map(String::as_str)
+                        .unwrap_or_else(|| attachment_type_from_path(relative_path));
+                    if kind == "video" {
+                        channel_stats.video_count += 1;
+                    } else {
+                        channel_stats.image_count += 1;
+                    }
+                    if let Ok(metadata) = fs::metadata(image_base_dir.join(relative_path)) {
+                        channel_stats.cache_size_bytes += metadata.len();
+                    }
+                }
+            }
+
+            let mut stats: Vec<ChannelIndexingStats> = stats.into_values().collect();
+            stats.sort_by(|a, b| b.message_count.cmp(&a.message_count));
+            Ok(stats)
+        })
+        .await
+}
 
+// "full" (default) or "thumb".
+fn wants_thumbnail(size: Option<&str>) -> Result<bool, AppError> {
+    match size {
+        None | Some("full") => Ok(false),
+        Some("thumb") => Ok(true),
+        Some(other) => Err(AppError::invalid_input(format!("Unknown image size: {}", other))),
+    }
+}
+
+// Reads one cached file as a data URI. `thumbnail` carries the thumbnail settings and cache
+// encryption flag when a thumbnail was asked for; videos have none and are always returned in full.
+pub(crate) fn load_image_data_uri(
+    base_dir: &Path,
+    relative_path: &str,
+    still_frame: bool,
+    thumbnail: Option<&(ThumbnailSettings, bool)>,
+) -> Result<String, AppError> {
     if relative_path.contains("..")
         || relative_path.starts_with('/')
         || relative_path.starts_with('\\')
     {
-        return Err("Invalid relative path provided.".to_string());
+        return Err(AppError::invalid_input("Invalid relative path provided."));
     }
 
-    let base_dir = get_image_base_dir(&app_handle)?;
-    let file_path = base_dir.join(&relative_path);
+    if let Some((settings, encrypt)) = thumbnail.filter(|_| attachment_type_from_path(relative_path) != "video") {
+        let jpeg_bytes = thumbnails::load_or_generate_thumbnail(base_dir, relative_path, settings, *encrypt)?;
+        return Ok(format!(
+            "data:image/jpeg;base64,{}",
+            base64_engine.encode(&jpeg_bytes)
+        ));
+    }
+
+    let file_path = base_dir.join(relative_path);
 
     info!("Attempting to read image file: {}", file_path.display());
 
     match fs::read(&file_path) {
         Ok(bytes) => {
+            let bytes = cache_crypto::decrypt_if_encrypted(bytes)?;
+            if still_frame {
+                match attachment_type_from_path(relative_path) {
+                    "gif" => {
+                        let png_bytes = flatten_gif_to_png(&bytes)?;
+                        info!("Flattened GIF to still frame: {}", relative_path);
+                        return Ok(format!(
+                            "data:image/png;base64,{}",
+                            base64_engine.encode(&png_bytes)
+                        ));
+                    }
+                    "video" => {
+                        return Err(AppError::invalid_input(format!(
+                            "Still frames are not supported for video attachments: {}",
+                            relative_path
+                        )))
+                    }
+                    _ => {}
+                }
+            }
             let mime_type =
                 mime_guess::from_path(&file_path).first_or("image/png".parse().unwrap());
 
@@ -708,120 +1747,343 @@ pub async fn get_cached_image_data(
         }
         Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
             error!("Image file not found: {}", file_path.display());
-            Err(format!("Image not found: {}", relative_path))
+            Err(AppError::not_found(format!("Image not found: {}", relative_path)))
         }
         Err(e) => {
             error!("Failed to read image file {}: {}", file_path.display(), e);
-            Err(format!("Failed to read image file: {}", e))
+            Err(AppError::from(e).with_context("Failed to read image file"))
         }
     }
 }
 
 #[tauri::command]
-pub async fn clean_old_data(
+pub async fn get_cached_image_data(
     app_handle: AppHandle,
     db_state: State<'_, DbConnection>,
-) -> Result<CleanupStats, String> {
-    info!("Starting cleanup of old data (entries > 30 days)...");
-
-    let thirty_days_ago = chrono::Utc::now()
-        .checked_sub_signed(chrono::Duration::days(30))
-        .expect("Valid timestamp calculation")
-        .timestamp();
+    relative_path: String,
+    still_frame: Option<bool>,
+    size: Option<String>, // "full" (default) | "thumb"
+) -> Result<String, AppError> {
+    info!("Fetching image data for relative path: {}", relative_path);
 
-    info!("Cleaning up data older than timestamp: {}", thirty_days_ago);
+    let thumbnail = if wants_thumbnail(size.as_deref())? {
+        Some(thumbnails::current_settings(&db_state).await?)
+    } else {
+        None
+    };
+    let base_dir = get_image_base_dir(&app_handle)?;
+    tokio::task::spawn_blocking(move || {
+        load_image_data_uri(&base_dir, &relative_path, still_frame.unwrap_or(false), thumbnail.as_ref())
+    })
+    .await
+    .context("Image read task failed")?
+}
 
-    let mut conn_guard = db_state
-        .0
-        .lock()
-        .map_err(|e| format!("DB lock error: {}", e))?;
+const IMAGE_BATCH_CONCURRENCY: usize = 8;
+
+// Loads many images in one IPC call. A file that can't be read only fails its own entry.
+pub(crate) async fn load_images_batch(
+    base_dir: PathBuf,
+    paths: Vec<String>,
+    still_frame: bool,
+    thumbnail: Option<(ThumbnailSettings, bool)>,
+) -> CachedImageBatch {
+    let semaphore = Arc::new(Semaphore::new(IMAGE_BATCH_CONCURRENCY));
+    let mut set = JoinSet::new();
+    let mut requested = HashSet::new();
+    for relative_path in paths {
+        if !requested.insert(relative_path.clone()) {
+            continue;
+        }
+        let Ok(permit) = semaphore.clone().acquire_owned().await else {
+            break;
+        };
+        let base_dir = base_dir.clone();
+        let thumbnail = thumbnail.clone();
+        set.spawn_blocking(move || {
+            let _permit = permit;
+            let result = load_image_data_uri(&base_dir, &relative_path, still_frame, thumbnail.as_ref());
+            (relative_path, result)
+        });
+    }
 
-    let skipped_count: i64 = conn_guard
-        .query_row(
-            "SELECT COUNT(*) FROM messages WHERE timestamp < ? AND is_used = 1",
-            params![thirty_days_ago],
-            |row| row.get(0),
-        )
-        .map_err(|e| format!("Failed to count skipped messages: {}", e))?;
+    let mut batch = CachedImageBatch::default();
+    while let Some(joined) = set.join_next().await {
+        match joined {
+            Ok((relative_path, Ok(data_uri))) => {
+                batch.images.insert(relative_path, data_uri);
+            }
+            Ok((relative_path, Err(e))) => {
+                batch.errors.insert(relative_path, e.message);
+            }
+            Err(e) => error!("Image read task panicked or was cancelled: {}", e),
+        }
+    }
+    batch
+}
 
+#[tauri::command]
+pub async fn get_cached_images_batch(
+    app_handle: AppHandle,
+    db_state: State<'_, DbConnection>,
+    paths: Vec<String>,
+    size: Option<String>, // "full" (default) | "thumb"
+    still_frame: Option<bool>,
+) -> Result<CachedImageBatch, AppError> {
+    let thumbnail = if wants_thumbnail(size.as_deref())? {
+        Some(thumbnails::current_settings(&db_state).await?)
+    } else {
+        None
+    };
+    let base_dir = get_image_base_dir(&app_handle)?;
+    let requested = paths.len();
+    let batch = load_images_batch(base_dir, paths, still_frame.unwrap_or(false), thumbnail).await;
     info!(
-        "Found {} used messages that will be skipped in cleanup",
-        skipped_count
+        "Loaded {} of {} requested image(s), {} failed",
+        batch.images.len(),
+        requested,
+        batch.errors.len()
     );
+    Ok(batch)
+}
 
-    let (message_ids, attachments_to_delete) =
-        {
-            let mut stmt = conn_guard.prepare(
-            "SELECT message_id, attachments FROM messages WHERE timestamp < ? AND is_used = 0"
-        ).map_err(|e| format!("Failed to prepare old message query: {}", e))?;
+// Decodes the first frame of a GIF and re-encodes it as PNG.
+fn flatten_gif_to_png(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let frame = image::load_from_memory_with_format(bytes, image::ImageFormat::Gif)
+        .context("Failed to decode GIF")?;
+    let mut png_bytes = Vec::new();
+    frame
+        .write_to(&mut std::io::Cursor::new(&mut png_bytes), image::ImageFormat::Png)
+        .context("Failed to encode still frame")?;
+    Ok(png_bytes)
+}
 
-            let mut attachments = Vec::new();
-            let mut ids = Vec::new();
+pub(crate) const DEFAULT_RETENTION_DAYS: u32 = 30;
+const CLEANUP_SAMPLE_SIZE: usize = 20;
+const CLEANUP_CHUNK_SIZE: usize = 500; // also keeps the DELETE below SQLite's variable limit
 
-            let rows = stmt
-                .query_map(params![thirty_days_ago], |row| {
-                    let message_id: String = row.get(0)?;
-                    let attachments_json: Option<String> = row.get(1)?;
-
-                    if let Some(json_str) = attachments_json {
-                        if !json_str.is_empty() && json_str != "null" {
-                            if let Ok(parsed_attachments) =
-                                serde_json::from_str::<Vec<String>>(&json_str)
-                            {
-                                attachments.extend(parsed_attachments);
-                            }
-                        }
-                    }
+// The explicit value wins, then the saved retentionDays, then the 30-day default.
+pub(crate) fn resolve_retention_days(
+    conn: &Connection,
+    retention_days: Option<u32>,
+) -> Result<u32, AppError> {
+    let days = match retention_days {
+        Some(days) => days,
+        None => retrieve_config(conn)?
+            .retention_days
+            .unwrap_or(DEFAULT_RETENTION_DAYS),
+    };
+    if days == 0 {
+        return Err(AppError::invalid_input(
+            "Retention period must be at least one day.",
+        ));
+    }
+    Ok(days)
+}
 
-                    ids.push(message_id.clone());
-                    Ok(message_id)
-                })
-                .map_err(|e| format!("Error querying old messages: {}", e))?;
+#[tauri::command]
+pub async fn clean_old_data(
+    app_handle: AppHandle,
+    db_state: State<'_, DbConnection>,
+    retention_days: Option<u32>,
+    dry_run: Option<bool>,
+) -> Result<CleanupStats, AppError> {
+    let db_path = get_db_path(&app_handle)?;
+    db_state
+        .0
+        .run(move |conn| {
+            let retention_days = resolve_retention_days(conn, retention_days)?;
+            let mut stats = perform_cleanup(
+                &app_handle,
+                conn,
+                retention_days,
+                dry_run.unwrap_or(false),
+                |progress| {
+                    app_handle
+                        .emit("cleanup-progress", progress)
+                        .unwrap_or_default();
+                },
+            )?;
+            stats.compaction = compact_after_cleanup(conn, &db_path, &stats, |progress| {
+                app_handle
+                    .emit("compact-progress", progress)
+                    .unwrap_or_default();
+            });
+            Ok(stats)
+        })
+        .await
+}
 
-            for result in rows {
-                if let Err(e) = result {
-                    warn!("Error processing message row: {}", e);
-                }
-            }
+// Runs compact_database_file when the cleanup removed rows and compactAfterCleanup is on. A
+// failed compaction is only logged: the cleanup itself already succeeded.
+pub(crate) fn compact_after_cleanup(
+    conn: &Connection,
+    db_path: &Path,
+    stats: &CleanupStats,
+    on_progress: impl FnMut(CompactProgress),
+) -> Option<CompactResult> {
+    if stats.dry_run || stats.messages_deleted == 0 {
+        return None;
+    }
+    match retrieve_config(conn) {
+        Ok(config) if config.compact_after_cleanup == Some(true) => {}
+        _ => return None,
+    }
+    match compact_database_file(conn, db_path, on_progress) {
+        Ok(result) => Some(result),
+        Err(e) => {
+            warn!("Compaction after cleanup failed: {}", e);
+            None
+        }
+    }
+}
 
-            (ids, attachments)
-        };
+fn file_size(path: &Path) -> u64 {
+    fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+}
 
-    let messages_count = message_ids.len();
-    info!("Found {} old AND UNUSED messages to delete", messages_count);
+fn wal_path(db_path: &Path) -> PathBuf {
+    let mut path = db_path.as_os_str().to_owned();
+    path.push("-wal");
+    PathBuf::from(path)
+}
+
+const COMPACT_STEPS: usize = 4;
+
+// Checkpoints the WAL into the main file, rewrites the file without free pages and truncates
+// the WAL again, since in WAL mode VACUUM writes every page through it. VACUUM may renumber the
+// implicit rowids messages_fts is keyed on, so the search index is rebuilt in between.
+pub(crate) fn compact_database_file(
+    conn: &Connection,
+    db_path: &Path,
+    mut on_progress: impl FnMut(CompactProgress),
+) -> Result<CompactResult, String> {
+    let started = std::time::Instant::now();
+    let size_before_bytes = file_size(db_path);
+    let wal_size_before_bytes = file_size(&wal_path(db_path));
+    let mut report = |step: usize, message: &str| {
+        info!("Compacting database ({}/{}): {}", step, COMPACT_STEPS, message);
+        on_progress(CompactProgress {
+            step,
+            total_steps: COMPACT_STEPS,
+            message: message.to_string(),
+        });
+    };
+
+    report(1, "Checkpointing write-ahead log");
+    conn.query_row("PRAGMA wal_checkpoint(TRUNCATE);", [], |_| Ok(()))
+        .context("Failed to checkpoint WAL")?;
+
+    report(2, "Rewriting database file");
+    conn.execute_batch("VACUUM;")
+        .context("Failed to vacuum database")?;
+
+    report(3, "Rebuilding search index");
+    conn.execute("INSERT INTO messages_fts(messages_fts) VALUES ('rebuild')", [])
+        .context("Failed to rebuild search index")?;
+
+    report(4, "Truncating write-ahead log");
+    conn.query_row("PRAGMA wal_checkpoint(TRUNCATE);", [], |_| Ok(()))
+        .context("Failed to checkpoint WAL")?;
+
+    let result = CompactResult {
+        size_before_bytes,
+        size_after_bytes: file_size(db_path),
+        wal_size_before_bytes,
+        wal_size_after_bytes: file_size(&wal_path(db_path)),
+        duration_ms: started.elapsed().as_millis() as u64,
+    };
+    info!(
+        "Database compacted from {} to {} (WAL {} to {})",
+        format_bytes(result.size_before_bytes),
+        format_bytes(result.size_after_bytes),
+        format_bytes(result.wal_size_before_bytes),
+        format_bytes(result.wal_size_after_bytes)
+    );
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn compact_database(
+    app_handle: AppHandle,
+    db_state: State<'_, DbConnection>,
+) -> Result<CompactResult, AppError> {
+    let db_path = get_db_path(&app_handle)?;
+    db_state
+        .0
+        .run(move |conn| {
+            Ok(compact_database_file(conn, &db_path, |progress| {
+                app_handle
+                    .emit("compact-progress", progress)
+                    .unwrap_or_default();
+            })?)
+        })
+        .await
+}
+
+// (message_id, attachments) of every message matching `condition`.
+fn load_message_attachments(
+    conn: &Connection,
+    condition: &str,
+    values: &[RusqliteValue],
+) -> Result<Vec<(String, Vec<String>)>, String> {
+    let mut stmt = conn
+        .prepare(&format!("SELECT message_id, attachments FROM messages WHERE {}", condition))
+        .context("Failed to prepare message query")?;
+
+    let rows = stmt
+        .query_map(params_from_iter(values.iter()), |row| {
+            let message_id: String = row.get(0)?;
+            let attachments_json: Option<String> = row.get(1)?;
+            let attachments = attachments_json
+                .filter(|json_str| !json_str.is_empty() && json_str != "null")
+                .and_then(|json_str| serde_json::from_str::<Vec<String>>(&json_str).ok())
+                .unwrap_or_default();
+            Ok((message_id, attachments))
+        })
+        .context("Error querying messages")?;
+
+    Ok(rows
+        .filter_map(|result| {
+            result
+                .map_err(|e| warn!("Error processing message row: {}", e))
+                .ok()
+        })
+        .collect())
+}
 
-    if !message_ids.is_empty() {
-        let tx = conn_guard
+// Deletes the rows and then their cached files and thumbnails, reporting progress once per
+// chunk. Each chunk commits before its files go, so an interrupted run never leaves rows
+// pointing at deleted files. Returns the number of cached files removed.
+fn delete_messages_with_files(
+    conn: &mut Connection,
+    image_base_dir: &Path,
+    messages: &[(String, Vec<String>)],
+    on_progress: &mut impl FnMut(CleanupProgress),
+) -> Result<usize, String> {
+    let mut files_deleted = 0;
+    let mut thumbnails_deleted = 0;
+    let mut processed = 0;
+
+    for chunk in messages.chunks(CLEANUP_CHUNK_SIZE) {
+        let tx = conn
             .transaction()
-            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+            .context("Failed to start transaction")?;
 
-        let placeholders = vec!["?"; message_ids.len()].join(",");
+        let placeholders = vec!["?"; chunk.len()].join(",");
         let delete_sql = format!(
             "DELETE FROM messages WHERE message_id IN ({})",
             placeholders
         );
+        tx.execute(&delete_sql, params_from_iter(chunk.iter().map(|(id, _)| id)))
+            .context("Failed to delete messages")?;
 
-        let params: Vec<&dyn rusqlite::ToSql> = message_ids
-            .iter()
-            .map(|id| id as &dyn rusqlite::ToSql)
-            .collect();
-
-        tx.execute(&delete_sql, &params[..])
-            .map_err(|e| format!("Failed to delete old messages: {}", e))?;
-
-        // Commit the transaction
         tx.commit()
-            .map_err(|e| format!("Failed to commit cleanup transaction: {}", e))?;
-
-        info!("Deleted {} old messages from database", messages_count);
-    }
+            .context("Failed to commit deletion transaction")?;
 
-    let mut files_deleted = 0;
-    let cached_dir = get_image_base_dir(&app_handle)?.join("cached");
-
-    if cached_dir.exists() {
-        for attachment_path in &attachments_to_delete {
-            let file_path = cached_dir.join(attachment_path);
+        // Attachment paths are stored relative to the image dir ("cached/<file>").
+        for attachment_path in chunk.iter().flat_map(|(_, attachments)| attachments) {
+            let file_path = image_base_dir.join(attachment_path);
             if file_path.exists() {
                 match fs::remove_file(&file_path) {
                     Ok(_) => {
@@ -837,9 +2099,113 @@ pub async fn clean_old_data(
                     }
                 }
             }
+            thumbnails_deleted += thumbnails::remove_thumbnails(image_base_dir, attachment_path);
         }
+
+        processed += chunk.len();
+        on_progress(CleanupProgress {
+            processed,
+            total: messages.len(),
+            files_deleted,
+        });
     }
 
+    if !messages.is_empty() {
+        info!("Deleted {} messages from database", messages.len());
+    }
+    if thumbnails_deleted > 0 {
+        info!("Deleted {} thumbnails of removed cached files", thumbnails_deleted);
+    }
+    Ok(files_deleted)
+}
+
+// Deletes unused messages older than `retention_days` and their cached files. With `dry_run`
+// nothing is touched and the stats describe what would be removed, plus a sample of the
+// messages. Progress is reported once per chunk of messages.
+pub(crate) fn perform_cleanup(
+    paths: &impl PathProvider,
+    conn_guard: &mut Connection,
+    retention_days: u32,
+    dry_run: bool,
+    mut on_progress: impl FnMut(CleanupProgress),
+) -> Result<CleanupStats, String> {
+    info!(
+        "Starting cleanup of old data (entries > {} days){}...",
+        retention_days,
+        if dry_run { " as a dry run" } else { "" }
+    );
+
+    let cutoff = chrono::Utc::now()
+        .checked_sub_signed(chrono::Duration::days(retention_days as i64))
+        .expect("Valid timestamp calculation")
+        .timestamp();
+
+    info!("Cleaning up data older than timestamp: {}", cutoff);
+
+    let skipped_count: i64 = conn_guard
+        .query_row(
+            "SELECT COUNT(*) FROM messages WHERE timestamp < ? AND is_used = 1",
+            params![cutoff],
+            |row| row.get(0),
+        )
+        .context("Failed to count skipped messages")?;
+
+    info!(
+        "Found {} used messages that will be skipped in cleanup",
+        skipped_count
+    );
+
+    let old_messages = load_message_attachments(
+        conn_guard,
+        "timestamp < ? AND is_used = 0",
+        &[cutoff.into()],
+    )?;
+
+    let messages_count = old_messages.len();
+    info!("Found {} old AND UNUSED messages to delete", messages_count);
+
+    let image_base_dir = get_image_base_dir(paths)?;
+
+    if dry_run {
+        let files_deleted = old_messages
+            .iter()
+            .flat_map(|(_, attachments)| attachments)
+            .filter(|attachment_path| image_base_dir.join(attachment_path).exists())
+            .count();
+        let sample_messages = {
+            let mut stmt = conn_guard
+                .prepare(&format!(
+                    "SELECT {} FROM messages WHERE timestamp < ? AND is_used = 0 ORDER BY timestamp ASC LIMIT ?",
+                    INDEXED_MESSAGE_COLUMNS
+                ))
+                .context("Failed to prepare cleanup sample query")?;
+            let rows = stmt
+                .query_map(
+                    params![cutoff, CLEANUP_SAMPLE_SIZE as i64],
+                    map_row_to_indexed_message,
+                )
+                .context("Failed to query cleanup sample")?;
+            rows.collect::<Result<Vec<_>, _>>()
+                .context("Failed to read cleanup sample")?
+        };
+        info!(
+            "Dry run: cleanup would remove {} messages and {} cached files",
+            messages_count, files_deleted
+        );
+        return Ok(CleanupStats {
+            messages_deleted: messages_count,
+            files_deleted,
+            skipped_used_messages: skipped_count as usize,
+            retention_days,
+            dry_run: true,
+            sample_messages,
+            compaction: None,
+        });
+    }
+
+    let files_deleted =
+        delete_messages_with_files(conn_guard, &image_base_dir, &old_messages, &mut on_progress)?;
+
     info!(
         "Cleanup completed: removed {} messages and {} cached files. Skipped {} used messages.",
         messages_count, files_deleted, skipped_count
@@ -849,65 +2215,312 @@ pub async fn clean_old_data(
         messages_deleted: messages_count,
         files_deleted,
         skipped_used_messages: skipped_count as usize,
+        retention_days,
+        dry_run: false,
+        sample_messages: Vec::new(),
+        compaction: None,
+    })
+}
+
+// Deletes the unused messages of `channel_ids` (all channels when empty) inside `date_range`,
+// with their cached files. Messages a showcase selects are kept. Removing a whole channel also
+// forgets its indexing runs, so the next run starts from scratch instead of resuming.
+pub(crate) fn delete_matching_messages(
+    conn: &mut Connection,
+    image_base_dir: &Path,
+    channel_ids: &[String],
+    date_range: &DateRange,
+    mut on_progress: impl FnMut(CleanupProgress),
+) -> Result<MessageDeletionResult, AppError> {
+    if channel_ids.is_empty() && date_range.from.is_none() && date_range.to.is_none() {
+        return Err(AppError::invalid_input(
+            "Choose at least one channel or a date range; use delete_all_application_data to remove everything.",
+        ));
+    }
+    let mut conditions: Vec<String> = Vec::new();
+    let mut values: Vec<RusqliteValue> = Vec::new();
+    if !channel_ids.is_empty() {
+        conditions.push(format!("channel_id IN ({})", vec!["?"; channel_ids.len()].join(", ")));
+        values.extend(channel_ids.iter().map(|id| RusqliteValue::from(id.clone())));
+    }
+    if let Some(from) = date_range.from {
+        conditions.push("timestamp >= ?".to_string());
+        values.push(from.into());
+    }
+    if let Some(to) = date_range.to {
+        conditions.push("timestamp < ?".to_string());
+        values.push(to.into());
+    }
+    let condition = conditions.join(" AND ");
+
+    let skipped_used_messages: i64 = conn
+        .query_row(
+            &format!("SELECT COUNT(*) FROM messages WHERE {} AND is_used = 1", condition),
+            params_from_iter(values.iter()),
+            |row| row.get(0),
+        )
+        .context("Failed to count used messages")?;
+    let messages = load_message_attachments(conn, &format!("{} AND is_used = 0", condition), &values)?;
+    let files_deleted = delete_messages_with_files(conn, image_base_dir, &messages, &mut on_progress)?;
+
+    if date_range.from.is_none() && date_range.to.is_none() {
+        conn.execute(
+            &format!(
+                "DELETE FROM channel_index_runs WHERE channel_id IN ({})",
+                vec!["?"; channel_ids.len()].join(", ")
+            ),
+            params_from_iter(channel_ids.iter()),
+        )
+        .context("Failed to reset channel indexing runs")?;
+    }
+
+    info!(
+        "Deleted {} messages and {} cached files, kept {} used messages",
+        messages.len(),
+        files_deleted,
+        skipped_used_messages
+    );
+    Ok(MessageDeletionResult {
+        messages_deleted: messages.len(),
+        files_deleted,
+        skipped_used_messages: skipped_used_messages as usize,
     })
 }
 
 #[tauri::command]
-pub async fn delete_all_application_data(
+pub async fn delete_indexed_messages(
     app_handle: AppHandle,
     db_state: State<'_, DbConnection>,
-) -> Result<(), String> {
-    info!("Starting full application data deletion...");
+    indexing_state: State<'_, IndexingState>,
+    channel_ids: Vec<String>,
+    date_range: Option<DateRange>,
+) -> Result<MessageDeletionResult, AppError> {
+    if indexing_state.0.is_running() {
+        return Err(AppError::conflict("Cannot delete messages while indexing is running."));
+    }
+    let image_base_dir = get_image_base_dir(&app_handle)?;
+    db_state
+        .0
+        .run(move |conn| {
+            delete_matching_messages(
+                conn,
+                &image_base_dir,
+                &channel_ids,
+                &date_range.unwrap_or_default(),
+                |progress| {
+                    app_handle
+                        .emit("cleanup-progress", progress)
+                        .unwrap_or_default();
+                },
+            )
+        })
+        .await
+}
 
-    let db_path = get_db_path(&app_handle)?;
-    info!("Database path to delete: {}", db_path.display());
+// Sizes and counts the files below `dir`, then removes it.
+fn remove_dir_counting(dir: &Path) -> (usize, u64) {
+    fn walk(dir: &Path, files: &mut usize) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                walk(&path, files);
+            } else {
+                *files += 1;
+            }
+        }
+    }
+    if !dir.exists() {
+        return (0, 0);
+    }
+    let bytes = calculate_dir_size(dir).unwrap_or(0);
+    let mut files = 0;
+    walk(dir, &mut files);
+    match fs::remove_dir_all(dir) {
+        Ok(()) => (files, bytes),
+        Err(e) => {
+            warn!("Failed to delete {}: {}", dir.display(), e);
+            (0, 0)
+        }
+    }
+}
 
-    {
-        let mut conn_guard = db_state
-            .0
-            .lock()
-            .map_err(|e| format!("DB lock error: {}", e))?;
+// Removes downloaded attachments, thumbnails and avatars but keeps every message row, so
+// repair_cache can download the attachments again. Showcase images live next to the cache and
+// are not touched.
+pub(crate) fn clear_cache_files(conn: &mut Connection, image_base_dir: &Path) -> Result<CacheClearResult, String> {
+    let tx = conn
+        .transaction()
+        .context("Failed to start transaction")?;
+    tx.execute("UPDATE messages SET author_avatar_path = NULL WHERE author_avatar_path IS NOT NULL", [])
+        .context("Failed to clear cached avatar paths")?;
+    tx.execute("DELETE FROM cached_files", [])
+        .context("Failed to clear cached file hashes")?;
+    tx.commit()
+        .context("Failed to commit transaction")?;
 
-        let _ = conn_guard.execute("PRAGMA wal_checkpoint(FULL);", []);
+    let mut result = CacheClearResult::default();
+    for dir in ["cached", thumbnails::THUMBNAIL_DIR, AVATAR_DIR] {
+        let (files, bytes) = remove_dir_counting(&image_base_dir.join(dir));
+        result.files_deleted += files;
+        result.bytes_freed += bytes;
+    }
+    info!(
+        "Image cache cleared: {} files, {}",
+        result.files_deleted,
+        format_bytes(result.bytes_freed)
+    );
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn clear_image_cache_only(
+    app_handle: AppHandle,
+    db_state: State<'_, DbConnection>,
+    indexing_state: State<'_, IndexingState>,
+) -> Result<CacheClearResult, AppError> {
+    if indexing_state.0.is_running() {
+        return Err(AppError::conflict("Cannot clear the image cache while indexing is running."));
+    }
+    let image_base_dir = get_image_base_dir(&app_handle)?;
+    db_state
+        .0
+        .run(move |conn| Ok(clear_cache_files(conn, &image_base_dir)?))
+        .await
+}
+
+// Forgets every setting, including the finished setup, but keeps indexed messages, showcases
+// and the secrets in the keyring. The removal is recorded in the config history, so it can be
+// undone with revert_config_to. Returns the number of keys removed.
+pub(crate) fn reset_config(conn: &mut Connection) -> Result<usize, String> {
+    let tx = conn
+        .transaction()
+        .context("Failed to start transaction")?;
+    let before = snapshot_config(&tx)?;
+    tx.execute("DELETE FROM config", [])
+        .context("Failed to delete configuration")?;
+    let after = snapshot_config(&tx)?;
+    record_config_changes(&tx, &before, &after)?;
+    tx.commit()
+        .context("Failed to commit configuration reset")?;
+    info!("Configuration reset, {} key(s) removed", before.len());
+    Ok(before.len())
+}
 
-        use std::mem;
-        let old_conn = mem::replace(&mut *conn_guard, Connection::open_in_memory().unwrap());
-        drop(old_conn);
+#[tauri::command]
+pub async fn reset_configuration_only(db_state: State<'_, DbConnection>) -> Result<usize, AppError> {
+    db_state
+        .0
+        .run(move |conn| Ok(reset_config(conn)?))
+        .await
+}
 
-        info!("Database connection closed properly");
+// Re-writes the plaintext database as a SQLCipher file keyed from the OS keyring. The encrypted
+// copy is written and checked next to the original before it replaces it, and every later launch
+// unlocks it automatically. Existing backups are left as they are.
+#[tauri::command]
+pub async fn encrypt_existing_database(
+    app_handle: AppHandle,
+    db_state: State<'_, DbConnection>,
+) -> Result<(), AppError> {
+    let db_path = get_db_path(&app_handle)?;
+    if !db_encryption::is_plaintext_database(&db_path) {
+        return Err(AppError::conflict("The database is already encrypted."));
     }
+    info!("Encrypting database at {}", db_path.display());
 
-    std::thread::sleep(std::time::Duration::from_millis(100));
+    let key = db_encryption::load_or_create_key(&KeyringSecrets)?;
+    let encrypted_path = db_path.with_extension("db.encrypting");
+    // Every pooled connection is closed for the whole swap, so no write can land in the
+    // plaintext file after it has been copied.
+    db_state.0.run_closed(move || -> Result<(), AppError> {
+        {
+            let conn = open_connection(&db_path, &KeyringSecrets)?;
+            conn.query_row("PRAGMA wal_checkpoint(TRUNCATE);", [], |_| Ok(()))
+                .context("Failed to checkpoint database")?;
+            db_encryption::export_encrypted(&conn, &encrypted_path, &key)?;
+        }
 
-    if db_path.exists() {
-        match fs::remove_file(&db_path) {
-            Ok(_) => info!("Successfully deleted database file"),
-            Err(e) => {
-                warn!("Failed to delete database file: {}", e);
-                if cfg!(windows) {
-                    std::thread::sleep(std::time::Duration::from_millis(500));
-                    match fs::remove_file(&db_path) {
-                        Ok(_) => info!("Successfully deleted database file on second attempt"),
-                        Err(e) => warn!("Failed to delete database file on second attempt: {}", e),
+        let encrypted = Connection::open(&encrypted_path)
+            .context("Failed to open encrypted database")?;
+        db_encryption::apply_key(&encrypted, &key)?;
+        // The export does not keep rowids, which the search index is keyed on.
+        encrypted
+            .execute("INSERT INTO messages_fts(messages_fts) VALUES ('rebuild')", [])
+            .context("Failed to rebuild search index")?;
+        let check: String = encrypted
+            .query_row("PRAGMA quick_check;", [], |row| row.get(0))
+            .context("Failed to verify encrypted database")?;
+        if check != "ok" {
+            drop(encrypted);
+            let _ = fs::remove_file(&encrypted_path);
+            return Err(format!("Encrypted database failed verification: {}", check).into());
+        }
+        drop(encrypted);
+
+        for extension in ["db-wal", "db-shm"] {
+            let _ = fs::remove_file(db_path.with_extension(extension));
+        }
+        fs::rename(&encrypted_path, &db_path).map_err(|e| {
+            AppError::from(e).with_context("Failed to replace database with encrypted copy")
+        })
+    })
+    .await??;
+
+    info!("Database encrypted. Backups in {} were not changed.", get_backup_dir(&app_handle)?.display());
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn delete_all_application_data(
+    app_handle: AppHandle,
+    db_state: State<'_, DbConnection>,
+) -> Result<(), AppError> {
+    info!("Starting full application data deletion...");
+
+    let db_path = get_db_path(&app_handle)?;
+    info!("Database path to delete: {}", db_path.display());
+
+    // The files are removed while every pooled connection is closed; the next query opens a
+    // fresh, empty database that is set up properly on the next launch.
+    db_state.0.run_closed(move || {
+        info!("Database connections closed properly");
+
+        std::thread::sleep(std::time::Duration::from_millis(100));
+
+        if db_path.exists() {
+            match fs::remove_file(&db_path) {
+                Ok(_) => info!("Successfully deleted database file"),
+                Err(e) => {
+                    warn!("Failed to delete database file: {}", e);
+                    if cfg!(windows) {
+                        std::thread::sleep(std::time::Duration::from_millis(500));
+                        match fs::remove_file(&db_path) {
+                            Ok(_) => info!("Successfully deleted database file on second attempt"),
+                            Err(e) => warn!("Failed to delete database file on second attempt: {}", e),
+                        }
                     }
                 }
             }
         }
-    }
 
-    let wal_path = db_path.with_extension("db-wal");
-    if wal_path.exists() {
-        if let Err(e) = fs::remove_file(&wal_path) {
-            warn!("Failed to delete WAL file: {}", e);
+        let wal_path = db_path.with_extension("db-wal");
+        if wal_path.exists() {
+            if let Err(e) = fs::remove_file(&wal_path) {
+                warn!("Failed to delete WAL file: {}", e);
+            }
         }
-    }
 
-    let shm_path = db_path.with_extension("db-shm");
-    if shm_path.exists() {
-        if let Err(e) = fs::remove_file(&shm_path) {
-            warn!("Failed to delete SHM file: {}", e);
+        let shm_path = db_path.with_extension("db-shm");
+        if shm_path.exists() {
+            if let Err(e) = fs::remove_file(&shm_path) {
+                warn!("Failed to delete SHM file: {}", e);
+            }
         }
-    }
+    })
+    .await?;
 
     let image_dir = get_image_base_dir(&app_handle)?;
     info!("Deleting all images from {}", image_dir.display());
@@ -918,10 +2531,7 @@ pub async fn delete_all_application_data(
         }
     }
 
-    let app_data_dir = app_handle
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data dir: {}", e))?;
+    let app_data_dir = app_paths::app_data_dir(&app_handle)?;
 
     let presentations_dir = app_data_dir.join("presentations");
     if presentations_dir.exists() {
@@ -931,27 +2541,19 @@ pub async fn delete_all_application_data(
         }
     }
 
-    const SERVICE_NAME: &str = "com.megalith.showcase-app";
-
-    let discord_token_entry = Entry::new(SERVICE_NAME, "discordBotToken")
-        .map_err(|e| format!("Failed to create keyring entry for Discord token: {}", e))?;
-
-    match discord_token_entry.delete_credential() {
-        Ok(_) => info!("Successfully deleted Discord bot token from keyring"),
-        Err(e) => {
-            warn!("Could not delete Discord bot token: {}", e);
-        }
-    }
-
-    let openrouter_key_entry = Entry::new(SERVICE_NAME, "openRouterApiKey")
-        .map_err(|e| format!("Failed to create keyring entry for OpenRouter key: {}", e))?;
-
-    match openrouter_key_entry.delete_credential() {
-        Ok(_) => info!("Successfully deleted OpenRouter key from keyring"),
-        Err(e) => {
-            warn!("Could not delete OpenRouter key: {}", e);
+    let secrets = [
+        (BOT_TOKEN_KEY, "Discord bot token"),
+        (cache_crypto::CACHE_KEY_NAME, "cache encryption key"),
+        (db_encryption::DB_KEY_NAME, "database encryption key"),
+        ("openRouterApiKey", "OpenRouter key"),
+    ];
+    for (key_name, label) in secrets {
+        match KeyringSecrets.delete_secret(key_name) {
+            Ok(()) => info!("Successfully deleted {} from keyring", label),
+            Err(e) => warn!("Could not delete {}: {}", label, e),
         }
     }
+    cache_crypto::forget_cached_key();
 
     info!("Application data deletion completed successfully.");
     Ok(())
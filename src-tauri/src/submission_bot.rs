@@ -0,0 +1,376 @@
+use rusqlite::{params, Connection};
+use serenity::all::{
+    Command, CommandInteraction, CommandOptionType, CreateCommand, CreateCommandOption,
+    EditInteractionResponse, GatewayIntents, Interaction, ResolvedValue, ShardManager,
+};
+use serenity::async_trait;
+use serenity::client::{ClientBuilder, Context, EventHandler};
+use serenity::model::channel::Attachment;
+use serenity::model::gateway::Ready;
+use serenity::model::id::GuildId;
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::Mutex as AsyncMutex;
+
+use crate::avatars;
+use crate::discord::{
+    download_to_cache, indexable_attachment_type, load_bot_token, CacheDownloadJob, AUTHOR_TYPE_USER,
+};
+use crate::error::{AppError, ResultExt};
+use crate::models::IndexedMessage;
+use crate::network::{discord_http, ensure_gateway_allowed, http_client};
+use crate::sqlite_manager::{get_image_base_dir, retrieve_config, DbConnection, DbPool};
+use crate::{log_error as error, log_info as info, log_warn as warn};
+
+const SUBMIT_COMMAND: &str = "submit";
+const IMAGE_OPTION: &str = "image";
+const CAPTION_OPTION: &str = "caption";
+
+#[derive(Default)]
+pub struct SubmissionBotState(pub AsyncMutex<Option<Arc<ShardManager>>>);
+
+#[derive(serde::Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmissionBotStatus {
+    pub running: bool,
+    pub submission_count: i64,
+}
+
+struct SubmissionHandler {
+    app_handle: AppHandle,
+    db: DbPool,
+    guild_id: Option<GuildId>,
+    encrypt_cache: bool,
+    include_animated: bool,
+    exclude_sensitive: bool,
+    image_base_dir: PathBuf,
+    cache_dir: PathBuf,
+    download_client: reqwest::Client,
+}
+
+fn submit_command() -> CreateCommand {
+    CreateCommand::new(SUBMIT_COMMAND)
+        .description("Submit artwork for the next showcase")
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::Attachment, IMAGE_OPTION, "The image to submit")
+                .required(true),
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::String, CAPTION_OPTION, "Optional caption shown with it")
+                .required(false),
+        )
+}
+
+fn insert_submission(conn: &Connection, submission: &IndexedMessage) -> Result<(), String> {
+    let to_json = |values: &Vec<String>| {
+        serde_json::to_string(values).map_err(|e| format!("JSON Serialize: {}", e))
+    };
+    conn.execute(
+        "INSERT INTO messages (message_id, channel_id, author_id, author_name, author_avatar, message_content, attachments, timestamp, attachment_types, attachment_sources, author_avatar_path, is_spoiler, is_submission)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, 1)",
+        params![
+            submission.message_id,
+            submission.channel_id,
+            submission.author_id,
+            submission.author_name,
+            submission.author_avatar,
+            submission.message_content,
+            to_json(&submission.attachments)?,
+            submission.timestamp,
+            to_json(&submission.attachment_types)?,
+            to_json(&submission.attachment_sources)?,
+            submission.author_avatar_path,
+            submission.is_spoiler,
+        ],
+    )
+    .context(format!("Failed to store submission {}", submission.message_id))?;
+    Ok(())
+}
+
+impl SubmissionHandler {
+    async fn store_submission(
+        &self,
+        command: &CommandInteraction,
+        attachment: &Attachment,
+        caption: &str,
+    ) -> Result<IndexedMessage, String> {
+        if self.guild_id.is_some() && command.guild_id != self.guild_id {
+            return Err("Submissions are only accepted in the configured server.".to_string());
+        }
+        let Some(kind) = indexable_attachment_type(attachment, self.include_animated) else {
+            return Err(format!("{} is not a supported image.", attachment.filename));
+        };
+        let is_spoiler = attachment.filename.starts_with("SPOILER_");
+        if is_spoiler && self.exclude_sensitive {
+            return Err("Spoilered images are not accepted.".to_string());
+        }
+
+        // The interaction ID stands in for a message ID; there is no channel message to link to.
+        let message_id = command.id.to_string();
+        let job = CacheDownloadJob::for_attachment(&self.cache_dir, 0, &message_id, attachment, kind);
+        let Some(cached_file) = download_to_cache(&self.download_client, &job, self.encrypt_cache).await? else {
+            return Err("Discord refused the attachment download.".to_string());
+        };
+
+        avatars::cache_author_avatars(
+            &self.download_client,
+            &self.image_base_dir,
+            [&command.user],
+            self.encrypt_cache,
+            &mut HashSet::new(),
+        )
+        .await;
+
+        let submission = IndexedMessage {
+            message_id,
+            channel_id: command.channel_id.to_string(),
+            author_id: command.user.id.to_string(),
+            author_name: command.user.name.clone(),
+            author_avatar: command.user.avatar_url(),
+            message_content: caption.to_string(),
+            attachments: vec![cached_file.relative_path],
+            attachment_types: vec![cached_file.attachment_type.to_string()],
+            attachment_sources: vec![cached_file.source.to_string()],
+            timestamp: command.id.created_at().unix_timestamp(),
+            is_used: false,
+            reaction_count: 0,
+            reactions: Vec::new(),
+            deleted: false,
+            reply_to: None,
+            author_avatar_path: avatars::avatar_cache_path(&command.user),
+            is_submission: true,
+            is_spoiler,
+            is_nsfw_channel: false,
+            review_decision: None,
+            author_type: AUTHOR_TYPE_USER.to_string(),
+            is_favorite: false,
+        };
+
+        let row = submission.clone();
+        self.db
+            .run(move |conn| Ok(insert_submission(conn, &row)?))
+            .await?;
+
+        Ok(submission)
+    }
+
+    async fn handle_submit(&self, ctx: &Context, command: &CommandInteraction) {
+        // Downloads can take longer than the 3 second reply window.
+        if let Err(e) = command.defer_ephemeral(&ctx.http).await {
+            warn!("Submission bot: could not acknowledge /submit: {}", e);
+            return;
+        }
+
+        let mut attachment = None;
+        let mut caption = "";
+        for option in command.data.options() {
+            match (option.name, option.value) {
+                (IMAGE_OPTION, ResolvedValue::Attachment(a)) => attachment = Some(a),
+                (CAPTION_OPTION, ResolvedValue::String(s)) => caption = s,
+                _ => {}
+            }
+        }
+
+        let reply = match attachment {
+            None => "Please attach an image.".to_string(),
+            Some(attachment) => match self.store_submission(command, attachment, caption).await {
+                Ok(submission) => {
+                    info!(
+                        "Stored submission {} from {}",
+                        submission.message_id, submission.author_name
+                    );
+                    self.app_handle
+                        .emit("message-indexed", submission)
+                        .unwrap_or_default();
+                    "Thanks! Your submission was received.".to_string()
+                }
+                Err(e) => {
+                    error!("Submission from {} failed: {}", command.user.id, e);
+                    format!("Your submission could not be saved: {}", e)
+                }
+            },
+        };
+
+        if let Err(e) = command
+            .edit_response(&ctx.http, EditInteractionResponse::new().content(reply))
+            .await
+        {
+            warn!("Submission bot: could not reply to /submit: {}", e);
+        }
+    }
+}
+
+#[async_trait]
+impl EventHandler for SubmissionHandler {
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        if let Interaction::Command(command) = interaction {
+            if command.data.name == SUBMIT_COMMAND {
+                self.handle_submit(&ctx, &command).await;
+            }
+        }
+    }
+
+    // Guild commands show up immediately; global ones can take up to an hour to propagate.
+    async fn ready(&self, ctx: Context, ready: Ready) {
+        let registered = match self.guild_id {
+            Some(guild_id) => guild_id
+                .set_commands(&ctx.http, vec![submit_command()])
+                .await
+                .map(|_| ()),
+            None => Command::create_global_command(&ctx.http, submit_command())
+                .await
+                .map(|_| ()),
+        };
+        match registered {
+            Ok(()) => info!("Submission bot connected as {}, /submit registered", ready.user.name),
+            Err(e) => error!("Submission bot could not register /submit: {}", e),
+        }
+        self.app_handle
+            .emit("submission-bot-status", "connected")
+            .unwrap_or_default();
+    }
+}
+
+async fn connect(app_handle: &AppHandle, bot_state: &SubmissionBotState) -> Result<(), String> {
+    let mut running = bot_state.0.lock().await;
+    if running.is_some() {
+        return Err("The submission bot is already running.".to_string());
+    }
+
+    let db_state = app_handle.state::<DbConnection>();
+    let config = db_state.0.run(|conn| Ok(retrieve_config(conn)?)).await?;
+    let network_settings = config.network_settings.clone().unwrap_or_default();
+    ensure_gateway_allowed(&network_settings)?;
+
+    let handler = SubmissionHandler {
+        app_handle: app_handle.clone(),
+        db: db_state.0.clone(),
+        guild_id: config
+            .selected_server_id
+            .as_deref()
+            .and_then(|id| id.parse::<u64>().ok())
+            .map(GuildId::new),
+        encrypt_cache: config.encrypt_image_cache.unwrap_or(false),
+        include_animated: config.include_animated_attachments.unwrap_or(false),
+        exclude_sensitive: config.exclude_sensitive_content.unwrap_or(false),
+        image_base_dir: get_image_base_dir(app_handle)?,
+        cache_dir: get_image_base_dir(app_handle)?.join("cached"),
+        download_client: http_client(&network_settings, Some(Duration::from_secs(30)))?,
+    };
+
+    // Interactions arrive without any privileged intent.
+    let token = load_bot_token()?;
+    let http = discord_http(&network_settings, &token)?;
+    let mut client = ClientBuilder::new_with_http(http, GatewayIntents::GUILDS)
+        .event_handler(handler)
+        .await
+        .context("Failed to create gateway client")?;
+
+    let shard_manager = client.shard_manager.clone();
+    *running = Some(shard_manager.clone());
+    drop(running);
+
+    let app_clone = app_handle.clone();
+    tokio::spawn(async move {
+        if let Err(e) = client.start().await {
+            error!("Submission bot gateway client stopped with error: {}", e);
+            app_clone
+                .emit("indexing-error", format!("Submission bot disconnected: {}", e))
+                .unwrap_or_default();
+        }
+        {
+            let bot_state = app_clone.state::<SubmissionBotState>();
+            let mut running = bot_state.0.lock().await;
+            if running
+                .as_ref()
+                .map_or(false, |current| Arc::ptr_eq(current, &shard_manager))
+            {
+                *running = None;
+            }
+        }
+        app_clone
+            .emit("submission-bot-status", "disconnected")
+            .unwrap_or_default();
+        info!("Submission bot gateway client exited.");
+    });
+
+    info!("Submission bot started.");
+    Ok(())
+}
+
+// Called from setup; connects only when the user enabled the submission bot.
+pub fn start_if_enabled(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let enabled = app_handle
+            .state::<DbConnection>()
+            .0
+            .run(|conn| {
+                let config = retrieve_config(conn)?;
+                Ok(config.is_setup_complete && config.submission_bot_enabled.unwrap_or(false))
+            })
+            .await;
+        let enabled = match enabled {
+            Ok(enabled) => enabled,
+            Err(e) => {
+                error!("Submission bot: could not read the settings: {}", e);
+                return;
+            }
+        };
+        if !enabled {
+            return;
+        }
+        let bot_state = app_handle.state::<SubmissionBotState>();
+        if let Err(e) = connect(&app_handle, &bot_state).await {
+            error!("Failed to start submission bot: {}", e);
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn start_submission_bot(
+    app_handle: AppHandle,
+    bot_state: State<'_, SubmissionBotState>,
+) -> Result<(), AppError> {
+    Ok(connect(&app_handle, &bot_state).await?)
+}
+
+#[tauri::command]
+pub async fn stop_submission_bot(bot_state: State<'_, SubmissionBotState>) -> Result<(), AppError> {
+    let shard_manager = bot_state.0.lock().await.take();
+    match shard_manager {
+        Some(manager) => {
+            info!("Stopping submission bot...");
+            manager.shutdown_all().await;
+            Ok(())
+        }
+        None => Err(AppError::conflict("The submission bot is not running.")),
+    }
+}
+
+#[tauri::command]
+pub async fn get_submission_bot_status(
+    db_state: State<'_, DbConnection>,
+    bot_state: State<'_, SubmissionBotState>,
+) -> Result<SubmissionBotStatus, AppError> {
+    let running = bot_state.0.lock().await.is_some();
+    let submission_count = db_state
+        .0
+        .run(|conn| {
+            Ok(conn
+                .query_row(
+                    "SELECT COUNT(*) FROM messages WHERE is_submission = 1 AND deleted = 0",
+                    [],
+                    |row| row.get(0),
+                )
+                .context("Failed to count submissions")?)
+        })
+        .await?;
+    Ok(SubmissionBotStatus {
+        running,
+        submission_count,
+    })
+}
@@ -0,0 +1,256 @@
+use chrono::Utc;
+use std::fs;
+use std::io::{Cursor, Write};
+use zip::write::{SimpleFileOptions, ZipWriter};
+use zip::CompressionMethod;
+
+use super::{message, selected, TestEnv, PNG_BYTES};
+use crate::error::ErrorKind;
+use crate::models::{ShowcaseImage, ShowcaseQuery, ShowcaseStatus};
+use crate::showcase_archive::{
+    archive_showcase_data, import_showcase_archive_from, unarchive_showcase_data,
+    write_showcase_archive,
+};
+use crate::showcase_manager::{
+    get_showcase_image_pack, insert_showcase, load_showcase, load_showcase_status,
+    query_showcases_page, store_selected_messages, write_showcase_image,
+};
+
+fn slide(message_id: &str) -> ShowcaseImage {
+    serde_json::from_value(serde_json::json!({
+        "message_id": message_id,
+        "sender": "artist501",
+        "avatar": "",
+        "message": "Sunset",
+        "is_edited": false,
+        "overlay": {
+            "position": "top-right",
+            "style": "white",
+            "showAvatar": true,
+            "width": 35.0,
+            "transparency": 10
+        }
+    }))
+    .unwrap()
+}
+
+#[test]
+fn archive_round_trips_into_a_new_showcase() {
+    let mut source = TestEnv::new();
+    let now = Utc::now().timestamp();
+    let picked = source.index(&message(1, 501, "Sunset", now, &[11]));
+    let id = insert_showcase(&source.conn, "Weekly Picks", Some("Best of the week")).unwrap();
+    store_selected_messages(
+        &mut source.conn,
+        &id,
+        &[selected("1", &picked[0]), selected("2", "cached/gone.png")],
+    )
+    .unwrap();
+    write_showcase_image(&source.conn, &id, &slide("1"), None).unwrap();
+    let showcase_dir = source.image_dir().join(&id);
+    fs::create_dir_all(&showcase_dir).unwrap();
+    fs::write(showcase_dir.join(format!("{}_1.jpg", id)), b"render").unwrap();
+
+    let showcase = load_showcase(&source.conn, &id).unwrap();
+    let (cursor, export) =
+        write_showcase_archive(Cursor::new(Vec::new()), &source.data_dir, &showcase).unwrap();
+    assert_eq!((export.files, export.slides), (1, 1));
+    assert_eq!(export.missing, ["cached/gone.png"]);
+
+    let mut target = TestEnv::new();
+    target.index(&message(2, 501, "Moonrise", now, &[21]));
+    let imported = import_showcase_archive_from(
+        Cursor::new(cursor.into_inner()),
+        &target.data_dir,
+        &mut target.conn,
+        false,
+    )
+    .unwrap();
+    assert_ne!(imported.showcase_id, id);
+    assert_eq!((imported.files_restored, imported.slides_restored), (1, 1));
+    assert!(target.is_used("2"));
+
+    let copy = load_showcase(&target.conn, &imported.showcase_id).unwrap();
+    assert_eq!(copy.title, "Weekly Picks");
+    assert_eq!(copy.description.as_deref(), Some("Best of the week"));
+    assert_eq!(copy.phase, showcase.phase);
+    let messages = copy.selected_messages.unwrap();
+    assert_eq!(messages.len(), 2);
+    assert_eq!(messages[0].attachment_filenames(), picked);
+    let images = copy.images.unwrap();
+    assert_eq!(images[0].overlay.width, 35.0);
+    assert_eq!(
+        fs::read(target.image_dir().join(&picked[0])).unwrap(),
+        PNG_BYTES
+    );
+    let render = target
+        .image_dir()
+        .join(&imported.showcase_id)
+        .join(format!("{}_1.jpg", imported.showcase_id));
+    assert_eq!(fs::read(render).unwrap(), b"render");
+}
+
+#[test]
+fn archive_entries_cannot_escape_the_image_cache() {
+    let manifest = serde_json::json!({
+        "format_version": 1,
+        "app_version": "0.0.0",
+        "exported_at": 0,
+        "title": "Sneaky",
+        "description": null,
+        "status": "Draft",
+        "phase": 1,
+        "created_at": 0,
+        "images": [slide("../../../escaped")]
+    });
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    for (name, bytes) in [
+        ("showcase.json", manifest.to_string().into_bytes()),
+        ("cache/../../outside.txt", b"escaped".to_vec()),
+        ("cache/cached/planted.png", b"unreferenced".to_vec()),
+        ("slides/unknown.png", b"stray".to_vec()),
+        ("slides/../../../escaped.png", b"escaped".to_vec()),
+    ] {
+        zip.start_file(name, SimpleFileOptions::default()).unwrap();
+        zip.write_all(&bytes).unwrap();
+    }
+    let bytes = zip.finish().unwrap().into_inner();
+
+    let mut env = TestEnv::new();
+    let imported =
+        import_showcase_archive_from(Cursor::new(bytes), &env.data_dir, &mut env.conn, false)
+            .unwrap();
+    assert_eq!((imported.files_restored, imported.slides_restored), (0, 0));
+    assert!(!env.data_dir.join("outside.txt").exists());
+    assert!(!env.image_dir().join("cached/planted.png").exists());
+    assert!(!env.image_dir().join("escaped.png").exists());
+    assert!(!env.image_dir().join(&imported.showcase_id).exists());
+
+    let newer = serde_json::json!({ "format_version": 99, "app_version": "9.0.0", "exported_at": 0,
+        "title": "Future", "description": null, "status": "Draft", "phase": 1, "created_at": 0 });
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    zip.start_file("showcase.json", SimpleFileOptions::default())
+        .unwrap();
+    zip.write_all(newer.to_string().as_bytes()).unwrap();
+    let bytes = zip.finish().unwrap().into_inner();
+    assert!(
+        import_showcase_archive_from(Cursor::new(bytes), &env.data_dir, &mut env.conn, false)
+            .is_err()
+    );
+}
+
+#[test]
+fn a_failed_import_leaves_nothing_behind() {
+    let manifest = serde_json::json!({
+        "format_version": 1,
+        "app_version": "0.0.0",
+        "exported_at": 0,
+        "title": "Broken",
+        "description": null,
+        "status": "Draft",
+        "phase": 1,
+        "created_at": 0,
+        "selected_messages": [selected("1", "cached/1_11.png")]
+    });
+    let stored = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    for (name, bytes) in [
+        ("showcase.json", manifest.to_string().into_bytes()),
+        ("cache/cached/1_11.png", PNG_BYTES.to_vec()),
+        ("slides/1_0.png", b"slide render".to_vec()),
+    ] {
+        zip.start_file(name, stored).unwrap();
+        zip.write_all(&bytes).unwrap();
+    }
+    let mut bytes = zip.finish().unwrap().into_inner();
+    // Damage the last entry so its checksum fails after the cache file was written.
+    let at = bytes
+        .windows(b"slide render".len())
+        .position(|window| window == b"slide render")
+        .unwrap();
+    bytes[at] = b'S';
+
+    let mut env = TestEnv::new();
+    assert!(
+        import_showcase_archive_from(Cursor::new(bytes), &env.data_dir, &mut env.conn, false)
+            .is_err()
+    );
+    assert!(!env.image_dir().join("cached/1_11.png").exists());
+    let listed = query_showcases_page(&env.conn, &ShowcaseQuery::default()).unwrap();
+    assert_eq!(listed.total, 0);
+}
+
+#[test]
+fn archiving_packs_renders_and_hides_the_showcase() {
+    let env = TestEnv::new();
+    let id = insert_showcase(&env.conn, "Weekly", None).unwrap();
+    let other = insert_showcase(&env.conn, "Monthly", None).unwrap();
+    env.conn
+        .execute("UPDATE showcases SET phase = 3 WHERE id = ?1", [&id])
+        .unwrap();
+    let showcase_dir = env.image_dir().join(&id);
+    fs::create_dir_all(&showcase_dir).unwrap();
+    fs::write(showcase_dir.join(format!("{}_1.png", id)), PNG_BYTES).unwrap();
+    fs::write(showcase_dir.join(format!("{}_2.png", id)), PNG_BYTES).unwrap();
+
+    let pack = archive_showcase_data(&env.data_dir, &env.conn, &id).unwrap();
+    assert_eq!(pack.files, 2);
+    assert_eq!(pack.original_bytes, 2 * PNG_BYTES.len() as u64);
+    assert!(!showcase_dir.exists());
+    assert!(get_showcase_image_pack(&env.data_dir, &id)
+        .unwrap()
+        .is_file());
+    assert_eq!(
+        load_showcase_status(&env.conn, &id).unwrap(),
+        ShowcaseStatus::Archived
+    );
+    let err = archive_showcase_data(&env.data_dir, &env.conn, &id).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::Conflict);
+
+    let listed = query_showcases_page(&env.conn, &ShowcaseQuery::default()).unwrap();
+    assert_eq!(listed.total, 1);
+    assert_eq!(listed.showcases[0].id, other);
+    let archived = ShowcaseQuery {
+        statuses: vec!["Archived".to_string()],
+        ..Default::default()
+    };
+    assert_eq!(query_showcases_page(&env.conn, &archived).unwrap().total, 1);
+
+    assert_eq!(
+        unarchive_showcase_data(&env.data_dir, &env.conn, &id).unwrap(),
+        2
+    );
+    assert_eq!(
+        fs::read(showcase_dir.join(format!("{}_2.png", id))).unwrap(),
+        PNG_BYTES
+    );
+    assert!(!get_showcase_image_pack(&env.data_dir, &id)
+        .unwrap()
+        .exists());
+    assert_eq!(
+        load_showcase_status(&env.conn, &id).unwrap(),
+        ShowcaseStatus::InProgress
+    );
+}
+
+#[test]
+fn phases_move_the_status_along() {
+    assert_eq!(
+        ShowcaseStatus::parse("in progress"),
+        Some(ShowcaseStatus::InProgress)
+    );
+    assert_eq!(ShowcaseStatus::parse("Published"), None);
+    assert_eq!(ShowcaseStatus::Draft.after_phase(1), ShowcaseStatus::Draft);
+    assert_eq!(
+        ShowcaseStatus::Draft.after_phase(2),
+        ShowcaseStatus::InProgress
+    );
+    assert_eq!(
+        ShowcaseStatus::InProgress.after_phase(4),
+        ShowcaseStatus::Completed
+    );
+    assert_eq!(
+        ShowcaseStatus::Completed.after_phase(3),
+        ShowcaseStatus::InProgress
+    );
+}
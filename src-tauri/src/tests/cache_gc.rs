@@ -0,0 +1,85 @@
+use chrono::Utc;
+use std::fs;
+use std::time::Duration;
+
+use super::{message, selected, TestEnv, PNG_BYTES};
+use crate::cache_repair::{
+    find_orphaned_files, remove_orphaned_cache_files, remove_orphaned_files,
+};
+use crate::showcase_manager::{insert_showcase, store_selected_messages};
+
+#[test]
+fn unreferenced_cache_files_are_reported_and_removed() {
+    let mut env = TestEnv::new();
+    let now = Utc::now().timestamp();
+    let kept = env.index(&message(1, 501, "Still indexed", now, &[11]));
+    let dropped = env.index(&message(2, 501, "Deleted later", now, &[21]));
+    env.conn
+        .execute("DELETE FROM messages WHERE message_id = '2'", [])
+        .unwrap();
+    let stray = env.image_dir().join("cached").join("stray.png");
+    fs::write(&stray, b"left over from an interrupted run").unwrap();
+
+    let orphans = find_orphaned_files(&env.conn, &env.image_dir(), Duration::ZERO).unwrap();
+    let paths: Vec<&str> = orphans.iter().map(|f| f.relative_path.as_str()).collect();
+    let expected_dropped = std::path::Path::new(&dropped[0])
+        .to_string_lossy()
+        .into_owned();
+    let expected_stray = std::path::Path::new("cached")
+        .join("stray.png")
+        .to_string_lossy()
+        .into_owned();
+    assert_eq!(
+        paths,
+        vec![expected_dropped.as_str(), expected_stray.as_str()]
+    );
+    assert_eq!(orphans[0].size_bytes, PNG_BYTES.len() as u64);
+
+    // Freshly written files may belong to a message that is about to be inserted.
+    assert!(
+        find_orphaned_files(&env.conn, &env.image_dir(), Duration::from_secs(3600))
+            .unwrap()
+            .is_empty()
+    );
+
+    let (deleted, bytes_freed) = remove_orphaned_files(&env.image_dir(), &orphans);
+    assert_eq!(deleted, 2);
+    assert_eq!(
+        bytes_freed,
+        orphans.iter().map(|f| f.size_bytes).sum::<u64>()
+    );
+    assert!(!stray.exists());
+    assert!(!env.image_dir().join(&dropped[0]).exists());
+    assert!(env.image_dir().join(&kept[0]).exists());
+}
+
+#[test]
+fn the_scheduled_sweep_leaves_fresh_files_alone() {
+    let env = TestEnv::new();
+    let stray = env.image_dir().join("cached").join("stray.png");
+    fs::create_dir_all(stray.parent().unwrap()).unwrap();
+    fs::write(&stray, b"just downloaded").unwrap();
+
+    assert_eq!(
+        remove_orphaned_cache_files(&env.conn, &env.image_dir()).unwrap(),
+        (0, 0)
+    );
+    assert!(stray.exists());
+}
+
+#[test]
+fn files_only_a_showcase_refers_to_are_kept() {
+    let mut env = TestEnv::new();
+    let imported = env.image_dir().join("cached").join("imported.png");
+    fs::create_dir_all(imported.parent().unwrap()).unwrap();
+    fs::write(&imported, PNG_BYTES).unwrap();
+    let id = insert_showcase(&env.conn, "Imported", None).unwrap();
+    store_selected_messages(&mut env.conn, &id, &[selected("9", "cached/imported.png")]).unwrap();
+
+    assert!(
+        find_orphaned_files(&env.conn, &env.image_dir(), Duration::ZERO)
+            .unwrap()
+            .is_empty()
+    );
+    assert!(imported.exists());
+}
@@ -0,0 +1,101 @@
+use chrono::Utc;
+
+use super::{message, TestEnv, DAY_SECS};
+use crate::error::ErrorKind;
+use crate::sqlite_manager::{perform_cleanup, resolve_retention_days, DEFAULT_RETENTION_DAYS};
+
+#[test]
+fn cleanup_removes_old_unused_messages_and_their_files() {
+    let mut env = TestEnv::new();
+    let now = Utc::now().timestamp();
+    let old_files = env.index(&message(
+        1,
+        501,
+        "Old and unused",
+        now - 40 * DAY_SECS,
+        &[11],
+    ));
+    let used_files = env.index(&message(
+        2,
+        501,
+        "Old but featured",
+        now - 40 * DAY_SECS,
+        &[21],
+    ));
+    let recent_files = env.index(&message(3, 501, "Recent", now - DAY_SECS, &[31]));
+    env.conn
+        .execute("UPDATE messages SET is_used = 1 WHERE message_id = '2'", [])
+        .unwrap();
+
+    let stats = perform_cleanup(&env.data_dir, &mut env.conn, 30, false, |_| {}).unwrap();
+
+    assert_eq!(stats.messages_deleted, 1);
+    assert_eq!(stats.files_deleted, 1);
+    assert_eq!(stats.skipped_used_messages, 1);
+    assert_eq!(env.message_ids(), vec!["2", "3"]);
+    assert!(!env.image_dir().join(&old_files[0]).exists());
+    assert!(env.image_dir().join(&used_files[0]).exists());
+    assert!(env.image_dir().join(&recent_files[0]).exists());
+}
+
+#[test]
+fn dry_run_reports_without_deleting() {
+    let mut env = TestEnv::new();
+    let now = Utc::now().timestamp();
+    let old_files = env.index(&message(1, 501, "Ten days old", now - 10 * DAY_SECS, &[11]));
+    env.index(&message(2, 501, "Recent", now - DAY_SECS, &[21]));
+
+    let stats = perform_cleanup(&env.data_dir, &mut env.conn, 7, true, |_| {
+        panic!("dry runs report no progress")
+    })
+    .unwrap();
+
+    assert!(stats.dry_run);
+    assert_eq!(stats.retention_days, 7);
+    assert_eq!(stats.messages_deleted, 1);
+    assert_eq!(stats.files_deleted, 1);
+    let sample: Vec<&str> = stats
+        .sample_messages
+        .iter()
+        .map(|m| m.message_id.as_str())
+        .collect();
+    assert_eq!(sample, vec!["1"]);
+    assert_eq!(env.message_ids(), vec!["1", "2"]);
+    assert!(env.image_dir().join(&old_files[0]).exists());
+}
+
+#[test]
+fn retention_period_comes_from_config_and_reports_progress() {
+    let mut env = TestEnv::new();
+    let now = Utc::now().timestamp();
+    env.index(&message(1, 501, "Ten days old", now - 10 * DAY_SECS, &[11]));
+    env.index(&message(2, 501, "Recent", now - DAY_SECS, &[21]));
+
+    assert_eq!(
+        resolve_retention_days(&env.conn, None).unwrap(),
+        DEFAULT_RETENTION_DAYS
+    );
+    env.conn
+        .execute(
+            "INSERT INTO config (key, value) VALUES ('retention_days', '7')",
+            [],
+        )
+        .unwrap();
+    let retention_days = resolve_retention_days(&env.conn, None).unwrap();
+    assert_eq!(retention_days, 7);
+    assert_eq!(resolve_retention_days(&env.conn, Some(3)).unwrap(), 3);
+    assert_eq!(
+        resolve_retention_days(&env.conn, Some(0)).unwrap_err().kind,
+        ErrorKind::InvalidInput
+    );
+
+    let mut progress = Vec::new();
+    let stats = perform_cleanup(&env.data_dir, &mut env.conn, retention_days, false, |p| {
+        progress.push((p.processed, p.total))
+    })
+    .unwrap();
+
+    assert_eq!(stats.messages_deleted, 1);
+    assert_eq!(progress, vec![(1, 1)]);
+    assert_eq!(env.message_ids(), vec!["2"]);
+}
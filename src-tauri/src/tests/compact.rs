@@ -0,0 +1,50 @@
+use chrono::Utc;
+use rusqlite::Connection;
+
+use super::{message, TestEnv};
+use crate::discord::insert_indexed_messages;
+use crate::migrations;
+use crate::sqlite_manager::compact_database_file;
+
+#[test]
+fn compaction_shrinks_the_file_and_keeps_search_working() {
+    let env = TestEnv::new();
+    let db_path = env.data_dir.join("compact.db");
+    let mut conn = Connection::open(&db_path).unwrap();
+    conn.query_row("PRAGMA journal_mode=WAL;", [], |_| Ok(()))
+        .unwrap();
+    migrations::migrate(&mut conn).unwrap();
+
+    let now = Utc::now().timestamp();
+    let padding = "lorem ipsum ".repeat(200);
+    let batch: Vec<_> = (1..=300)
+        .map(|id| {
+            let content = format!("message {} {}", id, padding);
+            (message(id, 501, &content, now, &[]), Vec::new())
+        })
+        .collect();
+    insert_indexed_messages(&mut conn, &batch, false).unwrap();
+    conn.execute(
+        "UPDATE messages SET message_content = 'kept harbour' WHERE message_id = '300'",
+        [],
+    )
+    .unwrap();
+    conn.execute("DELETE FROM messages WHERE message_id != '300'", [])
+        .unwrap();
+
+    let mut steps = Vec::new();
+    let result =
+        compact_database_file(&conn, &db_path, |progress| steps.push(progress.step)).unwrap();
+
+    assert_eq!(steps, vec![1, 2, 3, 4]);
+    assert!(result.size_after_bytes < result.size_before_bytes + result.wal_size_before_bytes);
+    assert_eq!(result.wal_size_after_bytes, 0);
+    let hit: String = conn
+        .query_row(
+            "SELECT m.message_id FROM messages_fts JOIN messages m ON m.rowid = messages_fts.rowid WHERE messages_fts MATCH 'harbour'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(hit, "300");
+}
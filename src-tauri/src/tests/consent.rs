@@ -0,0 +1,101 @@
+use chrono::Utc;
+
+use super::{message, selected, TestEnv};
+use crate::author_manager::{load_consent, set_consent, uncredited_authors};
+use crate::error::ErrorKind;
+use crate::models::{ConsentStatus, SelectedMessage};
+use crate::showcase_manager::{insert_showcase, store_selected_messages};
+
+fn by(author_id: &str, author_name: &str, mut message: SelectedMessage) -> SelectedMessage {
+    message.author_id = author_id.to_string();
+    message.author_name = author_name.to_string();
+    message
+}
+
+#[test]
+fn consent_survives_selection_changes() {
+    let mut env = TestEnv::new();
+    let now = Utc::now().timestamp();
+    let first = env.index(&message(1, 501, "Sunset", now, &[11]));
+    let second = env.index(&message(2, 502, "Harbour", now, &[21]));
+    let id = insert_showcase(&env.conn, "Weekly", None).unwrap();
+    let selection = vec![
+        selected("1", &first[0]),
+        by("502", "artist502", selected("2", &second[0])),
+    ];
+    store_selected_messages(&mut env.conn, &id, &selection).unwrap();
+
+    let consent = load_consent(&env.conn, &id).unwrap();
+    assert_eq!(consent.len(), 2);
+    assert!(consent.iter().all(|c| c.status == ConsentStatus::Pending));
+    assert_eq!(consent[0].credit_text, "artist501");
+    assert!(!consent[0].custom_credit);
+    assert_eq!(consent[0].updated_at, None);
+
+    set_consent(
+        &env.conn,
+        &id,
+        "1",
+        ConsentStatus::Granted,
+        Some(" @sunset_art "),
+    )
+    .unwrap();
+    set_consent(&env.conn, &id, "2", ConsentStatus::Declined, Some("")).unwrap();
+    store_selected_messages(&mut env.conn, &id, &selection).unwrap();
+
+    let consent = load_consent(&env.conn, &id).unwrap();
+    assert_eq!(consent[0].status, ConsentStatus::Granted);
+    assert_eq!(consent[0].credit_text, "@sunset_art");
+    assert!(consent[0].custom_credit);
+    assert!(consent[0].updated_at.is_some());
+    assert_eq!(consent[1].status, ConsentStatus::Declined);
+    assert_eq!(consent[1].credit_text, "artist502");
+    assert!(!consent[1].custom_credit);
+}
+
+#[test]
+fn only_selected_messages_take_consent() {
+    let mut env = TestEnv::new();
+    let now = Utc::now().timestamp();
+    let first = env.index(&message(1, 501, "Sunset", now, &[11]));
+    let id = insert_showcase(&env.conn, "Weekly", None).unwrap();
+    store_selected_messages(&mut env.conn, &id, &[selected("1", &first[0])]).unwrap();
+
+    let err = set_consent(&env.conn, &id, "2", ConsentStatus::Granted, None).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::NotFound);
+    let err = set_consent(&env.conn, "missing", "1", ConsentStatus::Granted, None).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::NotFound);
+}
+
+#[test]
+fn uncredited_authors_group_their_open_messages() {
+    let mut env = TestEnv::new();
+    let now = Utc::now().timestamp();
+    let first = env.index(&message(1, 501, "Sunset", now, &[11]));
+    let second = env.index(&message(2, 501, "Dusk", now, &[21]));
+    let third = env.index(&message(3, 502, "Harbour", now, &[31]));
+    let fourth = env.index(&message(4, 503, "Pier", now, &[41]));
+    let id = insert_showcase(&env.conn, "Weekly", None).unwrap();
+    store_selected_messages(
+        &mut env.conn,
+        &id,
+        &[
+            selected("1", &first[0]),
+            selected("2", &second[0]),
+            by("502", "Bram", selected("3", &third[0])),
+            by("503", "Cleo", selected("4", &fourth[0])),
+        ],
+    )
+    .unwrap();
+
+    set_consent(&env.conn, &id, "1", ConsentStatus::Declined, None).unwrap();
+    set_consent(&env.conn, &id, "4", ConsentStatus::Granted, None).unwrap();
+
+    let authors = uncredited_authors(&env.conn, &id).unwrap();
+    assert_eq!(authors.len(), 2);
+    assert_eq!(authors[0].author_name, "artist501");
+    assert_eq!(authors[0].status, ConsentStatus::Declined);
+    assert_eq!(authors[0].message_ids, vec!["1", "2"]);
+    assert_eq!(authors[1].author_name, "Bram");
+    assert_eq!(authors[1].status, ConsentStatus::Pending);
+}
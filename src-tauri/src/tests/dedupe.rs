@@ -0,0 +1,81 @@
+use chrono::Utc;
+use std::fs;
+
+use super::{message, TestEnv, PNG_BYTES};
+use crate::cache_dedupe::{content_hash, dedupe_cache, link_cached_duplicate};
+
+#[test]
+fn identical_cached_files_are_linked_and_hashed() {
+    let mut env = TestEnv::new();
+    let now = Utc::now().timestamp();
+    let first = env.index(&message(1, 501, "Posted here", now, &[11]));
+    let repost = env.index(&message(2, 501, "And reposted there", now, &[21]));
+    let other = env.image_dir().join("cached").join("other.png");
+    fs::write(&other, b"a different image").unwrap();
+
+    let report = dedupe_cache(&mut env.conn, &env.image_dir()).unwrap();
+    assert_eq!(report.scanned, 3);
+    assert_eq!(report.duplicates, 1);
+    assert_eq!(report.bytes_reclaimed, PNG_BYTES.len() as u64);
+    assert!(report.failed.is_empty());
+    assert_eq!(
+        fs::read(env.image_dir().join(&repost[0])).unwrap(),
+        PNG_BYTES
+    );
+    let same_hash: i64 = env
+        .conn
+        .query_row(
+            "SELECT COUNT(*) FROM cached_files WHERE content_hash = ?1",
+            [content_hash(PNG_BYTES)],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(same_hash, 2);
+
+    // Deleting one name keeps the content reachable through the other.
+    fs::remove_file(env.image_dir().join(&first[0])).unwrap();
+    assert_eq!(
+        fs::read(env.image_dir().join(&repost[0])).unwrap(),
+        PNG_BYTES
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn running_dedupe_again_reclaims_nothing() {
+    let mut env = TestEnv::new();
+    let now = Utc::now().timestamp();
+    env.index(&message(1, 501, "Posted here", now, &[11]));
+    env.index(&message(2, 501, "And reposted there", now, &[21]));
+
+    assert_eq!(
+        dedupe_cache(&mut env.conn, &env.image_dir())
+            .unwrap()
+            .duplicates,
+        1
+    );
+    let again = dedupe_cache(&mut env.conn, &env.image_dir()).unwrap();
+    assert_eq!(again.duplicates, 0);
+    assert_eq!(again.bytes_reclaimed, 0);
+}
+
+#[test]
+fn missing_file_is_restored_from_a_recorded_duplicate() {
+    let mut env = TestEnv::new();
+    let now = Utc::now().timestamp();
+    let first = env.index(&message(1, 501, "Posted here", now, &[11]));
+    let repost = env.index(&message(2, 501, "And reposted there", now, &[21]));
+    dedupe_cache(&mut env.conn, &env.image_dir()).unwrap();
+
+    fs::remove_file(env.image_dir().join(&repost[0])).unwrap();
+    assert!(link_cached_duplicate(&env.conn, &env.image_dir(), &repost[0]).unwrap());
+    assert_eq!(
+        fs::read(env.image_dir().join(&repost[0])).unwrap(),
+        PNG_BYTES
+    );
+
+    // Nothing to restore from once every copy is gone.
+    fs::remove_file(env.image_dir().join(&first[0])).unwrap();
+    fs::remove_file(env.image_dir().join(&repost[0])).unwrap();
+    assert!(!link_cached_duplicate(&env.conn, &env.image_dir(), &first[0]).unwrap());
+}
@@ -0,0 +1,167 @@
+use chrono::Utc;
+use std::fs;
+
+use super::{message, selected, TestEnv, DAY_SECS};
+use crate::error::ErrorKind;
+use crate::models::DateRange;
+use crate::showcase_manager::{
+    insert_showcase, load_showcase, purge_expired_trash, store_selected_messages,
+};
+use crate::sqlite_manager::{
+    clear_cache_files, delete_matching_messages, record_channel_index_run, reset_config,
+    retrieve_config,
+};
+
+#[test]
+fn messages_are_deleted_by_channel_and_date_range() {
+    let mut env = TestEnv::new();
+    let now = Utc::now().timestamp();
+    let old = env.index(&message(1, 501, "Old", now - 10 * DAY_SECS, &[11]));
+    let recent = env.index(&message(2, 501, "Recent", now - DAY_SECS, &[21]));
+    env.index(&message(3, 501, "Featured", now - 10 * DAY_SECS, &[31]));
+    env.index(&message(
+        4,
+        501,
+        "Other channel",
+        now - 10 * DAY_SECS,
+        &[41],
+    ));
+    env.conn
+        .execute_batch(
+            "UPDATE messages SET is_used = 1 WHERE message_id = '3';
+             UPDATE messages SET channel_id = '200' WHERE message_id = '4';",
+        )
+        .unwrap();
+    let image_dir = env.image_dir();
+
+    let nothing = DateRange::default();
+    let err =
+        delete_matching_messages(&mut env.conn, &image_dir, &[], &nothing, |_| {}).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::InvalidInput);
+
+    let older_than_a_week = DateRange {
+        from: None,
+        to: Some(now - 7 * DAY_SECS),
+    };
+    let result = delete_matching_messages(
+        &mut env.conn,
+        &image_dir,
+        &["100".to_string()],
+        &older_than_a_week,
+        |_| {},
+    )
+    .unwrap();
+    assert_eq!(result.messages_deleted, 1);
+    assert_eq!(result.files_deleted, 1);
+    assert_eq!(result.skipped_used_messages, 1);
+    assert_eq!(env.message_ids(), vec!["2", "3", "4"]);
+    assert!(!image_dir.join(&old[0]).exists());
+    assert!(image_dir.join(&recent[0]).exists());
+}
+
+#[test]
+fn deleting_a_whole_channel_forgets_its_indexing_runs() {
+    let mut env = TestEnv::new();
+    env.index(&message(1, 501, "Piece", Utc::now().timestamp(), &[11]));
+    record_channel_index_run(&env.conn, "100", "completed", 1, None, None).unwrap();
+    let image_dir = env.image_dir();
+
+    delete_matching_messages(
+        &mut env.conn,
+        &image_dir,
+        &["100".to_string()],
+        &DateRange::default(),
+        |_| {},
+    )
+    .unwrap();
+
+    assert!(env.message_ids().is_empty());
+    let runs: i64 = env
+        .conn
+        .query_row("SELECT COUNT(*) FROM channel_index_runs", [], |row| {
+            row.get(0)
+        })
+        .unwrap();
+    assert_eq!(runs, 0);
+}
+
+#[test]
+fn clearing_the_cache_keeps_messages_and_showcase_images() {
+    let mut env = TestEnv::new();
+    let files = env.index(&message(1, 501, "Piece", Utc::now().timestamp(), &[11]));
+    let image_dir = env.image_dir();
+    let slide = image_dir.join("some-showcase").join("slide.png");
+    fs::create_dir_all(slide.parent().unwrap()).unwrap();
+    fs::write(&slide, b"slide").unwrap();
+
+    let result = clear_cache_files(&mut env.conn, &image_dir).unwrap();
+
+    assert_eq!(result.files_deleted, 1);
+    assert!(!image_dir.join(&files[0]).exists());
+    assert!(slide.exists());
+    assert_eq!(env.message_ids(), vec!["1"]);
+}
+
+#[test]
+fn configuration_reset_is_recorded_in_history() {
+    let mut env = TestEnv::new();
+    env.conn
+        .execute_batch(
+            "INSERT INTO config (key, value) VALUES ('is_setup_complete', 'true');
+             INSERT INTO config (key, value) VALUES ('retention_days', '7');",
+        )
+        .unwrap();
+
+    assert_eq!(reset_config(&mut env.conn).unwrap(), 2);
+
+    let config = retrieve_config(&env.conn).unwrap();
+    assert!(!config.is_setup_complete);
+    assert_eq!(config.retention_days, None);
+    let history: i64 = env
+        .conn
+        .query_row("SELECT COUNT(*) FROM config_history", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(history, 2);
+}
+
+#[test]
+fn trashed_showcases_are_purged_after_the_retention_window() {
+    let mut env = TestEnv::new();
+    let now = Utc::now().timestamp();
+    let picked = env.index(&message(1, 501, "Sunset", now, &[11]));
+    let expired = insert_showcase(&env.conn, "Old", None).unwrap();
+    let recent = insert_showcase(&env.conn, "Recent", None).unwrap();
+    store_selected_messages(&mut env.conn, &expired, &[selected("1", &picked[0])]).unwrap();
+    let slides = env.image_dir().join(&expired);
+    fs::create_dir_all(&slides).unwrap();
+    fs::write(slides.join(format!("{}_1.png", expired)), b"render").unwrap();
+    env.conn
+        .execute(
+            "UPDATE showcases SET deleted_at = CASE id WHEN ?1 THEN ?2 ELSE ?3 END",
+            rusqlite::params![expired, now - 31 * DAY_SECS, now - DAY_SECS],
+        )
+        .unwrap();
+
+    // A trashed showcase still holds on to its messages until it is purged.
+    assert!(env.is_used("1"));
+    let purged = purge_expired_trash(&env.data_dir, &mut env.conn, now).unwrap();
+    assert_eq!(purged, vec![expired.clone()]);
+    assert!(load_showcase(&env.conn, &expired).is_err());
+    assert!(!slides.exists());
+    assert!(!env.is_used("1"));
+    assert_eq!(
+        load_showcase(&env.conn, &recent).unwrap().deleted_at,
+        Some(now - DAY_SECS)
+    );
+
+    env.conn
+        .execute(
+            "INSERT INTO config (key, value) VALUES ('trash_retention_days', '0')",
+            [],
+        )
+        .unwrap();
+    assert_eq!(
+        purge_expired_trash(&env.data_dir, &mut env.conn, now).unwrap(),
+        vec![recent]
+    );
+}
@@ -0,0 +1,51 @@
+use super::TestEnv;
+use crate::error::ErrorKind;
+use crate::showcase_drafts::{clear_completed_drafts, load_draft, write_draft, MAX_DRAFT_BYTES};
+use crate::showcase_manager::insert_showcase;
+
+#[test]
+fn drafts_are_kept_until_their_phase_is_completed() {
+    let env = TestEnv::new();
+    let id = insert_showcase(&env.conn, "Weekly", None).unwrap();
+    env.conn
+        .execute("UPDATE showcases SET phase = 2 WHERE id = ?1", [&id])
+        .unwrap();
+
+    write_draft(&env.conn, &id, 2, r#"{"selected":["1"]}"#).unwrap();
+    write_draft(&env.conn, &id, 2, r#"{"selected":["1","2"]}"#).unwrap();
+    write_draft(&env.conn, &id, 3, r#"{"order":[]}"#).unwrap();
+
+    // Without a phase the current one is used.
+    let draft = load_draft(&env.conn, &id, None).unwrap().unwrap();
+    assert_eq!(draft.phase, 2);
+    assert_eq!(draft.draft_json, r#"{"selected":["1","2"]}"#);
+
+    assert_eq!(clear_completed_drafts(&env.conn, &id, 3).unwrap(), 1);
+    assert!(load_draft(&env.conn, &id, Some(2)).unwrap().is_none());
+    assert!(load_draft(&env.conn, &id, Some(3)).unwrap().is_some());
+}
+
+#[test]
+fn invalid_drafts_are_rejected() {
+    let env = TestEnv::new();
+    let id = insert_showcase(&env.conn, "Weekly", None).unwrap();
+
+    let kind = |json: &str, phase: i32| write_draft(&env.conn, &id, phase, json).unwrap_err().kind;
+    assert_eq!(kind("{}", 1), ErrorKind::InvalidInput);
+    assert_eq!(kind("{}", 4), ErrorKind::InvalidInput);
+    assert_eq!(kind("{not json", 2), ErrorKind::InvalidInput);
+    let oversized = format!("\"{}\"", "x".repeat(MAX_DRAFT_BYTES));
+    assert_eq!(kind(&oversized, 2), ErrorKind::InvalidInput);
+
+    assert_eq!(
+        write_draft(&env.conn, "missing", 2, "{}").unwrap_err().kind,
+        ErrorKind::NotFound
+    );
+    env.conn
+        .execute(
+            "UPDATE showcases SET status = 'Archived' WHERE id = ?1",
+            [&id],
+        )
+        .unwrap();
+    assert_eq!(kind("{}", 2), ErrorKind::Conflict);
+}
@@ -0,0 +1,58 @@
+use chrono::Utc;
+
+use super::{message, MemorySecrets, TestEnv};
+use crate::db_encryption::{
+    export_encrypted, is_new_database, is_plaintext_database, load_or_create_key, open_read_only,
+    DB_KEY_NAME,
+};
+use crate::migrations;
+use crate::providers::SecretStore;
+use crate::sqlite_manager::open_connection;
+
+#[test]
+fn encrypted_copy_opens_only_with_the_stored_key() {
+    let mut env = TestEnv::new();
+    env.index(&message(1, 501, "Sunset", Utc::now().timestamp(), &[11]));
+    let secrets = MemorySecrets::default();
+    let key = load_or_create_key(&secrets).unwrap();
+    assert_eq!(load_or_create_key(&secrets).unwrap(), key);
+
+    let target = env.data_dir.join("encrypted.db");
+    export_encrypted(&env.conn, &target, &key).unwrap();
+    assert!(!is_plaintext_database(&target));
+
+    let conn = open_read_only(&target, &secrets).unwrap();
+    let count: i64 = conn
+        .query_row("SELECT COUNT(*) FROM messages", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(count, 1);
+    drop(conn);
+
+    secrets.delete_secret(DB_KEY_NAME).unwrap();
+    assert!(open_read_only(&target, &secrets).is_err());
+    load_or_create_key(&secrets).unwrap();
+    assert!(open_read_only(&target, &secrets).is_err());
+}
+
+#[test]
+fn first_launch_opens_without_a_key() {
+    let env = TestEnv::new();
+    let secrets = MemorySecrets::default();
+    let path = env.data_dir.join("showcase_app_data.db");
+    assert!(is_new_database(&path));
+
+    let mut conn = open_connection(&path, &secrets).unwrap();
+    migrations::migrate(&mut conn).unwrap();
+    drop(conn);
+    assert!(!is_new_database(&path));
+    assert!(is_plaintext_database(&path));
+    open_connection(&path, &secrets).unwrap();
+
+    // An earlier launch that stopped right after creating the file left it empty.
+    let empty = env.data_dir.join("empty.db");
+    std::fs::write(&empty, b"").unwrap();
+    assert!(is_new_database(&empty));
+    let mut conn = open_connection(&empty, &secrets).unwrap();
+    migrations::migrate(&mut conn).unwrap();
+    assert!(secrets.get_secret(DB_KEY_NAME).unwrap().is_none());
+}
@@ -0,0 +1,37 @@
+use super::TestEnv;
+use crate::error::{AppError, ErrorKind, ResultExt};
+use crate::showcase_manager::load_showcase;
+
+#[test]
+fn ids_and_titles_in_messages_do_not_pick_the_kind() {
+    let env = TestEnv::new();
+    let missing = load_showcase(&env.conn, "4010401429").unwrap_err();
+    assert_eq!(missing.kind, ErrorKind::NotFound);
+
+    // Untyped strings only go by the wording the OS and SQLite use, outside quoted values.
+    let untyped = AppError::from("Failed to export 'Unauthorized 401 timed out' (429)".to_string());
+    assert_eq!(untyped.kind, ErrorKind::Internal);
+    let disk = AppError::from("Failed to write slide: No space left on device (os error 28)");
+    assert_eq!(disk.kind, ErrorKind::DiskFull);
+}
+
+#[test]
+fn context_keeps_the_kind_of_the_source_error() {
+    let env = TestEnv::new();
+    let error = env
+        .conn
+        .execute("UPDATE no_such_table SET id = 401", [])
+        .context("Failed to update showcase 404")
+        .unwrap_err();
+    assert_eq!(error.kind, ErrorKind::Database);
+    assert_eq!(
+        error.context.as_deref(),
+        Some("Failed to update showcase 404")
+    );
+
+    let parsed = "abc"
+        .parse::<u64>()
+        .context_as(ErrorKind::InvalidInput, "Invalid channel ID")
+        .unwrap_err();
+    assert_eq!(parsed.kind, ErrorKind::InvalidInput);
+}
@@ -0,0 +1,98 @@
+use rusqlite::Connection;
+use sha2::{Digest, Sha256};
+use std::fs;
+
+use super::{TestEnv, PNG_BYTES};
+use crate::migrations;
+use crate::models::ExportManifest;
+use crate::showcase_manager::{
+    get_showcase_image_dir, get_showcase_presentation_dir, insert_showcase, load_showcase,
+    register_pptx_export,
+};
+use crate::sqlite_manager::{DbConnection, DbPool};
+
+const DECK_BYTES: &[u8] = b"PK\x03\x04 not really a deck";
+
+// Writes a deck and one slide image where the export steps before registration leave them.
+fn write_export_files(env: &TestEnv, id: &str) {
+    let image_dir = get_showcase_image_dir(&env.data_dir, id).unwrap();
+    fs::create_dir_all(&image_dir).unwrap();
+    fs::write(image_dir.join(format!("{}_1.png", id)), PNG_BYTES).unwrap();
+
+    let presentation_dir = get_showcase_presentation_dir(&env.data_dir, id).unwrap();
+    fs::create_dir_all(&presentation_dir).unwrap();
+    fs::write(
+        presentation_dir.join(format!("showcase_{}.pptx", id)),
+        DECK_BYTES,
+    )
+    .unwrap();
+}
+
+// Publishing visits the database through the pool, so these tests use a file-backed one.
+fn export_db(env: &TestEnv) -> DbConnection {
+    let path = env.data_dir.join("export.db");
+    let mut first = Connection::open(&path).unwrap();
+    migrations::migrate(&mut first).unwrap();
+    DbConnection(DbPool::new(first, move || {
+        Connection::open(&path).map_err(|e| e.to_string())
+    }))
+}
+
+#[tokio::test]
+async fn export_records_the_deck_and_writes_a_manifest() {
+    let env = TestEnv::new();
+    let db = export_db(&env);
+    let id = insert_showcase(&db.0.lock().unwrap(), "Weekly Picks", None).unwrap();
+    write_export_files(&env, &id);
+
+    let relative_path = register_pptx_export(&env.data_dir, &db, &id, None)
+        .await
+        .unwrap();
+    assert_eq!(
+        relative_path,
+        format!("presentations/{}/showcase_{}.pptx", id, id)
+    );
+
+    let showcase = load_showcase(&db.0.lock().unwrap(), &id).unwrap();
+    assert_eq!(showcase.phase, 4);
+    assert_eq!(showcase.pptx_path.as_deref(), Some(relative_path.as_str()));
+    assert_eq!(showcase.exports.len(), 1);
+    let artifact = &showcase.exports[0];
+    assert_eq!(artifact.format, "pptx");
+    assert_eq!(
+        artifact.sha256.as_deref(),
+        Some(format!("{:x}", Sha256::digest(DECK_BYTES)).as_str())
+    );
+
+    let manifest_json = fs::read_to_string(format!("{}.manifest.json", artifact.path)).unwrap();
+    let manifest: ExportManifest = serde_json::from_str(&manifest_json).unwrap();
+    assert_eq!(manifest.showcase_id, id);
+    assert_eq!(manifest.files.len(), 1);
+    assert_eq!(
+        manifest.files[0].sha256,
+        format!("{:x}", Sha256::digest(PNG_BYTES))
+    );
+}
+
+#[tokio::test]
+async fn export_is_copied_to_the_output_directory() {
+    let env = TestEnv::new();
+    let db = export_db(&env);
+    let id = insert_showcase(&db.0.lock().unwrap(), "Weekly Picks", None).unwrap();
+    write_export_files(&env, &id);
+    let output_dir = env.data_dir.join("exports");
+
+    register_pptx_export(
+        &env.data_dir,
+        &db,
+        &id,
+        Some(output_dir.to_string_lossy().to_string()),
+    )
+    .await
+    .unwrap();
+
+    let expected = output_dir.join(format!("Weekly_Picks_{}.pptx", &id[..8]));
+    assert_eq!(fs::read(&expected).unwrap(), DECK_BYTES);
+    let showcase = load_showcase(&db.0.lock().unwrap(), &id).unwrap();
+    assert_eq!(showcase.exports[0].path, expected.to_string_lossy());
+}
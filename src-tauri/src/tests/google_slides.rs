@@ -0,0 +1,32 @@
+use super::TestEnv;
+use crate::error::ErrorKind;
+use crate::google_slides::{google_slides_link, save_google_slides_url, DriveFile};
+use crate::showcase_manager::{insert_showcase, load_showcase};
+
+#[test]
+fn slides_links_are_kept_on_the_showcase() {
+    let env = TestEnv::new();
+    let id = insert_showcase(&env.conn, "Weekly", None).unwrap();
+    assert_eq!(
+        load_showcase(&env.conn, &id).unwrap().google_slides_url,
+        None
+    );
+
+    let converted: DriveFile = serde_json::from_str(r#"{"id":"1AbC"}"#).unwrap();
+    let url = google_slides_link(&converted);
+    assert_eq!(url, "https://docs.google.com/presentation/d/1AbC/edit");
+    save_google_slides_url(&env.conn, &id, Some(&url)).unwrap();
+    assert_eq!(
+        load_showcase(&env.conn, &id).unwrap().google_slides_url,
+        Some(url)
+    );
+
+    let linked: DriveFile = serde_json::from_str(
+        r#"{"id":"1AbC","webViewLink":"https://docs.google.com/presentation/d/1AbC/edit?usp=drivesdk"}"#,
+    )
+    .unwrap();
+    assert!(google_slides_link(&linked).ends_with("?usp=drivesdk"));
+
+    let err = save_google_slides_url(&env.conn, "missing", None).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::NotFound);
+}
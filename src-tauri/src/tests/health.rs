@@ -0,0 +1,85 @@
+use chrono::Utc;
+use std::fs;
+
+use super::{message, TestEnv};
+use crate::db_health::check_health;
+use crate::showcase_manager::{insert_showcase, load_showcase};
+
+#[test]
+fn healthy_database_reports_nothing() {
+    let mut env = TestEnv::new();
+    env.index(&message(1, 501, "Fine", Utc::now().timestamp(), &[11]));
+    insert_showcase(&env.conn, "Weekly", None).unwrap();
+
+    let image_dir = env.image_dir();
+    let report = check_health(&mut env.conn, &image_dir, false).unwrap();
+
+    assert!(report.integrity_errors.is_empty());
+    assert!(report.foreign_key_violations.is_empty());
+    assert!(report.corrupt_rows.is_empty());
+    assert!(report.missing_files.is_empty());
+}
+
+#[test]
+fn corrupt_rows_are_reported_and_quarantined() {
+    let mut env = TestEnv::new();
+    let now = Utc::now().timestamp();
+    let files = env.index(&message(1, 501, "Lost its file", now, &[11]));
+    env.index(&message(2, 501, "Broken reactions", now, &[21]));
+    fs::remove_file(env.image_dir().join(&files[0])).unwrap();
+    let good = insert_showcase(&env.conn, "Good", None).unwrap();
+    let bad = insert_showcase(&env.conn, "Bad", None).unwrap();
+    env.conn
+        .execute(
+            "INSERT INTO showcase_images (showcase_id, slide_key, position, message_id, sender, avatar, message, overlay_json)
+             VALUES (?1, '1', 0, '1', 'artist501', '', 'Lost its file', '{\"position\": 1')",
+            [&bad],
+        )
+        .unwrap();
+    env.conn
+        .execute(
+            "UPDATE messages SET reactions_json = 'not json' WHERE message_id = '2'",
+            [],
+        )
+        .unwrap();
+
+    let image_dir = env.image_dir();
+    let report = check_health(&mut env.conn, &image_dir, false).unwrap();
+    let corrupt: Vec<(&str, &str, &str)> = report
+        .corrupt_rows
+        .iter()
+        .map(|r| (r.table.as_str(), r.row_key.as_str(), r.column.as_str()))
+        .collect();
+    assert_eq!(
+        corrupt,
+        vec![
+            ("showcase_images", "1", "overlay_json"),
+            ("messages", "2", "reactions_json"),
+        ]
+    );
+    assert_eq!(report.missing_files.len(), 1);
+    assert_eq!(report.missing_files[0].message_id, "1");
+    assert_eq!(report.quarantined, 0);
+    assert!(load_showcase(&env.conn, &bad).is_err());
+
+    let report = check_health(&mut env.conn, &image_dir, true).unwrap();
+    assert_eq!(report.quarantined, 2);
+    assert!(load_showcase(&env.conn, &good).is_ok());
+    assert!(load_showcase(&env.conn, &bad).unwrap().images.is_none());
+    assert_eq!(env.message_ids(), vec!["1"]);
+
+    let (table, row_json): (String, String) = env
+        .conn
+        .query_row(
+            "SELECT table_name, row_json FROM quarantined_rows WHERE row_key = '1' AND table_name != 'messages'",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .unwrap();
+    assert_eq!(table, "showcase_images");
+    let row: serde_json::Value = serde_json::from_str(&row_json).unwrap();
+    assert_eq!(row["showcase_id"], bad.as_str());
+
+    let report = check_health(&mut env.conn, &image_dir, true).unwrap();
+    assert!(report.corrupt_rows.is_empty());
+}
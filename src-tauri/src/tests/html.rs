@@ -0,0 +1,84 @@
+use image::{DynamicImage, ImageFormat, Rgba, RgbaImage};
+use std::fs;
+use std::path::PathBuf;
+
+use super::TestEnv;
+use crate::html_export::{write_html_export, GalleryText};
+use crate::slide_render::SlidePlan;
+
+fn plan(slide_key: &str, original: PathBuf) -> SlidePlan {
+    SlidePlan {
+        slide_key: slide_key.to_string(),
+        render: None,
+        original: Some(original),
+        overlay: None,
+        avatar: None,
+    }
+}
+
+fn text(credit: &str, caption: &str) -> GalleryText {
+    GalleryText {
+        title: None,
+        credit: credit.to_string(),
+        caption: caption.to_string(),
+    }
+}
+
+fn slides(env: &TestEnv) -> Vec<(SlidePlan, GalleryText)> {
+    let original = env.image_dir().join("cached").join("1_11.png");
+    fs::create_dir_all(original.parent().unwrap()).unwrap();
+    DynamicImage::ImageRgba8(RgbaImage::from_pixel(64, 64, Rgba([0, 128, 255, 255])))
+        .save_with_format(&original, ImageFormat::Png)
+        .unwrap();
+    vec![
+        (plan("1", original.clone()), text("artist501", "Sunset <3")),
+        (
+            plan("2", env.image_dir().join("cached").join("missing.png")),
+            text("artist502", "Lost"),
+        ),
+        (plan("3", original), text("artist501", "")),
+    ]
+}
+
+#[test]
+fn gallery_links_rendered_slides_from_an_assets_folder() {
+    let env = TestEnv::new();
+    let folder = env.data_dir.join("presentations").join("s1").join("web");
+
+    let (index, count, skipped) = write_html_export(
+        &folder,
+        "Weekly & <Friends>",
+        Some("Best of the week"),
+        slides(&env),
+        false,
+        (320, 180),
+    )
+    .unwrap();
+    assert_eq!(index, folder.join("index.html"));
+    assert_eq!((count, skipped), (2, vec!["2".to_string()]));
+    assert!(folder.join("assets").join("slide_001.jpg").is_file());
+    assert!(folder.join("assets").join("slide_002.jpg").is_file());
+
+    let html = fs::read_to_string(&index).unwrap();
+    assert!(html.contains("<title>Weekly &amp; &lt;Friends&gt;</title>"));
+    assert!(html.contains("src=\"assets/slide_002.jpg\""));
+    assert!(html.contains("Sunset &lt;3"));
+    assert!(!html.contains("Lost"));
+    // Credited once in the footer even with two slides.
+    assert_eq!(html.matches("<li>artist501</li>").count(), 1);
+}
+
+#[test]
+fn inlined_gallery_is_a_single_file() {
+    let env = TestEnv::new();
+    let folder = env.data_dir.join("web");
+    fs::create_dir_all(folder.join("stale")).unwrap();
+
+    let (index, count, _) =
+        write_html_export(&folder, "Weekly", None, slides(&env), true, (320, 180)).unwrap();
+    assert_eq!(count, 2);
+    let html = fs::read_to_string(&index).unwrap();
+    assert_eq!(html.matches("src=\"data:image/jpeg;base64,").count(), 2);
+    let entries: Vec<_> = fs::read_dir(&folder).unwrap().collect();
+    assert_eq!(entries.len(), 1);
+}
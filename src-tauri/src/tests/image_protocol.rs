@@ -0,0 +1,145 @@
+use chrono::Utc;
+use tauri::http::{header, Request, StatusCode};
+
+use super::{message, TestEnv, PNG_BYTES};
+use crate::error::AppError;
+use crate::image_protocol::{parse_range, resolve_image_path, serve_image};
+use crate::models::ThumbnailSettings;
+use crate::sqlite_manager::load_images_batch;
+
+fn request(path: &str, headers: &[(header::HeaderName, &str)]) -> Request<Vec<u8>> {
+    let mut builder = Request::builder().uri(format!("showcase-image://localhost/{}", path));
+    for (name, value) in headers {
+        builder = builder.header(name, *value);
+    }
+    builder.body(Vec::new()).unwrap()
+}
+
+fn no_thumbnails() -> Result<(ThumbnailSettings, bool), AppError> {
+    Err(AppError::conflict("The database is not loaded."))
+}
+
+#[test]
+fn only_files_inside_the_image_directory_resolve() {
+    let mut env = TestEnv::new();
+    let cached = env.index(&message(1, 501, "Piece", Utc::now().timestamp(), &[11]));
+    std::fs::write(env.data_dir.join("secret.txt"), b"outside").unwrap();
+
+    let (relative_path, _) = resolve_image_path(
+        &env.image_dir(),
+        &format!("/{}", cached[0].replace('/', "%2F")),
+    )
+    .unwrap();
+    assert_eq!(relative_path, cached[0]);
+    assert!(resolve_image_path(&env.image_dir(), "/..%2Fsecret.txt").is_none());
+    assert!(resolve_image_path(&env.image_dir(), "/cached/..%2F..%2Fsecret.txt").is_none());
+    assert!(resolve_image_path(&env.image_dir(), "/cached%2Fmissing.png").is_none());
+    assert!(resolve_image_path(&env.image_dir(), "/cached").is_none());
+}
+
+#[test]
+fn ranges_are_parsed_against_the_file_length() {
+    assert_eq!(parse_range("bytes=0-3", 10), Some((0, 3)));
+    assert_eq!(parse_range("bytes=4-", 10), Some((4, 9)));
+    assert_eq!(parse_range("bytes=-3", 10), Some((7, 9)));
+    assert_eq!(parse_range("bytes=5-100", 10), Some((5, 9)));
+    assert_eq!(parse_range("bytes=10-", 10), None);
+    assert_eq!(parse_range("bytes=0-1,4-5", 10), None);
+    assert_eq!(parse_range("items=0-1", 10), None);
+}
+
+#[test]
+fn originals_are_served_with_ranges_and_revalidation() {
+    let mut env = TestEnv::new();
+    let cached = env.index(&message(1, 501, "Piece", Utc::now().timestamp(), &[11]));
+
+    let full = serve_image(&env.image_dir(), &request(&cached[0], &[]), no_thumbnails);
+    assert_eq!(full.status(), StatusCode::OK);
+    assert_eq!(full.body(), PNG_BYTES);
+    assert_eq!(full.headers()[header::CONTENT_TYPE], "image/png");
+    let etag = full.headers()[header::ETAG].to_str().unwrap().to_string();
+
+    let partial = serve_image(
+        &env.image_dir(),
+        &request(&cached[0], &[(header::RANGE, "bytes=0-3")]),
+        no_thumbnails,
+    );
+    assert_eq!(partial.status(), StatusCode::PARTIAL_CONTENT);
+    assert_eq!(partial.body(), &PNG_BYTES[..4]);
+    assert_eq!(
+        partial.headers()[header::CONTENT_RANGE],
+        format!("bytes 0-3/{}", PNG_BYTES.len()).as_str()
+    );
+
+    let unchanged = serve_image(
+        &env.image_dir(),
+        &request(&cached[0], &[(header::IF_NONE_MATCH, etag.as_str())]),
+        no_thumbnails,
+    );
+    assert_eq!(unchanged.status(), StatusCode::NOT_MODIFIED);
+    assert!(unchanged.body().is_empty());
+
+    let missing = serve_image(
+        &env.image_dir(),
+        &request("cached/nope.png", &[]),
+        no_thumbnails,
+    );
+    assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+}
+
+#[test]
+fn long_files_are_served_a_slice_at_a_time() {
+    let env = TestEnv::new();
+    let video: Vec<u8> = (0..9 * 1024 * 1024).map(|i| (i % 251) as u8).collect();
+    std::fs::create_dir_all(env.image_dir().join("cached")).unwrap();
+    std::fs::write(env.image_dir().join("cached/clip.mp4"), &video).unwrap();
+
+    let first = serve_image(
+        &env.image_dir(),
+        &request("cached/clip.mp4", &[(header::RANGE, "bytes=0-")]),
+        no_thumbnails,
+    );
+    assert_eq!(first.status(), StatusCode::PARTIAL_CONTENT);
+    assert_eq!(first.body().len(), 8 * 1024 * 1024);
+    assert_eq!(
+        first.headers()[header::CONTENT_RANGE],
+        format!("bytes 0-8388607/{}", video.len()).as_str()
+    );
+
+    let rest = serve_image(
+        &env.image_dir(),
+        &request("cached/clip.mp4", &[(header::RANGE, "bytes=8388608-")]),
+        no_thumbnails,
+    );
+    assert_eq!(rest.body(), &video[8 * 1024 * 1024..]);
+
+    let middle = serve_image(
+        &env.image_dir(),
+        &request("cached/clip.mp4", &[(header::RANGE, "bytes=1000-1999")]),
+        no_thumbnails,
+    );
+    assert_eq!(middle.body(), &video[1000..2000]);
+}
+
+#[tokio::test]
+async fn batch_reports_failures_per_path() {
+    let mut env = TestEnv::new();
+    let now = Utc::now().timestamp();
+    let first = env.index(&message(1, 501, "Piece", now, &[11]));
+    let second = env.index(&message(2, 501, "Piece", now, &[21]));
+    let paths = vec![
+        first[0].clone(),
+        "cached/missing.png".to_string(),
+        "../outside.png".to_string(),
+        second[0].clone(),
+        first[0].clone(),
+    ];
+
+    let batch = load_images_batch(env.image_dir(), paths, false, None).await;
+    assert_eq!(batch.images.len(), 2);
+    assert!(batch.images[&first[0]].starts_with("data:image/png;base64,"));
+    assert!(batch.images.contains_key(&second[0]));
+    assert_eq!(batch.errors.len(), 2);
+    assert!(batch.errors.contains_key("cached/missing.png"));
+    assert!(batch.errors.contains_key("../outside.png"));
+}
@@ -0,0 +1,140 @@
+use chrono::Utc;
+use std::fs;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+
+use super::{attachment_url, message, FakeHttp, MemorySecrets, TestEnv, DAY_SECS, PNG_BYTES};
+use crate::content_filter::ContentFilter;
+use crate::discord::{
+    bot_token_from, insert_indexed_messages, process_message_batch, BatchOptions, BOT_TOKEN_KEY,
+};
+use crate::error::ErrorKind;
+use crate::models::{AuthorFilterSettings, ContentFilterSettings, ImageSizeFilter};
+use crate::providers::SecretStore;
+
+fn batch_options(env: &TestEnv, start_ts: i64) -> BatchOptions {
+    BatchOptions {
+        start_ts,
+        end_ts: None,
+        author_filters: AuthorFilterSettings::default(),
+        content_filter: ContentFilter::default(),
+        size_filter: ImageSizeFilter::default(),
+        exclude_sensitive: false,
+        include_animated: false,
+        include_linked: false,
+        cache_dir: env.image_dir().join("cached"),
+        encrypt: false,
+    }
+}
+
+async fn run_batch(
+    options: &BatchOptions,
+    http: &FakeHttp,
+    msgs: Vec<serenity::model::channel::Message>,
+) -> crate::discord::BatchOutcome {
+    process_message_batch(
+        msgs,
+        "100",
+        options,
+        http,
+        Arc::new(Semaphore::new(2)),
+        |_| {},
+    )
+    .await
+}
+
+#[tokio::test]
+async fn batch_is_filtered_cached_and_searchable() {
+    let mut env = TestEnv::new();
+    let now = Utc::now().timestamp();
+    let http = FakeHttp::default();
+    http.serve(&attachment_url(1, 11), 200, "image/png", PNG_BYTES);
+
+    let mut options = batch_options(&env, now - 7 * DAY_SECS);
+    options.author_filters.global.blocked_author_ids = vec!["502".to_string()];
+    options.content_filter = ContentFilter::compile(&ContentFilterSettings {
+        exclude_patterns: vec!["wip".to_string()],
+        ..Default::default()
+    })
+    .unwrap();
+
+    let msgs = vec![
+        message(1, 501, "Sunset over the harbour", now - DAY_SECS, &[11]),
+        message(2, 502, "Blocked author", now - DAY_SECS, &[21]),
+        message(3, 501, "Too old", now - 30 * DAY_SECS, &[31]),
+        message(4, 501, "WIP, do not feature", now - DAY_SECS, &[41]),
+        message(5, 501, "No images here", now - DAY_SECS, &[]),
+    ];
+    let outcome = run_batch(&options, &http, msgs).await;
+
+    assert!(outcome.reached_older_messages);
+    assert!(outcome.failed.is_empty());
+    assert_eq!(outcome.images_saved, 1);
+    assert_eq!(http.requests(), vec![attachment_url(1, 11)]);
+    let saved_ids: Vec<String> = outcome
+        .saved
+        .iter()
+        .map(|(m, _)| m.id.to_string())
+        .collect();
+    assert_eq!(saved_ids, vec!["1"]);
+    let cached_path = env.image_dir().join(&outcome.saved[0].1[0].relative_path);
+    assert_eq!(fs::read(cached_path).unwrap(), PNG_BYTES);
+
+    insert_indexed_messages(&mut env.conn, &outcome.saved, false).unwrap();
+    assert_eq!(env.message_ids(), vec!["1"]);
+    let hit: String = env
+        .conn
+        .query_row(
+            "SELECT m.message_id FROM messages_fts JOIN messages m ON m.rowid = messages_fts.rowid WHERE messages_fts MATCH 'harbour'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(hit, "1");
+}
+
+#[tokio::test]
+async fn failed_attachment_download_drops_the_message() {
+    let env = TestEnv::new();
+    let now = Utc::now().timestamp();
+    let http = FakeHttp::default();
+    http.serve(&attachment_url(1, 11), 200, "image/png", PNG_BYTES);
+    http.serve(&attachment_url(1, 12), 503, "text/plain", b"");
+
+    let options = batch_options(&env, now - 7 * DAY_SECS);
+    let msgs = vec![message(1, 501, "Two pieces", now - DAY_SECS, &[11, 12])];
+    let outcome = run_batch(&options, &http, msgs).await;
+
+    assert!(outcome.saved.is_empty());
+    assert_eq!(outcome.failed.len(), 1);
+    assert_eq!(outcome.failed[0].to_string(), "1");
+    assert_eq!(outcome.failed_downloads, 1);
+    // Server errors are retried before giving up.
+    let attempts = http
+        .requests()
+        .iter()
+        .filter(|url| **url == attachment_url(1, 12))
+        .count();
+    assert_eq!(attempts, 3);
+}
+
+#[test]
+fn reindexing_a_message_keeps_a_single_row() {
+    let mut env = TestEnv::new();
+    let msg = message(1, 501, "Sunset", Utc::now().timestamp(), &[11]);
+    env.index(&msg);
+    env.index(&msg);
+    assert_eq!(env.message_ids(), vec!["1"]);
+}
+
+#[test]
+fn bot_token_must_be_saved_and_non_empty() {
+    let secrets = MemorySecrets::default();
+    assert_eq!(bot_token_from(&secrets).unwrap_err().kind, ErrorKind::Auth);
+
+    secrets.set_secret(BOT_TOKEN_KEY, "").unwrap();
+    assert_eq!(bot_token_from(&secrets).unwrap_err().kind, ErrorKind::Auth);
+
+    secrets.set_secret(BOT_TOKEN_KEY, "token-123").unwrap();
+    assert_eq!(bot_token_from(&secrets).unwrap(), "token-123");
+}
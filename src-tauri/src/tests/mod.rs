@@ -0,0 +1,273 @@
+// End-to-end checks of the indexing, selection, export and cleanup pipelines. They drive the
+// same functions the commands call, with the AppHandle, keyring and network swapped for a
+// temporary data directory, in-memory secrets and canned HTTP responses.
+mod archive;
+mod cache_gc;
+mod cleanup;
+mod compact;
+mod consent;
+mod dedupe;
+mod deletion;
+mod drafts;
+mod encryption;
+mod errors;
+mod export;
+mod google_slides;
+mod health;
+mod html;
+mod image_protocol;
+mod indexing;
+mod network;
+mod notify;
+mod ordering;
+mod pool;
+mod presentation;
+mod query;
+mod reveal;
+mod revisions;
+mod schema;
+mod selection;
+mod share;
+mod slide_images;
+mod storage;
+mod upload;
+mod video;
+
+use chrono::Utc;
+use rusqlite::{params, Connection};
+use serenity::model::channel::Message;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::discord::{insert_indexed_messages, CachedFile, SOURCE_ATTACHMENT};
+use crate::error::AppError;
+use crate::migrations;
+use crate::models::SelectedMessage;
+use crate::providers::{HttpFetcher, HttpResponse, SecretStore};
+
+pub(crate) const DAY_SECS: i64 = 24 * 60 * 60;
+
+// A fully migrated in-memory database plus a data directory that is removed on drop.
+pub(crate) struct TestEnv {
+    pub data_dir: PathBuf,
+    pub conn: Connection,
+}
+
+impl TestEnv {
+    pub fn new() -> Self {
+        let data_dir =
+            std::env::temp_dir().join(format!("showcase-studio-test-{}", Uuid::new_v4()));
+        fs::create_dir_all(&data_dir).expect("create test data dir");
+        let mut conn = Connection::open_in_memory().expect("open in-memory database");
+        migrations::migrate(&mut conn).expect("create schema");
+        TestEnv { data_dir, conn }
+    }
+
+    pub fn image_dir(&self) -> PathBuf {
+        self.data_dir.join("images")
+    }
+
+    // Stores `msg` as if indexing had cached one image per attachment, and writes those files.
+    pub fn index(&mut self, msg: &Message) -> Vec<String> {
+        let files: Vec<CachedFile> = msg
+            .attachments
+            .iter()
+            .map(|attachment| CachedFile {
+                relative_path: format!("cached/{}_{}.png", msg.id, attachment.id),
+                attachment_type: "image",
+                source: SOURCE_ATTACHMENT,
+                content_hash: None,
+            })
+            .collect();
+        for file in &files {
+            let path = self.image_dir().join(&file.relative_path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, PNG_BYTES).unwrap();
+        }
+        let relative_paths = files.iter().map(|f| f.relative_path.clone()).collect();
+        insert_indexed_messages(&mut self.conn, &[(msg.clone(), files)], false)
+            .expect("insert indexed message");
+        relative_paths
+    }
+
+    pub fn message_ids(&self) -> Vec<String> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT message_id FROM messages ORDER BY message_id")
+            .unwrap();
+        stmt.query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    pub fn is_used(&self, message_id: &str) -> bool {
+        self.conn
+            .query_row(
+                "SELECT is_used FROM messages WHERE message_id = ?1",
+                params![message_id],
+                |row| row.get(0),
+            )
+            .unwrap()
+    }
+}
+
+impl Drop for TestEnv {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.data_dir);
+    }
+}
+
+// Not a decodable image; nothing on these paths looks past the bytes.
+pub(crate) const PNG_BYTES: &[u8] = b"\x89PNG\r\n\x1a\nfixture";
+
+pub(crate) fn attachment_url(message_id: u64, attachment_id: u64) -> String {
+    format!(
+        "https://cdn.discordapp.test/attachments/{}/{}.png",
+        message_id, attachment_id
+    )
+}
+
+// A Discord message as the REST API returns it, with one PNG per entry of `attachment_ids`.
+pub(crate) fn message(
+    id: u64,
+    author_id: u64,
+    content: &str,
+    timestamp: i64,
+    attachment_ids: &[u64],
+) -> Message {
+    let attachments: Vec<serde_json::Value> = attachment_ids
+        .iter()
+        .map(|attachment_id| {
+            serde_json::json!({
+                "id": attachment_id.to_string(),
+                "filename": format!("{}.png", attachment_id),
+                "size": PNG_BYTES.len(),
+                "url": attachment_url(id, *attachment_id),
+                "proxy_url": attachment_url(id, *attachment_id),
+                "content_type": "image/png",
+                "width": 1920,
+                "height": 1080
+            })
+        })
+        .collect();
+    let timestamp = chrono::DateTime::from_timestamp(timestamp, 0)
+        .expect("valid timestamp")
+        .to_rfc3339();
+    serde_json::from_value(serde_json::json!({
+        "id": id.to_string(),
+        "channel_id": "100",
+        "author": {
+            "id": author_id.to_string(),
+            "username": format!("artist{}", author_id),
+            "discriminator": "0",
+            "avatar": null,
+            "bot": false
+        },
+        "content": content,
+        "timestamp": timestamp,
+        "edited_timestamp": null,
+        "tts": false,
+        "mention_everyone": false,
+        "mentions": [],
+        "mention_roles": [],
+        "attachments": attachments,
+        "embeds": [],
+        "reactions": [],
+        "pinned": false,
+        "type": 0
+    }))
+    .expect("valid message fixture")
+}
+
+// A selection of one attachment, as the selection grid saves it.
+pub(crate) fn selected(message_id: &str, attachment: &str) -> SelectedMessage {
+    SelectedMessage {
+        message_id: message_id.to_string(),
+        channel_id: "100".to_string(),
+        author_id: "501".to_string(),
+        author_name: "artist501".to_string(),
+        author_avatar: None,
+        message_content: "Sunset".to_string(),
+        selected_attachment_filename: attachment.to_string(),
+        selected_attachment_filenames: Vec::new(),
+        timestamp: Utc::now().timestamp(),
+        reply_to: None,
+        author_avatar_path: None,
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct MemorySecrets(Mutex<HashMap<String, String>>);
+
+impl SecretStore for MemorySecrets {
+    fn get_secret(&self, key_name: &str) -> Result<Option<String>, AppError> {
+        Ok(self.0.lock()?.get(key_name).cloned())
+    }
+
+    fn set_secret(&self, key_name: &str, secret: &str) -> Result<(), AppError> {
+        self.0
+            .lock()?
+            .insert(key_name.to_string(), secret.to_string());
+        Ok(())
+    }
+
+    fn delete_secret(&self, key_name: &str) -> Result<(), AppError> {
+        self.0.lock()?.remove(key_name);
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+struct CannedResponse {
+    status: u16,
+    content_type: &'static str,
+    body: Vec<u8>,
+}
+
+// Serves registered URLs and answers 404 for everything else. Every request is recorded.
+#[derive(Clone, Default)]
+pub(crate) struct FakeHttp {
+    responses: Arc<Mutex<HashMap<String, CannedResponse>>>,
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl FakeHttp {
+    pub fn serve(&self, url: &str, status: u16, content_type: &'static str, body: &[u8]) {
+        self.responses.lock().unwrap().insert(
+            url.to_string(),
+            CannedResponse {
+                status,
+                content_type,
+                body: body.to_vec(),
+            },
+        );
+    }
+
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl HttpFetcher for FakeHttp {
+    async fn fetch(&self, url: &str) -> Result<HttpResponse, String> {
+        self.requests.lock().unwrap().push(url.to_string());
+        let canned = self.responses.lock().unwrap().get(url).cloned();
+        let response = canned.unwrap_or(CannedResponse {
+            status: 404,
+            content_type: "text/plain",
+            body: Vec::new(),
+        });
+        Ok(HttpResponse {
+            status: response.status,
+            content_type: Some(response.content_type.to_string()),
+            // Retries happen immediately instead of after the real backoff.
+            retry_after: Some(Duration::ZERO),
+            body: response.body,
+        })
+    }
+}
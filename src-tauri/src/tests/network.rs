@@ -0,0 +1,32 @@
+use crate::models::NetworkSettings;
+use crate::network::{discord_http, ensure_gateway_allowed, http_client};
+
+fn proxied(proxy_url: &str) -> NetworkSettings {
+    NetworkSettings {
+        proxy_url: Some(proxy_url.to_string()),
+        ca_cert_path: None,
+    }
+}
+
+#[test]
+fn discord_clients_use_the_same_proxy_settings() {
+    let settings = proxied("socks5h://127.0.0.1:1080");
+    assert!(http_client(&settings, None).is_ok());
+    assert!(discord_http(&settings, "Bot token").is_ok());
+
+    let invalid = proxied("not a proxy url");
+    assert!(http_client(&invalid, None).is_err());
+    assert!(discord_http(&invalid, "Bot token").is_err());
+
+    let missing_ca = NetworkSettings {
+        proxy_url: None,
+        ca_cert_path: Some("/nonexistent/ca.pem".to_string()),
+    };
+    assert!(discord_http(&missing_ca, "Bot token").is_err());
+}
+
+#[test]
+fn gateway_features_are_refused_behind_a_proxy() {
+    assert!(ensure_gateway_allowed(&NetworkSettings::default()).is_ok());
+    assert!(ensure_gateway_allowed(&proxied("http://proxy.local:3128")).is_err());
+}
@@ -0,0 +1,85 @@
+use chrono::Utc;
+
+use super::{message, selected, TestEnv};
+use crate::author_notify::{
+    plan_notifications, record_notification, render_notification, set_notify_opt_out,
+    DEFAULT_NOTIFY_TEMPLATE,
+};
+use crate::error::ErrorKind;
+use crate::models::{NotifyOutcome, SelectedMessage};
+use crate::showcase_manager::{insert_showcase, store_selected_messages};
+
+fn by(author_id: &str, author_name: &str, mut message: SelectedMessage) -> SelectedMessage {
+    message.author_id = author_id.to_string();
+    message.author_name = author_name.to_string();
+    message
+}
+
+#[test]
+fn each_featured_author_is_planned_once() {
+    let mut env = TestEnv::new();
+    let now = Utc::now().timestamp();
+    let first = env.index(&message(1, 501, "Sunset", now, &[11]));
+    let second = env.index(&message(2, 501, "Dusk", now, &[21]));
+    let third = env.index(&message(3, 502, "Harbour", now, &[31]));
+    let fourth = env.index(&message(4, 503, "Pier", now, &[41]));
+    let fifth = env.index(&message(5, 504, "Dunes", now, &[51]));
+    let id = insert_showcase(&env.conn, "Weekly", None).unwrap();
+    store_selected_messages(
+        &mut env.conn,
+        &id,
+        &[
+            selected("1", &first[0]),
+            selected("2", &second[0]),
+            by("502", "Bram", selected("3", &third[0])),
+            by("503", "Cleo", selected("4", &fourth[0])),
+            by("manifest:dana", "Dana", selected("5", &fifth[0])),
+        ],
+    )
+    .unwrap();
+    set_notify_opt_out(&env.conn, "502", true).unwrap();
+    record_notification(&env.conn, &id, "503", "dm").unwrap();
+
+    let (title, targets) = plan_notifications(&env.conn, &id).unwrap();
+    assert_eq!(title, "Weekly");
+    let outcomes: Vec<_> = targets
+        .iter()
+        .map(|t| (t.author_id.as_str(), t.skip))
+        .collect();
+    assert_eq!(
+        outcomes,
+        vec![
+            ("501", None),
+            ("502", Some(NotifyOutcome::OptedOut)),
+            ("503", Some(NotifyOutcome::AlreadyNotified)),
+            ("manifest:dana", Some(NotifyOutcome::Skipped)),
+        ]
+    );
+    assert_eq!(targets[0].message_count, 2);
+    assert_eq!(targets[0].message_id, "1");
+
+    set_notify_opt_out(&env.conn, "502", false).unwrap();
+    let (_, targets) = plan_notifications(&env.conn, &id).unwrap();
+    assert_eq!(targets[1].skip, None);
+}
+
+#[test]
+fn notifications_fill_in_the_template() {
+    let mut env = TestEnv::new();
+    let now = Utc::now().timestamp();
+    let first = env.index(&message(1, 501, "Sunset", now, &[11]));
+    let id = insert_showcase(&env.conn, "Weekly", None).unwrap();
+    store_selected_messages(&mut env.conn, &id, &[selected("1", &first[0])]).unwrap();
+
+    let (title, targets) = plan_notifications(&env.conn, &id).unwrap();
+    assert_eq!(
+        render_notification("{mention} x{count} in {showcase}", &targets[0], &title),
+        "<@501> x1 in Weekly"
+    );
+    let text = render_notification(DEFAULT_NOTIFY_TEMPLATE, &targets[0], &title);
+    assert!(text.starts_with("Hi artist501!"));
+    assert!(text.contains("credited as artist501"));
+
+    let err = plan_notifications(&env.conn, "missing").unwrap_err();
+    assert_eq!(err.kind, ErrorKind::NotFound);
+}
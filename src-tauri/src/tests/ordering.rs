@@ -0,0 +1,97 @@
+use super::TestEnv;
+use crate::error::ErrorKind;
+use crate::models::ShowcaseImage;
+use crate::showcase_manager::{
+    insert_showcase, load_showcase_images, move_slide, shuffle_slides, write_showcase_image,
+};
+use crate::showcase_revisions::{load_revisions, ACTION_MOVE_IMAGE, ACTION_SHUFFLE_IMAGES};
+
+fn slide(message_id: &str) -> ShowcaseImage {
+    serde_json::from_value(serde_json::json!({
+        "message_id": message_id,
+        "sender": "artist501",
+        "avatar": "",
+        "message": "Sunset",
+        "is_edited": false,
+        "overlay": {
+            "position": "bottom-left",
+            "style": "black",
+            "showAvatar": true,
+            "width": 40.0,
+            "transparency": 20
+        }
+    }))
+    .unwrap()
+}
+
+fn with_slides(env: &TestEnv, count: usize) -> String {
+    let id = insert_showcase(&env.conn, "Weekly", None).unwrap();
+    for n in 0..count {
+        write_showcase_image(&env.conn, &id, &slide(&n.to_string()), None).unwrap();
+    }
+    id
+}
+
+fn order(images: &[ShowcaseImage]) -> Vec<String> {
+    images
+        .iter()
+        .map(|image| image.message_id.clone())
+        .collect()
+}
+
+#[test]
+fn moving_a_slide_shifts_the_ones_in_between() {
+    let mut env = TestEnv::new();
+    let id = with_slides(&env, 4);
+
+    let images = move_slide(&mut env.conn, &id, "3", 1).unwrap();
+    assert_eq!(order(&images), ["0", "3", "1", "2"]);
+    let indexes: Vec<i64> = images.iter().map(|image| image.sort_index).collect();
+    assert_eq!(indexes, [0, 1, 2, 3]);
+
+    let images = move_slide(&mut env.conn, &id, "0", 3).unwrap();
+    assert_eq!(order(&images), ["3", "1", "2", "0"]);
+    assert_eq!(
+        order(&load_showcase_images(&env.conn, &id).unwrap()),
+        ["3", "1", "2", "0"]
+    );
+    assert_eq!(
+        load_revisions(&env.conn, &id).unwrap()[0].action,
+        ACTION_MOVE_IMAGE
+    );
+
+    // Staying in place is not a change.
+    move_slide(&mut env.conn, &id, "1", 1).unwrap();
+    assert_eq!(load_revisions(&env.conn, &id).unwrap().len(), 2);
+
+    let kind = |result: Result<_, crate::error::AppError>| result.unwrap_err().kind;
+    assert_eq!(
+        kind(move_slide(&mut env.conn, &id, "1", 4)),
+        ErrorKind::InvalidInput
+    );
+    assert_eq!(
+        kind(move_slide(&mut env.conn, &id, "9", 0)),
+        ErrorKind::NotFound
+    );
+}
+
+#[test]
+fn shuffling_is_repeatable_for_a_seed() {
+    let mut env = TestEnv::new();
+    let first = with_slides(&env, 8);
+    let second = with_slides(&env, 8);
+
+    let shuffled = order(&shuffle_slides(&mut env.conn, &first, 42).unwrap());
+    assert_eq!(
+        order(&shuffle_slides(&mut env.conn, &second, 42).unwrap()),
+        shuffled
+    );
+    assert_ne!(shuffled, ["0", "1", "2", "3", "4", "5", "6", "7"]);
+    let mut sorted = shuffled.clone();
+    sorted.sort();
+    assert_eq!(sorted, ["0", "1", "2", "3", "4", "5", "6", "7"]);
+    assert_eq!(
+        load_revisions(&env.conn, &first).unwrap()[0].action,
+        ACTION_SHUFFLE_IMAGES
+    );
+}
@@ -0,0 +1,89 @@
+use rusqlite::Connection;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use super::TestEnv;
+use crate::sqlite_manager::DbPool;
+
+fn file_pool(env: &TestEnv) -> (DbPool, Arc<AtomicUsize>) {
+    let path = env.data_dir.join("pool.db");
+    let first = Connection::open(&path).unwrap();
+    first
+        .execute_batch("PRAGMA journal_mode=WAL; CREATE TABLE items (id INTEGER PRIMARY KEY);")
+        .unwrap();
+    let opened = Arc::new(AtomicUsize::new(1));
+    let counter = opened.clone();
+    let pool = DbPool::new(first, move || {
+        counter.fetch_add(1, Ordering::SeqCst);
+        Connection::open(&path).map_err(|e| e.to_string())
+    });
+    (pool, opened)
+}
+
+#[test]
+fn connections_are_reused_and_opened_on_demand() {
+    let env = TestEnv::new();
+    let (pool, opened) = file_pool(&env);
+
+    drop(pool.lock().unwrap());
+    drop(pool.lock().unwrap());
+    assert_eq!(opened.load(Ordering::SeqCst), 1);
+
+    let writer = pool.lock().unwrap();
+    let reader = pool.lock().unwrap();
+    assert_eq!(opened.load(Ordering::SeqCst), 2);
+    writer
+        .execute("INSERT INTO items (id) VALUES (1)", [])
+        .unwrap();
+    let count: i64 = reader
+        .query_row("SELECT COUNT(*) FROM items", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(count, 1);
+}
+
+#[test]
+fn closed_pool_reopens_after_the_file_is_replaced() {
+    let env = TestEnv::new();
+    let (pool, opened) = file_pool(&env);
+    drop(pool.lock().unwrap());
+
+    let path = env.data_dir.join("pool.db");
+    pool.with_closed(|| {
+        std::fs::remove_file(&path).unwrap();
+        let _ = std::fs::remove_file(path.with_extension("db-wal"));
+        let _ = std::fs::remove_file(path.with_extension("db-shm"));
+    })
+    .unwrap();
+
+    let conn = pool.lock().unwrap();
+    assert_eq!(opened.load(Ordering::SeqCst), 2);
+    assert!(conn
+        .query_row("SELECT COUNT(*) FROM items", [], |_| Ok(()))
+        .is_err());
+}
+
+#[test]
+fn a_panic_while_closed_leaves_the_pool_usable() {
+    let env = TestEnv::new();
+    let (pool, _) = file_pool(&env);
+
+    let swap = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        pool.with_closed(|| panic!("swap failed"))
+    }));
+    assert!(swap.is_err());
+    assert!(pool.lock().is_ok());
+}
+
+#[tokio::test]
+async fn run_executes_off_the_async_runtime() {
+    let env = TestEnv::new();
+    let (pool, _) = file_pool(&env);
+    let count: i64 = pool
+        .run(|conn| {
+            conn.execute("INSERT INTO items (id) VALUES (7)", [])?;
+            Ok(conn.query_row("SELECT COUNT(*) FROM items", [], |row| row.get(0))?)
+        })
+        .await
+        .unwrap();
+    assert_eq!(count, 1);
+}
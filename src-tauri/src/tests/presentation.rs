@@ -0,0 +1,315 @@
+use image::{DynamicImage, ImageFormat, Rgba, RgbaImage};
+use std::fs;
+use std::io::{Cursor, Read, Write};
+use zip::write::{SimpleFileOptions, ZipWriter};
+use zip::ZipArchive;
+
+use super::{TestEnv, PNG_BYTES};
+use crate::models::{ShowcaseCredit, ShowcaseImage};
+use crate::pptx::{credits_slide_image, load_slide_images, write_pptx};
+use crate::pptx_template::load_template;
+use crate::slide_render::{unique_credits, SlidePlan};
+
+const RESOLUTION: (u32, u32) = (160, 90);
+
+fn slide(message_id: &str, attachment_index: usize) -> ShowcaseImage {
+    serde_json::from_value(serde_json::json!({
+        "message_id": message_id,
+        "attachment_index": attachment_index,
+        "sender": "artist501",
+        "avatar": "",
+        "message": "Sunset",
+        "is_edited": true,
+        "overlay": {
+            "position": "bottom-left",
+            "style": "black",
+            "showAvatar": true,
+            "width": 40.0,
+            "transparency": 20
+        }
+    }))
+    .unwrap()
+}
+
+// The slide drawn over a cached original, the way exports render every slide.
+fn plan(env: &TestEnv, image: ShowcaseImage) -> SlidePlan {
+    let original = env
+        .image_dir()
+        .join("cached")
+        .join(format!("{}.png", image.slide_key()));
+    fs::create_dir_all(original.parent().unwrap()).unwrap();
+    DynamicImage::ImageRgba8(RgbaImage::from_pixel(64, 36, Rgba([200, 40, 40, 255])))
+        .save_with_format(&original, ImageFormat::Png)
+        .unwrap();
+    SlidePlan {
+        slide_key: image.slide_key(),
+        render: None,
+        original: Some(original),
+        overlay: Some(image),
+        avatar: None,
+    }
+}
+
+fn part(archive: &mut ZipArchive<Cursor<Vec<u8>>>, name: &str) -> String {
+    let mut text = String::new();
+    archive
+        .by_name(name)
+        .unwrap_or_else(|_| panic!("{} is missing", name))
+        .read_to_string(&mut text)
+        .unwrap();
+    text
+}
+
+#[test]
+fn deck_has_one_slide_per_drawn_slide() {
+    let env = TestEnv::new();
+    // Message 2's original is gone from the cache and it has no uploaded render, so its slide
+    // is left out.
+    let missing = plan(&env, slide("2", 0));
+    fs::remove_file(missing.original.as_ref().unwrap()).unwrap();
+    let plans = [
+        plan(&env, slide("1", 0)),
+        missing,
+        plan(&env, slide("1", 1)),
+    ];
+    let slides = load_slide_images(&plans, RESOLUTION, false);
+    assert_eq!(slides.len(), 2);
+
+    let bytes = write_pptx(
+        Cursor::new(Vec::new()),
+        "Weekly <best>",
+        &slides,
+        false,
+        None,
+    )
+    .unwrap()
+    .into_inner();
+    let mut archive = ZipArchive::new(Cursor::new(bytes)).unwrap();
+
+    let presentation = part(&mut archive, "ppt/presentation.xml");
+    assert_eq!(presentation.matches("<p:sldId ").count(), 2);
+    assert!(presentation.contains(r#"<p:sldSz cx="9144000" cy="5143500"/>"#));
+    assert!(part(&mut archive, "docProps/core.xml").contains("Weekly &lt;best&gt;"));
+    let content_types = part(&mut archive, "[Content_Types].xml");
+    assert!(content_types.contains(r#"Extension="jpg" ContentType="image/jpeg""#));
+    assert!(content_types.contains("/ppt/slides/slide2.xml"));
+    assert!(part(&mut archive, "ppt/slides/slide1.xml").contains("<p:bg>"));
+    assert!(part(&mut archive, "ppt/slides/_rels/slide2.xml.rels").contains("../media/image2.jpg"));
+
+    let mut media = Vec::new();
+    archive
+        .by_name("ppt/media/image1.jpg")
+        .unwrap()
+        .read_to_end(&mut media)
+        .unwrap();
+    let drawn = image::load_from_memory(&media).unwrap();
+    assert_eq!((drawn.width(), drawn.height()), RESOLUTION);
+}
+
+#[test]
+fn slide_titles_and_notes_become_speaker_notes() {
+    let env = TestEnv::new();
+    let mut first = slide("1", 0);
+    first.slide_title = Some("Harbour & lights".to_string());
+    first.notes = Some("Shot at dusk\nAsk about the lens".to_string());
+    let plans = [plan(&env, first), plan(&env, slide("2", 0))];
+    let slides = load_slide_images(&plans, RESOLUTION, false);
+    let bytes = write_pptx(Cursor::new(Vec::new()), "Weekly", &slides, false, None)
+        .unwrap()
+        .into_inner();
+    let mut archive = ZipArchive::new(Cursor::new(bytes)).unwrap();
+
+    assert!(part(&mut archive, "ppt/presentation.xml").contains("<p:notesMasterIdLst>"));
+    assert!(part(&mut archive, "ppt/_rels/presentation.xml.rels")
+        .contains(r#"Id="rId8" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/notesMaster""#));
+    assert!(part(&mut archive, "ppt/slides/slide1.xml")
+        .contains(r#"<p:cSld name="Harbour &amp; lights">"#));
+    assert!(part(&mut archive, "ppt/slides/_rels/slide1.xml.rels")
+        .contains("../notesSlides/notesSlide1.xml"));
+    let notes = part(&mut archive, "ppt/notesSlides/notesSlide1.xml");
+    assert!(notes.contains(r#"<a:rPr lang="en-US" b="1"/><a:t>Harbour &amp; lights</a:t>"#));
+    assert!(notes.contains("<a:t>Shot at dusk</a:t>"));
+    assert!(notes.contains("<a:t>Ask about the lens</a:t>"));
+    assert!(part(&mut archive, "[Content_Types].xml").contains("/ppt/notesSlides/notesSlide1.xml"));
+
+    // Slides without a title or notes get no notes page.
+    assert!(archive.by_name("ppt/notesSlides/notesSlide2.xml").is_err());
+    assert!(!part(&mut archive, "ppt/slides/_rels/slide2.xml.rels").contains("notesSlide"));
+}
+
+#[test]
+fn decks_close_with_a_credits_slide() {
+    let env = TestEnv::new();
+    let credit = |message_id: &str, text: &str| ShowcaseCredit {
+        message_id: message_id.to_string(),
+        author_id: "1".to_string(),
+        author_name: "artist501".to_string(),
+        credit_text: text.to_string(),
+    };
+    let lines = unique_credits(&[
+        credit("1", "Art by Zoë"),
+        credit("2", "Photo by Ana"),
+        credit("3", "Art by Zoë"),
+        credit("4", " "),
+    ]);
+    assert_eq!(lines, ["Art by Zoë", "Photo by Ana"]);
+    assert!(credits_slide_image(&[], RESOLUTION, false).is_none());
+
+    let mut slides = load_slide_images(&[plan(&env, slide("1", 0))], RESOLUTION, false);
+    slides.extend(credits_slide_image(&lines, RESOLUTION, false));
+    let bytes = write_pptx(Cursor::new(Vec::new()), "Weekly", &slides, false, None)
+        .unwrap()
+        .into_inner();
+    let mut archive = ZipArchive::new(Cursor::new(bytes)).unwrap();
+
+    assert_eq!(
+        part(&mut archive, "ppt/presentation.xml")
+            .matches("<p:sldId ")
+            .count(),
+        2
+    );
+    assert!(part(&mut archive, "ppt/slides/slide2.xml").contains(r#"<p:cSld name="Credits">"#));
+    let notes = part(&mut archive, "ppt/notesSlides/notesSlide2.xml");
+    assert!(notes.contains("<a:t>Art by Zoë</a:t>"));
+    assert!(notes.contains("<a:t>Photo by Ana</a:t>"));
+    let mut media = Vec::new();
+    archive
+        .by_name("ppt/media/image2.jpg")
+        .unwrap()
+        .read_to_end(&mut media)
+        .unwrap();
+    let drawn = image::load_from_memory(&media).unwrap().to_rgb8();
+    assert_eq!((drawn.width(), drawn.height()), RESOLUTION);
+    assert!(drawn.pixels().any(|pixel| pixel[0] > 128));
+}
+
+const REL: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+
+fn rels(entries: &[(&str, &str, &str)]) -> String {
+    let body: String = entries
+        .iter()
+        .map(|(id, kind, target)| {
+            format!(
+                r#"<Relationship Id="{}" Type="{}/{}" Target="{}"/>"#,
+                id, REL, kind, target
+            )
+        })
+        .collect();
+    format!("<Relationships>{}</Relationships>", body)
+}
+
+// A branded deck with a title layout, a picture layout and a logo on the master, plus one
+// slide of its own that exports must not carry over.
+fn branded_template() -> Vec<u8> {
+    let parts: Vec<(&str, Vec<u8>)> = vec![
+        (
+            "[Content_Types].xml",
+            br#"<Types><Default Extension="png" ContentType="image/png"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/ppt/slideLayouts/slideLayout2.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.slideLayout+xml"/><Override PartName="/ppt/slides/slide1.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.slide+xml"/></Types>"#.to_vec(),
+        ),
+        (
+            "ppt/presentation.xml",
+            br#"<p:presentation><p:sldMasterIdLst><p:sldMasterId id="2147483660" r:id="rId1"/></p:sldMasterIdLst><p:sldSz cx="12192000" cy="6858000"/></p:presentation>"#.to_vec(),
+        ),
+        (
+            "ppt/_rels/presentation.xml.rels",
+            rels(&[
+                ("rId1", "slideMaster", "slideMasters/slideMaster1.xml"),
+                ("rId2", "theme", "theme/theme1.xml"),
+                ("rId3", "slide", "slides/slide1.xml"),
+            ])
+            .into_bytes(),
+        ),
+        ("ppt/slideMasters/slideMaster1.xml", b"<p:sldMaster/>".to_vec()),
+        (
+            "ppt/slideMasters/_rels/slideMaster1.xml.rels",
+            rels(&[
+                ("rId1", "slideLayout", "../slideLayouts/slideLayout1.xml"),
+                ("rId2", "slideLayout", "../slideLayouts/slideLayout2.xml"),
+                ("rId3", "theme", "../theme/theme1.xml"),
+                ("rId4", "image", "../media/image1.png"),
+            ])
+            .into_bytes(),
+        ),
+        (
+            "ppt/slideLayouts/slideLayout1.xml",
+            br#"<p:sldLayout type="title"><p:ph type="ctrTitle"/></p:sldLayout>"#.to_vec(),
+        ),
+        (
+            "ppt/slideLayouts/slideLayout2.xml",
+            br#"<p:sldLayout type="picTx"><p:ph type="pic" sz="quarter" idx="13"/></p:sldLayout>"#.to_vec(),
+        ),
+        ("ppt/theme/theme1.xml", b"<a:theme/>".to_vec()),
+        ("ppt/media/image1.png", PNG_BYTES.to_vec()),
+        ("ppt/media/image2.png", PNG_BYTES.to_vec()),
+        ("ppt/slides/slide1.xml", b"<p:sld/>".to_vec()),
+        (
+            "ppt/slides/_rels/slide1.xml.rels",
+            rels(&[("rId1", "image", "../media/image2.png")]).into_bytes(),
+        ),
+    ];
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    for (name, bytes) in parts {
+        zip.start_file(name, SimpleFileOptions::default()).unwrap();
+        zip.write_all(&bytes).unwrap();
+    }
+    zip.finish().unwrap().into_inner()
+}
+
+#[test]
+fn templates_supply_the_masters_and_picture_placeholder() {
+    let template = load_template(Cursor::new(branded_template())).unwrap();
+    assert_eq!(template.layout, "slideLayouts/slideLayout2.xml");
+    assert_eq!(
+        template.picture_placeholder.as_deref(),
+        Some(r#"<p:ph type="pic" idx="13"/>"#)
+    );
+    assert_eq!(template.slide_size, Some((12192000, 6858000)));
+    let copied: Vec<&str> = template
+        .parts
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect();
+    assert!(copied.contains(&"ppt/media/image1.png"));
+    assert!(!copied.contains(&"ppt/media/image2.png"));
+    assert!(!copied.contains(&"ppt/slides/slide1.xml"));
+
+    let env = TestEnv::new();
+    let slides = load_slide_images(&[plan(&env, slide("1", 0))], RESOLUTION, false);
+    let bytes = write_pptx(
+        Cursor::new(Vec::new()),
+        "Weekly",
+        &slides,
+        false,
+        Some(&template),
+    )
+    .unwrap()
+    .into_inner();
+    let mut archive = ZipArchive::new(Cursor::new(bytes)).unwrap();
+
+    let presentation = part(&mut archive, "ppt/presentation.xml");
+    assert!(presentation.contains(r#"<p:sldMasterId id="2147483660" r:id="rId1"/>"#));
+    assert!(presentation.contains(r#"<p:sldSz cx="12192000" cy="6858000"/>"#));
+    assert!(part(&mut archive, "ppt/slides/slide1.xml").contains(r#"<p:ph type="pic" idx="13"/>"#));
+    let slide_rels = part(&mut archive, "ppt/slides/_rels/slide1.xml.rels");
+    assert!(slide_rels.contains("../slideLayouts/slideLayout2.xml"));
+    assert!(slide_rels.contains("../media/showcaseImage1.jpg"));
+    assert_eq!(
+        part(&mut archive, "ppt/slides/slide1.xml")
+            .matches("<p:pic>")
+            .count(),
+        1
+    );
+    assert!(archive.by_name("ppt/media/image1.png").is_ok());
+    assert!(archive.by_name("ppt/media/image2.png").is_err());
+    let content_types = part(&mut archive, "[Content_Types].xml");
+    assert!(content_types.contains("/ppt/slideLayouts/slideLayout2.xml"));
+    assert!(!content_types.contains("/ppt/slideLayouts/slideLayout1.xml\""));
+}
+
+#[test]
+fn files_without_a_master_are_not_templates() {
+    let err = load_template(Cursor::new(b"not a zip".to_vec()))
+        .err()
+        .unwrap();
+    assert!(err.contains("not a .pptx"));
+}
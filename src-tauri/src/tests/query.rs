@@ -0,0 +1,221 @@
+use super::{message, selected, TestEnv, DAY_SECS};
+use crate::models::{MessageQuery, MessageSearchFilters, ShowcaseQuery};
+use crate::showcase_manager::{insert_showcase, query_showcases_page, store_selected_messages};
+use crate::sqlite_manager::{messages_to_csv, query_messages_for_export, query_messages_page};
+
+fn indexed_env() -> TestEnv {
+    let mut env = TestEnv::new();
+    let base = 1_700_000_000;
+    for id in 1..=5u64 {
+        let author = if id % 2 == 0 { 502 } else { 501 };
+        env.index(&message(
+            id,
+            author,
+            "Piece",
+            base + id as i64 * DAY_SECS,
+            &[id * 10],
+        ));
+    }
+    env.conn
+        .execute("UPDATE messages SET is_used = 1 WHERE message_id = '3'", [])
+        .unwrap();
+    env
+}
+
+fn ids(env: &TestEnv, query: &MessageQuery) -> Vec<String> {
+    query_messages_page(&env.conn, query)
+        .unwrap()
+        .messages
+        .into_iter()
+        .map(|m| m.message_id)
+        .collect()
+}
+
+#[test]
+fn pages_are_stable_and_report_the_total() {
+    let env = indexed_env();
+    let mut query = MessageQuery {
+        filters: MessageSearchFilters {
+            limit: Some(2),
+            ..Default::default()
+        },
+        page: Some(2),
+        ..Default::default()
+    };
+    let page = query_messages_page(&env.conn, &query).unwrap();
+    assert_eq!(page.total, 5);
+    assert_eq!((page.page, page.limit), (2, 2));
+    assert_eq!(ids(&env, &query), vec!["3", "2"]);
+
+    query.sort_order = Some("asc".to_string());
+    query.page = Some(3);
+    assert_eq!(ids(&env, &query), vec!["5"]);
+}
+
+#[test]
+fn filters_are_combined() {
+    let env = indexed_env();
+    let base = 1_700_000_000;
+    let query = MessageQuery {
+        filters: MessageSearchFilters {
+            author_ids: vec!["501".to_string()],
+            from_date: Some(base + 2 * DAY_SECS),
+            ..Default::default()
+        },
+        is_used: Some(false),
+        ..Default::default()
+    };
+    assert_eq!(ids(&env, &query), vec!["5"]);
+    assert_eq!(query_messages_page(&env.conn, &query).unwrap().total, 1);
+}
+
+#[test]
+fn unknown_sort_options_are_rejected() {
+    let env = indexed_env();
+    let query = MessageQuery {
+        sort_order: Some("sideways".to_string()),
+        ..Default::default()
+    };
+    assert!(query_messages_page(&env.conn, &query).is_err());
+}
+
+#[test]
+fn favorites_filter_is_independent_of_is_used() {
+    let env = indexed_env();
+    env.conn
+        .execute(
+            "UPDATE messages SET is_favorite = 1 WHERE message_id IN ('2', '3')",
+            [],
+        )
+        .unwrap();
+    let mut query = MessageQuery {
+        filters: MessageSearchFilters {
+            favorites_only: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    assert_eq!(ids(&env, &query), vec!["3", "2"]);
+
+    query.is_used = Some(false);
+    assert_eq!(ids(&env, &query), vec!["2"]);
+    let page = query_messages_page(&env.conn, &query).unwrap();
+    assert!(page.messages[0].is_favorite);
+}
+
+#[test]
+fn export_includes_every_match_oldest_first() {
+    let env = indexed_env();
+    env.conn
+        .execute(
+            "UPDATE messages SET message_content = '=HYPERLINK(\"x\"), \"quoted\"' WHERE message_id = '1'",
+            [],
+        )
+        .unwrap();
+    let filters = MessageSearchFilters {
+        author_ids: vec!["501".to_string()],
+        limit: Some(1),
+        ..Default::default()
+    };
+    let messages = query_messages_for_export(&env.conn, &filters).unwrap();
+    let exported: Vec<&str> = messages.iter().map(|m| m.message_id.as_str()).collect();
+    assert_eq!(exported, vec!["1", "3", "5"]);
+
+    let csv = messages_to_csv(&messages);
+    let lines: Vec<&str> = csv.split("\r\n").collect();
+    assert_eq!(lines.len(), 5); // header, three rows and the trailing line break
+    assert!(lines[0].starts_with("message_id,channel_id,"));
+    assert!(lines[1].contains(",\"'=HYPERLINK(\"\"x\"\"), \"\"quoted\"\"\","));
+    assert!(lines[1].contains(",cached/1_10.png,image,"));
+}
+
+fn showcase_titles(env: &TestEnv, query: &ShowcaseQuery) -> Vec<String> {
+    query_showcases_page(&env.conn, query)
+        .unwrap()
+        .showcases
+        .into_iter()
+        .map(|s| s.title)
+        .collect()
+}
+
+#[test]
+fn showcases_are_searched_filtered_and_paged() {
+    let mut env = TestEnv::new();
+    let files = env.index(&message(1, 501, "Sunset", 1_700_000_000, &[11]));
+    for (index, (title, description)) in [
+        ("Weekly Picks", Some("Best of the week")),
+        ("Monthly 100%", None),
+        ("Weekly_Recap", Some("Spring")),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let id = insert_showcase(&env.conn, title, description).unwrap();
+        env.conn
+            .execute(
+                "UPDATE showcases SET created_at = ?1, last_modified = ?1 WHERE id = ?2",
+                rusqlite::params![1_700_000_000 + index as i64 * DAY_SECS, id],
+            )
+            .unwrap();
+        if index == 0 {
+            store_selected_messages(&mut env.conn, &id, &[selected("1", &files[0])]).unwrap();
+        }
+    }
+    let trashed = insert_showcase(&env.conn, "Weekly Trash", None).unwrap();
+    env.conn
+        .execute(
+            "UPDATE showcases SET deleted_at = 1 WHERE id = ?1",
+            [&trashed],
+        )
+        .unwrap();
+
+    let all = query_showcases_page(&env.conn, &ShowcaseQuery::default()).unwrap();
+    assert_eq!(all.total, 3);
+    let picks = all
+        .showcases
+        .iter()
+        .find(|s| s.title == "Weekly Picks")
+        .unwrap();
+    assert_eq!((picks.message_count, picks.phase), (1, 2));
+
+    let search = |text: &str| ShowcaseQuery {
+        search: Some(text.to_string()),
+        sort_by: Some("title".to_string()),
+        sort_order: Some("asc".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(
+        showcase_titles(&env, &search("weekly")),
+        ["Weekly Picks", "Weekly_Recap"]
+    );
+    assert_eq!(showcase_titles(&env, &search("spring")), ["Weekly_Recap"]);
+    // LIKE wildcards in the search text match literally.
+    assert_eq!(showcase_titles(&env, &search("%")), ["Monthly 100%"]);
+    assert_eq!(showcase_titles(&env, &search("y_R")), ["Weekly_Recap"]);
+
+    let filtered = ShowcaseQuery {
+        phases: vec![1],
+        created_from: Some(1_700_000_000 + DAY_SECS),
+        ..Default::default()
+    };
+    assert_eq!(
+        showcase_titles(&env, &filtered),
+        ["Weekly_Recap", "Monthly 100%"]
+    );
+
+    let second_page = ShowcaseQuery {
+        sort_by: Some("created_at".to_string()),
+        limit: Some(2),
+        page: Some(2),
+        ..Default::default()
+    };
+    let page = query_showcases_page(&env.conn, &second_page).unwrap();
+    assert_eq!((page.total, page.page, page.limit), (3, 2, 2));
+    assert_eq!(page.showcases[0].title, "Weekly Picks");
+
+    let sideways = ShowcaseQuery {
+        sort_by: Some("popularity".to_string()),
+        ..Default::default()
+    };
+    assert!(query_showcases_page(&env.conn, &sideways).is_err());
+}
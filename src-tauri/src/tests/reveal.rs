@@ -0,0 +1,70 @@
+use std::fs;
+
+use super::TestEnv;
+use crate::error::ErrorKind;
+use crate::reveal::resolve_openable_path;
+
+#[test]
+fn only_exports_inside_the_data_dir_open() {
+    let env = TestEnv::new();
+    let deck_dir = env.data_dir.join("presentations").join("abc");
+    fs::create_dir_all(&deck_dir).unwrap();
+    fs::write(deck_dir.join("showcase_abc.pptx"), b"deck").unwrap();
+    fs::write(env.data_dir.join("showcase_app_data.db"), b"db").unwrap();
+
+    let deck =
+        resolve_openable_path(&env.data_dir, None, "presentations/abc/showcase_abc.pptx").unwrap();
+    assert_eq!(
+        deck,
+        deck_dir.join("showcase_abc.pptx").canonicalize().unwrap()
+    );
+    let absolute = deck_dir.to_string_lossy().into_owned();
+    assert!(resolve_openable_path(&env.data_dir, None, &absolute)
+        .unwrap()
+        .is_dir());
+
+    for (path, kind) in [
+        ("showcase_app_data.db", ErrorKind::InvalidInput),
+        (
+            "presentations/abc/../../showcase_app_data.db",
+            ErrorKind::InvalidInput,
+        ),
+        ("presentations/missing.pptx", ErrorKind::NotFound),
+        ("", ErrorKind::InvalidInput),
+    ] {
+        let err = resolve_openable_path(&env.data_dir, None, path).unwrap_err();
+        assert_eq!(err.kind, kind, "{}", path);
+    }
+
+    let outside = env
+        .data_dir
+        .parent()
+        .unwrap()
+        .to_string_lossy()
+        .into_owned();
+    let err = resolve_openable_path(&env.data_dir, None, &outside).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::InvalidInput);
+}
+
+#[test]
+fn exports_in_the_configured_export_dir_open() {
+    let env = TestEnv::new();
+    let data_dir = env.data_dir.join("app");
+    let export_dir = env.data_dir.join("exports");
+    fs::create_dir_all(&data_dir).unwrap();
+    fs::create_dir_all(&export_dir).unwrap();
+    let deck = export_dir.join("Weekly_abc.pptx");
+    fs::write(&deck, b"deck").unwrap();
+    let deck_path = deck.to_string_lossy().into_owned();
+    let export_setting = export_dir.to_string_lossy().into_owned();
+
+    let err = resolve_openable_path(&data_dir, None, &deck_path).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::InvalidInput);
+    let resolved = resolve_openable_path(&data_dir, Some(&export_setting), &deck_path).unwrap();
+    assert_eq!(resolved, deck.canonicalize().unwrap());
+
+    fs::write(export_dir.join("notes.exe"), b"exe").unwrap();
+    let exe = export_dir.join("notes.exe").to_string_lossy().into_owned();
+    let err = resolve_openable_path(&data_dir, Some(&export_setting), &exe).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::InvalidInput);
+}
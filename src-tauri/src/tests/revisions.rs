@@ -0,0 +1,58 @@
+use chrono::Utc;
+
+use super::{message, selected, TestEnv};
+use crate::error::ErrorKind;
+use crate::showcase_manager::{insert_showcase, load_selected_messages, store_selected_messages};
+use crate::showcase_revisions::{
+    apply_revision, load_revisions, ACTION_RESTORE, ACTION_SAVE_SELECTION,
+    MAX_REVISIONS_PER_SHOWCASE,
+};
+
+#[test]
+fn selection_changes_can_be_undone() {
+    let mut env = TestEnv::new();
+    let now = Utc::now().timestamp();
+    let first = env.index(&message(1, 501, "Sunset", now, &[11]));
+    let second = env.index(&message(2, 502, "Harbour", now, &[21]));
+    let id = insert_showcase(&env.conn, "Weekly", None).unwrap();
+
+    store_selected_messages(&mut env.conn, &id, &[selected("1", &first[0])]).unwrap();
+    store_selected_messages(&mut env.conn, &id, &[selected("2", &second[0])]).unwrap();
+    assert!(!env.is_used("1"));
+
+    let revisions = load_revisions(&env.conn, &id).unwrap();
+    assert_eq!(revisions.len(), 2);
+    assert_eq!(revisions[0].action, ACTION_SAVE_SELECTION);
+    assert_eq!(revisions[0].message_count, 1);
+    assert_eq!(revisions[1].message_count, 0);
+    assert_eq!(revisions[1].phase, 1);
+
+    apply_revision(&mut env.conn, &id, revisions[0].id).unwrap();
+    let restored = load_selected_messages(&env.conn, &id).unwrap();
+    assert_eq!(restored[0].message_id, "1");
+    assert!(env.is_used("1"));
+    assert!(!env.is_used("2"));
+
+    // The restore is a revision of its own, so it can be undone too.
+    let revisions = load_revisions(&env.conn, &id).unwrap();
+    assert_eq!(revisions[0].action, ACTION_RESTORE);
+    assert_eq!(revisions.len(), 3);
+
+    let other = insert_showcase(&env.conn, "Monthly", None).unwrap();
+    let err = apply_revision(&mut env.conn, &other, revisions[0].id).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::NotFound);
+}
+
+#[test]
+fn revisions_are_capped_per_showcase() {
+    let mut env = TestEnv::new();
+    let now = Utc::now().timestamp();
+    let files = env.index(&message(1, 501, "Sunset", now, &[11]));
+    let id = insert_showcase(&env.conn, "Weekly", None).unwrap();
+    for _ in 0..MAX_REVISIONS_PER_SHOWCASE + 5 {
+        store_selected_messages(&mut env.conn, &id, &[selected("1", &files[0])]).unwrap();
+    }
+    let revisions = load_revisions(&env.conn, &id).unwrap();
+    assert_eq!(revisions.len() as i64, MAX_REVISIONS_PER_SHOWCASE);
+    assert!(revisions.windows(2).all(|pair| pair[0].id > pair[1].id));
+}
@@ -0,0 +1,98 @@
+use rusqlite::Connection;
+
+use crate::migrations::{migrate, schema_version, CURRENT_SCHEMA_VERSION};
+use crate::sqlite_manager::INDEXED_MESSAGE_COLUMNS;
+
+#[test]
+fn new_database_is_migrated_to_the_current_version() {
+    let mut conn = Connection::open_in_memory().unwrap();
+    assert_eq!(migrate(&mut conn).unwrap(), 0);
+    assert_eq!(schema_version(&conn).unwrap(), CURRENT_SCHEMA_VERSION);
+    conn.prepare(&format!("SELECT {} FROM messages", INDEXED_MESSAGE_COLUMNS))
+        .unwrap();
+    // Running again is a no-op.
+    assert_eq!(migrate(&mut conn).unwrap(), CURRENT_SCHEMA_VERSION);
+}
+
+#[test]
+fn old_database_keeps_its_rows() {
+    let mut conn = Connection::open_in_memory().unwrap();
+    conn.execute_batch(
+        "CREATE TABLE schema_version (version INTEGER PRIMARY KEY NOT NULL);
+         INSERT INTO schema_version (version) VALUES (1);
+         CREATE TABLE config (key TEXT PRIMARY KEY NOT NULL, value TEXT NOT NULL);
+         CREATE TABLE showcases (id TEXT PRIMARY KEY NOT NULL, title TEXT NOT NULL, description TEXT,
+             status TEXT NOT NULL DEFAULT 'Draft', created_at INTEGER NOT NULL DEFAULT 0,
+             last_modified INTEGER NOT NULL DEFAULT 0, phase INTEGER NOT NULL DEFAULT 1,
+             selected_messages_json TEXT, pptx_path TEXT, images_json TEXT);
+         CREATE TABLE messages (message_id TEXT PRIMARY KEY NOT NULL, channel_id TEXT NOT NULL,
+             author_id TEXT NOT NULL, author_name TEXT NOT NULL, author_avatar TEXT,
+             message_content TEXT NOT NULL, attachments TEXT NOT NULL DEFAULT '[]',
+             timestamp INTEGER NOT NULL, is_used INTEGER NOT NULL DEFAULT 0);
+         INSERT INTO messages (message_id, channel_id, author_id, author_name, message_content, timestamp)
+             VALUES ('1', '100', '501', 'artist', 'Harbour at dusk', 1700000000);
+         INSERT INTO showcases (id, title, selected_messages_json, images_json) VALUES ('s1', 'Weekly',
+             '[{\"message_id\": \"1\", \"channel_id\": \"100\", \"author_id\": \"501\", \"author_name\": \"artist\",
+               \"author_avatar\": null, \"message_content\": \"Harbour at dusk\",
+               \"selected_attachment_filename\": \"cached/1_11.png\", \"timestamp\": 1700000000},
+               {\"message_id\": 2}]',
+             'not json');",
+    )
+    .unwrap();
+
+    assert_eq!(migrate(&mut conn).unwrap(), 1);
+    let (author_type, hit): (String, String) = conn
+        .query_row(
+            "SELECT m.author_type, m.message_id FROM messages_fts JOIN messages m ON m.rowid = messages_fts.rowid WHERE messages_fts MATCH 'harbour'",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .unwrap();
+    assert_eq!((author_type.as_str(), hit.as_str()), ("user", "1"));
+
+    // The legacy selection blob is split into rows; entries that don't decode are quarantined.
+    let (message_id, filenames): (String, String) = conn
+        .query_row(
+            "SELECT message_id, attachment_filenames_json FROM showcase_messages WHERE showcase_id = 's1'",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .unwrap();
+    assert_eq!(message_id, "1");
+    assert_eq!(filenames, "[\"cached/1_11.png\"]");
+    let images: i64 = conn
+        .query_row("SELECT COUNT(*) FROM showcase_images", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(images, 0);
+    let quarantined: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM quarantined_rows WHERE row_key = 's1'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(quarantined, 2);
+    assert!(conn
+        .prepare("SELECT selected_messages_json FROM showcases")
+        .is_err());
+}
+
+#[test]
+fn unknown_databases_are_refused_untouched() {
+    let mut newer = Connection::open_in_memory().unwrap();
+    migrate(&mut newer).unwrap();
+    newer
+        .execute(
+            "UPDATE schema_version SET version = ?1",
+            [CURRENT_SCHEMA_VERSION + 1],
+        )
+        .unwrap();
+    assert!(migrate(&mut newer).is_err());
+
+    let mut foreign = Connection::open_in_memory().unwrap();
+    foreign
+        .execute("CREATE TABLE notes (body TEXT)", [])
+        .unwrap();
+    assert!(migrate(&mut foreign).is_err());
+    assert_eq!(schema_version(&foreign).unwrap(), 0);
+}
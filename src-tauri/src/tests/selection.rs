@@ -0,0 +1,79 @@
+use chrono::Utc;
+
+use super::{message, selected, TestEnv};
+use crate::showcase_manager::{
+    insert_showcase, load_selected_messages, load_showcase, repair_all_used_flags,
+    store_selected_messages,
+};
+
+#[test]
+fn selection_is_saved_and_protects_the_messages() {
+    let mut env = TestEnv::new();
+    let now = Utc::now().timestamp();
+    let picked = env.index(&message(1, 501, "Sunset", now, &[11]));
+    env.index(&message(2, 502, "Harbour", now, &[21]));
+
+    let id = insert_showcase(&env.conn, "Weekly Picks", Some("Best of the week")).unwrap();
+    let showcase = load_showcase(&env.conn, &id).unwrap();
+    assert_eq!(showcase.status, "Draft");
+    assert_eq!(showcase.phase, 1);
+
+    store_selected_messages(&mut env.conn, &id, &[selected("1", &picked[0])]).unwrap();
+
+    assert_eq!(load_showcase(&env.conn, &id).unwrap().phase, 2);
+    let stored = load_selected_messages(&env.conn, &id).unwrap();
+    assert_eq!(stored.len(), 1);
+    assert_eq!(stored[0].message_id, "1");
+    assert_eq!(stored[0].attachment_filenames(), picked);
+    assert!(env.is_used("1"));
+    assert!(!env.is_used("2"));
+}
+
+#[test]
+fn used_flag_is_reference_counted_across_showcases() {
+    let mut env = TestEnv::new();
+    let now = Utc::now().timestamp();
+    let first = env.index(&message(1, 501, "Sunset", now, &[11]));
+    let second = env.index(&message(2, 502, "Harbour", now, &[21]));
+
+    let weekly = insert_showcase(&env.conn, "Weekly", None).unwrap();
+    let monthly = insert_showcase(&env.conn, "Monthly", None).unwrap();
+    store_selected_messages(&mut env.conn, &weekly, &[selected("1", &first[0])]).unwrap();
+    store_selected_messages(
+        &mut env.conn,
+        &monthly,
+        &[selected("1", &first[0]), selected("2", &second[0])],
+    )
+    .unwrap();
+
+    // Still selected by the monthly showcase.
+    store_selected_messages(&mut env.conn, &weekly, &[]).unwrap();
+    assert!(env.is_used("1"));
+
+    store_selected_messages(&mut env.conn, &monthly, &[selected("2", &second[0])]).unwrap();
+    assert!(!env.is_used("1"));
+    assert!(env.is_used("2"));
+}
+
+#[test]
+fn repair_backfills_and_clears_used_flags() {
+    let mut env = TestEnv::new();
+    let now = Utc::now().timestamp();
+    let first = env.index(&message(1, 501, "Sunset", now, &[11]));
+    env.index(&message(2, 502, "Harbour", now, &[21]));
+
+    let id = insert_showcase(&env.conn, "Weekly", None).unwrap();
+    store_selected_messages(&mut env.conn, &id, &[selected("1", &first[0])]).unwrap();
+    // As left behind by versions that never cleared or set the flag.
+    env.conn
+        .execute(
+            "UPDATE messages SET is_used = CASE message_id WHEN '1' THEN 0 ELSE 1 END",
+            [],
+        )
+        .unwrap();
+
+    let repair = repair_all_used_flags(&mut env.conn).unwrap();
+    assert_eq!((repair.marked, repair.cleared), (1, 1));
+    assert!(env.is_used("1"));
+    assert!(!env.is_used("2"));
+}
@@ -0,0 +1,37 @@
+use super::TestEnv;
+use crate::error::ErrorKind;
+use crate::share_server::{ensure_shareable, tokens_match};
+use crate::showcase_manager::{insert_showcase, load_showcase};
+
+#[test]
+fn showcases_past_phase_one_can_still_be_shared() {
+    let env = TestEnv::new();
+    let id = insert_showcase(&env.conn, "Weekly", None).unwrap();
+    assert!(ensure_shareable(&load_showcase(&env.conn, &id).unwrap()).is_ok());
+
+    env.conn
+        .execute(
+            "UPDATE showcases SET phase = 2, status = 'In Progress' WHERE id = ?1",
+            [&id],
+        )
+        .unwrap();
+    assert!(ensure_shareable(&load_showcase(&env.conn, &id).unwrap()).is_ok());
+
+    env.conn
+        .execute(
+            "UPDATE showcases SET phase = 4, status = 'Completed' WHERE id = ?1",
+            [&id],
+        )
+        .unwrap();
+    let err = ensure_shareable(&load_showcase(&env.conn, &id).unwrap()).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::InvalidInput);
+}
+
+#[test]
+fn only_the_exact_token_opens_the_link() {
+    let token = "0123456789abcdef0123456789abcdef";
+    assert!(tokens_match(token, token));
+    assert!(!tokens_match("0123456789abcdef0123456789abcdee", token));
+    assert!(!tokens_match("0123456789abcdef", token));
+    assert!(!tokens_match("", token));
+}
@@ -0,0 +1,179 @@
+use image::{DynamicImage, ImageFormat, Rgba, RgbaImage};
+use std::fs;
+
+use super::TestEnv;
+use crate::error::ErrorKind;
+use crate::models::ShowcaseImage;
+use crate::slide_render::{
+    draw_overlay, parse_image_format, parse_resolution, preview_slide, render_slide,
+    write_slide_images, SlidePlan,
+};
+
+fn overlay_slide() -> ShowcaseImage {
+    serde_json::from_value(serde_json::json!({
+        "message_id": "1",
+        "sender": "artist501",
+        "avatar": "",
+        "message": "Sunset over the harbour",
+        "is_edited": false,
+        "overlay": {
+            "position": "bottom-left",
+            "style": "black",
+            "showAvatar": false,
+            "width": 800.0,
+            "transparency": 0
+        }
+    }))
+    .unwrap()
+}
+
+#[test]
+fn resolutions_and_formats_are_validated() {
+    assert_eq!(parse_resolution(None).unwrap(), (1920, 1080));
+    assert_eq!(parse_resolution(Some("4K")).unwrap(), (3840, 2160));
+    assert_eq!(parse_resolution(Some("1080 x 1080")).unwrap(), (1080, 1080));
+    assert!(parse_resolution(Some("8x8")).is_err());
+    assert!(parse_resolution(Some("huge")).is_err());
+    assert_eq!(parse_image_format("JPEG").unwrap(), "jpg");
+    assert!(parse_image_format("gif").is_err());
+}
+
+#[test]
+fn slides_are_fitted_on_black_with_the_overlay_drawn() {
+    let env = TestEnv::new();
+    let original = env.image_dir().join("cached").join("1_11.png");
+    fs::create_dir_all(original.parent().unwrap()).unwrap();
+    DynamicImage::ImageRgba8(RgbaImage::from_pixel(200, 200, Rgba([255, 0, 0, 255])))
+        .save_with_format(&original, ImageFormat::Png)
+        .unwrap();
+
+    let plans = vec![
+        SlidePlan {
+            slide_key: "1".to_string(),
+            render: None,
+            original: Some(original),
+            overlay: Some(overlay_slide()),
+            avatar: None,
+        },
+        SlidePlan {
+            slide_key: "2".to_string(),
+            render: None,
+            original: Some(env.image_dir().join("cached").join("missing.png")),
+            overlay: None,
+            avatar: None,
+        },
+    ];
+    let folder = env.data_dir.join("slides");
+    let (files, skipped) = write_slide_images(&plans, &folder, "png", (640, 360)).unwrap();
+    assert_eq!(files, vec!["slide_001.png"]);
+    assert_eq!(skipped, vec!["2"]);
+
+    let slide = image::open(folder.join(&files[0])).unwrap().to_rgba8();
+    assert_eq!(slide.dimensions(), (640, 360));
+    // Letterboxed on black, the picture in the middle, the opaque panel over its bottom left.
+    assert_eq!(slide.get_pixel(10, 180), &Rgba([0, 0, 0, 255]));
+    assert_eq!(slide.get_pixel(320, 180), &Rgba([255, 0, 0, 255]));
+    assert_eq!(slide.get_pixel(150, 352), &Rgba([0, 0, 0, 255]));
+    assert_eq!(slide.get_pixel(400, 352), &Rgba([255, 0, 0, 255]));
+}
+
+#[test]
+fn previews_can_bypass_the_uploaded_render() {
+    let env = TestEnv::new();
+    let dir = env.image_dir().join("cached");
+    fs::create_dir_all(&dir).unwrap();
+    let original = dir.join("1_11.png");
+    let render = dir.join("render.png");
+    for (path, color) in [(&original, [255, 0, 0, 255]), (&render, [0, 0, 255, 255])] {
+        DynamicImage::ImageRgba8(RgbaImage::from_pixel(64, 64, Rgba(color)))
+            .save_with_format(path, ImageFormat::Png)
+            .unwrap();
+    }
+    let plans = || {
+        vec![SlidePlan {
+            slide_key: "1".to_string(),
+            render: Some(render.clone()),
+            original: Some(original.clone()),
+            overlay: Some(overlay_slide()),
+            avatar: None,
+        }]
+    };
+    let center = |png: Vec<u8>| {
+        image::load_from_memory(&png)
+            .unwrap()
+            .to_rgba8()
+            .get_pixel(32, 32)
+            .0
+    };
+
+    let uploaded = preview_slide(plans(), "1", false, (64, 64)).unwrap();
+    assert_eq!(center(uploaded), [0, 0, 255, 255]);
+    let composited = preview_slide(plans(), "1", true, (64, 64)).unwrap();
+    assert_eq!(center(composited), [255, 0, 0, 255]);
+
+    let err = preview_slide(plans(), "2", false, (64, 64)).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::NotFound);
+}
+
+#[test]
+fn uploaded_render_stands_in_for_a_missing_original() {
+    let env = TestEnv::new();
+    let dir = env.image_dir().join("cached");
+    fs::create_dir_all(&dir).unwrap();
+    let render = dir.join("render.png");
+    DynamicImage::ImageRgba8(RgbaImage::from_pixel(64, 64, Rgba([0, 0, 255, 255])))
+        .save_with_format(&render, ImageFormat::Png)
+        .unwrap();
+    let plan = SlidePlan {
+        slide_key: "1".to_string(),
+        render: Some(render),
+        original: Some(dir.join("evicted.png")),
+        overlay: Some(overlay_slide()),
+        avatar: None,
+    };
+
+    let slide = render_slide(&plan, 64, 64).unwrap();
+    assert_eq!(slide.get_pixel(32, 32), &Rgba([0, 0, 255, 255]));
+}
+
+#[test]
+fn overlays_show_the_credit_in_place_of_the_sender() {
+    let env = TestEnv::new();
+    let original = env.image_dir().join("original.png");
+    DynamicImage::ImageRgba8(RgbaImage::from_pixel(64, 36, Rgba([255, 0, 0, 255])))
+        .save_with_format(&original, ImageFormat::Png)
+        .unwrap();
+    let draw = |overlay: ShowcaseImage| {
+        let plan = SlidePlan {
+            slide_key: "1".to_string(),
+            render: None,
+            original: Some(original.clone()),
+            overlay: Some(overlay),
+            avatar: None,
+        };
+        render_slide(&plan, 960, 540).unwrap()
+    };
+
+    let mut credited = overlay_slide();
+    credited.credit = Some("Art by Zoë (zoe.example)".to_string());
+    let mut renamed = overlay_slide();
+    renamed.sender = "Art by Zoë (zoe.example)".to_string();
+    assert_eq!(draw(credited.clone()), draw(renamed));
+    assert_ne!(draw(credited), draw(overlay_slide()));
+}
+
+#[test]
+fn overlay_text_is_drawn_for_any_script() {
+    let slide = overlay_slide();
+    for (sender, message) in [
+        ("Zoë Ångström", "Ночной порт"),
+        ("Αλέξης", "Ωραίο φως"),
+        ("", ""),
+    ] {
+        let mut canvas = RgbaImage::from_pixel(960, 540, Rgba([255, 0, 0, 255]));
+        draw_overlay(&mut canvas, &slide.overlay, sender, message, None);
+        // White text on the black panel; neither the panel nor the red picture has any green.
+        let text_pixels = canvas.pixels().filter(|pixel| pixel[1] > 128).count();
+        assert_eq!(text_pixels > 100, !sender.is_empty(), "{:?}", sender);
+    }
+}
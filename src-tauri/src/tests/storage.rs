@@ -0,0 +1,62 @@
+use chrono::Utc;
+use std::fs;
+
+use super::{message, TestEnv, PNG_BYTES};
+use crate::showcase_manager::{
+    get_showcase_image_dir, get_showcase_presentation_dir, insert_showcase,
+};
+use crate::sqlite_manager::{channel_storage, showcase_storage};
+
+#[test]
+fn storage_is_broken_down_per_showcase_and_channel() {
+    let mut env = TestEnv::new();
+    let now = Utc::now().timestamp();
+    env.index(&message(1, 501, "Sunset", now, &[11, 12]));
+    env.index(&message(2, 502, "Harbour", now, &[21]));
+    env.conn
+        .execute(
+            "UPDATE messages SET channel_id = '200' WHERE message_id = '2'",
+            [],
+        )
+        .unwrap();
+
+    let small = insert_showcase(&env.conn, "Small", None).unwrap();
+    let large = insert_showcase(&env.conn, "Large", None).unwrap();
+    env.conn
+        .execute(
+            "INSERT INTO showcase_messages (showcase_id, message_id, position, channel_id, author_id, author_name, message_content, attachment_filenames_json, timestamp)
+             VALUES (?1, '1', 0, '100', '501', 'artist501', 'Sunset', '[]', 0), (?1, '2', 1, '200', '502', 'artist502', 'Harbour', '[]', 0)",
+            [&large],
+        )
+        .unwrap();
+    let image_dir = get_showcase_image_dir(&env.data_dir, &large).unwrap();
+    fs::create_dir_all(&image_dir).unwrap();
+    fs::write(image_dir.join("slide.png"), vec![0u8; 1000]).unwrap();
+    let presentation_dir = get_showcase_presentation_dir(&env.data_dir, &large).unwrap();
+    fs::create_dir_all(&presentation_dir).unwrap();
+    fs::write(presentation_dir.join("deck.pptx"), vec![0u8; 500]).unwrap();
+
+    let showcases = showcase_storage(&env.conn, &env.data_dir).unwrap();
+    assert_eq!(showcases.len(), 2);
+    assert_eq!(showcases[0].showcase_id, large);
+    assert_eq!(showcases[0].image_dir_size_bytes, 1000);
+    assert_eq!(showcases[0].presentation_size_bytes, 500);
+    assert_eq!(showcases[0].message_count, 2);
+    assert_eq!(showcases[1].showcase_id, small);
+    assert_eq!(showcases[1].image_dir_size_bytes, 0);
+
+    let channels = channel_storage(&env.conn, &env.image_dir()).unwrap();
+    let summary: Vec<(&str, i64, u64, u64)> = channels
+        .iter()
+        .map(|c| {
+            (
+                c.channel_id.as_str(),
+                c.message_count,
+                c.cache_file_count,
+                c.cache_size_bytes,
+            )
+        })
+        .collect();
+    let png = PNG_BYTES.len() as u64;
+    assert_eq!(summary, vec![("100", 1, 2, 2 * png), ("200", 1, 1, png)]);
+}
@@ -0,0 +1,202 @@
+use std::fs;
+
+use super::TestEnv;
+use crate::error::ErrorKind;
+use crate::models::{OverlayPosition, ShowcaseImage, ShowcaseImagePatch};
+use crate::showcase_manager::{
+    ensure_valid_message_id, find_slide_image, insert_showcase, load_showcase_images, patch_slide,
+    publish_slide_image, remove_slide, save_uploaded_slide, save_uploaded_slides,
+    stage_slide_image,
+};
+use crate::showcase_revisions::load_revisions;
+
+fn slide(message_id: &str) -> ShowcaseImage {
+    serde_json::from_value(serde_json::json!({
+        "message_id": message_id,
+        "sender": "artist501",
+        "avatar": "",
+        "message": "Sunset",
+        "is_edited": false,
+        "overlay": {
+            "position": "bottom-left",
+            "style": "black",
+            "showAvatar": false,
+            "width": 40.0,
+            "transparency": 0
+        }
+    }))
+    .unwrap()
+}
+
+#[test]
+fn staged_uploads_are_invisible_until_published() {
+    let mut env = TestEnv::new();
+    let id = insert_showcase(&env.conn, "Weekly", None).unwrap();
+    let dir = env.image_dir().join(&id);
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join(format!("{}_1.jpg", id)), b"old render").unwrap();
+    fs::write(dir.join(format!("{}_1_1.jpg", id)), b"second attachment").unwrap();
+
+    let filename = format!("{}_1.png", id);
+    let staged = stage_slide_image(&dir, &filename, b"new render").unwrap();
+    assert_eq!(
+        find_slide_image(&dir, &id, "1").unwrap(),
+        dir.join(format!("{}_1.jpg", id))
+    );
+
+    save_uploaded_slide(&mut env.conn, &id, &slide("1")).unwrap();
+    publish_slide_image(&staged, &dir.join(&filename)).unwrap();
+    assert!(!staged.exists());
+    assert_eq!(
+        fs::read(find_slide_image(&dir, &id, "1").unwrap()).unwrap(),
+        b"new render"
+    );
+    assert!(!dir.join(format!("{}_1.jpg", id)).exists());
+    assert!(dir.join(format!("{}_1_1.jpg", id)).exists());
+    assert_eq!(load_showcase_images(&env.conn, &id).unwrap().len(), 1);
+}
+
+#[test]
+fn concurrent_uploads_of_a_slide_stage_separately() {
+    let env = TestEnv::new();
+    let dir = env.image_dir().join("showcase");
+    let first = stage_slide_image(&dir, "showcase_1.png", b"first").unwrap();
+    let second = stage_slide_image(&dir, "showcase_1.png", b"second").unwrap();
+    assert_ne!(first, second);
+    assert_eq!(fs::read(&first).unwrap(), b"first");
+    assert_eq!(fs::read(&second).unwrap(), b"second");
+
+    assert!(ensure_valid_message_id("1234567890").is_ok());
+    for bad in ["", "../1", "1/2", "12a", "1.png"] {
+        let err = ensure_valid_message_id(bad).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::InvalidInput);
+    }
+}
+
+#[test]
+fn failed_saves_write_no_slide_row() {
+    let mut env = TestEnv::new();
+    let err = save_uploaded_slide(&mut env.conn, "missing", &slide("1")).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::NotFound);
+    let rows: i64 = env
+        .conn
+        .query_row("SELECT COUNT(*) FROM showcase_images", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(rows, 0);
+}
+
+#[test]
+fn batch_saves_are_one_revision() {
+    let mut env = TestEnv::new();
+    let id = insert_showcase(&env.conn, "Weekly", None).unwrap();
+    save_uploaded_slides(&mut env.conn, &id, &[slide("1"), slide("2"), slide("3")]).unwrap();
+
+    let keys: Vec<String> = load_showcase_images(&env.conn, &id)
+        .unwrap()
+        .iter()
+        .map(|image| image.slide_key())
+        .collect();
+    assert_eq!(keys, ["1", "2", "3"]);
+    let revisions = load_revisions(&env.conn, &id).unwrap();
+    assert_eq!(revisions.len(), 1);
+    assert_eq!(revisions[0].slide_count, 0);
+}
+
+#[test]
+fn removing_a_slide_deletes_its_files_and_row() {
+    let mut env = TestEnv::new();
+    let id = insert_showcase(&env.conn, "Weekly", None).unwrap();
+    let mut second_attachment = slide("1");
+    second_attachment.attachment_index = 1;
+    save_uploaded_slides(
+        &mut env.conn,
+        &id,
+        &[slide("1"), second_attachment, slide("2")],
+    )
+    .unwrap();
+    let dir = env.image_dir().join(&id);
+    fs::create_dir_all(&dir).unwrap();
+    for name in ["1.png", "1_1.jpg", "2.png"] {
+        fs::write(dir.join(format!("{}_{}", id, name)), b"render").unwrap();
+    }
+
+    assert_eq!(
+        remove_slide(&env.data_dir, &mut env.conn, &id, "1", None).unwrap(),
+        2
+    );
+    let keys: Vec<String> = load_showcase_images(&env.conn, &id)
+        .unwrap()
+        .iter()
+        .map(|image| image.slide_key())
+        .collect();
+    assert_eq!(keys, ["2"]);
+    let mut files: Vec<String> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    files.sort();
+    assert_eq!(files, [format!("{}_2.png", id)]);
+
+    let err = remove_slide(&env.data_dir, &mut env.conn, &id, "1", None).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::NotFound);
+}
+
+#[test]
+fn removing_one_attachment_keeps_the_other_slides_of_the_message() {
+    let mut env = TestEnv::new();
+    let id = insert_showcase(&env.conn, "Weekly", None).unwrap();
+    let mut second_attachment = slide("1");
+    second_attachment.attachment_index = 1;
+    save_uploaded_slides(&mut env.conn, &id, &[slide("1"), second_attachment]).unwrap();
+    let dir = env.image_dir().join(&id);
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join(format!("{}_1.png", id)), b"first").unwrap();
+    fs::write(dir.join(format!("{}_1_1.png", id)), b"second").unwrap();
+
+    assert_eq!(
+        remove_slide(&env.data_dir, &mut env.conn, &id, "1", Some(1)).unwrap(),
+        1
+    );
+    let images = load_showcase_images(&env.conn, &id).unwrap();
+    assert_eq!(images.len(), 1);
+    assert_eq!(images[0].slide_key(), "1");
+    assert!(dir.join(format!("{}_1.png", id)).exists());
+    assert!(!dir.join(format!("{}_1_1.png", id)).exists());
+}
+
+#[test]
+fn patches_change_only_the_given_fields() {
+    let mut env = TestEnv::new();
+    let id = insert_showcase(&env.conn, "Weekly", None).unwrap();
+    save_uploaded_slides(&mut env.conn, &id, &[slide("1"), slide("2")]).unwrap();
+
+    let patch = ShowcaseImagePatch {
+        position: Some(OverlayPosition::Hidden),
+        caption: Some("Golden hour".to_string()),
+        notes: Some("Mention the tide".to_string()),
+        ..Default::default()
+    };
+    let patched = patch_slide(&mut env.conn, &id, "2", patch).unwrap();
+    assert_eq!(patched.overlay.position, OverlayPosition::Hidden);
+    assert_eq!(patched.message, "Golden hour");
+    assert!(!patched.overlay.show_avatar);
+
+    let images = load_showcase_images(&env.conn, &id).unwrap();
+    assert_eq!(images[0].message, "Sunset");
+    assert_eq!(images[1].slide_key(), "2");
+    assert_eq!(images[1].message, "Golden hour");
+    assert_eq!(images[1].overlay.position, OverlayPosition::Hidden);
+    assert_eq!(images[1].notes.as_deref(), Some("Mention the tide"));
+
+    let clear = ShowcaseImagePatch {
+        notes: Some(String::new()),
+        ..Default::default()
+    };
+    assert_eq!(
+        patch_slide(&mut env.conn, &id, "2", clear).unwrap().notes,
+        None
+    );
+
+    let err = patch_slide(&mut env.conn, &id, "3", ShowcaseImagePatch::default()).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::NotFound);
+}
@@ -0,0 +1,106 @@
+use std::path::{Path, PathBuf};
+
+use super::TestEnv;
+use crate::models::VideoSettings;
+use crate::video::{ffmpeg_args, resolve_video_settings, Transition};
+
+fn args_text(args: &[std::ffi::OsString]) -> Vec<String> {
+    args.iter()
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect()
+}
+
+#[test]
+fn video_settings_default_and_validate() {
+    let plan = resolve_video_settings(&VideoSettings::default()).unwrap();
+    assert_eq!(plan.slide_secs, 5.0);
+    assert_eq!(plan.transition, Transition::Fade);
+    assert_eq!((plan.width, plan.height), (1920, 1080));
+    assert_eq!(plan.total_secs(3), 15.0);
+
+    let cut = resolve_video_settings(&VideoSettings {
+        transition: Some("None".to_string()),
+        ..Default::default()
+    })
+    .unwrap();
+    assert_eq!((cut.transition, cut.fade_secs), (Transition::Cut, 0.0));
+
+    let invalid = [
+        VideoSettings {
+            slide_duration: Some(0.1),
+            ..Default::default()
+        },
+        VideoSettings {
+            transition: Some("wipe".to_string()),
+            ..Default::default()
+        },
+        VideoSettings {
+            slide_duration: Some(2.0),
+            transition_duration: Some(2.0),
+            ..Default::default()
+        },
+        VideoSettings {
+            resolution: Some("1081x720".to_string()),
+            ..Default::default()
+        },
+        VideoSettings {
+            music_path: Some("/nonexistent/track.mp3".to_string()),
+            ..Default::default()
+        },
+    ];
+    for settings in invalid {
+        assert!(resolve_video_settings(&settings).is_err(), "{:?}", settings);
+    }
+}
+
+#[test]
+fn fades_overlap_slides_and_music_is_cut_to_length() {
+    let env = TestEnv::new();
+    let music = env.data_dir.join("track.mp3");
+    std::fs::write(&music, b"not really audio").unwrap();
+    let plan = resolve_video_settings(&VideoSettings {
+        slide_duration: Some(4.0),
+        transition_duration: Some(1.0),
+        music_path: Some(music.to_string_lossy().into_owned()),
+        ..Default::default()
+    })
+    .unwrap();
+    let frames: Vec<PathBuf> = (1..=3)
+        .map(|i| PathBuf::from(format!("slide_{:03}.png", i)))
+        .collect();
+    let args = args_text(&ffmpeg_args(&frames, &plan, Path::new("out.mp4.part")));
+
+    // Every slide but the last runs one fade longer so the next can blend in.
+    let durations: Vec<&str> = args
+        .windows(2)
+        .filter(|pair| pair[0] == "-t")
+        .map(|pair| pair[1].as_str())
+        .collect();
+    assert_eq!(durations, ["5.000", "5.000", "4.000", "12.000"]);
+
+    let graph = &args[args.iter().position(|a| a == "-filter_complex").unwrap() + 1];
+    assert!(graph.contains("[v0][v1]xfade=transition=fade:duration=1.000:offset=4.000[x1]"));
+    assert!(graph.contains("[x1][v2]xfade=transition=fade:duration=1.000:offset=8.000[vout]"));
+    assert!(graph.contains("[3:a]afade=t=out:st=10.000:d=2.000[aout]"));
+    assert!(args.windows(2).any(|pair| pair == ["-stream_loop", "-1"]));
+    assert_eq!(args.last().unwrap(), "out.mp4.part");
+}
+
+#[test]
+fn cuts_concatenate_and_a_single_slide_needs_no_transition() {
+    let plan = resolve_video_settings(&VideoSettings {
+        transition: Some("none".to_string()),
+        ..Default::default()
+    })
+    .unwrap();
+    let frames = [PathBuf::from("a.png"), PathBuf::from("b.png")];
+    let args = args_text(&ffmpeg_args(&frames, &plan, Path::new("out.mp4")));
+    let graph = &args[args.iter().position(|a| a == "-filter_complex").unwrap() + 1];
+    assert!(graph.ends_with("[v0][v1]concat=n=2:v=1:a=0[vout]"));
+    assert!(!args.iter().any(|a| a == "[aout]"));
+
+    let fade = resolve_video_settings(&VideoSettings::default()).unwrap();
+    let args = args_text(&ffmpeg_args(&frames[..1], &fade, Path::new("out.mp4")));
+    assert!(!args.iter().any(|a| a.contains("xfade")));
+    assert!(args.windows(2).any(|pair| pair == ["-map", "[v0]"]));
+}
@@ -0,0 +1,168 @@
+use image::codecs::jpeg::JpegEncoder;
+use rusqlite::Connection;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, State};
+
+use crate::cache_crypto;
+use crate::discord::write_cache_file;
+use crate::error::{AppError, ResultExt};
+use crate::models::ThumbnailSettings;
+use crate::sqlite_manager::{attachment_type_from_path, get_image_base_dir, retrieve_config, DbConnection};
+use crate::{log_info as info, log_warn as warn};
+
+use base64::{engine::general_purpose::STANDARD as base64_engine, Engine as _};
+
+pub const THUMBNAIL_DIR: &str = "thumbs";
+const MIN_THUMBNAIL_DIMENSION: u32 = 64;
+const MAX_THUMBNAIL_DIMENSION: u32 = 2048;
+
+// Each size/quality combination gets its own folder, so changing the settings
+// never serves a thumbnail rendered with the old ones.
+fn variant_dir(base_dir: &Path, settings: &ThumbnailSettings) -> PathBuf {
+    base_dir.join(THUMBNAIL_DIR).join(format!(
+        "{}q{}",
+        settings.max_dimension, settings.quality
+    ))
+}
+
+pub fn thumbnail_path(base_dir: &Path, relative_path: &str, settings: &ThumbnailSettings) -> PathBuf {
+    variant_dir(base_dir, settings)
+        .join(relative_path)
+        .with_extension("jpg")
+}
+
+pub fn sanitize_settings(settings: ThumbnailSettings) -> ThumbnailSettings {
+    ThumbnailSettings {
+        max_dimension: settings
+            .max_dimension
+            .clamp(MIN_THUMBNAIL_DIMENSION, MAX_THUMBNAIL_DIMENSION),
+        quality: settings.quality.clamp(1, 100),
+        pregenerate: settings.pregenerate,
+    }
+}
+
+fn render_thumbnail(source_bytes: &[u8], settings: &ThumbnailSettings) -> Result<Vec<u8>, String> {
+    let image = image::load_from_memory(source_bytes)
+        .context("Failed to decode image")?;
+    let thumbnail = image
+        .thumbnail(settings.max_dimension, settings.max_dimension)
+        .to_rgb8();
+
+    let mut jpeg_bytes = Vec::new();
+    JpegEncoder::new_with_quality(&mut jpeg_bytes, settings.quality)
+        .encode_image(&thumbnail)
+        .context("Failed to encode thumbnail")?;
+    Ok(jpeg_bytes)
+}
+
+// Returns the JPEG bytes, rendering and caching the thumbnail when it does not exist yet.
+pub fn load_or_generate_thumbnail(
+    base_dir: &Path,
+    relative_path: &str,
+    settings: &ThumbnailSettings,
+    encrypt: bool,
+) -> Result<Vec<u8>, String> {
+    if attachment_type_from_path(relative_path) == "video" {
+        return Err(format!("Thumbnails are not generated for videos: {}", relative_path));
+    }
+
+    let thumb_path = thumbnail_path(base_dir, relative_path, settings);
+    if let Ok(bytes) = fs::read(&thumb_path) {
+        return cache_crypto::decrypt_if_encrypted(bytes);
+    }
+
+    let source_path = base_dir.join(relative_path);
+    let source_bytes = fs::read(&source_path)
+        .context(format!("Failed to read '{}'", source_path.display()))?;
+    let source_bytes = cache_crypto::decrypt_if_encrypted(source_bytes)?;
+
+    let jpeg_bytes = render_thumbnail(&source_bytes, settings)?;
+    write_cache_file(&thumb_path, &jpeg_bytes, encrypt)?;
+    info!("Generated thumbnail: {}", thumb_path.display());
+    Ok(jpeg_bytes)
+}
+
+// Eager policy: called after indexing downloads new files. Failures only cost a lazy render later.
+pub async fn pregenerate_thumbnails(
+    base_dir: PathBuf,
+    relative_paths: Vec<String>,
+    settings: ThumbnailSettings,
+    encrypt: bool,
+) {
+    let result = tokio::task::spawn_blocking(move || {
+        for relative_path in relative_paths
+            .iter()
+            .filter(|p| attachment_type_from_path(p) != "video")
+        {
+            if let Err(e) = load_or_generate_thumbnail(&base_dir, relative_path, &settings, encrypt) {
+                warn!("Thumbnail pre-generation failed for {}: {}", relative_path, e);
+            }
+        }
+    })
+    .await;
+    if let Err(e) = result {
+        warn!("Thumbnail pre-generation task failed: {}", e);
+    }
+}
+
+// Removes every size variant of a cached file's thumbnail.
+pub fn remove_thumbnails(base_dir: &Path, relative_path: &str) -> usize {
+    let Ok(entries) = fs::read_dir(base_dir.join(THUMBNAIL_DIR)) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| entry.path().join(relative_path).with_extension("jpg"))
+        .filter(|path| path.exists() && fs::remove_file(path).is_ok())
+        .count()
+}
+
+// The configured thumbnail settings and whether new thumbnails are written encrypted.
+pub(crate) fn read_settings(conn: &Connection) -> Result<(ThumbnailSettings, bool), String> {
+    let config = retrieve_config(conn)?;
+    Ok((
+        sanitize_settings(config.thumbnail_settings.unwrap_or_default()),
+        config.encrypt_image_cache.unwrap_or(false),
+    ))
+}
+
+pub(crate) async fn current_settings(db_state: &DbConnection) -> Result<(ThumbnailSettings, bool), AppError> {
+    db_state.0.run(|conn| Ok(read_settings(conn)?)).await
+}
+
+// `relative_path` must already be validated by the calling command.
+pub(crate) async fn thumbnail_data_uri(
+    app_handle: &AppHandle,
+    db_state: &DbConnection,
+    relative_path: String,
+) -> Result<String, AppError> {
+    let (settings, encrypt) = current_settings(db_state).await?;
+
+    let base_dir = get_image_base_dir(app_handle)?;
+    let jpeg_bytes = tokio::task::spawn_blocking(move || {
+        load_or_generate_thumbnail(&base_dir, &relative_path, &settings, encrypt)
+    })
+    .await
+    .context("Thumbnail task failed")??;
+
+    Ok(format!(
+        "data:image/jpeg;base64,{}",
+        base64_engine.encode(&jpeg_bytes)
+    ))
+}
+
+#[tauri::command]
+pub async fn get_cached_image_thumbnail(
+    app_handle: AppHandle,
+    relative_path: String,
+    db_state: State<'_, DbConnection>,
+) -> Result<String, AppError> {
+    if relative_path.contains("..")
+        || relative_path.starts_with('/')
+        || relative_path.starts_with('\\')
+    {
+        return Err(AppError::invalid_input("Invalid relative path provided."));
+    }
+    thumbnail_data_uri(&app_handle, &db_state, relative_path).await
+}
@@ -0,0 +1,143 @@
+use chrono::Utc;
+use rusqlite::{params, OptionalExtension};
+use tauri::State;
+
+use crate::error::{AppError, ResultExt};
+use crate::models::IndexedMessage;
+use crate::sqlite_manager::{
+    map_row_to_indexed_message, message_order_clause, DbConnection, INDEXED_MESSAGE_COLUMNS,
+};
+use crate::log_info as info;
+
+const DECISIONS: [&str; 3] = ["accept", "reject", "maybe"];
+
+#[derive(serde::Serialize, Clone, Debug, Default)]
+pub struct TriageNext {
+    pub message: Option<IndexedMessage>, // None once everything has been reviewed
+    pub remaining: i64,
+    pub accepted: i64,
+    pub rejected: i64,
+    pub maybe: i64,
+}
+
+// The decisions themselves are the review cursor: the next message is the first one without
+// a decision in the chosen order. With revisit_maybe, "maybe" messages are served instead,
+// least recently reviewed first, so deciding "maybe" again moves a message to the back.
+#[tauri::command]
+pub async fn get_next_unreviewed_message(
+    sort_by: Option<String>,
+    revisit_maybe: Option<bool>,
+    db_state: State<'_, DbConnection>,
+) -> Result<TriageNext, AppError> {
+    db_state
+        .0
+        .run(move |conn| {
+            let (condition, order_by) = if revisit_maybe.unwrap_or(false) {
+                ("review_decision = 'maybe'", "reviewed_at ASC, timestamp DESC")
+            } else {
+                ("review_decision IS NULL", message_order_clause(sort_by.as_deref())?)
+            };
+            let message = conn
+                .query_row(
+                    &format!(
+                        "SELECT {} FROM messages WHERE deleted = 0 AND {} ORDER BY {} LIMIT 1",
+                        INDEXED_MESSAGE_COLUMNS, condition, order_by
+                    ),
+                    [],
+                    map_row_to_indexed_message,
+                )
+                .optional()
+                .context("Failed to load next message to review")?;
+
+            let (remaining, accepted, rejected, maybe) = conn
+                .query_row(
+                    "SELECT
+                        COALESCE(SUM(review_decision IS NULL), 0),
+                        COALESCE(SUM(review_decision = 'accept'), 0),
+                        COALESCE(SUM(review_decision = 'reject'), 0),
+                        COALESCE(SUM(review_decision = 'maybe'), 0)
+                     FROM messages WHERE deleted = 0",
+                    [],
+                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+                )
+                .context("Failed to count review decisions")?;
+
+            Ok(TriageNext {
+                message,
+                remaining,
+                accepted,
+                rejected,
+                maybe,
+            })
+        })
+        .await
+}
+
+// Favorites are a shortlist that survives across sessions and is not tied to any showcase,
+// unlike is_used. Filter on them with favoritesOnly.
+#[tauri::command]
+pub async fn set_message_favorite(
+    message_id: String,
+    favorite: bool,
+    db_state: State<'_, DbConnection>,
+) -> Result<(), AppError> {
+    db_state
+        .0
+        .run(move |conn| {
+            let updated = conn
+                .execute(
+                    "UPDATE messages SET is_favorite = ?1 WHERE message_id = ?2",
+                    params![favorite, message_id],
+                )
+                .context(format!("Failed to update favorite for {}", message_id))?;
+            if updated == 0 {
+                return Err(AppError::not_found(format!("Message {} not found", message_id)));
+            }
+            info!(
+                "Message {} {}",
+                message_id,
+                if favorite { "added to favorites" } else { "removed from favorites" }
+            );
+            Ok(())
+        })
+        .await
+}
+
+// Pass None as the decision to put a message back in the unreviewed queue (undo).
+#[tauri::command]
+pub async fn record_selection_decision(
+    message_id: String,
+    decision: Option<String>,
+    db_state: State<'_, DbConnection>,
+) -> Result<(), AppError> {
+    if let Some(decision) = decision.as_deref() {
+        if !DECISIONS.contains(&decision) {
+            return Err(AppError::invalid_input(format!(
+                "Unknown review decision '{}', expected one of: {}",
+                decision,
+                DECISIONS.join(", ")
+            )));
+        }
+    }
+    db_state
+        .0
+        .run(move |conn| {
+            let reviewed_at = decision.as_ref().map(|_| Utc::now().timestamp());
+            let updated = conn
+                .execute(
+                    "UPDATE messages SET review_decision = ?1, reviewed_at = ?2 WHERE message_id = ?3",
+                    params![decision, reviewed_at, message_id],
+                )
+                .context(format!("Failed to record decision for {}", message_id))?;
+            if updated == 0 {
+                return Err(AppError::not_found(format!("Message {} not found", message_id)));
+            }
+            info!(
+                "Review decision for {}: {}",
+                message_id,
+                decision.as_deref().unwrap_or("cleared")
+            );
+            Ok(())
+        })
+        .await
+}
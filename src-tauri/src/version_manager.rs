@@ -3,6 +3,11 @@ use reqwest;
 use semver::Version;
 use serde::{Deserialize, Serialize};
 use std::error::Error;
+use tauri::State;
+
+use crate::error::AppError;
+use crate::network::configured_http_client;
+use crate::sqlite_manager::DbConnection;
 
 #[derive(Debug, Deserialize)]
 struct GitHubRelease {
@@ -25,8 +30,9 @@ pub struct SimpleVersionInfo {
 
 pub const CURRENT_VERSION: &str = "0.1.3-beta";
 
-async fn fetch_releases() -> Result<Vec<GitHubRelease>, Box<dyn Error + Send + Sync>> {
-    let client = reqwest::Client::new();
+async fn fetch_releases(
+    client: &reqwest::Client,
+) -> Result<Vec<GitHubRelease>, Box<dyn Error + Send + Sync>> {
     let releases = client
         .get("https://api.github.com/repos/MegalithOfficial/Showcase-Studio/releases")
         .header("User-Agent", "Showcase-Studio-App")
@@ -81,8 +87,12 @@ fn should_update(current_version: &str, latest_version: &str) -> bool {
 }
 
 #[tauri::command]
-pub async fn check_for_updates(current_version: String) -> Result<VersionInfo, String> {
-    let releases = fetch_releases().await.map_err(|e| e.to_string())?;
+pub async fn check_for_updates(
+    current_version: String,
+    db_state: State<'_, DbConnection>,
+) -> Result<VersionInfo, AppError> {
+    let client = configured_http_client(&db_state, None).await?;
+    let releases = fetch_releases(&client).await.map_err(|e| e.to_string())?;
 
     if let Some(latest_release) = find_latest_release(&releases) {
         let (latest_version, branch) = parse_version_info(&latest_release.tag_name);
@@ -94,7 +104,7 @@ pub async fn check_for_updates(current_version: String) -> Result<VersionInfo, S
             should_update: update_available,
         })
     } else {
-        Err("No releases found".to_string())
+        Err(AppError::not_found("No releases found"))
     }
 }
 
@@ -126,8 +136,9 @@ pub fn get_current_version() -> SimpleVersionInfo {
 }
 
 #[tauri::command]
-pub async fn get_update_github_link() -> Result<String, String> {
-    let releases = fetch_releases().await.map_err(|e| e.to_string())?;
+pub async fn get_update_github_link(db_state: State<'_, DbConnection>) -> Result<String, AppError> {
+    let client = configured_http_client(&db_state, None).await?;
+    let releases = fetch_releases(&client).await.map_err(|e| e.to_string())?;
 
     if let Some(latest_release) = find_latest_release(&releases) {
         let tag_name = &latest_release.tag_name;
@@ -137,6 +148,6 @@ pub async fn get_update_github_link() -> Result<String, String> {
         );
         Ok(github_url)
     } else {
-        Err("No releases found".to_string())
+        Err(AppError::not_found("No releases found"))
     }
 }
@@ -0,0 +1,373 @@
+use std::ffi::OsString;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, State};
+
+use crate::author_manager::resolve_showcase_credits;
+use crate::error::AppError;
+use crate::log_info as info;
+use crate::models::{VideoExport, VideoSettings};
+use crate::redaction::redact_text;
+use crate::showcase_manager::{
+    get_showcase_image_dir, get_showcase_presentation_dir, load_showcase, publish_export,
+};
+use crate::slide_render::{parse_resolution, plan_slides, write_slide_images};
+use crate::spans::Span;
+use crate::sqlite_manager::{get_image_base_dir, retrieve_config, DbConnection};
+
+const DEFAULT_SLIDE_SECS: f64 = 5.0;
+const MIN_SLIDE_SECS: f64 = 0.5;
+const MAX_SLIDE_SECS: f64 = 600.0;
+const DEFAULT_FADE_SECS: f64 = 1.0;
+const MUSIC_FADE_SECS: f64 = 2.0;
+const FRAME_RATE: u32 = 30;
+const VIDEO_FRAMES_DIR: &str = "video_frames";
+// Looked up on PATH; no encoder ships with the app.
+const FFMPEG: &str = "ffmpeg";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Transition {
+    Cut,
+    Fade,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct VideoPlan {
+    pub slide_secs: f64,
+    pub transition: Transition,
+    pub fade_secs: f64,
+    pub width: u32,
+    pub height: u32,
+    pub music: Option<PathBuf>,
+}
+
+impl VideoPlan {
+    // Fades overlap the next slide, so every slide adds exactly its own duration.
+    pub fn total_secs(&self, slides: usize) -> f64 {
+        self.slide_secs * slides as f64
+    }
+}
+
+pub(crate) fn resolve_video_settings(settings: &VideoSettings) -> Result<VideoPlan, AppError> {
+    let slide_secs = settings.slide_duration.unwrap_or(DEFAULT_SLIDE_SECS);
+    if !(MIN_SLIDE_SECS..=MAX_SLIDE_SECS).contains(&slide_secs) {
+        return Err(AppError::invalid_input(format!(
+            "Slide duration must be between {} and {} seconds.",
+            MIN_SLIDE_SECS, MAX_SLIDE_SECS
+        )));
+    }
+    let transition = match settings
+        .transition
+        .as_deref()
+        .map(|t| t.trim().to_ascii_lowercase())
+        .as_deref()
+    {
+        None | Some("") | Some("fade") => Transition::Fade,
+        Some("none") | Some("cut") => Transition::Cut,
+        Some(other) => {
+            return Err(AppError::invalid_input(format!(
+                "Unknown transition '{}'. Use 'fade' or 'none'.",
+                other
+            )))
+        }
+    };
+    let fade_secs = match transition {
+        Transition::Cut => 0.0,
+        Transition::Fade => settings.transition_duration.unwrap_or(DEFAULT_FADE_SECS),
+    };
+    if transition == Transition::Fade && !(fade_secs > 0.0 && fade_secs < slide_secs) {
+        return Err(AppError::invalid_input(
+            "The transition must be longer than zero and shorter than the slide duration.",
+        ));
+    }
+
+    // H.264 in yuv420p needs even dimensions.
+    let (width, height) = parse_resolution(settings.resolution.as_deref())?;
+    if width % 2 != 0 || height % 2 != 0 {
+        return Err(AppError::invalid_input(format!(
+            "Video resolution {}x{} must have an even width and height.",
+            width, height
+        )));
+    }
+
+    let music = match settings
+        .music_path
+        .as_deref()
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+    {
+        Some(path) => {
+            let path = PathBuf::from(path);
+            if !path.is_file() {
+                return Err(AppError::not_found(format!(
+                    "Music file '{}' does not exist.",
+                    path.display()
+                )));
+            }
+            Some(path)
+        }
+        None => None,
+    };
+
+    Ok(VideoPlan {
+        slide_secs,
+        transition,
+        fade_secs,
+        width,
+        height,
+        music,
+    })
+}
+
+// Each frame is looped as its own input. With fades every input but the last runs one fade
+// longer, and xfade offset k lands at k slide durations.
+pub(crate) fn ffmpeg_args(frames: &[PathBuf], plan: &VideoPlan, output: &Path) -> Vec<OsString> {
+    let mut args: Vec<OsString> = ["-hide_banner", "-loglevel", "error", "-y"]
+        .iter()
+        .map(OsString::from)
+        .collect();
+    let last = frames.len().saturating_sub(1);
+    for (index, frame) in frames.iter().enumerate() {
+        let secs = if index == last {
+            plan.slide_secs
+        } else {
+            plan.slide_secs + plan.fade_secs
+        };
+        args.extend(
+            [
+                "-loop".to_string(),
+                "1".to_string(),
+                "-framerate".to_string(),
+                FRAME_RATE.to_string(),
+                "-t".to_string(),
+                format!("{:.3}", secs),
+                "-i".to_string(),
+            ]
+            .map(OsString::from),
+        );
+        args.push(frame.as_os_str().to_os_string());
+    }
+    let total = plan.total_secs(frames.len());
+    if let Some(music) = &plan.music {
+        args.extend(["-stream_loop", "-1", "-i"].map(OsString::from));
+        args.push(music.as_os_str().to_os_string());
+    }
+
+    let mut filters: Vec<String> = (0..frames.len())
+        .map(|index| {
+            format!(
+                "[{0}:v]fps={1},format=yuv420p,setsar=1,settb=AVTB[v{0}]",
+                index, FRAME_RATE
+            )
+        })
+        .collect();
+    let video_label = if frames.len() == 1 {
+        "[v0]".to_string()
+    } else {
+        match plan.transition {
+            Transition::Cut => {
+                let inputs: String = (0..frames.len()).map(|i| format!("[v{}]", i)).collect();
+                filters.push(format!("{}concat=n={}:v=1:a=0[vout]", inputs, frames.len()));
+            }
+            Transition::Fade => {
+                let mut previous = "v0".to_string();
+                for index in 1..frames.len() {
+                    let label = if index == last {
+                        "vout".to_string()
+                    } else {
+                        format!("x{}", index)
+                    };
+                    filters.push(format!(
+                        "[{}][v{}]xfade=transition=fade:duration={:.3}:offset={:.3}[{}]",
+                        previous,
+                        index,
+                        plan.fade_secs,
+                        plan.slide_secs * index as f64,
+                        label
+                    ));
+                    previous = label;
+                }
+            }
+        }
+        "[vout]".to_string()
+    };
+    if plan.music.is_some() {
+        filters.push(format!(
+            "[{}:a]afade=t=out:st={:.3}:d={:.3}[aout]",
+            frames.len(),
+            (total - MUSIC_FADE_SECS).max(0.0),
+            MUSIC_FADE_SECS.min(total)
+        ));
+    }
+
+    args.push("-filter_complex".into());
+    args.push(filters.join(";").into());
+    args.extend(["-map".to_string(), video_label].map(OsString::from));
+    if plan.music.is_some() {
+        args.extend(["-map", "[aout]", "-c:a", "aac", "-b:a", "192k"].map(OsString::from));
+    }
+    args.extend(
+        [
+            "-c:v".to_string(),
+            "libx264".to_string(),
+            "-pix_fmt".to_string(),
+            "yuv420p".to_string(),
+            "-r".to_string(),
+            FRAME_RATE.to_string(),
+            "-t".to_string(),
+            format!("{:.3}", total),
+            "-movflags".to_string(),
+            "+faststart".to_string(),
+            "-f".to_string(),
+            "mp4".to_string(),
+        ]
+        .map(OsString::from),
+    );
+    args.push(output.as_os_str().to_os_string());
+    args
+}
+
+pub(crate) fn video_filename(id: &str) -> String {
+    format!("showcase_{}.mp4", id)
+}
+
+async fn run_ffmpeg(args: Vec<OsString>) -> Result<(), AppError> {
+    let output = match tokio::process::Command::new(FFMPEG)
+        .args(&args)
+        .kill_on_drop(true)
+        .output()
+        .await
+    {
+        Ok(output) => output,
+        Err(e) if e.kind() == ErrorKind::NotFound => {
+            return Err(AppError::not_found(
+                "FFmpeg was not found. Install it and make sure 'ffmpeg' is on your PATH to export videos.",
+            ))
+        }
+        Err(e) => return Err(AppError::from(e).with_context("Failed to start FFmpeg")),
+    };
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    Err(format!("FFmpeg failed ({}): {}", output.status, stderr.trim()).into())
+}
+
+// Renders the slides like the image export and encodes them into an MP4 slideshow under
+// presentations/<id>, published and recorded like a deck.
+#[tauri::command]
+pub async fn export_showcase_video(
+    app_handle: AppHandle,
+    id: String,
+    settings: Option<VideoSettings>,
+    db_state: State<'_, DbConnection>,
+) -> Result<VideoExport, AppError> {
+    let settings = settings.unwrap_or_default();
+    let plan = resolve_video_settings(&settings)?;
+    let span = Span::root("export.video", &[("showcase_id", id.clone())]);
+
+    let lookup_id = id.clone();
+    let (showcase, credits, redaction_rules) = db_state
+        .0
+        .run(move |conn| {
+            let showcase = load_showcase(conn, &lookup_id)?;
+            let selected_messages = showcase.selected_messages.clone().unwrap_or_default();
+            let credits = resolve_showcase_credits(conn, &selected_messages)?;
+            let rules = retrieve_config(conn)?.redaction_rules.unwrap_or_default();
+            Ok((showcase, credits, rules))
+        })
+        .await?;
+    let image_base_dir = get_image_base_dir(&app_handle)?;
+    let showcase_dir = get_showcase_image_dir(&app_handle, &id)?;
+    let slides = plan_slides(
+        &showcase,
+        &credits,
+        &image_base_dir,
+        &showcase_dir,
+        |text| redact_text(text, &redaction_rules),
+    );
+    if slides.is_empty() {
+        span.fail("no slides");
+        return Err(AppError::invalid_input(
+            "This showcase has no slides to export.",
+        ));
+    }
+
+    let presentation_dir = get_showcase_presentation_dir(&app_handle, &id)?;
+    let frames_dir = presentation_dir.join(VIDEO_FRAMES_DIR);
+    let task_frames_dir = frames_dir.clone();
+    let resolution = (plan.width, plan.height);
+    let rendered = tokio::task::spawn_blocking(move || {
+        write_slide_images(&slides, &task_frames_dir, "png", resolution)
+    })
+    .await
+    .map_err(|e| format!("Video frame task panicked or was cancelled: {}", e))
+    .and_then(|result| result);
+    let (files, skipped) = match rendered {
+        Ok(rendered) => rendered,
+        Err(e) => {
+            span.fail(&e);
+            return Err(e.into());
+        }
+    };
+    if files.is_empty() {
+        let _ = fs::remove_dir_all(&frames_dir);
+        span.fail("no frames");
+        return Err(AppError::invalid_input(
+            "None of the slides could be rendered.",
+        ));
+    }
+
+    let frames: Vec<PathBuf> = files.iter().map(|file| frames_dir.join(file)).collect();
+    let file_path = presentation_dir.join(video_filename(&id));
+    let mut part_name = file_path.clone().into_os_string();
+    part_name.push(".part");
+    let part_path = PathBuf::from(part_name);
+    let encoded = run_ffmpeg(ffmpeg_args(&frames, &plan, &part_path))
+        .await
+        .and_then(|_| {
+            fs::rename(&part_path, &file_path).map_err(|e| {
+                AppError::from(e).with_context(format!(
+                    "Failed to finalize video '{}'",
+                    file_path.display()
+                ))
+            })
+        });
+    let _ = fs::remove_dir_all(&frames_dir);
+    if let Err(e) = encoded {
+        let _ = fs::remove_file(&part_path);
+        span.fail(&e.message);
+        return Err(e);
+    }
+
+    let duration = plan.total_secs(files.len());
+    span.record("slides", files.len());
+    info!(
+        "Exported {}s video with {} slide(s) for showcase ID: {} ({} skipped)",
+        duration,
+        files.len(),
+        id,
+        skipped.len()
+    );
+
+    let slide_count = files.len();
+    let (width, height) = (plan.width, plan.height);
+    let published = publish_export(
+        &app_handle,
+        &db_state,
+        &id,
+        &file_path,
+        "mp4",
+        settings.output_dir,
+    )
+    .await?;
+    Ok(VideoExport {
+        path: published.to_string_lossy().into_owned(),
+        slides: slide_count,
+        skipped,
+        duration,
+        width,
+        height,
+    })
+}
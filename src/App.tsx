@@ -10,6 +10,7 @@ import EditImages from './pages/EditSelectedImages';
 import SortImagesPage from './pages/SortImagesPage';
 import GeneratePresentationPage from './pages/GeneratePresentationPage';
 import ShowcasePreviewPage from './pages/ShowcasePreviewPage';
+import SafeModePage from './pages/SafeModePage';
 
 import { AboutSection } from './components/settings/AboutSection';
 import { IndexingSection } from './components/settings/IndexingSection';
@@ -18,7 +19,9 @@ import { ApiTokensSection } from './components/settings/ApiTokensSection';
 import { SettingsLayout } from './components/settings/SettingsLayout';
 
 import Logger from './utils/log';
+import { SafeModeStatus } from './utils/types';
 import { CustomizationSection } from './components/settings/CustomizationSection';
+import { ShutdownPrompt } from './components/layout/ShutdownPrompt';
 
 const LoadingScreen: React.FC = () => (
   <div className="flex items-center justify-center min-h-screen bg-black">
@@ -40,12 +43,19 @@ const settingsSections = [
 const App: React.FC = () => {
   const [isLoading, setIsLoading] = useState<boolean>(true);
   const [isConfigured, setIsConfigured] = useState<boolean>(false);
+  const [safeMode, setSafeMode] = useState<SafeModeStatus | null>(null);
 
   useEffect(() => {
     const checkConfiguration = async () => {
-      const isSetupComplete = await invoke('is_setup_complete');
       setIsLoading(true);
       try {
+        const status = await invoke<SafeModeStatus>('get_safe_mode_status');
+        if (status.active) {
+          Logger.warn("Application started in safe mode:", status.reason);
+          setSafeMode(status);
+          return;
+        }
+        const isSetupComplete = await invoke('is_setup_complete');
         if (isSetupComplete) {
           Logger.info("Configuration found.");
           setIsConfigured(true);
@@ -68,32 +78,39 @@ const App: React.FC = () => {
     return <LoadingScreen />;
   }
 
+  if (safeMode) {
+    return <SafeModePage status={safeMode} />;
+  }
+
   return (
-    <Routes>
-      {isConfigured ? (
-        <>
-          <Route path="/" element={<MainAppPage />} />
-          <Route path="/setup" element={<Navigate to="/" replace />} />
-          <Route path="/select_images" element={<SelectImagesForShowcase />} />
-          <Route path="settings" element={<SettingsLayout />}>
-            <Route index element={<Navigate to="about" replace />} />
-            {settingsSections.map(section => (
-              <Route key={section.id} path={section.path} element={<section.component />} />
-            ))}
-          </Route>
-          <Route path="/edit_images" element={<EditImages />} />
-          <Route path="/sort_images" element={<SortImagesPage />} />
-          <Route path="/generate" element={<GeneratePresentationPage />} />
-          <Route path="/preview" element={<ShowcasePreviewPage />} />
-          <Route path="*" element={<Navigate to="/" replace />} />
-        </>
-      ) : (
-        <>
-          <Route path="/setup" element={<SetupPage />} />
-          <Route path="*" element={<Navigate to="/setup" replace />} />
-        </>
-      )}
-    </Routes>
+    <>
+      <ShutdownPrompt />
+      <Routes>
+        {isConfigured ? (
+          <>
+            <Route path="/" element={<MainAppPage />} />
+            <Route path="/setup" element={<Navigate to="/" replace />} />
+            <Route path="/select_images" element={<SelectImagesForShowcase />} />
+            <Route path="settings" element={<SettingsLayout />}>
+              <Route index element={<Navigate to="about" replace />} />
+              {settingsSections.map(section => (
+                <Route key={section.id} path={section.path} element={<section.component />} />
+              ))}
+            </Route>
+            <Route path="/edit_images" element={<EditImages />} />
+            <Route path="/sort_images" element={<SortImagesPage />} />
+            <Route path="/generate" element={<GeneratePresentationPage />} />
+            <Route path="/preview" element={<ShowcasePreviewPage />} />
+            <Route path="*" element={<Navigate to="/" replace />} />
+          </>
+        ) : (
+          <>
+            <Route path="/setup" element={<SetupPage />} />
+            <Route path="*" element={<Navigate to="/setup" replace />} />
+          </>
+        )}
+      </Routes>
+    </>
   );
 };
 
@@ -1,6 +1,8 @@
 import { useState, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core';
-import { SelectedMessage, EditableImage, ShowcaseImage } from '../../../utils/types';
+import { SelectedMessage, EditableImage, ShowcaseImage, ShowcaseRenderInfo } from '../../../utils/types';
+import { errorMessage } from '../../../utils/errors';
+import { selectedFilenames, slideKey } from '../../../utils/slides';
 import { DEFAULT_OVERLAY } from '../constants';
 
 export function useShowcaseLoader(showcaseId: string | null) {
@@ -23,10 +25,12 @@ export function useShowcaseLoader(showcaseId: string | null) {
       const loadData = async () => {
          try {
             // Load messages and existing edited images in parallel
-            const [msgs, existingImages] = await Promise.all([
+            const [msgs, existingImages, renderInfo] = await Promise.all([
                invoke<SelectedMessage[]>('get_selected_messages', { id: showcaseId }),
                invoke<ShowcaseImage[]>('get_showcase_images', { id: showcaseId })
-                  .catch(() => [] as ShowcaseImage[])
+                  .catch(() => [] as ShowcaseImage[]),
+               invoke<ShowcaseRenderInfo>('get_showcase_render_info', { id: showcaseId })
+                  .catch(() => null)
             ]);
 
             if (!msgs || msgs.length === 0) {
@@ -36,30 +40,45 @@ export function useShowcaseLoader(showcaseId: string | null) {
                return;
             }
 
+            // One slide per chosen attachment; slides from the same message share its credit and caption
+            const slides = msgs.flatMap(m => selectedFilenames(m).map((filename, attachment_index) => ({
+               m,
+               filename,
+               attachment_index,
+               key: slideKey({ message_id: m.message_id, attachment_index }),
+            })));
+
             const loadedImages: EditableImage[] = [];
-            await Promise.all(msgs.map(async (m) => {
+            await Promise.all(slides.map(async ({ m, filename, attachment_index, key }) => {
                try {
-                  const dataUri: string = await invoke('get_cached_image_data', { messageId: m.message_id, relativePath: m.selected_attachment_filename });
+                  const [dataUri, avatar] = await Promise.all([
+                     invoke<string>('get_cached_image_data', { messageId: m.message_id, relativePath: filename }),
+                     // Falls back to a generated initials avatar so overlays never show a broken image
+                     invoke<string>('get_author_avatar', { authorId: m.author_id, authorName: m.author_name, avatarUrl: m.author_avatar, avatarPath: m.author_avatar_path })
+                        .catch(() => m.author_avatar),
+                  ]);
 
-                  const existingImage = existingImages.find(img => img.message_id === m.message_id);
+                  const existingImage = existingImages.find(img => slideKey(img) === key);
 
                   loadedImages.push({
-                     id: m.message_id,
+                     id: key,
                      message_id: m.message_id,
-                     filename: m.selected_attachment_filename,
+                     attachment_index,
+                     filename,
                      sender: m.author_name,
-                     avatar: m.author_avatar,
-                     message: m.message_content,
+                     avatar,
+                     // Captions go through the configured redaction rules before reaching the overlay
+                     message: renderInfo?.slides.find(s => s.message_id === m.message_id)?.caption ?? m.message_content,
                      imageDataUrl: dataUri,
                      overlay: existingImage ? existingImage.overlay : { ...DEFAULT_OVERLAY }
                   });
                } catch (imgErr) {
-                  console.error(`Failed to load image data for message ${m.message_id}:`, imgErr);
+                  console.error(`Failed to load image data for slide ${key}:`, imgErr);
                }
             }));
 
-            const orderedImages = msgs
-               .map(m => loadedImages.find(img => img.id === m.message_id))
+            const orderedImages = slides
+               .map(slide => loadedImages.find(img => img.id === slide.key))
                .filter((img): img is EditableImage => img !== undefined);
 
             if (orderedImages.length === 0 && msgs.length > 0) {
@@ -72,7 +91,7 @@ export function useShowcaseLoader(showcaseId: string | null) {
 
          } catch (e) {
             console.error('Failed to load images:', e);
-            setError(`Failed to load image list: ${e instanceof Error ? e.message : String(e)}`);
+            setError(`Failed to load image list: ${errorMessage(e)}`);
             setImages([]);
          } finally {
             setIsLoading(false);
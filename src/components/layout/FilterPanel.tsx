@@ -1,6 +1,7 @@
 import React from 'react';
 import { motion } from 'framer-motion';
 import { ArrowDown, ArrowUp, X, Filter, RotateCcw, Check } from 'lucide-react';
+import { ShowcaseStatus } from '../../utils/types';
 
 type SortField = 'title' | 'dateCreated' | 'lastModified' | 'itemCount';
 type SortDirection = 'asc' | 'desc';
@@ -16,8 +17,7 @@ interface FilterPanelProps {
     onSortChange: (field: SortField, direction: SortDirection) => void;
 }
 
-// Mock filter options
-const statusFilters = ['All', 'Published', 'Draft', 'Archived'];
+const statusFilters: ('All' | ShowcaseStatus)[] = ['All', 'Draft', 'In Progress', 'Completed', 'Archived'];
 const sortOptions: { id: SortField, label: string }[] = [
     { id: 'lastModified', label: 'Last Modified' },
     { id: 'dateCreated', label: 'Date Created' },
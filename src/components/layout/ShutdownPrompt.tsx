@@ -0,0 +1,63 @@
+import React, { useEffect, useState } from 'react';
+import { AlertTriangle, X } from 'lucide-react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { ActiveJobs, ShutdownChoice } from '../../utils/types';
+import Logger from '../../utils/log';
+
+export const ShutdownPrompt: React.FC = () => {
+   const [jobs, setJobs] = useState<ActiveJobs | null>(null);
+
+   useEffect(() => {
+      const unlisten = listen<ActiveJobs>('shutdown-requested', (event) => setJobs(event.payload));
+      return () => {
+         unlisten.then(fn => fn());
+      };
+   }, []);
+
+   if (!jobs) return null;
+
+   const choose = async (choice: ShutdownChoice) => {
+      setJobs(null);
+      try {
+         await invoke('resolve_shutdown', { choice });
+      } catch (error) {
+         Logger.error("Failed to resolve shutdown:", error);
+      }
+   };
+
+   const running = [
+      jobs.indexing ? 'indexing' : null,
+      jobs.exports.length > 0 ? 'an export' : null,
+   ].filter(Boolean).join(' and ');
+
+   return (
+      <div className="fixed inset-0 z-50 flex items-center justify-center bg-black/70">
+         <div className="w-full max-w-md rounded-lg bg-[#2b2d31] p-6 shadow-xl">
+            <div className="flex items-start gap-3">
+               <AlertTriangle className="h-5 w-5 flex-shrink-0 text-amber-400" />
+               <div className="flex-1">
+                  <h2 className="text-base font-semibold text-white">Quit while {running} is running?</h2>
+                  <p className="mt-1 text-sm text-gray-400">
+                     Choose whether to wait for it to finish, cancel it now, or let it finish in the background.
+                  </p>
+               </div>
+               <button onClick={() => setJobs(null)} className="text-gray-400 hover:text-gray-300">
+                  <X size={16} />
+               </button>
+            </div>
+            <div className="mt-5 flex justify-end gap-2">
+               <button onClick={() => choose('cancel')} className="rounded-md bg-red-600/80 px-3 py-1.5 text-sm text-white hover:bg-red-600">
+                  Cancel and quit
+               </button>
+               <button onClick={() => choose('background')} className="rounded-md bg-gray-700 px-3 py-1.5 text-sm text-white hover:bg-gray-600">
+                  Finish in background
+               </button>
+               <button onClick={() => choose('wait')} className="rounded-md bg-purple-600 px-3 py-1.5 text-sm text-white hover:bg-purple-500">
+                  Wait, then quit
+               </button>
+            </div>
+         </div>
+      </div>
+   );
+};
@@ -2,6 +2,7 @@ import React, { useState, useEffect, useRef } from 'react';
 import { KeyRound, Bot, Route, Eye, EyeOff, AlertTriangle, ExternalLink, Shield, Check, X, Copy, Loader2 } from 'lucide-react';
 import { invoke } from '@tauri-apps/api/core';
 import Logger from '../../utils/log';
+import { errorMessage } from '../../utils/errors';
 
 const DISCORD_TOKEN_KEY = 'discordBotToken';
 const OPENROUTER_API_KEY = 'openRouterApiKey';
@@ -49,7 +50,7 @@ export const ApiTokensSection: React.FC = () => {
             setOpenRouterKey(ork ?? '');
          } catch (err) {
             Logger.error("Load keys error:", err);
-            setError(`Failed to load API keys: ${err}`);
+            setError(`Failed to load API keys: ${errorMessage(err)}`);
          } finally {
             setIsLoading(false);
          }
@@ -117,7 +118,7 @@ export const ApiTokensSection: React.FC = () => {
 
       } catch (err) {
          Logger.error(`Save ${keyName} error:`, err);
-         setError(`Failed to save ${keyName === DISCORD_TOKEN_KEY ? 'Discord Token' : 'OpenRouter Key'}: ${err}`);
+         setError(`Failed to save ${keyName === DISCORD_TOKEN_KEY ? 'Discord Token' : 'OpenRouter Key'}: ${errorMessage(err)}`);
       } finally {
          setTestingConnection(false);
       }
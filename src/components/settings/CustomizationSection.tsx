@@ -1,6 +1,6 @@
 import React, { useState, useEffect } from 'react';
 import { Paintbrush, RefreshCw, Bell, BellOff, Loader2, Layers, Image as ImageIcon, 
-  Upload, Sliders, User, Type, Info, X, Check, Save, RotateCcw } from 'lucide-react';
+  Upload, Sliders, User, Type, Info, X, Check, Save, RotateCcw, Presentation, FolderOpen } from 'lucide-react';
 import { invoke } from '@tauri-apps/api/core';
 import { InfoToast } from '../layout/Toasts';
 import Logger from '../../utils/log';
@@ -26,6 +26,11 @@ export const CustomizationSection: React.FC = () => {
    const [showTitle, setShowTitle] = useState<boolean>(true);
    const [showAuthor, setShowAuthor] = useState<boolean>(true);
 
+   // Export
+   const [legacyPptxCompatibility, setLegacyPptxCompatibility] = useState<boolean>(false);
+   const [exportDirectory, setExportDirectory] = useState<string | null>(null);
+   const [recentExportLocations, setRecentExportLocations] = useState<string[]>([]);
+
    useEffect(() => {
      const loadSettings = async () => {
        try {
@@ -46,6 +51,10 @@ export const CustomizationSection: React.FC = () => {
          setShowTitle(fs?.showTitle ?? true);
          setShowAuthor(fs?.showAuthor ?? true);
 
+         setLegacyPptxCompatibility(backendSettings.legacyPptxCompatibility ?? false);
+         setExportDirectory(backendSettings.exportDirectory ?? null);
+         setRecentExportLocations(await invoke<string[]>('get_recent_export_locations', { format: 'pptx' }));
+
        } catch (error) {
          Logger.error("Failed to load customization settings from backend:", error);
          // Fallback to UI defaults if backend load fails
@@ -58,6 +67,8 @@ export const CustomizationSection: React.FC = () => {
          setFirstSlideImage(null);
          setShowTitle(true);
          setShowAuthor(true);
+         setLegacyPptxCompatibility(false);
+         setExportDirectory(null);
        } finally {
          setIsLoading(false);
          setTimeout(() => { // Important: allow state updates to process
@@ -75,7 +86,8 @@ export const CustomizationSection: React.FC = () => {
      }
    }, [
      overlayPosition, overlayStyle, overlayWidth, overlayTransparency,
-     showAvatar, firstSlideImage, showTitle, showAuthor, autoUpdatesEnabled
+     showAvatar, firstSlideImage, showTitle, showAuthor, autoUpdatesEnabled,
+     legacyPptxCompatibility, exportDirectory
    ]);
 
    const handleToggleAutoUpdates = async () => {
@@ -115,6 +127,22 @@ export const CustomizationSection: React.FC = () => {
       }
    };
 
+   const handleSelectExportDirectory = async () => {
+      try {
+         const selected = await open({
+            directory: true,
+            multiple: false,
+            defaultPath: exportDirectory ?? undefined
+         });
+
+         if (selected && !Array.isArray(selected)) {
+            setExportDirectory(selected);
+         }
+      } catch (error) {
+         Logger.error("Failed to select export directory:", error);
+      }
+   };
+
    const handleClearFirstSlideImage = () => {
       setFirstSlideImage(null);
       InfoToast("Background image removed");
@@ -162,6 +190,8 @@ export const CustomizationSection: React.FC = () => {
        setFirstSlideImage(null);
        setShowTitle(true);
        setShowAuthor(true);
+       setLegacyPptxCompatibility(false);
+       setExportDirectory(null);
        
        InfoToast("Settings reset to defaults");
      } catch (error) {
@@ -176,6 +206,8 @@ export const CustomizationSection: React.FC = () => {
 
        const settingsToSave: CustomizationSettingsPayload = {
          autoUpdateEnabled: autoUpdatesEnabled,
+         legacyPptxCompatibility: legacyPptxCompatibility,
+         exportDirectory: exportDirectory,
          overlaySettings: {
            position: overlayPosition,
            style: overlayStyle,
@@ -602,6 +634,84 @@ export const CustomizationSection: React.FC = () => {
             </div>
          </div>
 
+         {/* Export Settings Section */}
+         <div className="space-y-6 pt-4">
+            <div className="flex items-center border-b border-gray-700 pb-2">
+               <Presentation className="w-5 h-5 text-indigo-400 mr-2" />
+               <h3 className="text-xl font-medium text-slate-200">Export Settings</h3>
+            </div>
+
+            <div className="p-6 bg-gray-800/40 rounded-xl border border-gray-700/60 backdrop-blur-sm shadow-lg shadow-black/10">
+               <div className="flex items-center justify-between py-4 px-5 bg-gray-900/50 rounded-xl border border-gray-700/50">
+                  <div>
+                     <h4 className="text-base font-medium text-slate-200">Legacy PowerPoint compatibility</h4>
+                     <p className="text-sm text-slate-400 mt-1">
+                        Converts slides to PNG and caps them at 1920×1080 so presentations open in PowerPoint 2016 and LibreOffice Impress
+                     </p>
+                  </div>
+                  <button
+                     onClick={() => setLegacyPptxCompatibility(!legacyPptxCompatibility)}
+                     className={`relative inline-flex h-6 w-11 flex-shrink-0 items-center rounded-full transition-colors ${
+                        legacyPptxCompatibility ? 'bg-indigo-600' : 'bg-gray-700'
+                     }`}
+                  >
+                     <span
+                        className={`inline-block h-4 w-4 transform rounded-full bg-white transition-transform ${
+                           legacyPptxCompatibility ? 'translate-x-6' : 'translate-x-1'
+                        }`}
+                     />
+                  </button>
+               </div>
+
+               <div className="mt-4 py-4 px-5 bg-gray-900/50 rounded-xl border border-gray-700/50">
+                  <div className="flex items-center justify-between gap-4">
+                     <div className="min-w-0">
+                        <h4 className="text-base font-medium text-slate-200">Export folder</h4>
+                        <p className="text-sm text-slate-400 mt-1 truncate" title={exportDirectory ?? undefined}>
+                           {exportDirectory ?? 'Presentations are only kept inside the app data folder'}
+                        </p>
+                     </div>
+                     <div className="flex items-center gap-2 flex-shrink-0">
+                        {exportDirectory && (
+                           <button
+                              onClick={() => setExportDirectory(null)}
+                              className="p-2 text-slate-400 hover:text-slate-200 rounded-lg hover:bg-gray-700/60 transition-colors"
+                              title="Clear export folder"
+                           >
+                              <X className="h-4 w-4" />
+                           </button>
+                        )}
+                        <button
+                           onClick={handleSelectExportDirectory}
+                           className="flex items-center gap-2 px-3 py-2 text-sm bg-indigo-600 hover:bg-indigo-700 text-white rounded-lg transition-colors"
+                        >
+                           <FolderOpen className="h-4 w-4" />
+                           Choose...
+                        </button>
+                     </div>
+                  </div>
+                  {recentExportLocations.length > 0 && (
+                     <div className="mt-3 flex flex-wrap gap-2">
+                        {recentExportLocations.map(location => (
+                           <button
+                              key={location}
+                              onClick={() => setExportDirectory(location)}
+                              className={`px-2 py-1 text-xs rounded-md border transition-colors max-w-xs truncate ${
+                                 location === exportDirectory
+                                    ? 'bg-indigo-900/40 border-indigo-600 text-indigo-200'
+                                    : 'bg-gray-800 border-gray-700 text-slate-400 hover:text-slate-200'
+                              }`}
+                              title={location}
+                           >
+                              {location}
+                           </button>
+                        ))}
+                     </div>
+                  )}
+               </div>
+            </div>
+         </div>
+
          {/* Updates Settings Section */}
          <div className="space-y-6 pt-4">
             <div className="flex items-center border-b border-gray-700 pb-2">
@@ -5,7 +5,7 @@ import {
 } from 'lucide-react';
 import { invoke } from '@tauri-apps/api/core';
 import { relaunch } from '@tauri-apps/plugin-process';
-import { StorageUsage } from '../../utils/types';
+import { CleanupStats, StorageUsage } from '../../utils/types';
 import Logger from '../../utils/log';
 
 const formatBytesToMB = (bytes: number, decimals: number = 2): string => {
@@ -75,8 +75,8 @@ export const DataStorageSection: React.FC = () => {
       setIsClearingCache(true);
       setOperationStatus(null);
       try {
-         Logger.info("Simulating invoke: clear_image_cache");
-         await invoke("clear_image_cache");
+         Logger.info("Clearing image cache");
+         await invoke("clear_image_cache_only");
          setOperationStatus({ type: 'success', message: 'Cache cleared successfully.' });
          setRefreshTrigger(prev => prev + 1);
          setTimeout(() => setOperationStatus(null), 5000);
@@ -93,11 +93,7 @@ export const DataStorageSection: React.FC = () => {
       setIsCleaningData(true);
       setOperationStatus(null);
       try {
-         const stats = await invoke<{
-            messages_deleted: number,
-            files_deleted: number,
-            skipped_used_messages: number
-         }>("clean_old_data");
+         const stats = await invoke<CleanupStats>("clean_old_data");
 
          setOperationStatus({
             type: 'success',
@@ -6,10 +6,11 @@ import {
    X
 } from 'lucide-react';
 import { invoke } from '@tauri-apps/api/core';
-import { DiscordChannel, IndexedMessage } from '../../utils/types';
+import { DiscordChannel, IndexedMessage, IndexingProgress } from '../../utils/types';
 import { listen } from '@tauri-apps/api/event';
 import toast from 'react-hot-toast';
 import Logger from '../../utils/log';
+import { errorMessage } from '../../utils/errors';
 
 type ChannelStats = {
    id: string;
@@ -130,7 +131,11 @@ export const IndexingSection: React.FC = () => {
 
       const listeners = [
          listen<string>('indexing-status', (event) => toast.loading(event.payload, { id: "indexing" })),
-         listen<string>('indexing-progress', (event) => toast.loading(`Progress: ${event.payload}`, { id: "indexing" })),
+         listen<IndexingProgress>('indexing-progress', (event) => {
+            const p = event.payload;
+            const channel = p.channel_name ? `#${p.channel_name}` : p.channel_id ?? '';
+            toast.loading(`Progress: ${Math.round(p.percent)}% ${channel} (${p.messages_scanned} scanned, ${p.images_downloaded} images)`, { id: "indexing" });
+         }),
          listen<string>('indexing-complete', () => {
             toast.success("Indexing complete!", { id: "indexing" });
             setIsIndexing(false);
@@ -214,7 +219,7 @@ export const IndexingSection: React.FC = () => {
          .then(() => Logger.info("Indexing process started successfully."))
          .catch((err) => {
             Logger.error("Failed to invoke start_initial_indexing:", err);
-            const errorMsg = err instanceof Error ? err.message : String(err);
+            const errorMsg = errorMessage(err);
             toast.error(`Failed to start indexing: ${errorMsg}`, { id: "indexing" });
             setIsIndexing(false);
          });
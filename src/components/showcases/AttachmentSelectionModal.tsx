@@ -100,19 +100,32 @@ ModalImage.displayName = 'ModalImage';
 interface AttachmentSelectionModalProps {
     isOpen: boolean;
     message: IndexedMessage | null;
-    chosenFilename: string | null;
-    onSelect: (messageId: string, filename: string) => void;
+    chosenFilenames: string[];
+    onSelect: (messageId: string, filenames: string[]) => void;
     onClose: () => void;
 }
 
 const AttachmentSelectionModal: React.FC<AttachmentSelectionModalProps> = ({
-    isOpen, message, chosenFilename, onSelect, onClose,
+    isOpen, message, chosenFilenames, onSelect, onClose,
 }) => {
+    const [pendingFilenames, setPendingFilenames] = useState<string[]>(chosenFilenames);
+
+    useEffect(() => {
+        if (isOpen) setPendingFilenames(chosenFilenames);
+    }, [isOpen, message, chosenFilenames]);
+
     if (!message) return null;
 
+    const handleToggleFilename = (filename: string) => {
+        setPendingFilenames(prev => prev.includes(filename)
+            ? prev.filter(f => f !== filename)
+            : [...prev, filename]);
+    };
+
     const handleConfirmSelection = () => {
-        if (chosenFilename) {
-            onSelect(message.message_id, chosenFilename);
+        if (pendingFilenames.length > 0) {
+            // Slides follow the order the artist posted the images in
+            onSelect(message.message_id, message.attachments.filter(f => pendingFilenames.includes(f)));
         }
     };
     
@@ -144,7 +157,7 @@ const AttachmentSelectionModal: React.FC<AttachmentSelectionModalProps> = ({
                                 <div className="p-1.5 bg-blue-500/20 rounded-lg">
                                     <ImageIcon className="w-5 h-5 text-blue-400" />
                                 </div>
-                                <h2 className="text-lg font-medium text-white">Select an Image</h2>
+                                <h2 className="text-lg font-medium text-white">Select Images</h2>
                             </div>
                             <button
                                 onClick={onClose}
@@ -196,7 +209,9 @@ const AttachmentSelectionModal: React.FC<AttachmentSelectionModalProps> = ({
                                 </div>
                                 
                                 <div className="text-sm px-3 py-1 rounded-md bg-blue-500/10 text-blue-300 border border-blue-500/20">
-                                    Select one to continue
+                                    {pendingFilenames.length > 0
+                                        ? `${pendingFilenames.length} selected, one slide each`
+                                        : 'Select one or more to continue'}
                                 </div>
                             </div>
 
@@ -206,8 +221,8 @@ const AttachmentSelectionModal: React.FC<AttachmentSelectionModalProps> = ({
                                     <ModalImage
                                         key={relativeFilename}
                                         relativePath={relativeFilename}
-                                        isSelected={chosenFilename === relativeFilename}
-                                        onClick={() => onSelect(message.message_id, relativeFilename)}
+                                        isSelected={pendingFilenames.includes(relativeFilename)}
+                                        onClick={() => handleToggleFilename(relativeFilename)}
                                     />
                                 ))}
                             </div>
@@ -223,9 +238,9 @@ const AttachmentSelectionModal: React.FC<AttachmentSelectionModalProps> = ({
                             
                             <button
                                 onClick={handleConfirmSelection}
-                                disabled={!chosenFilename}
+                                disabled={pendingFilenames.length === 0}
                                 className={`px-4 py-2 rounded-md text-sm font-medium shadow-sm transition-all flex items-center gap-2
-                                    ${!chosenFilename
+                                    ${pendingFilenames.length === 0
                                         ? 'bg-gray-700 text-gray-400 cursor-not-allowed'
                                         : 'bg-blue-600 hover:bg-blue-500 text-white shadow-md'
                                     }`}
@@ -1,10 +1,12 @@
 import React, { useState, useRef, useCallback, useEffect, memo } from 'react';
 import { useVirtualizer } from '@tanstack/react-virtual';
 import { motion } from 'framer-motion';
-import { CheckCircle, User, Layers, Loader2, AlertTriangle, ImageOff } from 'lucide-react';
+import { CheckCircle, User, Layers, Loader2, AlertTriangle, ImageOff, ExternalLink } from 'lucide-react';
 import { invoke } from '@tauri-apps/api/core';
 
 import { IndexedMessage } from '../../utils/types';
+import { cachedImageUrl } from '../../utils/images';
+import Logger from '../../utils/log';
 import AttachmentSelectionModal from './AttachmentSelectionModal';
 
 interface MessageSelectionGridProps {
@@ -13,33 +15,34 @@ interface MessageSelectionGridProps {
    error: string | null;
    appDataDirPath: string | null;
    selectedItems: Set<string>;
-   chosenFilenames: Map<string, string>;
+   chosenFilenames: Map<string, string[]>;
    onToggleSelection: (messageId: string) => void;
-   onAttachmentChosen: (messageId: string, filename: string) => void;
+   onAttachmentsChosen: (messageId: string, filenames: string[]) => void;
 }
 
 const MIN_CARD_WIDTH = 200;
 const CARD_HEIGHT_ESTIMATE = 300;
 const ROW_GAP = 16;
 const OVERSCAN_COUNT = 3;
+const NO_FILENAMES: string[] = [];
 
 interface MessageCardProps {
    message: IndexedMessage;
    isSelected: boolean;
-   chosenFilename: string | null;
+   chosenFilenames: string[];
    needsChoice: boolean;
    onCardClick: (message: IndexedMessage) => void;
 }
 
 const MessageCard: React.FC<MessageCardProps> = memo(({
-   message, isSelected, chosenFilename, needsChoice, onCardClick
+   message, isSelected, chosenFilenames, needsChoice, onCardClick
 }) => {
    const [imageDataUri, setImageDataUri] = useState<string | null>(null);
    const [imageError, setImageError] = useState<string | null>(null);
    const [isLoadingImage, setIsLoadingImage] = useState(true);
    const isMounted = useRef(true);
 
-   const previewFilename = chosenFilename ?? (message.attachments?.[0] || null);
+   const previewFilename = chosenFilenames[0] ?? (message.attachments?.[0] || null);
 
    useEffect(() => {
       isMounted.current = true;
@@ -47,10 +50,8 @@ const MessageCard: React.FC<MessageCardProps> = memo(({
       setImageDataUri(null); setImageError(null); setIsLoadingImage(true);
 
       if (currentFilename) {
-         invoke<string>('get_cached_image_data', { relativePath: currentFilename })
-            .then(dataUri => { if (isMounted.current && currentFilename === previewFilename) setImageDataUri(dataUri); })
-            .catch(err => { if (isMounted.current && currentFilename === previewFilename) setImageError(err instanceof Error ? err.message : "Failed to load preview"); })
-            .finally(() => { if (isMounted.current && currentFilename === previewFilename) setIsLoadingImage(false); });
+         setImageDataUri(cachedImageUrl(currentFilename, 'thumb'));
+         setIsLoadingImage(false);
       } else {
          setIsLoadingImage(false); setImageError("No attachment available.");
       }
@@ -60,6 +61,12 @@ const MessageCard: React.FC<MessageCardProps> = memo(({
    const handleImageError = useCallback(() => { if (!imageError) setImageError("Browser couldn't render image"); setIsLoadingImage(false); }, [imageError]);
    const handleImageLoad = useCallback(() => { if (isLoadingImage) setIsLoadingImage(false); setImageError(null); }, [isLoadingImage]);
 
+   const handleOpenInDiscord = useCallback((event: React.MouseEvent) => {
+      event.stopPropagation();
+      invoke('open_message_in_discord', { messageId: message.message_id })
+         .catch(err => Logger.error(`Failed to open message ${message.message_id} in Discord:`, err));
+   }, [message.message_id]);
+
    const hasMultiple = message.attachments.length > 1;
 
    return (
@@ -100,7 +107,11 @@ const MessageCard: React.FC<MessageCardProps> = memo(({
                   : 'bg-black/70 text-gray-200 border border-gray-600/30'}`}
             >
                <Layers className="w-3 h-3" />
-               <span>{message.attachments.length} images</span>
+               <span>
+                  {isSelected && chosenFilenames.length > 0
+                     ? `${chosenFilenames.length}/${message.attachments.length} images`
+                     : `${message.attachments.length} images`}
+               </span>
             </div>
          )}
 
@@ -153,6 +164,13 @@ const MessageCard: React.FC<MessageCardProps> = memo(({
                <span className="text-gray-200 font-medium truncate flex-shrink min-w-0" title={message.author_name}>
                   {message.author_name}
                </span>
+               <button
+                  onClick={handleOpenInDiscord}
+                  className="ml-auto p-1 rounded text-gray-500 hover:text-gray-200 hover:bg-gray-700/60 flex-shrink-0"
+                  title="Open in Discord"
+               >
+                  <ExternalLink className="w-3.5 h-3.5" />
+               </button>
             </div>
             <p className="text-gray-400 leading-relaxed line-clamp-2 overflow-hidden text-xs" title={message.message_content || ""}>
                {message.message_content || <span className="italic text-gray-500">No message text</span>}
@@ -170,7 +188,7 @@ const MessageSelectionGrid: React.FC<MessageSelectionGridProps> = ({
    selectedItems,
    chosenFilenames,
    onToggleSelection,
-   onAttachmentChosen,
+   onAttachmentsChosen,
 }) => {
    const [modalOpen, setModalOpen] = useState<boolean>(false);
    const [modalMessage, setModalMessage] = useState<IndexedMessage | null>(null);
@@ -193,7 +211,7 @@ const MessageSelectionGrid: React.FC<MessageSelectionGridProps> = ({
       else if (message.attachments.length > 1) { setModalMessage(message); setModalOpen(true); }
    }, [onToggleSelection]);
 
-   const handleSelectFromModal = useCallback((messageId: string, filename: string) => { onAttachmentChosen(messageId, filename); setModalOpen(false); }, [onAttachmentChosen]);
+   const handleSelectFromModal = useCallback((messageId: string, filenames: string[]) => { onAttachmentsChosen(messageId, filenames); setModalOpen(false); }, [onAttachmentsChosen]);
    const handleCloseModal = useCallback(() => { setModalOpen(false); setModalMessage(null); }, []);
 
    const getItemsForRow = (rowIndex: number): IndexedMessage[] => { const start = rowIndex * gridColumnCount; const end = Math.min(start + gridColumnCount, messages.length); return messages.slice(start, end); };
@@ -212,9 +230,9 @@ const MessageSelectionGrid: React.FC<MessageSelectionGridProps> = ({
                         <div className="grid gap-4 h-full" style={{ gridTemplateColumns: `repeat(${gridColumnCount}, minmax(0, 1fr))` }}>
                            {rowMessages.map(message => {
                               const isSelected = selectedItems.has(message.message_id);
-                              const currentChosenFilename = chosenFilenames.get(message.message_id) ?? null;
-                              const needsChoice = isSelected && message.attachments.length > 1 && !currentChosenFilename;
-                              return (<MessageCard key={message.message_id} message={message} isSelected={isSelected} chosenFilename={currentChosenFilename} needsChoice={needsChoice} onCardClick={handleCardClick} />);
+                              const currentChosenFilenames = chosenFilenames.get(message.message_id) ?? NO_FILENAMES;
+                              const needsChoice = isSelected && message.attachments.length > 1 && currentChosenFilenames.length === 0;
+                              return (<MessageCard key={message.message_id} message={message} isSelected={isSelected} chosenFilenames={currentChosenFilenames} needsChoice={needsChoice} onCardClick={handleCardClick} />);
                            })}
                         </div>
                      </div>
@@ -226,7 +244,7 @@ const MessageSelectionGrid: React.FC<MessageSelectionGridProps> = ({
          <AttachmentSelectionModal
             isOpen={modalOpen}
             message={modalMessage}
-            chosenFilename={modalMessage ? chosenFilenames.get(modalMessage.message_id) ?? null : null}
+            chosenFilenames={modalMessage ? chosenFilenames.get(modalMessage.message_id) ?? NO_FILENAMES : NO_FILENAMES}
             onSelect={handleSelectFromModal}
             onClose={handleCloseModal}
          />
@@ -3,7 +3,6 @@ import { motion, AnimatePresence } from 'framer-motion';
 import { Check, ChevronRight, Clock, Image, MessageSquare, ArrowUpDown, Trash2, Eye } from 'lucide-react';
 import { useNavigate } from 'react-router-dom';
 import { invoke } from '@tauri-apps/api/core';
-import { revealItemInDir } from '@tauri-apps/plugin-opener'
 import { Showcase } from '../../utils/types';
 import Logger from "../../utils/log";
 
@@ -91,8 +90,7 @@ const ShowcaseDetailsModal: React.FC<ShowcaseDetailsModalProps> = ({ isOpen, onC
       } else {
          const exists = await invoke("check_showcase_pptx_exists", { id: showcase.id });
          if (exists) {
-            const path = await invoke<string>('open_showcase_pptx', { id: showcase.id });
-            await revealItemInDir(path);
+            await invoke<string>('reveal_showcase_output', { id: showcase.id });
          } else navigate(`/generate?id=${showcase.id}`);
       }
    }; const handlePreviewShowcase = () => {
@@ -324,7 +322,7 @@ const ShowcaseDetailsModal: React.FC<ShowcaseDetailsModalProps> = ({ isOpen, onC
 
                   <div className="p-6">
                      <p className="text-gray-300 mb-6">
-                        Are you sure you want to delete "{showcase.title}"? This action cannot be undone.
+                        Move "{showcase.title}" to the trash? You can restore it until it is purged.
                      </p>
 
                      <div className="flex gap-3 justify-end">
@@ -13,7 +13,7 @@ const itemVariants = {
 
 interface ShowcaseListProps {
     searchTerm: string;
-    activeFilter: string; // 'All' | 'Published' | 'Draft' | 'Archived'
+    activeFilter: string; // 'All' or a ShowcaseStatus
 }
 
 const formatDate = (timestamp: number): string => {
@@ -27,11 +27,16 @@ const ShowcaseList: React.FC<ShowcaseListProps> = ({ searchTerm, activeFilter })
     const [selectedShowcase, setSelectedShowcase] = useState<Showcase | null>(null);
     const [isDetailsModalOpen, setIsDetailsModalOpen] = useState(false);
 
-    useEffect(() => {
-        invoke<Showcase[]>("list_showcases").then((data) => {
+    const includeArchived = activeFilter === 'Archived';
+    const loadShowcases = useCallback(() => {
+        return invoke<Showcase[]>("list_showcases", { includeArchived }).then((data) => {
             setShowcases(data);
         });
-    }, []);
+    }, [includeArchived]);
+
+    useEffect(() => {
+        loadShowcases();
+    }, [loadShowcases]);
 
     const filteredShowcases = useMemo(() => {
         return showcases
@@ -197,7 +202,7 @@ const ShowcaseList: React.FC<ShowcaseListProps> = ({ searchTerm, activeFilter })
                 isOpen={isDetailsModalOpen}
                 onClose={handleCloseDetailsModal}
                 showcase={selectedShowcase}
-                onRefresh={() => invoke<Showcase[]>("list_showcases").then((data) => setShowcases(data))}
+                onRefresh={loadShowcases}
             />
         </div>
     );
@@ -17,7 +17,9 @@ import WelcomeModal from '../components/image-editor/components/WelcomeModal';
 
 import { captureScreenshot } from '../utils/screenshot';
 import { EditableImage, ShowcaseImage } from '../utils/types';
+import { slideKey } from '../utils/slides';
 import Logger from '../utils/log';
+import { errorMessage } from '../utils/errors';
 import { ErrorToast } from '../components/layout/Toasts';
 
 const EditImages = () => {
@@ -54,7 +56,7 @@ const EditImages = () => {
                if (existingImages && existingImages.length > 0) {
                   const completedIds = existingImages
                      .filter(img => img.is_edited)
-                     .map(img => img.message_id);
+                     .map(img => slideKey(img));
 
                   setCompletedImages(prev => [...new Set([...prev, ...completedIds])]);
                }
@@ -112,6 +114,7 @@ const EditImages = () => {
 
          const imageMetadata: ShowcaseImage = {
             message_id: selectedImage.message_id,
+            attachment_index: selectedImage.attachment_index,
             sender: selectedImage.sender,
             avatar: selectedImage.avatar ?? "" as string,
             message: selectedImage.message,
@@ -148,7 +151,7 @@ const EditImages = () => {
          });
 
       } catch (err) {
-         ErrorToast(`Error: ${err}`);
+         ErrorToast(`Error: ${errorMessage(err)}`);
          Logger.error("Failed to save changes:", err);
       } finally {
          setIsSaving(false);
@@ -160,7 +163,7 @@ const EditImages = () => {
       const dataUri = await captureScreenshot(previewContainerRef.current);
       if (dataUri) {
          const link = document.createElement('a');
-         link.download = `showcase-${showcaseId}-${selectedImage.message_id}.png`;
+         link.download = `showcase-${showcaseId}-${selectedImage.id}.png`;
          link.href = dataUri;
          document.body.appendChild(link);
          link.click();
@@ -1,12 +1,13 @@
 import React, { useEffect, useState } from 'react';
 import { useNavigate, useSearchParams } from 'react-router-dom';
 import { invoke } from '@tauri-apps/api/core';
-import { revealItemInDir } from '@tauri-apps/plugin-opener';
 import { motion, AnimatePresence } from 'framer-motion';
-import pptxgen from 'pptxgenjs';
 import { AlertTriangle, ChevronLeft, Presentation, Check, FileCheck } from 'lucide-react';
 import { Showcase } from '../utils/types';
 import Logger from '../utils/log';
+import { errorMessage } from '../utils/errors';
+import { slideImagePath } from '../utils/slides';
+import { cachedImageUrl } from '../utils/images';
 import { ErrorToast, SuccessToast } from '../components/layout/Toasts';
 
 const GeneratePresentationPage: React.FC = () => {
@@ -45,7 +46,7 @@ const GeneratePresentationPage: React.FC = () => {
         generatePPTX(showcaseData);
       } catch (error) {
         Logger.error('Error loading showcase:', error);
-        setError(`Failed to load showcase: ${error instanceof Error ? error.message : String(error)}`);
+        setError(`Failed to load showcase: ${errorMessage(error)}`);
         setIsLoading(false);
       }
     };
@@ -68,59 +69,17 @@ const GeneratePresentationPage: React.FC = () => {
 
     setIsGenerating(true);
     try {
-      const pres = new pptxgen();
-      pres.layout = 'LAYOUT_16x9';
-
-      pres.title = showcaseData.title;
-      pres.subject = "Showcase Presentation";
-      pres.company = "Showcase Studio";
-
-      pres.defineSlideMaster({
-        title: 'MASTER_SLIDE',
-        background: { color: '000000' },
-      });
-
-      const imagePromises = showcaseData.images.map(async (image) => {
-        const imagePath = `${showcaseId}/${showcaseId}_${image.message_id}.png`;
-        try {
-          const dataUrl = await invoke<string>('get_cached_image_data', {
-            relativePath: imagePath
-          });
-          return { image, dataUrl };
-        } catch (error) {
-          Logger.error(`Failed to load image ${image.message_id}:`, error);
-          return { image, dataUrl: null };
-        }
-      });
-
-      const imageResults = await Promise.all(imagePromises);
-
-      const validDataUrls = imageResults
-        .filter(result => result.dataUrl !== null)
-        .map(result => result.dataUrl as string);
-      setPreviewImages(validDataUrls);
-
-      //@ts-ignore
-      for (const { image, dataUrl } of imageResults) {
-        if (!dataUrl) continue;
-
-        const slide = pres.addSlide();
-        slide.background = { data: dataUrl };
-      }
-
-      const pptxData = await pres.write({ outputType: "base64" });
-
-      const savePath = await invoke<string>('save_showcase_pptx', {
-        id: showcaseId,
-        title: showcaseData.title,
-        pptxBase64: pptxData
-      });
+      // The backend builds the deck from the uploaded slide renders, so no image data crosses IPC.
+      const savePath = await invoke<string>('generate_showcase_pptx', { id: showcaseData.id });
+      setPreviewImages(
+        showcaseData.images.slice(0, 5).map(image => cachedImageUrl(slideImagePath(showcaseData.id, image)))
+      );
 
       setPptxPath(savePath);
       SuccessToast('Presentation generated successfully!');
     } catch (error) {
       Logger.error('Error generating presentation:', error);
-      setError(`Failed to generate presentation: ${error instanceof Error ? error.message : String(error)}`);
+      setError(`Failed to generate presentation: ${errorMessage(error)}`);
       ErrorToast('Failed to generate presentation');
     } finally {
       setIsGenerating(false);
@@ -132,12 +91,11 @@ const GeneratePresentationPage: React.FC = () => {
     if (!showcaseId) return;
 
     try {
-      const path = await invoke<string>('open_showcase_pptx', { id: showcaseId });
-      await revealItemInDir(path);
+      await invoke<string>('reveal_showcase_output', { id: showcaseId });
       SuccessToast('Opening presentation file...');
     } catch (error) {
       Logger.error('Error opening presentation:', error);
-      setError(`Failed to open presentation: ${error instanceof Error ? error.message : String(error)}`);
+      setError(`Failed to open presentation: ${errorMessage(error)}`);
       ErrorToast('Failed to open presentation file');
     }
   };
@@ -0,0 +1,163 @@
+import React, { useCallback, useEffect, useState } from 'react';
+import { AlertTriangle, CheckCircle2, FileText, FolderOpen, Loader2, RefreshCw, RotateCcw, Stethoscope, XCircle } from 'lucide-react';
+import { invoke } from '@tauri-apps/api/core';
+import { open } from '@tauri-apps/plugin-dialog';
+import { relaunch } from '@tauri-apps/plugin-process';
+import { DatabaseBackup, DiagnosticCheck, SafeModeStatus } from '../utils/types';
+import Logger from '../utils/log';
+import { errorMessage } from '../utils/errors';
+
+interface SafeModePageProps {
+  status: SafeModeStatus;
+}
+
+const SafeModePage: React.FC<SafeModePageProps> = ({ status }) => {
+  const [checks, setChecks] = useState<DiagnosticCheck[] | null>(null);
+  const [logLines, setLogLines] = useState<string[] | null>(null);
+  const [backups, setBackups] = useState<DatabaseBackup[]>([]);
+  const [busy, setBusy] = useState(false);
+  const [message, setMessage] = useState<{ type: 'success' | 'error', text: string } | null>(null);
+
+  const loadBackups = useCallback(async () => {
+    try {
+      setBackups(await invoke<DatabaseBackup[]>('list_database_backups'));
+    } catch (error) {
+      Logger.error('Failed to list database backups:', error);
+    }
+  }, []);
+
+  useEffect(() => {
+    loadBackups();
+  }, [loadBackups]);
+
+  const runAction = async (action: () => Promise<void>) => {
+    if (busy) return;
+    setBusy(true);
+    setMessage(null);
+    try {
+      await action();
+    } catch (error) {
+      Logger.error('Safe mode action failed:', error);
+      setMessage({ type: 'error', text: errorMessage(error) });
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  const runDiagnostics = () => runAction(async () => {
+    setChecks(await invoke<DiagnosticCheck[]>('run_startup_diagnostics'));
+  });
+
+  const showLogs = () => runAction(async () => {
+    setLogLines(await invoke<string[]>('read_recent_logs', { maxLines: 200 }));
+  });
+
+  const restoreBackup = (backupPath: string) => runAction(async () => {
+    await invoke('restore_database_backup', { backupPath });
+    setMessage({ type: 'success', text: 'Backup restored. Restart the app to load it.' });
+  });
+
+  const restoreFromFile = () => runAction(async () => {
+    const selected = await open({ multiple: false, filters: [{ name: 'Database', extensions: ['db'] }] });
+    if (typeof selected !== 'string') return;
+    await invoke('restore_database_backup', { backupPath: selected });
+    setMessage({ type: 'success', text: 'Backup restored. Restart the app to load it.' });
+  });
+
+  const pickDataDir = () => runAction(async () => {
+    const selected = await open({ directory: true, multiple: false });
+    if (typeof selected !== 'string') return;
+    await invoke('set_data_directory', { path: selected });
+    setMessage({ type: 'success', text: `Data directory set to ${selected}. Restart the app to use it.` });
+  });
+
+  const resetDataDir = () => runAction(async () => {
+    await invoke('set_data_directory', { path: null });
+    setMessage({ type: 'success', text: 'Data directory reset to the default. Restart the app to use it.' });
+  });
+
+  return (
+    <div className="min-h-screen bg-black text-white p-8 overflow-y-auto">
+      <div className="max-w-3xl mx-auto space-y-6">
+        <div className="flex items-start gap-3 p-4 rounded-lg border border-amber-500/40 bg-amber-500/10">
+          <AlertTriangle className="h-6 w-6 text-amber-400 flex-shrink-0" />
+          <div>
+            <h1 className="text-xl font-semibold">Safe Mode</h1>
+            <p className="text-sm text-gray-300 mt-1">
+              Showcase Studio could not finish starting up ({status.failedStep}). Your data has not been changed.
+            </p>
+            {status.reason && <p className="text-xs text-gray-400 mt-2 font-mono break-all">{status.reason}</p>}
+            {status.dataDir && (
+              <p className="text-xs text-gray-400 mt-2">
+                Data directory: <span className="font-mono">{status.dataDir}</span>{status.dataDirOverridden && ' (custom)'}
+              </p>
+            )}
+          </div>
+        </div>
+
+        {message && (
+          <div className={`p-3 rounded-lg text-sm ${message.type === 'success' ? 'bg-green-500/10 text-green-300' : 'bg-red-500/10 text-red-300'}`}>
+            {message.text}
+          </div>
+        )}
+
+        <div className="grid grid-cols-2 gap-3">
+          <button onClick={runDiagnostics} disabled={busy} className="flex items-center gap-2 p-3 rounded-lg bg-gray-800 hover:bg-gray-700 disabled:opacity-50">
+            <Stethoscope className="h-4 w-4" /> Run health check
+          </button>
+          <button onClick={showLogs} disabled={busy || !status.logPath} className="flex items-center gap-2 p-3 rounded-lg bg-gray-800 hover:bg-gray-700 disabled:opacity-50">
+            <FileText className="h-4 w-4" /> View logs
+          </button>
+          <button onClick={pickDataDir} disabled={busy} className="flex items-center gap-2 p-3 rounded-lg bg-gray-800 hover:bg-gray-700 disabled:opacity-50">
+            <FolderOpen className="h-4 w-4" /> Choose data directory
+          </button>
+          <button onClick={resetDataDir} disabled={busy || !status.dataDirOverridden} className="flex items-center gap-2 p-3 rounded-lg bg-gray-800 hover:bg-gray-700 disabled:opacity-50">
+            <RotateCcw className="h-4 w-4" /> Use default data directory
+          </button>
+        </div>
+
+        {checks && (
+          <div className="space-y-2">
+            {checks.map(check => (
+              <div key={check.name} className="flex items-start gap-2 text-sm">
+                {check.ok ? <CheckCircle2 className="h-4 w-4 text-green-400 mt-0.5" /> : <XCircle className="h-4 w-4 text-red-400 mt-0.5" />}
+                <span className="font-medium">{check.name}:</span>
+                <span className="text-gray-300 break-all">{check.detail}</span>
+              </div>
+            ))}
+          </div>
+        )}
+
+        {logLines && (
+          <pre className="max-h-80 overflow-auto p-3 rounded-lg bg-gray-900 text-xs text-gray-300 whitespace-pre-wrap">
+            {logLines.join('\n')}
+          </pre>
+        )}
+
+        {status.failedStep === 'database' && (
+          <div className="space-y-2">
+            <h2 className="text-lg font-semibold">Restore a backup</h2>
+            {backups.length === 0 && <p className="text-sm text-gray-400">No automatic backups found.</p>}
+            {backups.map(backup => (
+              <div key={backup.path} className="flex items-center justify-between p-3 rounded-lg bg-gray-900 text-sm">
+                <span className="font-mono">{backup.fileName}</span>
+                <button onClick={() => restoreBackup(backup.path)} disabled={busy} className="px-3 py-1 rounded bg-purple-600 hover:bg-purple-500 disabled:opacity-50">
+                  Restore
+                </button>
+              </div>
+            ))}
+            <button onClick={restoreFromFile} disabled={busy} className="text-sm text-purple-400 hover:text-purple-300">
+              Restore from another file...
+            </button>
+          </div>
+        )}
+
+        <button onClick={() => relaunch()} disabled={busy} className="flex items-center gap-2 px-4 py-2 rounded-lg bg-purple-600 hover:bg-purple-500 disabled:opacity-50">
+          {busy ? <Loader2 className="h-4 w-4 animate-spin" /> : <RefreshCw className="h-4 w-4" />} Restart
+        </button>
+      </div>
+    </div>
+  );
+};
+
+export default SafeModePage;
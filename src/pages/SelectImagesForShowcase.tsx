@@ -4,9 +4,10 @@ import { invoke } from '@tauri-apps/api/core';
 import { Loader2, Layers, AlertTriangle, Home, ChevronRight, CheckCircle, Filter, X, ArrowLeft } from 'lucide-react';
 
 import MessageSelectionGrid from '../components/showcases/MessageSelectionGrid';
-import { SelectedMessage, Showcase } from '../utils/types';
+import { ReplyContext, SelectedMessage, Showcase } from '../utils/types';
 import { AnimatePresence, motion } from 'framer-motion';
 import Logger from '../utils/log';
+import { errorMessage } from '../utils/errors';
 
 export interface IndexedMessage {
     message_id: string;
@@ -17,6 +18,8 @@ export interface IndexedMessage {
     message_content: string;
     attachments: string[];
     timestamp: number; 
+    reply_to?: ReplyContext | null;
+    author_avatar_path?: string | null;
 }
 
 interface DerivedChannelId {
@@ -32,7 +35,7 @@ const SelectImagesForShowcase: React.FC = () => {
 
     const [showcaseInfo, setShowcaseInfo] = useState<Showcase | null>(null);
     const [selectedItems, setSelectedItems] = useState<Set<string>>(new Set());
-    const [chosenFilenames, setChosenFilenames] = useState<Map<string, string>>(new Map());
+    const [chosenFilenames, setChosenFilenames] = useState<Map<string, string[]>>(new Map());
 
     const [availableChannelIds, setAvailableChannelIds] = useState<DerivedChannelId[]>([]);
     const [selectedChannelId, setSelectedChannelId] = useState<string>(ALL_CHANNELS_ID);
@@ -59,7 +62,7 @@ const SelectImagesForShowcase: React.FC = () => {
         invoke<Showcase>('get_showcase', { id: showcaseId })
             .then(info => setShowcaseInfo(info))
             .catch(err => {
-                setError(`Failed to load showcase: ${err instanceof Error ? err.message : String(err)}`);
+                setError(`Failed to load showcase: ${errorMessage(err)}`);
             })
             .finally(() => setIsLoadingShowcase(false));
     }, [showcaseId]);
@@ -102,7 +105,7 @@ const SelectImagesForShowcase: React.FC = () => {
                 }
             })
             .catch(err => {
-                setMessageError(`Could not load messages: ${err instanceof Error ? err.message : String(err)}`);
+                setMessageError(`Could not load messages: ${errorMessage(err)}`);
                 setAllMessages([]);
                 setAvailableChannelIds([]);
             })
@@ -134,7 +137,7 @@ const SelectImagesForShowcase: React.FC = () => {
                 if (message.attachments?.length === 1) {
                     setChosenFilenames(prevMap => {
                         const newMap = new Map(prevMap);
-                        newMap.set(messageId, message.attachments[0]);
+                        newMap.set(messageId, [message.attachments[0]]);
                         return newMap;
                     });
                 }
@@ -144,10 +147,10 @@ const SelectImagesForShowcase: React.FC = () => {
         setSaveError(null);
     }, [allMessages]);
 
-    const handleAttachmentChosen = useCallback((messageId: string, filename: string) => {
+    const handleAttachmentsChosen = useCallback((messageId: string, filenames: string[]) => {
         setChosenFilenames(prevMap => {
             const newMap = new Map(prevMap);
-            newMap.set(messageId, filename);
+            newMap.set(messageId, filenames);
             return newMap;
         });
         setSelectedItems(prevSet => {
@@ -181,26 +184,29 @@ const SelectImagesForShowcase: React.FC = () => {
 
         for (const messageId of selectedItems) {
             const message = allMessages.find(msg => msg.message_id === messageId);
-            const chosenFilename = chosenFilenames.get(messageId);
+            const chosen = chosenFilenames.get(messageId) ?? [];
 
             if (!message) {
                 Logger.error(`Data inconsistency: Selected message ID ${messageId} not found.`);
                 setSaveError("Data inconsistency error. Please refresh.");
                 setIsSaving(false); return;
             }
-            if (!chosenFilename) {
+            if (chosen.length === 0) {
                 missingChoiceError = true;
                 if (!firstMissingId) firstMissingId = messageId;
             } else {
                 finalPayload.push({
                     message_id: message.message_id,
-                    selected_attachment_filename: chosenFilename,
+                    selected_attachment_filename: chosen[0],
+                    selected_attachment_filenames: chosen,
                     channel_id: message.channel_id,
                     author_id: message.author_id,
                     author_name: message.author_name,
                     author_avatar: message.author_avatar ?? null,
                     message_content: message.message_content,
                     timestamp: message.timestamp,
+                    reply_to: message.reply_to ?? null,
+                    author_avatar_path: message.author_avatar_path ?? null,
                 });
             }
         }
@@ -221,7 +227,7 @@ const SelectImagesForShowcase: React.FC = () => {
             navigate(`/edit_images?id=${showcaseId}`);
         } catch (err) {
             console.error("Failed to save selected messages:", err);
-            setSaveError(`Save failed: ${err instanceof Error ? err.message : String(err)}`);
+            setSaveError(`Save failed: ${errorMessage(err)}`);
             setIsSaving(false);
         }
     };
@@ -364,7 +370,7 @@ const SelectImagesForShowcase: React.FC = () => {
                             selectedItems={selectedItems}
                             chosenFilenames={chosenFilenames}
                             onToggleSelection={handleToggleSelection}
-                            onAttachmentChosen={handleAttachmentChosen}
+                            onAttachmentsChosen={handleAttachmentsChosen}
                             appDataDirPath={null}
                         />
 
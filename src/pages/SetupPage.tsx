@@ -16,6 +16,8 @@ import { listen } from '@tauri-apps/api/event';
 
 import { HeadlessFloatingSelect, SelectOption } from '../components/ui/CustomSelect';
 import Logger from '../utils/log';
+import { errorMessage } from '../utils/errors';
+import { IndexingProgress } from '../utils/types';
 import { ErrorToast } from '../components/layout/Toasts';
 
 interface SerializableGuild {
@@ -87,7 +89,7 @@ const SetupPage: React.FC = () => {
                setServers(fetchedServers);
             } catch (err) {
                Logger.error("Failed to fetch servers:", err);
-               ErrorToast(`Failed to load servers: ${err instanceof Error ? err.message : String(err)}`);
+               ErrorToast(`Failed to load servers: ${errorMessage(err)}`);
                setCurrentStep(1);
             } finally {
                setIsLoadingServers(false);
@@ -113,7 +115,7 @@ const SetupPage: React.FC = () => {
          setChannels(fetchedChannels);
       } catch (err) {
          Logger.error("Failed to fetch channels:", err);
-         ErrorToast(`Failed to load channels: ${err instanceof Error ? err.message : String(err)}`);
+         ErrorToast(`Failed to load channels: ${errorMessage(err)}`);
       } finally {
          setIsLoadingChannels(false);
       }
@@ -136,9 +138,10 @@ const SetupPage: React.FC = () => {
                   setIndexingStatus(event.payload);
                });
 
-               unlistenProgress = await listen<string>('indexing-progress', (event) => {
-                  Logger.info("Indexing progress:", event.payload);
-                  setIndexingStatus(`Progress: ${event.payload}`);
+               unlistenProgress = await listen<IndexingProgress>('indexing-progress', (event) => {
+                  const p = event.payload;
+                  Logger.info("Indexing progress:", p);
+                  setIndexingStatus(`Progress: ${Math.round(p.percent)}% - ${p.messages_scanned} messages scanned, ${p.images_downloaded} images`);
                });
 
                unlistenComplete = await listen<string>('indexing-complete', (event) => {
@@ -163,7 +166,7 @@ const SetupPage: React.FC = () => {
 
             } catch (err) {
                Logger.error("Failed to invoke start_initial_indexing:", err);
-               const errorMsg = err instanceof Error ? err.message : String(err);
+               const errorMsg = errorMessage(err);
                ErrorToast(`Failed to start indexing process: ${errorMsg}`);
                setIndexingStatus("Failed to start indexing.");
                unlistenStatus?.();
@@ -207,7 +210,7 @@ const SetupPage: React.FC = () => {
          setCurrentStep(2);
       } catch (err) {
          Logger.error("Failed to save configuration:", err);
-         ErrorToast(`Failed to save configuration: ${err instanceof Error ? err.message : String(err)}`);
+         ErrorToast(`Failed to save configuration: ${errorMessage(err)}`);
          setIsSaving(false);
       }
    };
@@ -287,7 +290,7 @@ const SetupPage: React.FC = () => {
          setCurrentStep(3);
       } catch (err) {
          Logger.error("Failed to save configuration:", err);
-         ErrorToast(`Failed to save settings: ${err instanceof Error ? err.message : String(err)}`);
+         ErrorToast(`Failed to save settings: ${errorMessage(err)}`);
       } finally {
          setIsFinishing(false);
       }
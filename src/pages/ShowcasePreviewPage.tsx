@@ -1,10 +1,16 @@
-import React, { useEffect, useState } from 'react';
+import React, { useEffect, useReducer, useState } from 'react';
 import { useNavigate, useSearchParams } from 'react-router-dom';
 import { invoke } from '@tauri-apps/api/core';
 import { motion, AnimatePresence } from 'framer-motion';
-import { ChevronLeft, Presentation, AlertTriangle, ArrowLeft, ArrowRight, FileCheck } from 'lucide-react';
+import { ChevronLeft, Presentation, AlertTriangle, ArrowLeft, ArrowRight, FileCheck, Timer, Pause, Play, Square } from 'lucide-react';
 import { Showcase } from '../utils/types';
 import Logger from '../utils/log';
+import { errorMessage } from '../utils/errors';
+import { currentElapsedMs, formatDuration, initialRehearsalState, rehearsalReducer, toRehearsalRun } from '../utils/rehearsal';
+import { SlideRef, slideImagePath, slideKey } from '../utils/slides';
+
+// A slide counts as running long when it takes this much longer than the average slide.
+const LONG_SLIDE_FACTOR = 1.5;
 
 const ShowcasePreviewPage: React.FC = () => {
    const [searchParams] = useSearchParams();
@@ -15,7 +21,11 @@ const ShowcasePreviewPage: React.FC = () => {
    const [isLoading, setIsLoading] = useState(true);
    const [error, setError] = useState<string | null>(null);
    const [previewImages, setPreviewImages] = useState<string[]>([]);
+   const [previewSlides, setPreviewSlides] = useState<SlideRef[]>([]);
    const [currentPreviewIndex, setCurrentPreviewIndex] = useState(0);
+   const [rehearsal, dispatchRehearsal] = useReducer(rehearsalReducer, initialRehearsalState);
+   const [now, setNow] = useState(Date.now());
+   const isRehearsing = rehearsal.status === 'running' || rehearsal.status === 'paused';
 
    useEffect(() => {
       if (!showcaseId) {
@@ -39,7 +49,7 @@ const ShowcasePreviewPage: React.FC = () => {
             await loadPreviewImages(showcaseData);
          } catch (error) {
             Logger.error('Error loading showcase:', error);
-            setError(`Failed to load showcase: ${error instanceof Error ? error.message : String(error)}`);
+            setError(`Failed to load showcase: ${errorMessage(error)}`);
             setIsLoading(false);
          }
       };
@@ -50,6 +60,17 @@ const ShowcasePreviewPage: React.FC = () => {
       if (previewImages.length <= 1) return;
    }, [previewImages]);
 
+   // Slide changes drive the rehearsal: time is attributed to whichever slide is on screen.
+   useEffect(() => {
+      dispatchRehearsal({ type: 'goto', slideIndex: currentPreviewIndex, now: Date.now() });
+   }, [currentPreviewIndex]);
+
+   useEffect(() => {
+      if (rehearsal.status !== 'running') return;
+      const interval = setInterval(() => setNow(Date.now()), 500);
+      return () => clearInterval(interval);
+   }, [rehearsal.status]);
+
    useEffect(() => {
       const handleKeyDown = (e: KeyboardEvent) => {
          if (e.target instanceof HTMLInputElement || e.target instanceof HTMLTextAreaElement) {
@@ -68,6 +89,9 @@ const ShowcasePreviewPage: React.FC = () => {
          } else if (e.key === 'Enter' || e.key === 'p' || e.key === 'P') {
             handleGeneratePresentation();
             e.preventDefault();
+         } else if (e.key === 'r' || e.key === 'R') {
+            handleToggleRehearsal();
+            e.preventDefault();
          } else if (e.key >= '1' && e.key <= '9') {
             const slideIndex = parseInt(e.key) - 1;
             if (slideIndex < previewImages.length) {
@@ -81,36 +105,35 @@ const ShowcasePreviewPage: React.FC = () => {
       return () => {
          window.removeEventListener('keydown', handleKeyDown);
       };
-   }, [previewImages.length, navigate, showcaseId]);
+   }, [previewImages.length, navigate, showcaseId, rehearsal, previewSlides]);
 
    const loadPreviewImages = async (showcaseData: Showcase) => {
       if (!showcaseData.images || showcaseData.images.length === 0) return;
 
       try {
          const imagePromises = showcaseData.images.map(async (image) => {
-            const imagePath = `${showcaseId}/${showcaseId}_${image.message_id}.png`;
+            const imagePath = slideImagePath(showcaseData.id, image);
             try {
                const dataUrl = await invoke<string>('get_cached_image_data', {
                   relativePath: imagePath
                });
                return { image, dataUrl };
             } catch (error) {
-               Logger.error(`Failed to load image ${image.message_id}:`, error);
+               Logger.error(`Failed to load image ${slideKey(image)}:`, error);
                return { image, dataUrl: null };
             }
          });
 
          const imageResults = await Promise.all(imagePromises);
 
-         const validDataUrls = imageResults
-            .filter(result => result.dataUrl !== null)
-            .map(result => result.dataUrl as string);
+         const validResults = imageResults.filter(result => result.dataUrl !== null);
 
-         setPreviewImages(validDataUrls);
+         setPreviewImages(validResults.map(result => result.dataUrl as string));
+         setPreviewSlides(validResults.map(result => result.image));
          setIsLoading(false);
       } catch (error) {
          Logger.error('Error loading preview images:', error);
-         setError(`Failed to load preview images: ${error instanceof Error ? error.message : String(error)}`);
+         setError(`Failed to load preview images: ${errorMessage(error)}`);
          setIsLoading(false);
       }
    };
@@ -123,6 +146,33 @@ const ShowcasePreviewPage: React.FC = () => {
       navigate(`/generate?id=${showcaseId}`);
    };
 
+   const handleToggleRehearsal = async () => {
+      if (!isRehearsing) {
+         dispatchRehearsal({ type: 'start', slideCount: previewImages.length, slideIndex: currentPreviewIndex, now: Date.now() });
+         return;
+      }
+      const finishedAt = Date.now();
+      const finished = rehearsalReducer(rehearsal, { type: 'finish', now: finishedAt });
+      dispatchRehearsal({ type: 'finish', now: finishedAt });
+      const run = toRehearsalRun(finished, previewSlides);
+      try {
+         await invoke('save_showcase_rehearsal', { id: showcaseId, rehearsal: run });
+         setShowcase(prev => prev ? { ...prev, rehearsal: run } : prev);
+      } catch (error) {
+         Logger.error('Failed to save rehearsal:', error);
+      }
+   };
+
+   const handlePauseRehearsal = () => {
+      dispatchRehearsal({ type: rehearsal.status === 'paused' ? 'resume' : 'pause', now: Date.now() });
+   };
+
+   const liveElapsed = isRehearsing ? currentElapsedMs(rehearsal, now) : [];
+   const savedTimings = showcase?.rehearsal?.slides ?? [];
+   const averageSlideMs = savedTimings.length > 0
+      ? savedTimings.reduce((sum, slide) => sum + slide.duration_ms, 0) / savedTimings.length
+      : 0;
+
    if (isLoading) {
       return (
          <div className="flex justify-center items-center h-screen bg-black text-white">
@@ -201,6 +251,27 @@ const ShowcasePreviewPage: React.FC = () => {
                </div>
 
                <div className="flex items-center gap-3">
+                  {isRehearsing && (
+                     <button
+                        onClick={handlePauseRehearsal}
+                        className="p-2 rounded-lg bg-gray-800 hover:bg-gray-700 text-gray-300 transition-colors"
+                        aria-label={rehearsal.status === 'paused' ? 'Resume rehearsal' : 'Pause rehearsal'}
+                     >
+                        {rehearsal.status === 'paused' ? <Play className="w-4 h-4" /> : <Pause className="w-4 h-4" />}
+                     </button>
+                  )}
+                  <button
+                     onClick={handleToggleRehearsal}
+                     disabled={previewImages.length === 0}
+                     className={`py-2 px-4 rounded-lg transition-colors flex items-center text-sm gap-2 font-medium shadow-md disabled:opacity-50 ${isRehearsing
+                        ? 'bg-red-600 hover:bg-red-500 text-white'
+                        : 'bg-gray-800 hover:bg-gray-700 text-gray-200 border border-gray-700/50'
+                        }`}
+                  >
+                     {isRehearsing ? <Square className="w-4 h-4" /> : <Timer className="w-4 h-4" />}
+                     <span>{isRehearsing ? 'Finish Rehearsal' : 'Rehearse'}</span>
+                  </button>
+
                   <button
                      onClick={handleGeneratePresentation}
                      className="py-2 px-4 bg-gradient-to-r from-indigo-600 to-blue-600 hover:from-indigo-500 hover:to-blue-500 text-white rounded-lg transition-colors flex items-center text-sm gap-2 font-medium shadow-md hover:shadow-lg"
@@ -278,6 +349,11 @@ const ShowcasePreviewPage: React.FC = () => {
                      <div className="text-xs text-gray-400 flex items-center gap-2">
                         <Presentation className="w-3.5 h-3.5 text-blue-400" />
                         <span>Slide {currentPreviewIndex + 1} of {previewImages.length}</span>
+                        {isRehearsing && (
+                           <span className={`ml-2 font-mono ${rehearsal.status === 'paused' ? 'text-amber-400' : 'text-red-400'}`}>
+                              {formatDuration(liveElapsed[currentPreviewIndex] ?? 0)} on slide · {formatDuration(liveElapsed.reduce((sum, ms) => sum + ms, 0))} total
+                           </span>
+                        )}
                      </div>
 
                      <div className="flex gap-2">
@@ -298,11 +374,38 @@ const ShowcasePreviewPage: React.FC = () => {
                      </div>
                   </div>
 
+                  {/* Last rehearsal, per slide */}
+                  {!isRehearsing && showcase?.rehearsal && savedTimings.length > 0 && (
+                     <div className="flex items-center gap-2 mb-2 overflow-x-auto text-[10px]">
+                        <span className="text-gray-400 whitespace-nowrap flex items-center gap-1">
+                           <Timer className="w-3 h-3" /> Last rehearsal {formatDuration(showcase.rehearsal.total_ms)}
+                        </span>
+                        {savedTimings.map((slide, index) => {
+                           const runsLong = slide.duration_ms > averageSlideMs * LONG_SLIDE_FACTOR;
+                           const slideIndex = previewSlides.findIndex(s => slideKey(s) === slideKey(slide));
+                           return (
+                              <button
+                                 key={slideKey(slide)}
+                                 onClick={() => slideIndex >= 0 && setCurrentPreviewIndex(slideIndex)}
+                                 className={`px-1.5 py-0.5 rounded font-mono whitespace-nowrap border ${runsLong
+                                    ? 'bg-red-900/30 text-red-300 border-red-700/40'
+                                    : 'bg-gray-800/80 text-gray-400 border-gray-700/50'
+                                    }`}
+                                 title={runsLong ? 'Runs long compared to the average slide' : undefined}
+                              >
+                                 {index + 1}: {formatDuration(slide.duration_ms)}
+                              </button>
+                           );
+                        })}
+                     </div>
+                  )}
+
                   {/* Keyboard shortcuts row */}
                   <div className="flex items-center justify-center flex-wrap gap-x-4 gap-y-1 text-gray-500/80 text-[10px]">
                      <span className="flex items-center"><kbd className="px-1.5 py-0.5 bg-gray-800/80 rounded mr-1.5 border border-gray-700/50">←/→</kbd> Navigate</span>
                      <span className="flex items-center"><kbd className="px-1.5 py-0.5 bg-gray-800/80 rounded mr-1.5 border border-gray-700/50">1-9</kbd> Jump to slide</span>
                      <span className="flex items-center"><kbd className="px-1.5 py-0.5 bg-gray-800/80 rounded mr-1.5 border border-gray-700/50">P</kbd> Generate</span>
+                     <span className="flex items-center"><kbd className="px-1.5 py-0.5 bg-gray-800/80 rounded mr-1.5 border border-gray-700/50">R</kbd> Rehearse</span>
                      <span className="flex items-center"><kbd className="px-1.5 py-0.5 bg-gray-800/80 rounded mr-1.5 border border-gray-700/50">Esc</kbd> Exit</span>
                   </div>
                </div>
@@ -3,9 +3,11 @@ import { useNavigate, useSearchParams } from 'react-router-dom';
 import { motion, Reorder } from 'framer-motion';
 import { ArrowUp, ArrowDown, Save, ArrowLeft, AlertTriangle, Loader2, Info, Move, ArrowUpDown, Check, Home } from 'lucide-react';
 import { invoke } from '@tauri-apps/api/core';
-import { ShowcaseImage, Showcase } from '../utils/types';
+import { CachedImageBatch, ShowcaseImage, Showcase } from '../utils/types';
+import { slideImagePath, slideKey } from '../utils/slides';
 import toast from 'react-hot-toast';
 import Logger from '../utils/log';
+import { errorMessage } from '../utils/errors';
 
 const SortImagesPage: React.FC = () => {
   const [searchParams] = useSearchParams();
@@ -38,21 +40,16 @@ const SortImagesPage: React.FC = () => {
           return;
         }
 
-        const imagesWithData = await Promise.all(
-          showcaseData.images.map(async (image, index) => {
-            try {
-              // TODO: Handle image exp dynamically
-              const imagePath = `${showcaseId}/${showcaseId}_${image.message_id}.png`;
-              const dataUrl = await invoke<string>('get_cached_image_data', {
-                relativePath: imagePath
-              });
-              return { ...image, dataUrl };
-            } catch (error) {
-              Logger.error(`Failed to load image ${index}:`, error);
-              return { ...image, dataUrl: undefined };
-            }
-          })
-        );
+        // TODO: Handle image exp dynamically
+        const imagePaths = showcaseData.images.map(image => slideImagePath(showcaseId, image));
+        const batch = await invoke<CachedImageBatch>('get_cached_images_batch', { paths: imagePaths });
+        const imagesWithData = showcaseData.images.map((image, index) => {
+          const imagePath = imagePaths[index];
+          if (batch.errors[imagePath]) {
+            Logger.error(`Failed to load image ${index}:`, batch.errors[imagePath]);
+          }
+          return { ...image, dataUrl: batch.images[imagePath] };
+        });
 
         setImages(imagesWithData);
         if (imagesWithData.length > 0) {
@@ -60,7 +57,7 @@ const SortImagesPage: React.FC = () => {
         }
       } catch (error) {
         Logger.error('Error loading showcase data:', error);
-        setError(`Failed to load showcase data: ${error instanceof Error ? error.message : String(error)}`);
+        setError(`Failed to load showcase data: ${errorMessage(error)}`);
       } finally {
         setIsLoading(false);
       }
@@ -143,7 +140,7 @@ const SortImagesPage: React.FC = () => {
       } catch (error) {
         Logger.error('Error saving sorted images:', error);
         setIsSaving(false);
-        throw new Error(`Failed to save image order: ${error instanceof Error ? error.message : String(error)}`);
+        throw new Error(`Failed to save image order: ${errorMessage(error)}`);
       }
     };
 
@@ -278,8 +275,8 @@ const SortImagesPage: React.FC = () => {
                 setImages(newImages);
                 
                 if (selectedIndex !== null) {
-                  const selectedItemId = images[selectedIndex].message_id;
-                  const newSelectedIndex = newImages.findIndex(img => img.message_id === selectedItemId);
+                  const selectedItemKey = slideKey(images[selectedIndex]);
+                  const newSelectedIndex = newImages.findIndex(img => slideKey(img) === selectedItemKey);
                   if (newSelectedIndex !== -1) {
                     setSelectedIndex(newSelectedIndex);
                   }
@@ -289,7 +286,7 @@ const SortImagesPage: React.FC = () => {
             >
               {images.map((image, index) => (
                 <Reorder.Item
-                  key={image.message_id}
+                  key={slideKey(image)}
                   value={image}
                   dragConstraints={{ top: 0, bottom: 0 }}
                   dragElastic={1}
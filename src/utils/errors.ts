@@ -0,0 +1,12 @@
+import { AppError } from './types';
+
+export const isAppError = (err: unknown): err is AppError =>
+  typeof err === 'object' && err !== null && 'kind' in err && 'message' in err;
+
+// Commands reject with an AppError object rather than a string, so `${err}` would print
+// "[object Object]".
+export const errorMessage = (err: unknown): string => {
+  if (isAppError(err)) return err.context ? `${err.context}: ${err.message}` : err.message;
+  if (err instanceof Error) return err.message;
+  return String(err);
+};
@@ -0,0 +1,10 @@
+import { convertFileSrc } from '@tauri-apps/api/core';
+
+export type CachedImageSize = 'thumb' | 'full';
+
+// URL of a file in the image cache, served by the showcase-image protocol in the backend.
+// Unlike get_cached_image_data there is no base64 copy, ranges work and the webview caches it.
+export const cachedImageUrl = (relativePath: string, size: CachedImageSize = 'full'): string => {
+  const url = convertFileSrc(relativePath, 'showcase-image');
+  return size === 'thumb' ? `${url}?size=thumb` : url;
+};
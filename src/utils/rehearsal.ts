@@ -0,0 +1,95 @@
+import { RehearsalRun } from './types';
+import { SlideRef } from './slides';
+
+// Presenter state machine for practice runs:
+//   idle --start--> running <--pause/resume--> paused
+//   running|paused --finish--> finished --reset--> idle
+// Time is only counted for the current slide while running.
+export type RehearsalStatus = 'idle' | 'running' | 'paused' | 'finished';
+
+export interface RehearsalState {
+  status: RehearsalStatus;
+  startedAt: number | null;
+  slideIndex: number;
+  slideStartedAt: number | null;
+  elapsedMs: number[]; // Per slide, parallel to the slide list
+}
+
+export type RehearsalAction =
+  | { type: 'start'; slideCount: number; slideIndex: number; now: number }
+  | { type: 'goto'; slideIndex: number; now: number }
+  | { type: 'pause'; now: number }
+  | { type: 'resume'; now: number }
+  | { type: 'finish'; now: number }
+  | { type: 'reset' };
+
+export const initialRehearsalState: RehearsalState = {
+  status: 'idle',
+  startedAt: null,
+  slideIndex: 0,
+  slideStartedAt: null,
+  elapsedMs: [],
+};
+
+const closeCurrentSlide = (state: RehearsalState, now: number): number[] => {
+  if (state.status !== 'running' || state.slideStartedAt === null) return state.elapsedMs;
+  const elapsedMs = [...state.elapsedMs];
+  elapsedMs[state.slideIndex] = (elapsedMs[state.slideIndex] ?? 0) + Math.max(0, now - state.slideStartedAt);
+  return elapsedMs;
+};
+
+export const rehearsalReducer = (state: RehearsalState, action: RehearsalAction): RehearsalState => {
+  switch (action.type) {
+    case 'start':
+      return {
+        status: 'running',
+        startedAt: action.now,
+        slideIndex: action.slideIndex,
+        slideStartedAt: action.now,
+        elapsedMs: new Array(action.slideCount).fill(0),
+      };
+    case 'goto':
+      if (state.status !== 'running' && state.status !== 'paused') return state;
+      if (action.slideIndex === state.slideIndex) return state;
+      return {
+        ...state,
+        elapsedMs: closeCurrentSlide(state, action.now),
+        slideIndex: action.slideIndex,
+        slideStartedAt: state.status === 'running' ? action.now : null,
+      };
+    case 'pause':
+      if (state.status !== 'running') return state;
+      return { ...state, status: 'paused', elapsedMs: closeCurrentSlide(state, action.now), slideStartedAt: null };
+    case 'resume':
+      if (state.status !== 'paused') return state;
+      return { ...state, status: 'running', slideStartedAt: action.now };
+    case 'finish':
+      if (state.status !== 'running' && state.status !== 'paused') return state;
+      return { ...state, status: 'finished', elapsedMs: closeCurrentSlide(state, action.now), slideStartedAt: null };
+    case 'reset':
+      return initialRehearsalState;
+  }
+};
+
+// Includes the time on the current slide so far, for a live clock.
+export const currentElapsedMs = (state: RehearsalState, now: number): number[] => closeCurrentSlide(state, now);
+
+export const toRehearsalRun = (state: RehearsalState, slideRefs: SlideRef[]): RehearsalRun => {
+  const slides = slideRefs.map((slide, index) => ({
+    message_id: slide.message_id,
+    attachment_index: slide.attachment_index ?? 0,
+    duration_ms: Math.round(state.elapsedMs[index] ?? 0),
+  }));
+  return {
+    started_at: Math.floor((state.startedAt ?? Date.now()) / 1000),
+    total_ms: slides.reduce((sum, slide) => sum + slide.duration_ms, 0),
+    slides,
+  };
+};
+
+export const formatDuration = (ms: number): string => {
+  const totalSeconds = Math.round(ms / 1000);
+  const minutes = Math.floor(totalSeconds / 60);
+  const seconds = totalSeconds % 60;
+  return `${minutes}:${seconds.toString().padStart(2, '0')}`;
+};
@@ -0,0 +1,19 @@
+import { SelectedMessage } from './types';
+
+export interface SlideRef {
+  message_id: string;
+  attachment_index?: number;
+}
+
+// Each attachment chosen from a message becomes its own slide. The first one keeps the bare
+// message ID so images saved before multi-attachment selection still resolve.
+export const slideKey = (slide: SlideRef): string =>
+  slide.attachment_index ? `${slide.message_id}_${slide.attachment_index}` : slide.message_id;
+
+export const slideImagePath = (showcaseId: string, slide: SlideRef): string =>
+  `${showcaseId}/${showcaseId}_${slideKey(slide)}.png`;
+
+export const selectedFilenames = (message: SelectedMessage): string[] =>
+  message.selected_attachment_filenames && message.selected_attachment_filenames.length > 0
+    ? message.selected_attachment_filenames
+    : [message.selected_attachment_filename];
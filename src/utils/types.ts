@@ -11,35 +11,165 @@ export interface OverlaySettings {
 
 export interface ShowcaseImage {
     message_id: string;
+    attachment_index?: number; // Position among the message's selected attachments
     sender: string;
     avatar: string; 
     message: string;
     is_edited: boolean; 
     overlay: OverlaySettings;
+    credit?: string | null; // Resolved author credit text, falls back to sender
+    slide_title?: string | null;
+    notes?: string | null; // Speaker notes, exported into the deck's notes pane
+    sort_index?: number; // Stored slide order; ignored when saving, the array order is used
+}
+
+export interface ShowcaseImagePatch {
+    position?: OverlayPosition;
+    style?: OverlayStyle;
+    showAvatar?: boolean;
+    caption?: string; // Replaces the message text shown on the slide
+    slide_title?: string; // An empty string clears the title
+    notes?: string; // An empty string clears the notes
+}
+
+export interface SlideUploadResult {
+    slide_key: string;
+    ok: boolean;
+    error?: string; // Why this image was not uploaded
 }
 
 export interface SelectedMessage {
     message_id: string;
     selected_attachment_filename: string;
+    selected_attachment_filenames?: string[]; // All chosen attachments; the first mirrors selected_attachment_filename
     channel_id: string;
     author_id: string;
     author_name: string;
     author_avatar: string | null;
     message_content: string;
     timestamp: number;
+    reply_to?: ReplyContext | null;
+    author_avatar_path?: string | null;
+}
+
+// Archived is only set by archive_showcase.
+export type ShowcaseStatus = 'Draft' | 'In Progress' | 'Completed' | 'Archived';
+
+export interface ShowcaseImagePack {
+    files: number;
+    original_bytes: number;
+    packed_bytes: number;
 }
 
 export interface Showcase {
     id: string;
     title: string;
     description: string | null;
-    status: string;
+    status: ShowcaseStatus;
     created_at: number;
     last_modified: number;
     phase: number;
     selected_messages: SelectedMessage[] | null;
     images: ShowcaseImage[] | null;
     pptx_path?: string | null; 
+    rehearsal?: RehearsalRun | null;
+    exports?: ExportArtifact[];
+    deleted_at?: number | null; // Set while the showcase is in the trash
+    google_slides_url?: string | null; // Last Google Slides upload
+}
+
+export interface ShowcaseQuery {
+    search?: string; // Title and description
+    statuses?: string[];
+    phases?: number[];
+    createdFrom?: number; // Unix seconds, inclusive
+    createdTo?: number; // Unix seconds, exclusive
+    modifiedFrom?: number;
+    modifiedTo?: number;
+    sortBy?: 'last_modified' | 'created_at' | 'title';
+    sortOrder?: 'asc' | 'desc';
+    page?: number; // 1-based
+    limit?: number;
+}
+
+export interface ShowcaseSummary {
+    id: string;
+    title: string;
+    description: string | null;
+    status: ShowcaseStatus;
+    created_at: number;
+    last_modified: number;
+    phase: number;
+    message_count: number;
+    slide_count: number;
+    pptx_path: string | null;
+}
+
+export interface ShowcasePage {
+    showcases: ShowcaseSummary[];
+    total: number; // Matching showcases across all pages
+    page: number;
+    limit: number;
+}
+
+export interface ShowcaseDraft {
+    showcase_id: string;
+    phase: number; // 2 or 3
+    draft_json: string; // Editor state, stored as given
+    updated_at: number; // Unix timestamp (seconds)
+}
+
+export interface ShowcaseRevision {
+    id: number;
+    showcase_id: string;
+    created_at: number;
+    action: 'save_selection' | 'upload_image' | 'sort_images' | 'remove_image' | 'edit_image' | 'move_image' | 'shuffle_images' | 'restore_revision'; // The change that replaced this state
+    phase: number;
+    message_count: number;
+    slide_count: number;
+}
+
+export interface ExportArtifact {
+    path: string; // Absolute path
+    format: string; // "pptx"
+    created_at: number; // Unix timestamp (seconds)
+    sha256?: string;
+    manifest?: ExportManifest;
+}
+
+export interface ExportManifestFile {
+    path: string;
+    sha256: string;
+    size_bytes: number;
+}
+
+export interface ExportManifest {
+    showcase_id: string;
+    showcase_title: string;
+    app_version: string;
+    created_at: number;
+    artifact: ExportManifestFile;
+    files: ExportManifestFile[]; // Slide images the deck was built from
+    settings: Record<string, unknown>;
+}
+
+export interface ExportVerification {
+    sha256: string;
+    size_bytes: number;
+    showcase_id: string | null; // Null when no recorded export matches
+    artifact: ExportArtifact | null;
+}
+
+export interface SlideTiming {
+    message_id: string;
+    attachment_index?: number;
+    duration_ms: number;
+}
+
+export interface RehearsalRun {
+    started_at: number; // Unix timestamp (seconds)
+    total_ms: number;
+    slides: SlideTiming[]; // In showcase order
 }
 
 export interface AttachmentInfo {
@@ -59,7 +189,415 @@ export interface IndexedMessage {
     author_avatar?: string | null;
     message_content: string;
     attachments: string[];
+    attachment_types: AttachmentType[]; // Parallel to attachments
+    attachment_sources: AttachmentSource[]; // Parallel to attachments
     timestamp: number; // Unix timestamp (seconds)
+    reaction_count: number;
+    reactions: ReactionSummary[];
+    deleted: boolean;
+    reply_to: ReplyContext | null;
+    author_avatar_path: string | null; // Relative to the image cache
+    is_submission: boolean; // Sent through the bot's /submit command
+    is_spoiler: boolean; // Has a SPOILER_ attachment
+    is_nsfw_channel: boolean;
+    review_decision?: ReviewDecision | null;
+    author_type: AuthorType;
+    is_favorite: boolean; // Shortlisted, independent of being used in a showcase
+}
+
+// Webhook authors get a synthetic author_id per displayed name, e.g. webhook-<id>-<name>.
+export type AuthorType = 'user' | 'webhook' | 'bot';
+
+// Optional filters for search_indexed_messages; the query itself supports "exact phrases" and prefix*.
+export interface MessageSearchFilters {
+    channelIds?: string[];
+    authorIds?: string[];
+    fromDate?: number; // Unix timestamp (seconds), inclusive
+    toDate?: number; // Unix timestamp (seconds), exclusive
+    includeDeleted?: boolean;
+    submissionsOnly?: boolean;
+    favoritesOnly?: boolean;
+    sortBy?: 'relevance' | 'timestamp' | 'reactions';
+    limit?: number;
+}
+
+// Arguments for query_indexed_messages. sortBy has no 'relevance' here since there is no search term.
+export interface MessageQuery extends Omit<MessageSearchFilters, 'sortBy'> {
+    sortBy?: 'timestamp' | 'reactions';
+    sortOrder?: 'asc' | 'desc';
+    isUsed?: boolean;
+    page?: number; // 1-based
+}
+
+// Both maps are keyed by the requested relative path.
+export interface CachedImageBatch {
+    images: Record<string, string>; // Data URIs
+    errors: Record<string, string>;
+}
+
+export interface MessagePage {
+    messages: IndexedMessage[];
+    total: number; // Matching messages across all pages
+    page: number;
+    limit: number;
+}
+
+export type MessageExportFormat = "csv" | "json";
+
+export interface MessageExportResult {
+    path: string;
+    format: MessageExportFormat;
+    rows: number;
+    size_bytes: number;
+}
+
+// Options of generate_showcase_pptx
+export interface PptxOptions {
+    legacyCompatibility?: boolean; // Defaults to the legacyPptxCompatibility setting
+    outputDir?: string;
+}
+
+export interface VideoSettings {
+    slideDuration?: number; // Seconds, 5 by default
+    transition?: 'fade' | 'none';
+    transitionDuration?: number; // Seconds, 1 by default
+    resolution?: string; // "720p", "1080p", "1440p", "4k" or "WIDTHxHEIGHT"
+    musicPath?: string;
+    outputDir?: string;
+}
+
+export interface VideoExport {
+    path: string;
+    slides: number;
+    skipped: string[]; // Slide keys that could not be rendered
+    duration: number; // Seconds
+    width: number;
+    height: number;
+}
+
+export interface HtmlExport {
+    path: string; // The page; its assets folder sits next to it unless inlined
+    slides: number;
+    skipped: string[]; // Slide keys that could not be rendered
+    inline_images: boolean;
+}
+
+export interface ShowcaseArchiveExport {
+    path: string;
+    files: number; // Cached attachments and avatars
+    slides: number; // Uploaded slide renders
+    missing: string[]; // Cache paths that were no longer on disk
+}
+
+export interface ShowcaseArchiveImport {
+    showcase_id: string; // Always a new ID
+    title: string;
+    files_restored: number;
+    slides_restored: number;
+}
+
+export interface SlideImageExport {
+    folder: string;
+    files: string[];
+    skipped: string[]; // Slide keys that could not be rendered
+    format: 'png' | 'jpg';
+    width: number;
+    height: number;
+}
+
+export interface CleanupStats {
+    messages_deleted: number; // For a dry run: would be deleted
+    files_deleted: number;
+    skipped_used_messages: number;
+    retention_days: number;
+    dry_run: boolean;
+    sample_messages: IndexedMessage[]; // Dry runs only
+    compaction: CompactResult | null; // Set when compactAfterCleanup ran
+}
+
+export interface DateRange {
+    from?: number | null; // Unix seconds, inclusive
+    to?: number | null; // Unix seconds, exclusive
+}
+
+export interface MessageDeletionResult {
+    messages_deleted: number;
+    files_deleted: number;
+    skipped_used_messages: number; // Selected by a showcase, kept
+}
+
+export interface CacheClearResult {
+    files_deleted: number;
+    bytes_freed: number;
+}
+
+export interface CompactResult {
+    size_before_bytes: number;
+    size_after_bytes: number;
+    wal_size_before_bytes: number;
+    wal_size_after_bytes: number;
+    duration_ms: number;
+}
+
+export interface CompactProgress {
+    step: number;
+    total_steps: number;
+    message: string;
+}
+
+export interface CleanupProgress {
+    processed: number;
+    total: number;
+    files_deleted: number;
+}
+
+export type ReviewDecision = 'accept' | 'reject' | 'maybe';
+
+export interface TriageNext {
+    message: IndexedMessage | null; // Null once everything has been reviewed
+    remaining: number;
+    accepted: number;
+    rejected: number;
+    maybe: number;
+}
+
+export interface SubmissionBotStatus {
+    running: boolean;
+    submissionCount: number;
+}
+
+export interface ReplyContext {
+    message_id: string;
+    author_id: string;
+    author_name: string;
+    content: string;
+}
+
+export interface ResyncResult {
+    checked: number;
+    updated: number;
+    deleted: number;
+    failed_channels: string[];
+}
+
+export interface RepairCacheResult {
+    scanned: number;
+    broken: number;
+    repaired: number;
+    permanently_gone: string[]; // Relative paths that can no longer be downloaded
+    failed: string[]; // Relative paths worth retrying later
+}
+
+export interface UsedFlagRepair {
+    marked: number; // Selected by a showcase but not flagged
+    cleared: number; // Flagged but no longer selected anywhere
+}
+
+export interface OrphanedCacheFile {
+    relative_path: string;
+    size_bytes: number;
+}
+
+export interface OrphanedCacheReport {
+    files: OrphanedCacheFile[];
+    total_bytes: number;
+    deleted: number; // 0 unless deletion was requested
+    bytes_freed: number;
+}
+
+export interface DedupeReport {
+    scanned: number;
+    duplicates: number; // Files replaced by a hard link to an identical file
+    bytes_reclaimed: number;
+    failed: string[];
+}
+
+export interface ReactionSummary {
+    emoji: string;
+    count: number;
+}
+
+export type AttachmentType = 'image' | 'gif' | 'video';
+
+export type AttachmentSource = 'attachment' | 'sticker' | 'embed' | 'link';
+
+export type IndexingPhase =
+    | 'idle'
+    | 'starting'
+    | 'fetching_messages'
+    | 'downloading'
+    | 'saving'
+    | 'paused'
+    | 'completed'
+    | 'cancelled';
+
+export interface IndexingProgress {
+    phase: IndexingPhase;
+    channel_id: string | null;
+    channel_name: string | null;
+    channel_index: number;
+    channel_count: number;
+    messages_scanned: number;
+    messages_saved: number;
+    images_downloaded: number;
+    estimated_total: number | null;
+    percent: number;
+    updated_at: number; // Unix timestamp (seconds)
+}
+
+export interface ChannelPreviewMessage {
+    message_id: string;
+    author_name: string;
+    author_avatar: string | null;
+    content: string;
+    timestamp: number; // Unix timestamp (seconds)
+    media_urls: string[]; // Discord CDN URLs, not cached
+}
+
+export interface IndexingEstimate {
+    channel_count: number;
+    message_count: number;
+    image_count: number; // Files to download, already cached ones excluded
+    total_bytes: number;
+    unknown_size_count: number; // Embed/link images, not in total_bytes
+    already_cached_count: number;
+    skipped_channels: string[];
+    failed_channels: string[];
+}
+
+export interface ExportMemoryProfile {
+    totalMemoryMb: number;
+    availableMemoryMb: number;
+    configuredMode: 'auto' | 'standard' | 'low_memory';
+    effectiveMode: 'standard' | 'low_memory';
+    maxImagesInFlight: number;
+    chunkSizeBytes: number;
+}
+
+export interface ChannelIndexStatus {
+    channel_id: string;
+    indexed_message_count: number;
+    newest_message_timestamp: number | null; // Unix timestamp (seconds)
+    last_run_at: number | null;
+    last_run_result: 'completed' | 'cancelled' | 'failed' | 'interrupted' | null;
+    last_run_messages_saved: number | null;
+    last_run_error: string | null;
+}
+
+export interface ChannelIndexingStats {
+    channel_id: string;
+    selected: boolean;
+    message_count: number;
+    image_count: number;
+    video_count: number;
+    unique_authors: number;
+    oldest_message_timestamp: number | null;
+    newest_message_timestamp: number | null;
+    cache_size_bytes: number;
+}
+
+export interface ConfigHistoryEntry {
+    id: number;
+    changed_at: number; // Unix timestamp (milliseconds), pass to revert_config_to
+    key: string;
+    old_value: string | null;
+    new_value: string | null;
+}
+
+export type ConsentStatus = 'pending' | 'granted' | 'declined';
+
+export interface MessageConsent {
+    message_id: string;
+    author_id: string;
+    author_name: string;
+    status: ConsentStatus;
+    credit_text: string; // Recorded credit, else the author's rendered credit
+    custom_credit: boolean;
+    updated_at: number | null; // Unix timestamp (seconds), null until first answered
+}
+
+export interface UncreditedAuthor {
+    author_id: string;
+    author_name: string;
+    status: ConsentStatus; // 'declined' if any message was declined
+    message_ids: string[];
+}
+
+export type NotifyOutcome = 'sent' | 'replied' | 'opted_out' | 'already_notified' | 'skipped' | 'failed';
+
+export interface AuthorNotifyResult {
+    author_id: string;
+    author_name: string;
+    outcome: NotifyOutcome; // 'replied' when DMs were closed
+    error?: string;
+}
+
+export interface SlideRenderInfo {
+    message_id: string;
+    attachment_index: number;
+    credit_text: string;
+    caption: string;
+    reply_author: string | null;
+    reply_caption: string | null;
+    posted_at: number;
+    posted_at_formatted: string;
+}
+
+export interface ShowcaseRenderInfo {
+    title: string;
+    locale: string;
+    created_at_formatted: string;
+    subtitle: string;
+    artist_count_formatted: string;
+    slide_count_formatted: string;
+    slides: SlideRenderInfo[];
+}
+
+export interface RedactionRules {
+    stripLinks: boolean;
+    stripEmails: boolean;
+    stripPhoneNumbers: boolean;
+    maxCaptionLength?: number;
+}
+
+export interface ImageSizeFilter {
+    minWidth: number;
+    minHeight: number;
+    maxFileSizeBytes?: number;
+}
+
+export interface ContentFilterSettings {
+    includePatterns: string[];
+    excludePatterns: string[];
+    useRegex: boolean;
+    caseSensitive: boolean;
+}
+
+export interface ContentFilterPreview {
+    total: number;
+    matching: number;
+    missing_include: number;
+    matched_exclude: number;
+}
+
+export interface ShareServerStatus {
+    running: boolean;
+    showcaseId: string | null;
+    url: string | null; // Loopback URL including the access token
+    expiresAt: number | null; // Unix timestamp (seconds)
+}
+
+export interface LiveIndexingStatus {
+    running: boolean;
+    channelCount: number;
+}
+
+export interface AuthorRefreshResult {
+    checked: number;
+    updated: number;
+    skipped: number;
+    not_found: string[];
+    failed: string[];
 }
 
 export interface SerializableGuild {
@@ -68,6 +606,25 @@ export interface SerializableGuild {
     icon: string | null;
 }
 
+export interface DiscordOAuthStatus {
+    loggedIn: boolean;
+    userId: string | null;
+    userName: string | null;
+}
+
+export interface TokenValidationReport {
+    token_valid: boolean;
+    bot_user_id: string | null;
+    bot_user_name: string | null;
+    guild_id: string | null;
+    guild_name: string | null;
+    guild_visible: boolean | null;
+    channels_missing_view: string[];
+    channels_missing_read_history: string[];
+    channels_not_found: string[];
+    errors: string[];
+}
+
 export interface DiscordChannel {
     id: string;
     name: string;
@@ -75,6 +632,7 @@ export interface DiscordChannel {
     position: number;
     parent_id: string;
     parent_name: string;
+    kind: 'text' | 'news' | 'forum' | 'public_thread' | 'other';
 }
 
 export interface StorageUsage {
@@ -82,7 +640,24 @@ export interface StorageUsage {
     image_cache_size_bytes: number,
     total_size_bytes: number,
     database_path: string,
-    image_cache_path: string
+    image_cache_path: string,
+    showcases: ShowcaseStorage[], // Largest first
+    channels: ChannelStorage[] // Largest cache first
+}
+
+export interface ShowcaseStorage {
+    showcase_id: string;
+    title: string;
+    image_dir_size_bytes: number;
+    presentation_size_bytes: number;
+    message_count: number; // Selected messages
+}
+
+export interface ChannelStorage {
+    channel_id: string;
+    message_count: number;
+    cache_file_count: number;
+    cache_size_bytes: number;
 }
 
 export interface FirstSlideSettings {
@@ -95,11 +670,14 @@ export interface CustomizationSettingsPayload {
   overlaySettings?: OverlaySettings | null;
   firstSlideSettings?: FirstSlideSettings | null;
   autoUpdateEnabled?: boolean | null;
+  legacyPptxCompatibility?: boolean | null;
+  exportDirectory?: string | null;
 }
 
 export interface EditableImage {
-    id: string;
+    id: string; // Slide key, see utils/slides.ts
     message_id: string;
+    attachment_index: number;
     filename: string;
     sender: string;
     avatar: string | null;
@@ -113,3 +691,73 @@ export interface EditableImage {
         transparency: number; // 0-100
     };
 }
+
+export interface SafeModeStatus {
+    active: boolean;
+    failedStep: string | null;
+    reason: string | null;
+    dataDir: string | null;
+    dataDirOverridden: boolean;
+    logPath: string | null;
+}
+
+export interface DiagnosticCheck {
+    name: string;
+    ok: boolean;
+    detail: string;
+}
+
+export interface CorruptRow {
+    table: string;
+    row_key: string; // Showcase id or message id
+    column: string;
+    error: string;
+}
+
+export interface MissingCacheFile {
+    message_id: string;
+    relative_path: string;
+}
+
+export interface DatabaseHealthReport {
+    integrity_errors: string[]; // Empty when the integrity check passed
+    foreign_key_violations: string[];
+    corrupt_rows: CorruptRow[];
+    missing_files: MissingCacheFile[];
+    quarantined: number; // Rows moved aside by this run
+}
+
+export interface DatabaseBackup {
+    path: string;
+    fileName: string;
+    sizeBytes: number;
+    modifiedAt: number | null;
+}
+
+export type ShutdownChoice = 'wait' | 'cancel' | 'background';
+
+export type AppErrorKind =
+    | 'auth'
+    | 'network'
+    | 'rate_limited'
+    | 'not_found'
+    | 'invalid_input'
+    | 'conflict'
+    | 'database'
+    | 'disk_full'
+    | 'permission_denied'
+    | 'io'
+    | 'internal';
+
+// What every backend command rejects with.
+export interface AppError {
+    kind: AppErrorKind;
+    message: string;
+    retryable: boolean;
+    context?: string; // What the command was doing when it failed
+}
+
+export interface ActiveJobs {
+    indexing: boolean;
+    exports: string[];
+}